@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use anyhow::{Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// Facts about an AnimSequence read from its properties and imports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationInfo {
+    pub class: String,
+    pub duration: Option<f64>,
+    pub frames: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub rate_scale: Option<f64>,
+    pub skeleton: Option<String>,
+    pub curves: Vec<String>,
+    pub additive_type: Option<String>,
+    pub bone_compression: Option<String>,
+    pub curve_compression: Option<String>,
+    /// Cooked (editor-filtered) packages ship compressed tracks only
+    pub raw_data_stripped: bool,
+}
+
+/// Parses the top-level properties of an AnimSequence (or other AnimSequenceBase) export
+pub fn parse_animation(data: &[u8]) -> Result<AnimationInfo> {
+    let package = Package::parse(data)?;
+    let export = package
+        .exports
+        .iter()
+        .find(|e| {
+            let class = package.export_class_name(e);
+            class == "AnimSequence" || class == "AnimMontage" || class == "AnimComposite"
+        })
+        .context("Package has no AnimSequence export")?;
+    let class = package.export_class_name(export);
+
+    let mut reader = ArchiveReader::new(package.export_data(data, export)?);
+    let properties = uasset_parser::read_properties(&package, &mut reader, &class)?;
+    let number = |key: &str| properties.get(key).and_then(Value::as_f64);
+    let string = |key: &str| properties.get(key).and_then(Value::as_str).map(str::to_string);
+
+    let duration = number("SequenceLength");
+    // UE5 stores sampled keys/frames; UE4 stores NumFrames (keys)
+    let keys = number("NumberOfSampledKeys").or_else(|| number("NumFrames"));
+    let frames = number("NumberOfSampledFrames")
+        .or_else(|| keys.map(|k| (k - 1.0).max(0.0)))
+        .map(|f| f as u32);
+
+    let frame_rate = properties
+        .get("SamplingFrameRate")
+        .and_then(|rate| {
+            let numerator = rate.get("numerator")?.as_f64()?;
+            let denominator = rate.get("denominator")?.as_f64()?;
+            (denominator != 0.0).then(|| numerator / denominator)
+        })
+        .or_else(|| match (frames, duration) {
+            (Some(frames), Some(duration)) if duration > 0.0 && frames > 0 => Some(f64::from(frames) / duration),
+            _ => None,
+        });
+
+    // Skeleton is a property, but fall back to the import table if it wasn't serialized
+    let skeleton = string("Skeleton").or_else(|| {
+        (0..package.imports.len())
+            .map(|i| -(i as i32) - 1)
+            .find(|&i| package.import(i).is_some_and(|import| import.class_name == "Skeleton"))
+            .and_then(|i| package.import_object_path(i))
+    });
+
+    let curves = properties
+        .get("RawCurveData")
+        .and_then(|raw| raw.get("FloatCurves"))
+        .and_then(Value::as_array)
+        .map(|curves| curves.iter().filter_map(curve_name).collect())
+        .unwrap_or_default();
+
+    Ok(AnimationInfo {
+        class,
+        duration,
+        frames,
+        frame_rate,
+        rate_scale: number("RateScale"),
+        skeleton,
+        curves,
+        additive_type: string("AdditiveAnimType"),
+        bone_compression: string("BoneCompressionSettings"),
+        curve_compression: string("CurveCompressionSettings"),
+        raw_data_stripped: package.summary.is_filter_editor_only(),
+    })
+}
+
+/// Curve name: UE5.3+ `CurveName`, or the display name of the older `Name` smart name
+fn curve_name(curve: &Value) -> Option<String> {
+    curve
+        .get("CurveName")
+        .or_else(|| curve.get("Name").and_then(|name| name.get("DisplayName")))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
@@ -0,0 +1,249 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::preview::utils::AssetLookupError;
+
+/// Stable machine-readable reason of an API error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// Malformed or unsupported parameters (sort key, export format, scan path)
+    InvalidRequest,
+    /// No valid `Authorization: Bearer` token
+    Unauthorized,
+    AssetNotFound,
+    /// The path exists in several paks; `context.paks` lists them
+    AssetAmbiguous,
+    /// The asset has no pak to read it from
+    AssetNotInPak,
+    EntryNotFound,
+    /// No decryption key is configured for the entry
+    EntryEncrypted,
+    PakNotFound,
+    /// The pak exists but couldn't be read or parsed
+    PakReadFailed,
+    ExtractFailed,
+    /// The entry's compression method can't be decompressed here (Oodle)
+    DecompressorUnavailable,
+    /// The file to extract to exists and `overwrite` wasn't set
+    DestinationExists,
+    /// The path isn't part of any mount, so it can't be revealed or opened
+    NotMounted,
+    /// The file manager or the default application couldn't be started
+    OpenFailed,
+    /// The settings file couldn't be written; the settings are unchanged
+    SettingsWriteFailed,
+    /// The file system watcher for a mount couldn't be started
+    WatchFailed,
+    /// The preview of the asset isn't an image, audio clip or other binary
+    NoBinaryPreview,
+    /// The preview can't be produced in the requested format
+    FormatUnavailable,
+    /// The asset isn't a texture, or its mips couldn't be read or decoded
+    TextureUnreadable,
+    /// A .locres file couldn't be parsed
+    LocresUnreadable,
+    /// The package uses unversioned properties and no .usmap mappings file is set
+    MappingsRequired,
+    /// An IoStore container exists but couldn't be read or parsed
+    ContainerReadFailed,
+    /// A preview, or a file made from one, couldn't be produced
+    PreviewFailed,
+    /// A dependency file couldn't be read or parsed
+    ImportFailed,
+    /// The dependency graph couldn't be written in the requested format
+    ExportFailed,
+    /// A file named in the request doesn't exist
+    FileNotFound,
+    /// Reading or writing a file failed
+    IoFailed,
+    /// Anything without a code of its own; `message` says what happened
+    Internal,
+    NoPaksFound,
+    JobNotFound,
+    MountNotFound,
+    /// The path is already mounted; remove that mount first to change it
+    MountExists,
+    /// No game profile has that name; `context.profiles` lists them
+    ProfileNotFound,
+    /// A game profile with that name exists already
+    ProfileExists,
+    /// The route is at its concurrency limit with a full queue; see `Retry-After`
+    TooManyRequests,
+    /// The job was stopped with `cancel_job` before it finished
+    Cancelled,
+}
+
+impl ErrorCode {
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidRequest | ErrorCode::NoPaksFound => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::NotMounted => StatusCode::FORBIDDEN,
+            ErrorCode::AssetNotFound
+            | ErrorCode::AssetNotInPak
+            | ErrorCode::EntryNotFound
+            | ErrorCode::PakNotFound
+            | ErrorCode::FileNotFound
+            | ErrorCode::JobNotFound
+            | ErrorCode::MountNotFound
+            | ErrorCode::ProfileNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::AssetAmbiguous
+            | ErrorCode::EntryEncrypted
+            | ErrorCode::MountExists
+            | ErrorCode::ProfileExists
+            | ErrorCode::DestinationExists
+            | ErrorCode::Cancelled => StatusCode::CONFLICT,
+            ErrorCode::DecompressorUnavailable => StatusCode::NOT_IMPLEMENTED,
+            ErrorCode::FormatUnavailable => StatusCode::NOT_ACCEPTABLE,
+            ErrorCode::NoBinaryPreview
+            | ErrorCode::TextureUnreadable
+            | ErrorCode::LocresUnreadable
+            | ErrorCode::MappingsRequired => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ErrorCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::PakReadFailed
+            | ErrorCode::ContainerReadFailed
+            | ErrorCode::ExtractFailed
+            | ErrorCode::PreviewFailed
+            | ErrorCode::ImportFailed
+            | ErrorCode::ExportFailed
+            | ErrorCode::IoFailed
+            | ErrorCode::Internal
+            | ErrorCode::OpenFailed
+            | ErrorCode::SettingsWriteFailed
+            | ErrorCode::WatchFailed => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Body of every HTTP API error response
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ErrorResponse {
+    pub error: ApiError,
+}
+
+/// Wire form of an error: `{ "code", "message", "context" }`, inside `{ "error": ... }`
+/// over HTTP
+///
+/// Also used as is wherever errors are reported in a successful response (scan progress),
+/// and as `PakSeekError::Api` for errors classified where they happen.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// What the error is about (path, pak, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<Value>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), context: None }
+    }
+
+    pub fn with_context(mut self, context: Value) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// A failed lookup of the asset at `path`
+    pub fn asset(error: AssetLookupError, path: &str) -> Self {
+        match error {
+            AssetLookupError::NotFound => {
+                Self::new(ErrorCode::AssetNotFound, error.to_string()).with_context(json!({ "path": path }))
+            }
+            AssetLookupError::Ambiguous { ref paks } => Self::new(ErrorCode::AssetAmbiguous, error.to_string())
+                .with_context(json!({ "path": path, "paks": paks })),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.code.status(), Json(ErrorResponse { error: self })).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every code with its wire name and status; renaming or remapping one must change this table
+    const CODES: [(ErrorCode, &str, StatusCode); 36] = [
+        (ErrorCode::InvalidRequest, "INVALID_REQUEST", StatusCode::BAD_REQUEST),
+        (ErrorCode::Unauthorized, "UNAUTHORIZED", StatusCode::UNAUTHORIZED),
+        (ErrorCode::AssetNotFound, "ASSET_NOT_FOUND", StatusCode::NOT_FOUND),
+        (ErrorCode::AssetAmbiguous, "ASSET_AMBIGUOUS", StatusCode::CONFLICT),
+        (ErrorCode::AssetNotInPak, "ASSET_NOT_IN_PAK", StatusCode::NOT_FOUND),
+        (ErrorCode::EntryNotFound, "ENTRY_NOT_FOUND", StatusCode::NOT_FOUND),
+        (ErrorCode::EntryEncrypted, "ENTRY_ENCRYPTED", StatusCode::CONFLICT),
+        (ErrorCode::PakNotFound, "PAK_NOT_FOUND", StatusCode::NOT_FOUND),
+        (ErrorCode::PakReadFailed, "PAK_READ_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::ExtractFailed, "EXTRACT_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::DecompressorUnavailable, "DECOMPRESSOR_UNAVAILABLE", StatusCode::NOT_IMPLEMENTED),
+        (ErrorCode::DestinationExists, "DESTINATION_EXISTS", StatusCode::CONFLICT),
+        (ErrorCode::NotMounted, "NOT_MOUNTED", StatusCode::FORBIDDEN),
+        (ErrorCode::OpenFailed, "OPEN_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::SettingsWriteFailed, "SETTINGS_WRITE_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::WatchFailed, "WATCH_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::NoBinaryPreview, "NO_BINARY_PREVIEW", StatusCode::UNPROCESSABLE_ENTITY),
+        (ErrorCode::FormatUnavailable, "FORMAT_UNAVAILABLE", StatusCode::NOT_ACCEPTABLE),
+        (ErrorCode::TextureUnreadable, "TEXTURE_UNREADABLE", StatusCode::UNPROCESSABLE_ENTITY),
+        (ErrorCode::LocresUnreadable, "LOCRES_UNREADABLE", StatusCode::UNPROCESSABLE_ENTITY),
+        (ErrorCode::MappingsRequired, "MAPPINGS_REQUIRED", StatusCode::UNPROCESSABLE_ENTITY),
+        (ErrorCode::ContainerReadFailed, "CONTAINER_READ_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::PreviewFailed, "PREVIEW_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::ImportFailed, "IMPORT_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::ExportFailed, "EXPORT_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::FileNotFound, "FILE_NOT_FOUND", StatusCode::NOT_FOUND),
+        (ErrorCode::IoFailed, "IO_FAILED", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::Internal, "INTERNAL", StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorCode::NoPaksFound, "NO_PAKS_FOUND", StatusCode::BAD_REQUEST),
+        (ErrorCode::JobNotFound, "JOB_NOT_FOUND", StatusCode::NOT_FOUND),
+        (ErrorCode::MountNotFound, "MOUNT_NOT_FOUND", StatusCode::NOT_FOUND),
+        (ErrorCode::MountExists, "MOUNT_EXISTS", StatusCode::CONFLICT),
+        (ErrorCode::ProfileNotFound, "PROFILE_NOT_FOUND", StatusCode::NOT_FOUND),
+        (ErrorCode::ProfileExists, "PROFILE_EXISTS", StatusCode::CONFLICT),
+        (ErrorCode::TooManyRequests, "TOO_MANY_REQUESTS", StatusCode::TOO_MANY_REQUESTS),
+        (ErrorCode::Cancelled, "CANCELLED", StatusCode::CONFLICT),
+    ];
+
+    #[test]
+    fn codes_have_stable_names_and_statuses() {
+        for (code, name, status) in CODES {
+            assert_eq!(serde_json::to_value(code).unwrap(), name);
+            assert_eq!(serde_json::from_value::<ErrorCode>(name.into()).unwrap(), code);
+            assert_eq!(code.status(), status, "{}", name);
+        }
+    }
+
+    #[test]
+    fn codes_match_the_frontend_type() {
+        let types = include_str!("../frontend/src/types/index.ts");
+        let union = types.split("export type ApiErrorCode =").nth(1).and_then(|rest| rest.split(';').next()).unwrap();
+        let frontend: Vec<&str> = union.split('|').map(|name| name.trim().trim_matches('\'')).filter(|name| !name.is_empty()).collect();
+        let backend: Vec<&str> = CODES.iter().map(|(_, name, _)| *name).collect();
+        assert_eq!(frontend, backend);
+    }
+
+    #[test]
+    fn error_body_has_code_message_and_context() {
+        let error = ApiError::new(ErrorCode::NotMounted, "Not in any mount").with_context(serde_json::json!({ "path": "C:/Game" }));
+        assert_eq!(
+            serde_json::to_value(ErrorResponse { error }).unwrap(),
+            serde_json::json!({ "error": { "code": "NOT_MOUNTED", "message": "Not in any mount", "context": { "path": "C:/Game" } } })
+        );
+    }
+}
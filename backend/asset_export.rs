@@ -0,0 +1,172 @@
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+use crate::preview::Asset;
+
+/// Output formats of `export_asset_list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Parses a user supplied format name ("csv", "json", "markdown" / "md")
+    pub fn parse(format: &str) -> Result<Self> {
+        match format.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            other => bail!("Unsupported asset list format: {} (expected csv, json or markdown)", other),
+        }
+    }
+}
+
+/// The columns of an exported asset
+#[derive(Serialize)]
+struct ExportRow<'a> {
+    name: &'a str,
+    path: &'a str,
+    #[serde(rename = "type")]
+    asset_type: &'a str,
+    size: u64,
+    compressed_size: Option<u64>,
+    compression: Option<&'a str>,
+    encrypted: Option<bool>,
+    pak: Option<&'a str>,
+}
+
+impl<'a> ExportRow<'a> {
+    const HEADERS: [&'static str; 8] = ["Name", "Path", "Type", "Size", "Compressed size", "Compression", "Encrypted", "Pak"];
+
+    fn new(asset: &'a Asset) -> Self {
+        ExportRow {
+            name: &asset.name,
+            path: &asset.path,
+            asset_type: asset.asset_type.as_str(),
+            size: asset.size,
+            compressed_size: asset.compressed_size,
+            compression: asset.compression_method.as_deref(),
+            encrypted: asset.is_encrypted,
+            pak: asset.pak_file.as_deref(),
+        }
+    }
+
+    /// The row as text, empty where a value is unknown
+    fn fields(&self) -> [String; 8] {
+        [
+            self.name.to_string(),
+            self.path.to_string(),
+            self.asset_type.to_string(),
+            self.size.to_string(),
+            self.compressed_size.map(|size| size.to_string()).unwrap_or_default(),
+            self.compression.unwrap_or_default().to_string(),
+            self.encrypted.map(|encrypted| encrypted.to_string()).unwrap_or_default(),
+            self.pak.unwrap_or_default().to_string(),
+        ]
+    }
+}
+
+/// Writes `assets` to `destination` one row at a time; returns the rows and bytes written
+///
+/// CSV follows RFC 4180 (CRLF line ends, quoted fields with doubled quotes), JSON is a
+/// pretty-printed array and Markdown a table. The file is written next to `destination`
+/// and moved over it once complete, so a failed export leaves no partial file.
+pub async fn write_asset_list<'a>(
+    assets: impl IntoIterator<Item = &'a Asset>,
+    format: ExportFormat,
+    destination: &Path,
+) -> Result<(usize, u64)> {
+    if let Some(dir) = destination.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir).await.with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut part = destination.as_os_str().to_owned();
+    part.push(".part");
+    let part = std::path::PathBuf::from(part);
+    let file = tokio::fs::File::create(&part).await.with_context(|| format!("Failed to create {}", part.display()))?;
+    let mut out = BufWriter::new(file);
+
+    let written = async {
+        let rows = write_rows(&mut out, assets, format).await?;
+        out.flush().await?;
+        anyhow::Ok(rows)
+    }
+    .await;
+    let rows = match written {
+        Ok(rows) => rows,
+        Err(e) => {
+            drop(out);
+            let _ = tokio::fs::remove_file(&part).await;
+            return Err(e.context(format!("Failed to write {}", destination.display())));
+        }
+    };
+    drop(out);
+    let bytes = tokio::fs::metadata(&part).await?.len();
+    tokio::fs::rename(&part, destination)
+        .await
+        .with_context(|| format!("Failed to replace {}", destination.display()))?;
+    Ok((rows, bytes))
+}
+
+async fn write_rows<'a, W: tokio::io::AsyncWrite + Unpin>(
+    out: &mut W,
+    assets: impl IntoIterator<Item = &'a Asset>,
+    format: ExportFormat,
+) -> Result<usize> {
+    let mut rows = 0;
+    match format {
+        ExportFormat::Csv => {
+            out.write_all(csv_line(&ExportRow::HEADERS.map(String::from)).as_bytes()).await?;
+            for asset in assets {
+                out.write_all(csv_line(&ExportRow::new(asset).fields()).as_bytes()).await?;
+                rows += 1;
+            }
+        }
+        ExportFormat::Json => {
+            out.write_all(b"[").await?;
+            for asset in assets {
+                let row = serde_json::to_string_pretty(&ExportRow::new(asset))?;
+                let separator = if rows == 0 { "\n  " } else { ",\n  " };
+                out.write_all(format!("{}{}", separator, row.replace('\n', "\n  ")).as_bytes()).await?;
+                rows += 1;
+            }
+            out.write_all(if rows == 0 { b"]\n" } else { b"\n]\n" }).await?;
+        }
+        ExportFormat::Markdown => {
+            out.write_all(markdown_line(&ExportRow::HEADERS.map(String::from)).as_bytes()).await?;
+            out.write_all(format!("|{}\n", " --- |".repeat(ExportRow::HEADERS.len())).as_bytes()).await?;
+            for asset in assets {
+                out.write_all(markdown_line(&ExportRow::new(asset).fields()).as_bytes()).await?;
+                rows += 1;
+            }
+        }
+    }
+    Ok(rows)
+}
+
+fn csv_line(fields: &[String]) -> String {
+    let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    format!("{}\r\n", fields.join(","))
+}
+
+/// A field quoted when it holds a comma, quote or line break, with quotes doubled (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn markdown_line(fields: &[String]) -> String {
+    let cells: Vec<String> = fields.iter().map(|field| markdown_cell(field)).collect();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+/// A table cell with pipes escaped and line breaks flattened, so the row stays one line
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
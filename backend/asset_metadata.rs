@@ -0,0 +1,120 @@
+use serde_json::{json, Value};
+use anyhow::{Context, Result};
+use std::sync::Arc;
+
+use crate::blueprint_parser;
+use crate::mesh_parser;
+use crate::pak_parser::OpenPak;
+use crate::preview::Asset;
+use crate::texture_parser;
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// Packages read at once during a deep scan
+pub const DEFAULT_SCAN_CONCURRENCY: usize = 8;
+
+/// Reads headline metadata for the asset detail pane from a package
+///
+/// Keys match the mock assets: textures get `resolution`/`format`/`mip_levels`, meshes
+/// `lod_count`, sounds `duration`/`sample_rate`/`channels`, blueprints `parent_class`.
+/// Returns None for classes without headline metadata.
+pub fn extract_metadata(data: &[u8]) -> Result<Option<Value>> {
+    let package = Package::parse(data)?;
+    let export = package.primary_export().context("Package has no exports")?;
+    let class = package.export_class_name(export);
+
+    let metadata = match class.as_str() {
+        c if texture_parser::is_texture_class(c) => {
+            let info = texture_parser::parse_texture(data)?;
+            json!({
+                "class": info.class,
+                "resolution": format!("{}x{}", info.width, info.height),
+                "format": info.pixel_format,
+                "mip_levels": info.mip_levels,
+            })
+        }
+        "StaticMesh" => json!({
+            "class": class,
+            "lod_count": mesh_parser::static_mesh_lod_count(data)?,
+        }),
+        "SkeletalMesh" => {
+            let info = mesh_parser::parse_skeletal_mesh(data)?;
+            json!({
+                "class": class,
+                "lod_count": info.lods.len(),
+                "bones": info.bones.len(),
+                "skeleton": info.skeleton,
+            })
+        }
+        "SoundWave" => {
+            let mut reader = ArchiveReader::new(package.export_data(data, export)?);
+            let properties = uasset_parser::read_properties(&package, &mut reader, &class)?;
+            json!({
+                "class": class,
+                "duration": properties.get("Duration").and_then(Value::as_f64).map(format_duration),
+                "sample_rate": properties.get("SampleRate").and_then(Value::as_i64),
+                "channels": properties.get("NumChannels").and_then(Value::as_i64),
+            })
+        }
+        c if c.ends_with("BlueprintGeneratedClass") || c.ends_with("Blueprint") => {
+            let info = blueprint_parser::parse_blueprint(data)?;
+            json!({
+                "class": class,
+                "parent_class": info.parent_class,
+            })
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(metadata))
+}
+
+/// Formats seconds the way the detail pane shows durations ("00:02:30")
+fn format_duration(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    format!("{:02}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+}
+
+/// Adds extracted keys to existing metadata (e.g. registry tags), replacing non-object metadata
+fn merge_metadata(existing: &mut Option<Value>, extracted: Value) {
+    match (existing.as_mut(), extracted) {
+        (Some(Value::Object(existing)), Value::Object(extracted)) => existing.extend(extracted),
+        (_, extracted) => *existing = Some(extracted),
+    }
+}
+
+/// Fills `metadata` for the package assets of one pak, reading `concurrency` packages at a time
+///
+/// Extracted keys are merged into existing metadata; unreadable packages keep theirs.
+pub async fn populate_metadata(pak_path: &str, assets: &mut [Asset], concurrency: usize) -> Result<()> {
+    let pak = Arc::new(OpenPak::open(pak_path).await?);
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency.max(1)));
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, asset) in assets.iter().enumerate() {
+        let lower = asset.path.to_lowercase();
+        if asset.is_encrypted == Some(true) || !(lower.ends_with(".uasset") || lower.ends_with(".umap")) {
+            continue;
+        }
+
+        let pak = pak.clone();
+        let semaphore = semaphore.clone();
+        let path = asset.path.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let metadata = match pak.extract_package(&path).await {
+                Ok(data) => extract_metadata(&data),
+                Err(e) => Err(e),
+            };
+            (index, path, metadata)
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, _, Ok(Some(metadata)))) => merge_metadata(&mut assets[index].metadata, metadata),
+            Ok((_, _, Ok(None))) => {}
+            Ok((_, path, Err(e))) => tracing::debug!("No metadata for {}: {:#}", path, e),
+            Err(e) => tracing::error!("Metadata task failed: {}", e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// The form an entry or package path is compared in, so spellings of one path meet
+///
+/// Separators become "/", the mount prefix ("../../../", "./", a leading "/") and empty
+/// or "." segments are dropped, ".." steps back a folder, and the first segment (the
+/// mount root: "Game", "Engine", a plugin) is lowercased; the rest keeps its case.
+/// "..\\..\\..\\Game\\Content\\Hero.uasset" and "/game/Content/Hero.uasset" both give
+/// "game/Content/Hero.uasset". Normalizing twice changes nothing.
+///
+/// This is a key, not something to show: assets keep the path as their pak spells it.
+pub fn normalize_asset_path(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    let mut normalized = String::with_capacity(path.len());
+    for (i, segment) in segments.iter().enumerate() {
+        if i == 0 {
+            normalized.push_str(&segment.to_lowercase());
+        } else {
+            normalized.push('/');
+            normalized.push_str(segment);
+        }
+    }
+    normalized
+}
+
+/// `path` with "\\" separators turned into "/", borrowed when it has none
+pub fn with_forward_slashes(path: &str) -> Cow<'_, str> {
+    if path.contains('\\') {
+        Cow::Owned(path.replace('\\', "/"))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// First spelling seen of each normalized path, so a graph built from several sources
+/// names a package one way however each source spells it
+#[derive(Debug, Default)]
+pub struct PathSpellings {
+    first: HashMap<String, String>,
+}
+
+impl PathSpellings {
+    /// The spelling `path` goes by: its own with forward slashes, unless an equivalent one
+    /// came first
+    pub fn spelling(&mut self, path: &str) -> String {
+        self.first
+            .entry(normalize_asset_path(path))
+            .or_insert_with(|| with_forward_slashes(path).into_owned())
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Paths as different paks and tools spell them
+    const SHAPES: &[&str] = &[
+        "Game/Content/UI/Icons/T_Sword.uasset",
+        "Engine/Content/EditorMaterials/M_Grid.uasset",
+        "MyPlugin/Content/Maps/Arena.umap",
+        "Game/Content/Characters/Hero Mesh/SK_Hero.uasset",
+        "Game/Content/Ünïcode/Ärger.uasset",
+        "Game",
+    ];
+
+    /// Equivalent spellings of `path`: mount prefixes, separators, empty and "." segments,
+    /// detours through "..", and the root in other cases
+    fn spellings(path: &str) -> Vec<String> {
+        let (root, rest) = path.split_once('/').unwrap_or((path, ""));
+        let roots = [root.to_string(), root.to_lowercase(), root.to_uppercase()];
+        let prefixes = ["", "/", "./", "../../../", "..\\..\\..\\", "/./"];
+        let mut spellings = Vec::new();
+        for root in &roots {
+            for prefix in prefixes {
+                for separator in ["/", "\\", "//", "/./"] {
+                    let tail = rest.replace('/', separator);
+                    let joined = if tail.is_empty() { root.clone() } else { format!("{}{}{}", root, separator, tail) };
+                    spellings.push(format!("{}{}", prefix, joined));
+                }
+            }
+            spellings.push(format!("{}/Detour/../{}", root, rest));
+        }
+        spellings
+    }
+
+    #[test]
+    fn equivalent_spellings_collide() {
+        for &path in SHAPES {
+            let expected = normalize_asset_path(path);
+            for spelling in spellings(path) {
+                assert_eq!(normalize_asset_path(&spelling), expected, "{:?}", spelling);
+            }
+        }
+        assert_eq!(normalize_asset_path("..\\..\\..\\Game\\Content\\Hero.uasset"), "game/Content/Hero.uasset");
+    }
+
+    #[test]
+    fn normalizing_is_idempotent() {
+        for &path in SHAPES {
+            for spelling in spellings(path) {
+                let once = normalize_asset_path(&spelling);
+                assert_eq!(normalize_asset_path(&once), once, "{:?}", spelling);
+                assert!(!once.contains('\\') && !once.starts_with('/') && !once.contains("//"));
+            }
+        }
+    }
+
+    #[test]
+    fn different_paths_stay_apart() {
+        let mut normalized: Vec<String> = SHAPES.iter().map(|path| normalize_asset_path(path)).collect();
+        normalized.push(normalize_asset_path("Game/content/UI/Icons/T_Sword.uasset"));
+        normalized.push(normalize_asset_path("Game/Content/UI/T_Sword.uasset"));
+        let count = normalized.len();
+        normalized.sort();
+        normalized.dedup();
+        assert_eq!(normalized.len(), count);
+    }
+
+    #[test]
+    fn first_spelling_wins() {
+        let mut names = PathSpellings::default();
+        assert_eq!(names.spelling("Game\\Content\\Hero.uasset"), "Game/Content/Hero.uasset");
+        assert_eq!(names.spelling("../../../game/Content/Hero.uasset"), "Game/Content/Hero.uasset");
+        assert_eq!(names.spelling("/Game/Content/Villain.uasset"), "/Game/Content/Villain.uasset");
+    }
+}
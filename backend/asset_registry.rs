@@ -0,0 +1,567 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::asset_path::{normalize_asset_path, PathSpellings};
+use crate::dependency_map::{DependencyKind, DependencyMap};
+use crate::pak_parser::OpenPak;
+use crate::uasset_parser::ArchiveReader;
+
+/// FAssetRegistryVersion GUID {717F9EE7-E9B0493A-88B39132-1B388107}
+const REGISTRY_VERSION_GUID: [u8; 16] = [
+    0xE7, 0x9E, 0x7F, 0x71, 0x3A, 0x49, 0xB0, 0xE9, 0x32, 0x91, 0xB3, 0x88, 0x07, 0x81, 0x38, 0x1B,
+];
+
+// FAssetRegistryVersion values that change the layout read here
+const VER_ADDED_HARD_MANAGE: i32 = 5;
+const VER_ADDED_DEPENDENCY_FLAGS: i32 = 7;
+const VER_FIXED_TAGS: i32 = 8;
+const VER_CLASS_PATHS: i32 = 14;
+const VER_REMOVE_ASSET_PATH_FNAMES: i32 = 15;
+const VER_ADDED_HEADER: i32 = 16;
+
+// Tag store markers
+const STORE_OLD_BEGIN_MAGIC: u32 = 0x1234_5678;
+const STORE_BEGIN_MAGIC: u32 = 0x1234_5679;
+const STORE_END_MAGIC: u32 = 0x8765_4321;
+
+/// Bits per package dependency in the flag array; bit 0 marks a hard reference
+const PACKAGE_FLAG_WIDTH: usize = 3;
+const MANAGE_FLAG_WIDTH: usize = 1;
+
+/// Registry tags asset listings include by default (`Settings::listed_tags`)
+pub const DEFAULT_LISTED_TAGS: &[&str] =
+    &["PrimaryAssetType", "PrimaryAssetName", "ParentClass", "NativeParentClass", "RowStructure"];
+
+/// One asset listed in the registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryAsset {
+    /// "/Game/Characters/Hero"
+    pub package_name: String,
+    pub package_path: String,
+    pub asset_name: String,
+    /// Class name without its package ("Texture2D")
+    pub class: String,
+    pub tags: BTreeMap<String, String>,
+    pub chunk_ids: Vec<i32>,
+}
+
+/// A package-to-package edge from the registry's dependency section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryDependency {
+    pub package: String,
+    pub dependency: String,
+    /// False for soft references (TSoftObjectPtr, soft object paths)
+    pub hard: bool,
+}
+
+/// Contents of a cooked AssetRegistry.bin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRegistry {
+    pub version: i32,
+    pub assets: Vec<RegistryAsset>,
+    pub dependencies: Vec<RegistryDependency>,
+}
+
+impl AssetRegistry {
+    /// The package dependency graph keyed by package name
+    ///
+    /// Names that differ only in separators or the root's case ("/Game/X", "/game/X") are
+    /// one node, under the first spelling.
+    pub fn dependency_map(&self) -> DependencyMap {
+        let mut map = DependencyMap::new();
+        let mut names = PathSpellings::default();
+        for edge in &self.dependencies {
+            let kind = if edge.hard { DependencyKind::Hard } else { DependencyKind::Soft };
+            map.add_typed_dependency(&names.spelling(&edge.package), &names.spelling(&edge.dependency), kind);
+        }
+        map.optimize();
+        for (package, asset) in self.assets_by_package() {
+            map.node_mut(&names.spelling(package)).asset_type = Some(asset.class.clone());
+        }
+        map
+    }
+
+    /// The main asset of a package (the one named after it), or its first asset
+    pub fn asset_for_package(&self, package_name: &str) -> Option<&RegistryAsset> {
+        let short_name = package_name.rsplit('/').next().unwrap_or(package_name);
+        let mut in_package = self.assets.iter().filter(|a| a.package_name == package_name);
+        let first = in_package.clone().next();
+        in_package.find(|a| a.asset_name == short_name).or(first)
+    }
+
+    /// Main asset per package name, for matching pak entries
+    pub fn assets_by_package(&self) -> HashMap<&str, &RegistryAsset> {
+        let mut by_package = HashMap::new();
+        for asset in &self.assets {
+            let short_name = asset.package_name.rsplit('/').next().unwrap_or(&asset.package_name);
+            if asset.asset_name == short_name || !by_package.contains_key(asset.package_name.as_str()) {
+                by_package.insert(asset.package_name.as_str(), asset);
+            }
+        }
+        by_package
+    }
+}
+
+/// Converts a pak entry path to its package name
+///
+/// "Game/Content/Maps/Arena.umap" becomes "/Game/Maps/Arena"; plugin content
+/// ("Game/Plugins/Weapons/Content/Rifle.uasset") becomes "/Weapons/Rifle".
+pub fn package_name_for_path(path: &str) -> Option<String> {
+    // Normalizing lowercases the root, so "Content" and "Engine" are matched in any case
+    let path = normalize_asset_path(path);
+    let stem = path.rsplit_once('.').map_or(path.as_str(), |(stem, _)| stem);
+    let segments: Vec<&str> = stem.split('/').collect();
+
+    let (_, folders) = segments.split_last()?;
+    let content = folders.iter().rposition(|segment| segment.eq_ignore_ascii_case("Content"))?;
+    let (before, after) = (&segments[..content], &segments[content + 1..]);
+    let mount = match before.last() {
+        Some(name) if name.eq_ignore_ascii_case("Engine") => "Engine",
+        Some(name) if before.iter().any(|segment| segment.eq_ignore_ascii_case("Plugins")) => name,
+        _ => "Game",
+    };
+    Some(format!("/{}/{}", mount, after.join("/")))
+}
+
+/// Parses a cooked AssetRegistry.bin (FAssetRegistryVersion 5 to 17, UE4.25 to 5.3)
+pub fn parse_asset_registry(data: &[u8]) -> Result<AssetRegistry> {
+    let mut reader = ArchiveReader::new(data);
+    if reader.read_guid()? != REGISTRY_VERSION_GUID {
+        bail!("Not an asset registry (missing version GUID)");
+    }
+    let version = reader.read_i32()?;
+    if version < VER_ADDED_HARD_MANAGE {
+        bail!("Asset registry version {} is older than UE4.25 and not supported", version);
+    }
+    if version >= VER_ADDED_HEADER {
+        reader.read_bool()?; // bFilterEditorOnlyData
+    }
+
+    let (names, store) = if version >= VER_FIXED_TAGS {
+        let names = Names(read_name_batch(&mut reader).context("Failed to read name batch")?);
+        let store = TagStore::read(&mut reader, &names, version).context("Failed to read tag store")?;
+        (names, Some(store))
+    } else {
+        (Names(read_name_table(&mut reader).context("Failed to read name table")?), None)
+    };
+
+    let asset_count = reader.read_count(8)?;
+    let mut assets = Vec::with_capacity(asset_count);
+    for _ in 0..asset_count {
+        assets.push(read_asset(&mut reader, &names, store.as_ref(), version).context("Failed to read asset data")?);
+    }
+
+    let dependencies = read_dependencies(&mut reader, &names, version).context("Failed to read dependencies")?;
+
+    Ok(AssetRegistry { version, assets, dependencies })
+}
+
+/// Finds and parses the AssetRegistry.bin shipped in a pak; None when the pak has none
+pub async fn load_from_pak(pak: &OpenPak) -> Result<Option<AssetRegistry>> {
+    let Some(entry) = pak
+        .pak()
+        .entries
+        .iter()
+        .find(|e| e.filename.rsplit('/').next() == Some("AssetRegistry.bin"))
+    else {
+        return Ok(None);
+    };
+    let data = pak.extract_file(&entry.filename).await?;
+    parse_asset_registry(&data).map(Some)
+}
+
+static REGISTRIES: OnceLock<Mutex<HashMap<String, Option<Arc<AssetRegistry>>>>> = OnceLock::new();
+
+/// Loads a pak's registry once per process; later calls reuse the parsed result
+///
+/// Paks without a readable registry are remembered as None so they aren't re-read.
+pub async fn cached_registry(pak_path: &str) -> Option<Arc<AssetRegistry>> {
+    let registries = REGISTRIES.get_or_init(Default::default);
+    if let Some(registry) = registries.lock().unwrap().get(pak_path) {
+        return registry.clone();
+    }
+
+    let registry = match OpenPak::open(pak_path).await {
+        Ok(pak) => match load_from_pak(&pak).await {
+            Ok(registry) => registry.map(Arc::new),
+            Err(e) => {
+                tracing::warn!("Failed to read asset registry from {}: {:#}", pak_path, e);
+                None
+            }
+        },
+        Err(e) => {
+            tracing::warn!("Failed to open {} for its asset registry: {:#}", pak_path, e);
+            None
+        }
+    };
+    registries.lock().unwrap().insert(pak_path.to_string(), registry.clone());
+    registry
+}
+
+struct Names(Vec<String>);
+
+impl Names {
+    fn get(&self, index: u32) -> Result<&str> {
+        self.0
+            .get(index as usize)
+            .map(String::as_str)
+            .with_context(|| format!("Name index {} out of range ({} names)", index, self.0.len()))
+    }
+
+    /// Reads a numbered FName (name index + number)
+    fn read(&self, reader: &mut ArchiveReader) -> Result<String> {
+        let index = reader.read_u32()?;
+        let number = reader.read_u32()?;
+        Ok(with_number(self.get(index)?, number))
+    }
+
+    /// Reads a numberless FName (name index only)
+    fn read_numberless(&self, reader: &mut ArchiveReader) -> Result<String> {
+        Ok(self.get(reader.read_u32()?)?.to_string())
+    }
+
+    /// Reads a class reference: an FTopLevelAssetPath from ClassPaths on, a plain FName before
+    fn read_class(&self, reader: &mut ArchiveReader, version: i32) -> Result<String> {
+        if version >= VER_CLASS_PATHS {
+            let _package = self.read(reader)?;
+            self.read(reader)
+        } else {
+            self.read(reader)
+        }
+    }
+}
+
+fn with_number(name: &str, number: u32) -> String {
+    match number {
+        0 => name.to_string(),
+        n => format!("{}_{}", name, n - 1),
+    }
+}
+
+/// Reads an FNameBatch: hashes, 2-byte headers, then the packed string data
+fn read_name_batch(reader: &mut ArchiveReader) -> Result<Vec<String>> {
+    let count = reader.read_count(10)?;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let string_bytes = reader.read_u32()? as usize;
+    reader.read_u64()?; // Hash algorithm version
+    reader.skip(count * 8)?; // Name hashes
+
+    let headers = reader.read_bytes(count * 2)?;
+    let strings = reader.read_bytes(string_bytes)?;
+
+    let mut names = Vec::with_capacity(count);
+    let mut offset = 0;
+    for header in headers.chunks_exact(2) {
+        let wide = header[0] & 0x80 != 0;
+        let len = (((header[0] & 0x7F) as usize) << 8) | header[1] as usize;
+        let name = if wide {
+            // Wide strings are 2-byte aligned within the blob
+            offset += offset % 2;
+            let bytes = strings.get(offset..offset + len * 2).context("Truncated name batch")?;
+            offset += len * 2;
+            let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            let bytes = strings.get(offset..offset + len).context("Truncated name batch")?;
+            offset += len;
+            String::from_utf8_lossy(bytes).into_owned()
+        };
+        names.push(name);
+    }
+    Ok(names)
+}
+
+/// Reads the name table of pre-FixedTags registries (offset prefix, table stored out of line)
+fn read_name_table(reader: &mut ArchiveReader) -> Result<Vec<String>> {
+    let table_offset = usize::try_from(reader.read_i64()?).context("Negative name table offset")?;
+    let resume = reader.position();
+    reader.seek(table_offset)?;
+
+    let count = reader.read_count(5)?;
+    let mut names = Vec::with_capacity(count);
+    for _ in 0..count {
+        names.push(reader.read_fstring()?);
+        reader.skip(4)?; // Non-case-preserving and case-preserving hashes
+    }
+
+    reader.seek(resume)?;
+    Ok(names)
+}
+
+/// The deduplicated tag value store of FixedTags+ registries
+struct TagStore {
+    numberless_pairs: Vec<(String, u32)>,
+    pairs: Vec<(String, u32)>,
+    numberless_names: Vec<String>,
+    names: Vec<String>,
+    numberless_export_paths: Vec<String>,
+    export_paths: Vec<String>,
+    texts: Vec<String>,
+    ansi_offsets: Vec<u32>,
+    wide_offsets: Vec<u32>,
+    ansi_strings: Vec<u8>,
+    wide_strings: Vec<u16>,
+}
+
+impl TagStore {
+    fn read(reader: &mut ArchiveReader, names: &Names, version: i32) -> Result<Self> {
+        let text_first = match reader.read_u32()? {
+            STORE_BEGIN_MAGIC => true,
+            STORE_OLD_BEGIN_MAGIC => false,
+            magic => bail!("Unexpected tag store magic {:#x}", magic),
+        };
+
+        let mut counts = [0usize; 11];
+        for count in &mut counts {
+            *count = reader.read_count(0)?;
+        }
+        let [numberless_names, numbered_names, numberless_exports, exports, texts, ansi_offsets, wide_offsets, ansi_bytes, wide_units, numberless_pairs, pairs] =
+            counts;
+
+        let read_texts = |reader: &mut ArchiveReader| -> Result<Vec<String>> {
+            (0..texts).map(|_| reader.read_fstring()).collect()
+        };
+
+        let mut store = TagStore {
+            numberless_pairs: Vec::new(),
+            pairs: Vec::new(),
+            numberless_names: Vec::new(),
+            names: Vec::new(),
+            numberless_export_paths: Vec::new(),
+            export_paths: Vec::new(),
+            texts: Vec::new(),
+            ansi_offsets: Vec::new(),
+            wide_offsets: Vec::new(),
+            ansi_strings: Vec::new(),
+            wide_strings: Vec::new(),
+        };
+
+        if text_first {
+            reader.read_u32()?; // Text data size
+            store.texts = read_texts(reader)?;
+        }
+        store.numberless_names = (0..numberless_names).map(|_| names.read_numberless(reader)).collect::<Result<_>>()?;
+        store.names = (0..numbered_names).map(|_| names.read(reader)).collect::<Result<_>>()?;
+        store.numberless_export_paths = (0..numberless_exports)
+            .map(|_| read_export_path(reader, names, version, true))
+            .collect::<Result<_>>()?;
+        store.export_paths = (0..exports)
+            .map(|_| read_export_path(reader, names, version, false))
+            .collect::<Result<_>>()?;
+        if !text_first {
+            store.texts = read_texts(reader)?;
+        }
+        store.ansi_offsets = (0..ansi_offsets).map(|_| reader.read_u32()).collect::<Result<_>>()?;
+        store.wide_offsets = (0..wide_offsets).map(|_| reader.read_u32()).collect::<Result<_>>()?;
+        store.ansi_strings = reader.read_bytes(ansi_bytes)?.to_vec();
+        store.wide_strings = reader
+            .read_bytes(wide_units * 2)?
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        store.numberless_pairs = (0..numberless_pairs)
+            .map(|_| Ok((names.read_numberless(reader)?, reader.read_u32()?)))
+            .collect::<Result<_>>()?;
+        store.pairs = (0..pairs)
+            .map(|_| Ok((names.read(reader)?, reader.read_u32()?)))
+            .collect::<Result<_>>()?;
+
+        if reader.read_u32()? != STORE_END_MAGIC {
+            bail!("Tag store end marker missing");
+        }
+        Ok(store)
+    }
+
+    /// Resolves an asset's tag map from its FPartialMapHandle
+    fn tags(&self, handle: u64) -> BTreeMap<String, String> {
+        let numberless = handle >> 63 != 0;
+        let count = ((handle >> 32) & 0xFFFF) as usize;
+        let begin = (handle & 0xFFFF_FFFF) as usize;
+
+        let pairs = if numberless { &self.numberless_pairs } else { &self.pairs };
+        pairs
+            .iter()
+            .skip(begin)
+            .take(count)
+            .map(|(key, value)| (key.clone(), self.value(*value).unwrap_or_default()))
+            .collect()
+    }
+
+    /// Resolves an FValueId: the low 3 bits are the value type, the rest its index
+    fn value(&self, id: u32) -> Option<String> {
+        let index = (id >> 3) as usize;
+        match id & 0x7 {
+            0 => {
+                let start = *self.ansi_offsets.get(index)? as usize;
+                let bytes = self.ansi_strings.get(start..)?;
+                let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+            }
+            1 => {
+                let start = *self.wide_offsets.get(index)? as usize;
+                let units = self.wide_strings.get(start..)?;
+                let end = units.iter().position(|&u| u == 0).unwrap_or(units.len());
+                Some(String::from_utf16_lossy(&units[..end]))
+            }
+            2 => self.numberless_names.get(index).cloned(),
+            3 => self.names.get(index).cloned(),
+            4 => self.numberless_export_paths.get(index).cloned(),
+            5 => self.export_paths.get(index).cloned(),
+            6 => self.texts.get(index).cloned(),
+            _ => None,
+        }
+    }
+}
+
+/// Reads an export path as "Class'Package.Object'"
+fn read_export_path(reader: &mut ArchiveReader, names: &Names, version: i32, numberless: bool) -> Result<String> {
+    let read = |reader: &mut ArchiveReader| if numberless { names.read_numberless(reader) } else { names.read(reader) };
+    let class = if version >= VER_CLASS_PATHS {
+        let _package = read(reader)?;
+        read(reader)?
+    } else {
+        read(reader)?
+    };
+    let object = read(reader)?;
+    let package = read(reader)?;
+    Ok(format!("{}'{}.{}'", class, package, object))
+}
+
+fn read_asset(reader: &mut ArchiveReader, names: &Names, store: Option<&TagStore>, version: i32) -> Result<RegistryAsset> {
+    if version < VER_REMOVE_ASSET_PATH_FNAMES {
+        names.read(reader)?; // ObjectPath
+    }
+    let package_path = names.read(reader)?;
+    let class = names.read_class(reader, version)?;
+    let package_name = names.read(reader)?;
+    let asset_name = names.read(reader)?;
+
+    let tags = match store {
+        Some(store) => store.tags(reader.read_u64()?),
+        None => {
+            let count = reader.read_count(12)?;
+            (0..count)
+                .map(|_| Ok((names.read(reader)?, reader.read_fstring()?)))
+                .collect::<Result<_>>()?
+        }
+    };
+
+    let chunk_count = reader.read_count(4)?;
+    let chunk_ids = (0..chunk_count).map(|_| reader.read_i32()).collect::<Result<_>>()?;
+    reader.read_u32()?; // PackageFlags
+
+    Ok(RegistryAsset {
+        package_name,
+        package_path,
+        asset_name,
+        class,
+        tags,
+        chunk_ids,
+    })
+}
+
+/// Reads an FAssetIdentifier; only package identifiers take part in the package graph
+fn read_identifier(reader: &mut ArchiveReader, names: &Names) -> Result<Option<String>> {
+    let fields = reader.read_u8()?;
+    let mut package = None;
+    let mut is_package = true;
+    for bit in 0..4 {
+        if fields & (1 << bit) == 0 {
+            continue;
+        }
+        let name = names.read(reader)?;
+        if bit == 0 {
+            package = Some(name);
+        } else {
+            // PrimaryAssetType, ObjectName or ValueName: not a plain package node
+            is_package = false;
+        }
+    }
+    Ok(package.filter(|_| is_package))
+}
+
+fn read_indices(reader: &mut ArchiveReader, count: usize) -> Result<Vec<usize>> {
+    (0..count)
+        .map(|_| usize::try_from(reader.read_i32()?).context("Negative depends node index"))
+        .collect()
+}
+
+/// Reads `TArray<int32>` node indices plus, when `flag_width` > 0, their packed flag bits
+fn read_flagged_indices(reader: &mut ArchiveReader, flag_width: usize) -> Result<(Vec<usize>, Vec<u32>)> {
+    let count = reader.read_count(4)?;
+    let indices = read_indices(reader, count)?;
+    if flag_width == 0 {
+        return Ok((indices, Vec::new()));
+    }
+    let bit_count = reader.read_count(0)?;
+    let words = (0..bit_count.div_ceil(32)).map(|_| reader.read_u32()).collect::<Result<_>>()?;
+    Ok((indices, words))
+}
+
+/// A depends node: its package (None for non-package identifiers) and its (node, hard) edges
+type DependsNode = (Option<String>, Vec<(usize, bool)>);
+
+fn read_dependencies(reader: &mut ArchiveReader, names: &Names, version: i32) -> Result<Vec<RegistryDependency>> {
+    let mut nodes: Vec<DependsNode> = Vec::new();
+
+    if version >= VER_ADDED_DEPENDENCY_FLAGS {
+        let section_size = usize::try_from(reader.read_i64()?).context("Negative dependency section size")?;
+        let section_end = reader.position() + section_size;
+
+        let count = reader.read_count(1)?;
+        for _ in 0..count {
+            let package = read_identifier(reader, names)?;
+            let (packages, flags) = read_flagged_indices(reader, PACKAGE_FLAG_WIDTH)?;
+            read_flagged_indices(reader, 0)?; // Searchable name dependencies
+            read_flagged_indices(reader, MANAGE_FLAG_WIDTH)?; // Manage dependencies
+            read_flagged_indices(reader, 0)?; // Referencers
+
+            let edges = packages
+                .into_iter()
+                .enumerate()
+                .map(|(i, node)| {
+                    let bit = i * PACKAGE_FLAG_WIDTH;
+                    let hard = flags.get(bit / 32).is_some_and(|word| word & (1 << (bit % 32)) != 0);
+                    (node, hard)
+                })
+                .collect();
+            nodes.push((package, edges));
+        }
+        reader.seek(section_end)?;
+    } else {
+        let count = reader.read_count(1)?;
+        for _ in 0..count {
+            let package = read_identifier(reader, names)?;
+            let hard = reader.read_count(0)?;
+            let soft = reader.read_count(0)?;
+            let searchable_names = reader.read_count(0)?;
+            let soft_manage = reader.read_count(0)?;
+            let hard_manage = if version >= VER_ADDED_HARD_MANAGE { reader.read_count(0)? } else { 0 };
+            let referencers = reader.read_count(0)?;
+
+            let mut edges: Vec<(usize, bool)> = read_indices(reader, hard)?.into_iter().map(|n| (n, true)).collect();
+            edges.extend(read_indices(reader, soft)?.into_iter().map(|n| (n, false)));
+            read_indices(reader, searchable_names + soft_manage + hard_manage + referencers)?;
+            nodes.push((package, edges));
+        }
+    }
+
+    let mut dependencies = Vec::new();
+    for (package, edges) in &nodes {
+        let Some(package) = package else { continue };
+        for &(node, hard) in edges {
+            if let Some((Some(dependency), _)) = nodes.get(node) {
+                dependencies.push(RegistryDependency {
+                    package: package.clone(),
+                    dependency: dependency.clone(),
+                    hard,
+                });
+            }
+        }
+    }
+    Ok(dependencies)
+}
@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::preview::Asset;
+
+/// Most levels a tree request expands at once
+pub const MAX_DEPTH: u32 = 8;
+
+/// Folders of the loaded assets as a trie, each with the totals of everything beneath it
+///
+/// Folder names are interned, so a name like "Textures" is stored once however many
+/// folders have it. Files are positions in the asset slice the tree was built from.
+#[derive(Debug, Clone)]
+pub struct AssetTree {
+    names: Vec<Arc<str>>,
+    name_ids: HashMap<Arc<str>, u32>,
+    /// The root is the first
+    folders: Vec<Folder>,
+    /// Folder for each (parent, name)
+    children: HashMap<(u32, u32), u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Folder {
+    name: u32,
+    subfolders: Vec<u32>,
+    files: Vec<usize>,
+    asset_count: usize,
+    total_size: u64,
+}
+
+/// A folder of the tree, with its subfolders and files down to the requested depth
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TreeFolder {
+    /// Empty for the root
+    pub name: String,
+    /// Folder names from the root joined with "/"; empty for the root
+    pub path: String,
+    /// Assets anywhere beneath
+    pub asset_count: usize,
+    /// Uncompressed bytes of the assets anywhere beneath
+    pub total_size: u64,
+    /// Subfolders directly inside, whether or not they're listed
+    pub folder_count: usize,
+    /// Files directly inside, whether or not they're listed
+    pub file_count: usize,
+    /// By name; absent below the requested depth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(no_recursion)]
+    pub folders: Option<Vec<TreeFolder>>,
+    /// By name; absent below the requested depth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Asset>>)]
+    pub files: Option<Vec<Arc<Asset>>>,
+}
+
+impl AssetTree {
+    pub fn new(assets: &[Arc<Asset>]) -> Self {
+        let mut tree = AssetTree {
+            names: Vec::new(),
+            name_ids: HashMap::new(),
+            folders: Vec::new(),
+            children: HashMap::new(),
+        };
+        let root_name = tree.intern("");
+        tree.folders.push(Folder { name: root_name, ..Default::default() });
+
+        for (i, asset) in assets.iter().enumerate() {
+            let segments: Vec<&str> = path_segments(&asset.path).collect();
+            let Some((_, folders)) = segments.split_last() else {
+                continue;
+            };
+            let mut folder = 0;
+            tree.add_to(folder, asset.size);
+            for name in folders {
+                folder = tree.subfolder(folder, name);
+                tree.add_to(folder, asset.size);
+            }
+            tree.folders[folder as usize].files.push(i);
+        }
+
+        let keys: Vec<(String, String)> = tree.folders.iter().map(|folder| name_key(&tree.names[folder.name as usize])).collect();
+        for folder in &mut tree.folders {
+            folder.subfolders.sort_by(|&a, &b| keys[a as usize].cmp(&keys[b as usize]));
+            folder.files.sort_by_cached_key(|&i| name_key(&assets[i].file_name));
+        }
+        tree
+    }
+
+    /// The folder at `root` ("Game/UI", either separator, leading and trailing ones
+    /// ignored; "" for the top), expanded `depth` levels down; None when there's no such
+    /// folder
+    ///
+    /// `assets` must be the slice the tree was built from.
+    pub fn listing(&self, assets: &[Arc<Asset>], root: &str, depth: u32) -> Option<TreeFolder> {
+        let mut folder = 0;
+        let mut path = Vec::new();
+        for name in path_segments(root) {
+            let name_id = *self.name_ids.get(name)?;
+            folder = *self.children.get(&(folder, name_id))?;
+            path.push(name);
+        }
+        Some(self.expand(assets, folder, path.join("/"), depth.min(MAX_DEPTH)))
+    }
+
+    fn expand(&self, assets: &[Arc<Asset>], id: u32, path: String, depth: u32) -> TreeFolder {
+        let folder = &self.folders[id as usize];
+        let (folders, files) = if depth == 0 {
+            (None, None)
+        } else {
+            let folders = folder
+                .subfolders
+                .iter()
+                .map(|&sub| {
+                    let name = &self.names[self.folders[sub as usize].name as usize];
+                    let path = if path.is_empty() { name.to_string() } else { format!("{}/{}", path, name) };
+                    self.expand(assets, sub, path, depth - 1)
+                })
+                .collect();
+            let files = folder.files.iter().map(|&i| assets[i].clone()).collect();
+            (Some(folders), Some(files))
+        };
+        TreeFolder {
+            name: self.names[folder.name as usize].to_string(),
+            path,
+            asset_count: folder.asset_count,
+            total_size: folder.total_size,
+            folder_count: folder.subfolders.len(),
+            file_count: folder.files.len(),
+            folders,
+            files,
+        }
+    }
+
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.name_ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        let name: Arc<str> = Arc::from(name);
+        self.names.push(name.clone());
+        self.name_ids.insert(name, id);
+        id
+    }
+
+    /// The folder `name` in `parent`, created when it's not there yet
+    fn subfolder(&mut self, parent: u32, name: &str) -> u32 {
+        let name = self.intern(name);
+        if let Some(&id) = self.children.get(&(parent, name)) {
+            return id;
+        }
+        let id = self.folders.len() as u32;
+        self.folders.push(Folder { name, ..Default::default() });
+        self.folders[parent as usize].subfolders.push(id);
+        self.children.insert((parent, name), id);
+        id
+    }
+
+    fn add_to(&mut self, folder: u32, size: u64) {
+        let folder = &mut self.folders[folder as usize];
+        folder.asset_count += 1;
+        folder.total_size += size;
+    }
+}
+
+impl Default for AssetTree {
+    fn default() -> Self {
+        AssetTree::new(&[])
+    }
+}
+
+/// Folder and file names of a path, skipping empty, "." and ".." segments (the
+/// "../../../" of mount points)
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split(['/', '\\']).filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+}
+
+/// Sorts names case-insensitively, with a plain comparison as the tie-break so the order
+/// is total
+fn name_key(name: &str) -> (String, String) {
+    (name.to_lowercase(), name.to_string())
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::intern::{self, intern};
+
+/// Kind of an asset, serialized as a lowercase snake_case id ("static_mesh")
+///
+/// Parsing never fails: UE class names ("Texture2D", "SoundWave"), older ids ("mesh",
+/// "audio") and display names ("Static Mesh") map onto the variants, and anything else
+/// becomes `Other` with its name in snake_case ("CurveTable" is `other("curve_table")`).
+/// `From` interns the names of other kinds, so it's for names read from paks; names from
+/// requests go through `lookup`, which doesn't.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AssetType {
+    Texture,
+    StaticMesh,
+    SkeletalMesh,
+    Material,
+    MaterialInstance,
+    Blueprint,
+    Sound,
+    Animation,
+    Map,
+    DataTable,
+    Font,
+    Particle,
+    /// Any other kind, as a snake_case id ("curve_table", "localization", "config"),
+    /// interned when it came from a pak
+    Other(Arc<str>),
+}
+
+impl AssetType {
+    /// An `Other` type with `name` in snake_case
+    pub fn other(name: &str) -> Self {
+        AssetType::Other(intern(&snake_case(name)))
+    }
+
+    /// The type a filter names, without interning it: a kind no scanned asset has is held
+    /// only as long as the filter, so requests can't grow the interned set
+    pub fn lookup(name: &str) -> Self {
+        known(name).unwrap_or_else(|| {
+            let name = snake_case(name);
+            AssetType::Other(intern::lookup(&name).unwrap_or_else(|| Arc::from(name)))
+        })
+    }
+
+    /// The id the type serializes as
+    pub fn as_str(&self) -> &str {
+        match self {
+            AssetType::Texture => "texture",
+            AssetType::StaticMesh => "static_mesh",
+            AssetType::SkeletalMesh => "skeletal_mesh",
+            AssetType::Material => "material",
+            AssetType::MaterialInstance => "material_instance",
+            AssetType::Blueprint => "blueprint",
+            AssetType::Sound => "sound",
+            AssetType::Animation => "animation",
+            AssetType::Map => "map",
+            AssetType::DataTable => "data_table",
+            AssetType::Font => "font",
+            AssetType::Particle => "particle",
+            AssetType::Other(name) => name,
+        }
+    }
+
+    /// Whether this is `Other` with one of these ids
+    pub fn is_other(&self, names: &[&str]) -> bool {
+        matches!(self, AssetType::Other(name) if names.contains(&&**name))
+    }
+}
+
+impl From<&str> for AssetType {
+    fn from(s: &str) -> Self {
+        known(s).unwrap_or_else(|| AssetType::other(s))
+    }
+}
+
+/// The variant `s` names, if it isn't `Other`
+fn known(s: &str) -> Option<AssetType> {
+    // Case, spaces and separators don't matter: "Static Mesh", "static_mesh", "StaticMesh"
+    let key: String = s
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let known = match key.as_str() {
+        "texture" | "image" | "texture2d" | "texturecube" | "texture2darray" | "texturecubearray"
+        | "volumetexture" | "texturerendertarget2d" => AssetType::Texture,
+        "mesh" | "staticmesh" => AssetType::StaticMesh,
+        "skeletalmesh" => AssetType::SkeletalMesh,
+        "material" => AssetType::Material,
+        "materialinstance" | "materialinstanceconstant" | "materialinstancedynamic" => AssetType::MaterialInstance,
+        "blueprint" | "blueprintgeneratedclass" | "widgetblueprint" | "widgetblueprintgeneratedclass"
+        | "animblueprint" | "animblueprintgeneratedclass" => AssetType::Blueprint,
+        "sound" | "audio" | "soundwave" | "soundcue" => AssetType::Sound,
+        "animation" | "animsequence" | "animmontage" => AssetType::Animation,
+        "map" | "level" | "world" => AssetType::Map,
+        "datatable" | "compositedatatable" => AssetType::DataTable,
+        "font" | "fontface" => AssetType::Font,
+        "particle" | "particlesystem" | "niagarasystem" => AssetType::Particle,
+        _ => return None,
+    };
+    Some(known)
+}
+
+impl FromStr for AssetType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(AssetType::lookup(s))
+    }
+}
+
+impl fmt::Display for AssetType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for AssetType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AssetType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(AssetType::from(name.as_str()))
+    }
+}
+
+/// A string in the OpenAPI spec, its known ids listed in the description
+impl utoipa::PartialSchema for AssetType {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        let known = [
+            AssetType::Texture,
+            AssetType::StaticMesh,
+            AssetType::SkeletalMesh,
+            AssetType::Material,
+            AssetType::MaterialInstance,
+            AssetType::Blueprint,
+            AssetType::Sound,
+            AssetType::Animation,
+            AssetType::Map,
+            AssetType::DataTable,
+            AssetType::Font,
+            AssetType::Particle,
+        ];
+        let ids: Vec<&str> = known.iter().map(AssetType::as_str).collect();
+        let description = format!(
+            "Asset kind as a snake_case id: one of {}, or another snake_case id for other kinds \
+             (\"curve_table\", \"localization\", \"config\", ...). Filters also accept UE class \
+             names and older ids (\"Texture2D\", \"mesh\", \"Static Mesh\").",
+            ids.join(", ")
+        );
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .description(Some(description))
+            .examples(["static_mesh"])
+            .into()
+    }
+}
+
+impl utoipa::ToSchema for AssetType {}
+
+/// `name` in lowercase with words joined by underscores: "CurveTable" and "Curve Table"
+/// both become "curve_table"
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    let mut previous: Option<char> = None;
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            let boundary = previous.is_some_and(|p| c.is_uppercase() && p.is_lowercase());
+            if boundary || (previous.is_none() && !snake.is_empty()) {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+            previous = Some(c);
+        } else {
+            previous = None;
+        }
+    }
+    snake
+}
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use anyhow::{Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, NameRef, ObjectExport, Package};
+
+/// A component declared by a Blueprint's construction script
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlueprintComponent {
+    pub name: String,
+    pub class: Option<String>,
+    /// Component (or native variable) this one is attached to
+    pub attach_parent: Option<String>,
+}
+
+/// An object referenced through the import table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectReference {
+    pub path: String,
+    pub class: String,
+}
+
+/// What a Blueprint derives from, declares and touches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlueprintInfo {
+    pub generated_class: String,
+    /// BlueprintGeneratedClass, WidgetBlueprintGeneratedClass, AnimBlueprintGeneratedClass...
+    pub class_type: String,
+    pub parent_class: Option<String>,
+    pub components: Vec<BlueprintComponent>,
+    pub interfaces: Vec<String>,
+    pub functions: Vec<String>,
+    pub references: Vec<ObjectReference>,
+}
+
+/// Parses the generated class of a cooked Blueprint package
+///
+/// This reads the export/import tables and construction script nodes only;
+/// graph bytecode is not decompiled.
+pub fn parse_blueprint(data: &[u8]) -> Result<BlueprintInfo> {
+    let package = Package::parse(data)?;
+    let (class_index, class_export) = package
+        .exports
+        .iter()
+        .enumerate()
+        .find(|(_, e)| package.export_class_name(e).ends_with("BlueprintGeneratedClass"))
+        .map(|(i, e)| (i as i32 + 1, e))
+        .context("Package has no BlueprintGeneratedClass export")?;
+
+    let parent_class = package.object_path(class_export.super_index);
+
+    let functions = package
+        .exports
+        .iter()
+        .filter(|e| e.outer_index == class_index && package.export_class_name(e) == "Function")
+        .map(|e| e.object_name.clone())
+        .collect();
+
+    let interfaces = package
+        .export_data(data, class_export)
+        .map(|bytes| find_implemented_interfaces(&package, bytes))
+        .unwrap_or_default();
+
+    let mut references: Vec<ObjectReference> = (0..package.imports.len())
+        .filter_map(|i| {
+            let index = -(i as i32) - 1;
+            let import = package.import(index)?;
+            // Package entries are just outers; the objects inside them are what's referenced
+            if import.class_name == "Package" {
+                return None;
+            }
+            Some(ObjectReference {
+                path: package.import_object_path(index)?,
+                class: import.class_name.clone(),
+            })
+        })
+        .collect();
+    references.sort_by(|a, b| a.path.cmp(&b.path));
+    references.dedup_by(|a, b| a.path == b.path);
+
+    Ok(BlueprintInfo {
+        generated_class: class_export.object_name.clone(),
+        class_type: package.export_class_name(class_export),
+        parent_class,
+        components: read_components(&package, data, class_index),
+        interfaces,
+        functions,
+        references,
+    })
+}
+
+/// Components from SCS_Node exports, falling back to component templates
+///
+/// SCS nodes are only readable with tagged properties or .usmap mappings; unversioned
+/// packages still expose the `*_GEN_VARIABLE` templates owned by the generated class.
+fn read_components(package: &Package, data: &[u8], class_index: i32) -> Vec<BlueprintComponent> {
+    let from_nodes: Vec<BlueprintComponent> = package
+        .exports
+        .iter()
+        .filter(|e| package.export_class_name(e) == "SCS_Node")
+        .filter_map(|node| read_scs_node(package, data, node).ok())
+        .collect();
+    if !from_nodes.is_empty() {
+        return from_nodes;
+    }
+
+    package
+        .exports
+        .iter()
+        .filter(|e| e.outer_index == class_index)
+        .filter_map(|e| {
+            let name = e.object_name.strip_suffix("_GEN_VARIABLE")?;
+            Some(BlueprintComponent {
+                name: name.to_string(),
+                class: Some(package.export_class_name(e)),
+                attach_parent: None,
+            })
+        })
+        .collect()
+}
+
+fn read_scs_node(package: &Package, data: &[u8], node: &ObjectExport) -> Result<BlueprintComponent> {
+    let mut reader = ArchiveReader::new(package.export_data(data, node)?);
+    let properties = uasset_parser::read_properties(package, &mut reader, &package.export_class_name(node))?;
+    let string = |key: &str| properties.get(key).and_then(Value::as_str).map(str::to_string);
+
+    Ok(BlueprintComponent {
+        name: string("InternalVariableName").unwrap_or_else(|| node.object_name.clone()),
+        class: string("ComponentClass"),
+        attach_parent: string("ParentComponentOrVariableName").filter(|p| p != "None"),
+    })
+}
+
+/// Locates the UClass Interfaces array in the generated class export
+///
+/// The array follows ClassWithin and ClassConfigName, which sit after the
+/// variable-length property/function/bytecode section. ClassWithin is almost
+/// always /Script/CoreUObject.Object, so that import anchors a validated scan.
+fn find_implemented_interfaces(package: &Package, data: &[u8]) -> Vec<String> {
+    let Some(object_class) = (0..package.imports.len())
+        .map(|i| -(i as i32) - 1)
+        .find(|&i| package.import_object_path(i).as_deref() == Some("/Script/CoreUObject.Object"))
+    else {
+        return Vec::new();
+    };
+    let anchor = object_class.to_le_bytes();
+
+    for start in (0..data.len().saturating_sub(16)).rev() {
+        if data[start..start + 4] != anchor {
+            continue;
+        }
+        if let Some(interfaces) = read_interfaces_at(package, &data[start + 4..]) {
+            return interfaces;
+        }
+    }
+    Vec::new()
+}
+
+fn read_interfaces_at(package: &Package, data: &[u8]) -> Option<Vec<String>> {
+    let mut reader = ArchiveReader::new(data);
+
+    // ClassConfigName
+    let index = reader.read_i32().ok()?;
+    let number = reader.read_i32().ok()?;
+    if !(0..package.names.len() as i32).contains(&index) || !(0..1024).contains(&number) {
+        return None;
+    }
+    package.resolve_name(NameRef { index, number }).ok()?;
+
+    // FImplementedInterface: class, pointer offset, bImplementedByK2
+    let count = reader.read_i32().ok()?;
+    if !(0..=64).contains(&count) {
+        return None;
+    }
+    let mut interfaces = Vec::new();
+    for _ in 0..count {
+        let class = reader.read_i32().ok()?;
+        let _pointer_offset = reader.read_i32().ok()?;
+        let implemented_by_k2 = reader.read_u32().ok()?;
+        if implemented_by_k2 > 1 {
+            return None;
+        }
+        interfaces.push(package.object_path(class).filter(|_| class != 0)?);
+    }
+    Some(interfaces)
+}
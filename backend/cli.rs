@@ -0,0 +1,477 @@
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Config;
+use crate::statistics::human_size;
+use crate::{logging, pak_parser, ApiError, Asset, AppState, ErrorCode, PakError, PakParser, PakSeekError};
+
+/// The command ran and everything in it succeeded
+const EXIT_OK: i32 = 0;
+/// The command failed, or finished with failures: paks that couldn't be read, entries
+/// that couldn't be extracted, a pak that isn't valid
+const EXIT_FAILED: i32 = 1;
+/// The command line doesn't parse
+const EXIT_USAGE: i32 = 2;
+/// Ctrl+C stopped the command
+const EXIT_CANCELLED: i32 = 130;
+
+const USAGE: &str = "\
+Usage: pakseek --headless <command> [options]
+
+Commands:
+  scan <folder> [--deep]                      Assets of a folder of paks (or one .pak)
+  list <pak> [--filter <pattern>]             Entries of a pak
+  extract <pak> <dest> [--pattern <pattern>] [--overwrite]
+                                              Writes entries under <dest>, keeping their folders
+  validate <pak>                              Header details and integrity of a pak
+  deps <folder> [--format dot|json|csv]       Dependency graph of a folder of paks (default json)
+  diff <a> <b>                                Files and dependencies that differ between two builds
+
+Options:
+  --json      Print JSON to stdout (the default)
+  --pretty    Print a table instead
+  -h, --help  Show this
+
+Patterns match entry paths case-insensitively; `*` matches any run of characters and
+`?` any one. A pattern without either matches paths that contain it. Progress goes to
+stderr. Exit codes: 0 success, 1 failure, 2 bad command line, 130 cancelled.";
+
+/// A command of `--headless` with its arguments
+#[derive(Debug)]
+enum Command {
+    Help,
+    Scan { folder: String, deep: bool },
+    List { pak: String, filter: Option<String> },
+    Extract { pak: String, destination: PathBuf, pattern: Option<String>, overwrite: bool },
+    Validate { pak: String },
+    Deps { folder: String, format: String },
+    Diff { a: String, b: String },
+}
+
+/// Whether `--headless` is on the command line, so `main` runs a command instead of the window
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--headless")
+}
+
+/// Cuts logging down to warnings unless `RUST_LOG` says otherwise, leaving stderr to progress
+pub fn quiet_logging() {
+    if std::env::var_os("RUST_LOG").is_none() {
+        let _ = logging::set_filter("warn");
+    }
+}
+
+/// Runs the command given after `--headless` and returns the process exit code
+///
+/// `--config` and `--no-index-cache` may appear anywhere; `Config::load` has read them.
+pub fn run(config: Config) -> i32 {
+    let (command, pretty) = match parse(&headless_args()) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    if matches!(command, Command::Help) {
+        println!("{}", USAGE);
+        return EXIT_OK;
+    }
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("error: Failed to start the runtime: {}", e);
+            return EXIT_FAILED;
+        }
+    };
+    runtime.block_on(async {
+        let cancel = CancellationToken::new();
+        let on_ctrl_c = cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                on_ctrl_c.cancel();
+            }
+        });
+
+        let state = AppState::new(Config { mock_data: false, ..config });
+        let result = match command {
+            Command::Help => unreachable!(),
+            Command::Scan { folder, deep } => scan(&folder, deep, &cancel, pretty).await,
+            Command::List { pak, filter } => list(&pak, filter.as_deref(), &cancel, pretty).await,
+            Command::Extract { pak, destination, pattern, overwrite } => {
+                extract(&state, &pak, &destination, pattern.as_deref(), overwrite, &cancel, pretty).await
+            }
+            Command::Validate { pak } => validate(&pak, &cancel, pretty).await,
+            Command::Deps { folder, format } => deps(&folder, &format).await,
+            Command::Diff { a, b } => diff(&a, &b, pretty).await,
+        };
+        match result {
+            Ok(true) => EXIT_OK,
+            Ok(false) => EXIT_FAILED,
+            Err(PakSeekError::Cancelled) => {
+                eprintln!("Cancelled");
+                EXIT_CANCELLED
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                EXIT_FAILED
+            }
+        }
+    })
+}
+
+/// The arguments after `--headless`, without the ones `Config::load` reads
+fn headless_args() -> Vec<String> {
+    let mut args = std::env::args().skip(1).skip_while(|arg| arg != "--headless").skip(1);
+    let mut kept = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => {
+                args.next();
+            }
+            "--no-index-cache" => {}
+            _ if arg.starts_with("--config=") => {}
+            _ => kept.push(arg),
+        }
+    }
+    kept
+}
+
+/// The command and whether `--pretty` was given
+fn parse(args: &[String]) -> Result<(Command, bool), String> {
+    let mut positional = Vec::new();
+    let mut options: Vec<(&str, Option<String>)> = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--").or_else(|| (arg == "-h").then_some("help")) else {
+            positional.push(arg.clone());
+            continue;
+        };
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (flag, None),
+        };
+        let value = match name {
+            "filter" | "pattern" | "format" => match value {
+                Some(value) => Some(value),
+                None => Some(args.next().ok_or_else(|| format!("--{} needs a value", name))?.clone()),
+            },
+            "json" | "pretty" | "deep" | "overwrite" | "help" if value.is_none() => None,
+            "json" | "pretty" | "deep" | "overwrite" | "help" => return Err(format!("--{} takes no value", name)),
+            _ => return Err(format!("Unknown option --{}", name)),
+        };
+        options.push((name, value));
+    }
+
+    if options.iter().any(|(name, _)| *name == "help") {
+        return Ok((Command::Help, false));
+    }
+    let pretty = options.iter().any(|(name, _)| *name == "pretty");
+    let Some((name, positional)) = positional.split_first() else {
+        return Err("No command given".to_string());
+    };
+    let allowed: &[&str] = match name.as_str() {
+        "scan" => &["deep"],
+        "list" => &["filter"],
+        "extract" => &["pattern", "overwrite"],
+        "deps" => &["format"],
+        "validate" | "diff" => &[],
+        other => return Err(format!("Unknown command {:?}", other)),
+    };
+    if let Some((option, _)) = options.iter().find(|(option, _)| !["json", "pretty"].contains(option) && !allowed.contains(option)) {
+        return Err(format!("{} doesn't take --{}", name, option));
+    }
+    let value = |option: &str| options.iter().rev().find(|(name, _)| *name == option).and_then(|(_, value)| value.clone());
+    let flag = |option: &str| options.iter().any(|(name, _)| *name == option);
+
+    let expected = match name.as_str() {
+        "extract" | "diff" => 2,
+        _ => 1,
+    };
+    if positional.len() != expected {
+        return Err(format!("{} takes {} path{}, got {}", name, expected, if expected == 1 { "" } else { "s" }, positional.len()));
+    }
+    let first = positional[0].clone();
+    let command = match name.as_str() {
+        "scan" => Command::Scan { folder: first, deep: flag("deep") },
+        "list" => Command::List { pak: first, filter: value("filter") },
+        "extract" => Command::Extract {
+            pak: first,
+            destination: PathBuf::from(&positional[1]),
+            pattern: value("pattern"),
+            overwrite: flag("overwrite"),
+        },
+        "validate" => Command::Validate { pak: first },
+        "deps" => Command::Deps { folder: first, format: value("format").unwrap_or_else(|| "json".to_string()) },
+        _ => Command::Diff { a: first, b: positional[1].clone() },
+    };
+    Ok((command, pretty))
+}
+
+/// What `scan` prints
+#[derive(Serialize)]
+struct ScanReport {
+    paks: usize,
+    /// Paks that couldn't be read
+    errors: Vec<ApiError>,
+    assets: Vec<Asset>,
+}
+
+async fn scan(folder: &str, deep: bool, cancel: &CancellationToken, pretty: bool) -> Result<bool, PakSeekError> {
+    let context = serde_json::json!({ "path": folder });
+    let pak_files = crate::find_scan_targets(folder)
+        .await
+        .map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e).with_context(context.clone()))?;
+    if pak_files.is_empty() {
+        let message = format!("No .pak files found in {}", folder);
+        return Err(ApiError::new(ErrorCode::NoPaksFound, message).with_context(context).into());
+    }
+
+    let total = pak_files.len();
+    let mut done = 0;
+    let mut errors = Vec::new();
+    let scanned = crate::load_scan(&pak_files, deep, cancel, |pak_file, result| {
+        done += 1;
+        match result {
+            Ok(assets) => eprintln!("[{}/{}] {}: {} assets", done, total, pak_file, assets),
+            Err(e) => {
+                eprintln!("[{}/{}] {}: {:#}", done, total, pak_file, e);
+                errors.push(PakSeekError::from(PakError::open(pak_file, e)).to_api_error());
+            }
+        }
+    })
+    .await;
+    let Some((assets, _)) = scanned else {
+        return Err(PakSeekError::Cancelled);
+    };
+
+    let report = ScanReport { paks: total, errors, assets };
+    emit(&report, pretty, || {
+        let rows = report.assets.iter().map(|asset| {
+            vec![
+                asset.path.clone(),
+                asset.asset_type.to_string(),
+                human_size(asset.size),
+                asset.pak_file.as_deref().map(file_name).unwrap_or_default().to_string(),
+            ]
+        });
+        table(&["Path", "Type", "Size", "Pak"], rows.collect())
+    });
+    Ok(report.errors.is_empty())
+}
+
+async fn list(pak: &str, filter: Option<&str>, cancel: &CancellationToken, pretty: bool) -> Result<bool, PakSeekError> {
+    let parsed = PakParser::new(pak).parse(cancel).await.map_err(|e| PakSeekError::pak_open(pak, &e))?;
+    let entries: Vec<&pak_parser::PakEntry> = parsed
+        .entries
+        .iter()
+        .filter(|entry| filter.is_none_or(|filter| matches_pattern(filter, &entry.filename)))
+        .collect();
+    emit(&entries, pretty, || {
+        let rows = entries.iter().map(|entry| {
+            vec![
+                entry.filename.clone(),
+                human_size(entry.uncompressed_size),
+                human_size(entry.compressed_size),
+                format!("{:?}", entry.compression_method),
+                if entry.is_encrypted { "yes" } else { "" }.to_string(),
+            ]
+        });
+        table(&["Path", "Size", "Stored", "Compression", "Encrypted"], rows.collect())
+    });
+    Ok(true)
+}
+
+async fn extract(
+    state: &AppState,
+    pak: &str,
+    destination: &std::path::Path,
+    pattern: Option<&str>,
+    overwrite: bool,
+    cancel: &CancellationToken,
+    pretty: bool,
+) -> Result<bool, PakSeekError> {
+    let assets = crate::parse_pak_assets(pak, false, cancel).await.map_err(|e| PakSeekError::pak_open(pak, &e))?;
+    let selection: Vec<String> = assets
+        .iter()
+        .flat_map(Asset::part_paths)
+        .filter(|path| pattern.is_none_or(|pattern| matches_pattern(pattern, path)))
+        .collect();
+    *state.assets.lock().await = Arc::new(crate::AssetIndex::new(assets));
+
+    let summary = crate::extract_selection(state, &selection, destination, true, overwrite, cancel, |event| match &event.error {
+        Some(error) => eprintln!("[{}/{}] {}: {}", event.done, event.total, event.path, error.message),
+        None => eprintln!("[{}/{}] {}", event.done, event.total, event.path),
+    })
+    .await;
+    if summary.cancelled {
+        return Err(PakSeekError::Cancelled);
+    }
+
+    emit(&summary, pretty, || {
+        let mut lines = format!(
+            "Extracted {} files ({}) to {}\n",
+            summary.extracted,
+            human_size(summary.total_bytes),
+            destination.display()
+        );
+        if summary.skipped_encrypted > 0 {
+            lines.push_str(&format!("Skipped {} encrypted entries\n", summary.skipped_encrypted));
+        }
+        for failed in &summary.failed {
+            lines.push_str(&format!("Failed {}: {}\n", failed.path, failed.reason.message));
+        }
+        lines
+    });
+    Ok(summary.failed.is_empty())
+}
+
+/// What `validate` prints
+#[derive(Serialize)]
+struct ValidationReport {
+    valid: bool,
+    info: pak_parser::PakInfo,
+    /// None when the index couldn't be read (encrypted without a key)
+    entries: Option<usize>,
+}
+
+async fn validate(pak: &str, cancel: &CancellationToken, pretty: bool) -> Result<bool, PakSeekError> {
+    let parser = PakParser::new(pak);
+    let mut info = parser.info().await.map_err(|e| PakError::open(pak, &e))?;
+    let valid = parser.validate().await.map_err(|e| PakError::open(pak, &e))?;
+    let entries = match parser.parse(cancel).await {
+        Ok(parsed) => {
+            info.total_uncompressed_bytes = Some(parsed.entries.iter().map(|entry| entry.uncompressed_size).sum());
+            Some(parsed.entries.len())
+        }
+        Err(e) if crate::error::is_cancelled(&e) => return Err(PakSeekError::Cancelled),
+        Err(e) => {
+            eprintln!("{}: index not read: {:#}", pak, e);
+            None
+        }
+    };
+
+    let report = ValidationReport { valid, info, entries };
+    emit(&report, pretty, || {
+        let info = &report.info;
+        let rows = vec![
+            vec!["Valid".to_string(), report.valid.to_string()],
+            vec!["Version".to_string(), info.version.to_string()],
+            vec!["Mount point".to_string(), info.mount_point.clone().unwrap_or_else(|| "(encrypted)".to_string())],
+            vec!["Entries".to_string(), report.entries.map_or_else(|| "-".to_string(), |entries| entries.to_string())],
+            vec!["File size".to_string(), human_size(info.file_size)],
+            vec!["Encrypted index".to_string(), info.encrypted_index.to_string()],
+            vec!["Key GUID".to_string(), info.encryption_key_guid.clone().unwrap_or_default()],
+            vec!["Compression".to_string(), info.compression_methods.join(", ")],
+        ];
+        table(&["", ""], rows)
+    });
+    Ok(report.valid)
+}
+
+async fn deps(folder: &str, format: &str) -> Result<bool, PakSeekError> {
+    eprintln!("Reading the dependencies of {}", folder);
+    let (_, dependencies) = crate::scan_build(folder).await?;
+    let contents = dependencies
+        .export_with_options(format, &Default::default())
+        .map_err(|source| crate::DependencyError::Export { format: format.to_string(), source })?;
+    write_stdout(&contents);
+    Ok(true)
+}
+
+async fn diff(a: &str, b: &str, pretty: bool) -> Result<bool, PakSeekError> {
+    let comparison = crate::compare_builds(a, b).await?;
+    emit(&comparison, pretty, || {
+        let files = &comparison.pak_diff;
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        rows.extend(files.added_files.iter().map(|path| vec!["added".to_string(), path.clone()]));
+        rows.extend(files.removed_files.iter().map(|path| vec!["removed".to_string(), path.clone()]));
+        rows.extend(files.changed_files.iter().map(|path| vec!["changed".to_string(), path.clone()]));
+        let totals = &comparison.dependency_diff.totals;
+        format!(
+            "{}\nDependencies: {} edges added, {} removed\n",
+            table(&["Change", "Path"], rows).trim_end(),
+            totals.added_edges,
+            totals.removed_edges
+        )
+    });
+    Ok(true)
+}
+
+/// Prints `value` as JSON, or what `pretty` makes of it with `--pretty`
+fn emit<T: Serialize>(value: &T, pretty: bool, human: impl FnOnce() -> String) {
+    if pretty {
+        write_stdout(&human());
+        return;
+    }
+    match serde_json::to_string(value) {
+        Ok(json) => write_stdout(&(json + "\n")),
+        Err(e) => eprintln!("error: Failed to write JSON: {}", e),
+    }
+}
+
+/// Writes to stdout, ignoring a closed pipe (`| head`)
+fn write_stdout(text: &str) {
+    let mut stdout = std::io::stdout().lock();
+    let _ = stdout.write_all(text.as_bytes()).and_then(|_| stdout.flush());
+}
+
+/// Rows under `headers`, each column padded to its widest cell; no header line when the
+/// headers are all empty
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &mut dyn Iterator<Item = &str>| {
+        let padded: Vec<String> = cells.zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+        padded.join("  ").trim_end().to_string() + "\n"
+    };
+    let mut out = String::new();
+    if headers.iter().any(|header| !header.is_empty()) {
+        out.push_str(&line(&mut headers.iter().copied()));
+    }
+    for row in &rows {
+        out.push_str(&line(&mut row.iter().map(String::as_str)));
+    }
+    out
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// Whether `path` matches `pattern`, ignoring case: `*` is any run of characters, `?` any
+/// one; without either, whether `path` contains `pattern`
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let pattern = crate::with_forward_slashes(&pattern.to_lowercase()).into_owned();
+    let path = crate::with_forward_slashes(&path.to_lowercase()).into_owned();
+    if !pattern.contains(['*', '?']) {
+        return path.contains(&pattern);
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+
+    // Backtracks to the last `*` on a mismatch
+    let (mut p, mut s) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while s < path.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == path[s]) {
+            p += 1;
+            s += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, s));
+            p += 1;
+        } else if let Some((star_p, star_s)) = star {
+            p = star_p + 1;
+            s = star_s + 1;
+            star = Some((star_p, star_s + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
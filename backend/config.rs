@@ -0,0 +1,317 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+use crate::resource_limit;
+use crate::server_config::{ServerConfig, ServerSection};
+use crate::settings::{self, Settings, SettingsStore};
+
+/// Entries `extract_assets` extracts at a time unless configured otherwise
+pub const DEFAULT_EXTRACT_CONCURRENCY: usize = 4;
+
+/// What a new `pakseek.toml` holds: every option, commented out at its default
+const DEFAULT_FILE: &str = r#"# PakSeek configuration
+#
+# Uncomment a line to change it. PAKSEEK_* environment variables override what's set
+# here, and `--config <path>` (or PAKSEEK_CONFIG) reads another file instead.
+
+[server]
+# Address of the HTTP API; the next free port is used when it's taken (PAKSEEK_ADDR)
+# addr = "127.0.0.1:3001"
+# Browser origins allowed to call the API; "*" allows any (PAKSEEK_ORIGINS, comma-separated)
+# origins = ["tauri://localhost", "http://localhost:1420"]
+# Bearer token every route but /health needs; made up at startup when unset (PAKSEEK_API_TOKEN)
+# token = ""
+
+[server.limits]
+# Requests of a kind running at once, and how many more may wait for a turn
+# preview = { concurrency = 8, queue = 32 }
+# extract = { concurrency = 4, queue = 16 }
+# scan = { concurrency = 1, queue = 0 }
+
+[app]
+# List sample assets until something is scanned (PAKSEEK_MOCK_DATA)
+# mock_data = true
+# Entries extract_assets extracts at a time (PAKSEEK_EXTRACT_CONCURRENCY)
+# extract_concurrency = 4
+# Keep parsed pak indexes and scan results between launches (PAKSEEK_INDEX_CACHE,
+# or --no-index-cache to turn it off for one run)
+# index_cache = true
+# Decompressions running at once across scans, extractions and previews; one per core
+# when unset (PAKSEEK_DECOMPRESSION_SLOTS)
+# decompression_slots = 8
+# Megabytes of decompressed data held at once; work waits for room beyond it
+# (PAKSEEK_BUFFER_BUDGET_MB)
+# buffer_budget_mb = 1024
+# Mirror the listing into an SQLite database in the cache dir, so searches and filters
+# over very large installs don't scan every asset (PAKSEEK_SQL_INDEX)
+# sql_index = false
+
+# Written by the app whenever settings change in the UI
+[settings]
+"#;
+
+/// Configuration read at startup, in `AppState` for the rest of the code
+///
+/// UI-editable settings live in the `[settings]` table of the same file but change while
+/// the app runs, so they're served by `settings::global` instead.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The file it was read from
+    pub path: PathBuf,
+    pub server: ServerConfig,
+    /// Sample assets are listed until the first scan
+    pub mock_data: bool,
+    /// Entries `extract_assets` extracts at a time
+    pub extract_concurrency: usize,
+    /// Parsed pak indexes and scan results are cached on disk (`scan_cache`)
+    pub index_cache: bool,
+    /// Decompressions running at once (`resource_limit`)
+    pub decompression_slots: usize,
+    /// Bytes of decompressed buffers in flight at once
+    pub buffer_budget: u64,
+    /// The listing is mirrored into SQLite for searching (`sql_index`)
+    pub sql_index: bool,
+}
+
+/// Every table of `pakseek.toml`
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct ConfigFile {
+    pub server: ServerSection,
+    pub app: AppSection,
+    pub settings: Option<Settings>,
+}
+
+/// `[app]` table
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct AppSection {
+    pub mock_data: Option<bool>,
+    pub extract_concurrency: Option<usize>,
+    pub index_cache: Option<bool>,
+    pub decompression_slots: Option<usize>,
+    pub buffer_budget_mb: Option<u64>,
+    pub sql_index: Option<bool>,
+}
+
+impl Config {
+    /// Reads `pakseek.toml`, writing the commented default first when there's none, and
+    /// applies the environment and command-line overrides
+    ///
+    /// A file that can't be read or parsed is logged with the line at fault and leaves its
+    /// tables at their defaults; startup carries on. The `[settings]` table (or a
+    /// `settings.toml` from before it) becomes what `settings::global` serves.
+    pub fn load() -> Self {
+        let path = config_path().to_path_buf();
+        let mut file = read_file(&path);
+        let source = path.display().to_string();
+
+        let legacy = if settings_table_is_empty(&path) { legacy_settings(&path) } else { None };
+        let migrated = legacy.is_some();
+        let store = SettingsStore::new(path.clone(), legacy.or(file.settings.take()).unwrap_or_default());
+        if migrated {
+            match store.save_current() {
+                Ok(()) => info!("Moved settings.toml into {}", source),
+                Err(e) => warn!("Failed to move settings.toml into {}: {:#}", source, e),
+            }
+        }
+        settings::init(store);
+
+        let mut config = Config {
+            path,
+            server: ServerConfig::load(file.server, &source),
+            mock_data: file.app.mock_data.unwrap_or(true),
+            extract_concurrency: DEFAULT_EXTRACT_CONCURRENCY,
+            index_cache: file.app.index_cache.unwrap_or(true),
+            decompression_slots: resource_limit::default_slots(),
+            buffer_budget: resource_limit::DEFAULT_BUFFER_BUDGET,
+            sql_index: file.app.sql_index.unwrap_or(false),
+        };
+        config.set_extract_concurrency(file.app.extract_concurrency, &source);
+        config.set_decompression_slots(file.app.decompression_slots, &source);
+        config.set_buffer_budget_mb(file.app.buffer_budget_mb, &source);
+
+        if let Ok(value) = std::env::var("PAKSEEK_MOCK_DATA") {
+            match parse_bool(&value) {
+                Some(mock_data) => config.mock_data = mock_data,
+                None => warn!("Ignoring PAKSEEK_MOCK_DATA={:?}: not true or false", value),
+            }
+        }
+        if let Ok(value) = std::env::var("PAKSEEK_INDEX_CACHE") {
+            match parse_bool(&value) {
+                Some(index_cache) => config.index_cache = index_cache,
+                None => warn!("Ignoring PAKSEEK_INDEX_CACHE={:?}: not true or false", value),
+            }
+        }
+        if let Ok(value) = std::env::var("PAKSEEK_SQL_INDEX") {
+            match parse_bool(&value) {
+                Some(sql_index) => config.sql_index = sql_index,
+                None => warn!("Ignoring PAKSEEK_SQL_INDEX={:?}: not true or false", value),
+            }
+        }
+        if has_flag("--no-index-cache") {
+            config.index_cache = false;
+        }
+        if let Ok(value) = std::env::var("PAKSEEK_EXTRACT_CONCURRENCY") {
+            match value.trim().parse() {
+                Ok(concurrency) => config.set_extract_concurrency(Some(concurrency), "the environment"),
+                Err(_) => warn!("Ignoring PAKSEEK_EXTRACT_CONCURRENCY={:?}: not a number", value),
+            }
+        }
+        if let Ok(value) = std::env::var("PAKSEEK_DECOMPRESSION_SLOTS") {
+            match value.trim().parse() {
+                Ok(slots) => config.set_decompression_slots(Some(slots), "the environment"),
+                Err(_) => warn!("Ignoring PAKSEEK_DECOMPRESSION_SLOTS={:?}: not a number", value),
+            }
+        }
+        if let Ok(value) = std::env::var("PAKSEEK_BUFFER_BUDGET_MB") {
+            match value.trim().parse() {
+                Ok(budget) => config.set_buffer_budget_mb(Some(budget), "the environment"),
+                Err(_) => warn!("Ignoring PAKSEEK_BUFFER_BUDGET_MB={:?}: not a number", value),
+            }
+        }
+        config
+    }
+
+    fn set_extract_concurrency(&mut self, concurrency: Option<usize>, source: &str) {
+        match concurrency {
+            Some(0) => warn!("Ignoring extract_concurrency = 0 from {}; it must be at least 1", source),
+            Some(concurrency) => self.extract_concurrency = concurrency,
+            None => {}
+        }
+    }
+
+    fn set_decompression_slots(&mut self, slots: Option<usize>, source: &str) {
+        match slots {
+            Some(0) => warn!("Ignoring decompression_slots = 0 from {}; it must be at least 1", source),
+            Some(slots) => self.decompression_slots = slots,
+            None => {}
+        }
+    }
+
+    fn set_buffer_budget_mb(&mut self, budget: Option<u64>, source: &str) {
+        match budget {
+            Some(0) => warn!("Ignoring buffer_budget_mb = 0 from {}; it must be at least 1", source),
+            Some(budget) => self.buffer_budget = budget.saturating_mul(1024 * 1024),
+            None => {}
+        }
+    }
+}
+
+/// The config file: `--config <path>` on the command line, else `PAKSEEK_CONFIG`, else
+/// `pakseek.toml` in the platform config dir
+pub fn config_path() -> &'static Path {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| {
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--config" {
+                if let Some(path) = args.next() {
+                    return PathBuf::from(path);
+                }
+            } else if let Some(path) = arg.strip_prefix("--config=") {
+                return PathBuf::from(path);
+            }
+        }
+        if let Some(path) = std::env::var_os("PAKSEEK_CONFIG").filter(|path| !path.is_empty()) {
+            return PathBuf::from(path);
+        }
+        dirs::config_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("pakseek")
+            .join("pakseek.toml")
+    })
+}
+
+/// Whether `flag` is on the command line
+fn has_flag(flag: &str) -> bool {
+    std::env::args().skip(1).any(|arg| arg == flag)
+}
+
+/// The tables of `path`, defaults for the ones that are missing or invalid
+///
+/// A missing file is created from `DEFAULT_FILE`. When the file doesn't parse as a whole,
+/// the error (with its line) is logged and each table is read on its own, so one bad
+/// value only costs its table.
+pub fn read_file(path: &Path) -> ConfigFile {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            write_default(path);
+            return ConfigFile::default();
+        }
+        Err(e) => {
+            warn!("Using the default configuration, {} can't be read: {}", path.display(), e);
+            return ConfigFile::default();
+        }
+    };
+
+    let error = match toml::from_str(&contents) {
+        Ok(file) => return file,
+        Err(e) => e,
+    };
+    warn!("Error in {}: {}", path.display(), error.to_string().trim_end());
+    let Ok(mut table) = toml::from_str::<toml::Table>(&contents) else {
+        warn!("Using the default configuration");
+        return ConfigFile::default();
+    };
+    ConfigFile {
+        server: section(&mut table, "server").unwrap_or_default(),
+        app: section(&mut table, "app").unwrap_or_default(),
+        settings: section(&mut table, "settings"),
+    }
+}
+
+/// The `[name]` table of a config file, None when it's absent or invalid (logged)
+fn section<T: DeserializeOwned>(table: &mut toml::Table, name: &str) -> Option<T> {
+    let value = table.remove(name)?;
+    match value.try_into() {
+        Ok(section) => Some(section),
+        Err(e) => {
+            warn!("Using the defaults for [{}]: {}", name, e.to_string().trim_end());
+            None
+        }
+    }
+}
+
+fn write_default(path: &Path) {
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(path, DEFAULT_FILE));
+    match written {
+        Ok(()) => info!("Wrote the default configuration to {}", path.display()),
+        Err(e) => warn!("Failed to write the default configuration to {}: {}", path.display(), e),
+    }
+}
+
+/// Whether the file at `path` has nothing in its `[settings]` table yet, as when it was
+/// just created
+fn settings_table_is_empty(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| contents.parse::<toml_edit::DocumentMut>().ok())
+        .is_none_or(|document| document.get("settings").and_then(toml_edit::Item::as_table).is_none_or(|table| table.is_empty()))
+}
+
+/// Settings from the `settings.toml` next to `path`, where they were kept before they
+/// moved into `pakseek.toml`
+fn legacy_settings(path: &Path) -> Option<Settings> {
+    let legacy = path.with_file_name("settings.toml");
+    let contents = std::fs::read_to_string(&legacy).ok()?;
+    toml::from_str(&contents)
+        .map_err(|e| warn!("Ignoring {}: {}", legacy.display(), e.to_string().trim_end()))
+        .ok()
+}
+
+/// "true"/"false", "1"/"0", "yes"/"no" or "on"/"off", in any case
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
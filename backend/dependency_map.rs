@@ -2,39 +2,391 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use anyhow::Result;
 
+use crate::asset_path::PathSpellings;
+use crate::search::{SearchMode, TextMatcher};
+
 /// Represents the dependency mapping between assets
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct DependencyMap {
     pub dependencies: HashMap<String, Vec<String>>,
+    /// Kind of each edge added with `add_typed_dependency`; edges missing here are hard
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub edge_kinds: HashMap<String, HashMap<String, DependencyKind>>,
+    /// Known facts about assets, carried into the graph exports as node attributes
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub nodes: HashMap<String, AssetNode>,
+    /// Edges each pak contributed through `add_assets_from` (pak -> asset -> dependency -> kind)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sources: HashMap<String, HashMap<String, HashMap<String, DependencyKind>>>,
+}
+
+/// Optional arguments for exports that render part of the graph (mermaid)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    /// Asset to start from; without one, every asset nothing depends on
+    pub root: Option<String>,
+    /// Dependency levels to follow from the root (unlimited by default)
+    pub max_depth: Option<u32>,
+    /// Nodes drawn before the rest collapse into a "…N more" node (default 100)
+    pub max_nodes: Option<usize>,
+}
+
+/// Node budget of a Mermaid export when none is given; larger charts stop being readable
+const DEFAULT_MERMAID_NODES: usize = 100;
+
+/// Node attributes of an asset in the dependency graph, each present when known
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AssetNode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pak_file: Option<String>,
+    /// `Asset::id` of the scanned asset the node resolved to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_id: Option<String>,
+}
+
+/// Whether a reference is loaded with the asset (hard) or resolved on demand (soft)
+///
+/// Soft references (TSoftObjectPtr, soft object paths in tables and config) are the
+/// ones that fail at runtime when the target is missing from the build.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    #[default]
+    Hard,
+    Soft,
+}
+
+impl DependencyKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DependencyKind::Hard => "hard",
+            DependencyKind::Soft => "soft",
+        }
+    }
+}
+
+/// One entry of an asset's dependency list in the YAML export: the bare path of a hard
+/// edge, or `soft: <path>` for a soft one
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum YamlDependency<'a> {
+    Hard(std::borrow::Cow<'a, str>),
+    Soft { soft: std::borrow::Cow<'a, str> },
 }
 
 /// Response structure for dependency data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct DependencyAnalysis {
     pub asset_name: String,
     pub direct_dependencies: Vec<String>,
     pub reverse_dependencies: Vec<String>,
+    /// Assets that depend on this one directly or through other assets
+    #[serde(default)]
+    pub transitive_dependents: Vec<String>,
     pub dependency_tree: DependencyTree,
+    /// What uses the asset, to the same depth as `dependency_tree`
+    #[serde(default)]
+    pub reverse_dependency_tree: DependencyTree,
     pub statistics: DependencyStatistics,
+    /// Present when the analysis was given asset sizes
+    #[serde(default)]
+    pub footprint: Option<FootprintReport>,
+    /// Edges to or from this asset that cross into another pak (see `annotate_containers`)
+    #[serde(default)]
+    pub cross_container_edges: Vec<CrossContainerEdges>,
 }
 
-/// Tree structure representing asset dependencies
+/// Bytes an asset pulls into a build through its dependencies
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FootprintReport {
+    /// Size of the asset itself
+    pub direct_bytes: u64,
+    /// The asset plus every unique transitive dependency, each counted once
+    pub transitive_bytes: u64,
+    pub unique_transitive_assets: usize,
+    /// Biggest dependencies, largest first
+    pub largest_contributors: Vec<(String, u64)>,
+}
+
+/// Which part of the graph to keep for visualization
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubgraphFilter {
+    /// Assets to walk outward from (always kept); every asset when empty
+    pub roots: Vec<String>,
+    /// Dependency levels to follow from the roots (unlimited by default)
+    pub max_depth: Option<u32>,
+    /// Keep only assets of these types (case-insensitive); assets of unknown type are pruned
+    pub include_types: Option<Vec<String>>,
+    /// Keep only assets whose name starts with this folder ("/Game/Characters/")
+    pub path_prefix: Option<String>,
+    /// Keep only assets whose name matches this, as `search_mode` says
+    pub search: Option<String>,
+    pub search_mode: SearchMode,
+    /// Connect kept assets through pruned ones instead of dropping those edges
+    pub keep_pass_through: bool,
+}
+
+impl SubgraphFilter {
+    /// True when the filter keeps the whole graph
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+            && self.max_depth.is_none()
+            && self.include_types.is_none()
+            && self.path_prefix.is_none()
+            && self.search.is_none()
+    }
+}
+
+/// What a subgraph left out
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SubgraphSummary {
+    pub kept_nodes: usize,
+    /// Reached assets that failed the type or folder filter
+    pub pruned_nodes: usize,
+    /// Edges from kept assets to pruned ones
+    pub pruned_edges: usize,
+    /// Edges added to connect kept assets through pruned ones
+    pub bridged_edges: usize,
+}
+
+/// A problem found by `validate` or `validate_references`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum ValidationIssue {
+    SelfReference { asset: String },
+    /// Assets of one strongly connected component, sorted by name
+    CircularDependency { cycle: Vec<String> },
+    EmptyDependencyList { asset: String },
+    /// References of one asset to assets the index doesn't have
+    MissingReferences { asset: String, missing: Vec<MissingReference> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct MissingReference {
+    pub dependency: String,
+    pub kind: DependencyKind,
+    pub reason: MissingReason,
+}
+
+/// Why a referenced asset isn't in the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingReason {
+    /// In no scanned pak
+    NotFound,
+    /// Only in a pak that couldn't be read (encrypted, or not scanned)
+    Unscanned,
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationIssue::SelfReference { asset } => write!(f, "Self-reference detected: {} depends on itself", asset),
+            ValidationIssue::CircularDependency { cycle } => write!(f, "Circular dependency among {}", format_cycle(cycle)),
+            ValidationIssue::EmptyDependencyList { asset } => write!(f, "Asset {} has empty dependency list", asset),
+            ValidationIssue::MissingReferences { asset, missing } => {
+                write!(f, "Asset {} references {} missing assets: ", asset, missing.len())?;
+                for (i, reference) in missing.iter().enumerate() {
+                    let reason = match reference.reason {
+                        MissingReason::NotFound => "not found",
+                        MissingReason::Unscanned => "unscanned pak",
+                    };
+                    let separator = if i == 0 { "" } else { ", " };
+                    write!(f, "{}{} ({})", separator, reference.dependency, reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Assets no root reaches, and how much space removing them would free
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnreachableReport {
+    pub roots: usize,
+    pub reachable: usize,
+    /// Unreachable assets with their sizes, largest first
+    pub unreachable: Vec<(String, u64)>,
+    pub reclaimable_bytes: u64,
+    /// Assets the graph has no data for (couldn't be parsed), so reachability is unknown
+    pub no_dependency_data: Vec<String>,
+}
+
+/// Outcome of merging one dependency map into another
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct MergeSummary {
+    pub edges_added: usize,
+    pub edges_already_present: usize,
+}
+
+/// What else breaks if an asset is removed
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovalImpact {
+    pub asset: String,
+    /// Dependents by distance: direct dependents first, then their dependents, ...
+    pub dependents_by_depth: Vec<Vec<String>>,
+    pub total_dependents: usize,
+    /// Affected assets nothing else depends on (maps and top-level blueprints)
+    pub affected_roots: usize,
+}
+
+/// Assets keyed by what they depend on, for repeated reverse lookups
+#[derive(Debug, Clone, Default)]
+pub struct ReverseIndex {
+    dependents: HashMap<String, Vec<String>>,
+}
+
+impl ReverseIndex {
+    /// Assets that directly depend on `asset`
+    pub fn dependents(&self, asset: &str) -> &[String] {
+        self.dependents.get(asset).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Everything that transitively depends on `asset`, grouped by distance from it
+    pub fn dependents_by_depth(&self, asset: &str) -> Vec<Vec<String>> {
+        let mut visited: HashSet<&str> = HashSet::from([asset]);
+        let mut levels = Vec::new();
+        let mut frontier = vec![asset];
+
+        while !frontier.is_empty() {
+            let mut level: Vec<&str> = frontier
+                .iter()
+                .flat_map(|&a| self.dependents(a))
+                .map(String::as_str)
+                .filter(|&dependent| visited.insert(dependent))
+                .collect();
+            if level.is_empty() {
+                break;
+            }
+            level.sort_unstable();
+            levels.push(level.iter().map(|a| a.to_string()).collect());
+            frontier = level;
+        }
+
+        levels
+    }
+}
+
+/// Tree structure representing asset dependencies
+///
+/// In a reverse tree (`build_reverse_tree`) the children are the assets that use their parent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct DependencyTree {
     pub asset: String,
     pub depth: u32,
+    #[schema(no_recursion)]
     pub dependencies: Vec<DependencyTree>,
     pub is_circular: bool,
+    /// How the parent references this node, or in a reverse tree how this node
+    /// references its parent (the root is hard)
+    #[serde(default)]
+    pub kind: DependencyKind,
+}
+
+/// Which way a dependency tree is walked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TreeDirection {
+    /// What the asset uses
+    #[default]
+    Forward,
+    /// What uses the asset
+    Reverse,
 }
 
 /// Statistics about asset dependencies
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct DependencyStatistics {
     pub total_dependencies: usize,
     pub max_depth: u32,
     pub circular_references: Vec<Vec<String>>,
     pub orphaned_assets: Vec<String>,
     pub most_referenced: Vec<(String, usize)>,
+    #[serde(default)]
+    pub soft_dependencies: usize,
+    /// Assets with the largest transitive footprint, when sizes were given
+    #[serde(default)]
+    pub largest_footprints: Vec<(String, u64)>,
+    /// Degree distribution, hubs and folder coupling (without per-asset degrees)
+    #[serde(default)]
+    pub metrics: GraphMetrics,
+    /// What each pak needs from other paks, when nodes know their pak
+    #[serde(default)]
+    pub containers: Vec<ContainerSummary>,
+}
+
+/// Shape of the dependency graph
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GraphMetrics {
+    /// In/out degree per asset
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub degrees: HashMap<String, NodeDegree>,
+    /// Number of assets per in-degree
+    pub in_degree_distribution: std::collections::BTreeMap<usize, usize>,
+    /// Number of assets per out-degree
+    pub out_degree_distribution: std::collections::BTreeMap<usize, usize>,
+    /// Assets with the highest combined degree, highest first
+    pub hubs: Vec<(String, usize)>,
+    /// Edges per asset in the graph
+    pub average_dependencies: f64,
+    /// Per top-level folder ("/Game/Characters"), largest first
+    pub folders: Vec<FolderMetrics>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NodeDegree {
+    pub in_degree: usize,
+    pub out_degree: usize,
+}
+
+/// How a folder's assets reference each other and the rest of the game
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FolderMetrics {
+    pub folder: String,
+    pub assets: usize,
+    /// Edges between two assets of this folder
+    pub internal_edges: usize,
+    /// Edges from this folder's assets into other folders
+    pub outgoing_edges: usize,
+    /// Edges from other folders into this one
+    pub incoming_edges: usize,
+}
+
+/// Hubs listed in the graph metrics
+const METRICS_HUB_COUNT: usize = 10;
+
+/// Edges from assets of one pak to assets of another
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CrossContainerEdges {
+    pub from_pak: String,
+    pub to_pak: String,
+    pub count: usize,
+    /// (dependent, dependency) pairs, sorted
+    pub edges: Vec<(String, String)>,
+}
+
+/// Which other paks a pak's assets depend on
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ContainerSummary {
+    pub pak: String,
+    /// Assets of this pak in the graph
+    pub assets: usize,
+    /// Most needed pak first
+    pub depends_on: Vec<ContainerDependency>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ContainerDependency {
+    pub pak: String,
+    /// Distinct assets of `pak` needed by this one
+    pub assets: usize,
+    pub edges: usize,
 }
 
 impl DependencyMap {
@@ -42,15 +394,204 @@ impl DependencyMap {
     pub fn new() -> Self {
         Self {
             dependencies: HashMap::new(),
+            edge_kinds: HashMap::new(),
+            nodes: HashMap::new(),
+            sources: HashMap::new(),
         }
     }
 
+    /// Node attributes of an asset, created empty on first use
+    pub fn node_mut(&mut self, asset: &str) -> &mut AssetNode {
+        self.nodes.entry(asset.to_string()).or_default()
+    }
+
+    /// Every asset in the graph, dependents and dependencies alike, sorted by name
+    pub fn all_assets(&self) -> Vec<&str> {
+        let assets: std::collections::BTreeSet<&str> = self
+            .dependencies
+            .iter()
+            .flat_map(|(asset, deps)| std::iter::once(asset.as_str()).chain(deps.iter().map(String::as_str)))
+            .collect();
+        assets.into_iter().collect()
+    }
+
+    /// Whether the graph has `asset` as a dependent or a dependency
+    pub fn contains(&self, asset: &str) -> bool {
+        self.dependencies.contains_key(asset) || self.dependencies.values().any(|deps| deps.iter().any(|d| d == asset))
+    }
+
     /// Adds a dependency relationship
     pub fn add_dependency(&mut self, asset: &str, dependency: &str) {
+        self.add_typed_dependency(asset, dependency, DependencyKind::Hard);
+    }
+
+    /// Adds a dependency relationship of the given kind
+    ///
+    /// An edge that is both hard and soft is hard: it's loaded with the asset either way.
+    pub fn add_typed_dependency(&mut self, asset: &str, dependency: &str, kind: DependencyKind) {
+        let existing = self
+            .dependencies
+            .get(asset)
+            .is_some_and(|deps| deps.iter().any(|d| d == dependency));
+        let known_hard = existing && self.dependency_kind(asset, dependency) == DependencyKind::Hard;
+
         self.dependencies
             .entry(asset.to_string())
             .or_insert_with(Vec::new)
             .push(dependency.to_string());
+
+        match kind {
+            DependencyKind::Soft if !known_hard => {
+                self.edge_kinds
+                    .entry(asset.to_string())
+                    .or_default()
+                    .insert(dependency.to_string(), DependencyKind::Soft);
+            }
+            DependencyKind::Soft => {}
+            DependencyKind::Hard => self.clear_edge_kind(asset, dependency),
+        }
+    }
+
+    /// Forgets a recorded edge kind, making the edge hard
+    fn clear_edge_kind(&mut self, asset: &str, dependency: &str) {
+        if let Some(kinds) = self.edge_kinds.get_mut(asset) {
+            kinds.remove(dependency);
+            if kinds.is_empty() {
+                self.edge_kinds.remove(asset);
+            }
+        }
+    }
+
+    /// Adds the edges and node attributes of another map, counting edges already present
+    ///
+    /// An edge present in both maps is hard if either side has it hard.
+    pub fn merge(&mut self, other: &DependencyMap) -> MergeSummary {
+        let mut summary = MergeSummary::default();
+        for (asset, deps) in &other.dependencies {
+            for dep in deps {
+                let kind = other.dependency_kind(asset, dep);
+                if self.dependencies.get(asset).is_some_and(|existing| existing.contains(dep)) {
+                    summary.edges_already_present += 1;
+                    if kind == DependencyKind::Hard {
+                        self.clear_edge_kind(asset, dep);
+                    }
+                } else {
+                    summary.edges_added += 1;
+                    self.add_typed_dependency(asset, dep, kind);
+                }
+            }
+        }
+        self.nodes.extend(other.nodes.iter().map(|(asset, node)| (asset.clone(), node.clone())));
+        summary
+    }
+
+    /// Adds one pak's edges and nodes, remembering them so `remove_assets_from` can take them out
+    ///
+    /// Whatever the pak contributed before is replaced. Nodes without a pak are attributed
+    /// to `source`. Dependency lists the pak touched are kept sorted, so adding paks in any
+    /// order gives the same map.
+    pub fn add_assets_from(&mut self, source: &str, edges: &DependencyMap) -> MergeSummary {
+        self.remove_assets_from(source);
+
+        let mut contributed: HashMap<String, HashMap<String, DependencyKind>> = HashMap::new();
+        for (asset, deps) in &edges.dependencies {
+            for dep in deps {
+                let kind = edges.dependency_kind(asset, dep);
+                let recorded = contributed.entry(asset.clone()).or_default().entry(dep.clone()).or_insert(kind);
+                if kind == DependencyKind::Hard {
+                    *recorded = DependencyKind::Hard;
+                }
+            }
+        }
+
+        let mut summary = MergeSummary::default();
+        for (asset, deps) in &contributed {
+            for (dep, &kind) in deps {
+                if self.dependencies.get(asset).is_some_and(|existing| existing.contains(dep)) {
+                    summary.edges_already_present += 1;
+                    if kind == DependencyKind::Hard {
+                        self.clear_edge_kind(asset, dep);
+                    }
+                } else {
+                    summary.edges_added += 1;
+                    self.add_typed_dependency(asset, dep, kind);
+                }
+            }
+            if let Some(list) = self.dependencies.get_mut(asset) {
+                list.sort();
+            }
+        }
+
+        for (asset, node) in &edges.nodes {
+            let mut node = node.clone();
+            node.pak_file.get_or_insert_with(|| source.to_string());
+            self.nodes.insert(asset.clone(), node);
+        }
+        self.sources.insert(source.to_string(), contributed);
+        summary
+    }
+
+    /// Takes out the edges and nodes a pak contributed through `add_assets_from`
+    ///
+    /// Edges another pak also contributed stay, hard if any remaining pak has them hard.
+    /// Returns how many edges were removed.
+    pub fn remove_assets_from(&mut self, source: &str) -> usize {
+        let Some(contributed) = self.sources.remove(source) else {
+            return 0;
+        };
+
+        let mut removed = 0;
+        for (asset, deps) in &contributed {
+            for dep in deps.keys() {
+                match self.source_kind(asset, dep) {
+                    None => {
+                        self.remove_dependency(asset, dep);
+                        removed += 1;
+                    }
+                    Some(DependencyKind::Hard) => self.clear_edge_kind(asset, dep),
+                    Some(DependencyKind::Soft) => {
+                        self.edge_kinds
+                            .entry(asset.clone())
+                            .or_default()
+                            .insert(dep.clone(), DependencyKind::Soft);
+                    }
+                }
+            }
+        }
+        self.nodes.retain(|_, node| node.pak_file.as_deref() != Some(source));
+        removed
+    }
+
+    /// Kind of an edge across the paks that contributed it; `None` when none did
+    fn source_kind(&self, asset: &str, dependency: &str) -> Option<DependencyKind> {
+        self.sources
+            .values()
+            .filter_map(|assets| assets.get(asset)?.get(dependency).copied())
+            .reduce(|a, b| if a == DependencyKind::Hard { a } else { b })
+    }
+
+    /// Kind of an edge (hard unless it was added as soft)
+    pub fn dependency_kind(&self, asset: &str, dependency: &str) -> DependencyKind {
+        self.edge_kinds
+            .get(asset)
+            .and_then(|kinds| kinds.get(dependency))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns a map holding only edges of the given kinds, for kind-filtered trees and analysis
+    pub fn filter_by_kind(&self, kinds: &[DependencyKind]) -> DependencyMap {
+        let mut filtered = DependencyMap::new();
+        for (asset, deps) in &self.dependencies {
+            for dep in deps {
+                let kind = self.dependency_kind(asset, dep);
+                if kinds.contains(&kind) {
+                    filtered.add_typed_dependency(asset, dep, kind);
+                }
+            }
+        }
+        filtered.nodes = self.nodes.clone();
+        filtered
     }
 
     /// Removes a dependency relationship
@@ -61,6 +602,7 @@ impl DependencyMap {
                 self.dependencies.remove(asset);
             }
         }
+        self.clear_edge_kind(asset, dependency);
     }
 
     /// Gets direct dependencies for an asset
@@ -85,6 +627,194 @@ impl DependencyMap {
             .collect()
     }
 
+    /// Returns the part of the graph reachable from `roots` that matches the type and folder filters
+    ///
+    /// See `SubgraphFilter` for the meaning of each argument.
+    pub fn subgraph(
+        &self,
+        roots: &[String],
+        max_depth: u32,
+        include_types: Option<&[String]>,
+        path_prefix: Option<&str>,
+        keep_pass_through: bool,
+    ) -> DependencyMap {
+        let filter = SubgraphFilter {
+            roots: roots.to_vec(),
+            max_depth: Some(max_depth),
+            include_types: include_types.map(<[String]>::to_vec),
+            path_prefix: path_prefix.map(str::to_string),
+            keep_pass_through,
+            ..Default::default()
+        };
+        self.subgraph_matching(&filter, None).0
+    }
+
+    /// Like `subgraph`, also counting what was pruned
+    ///
+    /// Fails when the filter's search is an invalid regex.
+    pub fn subgraph_with_summary(&self, filter: &SubgraphFilter) -> Result<(DependencyMap, SubgraphSummary)> {
+        let search = filter
+            .search
+            .as_deref()
+            .map(|search| TextMatcher::new(search, filter.search_mode))
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
+        Ok(self.subgraph_matching(filter, search.as_ref()))
+    }
+
+    fn subgraph_matching(&self, filter: &SubgraphFilter, search: Option<&TextMatcher>) -> (DependencyMap, SubgraphSummary) {
+        let max_depth = filter.max_depth.unwrap_or(u32::MAX);
+        let starts: Vec<&str> = if filter.roots.is_empty() {
+            self.all_assets()
+        } else {
+            filter.roots.iter().map(String::as_str).collect()
+        };
+
+        // Breadth-first, so each asset gets its shortest distance from a root
+        let mut depth_of: HashMap<&str, u32> = HashMap::new();
+        let mut queue = std::collections::VecDeque::new();
+        for start in starts {
+            if depth_of.insert(start, 0).is_none() {
+                queue.push_back(start);
+            }
+        }
+        while let Some(asset) = queue.pop_front() {
+            let depth = depth_of[asset];
+            if depth >= max_depth {
+                continue;
+            }
+            for dep in self.dependencies.get(asset).into_iter().flatten() {
+                if !depth_of.contains_key(dep.as_str()) {
+                    depth_of.insert(dep, depth + 1);
+                    queue.push_back(dep);
+                }
+            }
+        }
+
+        let explicit_roots: HashSet<&str> = filter.roots.iter().map(String::as_str).collect();
+        let kept: HashSet<&str> = depth_of
+            .keys()
+            .copied()
+            .filter(|asset| {
+                explicit_roots.contains(asset)
+                    || (self.matches_filter(asset, filter) && search.is_none_or(|search| search.is_match(asset)))
+            })
+            .collect();
+
+        let mut subgraph = DependencyMap::new();
+        let mut pruned_edges = 0;
+        let mut bridged: HashSet<(&str, &str)> = HashSet::new();
+        for &asset in &kept {
+            for dep in self.dependencies.get(asset).into_iter().flatten() {
+                let kind = self.dependency_kind(asset, dep);
+                if kept.contains(dep.as_str()) {
+                    subgraph.add_typed_dependency(asset, dep, kind);
+                    continue;
+                }
+                if !depth_of.contains_key(dep.as_str()) {
+                    continue; // Beyond the depth limit, not pruned
+                }
+                pruned_edges += 1;
+                if !filter.keep_pass_through {
+                    continue;
+                }
+
+                // Follow pruned assets to the kept ones behind them; soft anywhere on the way makes the edge soft
+                let mut stack = vec![(dep.as_str(), kind)];
+                let mut seen = HashSet::from([dep.as_str()]);
+                while let Some((through, kind)) = stack.pop() {
+                    for next in self.dependencies.get(through).into_iter().flatten() {
+                        let next_kind = match (kind, self.dependency_kind(through, next)) {
+                            (DependencyKind::Hard, DependencyKind::Hard) => DependencyKind::Hard,
+                            _ => DependencyKind::Soft,
+                        };
+                        if kept.contains(next.as_str()) {
+                            if next != asset && bridged.insert((asset, next)) {
+                                subgraph.add_typed_dependency(asset, next, next_kind);
+                            }
+                        } else if depth_of.contains_key(next.as_str()) && seen.insert(next) {
+                            stack.push((next, next_kind));
+                        }
+                    }
+                }
+            }
+        }
+        subgraph.optimize();
+
+        // Kept assets without edges still belong in the view
+        let present: HashSet<String> = subgraph.all_assets().into_iter().map(str::to_string).collect();
+        for &asset in &kept {
+            if !present.contains(asset) {
+                subgraph.dependencies.entry(asset.to_string()).or_default();
+            }
+            if let Some(node) = self.nodes.get(asset) {
+                subgraph.nodes.insert(asset.to_string(), node.clone());
+            }
+        }
+
+        let summary = SubgraphSummary {
+            kept_nodes: kept.len(),
+            pruned_nodes: depth_of.len() - kept.len(),
+            pruned_edges,
+            bridged_edges: bridged.len(),
+        };
+        (subgraph, summary)
+    }
+
+    /// Whether an asset passes the type and folder filters (the search is checked apart,
+    /// compiled once)
+    fn matches_filter(&self, asset: &str, filter: &SubgraphFilter) -> bool {
+        let prefix_matches = filter
+            .path_prefix
+            .as_ref()
+            .is_none_or(|prefix| asset.to_lowercase().starts_with(&prefix.to_lowercase()));
+        let type_matches = filter.include_types.as_ref().is_none_or(|types| {
+            self.nodes
+                .get(asset)
+                .and_then(|node| node.asset_type.as_deref())
+                .is_some_and(|asset_type| types.iter().any(|t| t.eq_ignore_ascii_case(asset_type)))
+        });
+        prefix_matches && type_matches
+    }
+
+    /// Builds the reverse adjacency index used for dependent lookups
+    pub fn reverse_index(&self) -> ReverseIndex {
+        let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+        for (asset, deps) in &self.dependencies {
+            for dep in deps.iter().collect::<HashSet<_>>() {
+                dependents.entry(dep.clone()).or_default().push(asset.clone());
+            }
+        }
+        for list in dependents.values_mut() {
+            list.sort_unstable();
+        }
+        ReverseIndex { dependents }
+    }
+
+    /// Gets every asset that depends on the given asset, directly or transitively
+    pub fn get_all_reverse_dependencies(&self, asset: &str) -> Vec<String> {
+        self.reverse_index().dependents_by_depth(asset).into_iter().flatten().collect()
+    }
+
+    /// Reports what depends on an asset, to check before removing it from a pak
+    pub fn analyze_removal_impact(&self, asset: &str) -> RemovalImpact {
+        let index = self.reverse_index();
+        let dependents_by_depth = index.dependents_by_depth(asset);
+        let total_dependents = dependents_by_depth.iter().map(Vec::len).sum();
+        let affected_roots = dependents_by_depth
+            .iter()
+            .flatten()
+            .filter(|dependent| index.dependents(dependent).is_empty())
+            .count();
+
+        RemovalImpact {
+            asset: asset.to_string(),
+            dependents_by_depth,
+            total_dependents,
+            affected_roots,
+        }
+    }
+
     /// Gets all dependencies recursively (dependency tree)
     pub fn get_all_dependencies(&self, asset: &str) -> Result<Vec<String>> {
         let mut visited = HashSet::new();
@@ -121,13 +851,14 @@ impl DependencyMap {
     /// Builds a dependency tree for visualization
     pub fn build_dependency_tree(&self, asset: &str, max_depth: u32) -> DependencyTree {
         let mut visited = HashSet::new();
-        self.build_tree_recursive(asset, 0, max_depth, &mut visited)
+        self.build_tree_recursive(asset, DependencyKind::Hard, 0, max_depth, &mut visited)
     }
 
     /// Recursive helper for building dependency tree
     fn build_tree_recursive(
         &self,
         asset: &str,
+        kind: DependencyKind,
         depth: u32,
         max_depth: u32,
         visited: &mut HashSet<String>,
@@ -140,6 +871,7 @@ impl DependencyMap {
                 depth,
                 dependencies: Vec::new(),
                 is_circular,
+                kind,
             };
         }
 
@@ -150,7 +882,10 @@ impl DependencyMap {
             .get(asset)
             .map(|deps| {
                 deps.iter()
-                    .map(|dep| self.build_tree_recursive(dep, depth + 1, max_depth, visited))
+                    .map(|dep| {
+                        let kind = self.dependency_kind(asset, dep);
+                        self.build_tree_recursive(dep, kind, depth + 1, max_depth, visited)
+                    })
                     .collect()
             })
             .unwrap_or_default();
@@ -162,59 +897,196 @@ impl DependencyMap {
             depth,
             dependencies,
             is_circular: false,
+            kind,
+        }
+    }
+
+    /// Builds a tree of what uses an asset, the counterpart of `build_dependency_tree`
+    pub fn build_reverse_tree(&self, asset: &str, max_depth: u32) -> DependencyTree {
+        self.build_reverse_tree_with_index(&self.reverse_index(), asset, max_depth)
+    }
+
+    /// `build_reverse_tree` with an index built once for several trees
+    pub fn build_reverse_tree_with_index(&self, index: &ReverseIndex, asset: &str, max_depth: u32) -> DependencyTree {
+        let mut visited = HashSet::new();
+        self.build_reverse_tree_recursive(index, asset, DependencyKind::Hard, 0, max_depth, &mut visited)
+    }
+
+    /// Recursive helper for building reverse dependency tree
+    fn build_reverse_tree_recursive(
+        &self,
+        index: &ReverseIndex,
+        asset: &str,
+        kind: DependencyKind,
+        depth: u32,
+        max_depth: u32,
+        visited: &mut HashSet<String>,
+    ) -> DependencyTree {
+        let is_circular = visited.contains(asset);
+
+        if is_circular || depth >= max_depth {
+            return DependencyTree {
+                asset: asset.to_string(),
+                depth,
+                dependencies: Vec::new(),
+                is_circular,
+                kind,
+            };
+        }
+
+        visited.insert(asset.to_string());
+
+        let dependencies = index
+            .dependents(asset)
+            .iter()
+            .map(|dependent| {
+                let kind = self.dependency_kind(dependent, asset);
+                self.build_reverse_tree_recursive(index, dependent, kind, depth + 1, max_depth, visited)
+            })
+            .collect();
+
+        visited.remove(asset);
+
+        DependencyTree {
+            asset: asset.to_string(),
+            depth,
+            dependencies,
+            is_circular: false,
+            kind,
+        }
+    }
+
+    /// Dependency tree of an asset in either direction
+    pub fn build_tree(&self, asset: &str, max_depth: u32, direction: TreeDirection) -> DependencyTree {
+        match direction {
+            TreeDirection::Forward => self.build_dependency_tree(asset, max_depth),
+            TreeDirection::Reverse => self.build_reverse_tree(asset, max_depth),
         }
     }
 
     /// Detects circular dependencies in the map
+    ///
+    /// Each strongly connected component with more than one asset, and each asset that
+    /// depends on itself, is reported once with its members sorted by name. The members
+    /// are the assets of the cycle, not an edge path through it; see [`format_cycle`].
+    /// Uses an iterative Tarjan search so deep graphs can't overflow the stack.
     pub fn detect_circular_dependencies(&self) -> Vec<Vec<String>> {
-        let mut circular_refs = Vec::new();
-        let mut visited = HashSet::new();
-        let mut recursion_stack = HashSet::new();
+        // Number every asset, sorted so the search order is stable
+        let mut names: Vec<&str> = self
+            .dependencies
+            .iter()
+            .flat_map(|(asset, deps)| std::iter::once(asset.as_str()).chain(deps.iter().map(String::as_str)))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        let ids: HashMap<&str, usize> = names.iter().enumerate().map(|(i, &name)| (name, i)).collect();
+        let edges: Vec<Vec<usize>> = names
+            .iter()
+            .map(|name| self.dependencies.get(*name).into_iter().flatten().map(|dep| ids[dep.as_str()]).collect())
+            .collect();
 
-        for asset in self.dependencies.keys() {
-            if !visited.contains(asset) {
-                let mut path = Vec::new();
-                self.detect_cycles_dfs(
-                    asset,
-                    &mut visited,
-                    &mut recursion_stack,
-                    &mut path,
-                    &mut circular_refs,
-                );
+        const UNVISITED: usize = usize::MAX;
+        let mut index = vec![UNVISITED; names.len()];
+        let mut lowlink = vec![0; names.len()];
+        let mut on_stack = vec![false; names.len()];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut cycles = Vec::new();
+
+        for root in 0..names.len() {
+            if index[root] != UNVISITED {
+                continue;
+            }
+
+            // Explicit call stack of (asset, next edge to follow)
+            let mut call_stack = vec![(root, 0usize)];
+            index[root] = next_index;
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, edge)) = call_stack.last_mut() {
+                let node = *node;
+                if let Some(&dep) = edges[node].get(*edge) {
+                    *edge += 1;
+                    if index[dep] == UNVISITED {
+                        index[dep] = next_index;
+                        lowlink[dep] = next_index;
+                        next_index += 1;
+                        stack.push(dep);
+                        on_stack[dep] = true;
+                        call_stack.push((dep, 0));
+                    } else if on_stack[dep] {
+                        lowlink[node] = lowlink[node].min(index[dep]);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[node]);
+                }
+
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().expect("component root is on the stack");
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    if component.len() > 1 || edges[node].contains(&node) {
+                        // Ids follow name order, so this sorts the members by name
+                        component.sort_unstable();
+                        cycles.push(component.into_iter().map(|i| names[i].to_string()).collect());
+                    }
+                }
             }
         }
 
-        circular_refs
+        normalize_cycles(cycles)
     }
 
-    /// DFS helper for cycle detection
-    fn detect_cycles_dfs(
-        &self,
-        asset: &str,
-        visited: &mut HashSet<String>,
-        recursion_stack: &mut HashSet<String>,
-        path: &mut Vec<String>,
-        circular_refs: &mut Vec<Vec<String>>,
-    ) {
-        visited.insert(asset.to_string());
-        recursion_stack.insert(asset.to_string());
-        path.push(asset.to_string());
+    /// Orders assets so every dependency comes before its dependents
+    ///
+    /// Assets that only appear as dependencies are included. Ties are broken by name so
+    /// the order is stable. Fails with the graph's cycles when it isn't a DAG.
+    pub fn topological_sort(&self) -> std::result::Result<Vec<String>, Vec<Vec<String>>> {
+        use std::collections::BTreeSet;
 
-        if let Some(deps) = self.dependencies.get(asset) {
-            for dep in deps {
-                if !visited.contains(dep) {
-                    self.detect_cycles_dfs(dep, visited, recursion_stack, path, circular_refs);
-                } else if recursion_stack.contains(dep) {
-                    // Found a cycle
-                    let cycle_start = path.iter().position(|a| a == dep).unwrap();
-                    let cycle = path[cycle_start..].to_vec();
-                    circular_refs.push(cycle);
+        // Remaining unresolved dependencies per asset, and who waits on each asset
+        let mut pending: HashMap<&str, usize> = HashMap::new();
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (asset, deps) in &self.dependencies {
+            let unique: HashSet<&str> = deps.iter().map(String::as_str).collect();
+            *pending.entry(asset.as_str()).or_default() += unique.len();
+            for dep in unique {
+                pending.entry(dep).or_default();
+                dependents.entry(dep).or_default().push(asset.as_str());
+            }
+        }
+
+        let mut ready: BTreeSet<&str> = pending.iter().filter(|(_, &n)| n == 0).map(|(&a, _)| a).collect();
+        let mut order = Vec::with_capacity(pending.len());
+        while let Some(asset) = ready.pop_first() {
+            order.push(asset.to_string());
+            for &dependent in dependents.get(asset).into_iter().flatten() {
+                let remaining = pending.get_mut(dependent).expect("every dependent is tracked");
+                *remaining -= 1;
+                if *remaining == 0 {
+                    ready.insert(dependent);
                 }
             }
         }
 
-        path.pop();
-        recursion_stack.remove(asset);
+        if order.len() == pending.len() {
+            Ok(order)
+        } else {
+            Err(self.detect_circular_dependencies())
+        }
     }
 
     /// Finds orphaned assets (assets with no dependencies and no reverse dependencies)
@@ -239,6 +1111,207 @@ impl DependencyMap {
         orphaned
     }
 
+    /// Assets in `all_assets` that no root reaches through its dependencies
+    pub fn find_unreachable(&self, roots: &[String], all_assets: &[String]) -> Vec<String> {
+        let reachable = self.reachable_from(roots);
+        all_assets
+            .iter()
+            .filter(|asset| !reachable.contains(asset.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Like `find_unreachable`, with sizes and assets lacking dependency data split out
+    ///
+    /// An asset has dependency data when it's part of the graph or has node attributes;
+    /// `utils::build_from_pak` records a node for every package it could parse.
+    pub fn unreachable_report(&self, roots: &[String], all_assets: &[String], sizes: &HashMap<String, u64>) -> UnreachableReport {
+        let reachable = self.reachable_from(roots);
+        let in_graph: HashSet<&str> = self.all_assets().into_iter().collect();
+
+        let mut report = UnreachableReport {
+            roots: roots.len(),
+            reachable: reachable.len(),
+            ..Default::default()
+        };
+        for asset in all_assets {
+            if reachable.contains(asset.as_str()) {
+                continue;
+            }
+            if in_graph.contains(asset.as_str()) || self.nodes.contains_key(asset) {
+                let size = sizes.get(asset).copied().unwrap_or(0);
+                report.reclaimable_bytes += size;
+                report.unreachable.push((asset.clone(), size));
+            } else {
+                report.no_dependency_data.push(asset.clone());
+            }
+        }
+        report.unreachable.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        report.no_dependency_data.sort();
+        report
+    }
+
+    /// The roots and everything they depend on, directly or transitively
+    fn reachable_from<'a>(&'a self, roots: &'a [String]) -> HashSet<&'a str> {
+        let mut reachable: HashSet<&str> = roots.iter().map(String::as_str).collect();
+        let mut stack: Vec<&str> = reachable.iter().copied().collect();
+        while let Some(asset) = stack.pop() {
+            for dep in self.dependencies.get(asset).into_iter().flatten() {
+                if reachable.insert(dep) {
+                    stack.push(dep);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Computes degree, hub and folder metrics in one pass over the edges
+    pub fn metrics(&self) -> GraphMetrics {
+        let mut degrees: HashMap<&str, NodeDegree> = HashMap::new();
+        let mut folders: HashMap<&str, FolderMetrics> = HashMap::new();
+        let mut edge_count = 0;
+
+        for (asset, deps) in &self.dependencies {
+            let asset_folder = top_level_folder(asset);
+            degrees.entry(asset).or_default().out_degree += deps.len();
+            for dep in deps {
+                edge_count += 1;
+                degrees.entry(dep).or_default().in_degree += 1;
+
+                let dep_folder = top_level_folder(dep);
+                if asset_folder == dep_folder {
+                    folders.entry(asset_folder).or_default().internal_edges += 1;
+                } else {
+                    folders.entry(asset_folder).or_default().outgoing_edges += 1;
+                    folders.entry(dep_folder).or_default().incoming_edges += 1;
+                }
+            }
+        }
+
+        let mut in_degree_distribution = std::collections::BTreeMap::new();
+        let mut out_degree_distribution = std::collections::BTreeMap::new();
+        let mut hubs = Vec::with_capacity(degrees.len());
+        for (&asset, degree) in &degrees {
+            *in_degree_distribution.entry(degree.in_degree).or_default() += 1;
+            *out_degree_distribution.entry(degree.out_degree).or_default() += 1;
+            folders.entry(top_level_folder(asset)).or_default().assets += 1;
+            hubs.push((asset.to_string(), degree.in_degree + degree.out_degree));
+        }
+        hubs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        hubs.truncate(METRICS_HUB_COUNT);
+
+        let mut folders: Vec<FolderMetrics> = folders
+            .into_iter()
+            .map(|(folder, metrics)| FolderMetrics { folder: folder.to_string(), ..metrics })
+            .collect();
+        folders.sort_by(|a, b| b.assets.cmp(&a.assets).then_with(|| a.folder.cmp(&b.folder)));
+
+        GraphMetrics {
+            average_dependencies: if degrees.is_empty() { 0.0 } else { edge_count as f64 / degrees.len() as f64 },
+            degrees: degrees.into_iter().map(|(asset, degree)| (asset.to_string(), degree)).collect(),
+            in_degree_distribution,
+            out_degree_distribution,
+            hubs,
+            folders,
+        }
+    }
+
+    /// Records the pak each asset comes from, and its id, on its node
+    ///
+    /// Nodes are matched by package name ("/Game/Maps/Arena") or by asset name, whichever
+    /// the graph uses; the keys stay those names, the id only says which asset they found.
+    pub fn annotate_containers(&mut self, assets: &[std::sync::Arc<crate::preview::Asset>]) {
+        let known: HashSet<String> = self.all_assets().into_iter().map(str::to_string).collect();
+        for asset in assets {
+            for key in asset_keys(asset) {
+                if known.contains(&key) {
+                    let node = self.node_mut(&key);
+                    if asset.pak_file.is_some() {
+                        node.pak_file = asset.pak_file.as_deref().map(str::to_string);
+                    }
+                    node.asset_id = Some(asset.id.clone());
+                }
+            }
+        }
+    }
+
+    fn pak_of(&self, asset: &str) -> Option<&str> {
+        self.nodes.get(asset).and_then(|node| node.pak_file.as_deref())
+    }
+
+    /// Every edge whose ends live in different paks, grouped by (from, to), largest group first
+    pub fn cross_container_edges(&self) -> Vec<CrossContainerEdges> {
+        let edges = self
+            .dependencies
+            .iter()
+            .flat_map(|(asset, deps)| deps.iter().map(move |dep| (asset.as_str(), dep.as_str())));
+        self.group_cross_container(edges)
+    }
+
+    /// Like `cross_container_edges`, keeping only edges to or from `asset`
+    pub fn cross_container_edges_of(&self, asset: &str) -> Vec<CrossContainerEdges> {
+        let outgoing = self.dependencies.get(asset).into_iter().flatten().map(|dep| (asset, dep.as_str()));
+        let incoming = self
+            .dependencies
+            .iter()
+            .filter(|(dependent, deps)| dependent.as_str() != asset && deps.iter().any(|dep| dep == asset))
+            .map(|(dependent, _)| (dependent.as_str(), asset));
+        self.group_cross_container(outgoing.chain(incoming))
+    }
+
+    fn group_cross_container<'a>(&'a self, edges: impl Iterator<Item = (&'a str, &'a str)>) -> Vec<CrossContainerEdges> {
+        let mut groups: HashMap<(&str, &str), Vec<(String, String)>> = HashMap::new();
+        for (asset, dep) in edges {
+            if let (Some(from_pak), Some(to_pak)) = (self.pak_of(asset), self.pak_of(dep)) {
+                if from_pak != to_pak {
+                    groups.entry((from_pak, to_pak)).or_default().push((asset.to_string(), dep.to_string()));
+                }
+            }
+        }
+
+        let mut groups: Vec<CrossContainerEdges> = groups
+            .into_iter()
+            .map(|((from_pak, to_pak), mut edges)| {
+                edges.sort();
+                CrossContainerEdges { from_pak: from_pak.to_string(), to_pak: to_pak.to_string(), count: edges.len(), edges }
+            })
+            .collect();
+        groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| (&a.from_pak, &a.to_pak).cmp(&(&b.from_pak, &b.to_pak))));
+        groups
+    }
+
+    /// Per pak, how many assets it has and what it needs from other paks
+    pub fn container_summaries(&self) -> Vec<ContainerSummary> {
+        let mut assets_per_pak: HashMap<&str, usize> = HashMap::new();
+        for asset in self.all_assets() {
+            if let Some(pak) = self.pak_of(asset) {
+                *assets_per_pak.entry(pak).or_default() += 1;
+            }
+        }
+
+        let mut depends_on: HashMap<String, Vec<ContainerDependency>> = HashMap::new();
+        for group in self.cross_container_edges() {
+            let needed: HashSet<&str> = group.edges.iter().map(|(_, dep)| dep.as_str()).collect();
+            let needed = needed.len();
+            depends_on.entry(group.from_pak).or_default().push(ContainerDependency {
+                pak: group.to_pak,
+                assets: needed,
+                edges: group.count,
+            });
+        }
+
+        let mut summaries: Vec<ContainerSummary> = assets_per_pak
+            .into_iter()
+            .map(|(pak, assets)| {
+                let mut depends_on = depends_on.remove(pak).unwrap_or_default();
+                depends_on.sort_by(|a, b| b.assets.cmp(&a.assets).then_with(|| a.pak.cmp(&b.pak)));
+                ContainerSummary { pak: pak.to_string(), assets, depends_on }
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.pak.cmp(&b.pak));
+        summaries
+    }
+
     /// Gets the most referenced assets (sorted by reference count)
     pub fn get_most_referenced_assets(&self, limit: usize) -> Vec<(String, usize)> {
         let mut reference_counts: HashMap<String, usize> = HashMap::new();
@@ -260,22 +1333,107 @@ impl DependencyMap {
 
     /// Generates comprehensive dependency analysis for an asset
     pub fn analyze_asset_dependencies(&self, asset: &str, all_assets: &[String]) -> DependencyAnalysis {
+        self.analyze_asset_dependencies_with_sizes(asset, all_assets, &HashMap::new())
+    }
+
+    /// Like `analyze_asset_dependencies`, adding footprints from asset sizes (see `utils::package_sizes`)
+    pub fn analyze_asset_dependencies_with_sizes(
+        &self,
+        asset: &str,
+        all_assets: &[String],
+        sizes: &HashMap<String, u64>,
+    ) -> DependencyAnalysis {
+        self.analyze_asset_dependencies_to_depth(asset, all_assets, sizes, 5)
+    }
+
+    /// Like `analyze_asset_dependencies_with_sizes`, with both trees `depth` levels deep
+    pub fn analyze_asset_dependencies_to_depth(
+        &self,
+        asset: &str,
+        all_assets: &[String],
+        sizes: &HashMap<String, u64>,
+        depth: u32,
+    ) -> DependencyAnalysis {
+        let index = self.reverse_index();
         let direct_dependencies = self.get_dependencies(asset);
-        let reverse_dependencies = self.get_reverse_dependencies(asset);
-        let dependency_tree = self.build_dependency_tree(asset, 5); // Max depth of 5
-        let statistics = self.generate_statistics(all_assets);
+        let reverse_dependencies = index.dependents(asset).to_vec();
+        let transitive_dependents = index.dependents_by_depth(asset).into_iter().flatten().collect();
+        let dependency_tree = self.build_dependency_tree(asset, depth);
+        let reverse_dependency_tree = self.build_reverse_tree_with_index(&index, asset, depth);
+        let statistics = self.generate_statistics_with_sizes(all_assets, sizes);
+        let footprint = (!sizes.is_empty()).then(|| self.footprint(asset, sizes));
+        let cross_container_edges = self.cross_container_edges_of(asset);
 
         DependencyAnalysis {
             asset_name: asset.to_string(),
             direct_dependencies,
             reverse_dependencies,
+            transitive_dependents,
             dependency_tree,
+            reverse_dependency_tree,
             statistics,
+            footprint,
+            cross_container_edges,
         }
     }
 
+    /// Every asset reachable from `asset` through its dependencies, excluding itself
+    fn transitive_dependencies<'a>(&'a self, asset: &'a str) -> HashSet<&'a str> {
+        let mut reached = HashSet::new();
+        let mut stack = vec![asset];
+        while let Some(current) = stack.pop() {
+            for dep in self.dependencies.get(current).into_iter().flatten() {
+                if dep != asset && reached.insert(dep.as_str()) {
+                    stack.push(dep);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Sums the sizes of an asset and its transitive dependencies
+    ///
+    /// Dependencies shared by several branches are counted once; assets missing from
+    /// `sizes` count as zero bytes.
+    pub fn footprint(&self, asset: &str, sizes: &HashMap<String, u64>) -> FootprintReport {
+        let size_of = |a: &str| sizes.get(a).copied().unwrap_or(0);
+        let reached = self.transitive_dependencies(asset);
+
+        let mut largest_contributors: Vec<(String, u64)> = reached
+            .iter()
+            .map(|&dep| (dep.to_string(), size_of(dep)))
+            .collect();
+        largest_contributors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let transitive_bytes = size_of(asset) + largest_contributors.iter().map(|(_, size)| size).sum::<u64>();
+        largest_contributors.truncate(10);
+
+        FootprintReport {
+            direct_bytes: size_of(asset),
+            transitive_bytes,
+            unique_transitive_assets: reached.len(),
+            largest_contributors,
+        }
+    }
+
+    /// Assets with the largest transitive footprint, largest first
+    pub fn largest_footprints(&self, sizes: &HashMap<String, u64>, limit: usize) -> Vec<(String, u64)> {
+        let assets: HashSet<&str> = self.dependencies.keys().chain(sizes.keys()).map(String::as_str).collect();
+        let mut footprints: Vec<(String, u64)> = assets
+            .into_iter()
+            .map(|asset| (asset.to_string(), self.footprint(asset, sizes).transitive_bytes))
+            .collect();
+        footprints.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        footprints.truncate(limit);
+        footprints
+    }
+
     /// Generates overall statistics for the dependency map
     pub fn generate_statistics(&self, all_assets: &[String]) -> DependencyStatistics {
+        self.generate_statistics_with_sizes(all_assets, &HashMap::new())
+    }
+
+    /// Like `generate_statistics`, adding the top 20 assets by transitive footprint
+    pub fn generate_statistics_with_sizes(&self, all_assets: &[String], sizes: &HashMap<String, u64>) -> DependencyStatistics {
         let circular_references = self.detect_circular_dependencies();
         let orphaned_assets = self.find_orphaned_assets(all_assets);
         let most_referenced = self.get_most_referenced_assets(10);
@@ -293,12 +1451,28 @@ impl DependencyMap {
             .map(|deps| deps.len())
             .sum();
 
+        let soft_dependencies = self.edge_kinds.values().map(|kinds| kinds.len()).sum();
+        let largest_footprints = if sizes.is_empty() {
+            Vec::new()
+        } else {
+            self.largest_footprints(sizes, 20)
+        };
+
+        // Per-asset degrees would dwarf the rest of the statistics
+        let mut metrics = self.metrics();
+        metrics.degrees.clear();
+        let containers = self.container_summaries();
+
         DependencyStatistics {
             total_dependencies,
             max_depth,
             circular_references,
             orphaned_assets,
             most_referenced,
+            soft_dependencies,
+            largest_footprints,
+            metrics,
+            containers,
         }
     }
 
@@ -325,68 +1499,397 @@ impl DependencyMap {
                     .max()
                     .unwrap_or(0)
             })
-            .unwrap_or(0);
+            .unwrap_or(0);
+
+        visited.remove(asset);
+        max_child_depth + 1
+    }
+
+    /// Exports dependency map to various formats
+    pub fn export_to_format(&self, format: &str) -> Result<String> {
+        self.export_with_options(format, &ExportOptions::default())
+    }
+
+    /// Exports in the given format, passing `options` to the formats that use them
+    pub fn export_with_options(&self, format: &str, options: &ExportOptions) -> Result<String> {
+        match format.to_lowercase().as_str() {
+            "json" => Ok(serde_json::to_string_pretty(self)?),
+            "dot" => Ok(self.export_to_dot()),
+            "csv" => Ok(self.export_to_csv()),
+            "graphml" => Ok(self.export_to_graphml()),
+            "cytoscape" => Ok(self.export_to_cytoscape()?),
+            "mermaid" => {
+                let max_depth = options.max_depth.unwrap_or(u32::MAX);
+                let max_nodes = options.max_nodes.unwrap_or(DEFAULT_MERMAID_NODES);
+                Ok(match &options.root {
+                    Some(root) => self.export_mermaid(root, max_depth, max_nodes),
+                    None => self.mermaid_from(&self.root_assets(), max_depth, max_nodes),
+                })
+            }
+            "order" => match self.topological_sort() {
+                Ok(order) => Ok(order.join("\n")),
+                Err(cycles) => {
+                    let cycles: Vec<String> = cycles.iter().map(|c| format_cycle(c)).collect();
+                    Err(anyhow::anyhow!("Dependency graph has cycles: {}", cycles.join("; ")))
+                }
+            },
+            "yaml" => Ok(self.export_to_yaml()?),
+            _ => Err(anyhow::anyhow!("Unsupported export format: {}", format)),
+        }
+    }
+
+    /// Exports to DOT format for GraphViz visualization
+    fn export_to_dot(&self) -> String {
+        let mut dot = String::from("digraph AssetDependencies {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    node [shape=box, style=rounded];\n\n");
+
+        // Add nodes
+        let all_assets: HashSet<String> = self
+            .dependencies
+            .keys()
+            .cloned()
+            .chain(
+                self.dependencies
+                    .values()
+                    .flat_map(|deps| deps.iter().cloned()),
+            )
+            .collect();
+
+        for asset in &all_assets {
+            dot.push_str(&format!("    \"{}\";\n", asset));
+        }
+
+        dot.push('\n');
+
+        // Add edges (soft references dashed)
+        for (asset, deps) in &self.dependencies {
+            for dep in deps {
+                match self.dependency_kind(asset, dep) {
+                    DependencyKind::Hard => dot.push_str(&format!("    \"{}\" -> \"{}\";\n", asset, dep)),
+                    DependencyKind::Soft => {
+                        dot.push_str(&format!("    \"{}\" -> \"{}\" [style=dashed];\n", asset, dep))
+                    }
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports to GraphML (yEd, Gephi) with node attributes and edge kinds
+    fn export_to_graphml(&self) -> String {
+        let assets = self.all_assets();
+        let edge_count: usize = self.dependencies.values().map(Vec::len).sum();
+        let mut xml = String::with_capacity(512 + assets.len() * 96 + edge_count * 96);
+
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        xml.push_str("  <key id=\"asset_type\" for=\"node\" attr.name=\"asset_type\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"size\" for=\"node\" attr.name=\"size\" attr.type=\"long\"/>\n");
+        xml.push_str("  <key id=\"pak_file\" for=\"node\" attr.name=\"pak_file\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n");
+        xml.push_str("  <graph id=\"AssetDependencies\" edgedefault=\"directed\">\n");
+
+        for asset in &assets {
+            xml.push_str("    <node id=\"");
+            push_xml_escaped(&mut xml, asset);
+            xml.push('"');
+            match self.nodes.get(*asset) {
+                Some(node) if *node != AssetNode::default() => {
+                    xml.push_str(">\n");
+                    if let Some(asset_type) = &node.asset_type {
+                        xml.push_str("      <data key=\"asset_type\">");
+                        push_xml_escaped(&mut xml, asset_type);
+                        xml.push_str("</data>\n");
+                    }
+                    if let Some(size) = node.size {
+                        xml.push_str("      <data key=\"size\">");
+                        xml.push_str(&size.to_string());
+                        xml.push_str("</data>\n");
+                    }
+                    if let Some(pak_file) = &node.pak_file {
+                        xml.push_str("      <data key=\"pak_file\">");
+                        push_xml_escaped(&mut xml, pak_file);
+                        xml.push_str("</data>\n");
+                    }
+                    xml.push_str("    </node>\n");
+                }
+                _ => xml.push_str("/>\n"),
+            }
+        }
+
+        let mut sources: Vec<&String> = self.dependencies.keys().collect();
+        sources.sort();
+        for asset in sources {
+            for dep in &self.dependencies[asset] {
+                xml.push_str("    <edge source=\"");
+                push_xml_escaped(&mut xml, asset);
+                xml.push_str("\" target=\"");
+                push_xml_escaped(&mut xml, dep);
+                xml.push_str("\"><data key=\"kind\">");
+                xml.push_str(self.dependency_kind(asset, dep).as_str());
+                xml.push_str("</data></edge>\n");
+            }
+        }
+
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// Exports to Cytoscape.js elements JSON
+    fn export_to_cytoscape(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct Element<T> {
+            data: T,
+        }
+        #[derive(Serialize)]
+        struct NodeData<'a> {
+            id: &'a str,
+            #[serde(flatten)]
+            node: Option<&'a AssetNode>,
+        }
+        #[derive(Serialize)]
+        struct EdgeData<'a> {
+            id: String,
+            source: &'a str,
+            target: &'a str,
+            kind: DependencyKind,
+        }
+        #[derive(Serialize)]
+        struct Elements<'a> {
+            nodes: Vec<Element<NodeData<'a>>>,
+            edges: Vec<Element<EdgeData<'a>>>,
+        }
+
+        let nodes = self
+            .all_assets()
+            .into_iter()
+            .map(|id| Element { data: NodeData { id, node: self.nodes.get(id) } })
+            .collect();
+
+        let mut sources: Vec<&String> = self.dependencies.keys().collect();
+        sources.sort();
+        let edges = sources
+            .into_iter()
+            .flat_map(|asset| self.dependencies[asset].iter().map(move |dep| (asset, dep)))
+            .enumerate()
+            .map(|(i, (asset, dep))| Element {
+                data: EdgeData {
+                    id: format!("e{}", i),
+                    source: asset,
+                    target: dep,
+                    kind: self.dependency_kind(asset, dep),
+                },
+            })
+            .collect();
 
-        visited.remove(asset);
-        max_child_depth + 1
+        #[derive(Serialize)]
+        struct Document<'a> {
+            elements: Elements<'a>,
+        }
+        Ok(serde_json::to_string(&Document { elements: Elements { nodes, edges } })?)
     }
 
-    /// Exports dependency map to various formats
-    pub fn export_to_format(&self, format: &str) -> Result<String> {
-        match format.to_lowercase().as_str() {
-            "json" => Ok(serde_json::to_string_pretty(self)?),
-            "dot" => Ok(self.export_to_dot()),
-            "csv" => Ok(self.export_to_csv()),
-            "yaml" => {
-                // Note: Would need serde_yaml crate for actual YAML support
-                Ok("YAML export not implemented yet".to_string())
+    /// Exports a Mermaid `graph LR` flowchart of the assets reachable from `root`
+    ///
+    /// Follows at most `max_depth` levels and draws at most `max_nodes` assets; the rest
+    /// are summarized by a "…N more" node. Edges are labelled with their kind.
+    pub fn export_mermaid(&self, root: &str, max_depth: u32, max_nodes: usize) -> String {
+        self.mermaid_from(&[root], max_depth, max_nodes)
+    }
+
+    /// Assets nothing depends on, or every asset when cycles leave none
+    fn root_assets(&self) -> Vec<&str> {
+        let dependencies: HashSet<&str> = self.dependencies.values().flatten().map(String::as_str).collect();
+        let all_assets = self.all_assets();
+        let roots: Vec<&str> = all_assets.iter().copied().filter(|a| !dependencies.contains(a)).collect();
+        if roots.is_empty() {
+            all_assets
+        } else {
+            roots
+        }
+    }
+
+    /// Breadth-first Mermaid rendering from several roots
+    fn mermaid_from<'a>(&'a self, roots: &[&'a str], max_depth: u32, max_nodes: usize) -> String {
+        let max_nodes = max_nodes.max(1);
+        let mut ids: HashMap<&str, usize> = HashMap::new();
+        let mut assets: Vec<&str> = Vec::new();
+        let mut edges: Vec<(usize, usize, DependencyKind)> = Vec::new();
+        let mut seen_edges = HashSet::new();
+        let mut left_out: HashSet<&str> = HashSet::new();
+        let mut truncated_parents = std::collections::BTreeSet::new();
+        let mut queue = std::collections::VecDeque::new();
+
+        for &root in roots {
+            if ids.contains_key(root) {
+                continue;
             }
-            _ => Err(anyhow::anyhow!("Unsupported export format: {}", format)),
+            if assets.len() < max_nodes {
+                ids.insert(root, assets.len());
+                assets.push(root);
+                queue.push_back((root, 0));
+            } else {
+                left_out.insert(root);
+            }
+        }
+
+        while let Some((asset, depth)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+            let from = ids[asset];
+            for dep in self.dependencies.get(asset).into_iter().flatten() {
+                let dep = dep.as_str();
+                let to = match ids.get(dep) {
+                    Some(&to) => to,
+                    None if assets.len() < max_nodes => {
+                        ids.insert(dep, assets.len());
+                        assets.push(dep);
+                        queue.push_back((dep, depth + 1));
+                        assets.len() - 1
+                    }
+                    None => {
+                        left_out.insert(dep);
+                        truncated_parents.insert(from);
+                        continue;
+                    }
+                };
+                if seen_edges.insert((from, to)) {
+                    edges.push((from, to, self.dependency_kind(asset, dep)));
+                }
+            }
+        }
+
+        let mut mermaid = String::with_capacity(32 + assets.len() * 48 + edges.len() * 24);
+        mermaid.push_str("graph LR\n");
+        for (id, asset) in assets.iter().enumerate() {
+            mermaid.push_str(&format!("    n{}[\"{}\"]\n", id, mermaid_label(asset)));
+        }
+        if !left_out.is_empty() {
+            mermaid.push_str(&format!("    more[\"…{} more\"]\n", left_out.len()));
+        }
+        for (from, to, kind) in edges {
+            let arrow = match kind {
+                DependencyKind::Hard => "-->",
+                DependencyKind::Soft => "-.->",
+            };
+            mermaid.push_str(&format!("    n{} {}|{}| n{}\n", from, arrow, kind.as_str(), to));
+        }
+        for parent in truncated_parents {
+            mermaid.push_str(&format!("    n{} -.- more\n", parent));
         }
+        mermaid
     }
 
-    /// Exports to DOT format for GraphViz visualization
-    fn export_to_dot(&self) -> String {
-        let mut dot = String::from("digraph AssetDependencies {\n");
-        dot.push_str("    rankdir=LR;\n");
-        dot.push_str("    node [shape=box, style=rounded];\n\n");
+    /// Exports to YAML as a mapping of asset to its list of dependencies
+    ///
+    /// Soft edges are written as `soft: <path>`. Node attributes and pak sources are
+    /// left out; use JSON to keep them.
+    fn export_to_yaml(&self) -> Result<String> {
+        use std::borrow::Cow;
 
-        // Add nodes
-        let all_assets: HashSet<String> = self
+        let sorted: std::collections::BTreeMap<&str, Vec<YamlDependency<'_>>> = self
             .dependencies
-            .keys()
-            .cloned()
-            .chain(
-                self.dependencies
-                    .values()
-                    .flat_map(|deps| deps.iter().cloned()),
-            )
+            .iter()
+            .map(|(asset, deps)| {
+                let deps = deps
+                    .iter()
+                    .map(|dep| match self.dependency_kind(asset, dep) {
+                        DependencyKind::Hard => YamlDependency::Hard(Cow::Borrowed(dep)),
+                        DependencyKind::Soft => YamlDependency::Soft { soft: Cow::Borrowed(dep) },
+                    })
+                    .collect();
+                (asset.as_str(), deps)
+            })
             .collect();
+        Ok(serde_yaml::to_string(&sorted)?)
+    }
 
-        for asset in &all_assets {
-            dot.push_str(&format!("    \"{}\";\n", asset));
-        }
+    /// Saves the map in the scan cache's versioned binary format
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        crate::scan_cache::write_versioned(path.as_ref(), self)
+    }
 
-        dot.push('\n');
+    /// Loads a map written by `save`; corrupt or outdated files are errors
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<DependencyMap> {
+        crate::scan_cache::read_versioned(path.as_ref())
+    }
 
-        // Add edges
-        for (asset, deps) in &self.dependencies {
+    /// Loads a map written by the YAML export, soft edges included
+    ///
+    /// Node attributes aren't part of the YAML format, so the loaded map has none.
+    pub fn import_from_yaml(yaml: &str) -> Result<DependencyMap> {
+        let assets: HashMap<String, Vec<YamlDependency<'static>>> = serde_yaml::from_str(yaml)?;
+        let mut map = DependencyMap::new();
+        for (asset, deps) in assets {
+            map.dependencies.entry(asset.clone()).or_default();
             for dep in deps {
-                dot.push_str(&format!("    \"{}\" -> \"{}\";\n", asset, dep));
+                match dep {
+                    YamlDependency::Hard(dep) => map.add_typed_dependency(&asset, &dep, DependencyKind::Hard),
+                    YamlDependency::Soft { soft } => map.add_typed_dependency(&asset, &soft, DependencyKind::Soft),
+                }
             }
         }
+        Ok(map)
+    }
 
-        dot.push_str("}\n");
-        dot
+    /// Loads `Asset,Dependency[,Kind]` rows as written by the CSV export
+    ///
+    /// A header row and blank lines are skipped; a missing kind means hard. Malformed rows
+    /// fail with their line number and content.
+    pub fn import_from_csv<R: std::io::Read>(reader: R) -> Result<DependencyMap> {
+        use std::io::BufRead;
+
+        let mut map = DependencyMap::new();
+        for (index, line) in std::io::BufReader::new(reader).lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.map_err(|e| anyhow::anyhow!("Line {}: {}", line_number, e))?;
+            let row = line.trim();
+            if row.is_empty() || (line_number == 1 && row.to_lowercase().starts_with("asset,dependency")) {
+                continue;
+            }
+
+            let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+            let (asset, dependency, kind) = match fields.as_slice() {
+                [asset, dependency] => (*asset, *dependency, ""),
+                [asset, dependency, kind] => (*asset, *dependency, *kind),
+                _ => anyhow::bail!("Line {}: expected Asset,Dependency[,Kind], got {:?}", line_number, row),
+            };
+            if asset.is_empty() || dependency.is_empty() {
+                anyhow::bail!("Line {}: empty asset or dependency in {:?}", line_number, row);
+            }
+            let kind = match kind {
+                "" | "hard" => DependencyKind::Hard,
+                "soft" => DependencyKind::Soft,
+                other => anyhow::bail!("Line {}: unknown dependency kind {:?} in {:?}", line_number, other, row),
+            };
+            map.add_typed_dependency(asset, dependency, kind);
+        }
+        Ok(map)
+    }
+
+    /// Loads a map written by the JSON export
+    ///
+    /// Parse errors name the line and its content.
+    pub fn import_from_json<R: std::io::Read>(mut reader: R) -> Result<DependencyMap> {
+        let mut json = String::new();
+        reader.read_to_string(&mut json)?;
+        serde_json::from_str(&json).map_err(|e| {
+            let content = json.lines().nth(e.line().saturating_sub(1)).unwrap_or_default().trim();
+            anyhow::anyhow!("Line {}: {} in {:?}", e.line(), e, content)
+        })
     }
 
     /// Exports to CSV format
     fn export_to_csv(&self) -> String {
-        let mut csv = String::from("Asset,Dependency\n");
+        let mut csv = String::from("Asset,Dependency,Kind\n");
         
         for (asset, deps) in &self.dependencies {
             for dep in deps {
-                csv.push_str(&format!("{},{}\n", asset, dep));
+                csv.push_str(&format!("{},{},{}\n", asset, dep, self.dependency_kind(asset, dep).as_str()));
             }
         }
 
@@ -394,29 +1897,71 @@ impl DependencyMap {
     }
 
     /// Validates the dependency map for consistency
-    pub fn validate(&self) -> Vec<String> {
+    pub fn validate(&self) -> Vec<ValidationIssue> {
         let mut issues = Vec::new();
 
-        // Check for self-references
-        for (asset, deps) in &self.dependencies {
-            if deps.contains(asset) {
-                issues.push(format!("Self-reference detected: {} depends on itself", asset));
+        // Check for self-references and circular dependencies
+        for cycle in self.detect_circular_dependencies() {
+            if let [asset] = cycle.as_slice() {
+                issues.push(ValidationIssue::SelfReference { asset: asset.clone() });
+            } else {
+                issues.push(ValidationIssue::CircularDependency { cycle });
             }
         }
 
-        // Check for circular dependencies
-        let circular_deps = self.detect_circular_dependencies();
-        for cycle in circular_deps {
-            issues.push(format!("Circular dependency detected: {}", cycle.join(" -> ")));
-        }
-
         // Check for empty dependency lists
-        for (asset, deps) in &self.dependencies {
-            if deps.is_empty() {
-                issues.push(format!("Asset {} has empty dependency list", asset));
-            }
+        let mut empty: Vec<&String> = self.dependencies.iter().filter(|(_, deps)| deps.is_empty()).map(|(asset, _)| asset).collect();
+        empty.sort();
+        issues.extend(empty.into_iter().map(|asset| ValidationIssue::EmptyDependencyList { asset: asset.clone() }));
+
+        issues
+    }
+
+    /// Reports references to assets missing from the index, grouped by referencing asset
+    ///
+    /// `known` holds the scanned assets and `unscanned` those only seen in paks whose
+    /// contents couldn't be read. Native classes ("/Script/...") never live in paks and
+    /// are not reported.
+    pub fn validate_references(&self, known: &HashSet<String>, unscanned: &HashSet<String>) -> Vec<ValidationIssue> {
+        let mut issues: Vec<(&String, Vec<MissingReference>)> = self
+            .dependencies
+            .iter()
+            .filter_map(|(asset, deps)| {
+                let mut missing: Vec<MissingReference> = deps
+                    .iter()
+                    .filter(|dep| !dep.starts_with("/Script/") && !known.contains(dep.as_str()))
+                    .map(|dep| MissingReference {
+                        dependency: dep.clone(),
+                        kind: self.dependency_kind(asset, dep),
+                        reason: if unscanned.contains(dep.as_str()) { MissingReason::Unscanned } else { MissingReason::NotFound },
+                    })
+                    .collect();
+                missing.sort_by(|a, b| a.dependency.cmp(&b.dependency));
+                missing.dedup_by(|a, b| a.dependency == b.dependency);
+                (!missing.is_empty()).then_some((asset, missing))
+            })
+            .collect();
+        issues.sort_by(|a, b| a.0.cmp(b.0));
+        issues
+            .into_iter()
+            .map(|(asset, missing)| ValidationIssue::MissingReferences { asset: asset.clone(), missing })
+            .collect()
+    }
+
+    /// `validate` plus `validate_references` against a scanned asset list
+    ///
+    /// Assets are known by package name and by asset name; assets of encrypted paks count
+    /// as unscanned.
+    pub fn validate_against_assets(&self, assets: &[std::sync::Arc<crate::preview::Asset>]) -> Vec<ValidationIssue> {
+        let mut known = HashSet::new();
+        let mut unscanned = HashSet::new();
+        for asset in assets {
+            let keys = if asset.is_encrypted == Some(true) { &mut unscanned } else { &mut known };
+            keys.extend(asset_keys(asset));
         }
 
+        let mut issues = self.validate();
+        issues.extend(self.validate_references(&known, &unscanned));
         issues
     }
 
@@ -444,10 +1989,96 @@ impl DependencyMap {
             self.dependencies.remove(&key);
         }
 
+        // Drop kinds of edges that no longer exist
+        let dependencies = &self.dependencies;
+        self.edge_kinds.retain(|asset, kinds| {
+            kinds.retain(|dep, _| dependencies.get(asset).is_some_and(|deps| deps.contains(dep)));
+            !kinds.is_empty()
+        });
+
         removed_count
     }
 }
 
+/// Rotates each cycle to start at its smallest member, then sorts and deduplicates them
+///
+/// The same cycle found from different entry points normalizes to one entry.
+pub fn normalize_cycles(cycles: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    let mut normalized: Vec<Vec<String>> = cycles
+        .into_iter()
+        .filter(|cycle| !cycle.is_empty())
+        .map(|mut cycle| {
+            let start = cycle
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.cmp(b.1))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            cycle.rotate_left(start);
+            cycle
+        })
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+/// Formats the members of a detected cycle for messages and reports
+///
+/// A lone asset depends on itself, so it prints as the edge "A -> A". Larger components
+/// print as the set "{A, B, C}": their members don't form a single path in that order.
+pub fn format_cycle(members: &[String]) -> String {
+    match members {
+        [asset] => format!("{} -> {}", asset, asset),
+        _ => format!("{{{}}}", members.join(", ")),
+    }
+}
+
+/// Top-level folder of an asset ("/Game/Characters/Hero/Mesh" -> "/Game/Characters"),
+/// or "" for names that aren't paths
+fn top_level_folder(asset: &str) -> &str {
+    if !asset.starts_with('/') {
+        return "";
+    }
+    match asset.match_indices('/').nth(2) {
+        Some((end, _)) => &asset[..end],
+        None => asset.rsplit_once('/').map_or("", |(folder, _)| folder),
+    }
+}
+
+/// Names an asset can have in the graph: its package name ("/Game/Maps/Arena"), if its
+/// path has one, and its asset name
+pub fn asset_keys(asset: &crate::preview::Asset) -> impl Iterator<Item = String> {
+    crate::asset_registry::package_name_for_path(&asset.path)
+        .into_iter()
+        .chain(std::iter::once(asset.name.clone()))
+}
+
+/// File name of a pak without its folder or extension ("Paks/pakchunk2-Windows.pak" -> "pakchunk2-Windows")
+fn pak_label(pak_file: &str) -> &str {
+    let name = pak_file.rsplit(['/', '\\']).next().unwrap_or(pak_file);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+/// Escapes an asset name for a quoted Mermaid node label
+fn mermaid_label(asset: &str) -> String {
+    asset.replace('"', "#quot;")
+}
+
+/// Appends text with the five XML special characters escaped
+fn push_xml_escaped(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+}
+
 impl Default for DependencyMap {
     fn default() -> Self {
         Self::new()
@@ -457,6 +2088,29 @@ impl Default for DependencyMap {
 /// Utility functions for working with dependencies
 pub mod utils {
     use super::*;
+    use std::sync::Arc;
+    use tokio_util::sync::CancellationToken;
+
+    use crate::error::Cancelled;
+
+    /// Assets read at once when extracting references
+    pub const DEFAULT_SCAN_CONCURRENCY: usize = 8;
+
+    /// Totals file sizes per dependency graph key
+    ///
+    /// Files of one package (.uasset, .uexp, .ubulk) add up under its package name
+    /// ("/Game/Maps/Arena"); paths outside a Content folder are keyed by file stem.
+    pub fn package_sizes<'a>(files: impl IntoIterator<Item = (&'a str, u64)>) -> HashMap<String, u64> {
+        let mut sizes = HashMap::new();
+        for (path, size) in files {
+            let key = crate::asset_registry::package_name_for_path(path).unwrap_or_else(|| {
+                let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+                name.split('.').next().unwrap_or(name).to_string()
+            });
+            *sizes.entry(key).or_default() += size;
+        }
+        sizes
+    }
 
     /// Parses dependencies from Unreal Engine asset files
     /// 
@@ -499,50 +2153,247 @@ pub mod utils {
         Ok(dependencies)
     }
 
+    /// Packages referenced by a cooked package, typed as hard or soft
+    ///
+    /// Imports are hard references. Soft ones come from the summary's soft package list,
+    /// the UE5.1+ soft object path table, and property values that look like content
+    /// object paths ("/Game/Props/Crate.Crate"). Native `/Script/` packages are skipped.
+    /// Zen packages (IoStore) only give the hard references their header names, from UE 5.3.
+    pub fn extract_package_references(data: &[u8]) -> Result<Vec<(String, DependencyKind)>> {
+        use crate::uasset_parser::{self, ArchiveReader, Package};
+        use crate::zen_package::ZenPackage;
+
+        if ZenPackage::is_zen(data) {
+            let package = ZenPackage::parse(data)?;
+            return Ok(package.imported_packages().into_iter().map(|p| (p, DependencyKind::Hard)).collect());
+        }
+        let package = Package::parse(data)?;
+        let mut references: Vec<(String, DependencyKind)> = package
+            .imported_packages()
+            .into_iter()
+            .filter(|p| !p.starts_with("/Script/"))
+            .map(|p| (p, DependencyKind::Hard))
+            .collect();
+
+        let mut soft: Vec<String> = package.soft_package_references(data).unwrap_or_default();
+        soft.extend(package.soft_object_paths.iter().filter_map(|path| object_path_package(path)));
+        for export in &package.exports {
+            let Ok(bytes) = package.export_data(data, export) else { continue };
+            // Unknown layouts, and unversioned ones without mappings, just contribute nothing
+            let class = package.export_class_name(export);
+            if let Ok(properties) = uasset_parser::read_properties(&package, &mut ArchiveReader::new(bytes), &class) {
+                collect_object_paths(&serde_json::Value::Object(properties), &mut soft);
+            }
+        }
+
+        soft.sort();
+        soft.dedup();
+        for path in soft {
+            if !references.iter().any(|(p, _)| *p == path) {
+                references.push((path, DependencyKind::Soft));
+            }
+        }
+        Ok(references)
+    }
+
+    /// Package of an object path ("/Game/A/B.B:Sub" -> "/Game/A/B"), if the string is one
+    pub fn object_path_package(value: &str) -> Option<String> {
+        let value = value.trim();
+        let rest = value.strip_prefix('/')?;
+        let (mount, _) = rest.split_once('/')?;
+        if mount.is_empty()
+            || matches!(mount, "Script" | "Temp" | "Memory")
+            || !mount.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            || value.chars().any(char::is_whitespace)
+        {
+            return None;
+        }
+        let package = value.split(['.', ':']).next()?;
+        (package.len() > mount.len() + 2).then(|| package.to_string())
+    }
+
+    fn collect_object_paths(value: &serde_json::Value, out: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) => out.extend(object_path_package(s)),
+            serde_json::Value::Array(items) => items.iter().for_each(|v| collect_object_paths(v, out)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| collect_object_paths(v, out)),
+            _ => {}
+        }
+    }
+
     /// Scans a directory for asset files and builds a dependency map
-    pub async fn scan_directory_for_dependencies(dir: &str) -> Result<DependencyMap> {
-        use std::fs;
+    ///
+    /// Fails with `Cancelled` once `cancel` fires; assets being read then are abandoned.
+    pub async fn scan_directory_for_dependencies(dir: &str, cancel: &CancellationToken) -> Result<DependencyMap> {
+        scan_directory_with_progress(dir, DEFAULT_SCAN_CONCURRENCY, |_, _| {}, cancel).await
+    }
+
+    /// Like `scan_directory_for_dependencies`, reading `concurrency` assets at a time
+    ///
+    /// `on_progress` gets (assets done, total) as assets finish.
+    pub async fn scan_directory_with_progress(
+        dir: &str,
+        concurrency: usize,
+        on_progress: impl FnMut(usize, usize),
+        cancel: &CancellationToken,
+    ) -> Result<DependencyMap> {
+        let mut asset_paths = Vec::new();
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|extension| extension == "uasset") && path.to_str().is_some() {
+                    asset_paths.push(path);
+                }
+            }
+        }
+
+        collect_references(asset_paths, concurrency, on_progress, cancel, |path| async move {
+            let asset_name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("Unknown").to_string();
+
+            // Real packages give typed references; anything else uses the placeholder
+            let mut data = tokio::fs::read(&path).await.ok()?;
+            if let Ok(uexp) = tokio::fs::read(path.with_extension("uexp")).await {
+                data.extend(uexp);
+            }
+            let references = match extract_package_references(&data) {
+                Ok(references) => references,
+                Err(_) => extract_dependencies_from_asset(path.to_str()?)
+                    .await
+                    .ok()?
+                    .into_iter()
+                    .map(|dep| (dep, DependencyKind::Hard))
+                    .collect(),
+            };
+            Some((asset_name, references))
+        })
+        .await
+    }
+
+    /// Runs `extract` on every item, `concurrency` at a time, and builds a map of the results
+    ///
+    /// `extract` yields an asset and its references, or None when the item has no data.
+    /// Results arrive in completion order; the final `optimize` makes the map independent of it.
+    /// Names that differ only in separators, mount prefix or the root's case are one node.
+    /// Fails with `Cancelled` as soon as `cancel` fires, aborting the items still being read.
+    async fn collect_references<T, F, Fut>(
+        items: Vec<T>,
+        concurrency: usize,
+        mut on_progress: impl FnMut(usize, usize),
+        cancel: &CancellationToken,
+        extract: F,
+    ) -> Result<DependencyMap>
+    where
+        T: Send + 'static,
+        F: Fn(T) -> Fut,
+        Fut: std::future::Future<Output = Option<(String, Vec<(String, DependencyKind)>)>> + Send + 'static,
+    {
+        let total = items.len();
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency.max(1)));
+        let mut tasks = tokio::task::JoinSet::new();
+        for item in items {
+            let semaphore = semaphore.clone();
+            let extraction = extract(item);
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                extraction.await
+            });
+        }
 
         let mut dependency_map = DependencyMap::new();
-        
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if let Some(extension) = path.extension() {
-                        if extension == "uasset" {
-                            if let Some(path_str) = path.to_str() {
-                                let asset_name = path
-                                    .file_stem()
-                                    .and_then(|s| s.to_str())
-                                    .unwrap_or("Unknown")
-                                    .to_string();
-
-                                let dependencies = extract_dependencies_from_asset(path_str).await?;
-                                
-                                for dep in dependencies {
-                                    dependency_map.add_dependency(&asset_name, &dep);
-                                }
-                            }
-                        }
+        let mut names = PathSpellings::default();
+        let mut done = 0;
+        loop {
+            let joined = tokio::select! {
+                joined = tasks.join_next() => joined,
+                _ = cancel.cancelled() => None,
+            };
+            let Some(joined) = joined else {
+                break;
+            };
+            done += 1;
+            match joined {
+                Ok(Some((asset, references))) => {
+                    // Assets with data get a node even without references, so they count as known
+                    let asset = names.spelling(&asset);
+                    dependency_map.node_mut(&asset);
+                    for (dep, kind) in references {
+                        dependency_map.add_typed_dependency(&asset, &names.spelling(&dep), kind);
                     }
                 }
+                Ok(None) => {}
+                Err(e) => tracing::error!("Dependency extraction task failed: {}", e),
             }
+            on_progress(done, total);
+        }
+        if cancel.is_cancelled() {
+            tasks.abort_all();
+            tracing::info!("Dependency scan cancelled after {} of {} assets", done, total);
+            return Err(Cancelled.into());
         }
 
+        dependency_map.optimize();
         Ok(dependency_map)
     }
 
+    /// Builds the dependency graph of one pak, keyed by package name
+    ///
+    /// Uses the pak's AssetRegistry.bin when it has one; otherwise reads the references
+    /// of each package. Packages that can't be read or parsed are skipped.
+    pub async fn build_from_pak(pak_path: &str) -> Result<DependencyMap> {
+        build_from_pak_with_progress(pak_path, DEFAULT_SCAN_CONCURRENCY, |_, _| {}, &CancellationToken::new()).await
+    }
+
+    /// Like `build_from_pak`, reading `concurrency` packages at a time with progress and cancellation
+    pub async fn build_from_pak_with_progress(
+        pak_path: &str,
+        concurrency: usize,
+        on_progress: impl FnMut(usize, usize),
+        cancel: &CancellationToken,
+    ) -> Result<DependencyMap> {
+        if let Some(registry) = crate::asset_registry::cached_registry(pak_path).await {
+            return Ok(registry.dependency_map());
+        }
+
+        let pak = Arc::new(crate::pak_parser::OpenPak::open(pak_path).await?);
+        let packages: Vec<(String, String)> = pak
+            .pak()
+            .entries
+            .iter()
+            .filter(|entry| {
+                let lower = entry.filename.to_lowercase();
+                !entry.is_encrypted && (lower.ends_with(".uasset") || lower.ends_with(".umap"))
+            })
+            .filter_map(|entry| {
+                let package = crate::asset_registry::package_name_for_path(&entry.filename)?;
+                Some((package, entry.filename.clone()))
+            })
+            .collect();
+
+        collect_references(packages, concurrency, on_progress, cancel, |(package, filename)| {
+            let pak = pak.clone();
+            async move {
+                let references = match pak.extract_package(&filename).await {
+                    Ok(data) => extract_package_references(&data),
+                    Err(e) => Err(e),
+                };
+                match references {
+                    Ok(references) => Some((package, references)),
+                    Err(e) => {
+                        tracing::debug!("No references for {}: {:#}", filename, e);
+                        None
+                    }
+                }
+            }
+        })
+        .await
+    }
+
     /// Merges multiple dependency maps
     pub fn merge_dependency_maps(maps: Vec<DependencyMap>) -> DependencyMap {
         let mut merged = DependencyMap::new();
 
-        for map in maps {
-            for (asset, deps) in map.dependencies {
-                for dep in deps {
-                    merged.add_dependency(&asset, &dep);
-                }
-            }
+        for map in &maps {
+            merged.merge(map);
         }
 
         merged.optimize();
@@ -564,7 +2415,7 @@ pub mod utils {
 
             if asset_matches {
                 for dep in deps {
-                    filtered.add_dependency(asset, dep);
+                    filtered.add_typed_dependency(asset, dep, map.dependency_kind(asset, dep));
                 }
             }
         }
@@ -572,16 +2423,19 @@ pub mod utils {
         filtered
     }
 
-    /// Generates a dependency report in markdown format
+    /// Generates a dependency report in markdown format, with a storage section when
+    /// `storage` is given
     pub fn generate_markdown_report(
         map: &DependencyMap,
         all_assets: &[String],
+        storage: Option<&crate::statistics::StorageReport>,
     ) -> String {
         let stats = map.generate_statistics(all_assets);
         
         let mut report = String::from("# Asset Dependency Report\n\n");
         
         report.push_str(&format!("- **Total Dependencies**: {}\n", stats.total_dependencies));
+        report.push_str(&format!("- **Soft References**: {}\n", stats.soft_dependencies));
         report.push_str(&format!("- **Maximum Depth**: {}\n", stats.max_depth));
         report.push_str(&format!("- **Circular References**: {}\n", stats.circular_references.len()));
         report.push_str(&format!("- **Orphaned Assets**: {}\n\n", stats.orphaned_assets.len()));
@@ -594,10 +2448,50 @@ pub mod utils {
             report.push('\n');
         }
 
+        if !stats.metrics.hubs.is_empty() {
+            report.push_str(&format!(
+                "## Hub Assets\n\nAverage dependencies per asset: {:.2}\n\n",
+                stats.metrics.average_dependencies
+            ));
+            for (asset, degree) in &stats.metrics.hubs {
+                report.push_str(&format!("- **{}**: {} edges\n", asset, degree));
+            }
+            report.push('\n');
+        }
+
+        if !stats.metrics.folders.is_empty() {
+            report.push_str("## Folders\n\n");
+            report.push_str("| Folder | Assets | Internal Edges | Outgoing Edges | Incoming Edges |\n");
+            report.push_str("|---|---|---|---|---|\n");
+            for folder in &stats.metrics.folders {
+                let name = if folder.folder.is_empty() { "(no folder)" } else { folder.folder.as_str() };
+                report.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    name, folder.assets, folder.internal_edges, folder.outgoing_edges, folder.incoming_edges
+                ));
+            }
+            report.push('\n');
+        }
+
+        if stats.containers.iter().any(|c| !c.depends_on.is_empty()) {
+            report.push_str("## Paks\n\n");
+            for container in &stats.containers {
+                for dependency in &container.depends_on {
+                    report.push_str(&format!(
+                        "- {} depends on {} assets from {}\n",
+                        pak_label(&container.pak),
+                        dependency.assets,
+                        pak_label(&dependency.pak)
+                    ));
+                }
+            }
+            report.push('\n');
+        }
+
         if !stats.circular_references.is_empty() {
             report.push_str("## Circular Dependencies\n\n");
             for cycle in &stats.circular_references {
-                report.push_str(&format!("- {}\n", cycle.join(" → ")));
+                report.push_str(&format!("- {}\n", format_cycle(cycle)));
             }
             report.push('\n');
         }
@@ -607,8 +2501,391 @@ pub mod utils {
             for asset in &stats.orphaned_assets {
                 report.push_str(&format!("- {}\n", asset));
             }
+            report.push('\n');
+        }
+
+        // Without an asset list every reference would count as missing
+        if !all_assets.is_empty() {
+            let known: HashSet<String> = all_assets.iter().cloned().collect();
+            let issues = map.validate_references(&known, &HashSet::new());
+            if !issues.is_empty() {
+                report.push_str("## Missing References\n\n");
+                for issue in &issues {
+                    report.push_str(&format!("- {}\n", issue));
+                }
+                report.push('\n');
+            }
+        }
+
+        if let Some(storage) = storage {
+            report.push_str(&crate::statistics::storage_markdown(storage));
         }
 
         report
     }
-}
\ No newline at end of file
+}
+/// Comparing the dependency graphs of two builds
+pub mod diff {
+    use super::*;
+
+    /// Nodes and edges that appeared or disappeared between two graphs
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    pub struct DependencyDiff {
+        pub added_nodes: Vec<String>,
+        pub removed_nodes: Vec<String>,
+        /// (asset, dependency) pairs
+        pub added_edges: Vec<(String, String)>,
+        pub removed_edges: Vec<(String, String)>,
+        /// Assets in both graphs whose number of direct dependencies changed
+        pub changed_assets: Vec<DependencyCountChange>,
+        pub totals: DiffTotals,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct DependencyCountChange {
+        pub asset: String,
+        pub old_count: usize,
+        pub new_count: usize,
+    }
+
+    #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+    pub struct DiffTotals {
+        pub added_nodes: usize,
+        pub removed_nodes: usize,
+        pub added_edges: usize,
+        pub removed_edges: usize,
+        pub changed_assets: usize,
+    }
+
+    /// Compares two graphs; every list in the result is sorted
+    pub fn compare(old: &DependencyMap, new: &DependencyMap) -> DependencyDiff {
+        let old_nodes: HashSet<&str> = old.all_assets().into_iter().collect();
+        let new_nodes: HashSet<&str> = new.all_assets().into_iter().collect();
+        let old_edges = edge_set(old);
+        let new_edges = edge_set(new);
+
+        let added_nodes = sorted(new_nodes.difference(&old_nodes).map(|n| n.to_string()));
+        let removed_nodes = sorted(old_nodes.difference(&new_nodes).map(|n| n.to_string()));
+        let added_edges = sorted(new_edges.difference(&old_edges).map(|(a, d)| (a.to_string(), d.to_string())));
+        let removed_edges = sorted(old_edges.difference(&new_edges).map(|(a, d)| (a.to_string(), d.to_string())));
+
+        let count = |map: &DependencyMap, asset: &str| {
+            map.dependencies
+                .get(asset)
+                .map_or(0, |deps| deps.iter().collect::<HashSet<_>>().len())
+        };
+        let mut changed_assets: Vec<DependencyCountChange> = old_nodes
+            .intersection(&new_nodes)
+            .filter_map(|&asset| {
+                let (old_count, new_count) = (count(old, asset), count(new, asset));
+                (old_count != new_count).then(|| DependencyCountChange {
+                    asset: asset.to_string(),
+                    old_count,
+                    new_count,
+                })
+            })
+            .collect();
+        changed_assets.sort_by(|a, b| a.asset.cmp(&b.asset));
+
+        let totals = DiffTotals {
+            added_nodes: added_nodes.len(),
+            removed_nodes: removed_nodes.len(),
+            added_edges: added_edges.len(),
+            removed_edges: removed_edges.len(),
+            changed_assets: changed_assets.len(),
+        };
+        DependencyDiff {
+            added_nodes,
+            removed_nodes,
+            added_edges,
+            removed_edges,
+            changed_assets,
+            totals,
+        }
+    }
+
+    fn edge_set(map: &DependencyMap) -> HashSet<(&str, &str)> {
+        map.dependencies
+            .iter()
+            .flat_map(|(asset, deps)| deps.iter().map(move |dep| (asset.as_str(), dep.as_str())))
+            .collect()
+    }
+
+    fn sorted<T: Ord>(items: impl Iterator<Item = T>) -> Vec<T> {
+        let mut items: Vec<T> = items.collect();
+        items.sort_unstable();
+        items
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_util::sync::CancellationToken;
+
+    /// A chain of `len` assets where each depends on the next
+    fn chain(len: usize) -> DependencyMap {
+        let mut map = DependencyMap::new();
+        for i in 1..len {
+            map.add_dependency(&format!("A{:06}", i - 1), &format!("A{:06}", i));
+        }
+        map
+    }
+
+    #[test]
+    fn long_chain_has_no_cycles() {
+        let map = chain(100_000);
+        assert!(map.detect_circular_dependencies().is_empty());
+        assert_eq!(map.topological_sort().map(|order| order.len()), Ok(100_000));
+    }
+
+    #[test]
+    fn closed_chain_is_one_component() {
+        let mut map = chain(100_000);
+        map.add_dependency("A099999", "A000000");
+        let cycles = map.detect_circular_dependencies();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), 100_000);
+        assert!(cycles[0].windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn diamond_sorts_without_false_cycle() {
+        // Top -> Left, Right; Left, Right -> Bottom; Bottom -> Leaf, which only appears as a dependency
+        let mut map = DependencyMap::new();
+        map.add_dependency("Top", "Left");
+        map.add_dependency("Top", "Right");
+        map.add_dependency("Left", "Bottom");
+        map.add_dependency("Right", "Bottom");
+        map.add_dependency("Bottom", "Leaf");
+
+        assert!(map.detect_circular_dependencies().is_empty());
+        let order = map.topological_sort().expect("a diamond is a DAG");
+        assert_eq!(order, vec!["Leaf", "Bottom", "Left", "Right", "Top"]);
+
+        let position = |asset: &str| order.iter().position(|a| a == asset).unwrap();
+        for (asset, deps) in &map.dependencies {
+            for dep in deps {
+                assert!(position(dep) < position(asset), "{} must come before {}", dep, asset);
+            }
+        }
+    }
+
+    #[test]
+    fn cycle_fails_topological_sort() {
+        let mut map = DependencyMap::new();
+        map.add_dependency("Root", "A");
+        map.add_dependency("A", "B");
+        map.add_dependency("B", "A");
+
+        assert_eq!(map.topological_sort(), Err(vec![vec!["A".to_string(), "B".to_string()]]));
+        assert!(map.export_to_format("order").is_err());
+    }
+
+    /// A fresh folder of `count` small files, cycling through names the placeholder
+    /// extractor gives different references
+    fn fixture_dir(count: usize) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("pakseek-deps-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 0..count {
+            let stem = ["Character", "Material", "Audio", "Prop"][i % 4];
+            std::fs::write(dir.join(format!("{}_{}.uasset", stem, i)), [i as u8; 16]).unwrap();
+        }
+        std::fs::write(dir.join("Readme.txt"), "not an asset").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn parallel_scan_matches_serial() {
+        let dir = fixture_dir(1000);
+        let path = dir.to_str().unwrap();
+        let cancel = CancellationToken::new();
+
+        let serial = utils::scan_directory_with_progress(path, 1, |_, _| {}, &cancel).await.unwrap();
+        let mut progress = Vec::new();
+        let parallel = utils::scan_directory_with_progress(path, 16, |done, total| progress.push((done, total)), &cancel)
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(serial.dependencies.len(), 1000);
+        assert_eq!(serde_json::to_value(&serial).unwrap(), serde_json::to_value(&parallel).unwrap());
+        assert_eq!(progress.len(), 1000);
+        assert!(progress.iter().enumerate().all(|(i, &(done, total))| done == i + 1 && total == 1000));
+    }
+
+    #[tokio::test]
+    async fn cancelled_scan_fails() {
+        let dir = fixture_dir(50);
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = utils::scan_directory_for_dependencies(dir.to_str().unwrap(), &cancel).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(result.unwrap_err().is::<crate::error::Cancelled>());
+    }
+
+    /// Edges and nodes one pak would contribute
+    fn pak_edges(edges: &[(&str, &str, DependencyKind)], nodes: &[&str]) -> DependencyMap {
+        let mut map = DependencyMap::new();
+        for &(asset, dep, kind) in edges {
+            map.add_typed_dependency(asset, dep, kind);
+        }
+        for &asset in nodes {
+            map.nodes.insert(asset.to_string(), AssetNode { size: Some(1), ..Default::default() });
+        }
+        map
+    }
+
+    #[test]
+    fn readding_a_pak_equals_full_rebuild() {
+        let base = pak_edges(
+            &[("/Game/Hero", "/Game/HeroMat", DependencyKind::Hard), ("/Game/Hero", "/Game/Sword", DependencyKind::Soft)],
+            &["/Game/Hero", "/Game/HeroMat"],
+        );
+        let patch = pak_edges(
+            &[
+                ("/Game/Hero", "/Game/Sword", DependencyKind::Hard),
+                ("/Game/Hero", "/Game/Cape", DependencyKind::Hard),
+                ("/Game/Cape", "/Game/CapeMat", DependencyKind::Soft),
+            ],
+            &["/Game/Cape"],
+        );
+
+        let mut rebuilt = DependencyMap::new();
+        rebuilt.add_assets_from("pakchunk0.pak", &base);
+        rebuilt.add_assets_from("pakchunk0_P.pak", &patch);
+        assert_eq!(rebuilt.dependency_kind("/Game/Hero", "/Game/Sword"), DependencyKind::Hard);
+
+        let mut updated = rebuilt.clone();
+        assert_eq!(updated.remove_assets_from("pakchunk0_P.pak"), 2);
+        assert_eq!(updated.dependency_kind("/Game/Hero", "/Game/Sword"), DependencyKind::Soft);
+        assert!(!updated.nodes.contains_key("/Game/Cape"));
+        updated.add_assets_from("pakchunk0_P.pak", &patch);
+        assert_eq!(serde_json::to_value(&updated).unwrap(), serde_json::to_value(&rebuilt).unwrap());
+
+        // The other order of paks builds the same map
+        let mut reversed = DependencyMap::new();
+        reversed.add_assets_from("pakchunk0_P.pak", &patch);
+        reversed.add_assets_from("pakchunk0.pak", &base);
+        assert_eq!(serde_json::to_value(&reversed).unwrap(), serde_json::to_value(&rebuilt).unwrap());
+    }
+
+    #[test]
+    fn provenance_survives_serialization() {
+        let mut map = DependencyMap::new();
+        map.add_assets_from("pakchunk1.pak", &pak_edges(&[("/Game/A", "/Game/B", DependencyKind::Hard)], &[]));
+        let mut restored: DependencyMap = serde_json::from_str(&serde_json::to_string(&map).unwrap()).unwrap();
+        assert_eq!(restored.remove_assets_from("pakchunk1.pak"), 1);
+        assert!(restored.dependencies.get("/Game/A").is_none_or(Vec::is_empty));
+    }
+
+    /// Checks a Mermaid export line by line, returning its node ids and labels
+    fn check_mermaid(mermaid: &str) -> (HashMap<String, String>, Vec<String>) {
+        let node = regex::Regex::new(r#"^    (n\d+|more)\["([^"]*)"\]$"#).unwrap();
+        let edge = regex::Regex::new(r"^    (n\d+) (-->\|hard\||-\.->\|soft\|) (n\d+)$").unwrap();
+        let truncation = regex::Regex::new(r"^    (n\d+) -\.- more$").unwrap();
+
+        let mut lines = mermaid.lines();
+        assert_eq!(lines.next(), Some("graph LR"));
+        let mut nodes = HashMap::new();
+        let mut edges = Vec::new();
+        for line in lines {
+            if let Some(caps) = node.captures(line) {
+                assert!(nodes.insert(caps[1].to_string(), caps[2].to_string()).is_none(), "duplicate id in {}", line);
+            } else if let Some(caps) = edge.captures(line) {
+                for id in [&caps[1], &caps[3]] {
+                    assert!(nodes.contains_key(id), "undeclared id in {}", line);
+                }
+                edges.push(caps[2].to_string());
+            } else if let Some(caps) = truncation.captures(line) {
+                assert!(nodes.contains_key(&caps[1]) && nodes.contains_key("more"), "undeclared id in {}", line);
+            } else {
+                panic!("unexpected line {:?}", line);
+            }
+        }
+        (nodes, edges)
+    }
+
+    #[test]
+    fn mermaid_export_is_valid() {
+        let mut map = DependencyMap::new();
+        map.add_dependency("/Game/Maps/Arena Level", "/Game/Props/Crate \"Big\"");
+        map.add_typed_dependency("/Game/Maps/Arena Level", "/Game/UI/[Menu]|Main", DependencyKind::Soft);
+        map.add_dependency("/Game/Props/Crate \"Big\"", "/Game/Props/Crate_Mat");
+
+        let mermaid = map.export_mermaid("/Game/Maps/Arena Level", u32::MAX, 100);
+        let (nodes, edges) = check_mermaid(&mermaid);
+        let mut labels: Vec<&str> = nodes.values().map(String::as_str).collect();
+        labels.sort();
+        assert_eq!(
+            labels,
+            vec!["/Game/Maps/Arena Level", "/Game/Props/Crate #quot;Big#quot;", "/Game/Props/Crate_Mat", "/Game/UI/[Menu]|Main"]
+        );
+        let mut edges: Vec<&str> = edges.iter().map(String::as_str).collect();
+        edges.sort();
+        assert_eq!(edges, vec!["-->|hard|", "-->|hard|", "-.->|soft|"]);
+    }
+
+    #[test]
+    fn mermaid_export_truncates_to_more_node() {
+        let mut map = DependencyMap::new();
+        for i in 0..10 {
+            map.add_dependency("Root", &format!("Dep{}", i));
+        }
+
+        let (nodes, edges) = check_mermaid(&map.export_mermaid("Root", u32::MAX, 4));
+        assert_eq!(nodes.len(), 5);
+        assert_eq!(nodes["more"], "…7 more");
+        assert_eq!(edges.len(), 3);
+        assert!(map.export_mermaid("Root", u32::MAX, 4).ends_with("    n0 -.- more\n"));
+    }
+
+    #[test]
+    fn cycles_print_as_member_sets() {
+        let mut map = DependencyMap::new();
+        map.add_dependency("C", "A");
+        map.add_dependency("A", "B");
+        map.add_dependency("B", "C");
+        map.add_dependency("S", "S");
+
+        let cycles = map.detect_circular_dependencies();
+        assert_eq!(cycles, vec![vec!["A".to_string(), "B".to_string(), "C".to_string()], vec!["S".to_string()]]);
+        assert_eq!(format_cycle(&cycles[0]), "{A, B, C}");
+        assert_eq!(format_cycle(&cycles[1]), "S -> S");
+
+        let messages: Vec<String> = map.validate().iter().map(ToString::to_string).collect();
+        assert!(messages.contains(&"Circular dependency among {A, B, C}".to_string()));
+    }
+
+    #[test]
+    fn yaml_round_trips_edge_kinds_and_special_keys() {
+        let mut map = DependencyMap::new();
+        map.add_dependency("/Game/Maps/Arena: Night", "/Game/Props/#Crate");
+        map.add_typed_dependency("/Game/Maps/Arena: Night", "/Game/UI/Icon #2", DependencyKind::Soft);
+        map.add_typed_dependency("#Startup", "/Game/Maps/Arena: Night", DependencyKind::Soft);
+        map.dependencies.insert("/Game/Props/#Crate".to_string(), Vec::new());
+
+        let yaml = map.export_to_format("yaml").unwrap();
+        // ": " and " #" (or a leading "#") would end a plain scalar, so those paths are quoted
+        let lines: Vec<&str> = yaml.lines().collect();
+        for line in ["'#Startup':", "'/Game/Maps/Arena: Night':", "- soft: '/Game/UI/Icon #2'", "- /Game/Props/#Crate"] {
+            assert!(lines.contains(&line), "{:?} missing from\n{}", line, yaml);
+        }
+
+        let loaded = DependencyMap::import_from_yaml(&yaml).unwrap();
+        let mut expected: Vec<_> = map.dependencies.iter().collect();
+        let mut actual: Vec<_> = loaded.dependencies.iter().collect();
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+        assert_eq!(loaded.edge_kinds, map.edge_kinds);
+        assert_eq!(loaded.dependency_kind("#Startup", "/Game/Maps/Arena: Night"), DependencyKind::Soft);
+        assert_eq!(loaded.dependency_kind("/Game/Maps/Arena: Night", "/Game/Props/#Crate"), DependencyKind::Hard);
+
+        // Maps written before soft edges were tagged still load, as hard edges
+        let legacy = DependencyMap::import_from_yaml("/Game/A:\n- /Game/B\n").unwrap();
+        assert_eq!(legacy.dependencies["/Game/A"], ["/Game/B"]);
+        assert!(legacy.edge_kinds.is_empty());
+    }
+}
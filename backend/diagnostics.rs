@@ -0,0 +1,136 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::preview_cache::CacheStats;
+
+/// Operation timings kept for the diagnostics
+pub const MAX_TIMINGS: usize = 50;
+
+/// Lines of the log file included by `export_diagnostics`
+pub const MAX_LOG_LINES: usize = 500;
+
+/// What users are asked to capture when something is slow or uses too much memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostics {
+    pub generated_at: DateTime<Utc>,
+    pub version: String,
+    /// `std::env::consts::OS` and `ARCH`
+    pub platform: String,
+    /// Game profile in use, if one was activated
+    pub active_profile: Option<String>,
+    pub uptime_secs: u64,
+    /// Resident memory of the process, where the platform reports it
+    pub rss_bytes: Option<u64>,
+    pub virtual_bytes: Option<u64>,
+    pub open_pak_handles: usize,
+    /// The pak of each open handle
+    pub open_paks: Vec<String>,
+    /// Pak indexes read from their files since startup
+    pub pak_index_reads: usize,
+    /// Pak indexes loaded from the index cache instead
+    pub pak_index_cache_hits: usize,
+    pub preview_cache: CacheStats,
+    /// In-memory structures, largest first
+    pub structures: Vec<StructureSize>,
+    /// The last `MAX_TIMINGS` scans, previews and extractions, newest first
+    pub timings: Vec<OperationTiming>,
+}
+
+/// How much an in-memory structure holds, and roughly how much memory that takes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructureSize {
+    pub name: String,
+    pub count: usize,
+    /// `count` times the size of one item; strings and other heap data aren't counted
+    pub approx_bytes: u64,
+}
+
+impl StructureSize {
+    pub fn of<T>(name: &str, count: usize) -> Self {
+        StructureSize { name: name.to_string(), count, approx_bytes: (count * std::mem::size_of::<T>()) as u64 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Scan,
+    Preview,
+    Extract,
+}
+
+/// How long one operation took
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationTiming {
+    pub operation: Operation,
+    /// The folder scanned, or the asset previewed or extracted
+    pub target: String,
+    pub duration_ms: u64,
+    pub finished_at: DateTime<Utc>,
+    /// False when it failed or was cancelled
+    pub ok: bool,
+}
+
+static TIMINGS: Mutex<VecDeque<OperationTiming>> = Mutex::new(VecDeque::new());
+
+/// Records an operation that just finished, dropping the oldest beyond `MAX_TIMINGS`
+pub fn record(operation: Operation, target: &str, duration: Duration, ok: bool) {
+    let timing = OperationTiming {
+        operation,
+        target: target.to_string(),
+        duration_ms: duration.as_millis() as u64,
+        finished_at: Utc::now(),
+        ok,
+    };
+    let mut timings = TIMINGS.lock().unwrap();
+    if timings.len() == MAX_TIMINGS {
+        timings.pop_back();
+    }
+    timings.push_front(timing);
+}
+
+/// The recorded timings, newest first
+pub fn timings() -> Vec<OperationTiming> {
+    TIMINGS.lock().unwrap().iter().cloned().collect()
+}
+
+/// Writes a zip to attach to bug reports: `diagnostics.json` and `recent.log`; returns its size
+///
+/// The zip is written next to `destination` and moved over it once complete.
+pub fn write_bundle(diagnostics: &Diagnostics, destination: &Path) -> Result<u64> {
+    if let Some(dir) = destination.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut part = destination.as_os_str().to_owned();
+    part.push(".part");
+    let part = std::path::PathBuf::from(part);
+
+    let written = (|| {
+        let file = std::fs::File::create(&part).with_context(|| format!("Failed to create {}", part.display()))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file("diagnostics.json", options)?;
+        zip.write_all(&serde_json::to_vec_pretty(diagnostics)?)?;
+        zip.start_file("recent.log", options)?;
+        let lines = crate::logging::recent_lines(MAX_LOG_LINES).unwrap_or_else(|e| vec![format!("No log lines: {:#}", e)]);
+        for line in lines {
+            zip.write_all(line.as_bytes())?;
+            zip.write_all(b"\n")?;
+        }
+        zip.finish()?;
+        anyhow::Ok(())
+    })();
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&part);
+        return Err(e.context(format!("Failed to write {}", destination.display())));
+    }
+    let bytes = std::fs::metadata(&part)?.len();
+    std::fs::rename(&part, destination).with_context(|| format!("Failed to replace {}", destination.display()))?;
+    Ok(bytes)
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::preview::Asset;
+
+/// Assets with the same size and SHA-1, of which all but one copy could go
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DuplicateGroup {
+    /// Uncompressed size of each copy
+    pub size: u64,
+    /// Lowercase hex
+    pub sha1: String,
+    /// Stored bytes of every copy but the smallest
+    pub reclaimable_bytes: u64,
+    pub members: Vec<DuplicateMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DuplicateMember {
+    pub path: String,
+    pub pak_file: Option<String>,
+    pub compressed_size: Option<u64>,
+}
+
+/// What `find_duplicates` and `GET /duplicates` return
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DuplicateReport {
+    /// Most reclaimable bytes first
+    pub groups: Vec<DuplicateGroup>,
+    pub reclaimable_bytes: u64,
+    /// Entries whose content was read to hash them, for lack of a hash in the index
+    pub hashed_on_demand: usize,
+    pub bytes_read: u64,
+    /// Same-size entries left out: no hash in the index and not read (budget spent,
+    /// encrypted, or unreadable)
+    pub unhashed: usize,
+}
+
+/// An asset sharing its size with another, copied out of the listing for grouping
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub path: String,
+    pub pak_file: Option<String>,
+    pub size: u64,
+    pub compressed_size: Option<u64>,
+    pub sha1: Option<String>,
+}
+
+/// The SHA-1 recorded for an asset, as lowercase hex; None unless it's a full 40-digit hash
+pub fn stored_sha1(asset: &Asset) -> Option<String> {
+    asset.hash.as_deref().and_then(crate::pak_parser::parse_sha1).map(|hash| crate::pak_parser::to_hex(&hash))
+}
+
+/// The assets of at least `min_size` bytes that share their size with another one
+///
+/// Only these are copied, so checking a large listing with few candidates stays cheap.
+pub fn same_size_candidates(assets: &[Arc<Asset>], min_size: u64) -> Vec<Candidate> {
+    let mut by_size: HashMap<u64, Vec<&Asset>> = HashMap::new();
+    for asset in assets.iter().filter(|asset| asset.size > 0 && asset.size >= min_size) {
+        by_size.entry(asset.size).or_default().push(asset);
+    }
+    by_size
+        .into_values()
+        .filter(|same_size| same_size.len() > 1)
+        .flatten()
+        .map(|asset| Candidate {
+            path: asset.path.clone(),
+            pak_file: asset.pak_file.as_deref().map(str::to_string),
+            size: asset.size,
+            compressed_size: asset.compressed_size,
+            sha1: stored_sha1(asset),
+        })
+        .collect()
+}
+
+/// Groups hashed candidates by size and SHA-1, keeping groups of two or more, most
+/// reclaimable bytes first
+pub fn group(candidates: Vec<Candidate>) -> Vec<DuplicateGroup> {
+    let mut by_content: HashMap<(u64, String), Vec<Candidate>> = HashMap::new();
+    for candidate in candidates {
+        if let Some(sha1) = candidate.sha1.clone() {
+            by_content.entry((candidate.size, sha1)).or_default().push(candidate);
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_content
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|((size, sha1), mut members)| {
+            members.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.pak_file.cmp(&b.pak_file)));
+            let stored: Vec<u64> = members.iter().map(|member| member.compressed_size.unwrap_or(size)).collect();
+            let reclaimable_bytes = stored.iter().sum::<u64>() - stored.iter().min().copied().unwrap_or(0);
+            let members = members
+                .into_iter()
+                .map(|member| DuplicateMember { path: member.path, pak_file: member.pak_file, compressed_size: member.compressed_size })
+                .collect();
+            DuplicateGroup { size, sha1, reclaimable_bytes, members }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.reclaimable_bytes.cmp(&a.reclaimable_bytes).then_with(|| a.sha1.cmp(&b.sha1)));
+    groups
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::api_error::{ApiError, ErrorCode};
+use crate::error::{ContainerError, PakError, PakSeekError};
+use crate::pak_parser::{self, PakParser};
+use crate::settings::{self, Settings};
+use crate::utoc_parser::{self, UtocUcasParser};
+
+/// What kind of container an audited file is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerKind {
+    Pak,
+    /// A .utoc/.ucas (IoStore) container
+    Utoc,
+}
+
+/// How much of a container is encrypted, as far as its footer or TOC header tells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptionStatus {
+    None,
+    /// Nothing can be listed without the key
+    EncryptedIndex,
+    /// The index is readable, but the footer names a key GUID, so some entries are
+    /// encrypted with that key
+    EncryptedEntries,
+}
+
+/// One container of the audited folder
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ContainerAudit {
+    pub path: String,
+    pub kind: ContainerKind,
+    /// None when the container couldn't be read; `error` says why
+    pub status: Option<EncryptionStatus>,
+    /// GUID of the key the container needs; None for the default key
+    pub encryption_key_guid: Option<String>,
+    /// Whether a key applies: the container's own, its GUID's or the default one
+    pub key_configured: bool,
+    /// Whether that key decrypts the index (for paks, its SHA-1 then matches the footer's);
+    /// None without a key, or when the index isn't encrypted and there's nothing to check
+    pub key_valid: Option<bool>,
+    pub error: Option<ApiError>,
+}
+
+impl ContainerAudit {
+    /// Encrypted, and without a key known to work
+    pub fn is_locked(&self) -> bool {
+        match self.status {
+            Some(EncryptionStatus::EncryptedIndex) => self.key_valid != Some(true),
+            Some(EncryptionStatus::EncryptedEntries) => !self.key_configured,
+            Some(EncryptionStatus::None) | None => false,
+        }
+    }
+}
+
+/// A key GUID the audited containers need
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct KeyGuidAudit {
+    /// None for the default key
+    pub guid: Option<String>,
+    /// Encrypted containers needing it
+    pub containers: usize,
+    /// Of those, the ones without a key that works
+    pub locked: usize,
+}
+
+/// What in a folder needs keys, from `audit_encryption`
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct EncryptionAudit {
+    pub folder: String,
+    /// By path
+    pub containers: Vec<ContainerAudit>,
+    /// Containers with an encrypted index or encrypted entries
+    pub encrypted: usize,
+    /// Encrypted containers without a key known to work
+    pub locked: usize,
+    /// Containers that couldn't be read
+    pub unreadable: usize,
+    /// Key GUIDs of the encrypted containers, the default key last
+    pub key_guids: Vec<KeyGuidAudit>,
+}
+
+/// The .pak and .utoc files of `folder`, or `folder` itself when it's one
+async fn containers(folder: &str) -> Result<Vec<(String, ContainerKind)>, ApiError> {
+    let path = Path::new(folder);
+    let context = json!({ "folder": folder });
+    let kind = |path: &str| match path.to_ascii_lowercase().rsplit_once('.').map(|(_, extension)| extension.to_string()) {
+        Some(extension) if extension == "pak" => Some(ContainerKind::Pak),
+        Some(extension) if extension == "utoc" || extension == "ucas" => Some(ContainerKind::Utoc),
+        _ => None,
+    };
+    if path.is_file() {
+        return match kind(folder) {
+            Some(ContainerKind::Utoc) => {
+                Ok(vec![(path.with_extension("utoc").to_string_lossy().into_owned(), ContainerKind::Utoc)])
+            }
+            Some(kind) => Ok(vec![(folder.to_string(), kind)]),
+            None => Err(ApiError::new(ErrorCode::InvalidRequest, format!("{} is not a .pak or .utoc", folder)).with_context(context)),
+        };
+    }
+    if !path.is_dir() {
+        return Err(ApiError::new(ErrorCode::InvalidRequest, format!("Path does not exist: {}", folder)).with_context(context));
+    }
+
+    let io_error = |e: anyhow::Error| ApiError::new(ErrorCode::IoFailed, format!("Failed to list {}: {:#}", folder, e));
+    let mut found: Vec<(String, ContainerKind)> = pak_parser::utils::find_pak_files(folder)
+        .await
+        .map_err(io_error)?
+        .into_iter()
+        .map(|pak| (pak, ContainerKind::Pak))
+        .collect();
+    found.extend(
+        utoc_parser::utils::find_utoc_ucas_pairs(folder)
+            .await
+            .map_err(io_error)?
+            .into_iter()
+            .map(|(utoc, _)| (utoc, ContainerKind::Utoc)),
+    );
+    if found.is_empty() {
+        return Err(ApiError::new(ErrorCode::NoPaksFound, format!("No .pak or .utoc files found in {}", folder)).with_context(context));
+    }
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(found)
+}
+
+/// Reads the footer (or TOC header) of every container in `folder` and checks the keys
+/// `settings` has for the encrypted ones
+///
+/// Only encrypted indexes are decrypted, to test the key; entries aren't read. A container
+/// that can't be read is listed with its error and the others are still audited.
+pub async fn audit(folder: &str, settings: &Settings) -> Result<EncryptionAudit, ApiError> {
+    let mut audited = Vec::new();
+    for (path, kind) in containers(folder).await? {
+        audited.push(audit_container(path, kind, settings).await);
+    }
+
+    let mut key_guids: BTreeMap<(bool, Option<String>), KeyGuidAudit> = BTreeMap::new();
+    for container in audited.iter().filter(|container| container.status.is_some_and(|status| status != EncryptionStatus::None)) {
+        let guid = container.encryption_key_guid.clone();
+        let entry = key_guids
+            .entry((guid.is_none(), guid.clone()))
+            .or_insert_with(|| KeyGuidAudit { guid, containers: 0, locked: 0 });
+        entry.containers += 1;
+        if container.is_locked() {
+            entry.locked += 1;
+        }
+    }
+
+    Ok(EncryptionAudit {
+        folder: folder.to_string(),
+        encrypted: key_guids.values().map(|guid| guid.containers).sum(),
+        locked: audited.iter().filter(|container| container.is_locked()).count(),
+        unreadable: audited.iter().filter(|container| container.status.is_none()).count(),
+        key_guids: key_guids.into_values().collect(),
+        containers: audited,
+    })
+}
+
+async fn audit_container(path: String, kind: ContainerKind, settings: &Settings) -> ContainerAudit {
+    let mut audit = ContainerAudit {
+        path,
+        kind,
+        status: None,
+        encryption_key_guid: None,
+        key_configured: false,
+        key_valid: None,
+        error: None,
+    };
+    let path = audit.path.as_str();
+    let probed = match kind {
+        ContainerKind::Pak => {
+            let parser = PakParser::new(path);
+            match parser.info().await {
+                Ok(info) => {
+                    let status = if info.encrypted_index {
+                        EncryptionStatus::EncryptedIndex
+                    } else if info.encryption_key_guid.is_some() {
+                        EncryptionStatus::EncryptedEntries
+                    } else {
+                        EncryptionStatus::None
+                    };
+                    Ok((status, info.encryption_key_guid))
+                }
+                Err(e) => Err(PakSeekError::from(PakError::open(path, &e)).to_api_error()),
+            }
+        }
+        ContainerKind::Utoc => {
+            let info = async { UtocUcasParser::new(path)?.info().await }.await;
+            match info {
+                Ok(info) if info.encrypted => Ok((EncryptionStatus::EncryptedIndex, info.encryption_key_guid)),
+                Ok(info) => Ok((EncryptionStatus::None, info.encryption_key_guid)),
+                Err(e) => Err(PakSeekError::from(ContainerError::open(path, &e)).to_api_error()),
+            }
+        }
+    };
+    let (status, guid) = match probed {
+        Ok(probed) => probed,
+        Err(error) => {
+            audit.error = Some(error);
+            return audit;
+        }
+    };
+    audit.status = Some(status);
+    audit.encryption_key_guid = guid;
+    if status == EncryptionStatus::None {
+        return audit;
+    }
+
+    let key = settings.key_for(path, audit.encryption_key_guid.as_deref()).and_then(settings::parse_key);
+    audit.key_configured = key.is_some();
+    let Some(key) = key.filter(|_| status == EncryptionStatus::EncryptedIndex) else {
+        return audit;
+    };
+    let tested = match kind {
+        ContainerKind::Pak => PakParser::new(path).test_key(&key).await,
+        ContainerKind::Utoc => async { UtocUcasParser::new(path)?.test_key(&key).await }.await,
+    };
+    match tested {
+        Ok(valid) => audit.key_valid = Some(valid),
+        Err(e) => {
+            audit.error = Some(
+                ApiError::new(ErrorCode::PakReadFailed, format!("Failed to check the key for {}: {:#}", path, e))
+                    .with_context(json!({ "pak_file": path })),
+            );
+        }
+    }
+    audit
+}
@@ -0,0 +1,327 @@
+use axum::response::{IntoResponse, Json, Response};
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use tokio_util::sync::CancellationToken;
+
+use crate::api_error::{ApiError, ErrorCode, ErrorResponse};
+
+/// A .pak that couldn't be read, or an entry of one that can't be extracted
+#[derive(Debug, thiserror::Error)]
+pub enum PakError {
+    #[error("Pak not found: {pak_file}")]
+    NotFound { pak_file: String },
+    #[error("{message}")]
+    Read { pak_file: String, message: String },
+    #[error("Entry not found in {pak_file}")]
+    EntryNotFound { pak_file: String, path: String },
+    #[error("Entry is encrypted in {pak_file} and no decryption key is configured")]
+    EntryEncrypted { pak_file: String, path: String },
+    #[error("Entry is compressed with {method}, which can't be decompressed")]
+    DecompressorUnavailable { pak_file: String, path: String, method: String },
+    #[error("Failed to extract: {source:#}")]
+    Extract { pak_file: String, path: String, source: anyhow::Error },
+}
+
+impl PakError {
+    /// A pak that couldn't be opened or parsed: `NotFound` when the file is missing
+    pub fn open(pak_file: &str, error: &anyhow::Error) -> Self {
+        let pak_file = pak_file.to_string();
+        if is_not_found(error) {
+            PakError::NotFound { pak_file }
+        } else {
+            PakError::Read { pak_file, message: format!("{:#}", error) }
+        }
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            PakError::NotFound { .. } => ErrorCode::PakNotFound,
+            PakError::Read { .. } => ErrorCode::PakReadFailed,
+            PakError::EntryNotFound { .. } => ErrorCode::EntryNotFound,
+            PakError::EntryEncrypted { .. } => ErrorCode::EntryEncrypted,
+            PakError::DecompressorUnavailable { .. } => ErrorCode::DecompressorUnavailable,
+            PakError::Extract { .. } => ErrorCode::ExtractFailed,
+        }
+    }
+
+    fn context(&self) -> Value {
+        match self {
+            PakError::NotFound { pak_file } | PakError::Read { pak_file, .. } => json!({ "pak_file": pak_file }),
+            PakError::EntryNotFound { pak_file, path }
+            | PakError::EntryEncrypted { pak_file, path }
+            | PakError::Extract { pak_file, path, .. } => json!({ "path": path, "pak_file": pak_file }),
+            PakError::DecompressorUnavailable { pak_file, path, method } => {
+                json!({ "path": path, "pak_file": pak_file, "method": method })
+            }
+        }
+    }
+}
+
+/// An IoStore container (.utoc/.ucas) that couldn't be read
+#[derive(Debug, thiserror::Error)]
+pub enum ContainerError {
+    #[error("Container not found: {container}")]
+    NotFound { container: String },
+    #[error("{message}")]
+    Read { container: String, message: String },
+}
+
+impl ContainerError {
+    /// A container that couldn't be opened or parsed: `NotFound` when the file is missing
+    pub fn open(container: &str, error: &anyhow::Error) -> Self {
+        let container = container.to_string();
+        if is_not_found(error) {
+            ContainerError::NotFound { container }
+        } else {
+            ContainerError::Read { container, message: format!("{:#}", error) }
+        }
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            ContainerError::NotFound { .. } => ErrorCode::PakNotFound,
+            ContainerError::Read { .. } => ErrorCode::ContainerReadFailed,
+        }
+    }
+
+    fn context(&self) -> Value {
+        match self {
+            ContainerError::NotFound { container } | ContainerError::Read { container, .. } => json!({ "container": container }),
+        }
+    }
+}
+
+/// A preview, or an image made from one, that can't be produced
+#[derive(Debug, thiserror::Error)]
+pub enum PreviewError {
+    #[error("Asset has no binary preview")]
+    NoBinaryPreview { path: String },
+    #[error("Preview is {available}, not {requested}")]
+    FormatUnavailable { path: String, requested: String, available: String },
+    #[error("{message}")]
+    TextureUnreadable { path: String, pak_file: String, message: String },
+    #[error("{message}")]
+    Failed { path: String, message: String },
+    #[error("{path} uses unversioned properties; a .usmap mappings file is required")]
+    MappingsRequired { path: String },
+}
+
+impl PreviewError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            PreviewError::NoBinaryPreview { .. } => ErrorCode::NoBinaryPreview,
+            PreviewError::FormatUnavailable { .. } => ErrorCode::FormatUnavailable,
+            PreviewError::TextureUnreadable { .. } => ErrorCode::TextureUnreadable,
+            PreviewError::Failed { .. } => ErrorCode::PreviewFailed,
+            PreviewError::MappingsRequired { .. } => ErrorCode::MappingsRequired,
+        }
+    }
+
+    fn context(&self) -> Value {
+        match self {
+            PreviewError::NoBinaryPreview { path }
+            | PreviewError::Failed { path, .. }
+            | PreviewError::MappingsRequired { path } => json!({ "path": path }),
+            PreviewError::FormatUnavailable { path, available, .. } => json!({ "path": path, "available": available }),
+            PreviewError::TextureUnreadable { path, pak_file, .. } => json!({ "path": path, "pak_file": pak_file }),
+        }
+    }
+}
+
+/// A dependency graph question that can't be answered, or a graph file that can't be
+/// read or written
+#[derive(Debug, thiserror::Error)]
+pub enum DependencyError {
+    #[error("Asset not in the dependency graph: {asset}")]
+    NotInGraph { asset: String },
+    #[error("Invalid subgraph filter: {source:#}")]
+    InvalidFilter { source: anyhow::Error },
+    #[error("Unsupported dependency file: {path}")]
+    UnsupportedFile { path: String },
+    #[error("Failed to import {path}: {source:#}")]
+    Import { path: String, source: anyhow::Error },
+    #[error("Failed to export as {format}: {source:#}")]
+    Export { format: String, source: anyhow::Error },
+}
+
+impl DependencyError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            DependencyError::NotInGraph { .. } => ErrorCode::AssetNotFound,
+            DependencyError::InvalidFilter { .. } | DependencyError::UnsupportedFile { .. } => ErrorCode::InvalidRequest,
+            DependencyError::Import { .. } => ErrorCode::ImportFailed,
+            DependencyError::Export { .. } => ErrorCode::ExportFailed,
+        }
+    }
+
+    fn context(&self) -> Option<Value> {
+        match self {
+            DependencyError::NotInGraph { asset } => Some(json!({ "asset": asset })),
+            DependencyError::InvalidFilter { .. } => Some(json!({ "field": "filter" })),
+            DependencyError::UnsupportedFile { path } | DependencyError::Import { path, .. } => Some(json!({ "path": path })),
+            DependencyError::Export { format, .. } => Some(json!({ "format": format })),
+        }
+    }
+}
+
+/// Work stopped through its `CancellationToken`
+///
+/// Raised inside `anyhow` errors by the parsers' loops; `PakSeekError::from` turns it into
+/// `PakSeekError::Cancelled` wherever it sits in the chain.
+#[derive(Debug, thiserror::Error)]
+#[error("Cancelled")]
+pub struct Cancelled;
+
+impl Cancelled {
+    /// `Err(Cancelled)` once `cancel` has fired
+    pub fn check(cancel: &CancellationToken) -> Result<(), Cancelled> {
+        if cancel.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Unversioned properties met without a .usmap mappings file to read them with
+///
+/// Raised inside `anyhow` errors by the property reader, so that features built on it fail
+/// with `MAPPINGS_REQUIRED` instead of guessing at values.
+#[derive(Debug, thiserror::Error)]
+#[error("Package uses unversioned properties; a .usmap mappings file is required")]
+pub struct MappingsRequired;
+
+/// Error of every Tauri command and HTTP handler, sent as `{ code, message, context }`
+///
+/// `code` is a stable `ErrorCode` for the frontend to branch on; `context` says what the
+/// error is about (path, pak, ...). Errors that already carry a code travel as `Api`, and
+/// plain messages as `Other` with the `INTERNAL` code.
+#[derive(Debug, thiserror::Error)]
+pub enum PakSeekError {
+    #[error(transparent)]
+    Pak(#[from] PakError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Preview(#[from] PreviewError),
+    #[error(transparent)]
+    Dependency(#[from] DependencyError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Api(#[from] ApiError),
+    #[error("Cancelled")]
+    Cancelled,
+    #[error(transparent)]
+    MappingsRequired(#[from] MappingsRequired),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl PakSeekError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            PakSeekError::Pak(e) => e.code(),
+            PakSeekError::Container(e) => e.code(),
+            PakSeekError::Preview(e) => e.code(),
+            PakSeekError::Dependency(e) => e.code(),
+            PakSeekError::Io(e) if e.kind() == std::io::ErrorKind::NotFound => ErrorCode::FileNotFound,
+            PakSeekError::Io(_) => ErrorCode::IoFailed,
+            PakSeekError::Api(e) => e.code,
+            PakSeekError::Cancelled => ErrorCode::Cancelled,
+            PakSeekError::MappingsRequired(_) => ErrorCode::MappingsRequired,
+            PakSeekError::Other(_) => ErrorCode::Internal,
+        }
+    }
+
+    pub fn context(&self) -> Option<Value> {
+        match self {
+            PakSeekError::Pak(e) => Some(e.context()),
+            PakSeekError::Container(e) => Some(e.context()),
+            PakSeekError::Preview(e) => Some(e.context()),
+            PakSeekError::Dependency(e) => e.context(),
+            PakSeekError::Io(e) => Some(json!({ "kind": e.kind().to_string() })),
+            PakSeekError::Api(e) => e.context.clone(),
+            PakSeekError::Cancelled | PakSeekError::MappingsRequired(_) | PakSeekError::Other(_) => None,
+        }
+    }
+
+    /// `PakError::open` for a pak whose index couldn't be read, or `Cancelled` when reading
+    /// it was
+    pub fn pak_open(pak_file: &str, error: &anyhow::Error) -> Self {
+        if is_cancelled(error) {
+            PakSeekError::Cancelled
+        } else {
+            PakError::open(pak_file, error).into()
+        }
+    }
+
+    /// The error as it goes over the wire, and into scan progress
+    pub fn to_api_error(&self) -> ApiError {
+        match self {
+            PakSeekError::Api(e) => e.clone(),
+            other => ApiError { code: other.code(), message: other.to_string(), context: other.context() },
+        }
+    }
+}
+
+impl From<String> for PakSeekError {
+    fn from(message: String) -> Self {
+        PakSeekError::Other(message)
+    }
+}
+
+impl From<&str> for PakSeekError {
+    fn from(message: &str) -> Self {
+        PakSeekError::Other(message.to_string())
+    }
+}
+
+impl From<anyhow::Error> for PakSeekError {
+    fn from(error: anyhow::Error) -> Self {
+        if is_cancelled(&error) {
+            return PakSeekError::Cancelled;
+        }
+        if needs_mappings(&error) {
+            return PakSeekError::MappingsRequired(MappingsRequired);
+        }
+        PakSeekError::Other(format!("{:#}", error))
+    }
+}
+
+impl From<Cancelled> for PakSeekError {
+    fn from(_: Cancelled) -> Self {
+        PakSeekError::Cancelled
+    }
+}
+
+impl Serialize for PakSeekError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_api_error().serialize(serializer)
+    }
+}
+
+impl IntoResponse for PakSeekError {
+    fn into_response(self) -> Response {
+        let error = self.to_api_error();
+        (error.code.status(), Json(ErrorResponse { error })).into_response()
+    }
+}
+
+/// Whether the error comes down to a `Cancelled` token
+pub fn is_cancelled(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.is::<Cancelled>())
+}
+
+/// Whether the error comes down to unversioned properties without mappings
+pub fn needs_mappings(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.is::<MappingsRequired>())
+}
+
+/// Whether the error comes down to a missing file
+fn is_not_found(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|io| io.kind() == std::io::ErrorKind::NotFound)
+}
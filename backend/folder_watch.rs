@@ -0,0 +1,86 @@
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+
+/// Quiet time after the last change before a batch of changed paks is re-read
+pub const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// First and longest wait between size checks of a pak that's still being written
+const SETTLE_FIRST_DELAY: Duration = Duration::from_millis(500);
+const SETTLE_MAX_DELAY: Duration = Duration::from_secs(8);
+
+/// How long a pak may keep changing before it's left for the next change event
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Watches the folder a mount covers (or the folder of its .pak/.utoc) for container changes
+///
+/// The .pak of every created, changed or deleted .pak or .utoc goes to the returned channel,
+/// since the listing is made of .pak files. A mount of a single container only hears about
+/// that container. Dropping the watcher closes the channel.
+pub fn watch(mount_path: &Path) -> notify::Result<(RecommendedWatcher, mpsc::UnboundedReceiver<String>)> {
+    let (folder, only) = if mount_path.is_dir() {
+        (mount_path.to_path_buf(), None)
+    } else {
+        let folder = mount_path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        (folder.to_path_buf(), Some(mount_path.with_extension("pak")))
+    };
+
+    let (send, receive) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+            return;
+        }
+        for pak_file in event.paths.iter().filter_map(|path| container_pak(path)) {
+            if only.as_ref().is_none_or(|only| *only == pak_file) {
+                let _ = send.send(pak_file.to_string_lossy().into_owned());
+            }
+        }
+    })?;
+    watcher.watch(&folder, RecursiveMode::NonRecursive)?;
+    Ok((watcher, receive))
+}
+
+/// The .pak a changed file belongs to, if it's a .pak or .utoc
+fn container_pak(path: &Path) -> Option<PathBuf> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "pak" => Some(path.to_path_buf()),
+        "utoc" => Some(path.with_extension("pak")),
+        _ => None,
+    }
+}
+
+/// The paks changed until `DEBOUNCE` passed without another change; None once the watcher is gone
+pub async fn next_batch(changes: &mut mpsc::UnboundedReceiver<String>) -> Option<BTreeSet<String>> {
+    let mut batch = BTreeSet::from([changes.recv().await?]);
+    while let Ok(Some(pak_file)) = tokio::time::timeout(DEBOUNCE, changes.recv()).await {
+        batch.insert(pak_file);
+    }
+    Some(batch)
+}
+
+/// Waits, backing off, until a file's size and modification time stop changing
+///
+/// Keeps a pak still being copied in from being parsed half-written. Returns its size
+/// then; None when it's deleted meanwhile or doesn't settle within `SETTLE_TIMEOUT`.
+pub async fn wait_until_settled(path: &Path) -> Option<u64> {
+    let started = Instant::now();
+    let mut delay = SETTLE_FIRST_DELAY;
+    let mut last: Option<(u64, SystemTime)> = None;
+    loop {
+        let metadata = tokio::fs::metadata(path).await.ok()?;
+        let current = (metadata.len(), metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+        if last == Some(current) {
+            return Some(current.0);
+        }
+        if started.elapsed() >= SETTLE_TIMEOUT {
+            return None;
+        }
+        last = Some(current);
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(SETTLE_MAX_DELAY);
+    }
+}
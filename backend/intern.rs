@@ -0,0 +1,32 @@
+use serde::{Deserialize, Deserializer};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Strings many assets repeat (pak paths, compression methods, type ids), each held once
+///
+/// Interned strings are never freed; only values drawn from a small set belong here.
+fn interned() -> &'static Mutex<HashSet<Arc<str>>> {
+    static INTERNED: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+    INTERNED.get_or_init(Mutex::default)
+}
+
+/// The shared copy of `value`
+pub fn intern(value: &str) -> Arc<str> {
+    let mut interned = interned().lock().unwrap();
+    if let Some(existing) = interned.get(value) {
+        return existing.clone();
+    }
+    let value: Arc<str> = Arc::from(value);
+    interned.insert(value.clone());
+    value
+}
+
+/// The shared copy of `value` if there is one, without adding it
+pub fn lookup(value: &str) -> Option<Arc<str>> {
+    interned().lock().unwrap().get(value).cloned()
+}
+
+/// Deserializes an optional string into its shared copy, for `deserialize_with`
+pub fn deserialize_option<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Arc<str>>, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.map(|value| intern(&value)))
+}
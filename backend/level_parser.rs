@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// Actors of one class placed in the level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorClassSummary {
+    pub class: String,
+    pub count: usize,
+    /// Actor names, capped at the requested limit
+    pub actors: Vec<String>,
+    pub truncated: bool,
+}
+
+/// A streaming sublevel referenced by the level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamingLevel {
+    pub name: String,
+    pub class: String,
+    pub world: Option<String>,
+}
+
+/// What's inside a .umap: actors by class, sublevels and referenced packages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelSummary {
+    pub world: Option<String>,
+    pub total_actors: usize,
+    pub actor_classes: Vec<ActorClassSummary>,
+    pub streaming_levels: Vec<StreamingLevel>,
+    pub world_partition_cells: Vec<String>,
+    pub referenced_packages: Vec<String>,
+}
+
+/// Parses the export table of a level package
+///
+/// `actor_limit` caps how many actor names are listed per class; counts are always complete.
+pub fn parse_level(data: &[u8], actor_limit: usize) -> Result<LevelSummary> {
+    let package = Package::parse(data)?;
+    let level_index = package
+        .exports
+        .iter()
+        .position(|e| package.export_class_name(e) == "Level")
+        .map(|i| i as i32 + 1)
+        .context("Package has no Level export")?;
+
+    let world = package
+        .exports
+        .iter()
+        .find(|e| package.export_class_name(e) == "World")
+        .map(|e| e.object_name.clone());
+
+    // Everything owned directly by the level is an actor, apart from BSP data
+    let mut by_class: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for export in package.exports.iter().filter(|e| e.outer_index == level_index) {
+        let class = package.export_class_name(export);
+        if class == "Model" || class.ends_with("Component") {
+            continue;
+        }
+        by_class.entry(class).or_default().push(export.object_name.clone());
+    }
+
+    let total_actors = by_class.values().map(Vec::len).sum();
+    let mut actor_classes: Vec<ActorClassSummary> = by_class
+        .into_iter()
+        .map(|(class, mut actors)| {
+            let count = actors.len();
+            actors.truncate(actor_limit);
+            ActorClassSummary { class, count, truncated: count > actors.len(), actors }
+        })
+        .collect();
+    actor_classes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.class.cmp(&b.class)));
+
+    let streaming_levels = package
+        .exports
+        .iter()
+        .filter_map(|export| {
+            let class = package.export_class_name(export);
+            if !class.starts_with("LevelStreaming") {
+                return None;
+            }
+            // WorldAsset is only readable from tagged properties, or unversioned ones with mappings
+            let world = package
+                .export_data(data, export)
+                .ok()
+                .and_then(|bytes| uasset_parser::read_properties(&package, &mut ArchiveReader::new(bytes), &class).ok())
+                .and_then(|properties| properties.get("WorldAsset").and_then(Value::as_str).map(str::to_string));
+            Some(StreamingLevel { name: export.object_name.clone(), class, world })
+        })
+        .collect();
+
+    let world_partition_cells = package
+        .exports
+        .iter()
+        .filter(|e| {
+            let class = package.export_class_name(e);
+            class.starts_with("WorldPartitionRuntime") && class.ends_with("Cell")
+        })
+        .map(|e| e.object_name.clone())
+        .collect();
+
+    Ok(LevelSummary {
+        world,
+        total_actors,
+        actor_classes,
+        streaming_levels,
+        world_partition_cells,
+        referenced_packages: package.imported_packages(),
+    })
+}
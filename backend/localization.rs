@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::api_error::{ApiError, ErrorCode};
+use crate::error::{PakError, PakSeekError};
+use crate::localization_parser::{self, LocalizationEntry};
+use crate::pak_parser::OpenPak;
+use crate::preview::Asset;
+
+/// Culture the others are compared with when none is asked for, if it is present
+pub const DEFAULT_SOURCE_CULTURE: &str = "en";
+/// Missing keys listed per culture; `missing_count` counts them all
+pub const MISSING_KEYS_LISTED: usize = 500;
+/// Entries per page of a culture's strings when no limit is given
+pub const DEFAULT_ENTRY_LIMIT: usize = 200;
+pub const MAX_ENTRY_LIMIT: usize = 5000;
+
+/// A .locres file of the listing, read and parsed
+#[derive(Debug, Clone)]
+pub struct LocresFile {
+    pub path: String,
+    pub pak_file: Option<Arc<str>>,
+    /// Folder the file is in (`Localization/Game/de/Game.locres` is "de")
+    pub culture: String,
+    /// Localization target, the file's stem ("Game")
+    pub target: String,
+    pub entries: Vec<LocalizationEntry>,
+}
+
+/// Where a string is, independently of the culture it's translated to
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LocalizationKey {
+    pub target: String,
+    pub namespace: String,
+    pub key: String,
+}
+
+/// String counts of one culture, and the keys of the source culture it lacks
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CultureSummary {
+    pub culture: String,
+    /// Paths of the culture's .locres files
+    pub files: Vec<String>,
+    /// Distinct keys, across the culture's targets
+    pub string_count: usize,
+    pub namespace_count: usize,
+    /// Keys of the source culture this one doesn't have
+    pub missing_count: usize,
+    /// Share of the source culture's keys this one has; None when the source has none
+    pub coverage: Option<f64>,
+    /// The first `MISSING_KEYS_LISTED` missing keys, sorted
+    pub missing_keys: Vec<LocalizationKey>,
+}
+
+/// Localization coverage of the loaded assets, per culture
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LocalizationSummary {
+    /// Culture the others were compared with; None when no .locres file was read
+    pub source_culture: Option<String>,
+    /// By culture name
+    pub cultures: Vec<CultureSummary>,
+    /// .locres files (or paks holding them) that couldn't be read or parsed
+    pub errors: Vec<ApiError>,
+}
+
+/// A localized string of one culture
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CultureEntry {
+    pub target: String,
+    pub namespace: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// A page of one culture's strings, sorted by target, namespace and key
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CultureEntries {
+    pub culture: String,
+    /// Strings of the culture
+    pub total: usize,
+    /// Strings matching the search
+    pub filtered: usize,
+    pub offset: usize,
+    pub entries: Vec<CultureEntry>,
+    /// The culture's .locres files that couldn't be read or parsed
+    pub errors: Vec<ApiError>,
+}
+
+/// Whether the asset is a compiled .locres file
+pub fn is_locres(path: &str) -> bool {
+    path.to_lowercase().ends_with(".locres")
+}
+
+/// Culture and target of a .locres path: its folder and its stem
+pub fn locres_culture(path: &str) -> Option<(String, String)> {
+    let mut parts = path.rsplit(['/', '\\']);
+    let target = parts.next()?;
+    let target = target.get(..target.len().checked_sub(".locres".len())?)?;
+    let culture = parts.next().filter(|culture| !culture.is_empty())?;
+    Some((culture.to_string(), target.to_string()))
+}
+
+/// Reads and parses the .locres files among `assets`, opening each pak once
+///
+/// `culture` keeps only that culture's files (case-insensitively). A file that can't be
+/// extracted or parsed, or a pak that can't be opened, is returned as an error of its
+/// own and the other files are still read.
+pub async fn read_locres(assets: &[Arc<Asset>], culture: Option<&str>) -> (Vec<LocresFile>, Vec<ApiError>) {
+    let mut by_pak: BTreeMap<Option<&str>, Vec<(&Asset, String, String)>> = BTreeMap::new();
+    for asset in assets.iter().filter(|asset| is_locres(&asset.path)) {
+        let Some((file_culture, target)) = locres_culture(&asset.path) else {
+            continue;
+        };
+        if culture.is_some_and(|culture| !culture.eq_ignore_ascii_case(&file_culture)) {
+            continue;
+        }
+        by_pak.entry(asset.pak_file.as_deref()).or_default().push((asset, file_culture, target));
+    }
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for (pak_file, locres) in by_pak {
+        let pak = match pak_file {
+            Some(pak_file) => match OpenPak::open(pak_file).await {
+                Ok(pak) => Some(pak),
+                Err(e) => {
+                    errors.push(PakSeekError::from(PakError::open(pak_file, &e)).to_api_error());
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        for (asset, culture, target) in locres {
+            let data = match &pak {
+                Some(pak) => pak.extract_file(&asset.path).await.map_err(|source| {
+                    let pak_file = pak.path().to_string();
+                    PakSeekError::from(PakError::Extract { pak_file, path: asset.path.clone(), source }).to_api_error()
+                }),
+                None => tokio::fs::read(&asset.path).await.map_err(|e| {
+                    ApiError::new(ErrorCode::IoFailed, format!("Failed to read {}: {}", asset.path, e))
+                        .with_context(json!({ "path": asset.path }))
+                }),
+            };
+            let parsed = data.and_then(|data| {
+                localization_parser::parse_locres(&data).map_err(|e| {
+                    ApiError::new(ErrorCode::LocresUnreadable, format!("Failed to parse {}: {:#}", asset.path, e))
+                        .with_context(json!({ "path": asset.path, "pak_file": asset.pak_file }))
+                })
+            });
+            match parsed {
+                Ok(table) => files.push(LocresFile {
+                    path: asset.path.clone(),
+                    pak_file: asset.pak_file.clone(),
+                    culture,
+                    target,
+                    entries: table.entries,
+                }),
+                Err(error) => {
+                    tracing::warn!("Skipping {}: {}", asset.path, error);
+                    errors.push(error);
+                }
+            }
+        }
+    }
+    (files, errors)
+}
+
+/// Groups the files by culture and compares each culture's keys with `source`'s
+///
+/// Without a source, "en" is used when present and otherwise the culture with the most
+/// keys. A source that isn't among the cultures is an `InvalidRequest`.
+pub fn summarize(
+    files: &[LocresFile],
+    source: Option<&str>,
+    errors: Vec<ApiError>,
+) -> Result<LocalizationSummary, ApiError> {
+    let mut cultures: BTreeMap<&str, Vec<&LocresFile>> = BTreeMap::new();
+    for file in files {
+        cultures.entry(file.culture.as_str()).or_default().push(file);
+    }
+    let keys: BTreeMap<&str, HashSet<LocalizationKey>> =
+        cultures.iter().map(|(&culture, files)| (culture, culture_keys(files))).collect();
+
+    let source_culture = match source {
+        Some(source) => Some(*keys.keys().find(|culture| culture.eq_ignore_ascii_case(source)).ok_or_else(|| {
+            let cultures: Vec<&str> = keys.keys().copied().collect();
+            ApiError::new(ErrorCode::InvalidRequest, format!("No .locres files for culture {}", source))
+                .with_context(json!({ "culture": source, "cultures": cultures }))
+        })?),
+        None => keys
+            .keys()
+            .find(|culture| culture.eq_ignore_ascii_case(DEFAULT_SOURCE_CULTURE))
+            .or_else(|| keys.iter().max_by(|a, b| a.1.len().cmp(&b.1.len()).then(b.0.cmp(a.0))).map(|(culture, _)| culture))
+            .copied(),
+    };
+    let empty = HashSet::new();
+    let source_keys = source_culture.and_then(|culture| keys.get(culture)).unwrap_or(&empty);
+
+    let cultures = cultures
+        .iter()
+        .map(|(&culture, files)| {
+            let culture_keys = &keys[culture];
+            let mut missing: Vec<&LocalizationKey> = source_keys.difference(culture_keys).collect();
+            missing.sort_unstable();
+            let namespaces: HashSet<(&str, &str)> =
+                culture_keys.iter().map(|key| (key.target.as_str(), key.namespace.as_str())).collect();
+            CultureSummary {
+                culture: culture.to_string(),
+                files: files.iter().map(|file| file.path.clone()).collect(),
+                string_count: culture_keys.len(),
+                namespace_count: namespaces.len(),
+                missing_count: missing.len(),
+                coverage: (!source_keys.is_empty())
+                    .then(|| (source_keys.len() - missing.len()) as f64 / source_keys.len() as f64),
+                missing_keys: missing.into_iter().take(MISSING_KEYS_LISTED).cloned().collect(),
+            }
+        })
+        .collect();
+
+    Ok(LocalizationSummary { source_culture: source_culture.map(str::to_string), cultures, errors })
+}
+
+fn culture_keys(files: &[&LocresFile]) -> HashSet<LocalizationKey> {
+    files
+        .iter()
+        .flat_map(|file| {
+            file.entries.iter().map(|entry| LocalizationKey {
+                target: file.target.clone(),
+                namespace: entry.namespace.clone(),
+                key: entry.key.clone(),
+            })
+        })
+        .collect()
+}
+
+/// A page of `culture`'s strings whose key or value contains `search` (case-insensitive)
+///
+/// A key found in several of the culture's files is listed once, with the value read last.
+pub fn culture_entries(
+    files: &[LocresFile],
+    culture: &str,
+    search: Option<&str>,
+    offset: usize,
+    limit: usize,
+    errors: Vec<ApiError>,
+) -> CultureEntries {
+    let mut strings: HashMap<LocalizationKey, &str> = HashMap::new();
+    for file in files.iter().filter(|file| file.culture.eq_ignore_ascii_case(culture)) {
+        for entry in &file.entries {
+            let key = LocalizationKey {
+                target: file.target.clone(),
+                namespace: entry.namespace.clone(),
+                key: entry.key.clone(),
+            };
+            strings.insert(key, &entry.value);
+        }
+    }
+    let total = strings.len();
+
+    let search = search.filter(|search| !search.is_empty()).map(str::to_lowercase);
+    let mut matching: Vec<(LocalizationKey, &str)> = strings
+        .into_iter()
+        .filter(|(key, value)| match &search {
+            Some(search) => key.key.to_lowercase().contains(search) || value.to_lowercase().contains(search),
+            None => true,
+        })
+        .collect();
+    matching.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+    let culture = files
+        .iter()
+        .find(|file| file.culture.eq_ignore_ascii_case(culture))
+        .map_or(culture, |file| file.culture.as_str());
+    CultureEntries {
+        culture: culture.to_string(),
+        total,
+        filtered: matching.len(),
+        offset,
+        entries: matching
+            .into_iter()
+            .skip(offset)
+            .take(limit.clamp(1, MAX_ENTRY_LIMIT))
+            .map(|(key, value)| CultureEntry {
+                target: key.target,
+                namespace: key.namespace,
+                key: key.key,
+                value: value.to_string(),
+            })
+            .collect(),
+        errors,
+    }
+}
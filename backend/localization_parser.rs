@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// GUID written at the start of every versioned .locres file
+const LOCRES_MAGIC: [u8; 16] = [
+    0x0E, 0x14, 0x74, 0x75, 0x67, 0x4A, 0x03, 0xFC, 0x4A, 0x15, 0x90, 0x9D, 0xC3, 0x37, 0x7F, 0x1B,
+];
+
+/// .locres format versions (ELocResVersion)
+const LOCRES_VERSION_COMPACT: u8 = 1;
+const LOCRES_VERSION_OPTIMIZED_CRC32: u8 = 2;
+const LOCRES_VERSION_LATEST: u8 = 3;
+
+/// A single localized string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalizationEntry {
+    pub namespace: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// Strings from a .locres file or StringTable asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalizationTable {
+    /// "locres" or "string_table"
+    pub source: String,
+    pub version: Option<u8>,
+    pub entries: Vec<LocalizationEntry>,
+}
+
+impl LocalizationTable {
+    /// Number of distinct namespaces
+    pub fn namespace_count(&self) -> usize {
+        let mut namespaces: Vec<&str> = self.entries.iter().map(|e| e.namespace.as_str()).collect();
+        namespaces.sort_unstable();
+        namespaces.dedup();
+        namespaces.len()
+    }
+
+    /// Keeps entries whose key or value contains `search` (case-insensitive)
+    pub fn filter(&mut self, search: &str) {
+        let search = search.to_lowercase();
+        self.entries.retain(|e| {
+            e.key.to_lowercase().contains(&search) || e.value.to_lowercase().contains(&search)
+        });
+    }
+}
+
+/// Parses a compiled .locres file (legacy through CityHash64/UTF-16 versions)
+pub fn parse_locres(data: &[u8]) -> Result<LocalizationTable> {
+    let mut reader = ArchiveReader::new(data);
+
+    let version = if data.starts_with(&LOCRES_MAGIC) {
+        reader.skip(LOCRES_MAGIC.len())?;
+        let version = reader.read_u8()?;
+        if version > LOCRES_VERSION_LATEST {
+            bail!("Unsupported .locres version {}", version);
+        }
+        version
+    } else {
+        0
+    };
+
+    // Compact and later versions store values once in a trailing string array
+    let mut localized_strings = Vec::new();
+    if version >= LOCRES_VERSION_COMPACT {
+        let offset = reader.read_i64()?;
+        let resume = reader.position();
+        reader.seek(usize::try_from(offset).context("Negative .locres string array offset")?)?;
+
+        let count = reader.read_count(4)?;
+        localized_strings.reserve(count);
+        for _ in 0..count {
+            localized_strings.push(reader.read_fstring()?);
+            if version >= LOCRES_VERSION_OPTIMIZED_CRC32 {
+                let _ref_count = reader.read_i32()?;
+            }
+        }
+        reader.seek(resume)?;
+    }
+
+    if version >= LOCRES_VERSION_OPTIMIZED_CRC32 {
+        let _entry_count = reader.read_u32()?;
+    }
+
+    let mut entries = Vec::new();
+    let namespace_count = reader.read_count(8)?;
+    for _ in 0..namespace_count {
+        if version >= LOCRES_VERSION_OPTIMIZED_CRC32 {
+            let _namespace_hash = reader.read_u32()?;
+        }
+        let namespace = reader.read_fstring()?;
+
+        let key_count = reader.read_count(12)?;
+        for _ in 0..key_count {
+            if version >= LOCRES_VERSION_OPTIMIZED_CRC32 {
+                let _key_hash = reader.read_u32()?;
+            }
+            let key = reader.read_fstring()?;
+            let _source_hash = reader.read_u32()?;
+
+            let value = if version >= LOCRES_VERSION_COMPACT {
+                let index = reader.read_i32()?;
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|i| localized_strings.get(i))
+                    .cloned()
+                    .with_context(|| format!("String index {} out of range for key {}", index, key))?
+            } else {
+                reader.read_fstring()?
+            };
+
+            entries.push(LocalizationEntry { namespace: namespace.clone(), key, value });
+        }
+    }
+
+    Ok(LocalizationTable {
+        source: "locres".to_string(),
+        version: Some(version),
+        entries,
+    })
+}
+
+/// Parses a cooked StringTable asset (namespace plus key/source string map)
+pub fn parse_string_table(data: &[u8]) -> Result<LocalizationTable> {
+    let package = Package::parse(data)?;
+    let export = package
+        .exports
+        .iter()
+        .find(|e| package.export_class_name(e) == "StringTable")
+        .context("Package has no StringTable export")?;
+
+    let mut reader = ArchiveReader::new(package.export_data(data, export)?);
+    uasset_parser::skip_properties(&package, &mut reader)?;
+    uasset_parser::skip_object_guid(&mut reader)?;
+
+    let namespace = reader.read_fstring()?;
+    let count = reader.read_count(8)?;
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        let key = reader.read_fstring()?;
+        let value = reader.read_fstring()?;
+        entries.push(LocalizationEntry { namespace: namespace.clone(), key, value });
+    }
+
+    Ok(LocalizationTable {
+        source: "string_table".to_string(),
+        version: None,
+        entries,
+    })
+}
@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{prelude::*, reload, EnvFilter, Registry};
+
+/// Name of the current log file; rotated ones get ".1", ".2", ... (".1" is the newest)
+pub const LOG_FILE_NAME: &str = "pakseek.log";
+
+/// Size at which the current log file is rotated
+pub const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Log files kept, the current one included, so logs take at most about 20 MB
+pub const MAX_LOG_FILES: usize = 4;
+
+/// Filter used unless `RUST_LOG` says otherwise
+pub const DEFAULT_FILTER: &str = "info";
+
+/// Most lines `recent_lines` returns
+pub const MAX_RECENT_LINES: usize = 5000;
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Where the log files go: ~/Library/Logs/PakSeek on macOS, the local data dir elsewhere
+pub fn log_dir() -> PathBuf {
+    let dir = if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|home| home.join("Library").join("Logs").join("PakSeek"))
+    } else {
+        dirs::data_local_dir().map(|data| data.join("pakseek").join("logs"))
+    };
+    dir.unwrap_or_else(|| std::env::temp_dir().join("pakseek-logs"))
+}
+
+pub fn log_file() -> PathBuf {
+    log_dir().join(LOG_FILE_NAME)
+}
+
+/// Logs to stderr and to the log file, filtered by `RUST_LOG` or `DEFAULT_FILTER`
+///
+/// Lines are written to the file on a background thread, which the returned guard flushes
+/// when dropped; keep it for the life of the process. Without a writable log dir only
+/// stderr is used.
+pub fn init() -> Option<WorkerGuard> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let (filter, handle) = reload::Layer::new(filter);
+    let _ = FILTER.set(handle);
+
+    let file = RollingFile::open(log_dir());
+    let (file_layer, guard, error) = match file {
+        Ok(file) => {
+            let (writer, guard) = tracing_appender::non_blocking(file);
+            (Some(tracing_subscriber::fmt::layer().with_ansi(false).with_writer(writer)), Some(guard), None)
+        }
+        Err(e) => (None, None, Some(e)),
+    };
+    tracing_subscriber::registry().with(filter).with(tracing_subscriber::fmt::layer()).with(file_layer).init();
+    if let Some(e) = error {
+        tracing::warn!("Logging to stderr only: {:#}", e);
+    }
+    guard
+}
+
+/// Replaces the log filter: a level ("debug") or `RUST_LOG` directives ("info,hyper=warn")
+pub fn set_filter(directives: &str) -> Result<()> {
+    let filter = EnvFilter::try_new(directives).with_context(|| format!("Invalid log level: {}", directives))?;
+    let handle = FILTER.get().context("Logging isn't initialized")?;
+    handle.reload(filter).context("Failed to change the log level")?;
+    Ok(())
+}
+
+/// The last `lines` lines of the current log file, oldest first
+///
+/// The file is read backwards in chunks, so a long log costs no more than its tail.
+pub fn recent_lines(lines: usize) -> Result<Vec<String>> {
+    let lines = lines.min(MAX_RECENT_LINES);
+    let path = log_file();
+    let mut file = match File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open {}", path.display())),
+    };
+    const CHUNK: u64 = 64 * 1024;
+    let mut end = file.metadata()?.len();
+    let mut tail: Vec<u8> = Vec::new();
+    // One newline more than asked for, so the first line kept is whole
+    while end > 0 && tail.iter().filter(|&&b| b == b'\n').count() <= lines {
+        let start = end.saturating_sub(CHUNK);
+        let mut chunk = vec![0u8; (end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&tail);
+        tail = chunk;
+        end = start;
+    }
+    let text = String::from_utf8_lossy(&tail);
+    let all: Vec<&str> = text.lines().collect();
+    Ok(all[all.len().saturating_sub(lines)..].iter().map(|line| line.to_string()).collect())
+}
+
+/// The log file, moved aside once it reaches `MAX_LOG_FILE_BYTES`
+struct RollingFile {
+    dir: PathBuf,
+    /// None only while rotating, since Windows can't rename an open file
+    file: Option<File>,
+    written: u64,
+}
+
+impl RollingFile {
+    fn open(dir: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(LOG_FILE_NAME);
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let written = file.metadata()?.len();
+        Ok(RollingFile { dir, file: Some(file), written })
+    }
+
+    fn rotated(dir: &Path, n: usize) -> PathBuf {
+        dir.join(format!("{}.{}", LOG_FILE_NAME, n))
+    }
+
+    /// Shifts pakseek.log.N up by one, dropping the oldest, and starts a new pakseek.log
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let _ = std::fs::remove_file(Self::rotated(&self.dir, MAX_LOG_FILES - 1));
+        for n in (1..MAX_LOG_FILES - 1).rev() {
+            let _ = std::fs::rename(Self::rotated(&self.dir, n), Self::rotated(&self.dir, n + 1));
+        }
+        let current = self.dir.join(LOG_FILE_NAME);
+        self.file = None;
+        let renamed = std::fs::rename(&current, Self::rotated(&self.dir, 1));
+        // Reopened either way: when it couldn't be moved aside, logging carries on in it
+        self.file = Some(std::fs::OpenOptions::new().create(true).append(true).open(&current)?);
+        self.written = 0;
+        renamed
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > MAX_LOG_FILE_BYTES {
+            // A failed rotation is tried again after another MAX_LOG_FILE_BYTES
+            let _ = self.rotate();
+        }
+        let file = self.file.as_mut().ok_or_else(|| std::io::Error::other("Log file is closed"))?;
+        let written = file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.as_mut().map_or(Ok(()), File::flush)
+    }
+}
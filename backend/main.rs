@@ -4,93 +4,496 @@
 #![allow(dead_code)]
 
 use axum::{
-    extract::{Path, Query},
-    http::{HeaderValue, Method, StatusCode},
-    response::Json,
-    routing::get,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, RawQuery,
+    },
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, warn};
+use tauri::{Emitter, Manager};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{broadcast, watch, Mutex};
+use tokio_util::sync::CancellationToken;
+use tower_http::compression::{predicate::{DefaultPredicate, Predicate}, CompressionLayer};
+use tracing::{debug, info, warn};
 
 // Import our modules
 mod pak_parser;
 mod utoc_parser;
 mod preview;
 mod dependency_map;
+mod uasset_parser;
+mod mesh_parser;
+mod material_parser;
+mod blueprint_parser;
+mod table_parser;
+mod localization_parser;
+mod level_parser;
+mod ui_parser;
+mod anim_parser;
+mod particle_parser;
+mod preview_cache;
+mod texture_parser;
+mod asset_metadata;
+mod asset_registry;
+mod scan_cache;
+mod api_error;
+mod server_config;
+mod mounts;
+mod search;
+mod route_limit;
+mod settings;
+mod folder_watch;
+mod asset_export;
+mod statistics;
+mod duplicates;
+mod diagnostics;
+mod logging;
+mod texture_decode;
+mod asset_type;
+mod asset_tree;
+mod error;
+mod asset_path;
+mod config;
+mod intern;
+mod resource_limit;
+mod cli;
+mod sql_index;
+mod localization;
+mod oodle;
+mod usmap;
+mod encryption_audit;
+mod zen_package;
 
 // Re-export specific types from modules to avoid naming conflicts
+pub use api_error::{ApiError, ErrorCode, ErrorResponse};
+pub use error::{Cancelled, ContainerError, DependencyError, PakError, PakSeekError, PreviewError};
 pub use pak_parser::{PakParser, PakFile, PakEntry, CompressionMethod};
 pub use utoc_parser::{UtocUcasParser, UtocFile};
-pub use preview::{Asset, PreviewResponse, PreviewType, PreviewData, generate_preview_data};
-pub use dependency_map::{DependencyMap};
+pub use preview::{Asset, AssetIndex, AssetPart, TypeConfidence, PreviewOptions, PreviewResponse, PreviewType, PreviewData, generate_preview_data};
+pub use asset_type::AssetType;
+pub use asset_path::{normalize_asset_path, with_forward_slashes};
+pub use dependency_map::{
+    DependencyAnalysis, DependencyKind, DependencyMap, DependencyStatistics, ExportOptions, MergeSummary, RemovalImpact, SubgraphFilter,
+    DependencyTree, SubgraphSummary, TreeDirection, UnreachableReport, ValidationIssue,
+};
 
 /// Application state shared between handlers
 #[derive(Clone)]
 pub struct AppState {
-    pub assets: Arc<Mutex<Vec<Asset>>>,
+    /// Swapped whole when the listing changes; readers take a `snapshot` and let go of the lock
+    pub assets: Arc<Mutex<Arc<AssetIndex>>>,
     pub dependencies: Arc<Mutex<DependencyMap>>,
+    /// `POST /scan` and `start_scan` jobs by id
+    pub scans: Arc<std::sync::Mutex<HashMap<String, ScanProgress>>>,
+    /// Tokens of the jobs still running (scans, mount scans, bulk extractions, duplicate searches) by id, for `cancel_job`
+    pub cancellations: Arc<std::sync::Mutex<HashMap<String, CancellationToken>>>,
+    /// Folders and containers the listing is the union of
+    pub mounts: Arc<std::sync::Mutex<mounts::MountTable>>,
+    /// Watchers started by `watch_folder`, by mount id; dropping one stops it
+    pub watchers: Arc<std::sync::Mutex<HashMap<String, notify::RecommendedWatcher>>>,
+    /// Folders of the files `extract_and_open` wrote, removed when the app exits
+    pub opened_files: Arc<std::sync::Mutex<Vec<std::path::PathBuf>>>,
+    /// Progress of HTTP-started jobs, fanned out to `GET /ws` clients
+    pub events: broadcast::Sender<ProgressEvent>,
+    /// Changes whenever `assets` or `dependencies` do; ETags are derived from it
+    pub generation: Arc<AtomicU64>,
+    /// What `pakseek.toml` and the environment configured at startup
+    pub config: Arc<config::Config>,
+    /// Decompression slots and buffer budget, shared with the parsers as `resource_limit::global`
+    pub limiter: Arc<resource_limit::ResourceLimiter>,
+    /// The listing mirrored into SQLite when `config.sql_index` is on and the file opened
+    pub sql_index: Option<Arc<sql_index::SqlIndex>>,
+}
+
+impl AppState {
+    /// Empty state, or holding the development mock assets and dependencies when
+    /// `config.mock_data` is on
+    fn new(config: config::Config) -> Self {
+        let (assets, dependencies) = if config.mock_data {
+            (create_mock_assets(), create_mock_dependencies())
+        } else {
+            (Vec::new(), DependencyMap::new())
+        };
+        Self {
+            assets: Arc::new(Mutex::new(Arc::new(AssetIndex::new(assets)))),
+            dependencies: Arc::new(Mutex::new(dependencies)),
+            scans: Arc::default(),
+            cancellations: Arc::default(),
+            mounts: Arc::default(),
+            watchers: Arc::default(),
+            opened_files: Arc::default(),
+            events: broadcast::channel(256).0,
+            // Random start, so ETags from an earlier run never match
+            generation: Arc::new(AtomicU64::new(uuid::Uuid::new_v4().as_u64_pair().0)),
+            limiter: resource_limit::init(config.decompression_slots, config.buffer_budget),
+            sql_index: if config.sql_index { sql_index::open_default() } else { None },
+            config: Arc::new(config),
+        }
+    }
+
+    /// Marks the assets or dependencies as changed; call while holding the lock
+    fn touch(&self) {
+        self.generation.fetch_add(1, AtomicOrdering::SeqCst);
+    }
+
+    /// ETag of a response computed from the current state for `path` and `query`
+    ///
+    /// Weak, since the same tag covers compressed and plain bodies. Read it while holding
+    /// the locks the response is built under, so it matches the data.
+    fn etag(&self, path: &str, query: Option<&str>) -> String {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        hasher.update(self.generation.load(AtomicOrdering::SeqCst).to_le_bytes());
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(query.unwrap_or_default().as_bytes());
+        let digest: String = hasher.finalize()[..12].iter().map(|b| format!("{:02x}", b)).collect();
+        format!("W/\"{}\"", digest)
+    }
+
+    /// Registers a scan of `path` that's about to start, with the token that cancels it
+    fn register_scan(&self, path: &str, paks_total: usize) -> (ScanProgress, CancellationToken) {
+        let progress = ScanProgress {
+            job_id: uuid::Uuid::new_v4().to_string(),
+            path: path.to_string(),
+            status: ScanStatus::Running,
+            paks_done: 0,
+            paks_total,
+            assets_found: 0,
+            errors: Vec::new(),
+        };
+        let cancel = CancellationToken::new();
+        self.scans.lock().unwrap().insert(progress.job_id.clone(), progress.clone());
+        self.cancellations.lock().unwrap().insert(progress.job_id.clone(), cancel.clone());
+        (progress, cancel)
+    }
+
+    /// Registers a job under `job_id` (made up when None) with the token `cancel_job` fires;
+    /// fails when a job with that id is running. Call `finish_job` once it's done.
+    fn register_job(&self, job_id: Option<String>) -> Result<(String, CancellationToken), PakSeekError> {
+        let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let cancel = CancellationToken::new();
+        let mut cancellations = self.cancellations.lock().unwrap();
+        if cancellations.contains_key(&job_id) {
+            return Err(format!("Job {} is already running", job_id).into());
+        }
+        cancellations.insert(job_id.clone(), cancel.clone());
+        Ok((job_id, cancel))
+    }
+
+    fn finish_job(&self, job_id: &str) {
+        self.cancellations.lock().unwrap().remove(job_id);
+    }
+
+    /// Cancels a running job; false when there's none with this id
+    fn cancel_job(&self, id: &str) -> bool {
+        match self.cancellations.lock().unwrap().remove(id) {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn update_scan(&self, job_id: &str, update: impl FnOnce(&mut ScanProgress)) {
+        if let Some(progress) = self.scans.lock().unwrap().get_mut(job_id) {
+            update(progress);
+        }
+    }
+
+    /// Sends an event to the connected `GET /ws` clients; with none connected it's dropped
+    fn publish(&self, event: ProgressEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Swaps in the results of a single-folder scan, which becomes the only mount
+    ///
+    /// Both locks are held while swapping, so no reader sees assets from one scan and
+    /// dependencies from another.
+    async fn replace_with_scan(&self, path: &str, deep: bool, pak_files: Vec<String>, assets: Vec<Asset>, dependencies: DependencyMap) {
+        let assets = preview::share_assets(assets);
+        let index = Arc::new(AssetIndex::from_shared(assets.clone()));
+        let mut current_assets = self.assets.lock().await;
+        let mut current_dependencies = self.dependencies.lock().await;
+        self.mounts.lock().unwrap().reset(path, deep, pak_files, assets);
+        // The old mounts' ids are gone, and their watchers with them
+        self.watchers.lock().unwrap().clear();
+        self.sync_sql_index(&index);
+        *current_assets = index;
+        *current_dependencies = dependencies;
+        self.touch();
+    }
+
+    /// Rebuilds the listing from the mounts, after one is added or removed
+    async fn rebuild_from_mounts(&self) {
+        let mut current_assets = self.assets.lock().await;
+        let union = self.mounts.lock().unwrap().union();
+        let index = Arc::new(AssetIndex::from_shared(union));
+        self.sync_sql_index(&index);
+        *current_assets = index;
+        self.touch();
+    }
+
+    /// Brings the SQL index up to date with `assets` in the background; listings are
+    /// served from memory until it is
+    fn sync_sql_index(&self, assets: &Arc<AssetIndex>) {
+        let Some(sql_index) = self.sql_index.clone() else {
+            return;
+        };
+        sql_index.want(assets);
+        let assets = assets.clone();
+        tokio::task::spawn_blocking(move || match sql_index.sync(&assets) {
+            Ok(Some(summary)) => info!(
+                "SQL index synced: {} paks written ({} assets), {} unchanged, {} removed",
+                summary.paks_written, summary.assets_written, summary.paks_unchanged, summary.paks_removed
+            ),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to sync the SQL index: {}", e),
+        });
+    }
+
+    /// `AssetQuery::apply` over `assets`, answered by the SQL index when it holds them and
+    /// the search (if any) is a plain substring
+    async fn query_assets(&self, query: &AssetQuery, filter: &AssetFilter, assets: &Arc<AssetIndex>) -> Result<AssetsResponse, String> {
+        if let Some(sql_index) = &self.sql_index {
+            if sql_index.mirrors(assets) {
+                if let Some(sql_filter) = filter.matcher()?.sql_filter() {
+                    let pak = sql_filter.pak.clone();
+                    let sort = query.sort_order()?;
+                    let (offset, limit) = query.page();
+                    let (sql_index, snapshot) = (sql_index.clone(), assets.clone());
+                    let page = tokio::task::spawn_blocking(move || {
+                        sql_index.query(&snapshot, &sql_filter, sort.map(|(field, descending)| (field.as_str(), descending)), offset, limit)
+                    })
+                    .await;
+                    match page {
+                        Ok(Ok(Some(page))) => {
+                            if let Some(response) = query.sql_response(page, assets, pak.as_deref()) {
+                                return Ok(response);
+                            }
+                        }
+                        Ok(Ok(None)) => {}
+                        Ok(Err(e)) => warn!("SQL index query failed, searching in memory: {}", e),
+                        Err(e) => warn!("SQL index query failed, searching in memory: {}", e),
+                    }
+                }
+            }
+        }
+        query.apply(filter, assets)
+    }
+
+    /// The listing as it is now, to read without holding the lock
+    async fn snapshot(&self) -> Arc<AssetIndex> {
+        Arc::clone(&*self.assets.lock().await)
+    }
+
+    /// `snapshot` with the ETag it gets for `path` and `query`
+    async fn snapshot_with_etag(&self, path: &str, query: Option<&str>) -> (Arc<AssetIndex>, String) {
+        let assets = self.assets.lock().await;
+        (Arc::clone(&assets), self.etag(path, query))
+    }
 }
 
 /// Main entry point for the Tauri application
 fn main() {
-    // Initialize tracing for logging
-    tracing_subscriber::fmt::init();
+    PROCESS_STARTED.get_or_init(std::time::Instant::now);
+    // Log to stderr and the log file; the guard flushes the file when main returns
+    let _log_guard = logging::init();
+    if cli::requested() {
+        cli::quiet_logging();
+    }
+
+    info!("Starting PakSeek {}", env!("CARGO_PKG_VERSION"));
+    debug!("Working directory: {:?}", std::env::current_dir());
+    let config = config::Config::load();
+    info!("Configuration: {}", config.path.display());
+    scan_cache::set_enabled(config.index_cache);
 
-    println!("=== TAURI DEBUG: Starting Tauri application...");
-    println!("=== TAURI DEBUG: Current working directory: {:?}", std::env::current_dir());
+    if cli::requested() {
+        let code = cli::run(config);
+        // Exiting skips destructors, and the guard has log lines to flush
+        drop(_log_guard);
+        std::process::exit(code);
+    }
 
     // Start Tauri application
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .manage(AppState::new(config))
+        .setup(|app| {
+            // The HTTP API serves the same state as the commands. Without a configured token
+            // one is made up, which only the embedded UI learns (`get_api_token`).
+            let state = app.state::<AppState>().inner().clone();
+            let mut config = state.config.server.clone();
+            let token = config.token.get_or_insert_with(|| uuid::Uuid::new_v4().simple().to_string()).clone();
+            app.manage(ApiToken(token));
+
+            let handle = app.handle().clone();
+            let (signal, shutdown) = watch::channel(false);
+            let task = tauri::async_runtime::spawn(async move {
+                if let Err(e) = start_api_server(state, config, handle, shutdown).await {
+                    warn!("API server stopped: {:#}", e);
+                }
+            });
+            app.manage(ApiServerShutdown { signal, task: std::sync::Mutex::new(Some(task)) });
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // Files and folders dropped onto the window are opened as mounts; the outcome
+            // goes to the UI as `drop://opened`
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                let paths: Vec<String> = paths.iter().map(|path| path.to_string_lossy().into_owned()).collect();
+                let app = window.app_handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let state = app.state::<AppState>().inner().clone();
+                    let opened = open_dropped(&state, &app, paths).await;
+                    let _ = app.emit("drop://opened", &opened);
+                });
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             test_command,
             list_assets,
+            add_mount,
+            remove_mount,
+            get_mounts,
+            get_status,
+            get_diagnostics,
+            export_diagnostics,
+            set_log_level,
+            get_recent_logs,
+            extract_asset,
+            convert_texture,
+            extract_assets,
+            cancel_job,
+            reveal_pak,
+            get_pak_info,
+            extract_and_open,
+            start_scan,
+            cancel_scan,
+            get_asset_details,
+            get_asset_tree,
             get_preview,
+            cancel_preview,
+            get_previews,
             get_dependencies,
-            get_app_info
+            get_build_order,
+            analyze_removal_impact,
+            get_dependency_statistics,
+            export_dependencies,
+            import_dependencies,
+            compare_versions,
+            find_unused_assets,
+            validate_dependencies,
+            get_app_info,
+            get_api_token,
+            export_datatable,
+            get_preview_cache_stats,
+            clear_preview_cache,
+            clear_index_cache,
+            set_preview_cache_budget,
+            get_settings,
+            update_settings,
+            get_recent_folders,
+            pin_favorite,
+            unpin_favorite,
+            set_pak_key,
+            set_mappings_file,
+            get_mappings_info,
+            list_profiles,
+            create_profile,
+            activate_profile,
+            list_pak_keys,
+            test_key,
+            watch_folder,
+            unwatch_folder,
+            open_dropped_paths,
+            export_asset_list,
+            get_statistics,
+            analyze_storage,
+            get_chunk_summary,
+            audit_encryption,
+            get_localization_summary,
+            get_localization_entries,
+            find_duplicates
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                app.state::<ApiServerShutdown>().shutdown();
+                let _ = std::fs::remove_dir_all(preview_file_dir());
+                for folder in app.state::<AppState>().opened_files.lock().unwrap().drain(..) {
+                    let _ = std::fs::remove_dir_all(folder);
+                }
+            }
+        });
 }
 
-/// Starts the API server on localhost:3001
-async fn start_api_server() -> anyhow::Result<()> {
-    info!("Starting API server...");
+/// When the process started, for the uptime in status reports
+static PROCESS_STARTED: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
 
-    // Initialize application state with mock data
-    let state = AppState {
-        assets: Arc::new(Mutex::new(create_mock_assets())),
-        dependencies: Arc::new(Mutex::new(create_mock_dependencies())),
-    };
+/// How long in-flight API requests get to finish when the app exits
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
 
-    // Configure CORS for Tauri frontend
-    let cors = CorsLayer::new()
-        .allow_origin("tauri://localhost".parse::<HeaderValue>().unwrap())
-        .allow_origin("http://localhost:1420".parse::<HeaderValue>().unwrap()) // Tauri dev server
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_headers(Any);
+/// Stops the API server started in `setup`
+pub struct ApiServerShutdown {
+    signal: watch::Sender<bool>,
+    task: std::sync::Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+}
 
-    // Build the router with all endpoints
-    let app = Router::new()
-        .route("/assets", get(get_assets))
-        .route("/preview/:asset_name", get(get_preview_http))
-        .route("/dependencies", get(get_dependencies_http))
-        .route("/health", get(health_check))
-        .layer(cors)
-        .with_state(state);
+impl ApiServerShutdown {
+    /// Stops accepting connections and waits up to `SHUTDOWN_GRACE` for in-flight requests
+    fn shutdown(&self) {
+        let _ = self.signal.send(true);
+        if let Some(task) = self.task.lock().unwrap().take() {
+            tauri::async_runtime::block_on(async {
+                if tokio::time::timeout(SHUTDOWN_GRACE, task).await.is_err() {
+                    warn!("API server didn't stop within {:?}", SHUTDOWN_GRACE);
+                }
+            });
+        }
+    }
+}
 
-    // Start server on localhost:3001
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3001));
-    info!("API server listening on http://{}", addr);
+/// Starts the API server, serving the same state as the Tauri commands
+///
+/// Listens on the configured address (localhost:3001 by default), or the next free port
+/// when it's taken; the address in use is sent as the `api-server-ready` event. Runs
+/// until `shutdown` turns true, letting in-flight requests finish.
+async fn start_api_server(
+    state: AppState,
+    config: server_config::ServerConfig,
+    app: tauri::AppHandle,
+    shutdown: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    info!("Starting API server...");
+
+    let listener = config.bind().await?;
+    let addr = listener.local_addr()?;
+    let _ = app.emit("api-server-ready", ApiServerInfo { addr: addr.to_string(), port: addr.port() });
+    serve_api(listener, api_router(state, &config, addr), shutdown).await
+}
 
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    match axum::serve(listener, app.into_make_service()).await {
+/// Serves `router` on `listener` until `shutdown` turns true
+async fn serve_api(listener: tokio::net::TcpListener, router: Router, mut shutdown: watch::Receiver<bool>) -> anyhow::Result<()> {
+    info!("API server listening on http://{}", listener.local_addr()?);
+    let stopped = async move {
+        let _ = shutdown.wait_for(|stop| *stop).await;
+        info!("API server shutting down");
+    };
+    match axum::serve(listener, router.into_make_service()).with_graceful_shutdown(stopped).await {
         Ok(_) => {
             info!("API server shut down gracefully");
             Ok(())
@@ -102,293 +505,4555 @@ async fn start_api_server() -> anyhow::Result<()> {
     }
 }
 
-// ============================================================================
-// API HANDLERS
-// ============================================================================
+/// Every endpoint of the API server listening on `addr`
+fn api_router(state: AppState, config: &server_config::ServerConfig, addr: SocketAddr) -> Router {
+    let started = std::time::Instant::now();
+    let limits = Arc::new(route_limit::ApiLimits::new(&config.limits));
+    let limited = |limit: &Arc<route_limit::RouteLimit>| {
+        axum::middleware::from_fn_with_state(limit.clone(), route_limit::limit_concurrency)
+    };
+    let app = Router::new()
+        .route("/assets", get(get_assets))
+        .route("/assets/stream", get(stream_assets_http))
+        .route("/assets/detail", get(get_asset_details_http))
+        .route("/assets/tree", get(get_asset_tree_http))
+        .route("/preview", get(get_preview_by_path_http).layer(limited(&limits.preview)))
+        .route("/preview/raw", get(get_raw_preview_http).layer(limited(&limits.preview)))
+        .route("/previews", post(get_previews_http).layer(limited(&limits.preview)))
+        .route("/extract", get(extract_http).layer(limited(&limits.extract)))
+        .route("/preview/:asset_name", get(get_preview_http).layer(limited(&limits.preview)))
+        .route("/dependencies", get(get_dependencies_http))
+        .route("/dependencies/export", get(export_dependencies_http))
+        .route("/dependencies/validate", get(validate_dependencies_http))
+        .route("/dependencies/:asset", get(analyze_dependencies_http))
+        .route("/scan", post(start_scan_http).layer(limited(&limits.scan)))
+        .route("/scan/:job_id", get(get_scan_http))
+        .route("/mounts", get(get_mounts_http).post(add_mount_http))
+        .route("/mounts/:id", delete(remove_mount_http))
+        .route("/stats", get(get_statistics_http))
+        .route("/stats/storage", get(analyze_storage_http))
+        .route("/stats/chunks", get(get_chunk_summary_http))
+        .route("/audit/encryption", get(audit_encryption_http))
+        .route("/localization", get(get_localization_summary_http))
+        .route("/localization/:culture", get(get_localization_entries_http))
+        .route("/duplicates", get(find_duplicates_http))
+        .route("/ws", get(progress_ws));
+    let app = match &config.token {
+        Some(token) => app.route_layer(axum::middleware::from_fn_with_state(Arc::<str>::from(token.as_str()), require_token)),
+        None => app,
+    };
+    app.route("/health", get(move |state| health_check(state, addr, started, limits.clone())))
+        .route("/openapi.json", get(openapi_json))
+        .route("/docs", get(swagger_ui))
+        .layer(config.cors())
+        .layer(CompressionLayer::new().compress_when(DefaultPredicate::new().and(not_ranged)))
+        .with_state(state)
+}
 
-/// GET /assets - Returns list of all assets
-async fn get_assets(
-    axum::extract::State(state): axum::extract::State<AppState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Json<AssetsResponse>, StatusCode> {
-    let assets = state.assets.lock().await;
-    let mut filtered_assets = assets.clone();
+/// OpenAPI description of the HTTP API, generated from the handlers and the types they exchange
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    info(title = "PakSeek API", description = "Browse, preview and extract the assets of Unreal Engine paks"),
+    paths(
+        get_assets,
+        stream_assets_http,
+        get_asset_details_http,
+        get_asset_tree_http,
+        get_preview_by_path_http,
+        get_raw_preview_http,
+        get_previews_http,
+        extract_http,
+        get_preview_http,
+        get_dependencies_http,
+        export_dependencies_http,
+        validate_dependencies_http,
+        analyze_dependencies_http,
+        start_scan_http,
+        get_scan_http,
+        get_mounts_http,
+        add_mount_http,
+        remove_mount_http,
+        get_statistics_http,
+        analyze_storage_http,
+        get_chunk_summary_http,
+        audit_encryption_http,
+        get_localization_summary_http,
+        get_localization_entries_http,
+        find_duplicates_http,
+        progress_ws,
+        health_check,
+    ),
+    components(schemas(ProgressEvent, ProgressUpdate, StatusReport)),
+    modifiers(&BearerAuth),
+    security(("bearer" = [])),
+)]
+struct ApiDoc;
 
-    // Apply filters if provided
-    if let Some(asset_type) = params.get("type") {
-        filtered_assets.retain(|asset| asset.asset_type == *asset_type);
-    }
+/// Declares the `bearer` scheme the routes require when a token is configured
+struct BearerAuth;
 
-    if let Some(search) = params.get("search") {
-        let search_lower = search.to_lowercase();
-        filtered_assets.retain(|asset| {
-            asset.name.to_lowercase().contains(&search_lower) ||
-            asset.path.to_lowercase().contains(&search_lower)
-        });
+impl utoipa::Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme("bearer", SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()));
     }
+}
 
-    Ok(Json(AssetsResponse {
-        assets: filtered_assets.clone(),
-        total: assets.len(),
-        filtered: filtered_assets.len(),
-    }))
+/// GET /openapi.json - OpenAPI 3.1 description of the API
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(<ApiDoc as utoipa::OpenApi>::openapi())
 }
 
-/// GET /preview/{asset_name} - Returns preview data for an asset
-async fn get_preview_http(
-    Path(asset_name): Path<String>,
-    axum::extract::State(state): axum::extract::State<AppState>,
-) -> Result<Json<PreviewResponse>, StatusCode> {
-    let assets = state.assets.lock().await;
-    
-    if let Some(asset) = assets.iter().find(|a| a.name == asset_name) {
-        let preview_data = generate_preview_data(asset).await;
-        Ok(Json(preview_data))
-    } else {
-        Err(StatusCode::NOT_FOUND)
+/// GET /docs - Swagger UI for /openapi.json, loaded from a CDN
+async fn swagger_ui() -> axum::response::Html<&'static str> {
+    axum::response::Html(SWAGGER_UI)
+}
+
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>PakSeek API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui", persistAuthorization: true });
+  </script>
+</body>
+</html>
+"##;
+
+/// Lets requests through only with `Authorization: Bearer <token>`, answering 401 otherwise
+///
+/// WebSocket upgrades and `GET /preview/raw` may pass `?token=...` instead, since browsers
+/// can't add headers to them or to `<img>` loads of `PreviewData::Url` links.
+async fn require_token(
+    axum::extract::State(token): axum::extract::State<Arc<str>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    if presented_token(&request).is_some_and(|presented| tokens_equal(presented.as_bytes(), token.as_bytes())) {
+        return next.run(request).await;
     }
+    let error = ApiError::new(ErrorCode::Unauthorized, "Missing or invalid API token");
+    ([(header::WWW_AUTHENTICATE, "Bearer")], error).into_response()
 }
 
-/// GET /dependencies - Returns asset dependency mapping
-async fn get_dependencies_http(
-    axum::extract::State(state): axum::extract::State<AppState>,
-) -> Result<Json<DependencyResponse>, StatusCode> {
-    let dependencies = state.dependencies.lock().await;
-    Ok(Json(DependencyResponse {
-        dependencies: dependencies.clone(),
-    }))
+/// The bearer token of a request, or the `token` query parameter of a WebSocket upgrade
+/// or a raw preview
+fn presented_token(request: &axum::extract::Request) -> Option<std::borrow::Cow<'_, str>> {
+    let headers = request.headers();
+    let is_upgrade = headers.get(header::UPGRADE).is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"websocket"));
+    let is_raw_preview = request.method() == axum::http::Method::GET && request.uri().path() == "/preview/raw";
+    bearer_token(headers).map(std::borrow::Cow::Borrowed).or_else(|| {
+        let query = request.uri().query().filter(|_| is_upgrade || is_raw_preview)?;
+        let token = query.split('&').find_map(|pair| pair.strip_prefix("token="))?;
+        percent_encoding::percent_decode_str(token).decode_utf8().ok()
+    })
 }
 
-/// GET /health - Health check endpoint
-async fn health_check() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "status": "healthy",
-        "timestamp": chrono::Utc::now(),
-        "version": env!("CARGO_PKG_VERSION")
-    }))
+/// The token of an `Authorization: Bearer <token>` header
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Compares without stopping at the first difference, so timing doesn't leak the token
+fn tokens_equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Leaves downloads that support ranges (`GET /extract`) uncompressed, so byte offsets
+/// keep referring to the entry
+fn not_ranged(_: StatusCode, _: axum::http::Version, headers: &HeaderMap, _: &axum::http::Extensions) -> bool {
+    !headers.contains_key(header::ACCEPT_RANGES)
 }
 
 // ============================================================================
-// TAURI COMMANDS (for frontend integration)
+// API HANDLERS
 // ============================================================================
 
-/// Simple test command to verify frontend-backend communication
-#[tauri::command]
-async fn test_command(message: String) -> Result<String, String> {
-    eprintln!("=== DEBUG: test_command called with message: {}", message);
-    Ok(format!("Backend received: {}", message))
+/// Whether the client's `If-None-Match` already names `etag`
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    let strip_weak = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = strip_weak(etag);
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || strip_weak(tag) == etag)
 }
 
-/// Tauri command to list assets with optional filtering
-#[tauri::command]
-async fn list_assets(
+/// `304 Not Modified` for `etag`
+fn not_modified(etag: String) -> Response {
+    (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response()
+}
+
+/// Assets per page when no limit is given
+const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// Largest page a client can ask for
+const MAX_PAGE_SIZE: usize = 5000;
+
+/// Ordering and page of an asset listing
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AssetQuery {
+    offset: Option<usize>,
+    /// Page size (default 500, at most 5000)
+    limit: Option<usize>,
+    /// `name`, `size`, `type` or `path`, with `:desc` for descending
+    sort: Option<String>,
+}
+
+/// Filters of an asset listing; an asset has to pass every one given
+#[derive(Debug, Clone, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AssetFilter {
+    #[serde(rename = "type")]
     asset_type: Option<String>,
+    /// Comma-separated types, any of which matches (`texture,static_mesh`); class names
+    /// and older ids (`Texture2D`, `mesh`) are read as the type they stand for
+    types: Option<String>,
+    /// Matched against name and path, case-insensitively; `tag:Key=Value` words in it
+    /// match asset registry tags instead (`tag:Key` for any value)
     search: Option<String>,
-    target_folder: Option<String>,
-) -> Result<AssetsResponse, String> {
-    eprintln!("=== DEBUG: list_assets command called!");
-    eprintln!("=== DEBUG: target_folder parameter: {:?}", target_folder);
-    eprintln!("=== DEBUG: asset_type parameter: {:?}", asset_type);
-    eprintln!("=== DEBUG: search parameter: {:?}", search);
-    
-    // Use provided folder or default to current directory
-    let folder = target_folder.unwrap_or_else(|| ".".to_string());
-    
-    eprintln!("=== DEBUG: Starting asset scan for path: {}", folder);
-    eprintln!("=== DEBUG: Current working directory: {:?}", std::env::current_dir());
-    
-    // Check if the path exists first
-    let path = std::path::Path::new(&folder);
-    eprintln!("=== DEBUG: Path exists: {}", path.exists());
-    eprintln!("=== DEBUG: Path is file: {}", path.is_file());
-    eprintln!("=== DEBUG: Path is dir: {}", path.is_dir());
-    
-    if !path.exists() {
-        let error_msg = format!("Path does not exist: {}", folder);
-        eprintln!("=== ERROR: {}", error_msg);
-        return Err(error_msg);
+    /// How `search` matches: `substring` (default), `regex` or `fuzzy`
+    search_mode: Option<search::SearchMode>,
+    /// Uncompressed size in bytes, inclusive
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    /// Compression method of the entry (`None`, `Zlib`, `Oodle`, ...), case-insensitively
+    compression: Option<String>,
+    encrypted: Option<bool>,
+    /// Full path or file name of the pak the asset is in
+    pak: Option<String>,
+    /// File extension, with or without the dot (`uasset`, `.ubulk`)
+    extension: Option<String>,
+    /// Chunk of the asset's pak (3 for `pakchunk3-Windows.pak`)
+    chunk: Option<u32>,
+}
+
+impl AssetFilter {
+    /// The filter with its strings normalized and its search compiled once, for matching
+    /// many assets; fails on an invalid regex
+    fn matcher(&self) -> Result<AssetMatcher, String> {
+        let types = self
+            .asset_type
+            .iter()
+            .map(String::as_str)
+            .chain(self.types.iter().flat_map(|types| types.split(',')))
+            .map(str::trim)
+            .filter(|asset_type| !asset_type.is_empty())
+            .map(AssetType::lookup)
+            .collect();
+        let search_mode = self.search_mode.unwrap_or_default();
+        let (tags, search) = match self.search.as_deref() {
+            Some(search) => search::split_tag_terms(search)?,
+            None => (Vec::new(), None),
+        };
+        // Paths are matched with "/" separators, so "UI\Icons" finds "UI/Icons"; regexes are left alone
+        let search = search
+            .as_deref()
+            .map(|search| match search_mode {
+                search::SearchMode::Regex => search::TextMatcher::new(search, search_mode),
+                _ => search::TextMatcher::new(&with_forward_slashes(search), search_mode),
+            })
+            .transpose()?;
+        Ok(AssetMatcher {
+            types,
+            search,
+            tags,
+            min_size: self.min_size,
+            max_size: self.max_size,
+            compression: self.compression.clone(),
+            encrypted: self.encrypted,
+            pak: self.pak.clone(),
+            extension: self.extension.as_deref().map(|extension| extension.trim_start_matches('.').to_string()),
+            chunk: self.chunk,
+        })
     }
-    
-    let pak_files = if path.is_file() && folder.to_lowercase().ends_with(".pak") {
-        // Single .pak file provided
-        eprintln!("=== DEBUG: Processing single .pak file: {}", folder);
-        vec![folder.clone()]
-    } else if path.is_dir() {
-        // Directory provided - scan for .pak files
-        eprintln!("=== DEBUG: Scanning directory for .pak files...");
-        eprintln!("=== DEBUG: Directory exists: {}", path.exists());
-        eprintln!("=== DEBUG: Directory readable: {:?}", std::fs::read_dir(&folder));
-        
-        match pak_parser::utils::find_pak_files(&folder).await {
-            Ok(files) => {
-                eprintln!("=== DEBUG: Successfully found {} .pak files", files.len());
-                files
-            },
-            Err(e) => {
-                let error_msg = format!("Failed to scan directory '{}': {}", folder, e);
-                eprintln!("=== ERROR: {}", error_msg);
-                eprintln!("=== DEBUG: Attempting manual directory scan...");
-                
-                // Fallback: try manual directory scan
-                match std::fs::read_dir(&folder) {
-                    Ok(entries) => {
-                        let mut manual_pak_files = Vec::new();
-                        for entry in entries {
-                            if let Ok(entry) = entry {
-                                let entry_path = entry.path();
-                                eprintln!("=== DEBUG: Found file: {:?}", entry_path);
-                                if let Some(ext) = entry_path.extension() {
-                                    if ext == "pak" {
-                                        if let Some(path_str) = entry_path.to_str() {
-                                            manual_pak_files.push(path_str.to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        eprintln!("=== DEBUG: Manual scan found {} .pak files", manual_pak_files.len());
-                        manual_pak_files
-                    },
-                    Err(dir_err) => {
-                        let final_error = format!("Failed to read directory '{}': {}", folder, dir_err);
-                        eprintln!("=== ERROR: {}", final_error);
-                        return Err(final_error);
+}
+
+/// A compiled `AssetFilter`
+struct AssetMatcher {
+    /// Any of these; empty for any type
+    types: Vec<AssetType>,
+    /// Matched against names and paths
+    search: Option<search::TextMatcher>,
+    /// Registry tags the asset needs, all of them
+    tags: Vec<search::TagTerm>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    compression: Option<String>,
+    encrypted: Option<bool>,
+    pak: Option<String>,
+    extension: Option<String>,
+    chunk: Option<u32>,
+}
+
+impl AssetMatcher {
+    fn matches(&self, asset: &Asset) -> bool {
+        self.score(asset).is_some()
+    }
+
+    /// Search score of a matching asset (0 unless fuzzy), None when it doesn't match
+    ///
+    /// Cheap checks come first, so most rejected assets are never searched.
+    fn score(&self, asset: &Asset) -> Option<i64> {
+        if !(self.matches_type(asset) && self.matches_pak(asset) && self.matches_extension(asset) && self.matches_chunk(asset)) {
+            return None;
+        }
+        self.score_unfaceted(asset)
+    }
+
+    /// Search score of an asset passing every filter but the faceted ones (type, pak,
+    /// extension and chunk), None when it doesn't
+    fn score_unfaceted(&self, asset: &Asset) -> Option<i64> {
+        let passes = self.min_size.is_none_or(|min| asset.size >= min)
+            && self.max_size.is_none_or(|max| asset.size <= max)
+            && self.encrypted.is_none_or(|encrypted| asset.is_encrypted.unwrap_or(false) == encrypted)
+            && self.compression.as_ref().is_none_or(|compression| {
+                asset.compression_method.as_ref().is_some_and(|method| method.eq_ignore_ascii_case(compression))
+            })
+            && self.tags.iter().all(|term| term.matches(&asset.tags));
+        if !passes {
+            return None;
+        }
+        match &self.search {
+            Some(search) => search.score([asset.name.as_str(), &with_forward_slashes(&asset.path)]),
+            None => Some(0),
+        }
+    }
+
+    fn matches_type(&self, asset: &Asset) -> bool {
+        self.types.is_empty() || self.types.contains(&asset.asset_type)
+    }
+
+    fn matches_pak(&self, asset: &Asset) -> bool {
+        self.pak.as_ref().is_none_or(|pak| {
+            asset.pak_file.as_deref().is_some_and(|pak_file| pak_file == pak || file_name_str(pak_file) == pak)
+        })
+    }
+
+    /// Whether the asset or one of its parts has the extension asked for
+    fn matches_extension(&self, asset: &Asset) -> bool {
+        self.extension.as_ref().is_none_or(|extension| asset.has_extension(extension))
+    }
+
+    fn matches_chunk(&self, asset: &Asset) -> bool {
+        self.chunk.is_none_or(|chunk| asset.chunk_id == Some(chunk))
+    }
+
+    fn is_fuzzy(&self) -> bool {
+        self.search.as_ref().is_some_and(search::TextMatcher::is_fuzzy)
+    }
+
+    /// The filter as the SQL index takes it; None for regex and fuzzy searches, tag terms
+    /// and chunks, which only the in-memory path does
+    fn sql_filter(&self) -> Option<sql_index::Filter> {
+        if !self.tags.is_empty() || self.chunk.is_some() {
+            return None;
+        }
+        let search = match &self.search {
+            Some(search::TextMatcher::Substring(term)) => Some(term.clone()),
+            Some(_) => return None,
+            None => None,
+        };
+        Some(sql_index::Filter {
+            types: self.types.iter().map(|asset_type| asset_type.as_str().to_string()).collect(),
+            search,
+            min_size: self.min_size,
+            max_size: self.max_size,
+            compression: self.compression.clone(),
+            encrypted: self.encrypted,
+            pak: self.pak.clone(),
+            extension: self.extension.as_deref().map(str::to_ascii_lowercase),
+        })
+    }
+}
+
+/// Assets of a listing with their search scores (0 unless the search is fuzzy)
+type ScoredAssets<'a> = Vec<(&'a Arc<Asset>, i64)>;
+
+/// Counts of a listing's assets by type, pak, extension and chunk, biggest first
+///
+/// Each dimension is counted with every filter applied except its own, so the counts say
+/// how many assets picking that type (pak, extension, chunk) instead would list. Paks are
+/// keyed by file name, extensions in lowercase without the dot and chunks by number, the
+/// way the `pak`, `extension` and `chunk` filters take them.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, utoipa::ToSchema)]
+pub struct AssetFacets {
+    pub by_type: Vec<(String, usize)>,
+    pub by_pak: Vec<(String, usize)>,
+    pub by_extension: Vec<(String, usize)>,
+    /// Assets of paks outside the `pakchunkN` naming aren't counted
+    #[serde(default)]
+    pub by_chunk: Vec<(String, usize)>,
+}
+
+/// `AssetFacets` being counted, keyed by strings borrowed from the assets
+#[derive(Default)]
+struct FacetCounts<'a> {
+    by_type: HashMap<&'a str, usize>,
+    by_pak: HashMap<&'a str, usize>,
+    by_extension: HashMap<Cow<'a, str>, usize>,
+    by_chunk: HashMap<u32, usize>,
+}
+
+impl<'a> FacetCounts<'a> {
+    fn count_type(&mut self, asset: &'a Asset) {
+        *self.by_type.entry(asset.asset_type.as_str()).or_default() += 1;
+    }
+
+    fn count_pak(&mut self, asset: &'a Asset) {
+        if let Some(pak_file) = &asset.pak_file {
+            *self.by_pak.entry(file_name_str(pak_file)).or_default() += 1;
+        }
+    }
+
+    /// Counts the asset once for each extension of its parts, as the `extension` filter
+    /// matches it by any of them
+    fn count_extension(&mut self, asset: &'a Asset) {
+        let mut extensions: Vec<Cow<'a, str>> = match asset.parts.as_slice() {
+            [] => extension_of(&asset.path).into_iter().map(ascii_lowercase).collect(),
+            parts => parts.iter().map(|part| ascii_lowercase(&part.extension)).collect(),
+        };
+        extensions.dedup();
+        for extension in extensions {
+            *self.by_extension.entry(extension).or_default() += 1;
+        }
+    }
+
+    fn count_chunk(&mut self, asset: &'a Asset) {
+        if let Some(chunk_id) = asset.chunk_id {
+            *self.by_chunk.entry(chunk_id).or_default() += 1;
+        }
+    }
+
+    fn finish(self) -> AssetFacets {
+        AssetFacets {
+            by_type: sorted_counts(self.by_type),
+            by_pak: sorted_counts(self.by_pak),
+            by_extension: sorted_counts(self.by_extension),
+            by_chunk: chunk_counts(self.by_chunk),
+        }
+    }
+}
+
+/// `sorted_counts` of counts by chunk number
+fn chunk_counts(counts: HashMap<u32, usize>) -> Vec<(String, usize)> {
+    sorted_counts(counts.into_iter().map(|(chunk_id, count)| (chunk_id.to_string(), count)).collect::<HashMap<_, _>>())
+}
+
+/// Counts biggest first, ties by key
+fn sorted_counts<K: AsRef<str>>(counts: HashMap<K, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().map(|(key, count)| (key.as_ref().to_string(), count)).collect();
+    counts.sort_by(|(a_key, a), (b_key, b)| b.cmp(a).then_with(|| natural_cmp(a_key, b_key)));
+    counts
+}
+
+/// What a listing can be sorted by
+#[derive(Debug, Clone, Copy)]
+enum SortField {
+    Name,
+    Size,
+    Type,
+    Path,
+}
+
+impl SortField {
+    fn as_str(self) -> &'static str {
+        match self {
+            SortField::Name => "name",
+            SortField::Size => "size",
+            SortField::Type => "type",
+            SortField::Path => "path",
+        }
+    }
+
+    fn compare(self) -> fn(&Asset, &Asset) -> std::cmp::Ordering {
+        match self {
+            SortField::Name => |a, b| natural_cmp(&a.name, &b.name),
+            SortField::Size => |a, b| a.size.cmp(&b.size),
+            SortField::Type => |a, b| a.asset_type.as_str().cmp(b.asset_type.as_str()),
+            SortField::Path => |a, b| natural_cmp(&a.path, &b.path),
+        }
+    }
+}
+
+impl AssetQuery {
+    /// Filters, sorts and pages `assets`; `total`, `filtered` and `facets` count the full set
+    ///
+    /// Only references to the matching assets are collected, and the page shares its assets
+    /// with the index.
+    /// Fuzzy searches come back best match first unless `sort` says otherwise, with scores.
+    fn apply(&self, filter: &AssetFilter, assets: &[Arc<Asset>]) -> Result<AssetsResponse, String> {
+        let mut facets = FacetCounts::default();
+        let (filtered_assets, fuzzy) = self.matching(filter, assets, Some(&mut facets))?;
+        let (offset, limit) = self.page();
+        let filtered = filtered_assets.len();
+        let listed_tags = settings::global().get().listed_tags;
+        let page = filtered_assets.into_iter().skip(offset).take(limit);
+        let (page, scores): (Vec<Arc<Asset>>, Vec<i64>) =
+            page.map(|(asset, score)| (asset.with_listed_tags(&listed_tags), score)).unzip();
+        Ok(AssetsResponse {
+            assets: page,
+            total: assets.len(),
+            filtered,
+            offset,
+            limit,
+            scores: fuzzy.then_some(scores),
+            facets: facets.finish(),
+        })
+    }
+
+    /// `offset` and `limit` with their defaults
+    fn page(&self) -> (usize, usize) {
+        (self.offset.unwrap_or(0), self.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE))
+    }
+
+    /// The field and direction `sort` asks for, None when it's not given
+    fn sort_order(&self) -> Result<Option<(SortField, bool)>, String> {
+        let Some(sort) = &self.sort else {
+            return Ok(None);
+        };
+        let (field, descending) = match sort.split_once(':') {
+            Some((field, "desc")) => (field, true),
+            Some((field, "asc")) => (field, false),
+            Some(_) => return Err(format!("Invalid sort order: {}", sort)),
+            None => (sort.as_str(), false),
+        };
+        let field = match field {
+            "name" => SortField::Name,
+            "size" => SortField::Size,
+            "type" => SortField::Type,
+            "path" => SortField::Path,
+            _ => return Err(format!("Invalid sort field: {}", field)),
+        };
+        Ok(Some((field, descending)))
+    }
+
+    /// The response for a page the SQL index found in `assets` with the `pak` filter given;
+    /// None when one of its ids isn't there
+    ///
+    /// Chunks follow from paks, so they're counted from the pak counts, or, when a pak is
+    /// asked for, are that pak's chunk.
+    fn sql_response(&self, page: sql_index::Page, assets: &AssetIndex, pak: Option<&str>) -> Option<AssetsResponse> {
+        let (offset, limit) = self.page();
+        let listed_tags = settings::global().get().listed_tags;
+        let page_assets = page
+            .ids
+            .iter()
+            .map(|id| assets.with_id(id).map(|asset| asset.with_listed_tags(&listed_tags)))
+            .collect::<Option<Vec<_>>>()?;
+        let mut by_chunk: HashMap<u32, usize> = HashMap::new();
+        match pak {
+            Some(pak) => {
+                if let Some(chunk_id) = pak_parser::chunk_id(pak).filter(|_| page.filtered > 0) {
+                    by_chunk.insert(chunk_id, page.filtered);
+                }
+            }
+            None => {
+                for (pak_name, count) in &page.by_pak {
+                    if let Some(chunk_id) = pak_parser::chunk_id(pak_name) {
+                        *by_chunk.entry(chunk_id).or_default() += count;
                     }
                 }
             }
         }
-    } else {
-        let error_msg = format!("Path is neither a .pak file nor a directory: {}", folder);
-        eprintln!("=== ERROR: {}", error_msg);
-        return Err(error_msg);
-    };
-    
-    eprintln!("=== DEBUG: Found {} .pak files: {:?}", pak_files.len(), pak_files);
-    
-    if pak_files.is_empty() {
-        eprintln!("=== DEBUG: No .pak files found, returning mock data for development");
-        // Return mock data if no pak files found (for development)
-        let mock_assets = create_mock_assets();
-        return Ok(AssetsResponse {
-            assets: mock_assets.clone(),
-            total: mock_assets.len(),
-            filtered: mock_assets.len(),
-        });
+        Some(AssetsResponse {
+            assets: page_assets,
+            total: assets.len(),
+            filtered: page.filtered,
+            offset,
+            limit,
+            scores: None,
+            facets: AssetFacets {
+                by_type: sorted_counts(page.by_type),
+                by_pak: sorted_counts(page.by_pak),
+                by_extension: sorted_counts(page.by_extension),
+                by_chunk: chunk_counts(by_chunk),
+            },
+        })
     }
-    
-    let mut all_assets = Vec::new();
-    
-    // Parse each .pak file and extract asset information (without size limits)
-    for pak_path in &pak_files {
-        eprintln!("=== DEBUG: Processing .pak file: {}", pak_path);
-        
-        // Check file size for logging but don't limit it
-        if let Ok(metadata) = std::fs::metadata(pak_path) {
-            let file_size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-            eprintln!("=== DEBUG: .pak file size: {:.2} MB", file_size_mb);
-        }
-        
-        let parser = pak_parser::PakParser::new(pak_path);
-        match parser.parse().await {
-            Ok(pak_file) => {
-                eprintln!("=== DEBUG: Successfully parsed {} with {} entries", pak_path, pak_file.entries.len());
-                // Convert pak entries to our Asset format
-                for entry in pak_file.entries {
-                    // Determine asset type from file extension
-                    let determined_type = determine_asset_type(&entry.filename);
-                    
-                    let asset = Asset {
-                        name: extract_asset_name(&entry.filename),
-                        path: entry.filename.clone(),
-                        asset_type: determined_type,
-                        size: entry.uncompressed_size,
-                        pak_file: Some(pak_path.clone()),
-                        compressed_size: Some(entry.compressed_size),
-                        compression_method: Some(format!("{:?}", entry.compression_method)),
-                        is_encrypted: Some(entry.is_encrypted),
-                        hash: entry.sha1_hash.map(|h| h.into_bytes()),
-                        last_modified: chrono::Utc::now(), // Default since pak files don't store modification times
-                        metadata: None, // Will be populated later if needed
-                    };
-                    
-                    all_assets.push(asset);
+
+    /// The assets passing `filter` in the requested order, with their search scores, and
+    /// whether the search was fuzzy; `offset` and `limit` aren't applied
+    ///
+    /// With `facets`, counts them in the same pass: an asset failing only one of the type,
+    /// pak, extension and chunk filters still counts towards that dimension.
+    fn matching<'a>(
+        &self,
+        filter: &AssetFilter,
+        assets: &'a [Arc<Asset>],
+        mut facets: Option<&mut FacetCounts<'a>>,
+    ) -> Result<(ScoredAssets<'a>, bool), String> {
+        let matcher = filter.matcher()?;
+        let mut filtered_assets: ScoredAssets<'a> = Vec::new();
+        for asset in assets {
+            let Some(facets) = facets.as_deref_mut() else {
+                if let Some(score) = matcher.score(asset) {
+                    filtered_assets.push((asset, score));
                 }
-            },
-            Err(e) => {
-                eprintln!("=== DEBUG: Failed to parse .pak file {}: {}", pak_path, e);
-                // Continue processing other pak files instead of failing completely
+                continue;
+            };
+            let passed = [
+                matcher.matches_type(asset),
+                matcher.matches_pak(asset),
+                matcher.matches_extension(asset),
+                matcher.matches_chunk(asset),
+            ];
+            let misses = passed.iter().filter(|passed| !**passed).count();
+            if misses > 1 {
+                continue;
+            }
+            let Some(score) = matcher.score_unfaceted(asset) else {
+                continue;
+            };
+            // Counted towards a dimension when every other one passes
+            let counted = |dimension: usize| misses == 0 || !passed[dimension];
+            if counted(0) {
+                facets.count_type(asset);
+            }
+            if counted(1) {
+                facets.count_pak(asset);
             }
+            if counted(2) {
+                facets.count_extension(asset);
+            }
+            if counted(3) {
+                facets.count_chunk(asset);
+            }
+            if misses == 0 {
+                filtered_assets.push((asset, score));
+            }
+        }
+
+        if let Some((field, descending)) = self.sort_order()? {
+            let compare = field.compare();
+            // Stable either way: ties keep their scan order
+            if descending {
+                filtered_assets.sort_by(|(a, _), (b, _)| compare(b, a));
+            } else {
+                filtered_assets.sort_by(|(a, _), (b, _)| compare(a, b));
+            }
+        } else if matcher.is_fuzzy() {
+            filtered_assets.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        }
+        Ok((filtered_assets, matcher.is_fuzzy()))
+    }
+}
+
+/// Compares names with digit runs as numbers, so "Rock_2" sorts before "Rock_10"
+///
+/// Letters compare case-insensitively; names equal under those rules fall back to a
+/// plain comparison so the order is total.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let (mut a_rest, mut b_rest) = (a, b);
+    loop {
+        let (Some(x), Some(y)) = (a_rest.chars().next(), b_rest.chars().next()) else {
+            return a_rest.len().cmp(&b_rest.len()).then_with(|| a.cmp(b));
+        };
+
+        let ordering = if x.is_ascii_digit() && y.is_ascii_digit() {
+            let a_end = a_rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(a_rest.len());
+            let b_end = b_rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(b_rest.len());
+            let a_number = a_rest[..a_end].trim_start_matches('0');
+            let b_number = b_rest[..b_end].trim_start_matches('0');
+            a_rest = &a_rest[a_end..];
+            b_rest = &b_rest[b_end..];
+            a_number.len().cmp(&b_number.len()).then_with(|| a_number.cmp(b_number))
+        } else {
+            a_rest = &a_rest[x.len_utf8()..];
+            b_rest = &b_rest[y.len_utf8()..];
+            x.to_lowercase().cmp(y.to_lowercase())
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// GET /assets - Returns a page of assets
+///
+/// Query params: `?type=Texture2D&search=rock` to filter (`search_mode=regex` or `fuzzy`
+/// to search differently, `tag:PrimaryAssetType=Map` words in `search` for registry tags),
+/// with `types`, `min_size`, `max_size`, `compression`,
+/// `encrypted`, `pak`, `extension` and `chunk` narrowing further (all must match); `?sort=name:desc` to order (name, size, type or path), `?offset=500&limit=500`
+/// to page. Assets carry only the registry tags in `Settings::listed_tags`; `GET
+/// /assets/detail` has them all. `facets` counts the filtered assets by type, pak,
+/// extension and chunk, each ignoring its own filter. Responses carry an ETag;
+/// `If-None-Match` with it gets 304 until the next scan.
+#[utoipa::path(
+    get, path = "/assets", tag = "assets",
+    params(AssetFilter, AssetQuery),
+    responses(
+        (status = 200, description = "A page of assets", body = AssetsResponse),
+        (status = 304, description = "Unchanged since the ETag in `If-None-Match`"),
+        (status = 400, description = "Invalid sort or search regex", body = ErrorResponse),
+    )
+)]
+async fn get_assets(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(filter): Query<AssetFilter>,
+    Query(query): Query<AssetQuery>,
+    RawQuery(raw_query): RawQuery,
+    headers: HeaderMap,
+) -> Result<Response, PakSeekError> {
+    let (assets, etag) = state.snapshot_with_etag("/assets", raw_query.as_deref()).await;
+    if etag_matches(&headers, &etag) {
+        return Ok(not_modified(etag));
+    }
+    let response = state.query_assets(&query, &filter, &assets).await.map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e))?;
+    Ok(([(header::ETAG, etag)], Json(response)).into_response())
+}
+
+/// GET /stats - Totals and per-type, per-pak and per-compression breakdowns of the loaded
+/// assets, with the largest ones
+///
+/// Responses carry an ETag; `If-None-Match` with it gets 304 until the next scan.
+#[utoipa::path(
+    get, path = "/stats", tag = "assets",
+    responses(
+        (status = 200, description = "Statistics of the loaded assets", body = statistics::ScanStatistics),
+        (status = 304, description = "Unchanged since the ETag in `If-None-Match`"),
+    )
+)]
+async fn get_statistics_http(axum::extract::State(state): axum::extract::State<AppState>, headers: HeaderMap) -> Response {
+    let (assets, etag) = state.snapshot_with_etag("/stats", None).await;
+    if etag_matches(&headers, &etag) {
+        return not_modified(etag);
+    }
+    ([(header::ETAG, etag)], Json(statistics::compute(&assets))).into_response()
+}
+
+/// Query of `GET /stats/storage`
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct StorageQuery {
+    /// Folder (or single pak) to scan and analyze instead of the loaded assets; the
+    /// listing is left as it is
+    folder: Option<String>,
+}
+
+/// GET /stats/storage - Raw and stored bytes, compression ratio, duplicate content and
+/// entry size histogram by asset type and extension, with the entries that are marked
+/// compressed but don't shrink
+///
+/// `?folder=` analyzes another install without loading it. Responses about the loaded
+/// assets carry an ETag; `If-None-Match` with it gets 304 until the next scan.
+#[utoipa::path(
+    get, path = "/stats/storage", tag = "assets",
+    params(StorageQuery),
+    responses(
+        (status = 200, description = "Storage breakdown", body = statistics::StorageReport),
+        (status = 304, description = "Unchanged since the ETag in `If-None-Match`"),
+        (status = 400, description = "The folder doesn't exist or has no paks", body = ErrorResponse),
+    )
+)]
+async fn analyze_storage_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(query): Query<StorageQuery>,
+    headers: HeaderMap,
+) -> Result<Response, PakSeekError> {
+    if let Some(folder) = &query.folder {
+        return Ok(Json(storage_report(&state, Some(folder)).await?).into_response());
+    }
+    let (assets, etag) = state.snapshot_with_etag("/stats/storage", None).await;
+    if etag_matches(&headers, &etag) {
+        return Ok(not_modified(etag));
+    }
+    Ok(([(header::ETAG, etag)], Json(statistics::analyze_storage(&assets))).into_response())
+}
+
+/// GET /stats/chunks - The loaded assets by `pakchunkN` chunk: paks, asset count, sizes and
+/// the top-level content folders each chunk holds
+///
+/// Assets of paks named otherwise come last, with a null `chunk_id`. Responses carry an
+/// ETag; `If-None-Match` with it gets 304 until the next scan.
+#[utoipa::path(
+    get, path = "/stats/chunks", tag = "assets",
+    responses(
+        (status = 200, description = "Chunks in order", body = Vec<statistics::ChunkSummary>),
+        (status = 304, description = "Unchanged since the ETag in `If-None-Match`"),
+    )
+)]
+async fn get_chunk_summary_http(axum::extract::State(state): axum::extract::State<AppState>, headers: HeaderMap) -> Response {
+    let (assets, etag) = state.snapshot_with_etag("/stats/chunks", None).await;
+    if etag_matches(&headers, &etag) {
+        return not_modified(etag);
+    }
+    ([(header::ETAG, etag)], Json(statistics::chunk_summary(&assets))).into_response()
+}
+
+/// Query of `GET /audit/encryption`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct EncryptionAuditQuery {
+    /// Folder of .pak and .utoc files, or one of them
+    folder: String,
+}
+
+/// GET /audit/encryption?folder=... - Which containers of a folder are encrypted, the key
+/// GUIDs they need and whether the configured keys open them, without scanning
+///
+/// Only footers and TOC headers are read, plus the encrypted indexes to test keys against.
+/// Containers that can't be read are listed with their `error`.
+#[utoipa::path(
+    get, path = "/audit/encryption", tag = "scans",
+    params(EncryptionAuditQuery),
+    responses(
+        (status = 200, description = "Encryption of each container", body = encryption_audit::EncryptionAudit),
+        (status = 400, description = "The folder doesn't exist or has no containers", body = ErrorResponse),
+    )
+)]
+async fn audit_encryption_http(Query(query): Query<EncryptionAuditQuery>) -> Result<Json<encryption_audit::EncryptionAudit>, PakSeekError> {
+    Ok(Json(encryption_audit::audit(&query.folder, &settings::global().get()).await?))
+}
+
+/// `statistics::analyze_storage` of the assets in `folder` (scanned, or read from the scan
+/// cache), or of the loaded ones when None
+async fn storage_report(state: &AppState, folder: Option<&str>) -> Result<statistics::StorageReport, PakSeekError> {
+    let Some(folder) = folder else {
+        return Ok(statistics::analyze_storage(&state.snapshot().await));
+    };
+    let context = serde_json::json!({ "path": folder });
+    let pak_files = find_scan_targets(folder)
+        .await
+        .map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e).with_context(context.clone()))?;
+    if pak_files.is_empty() {
+        let message = format!("No .pak files found in {}", folder);
+        return Err(ApiError::new(ErrorCode::NoPaksFound, message).with_context(context).into());
+    }
+    let (assets, _) = load_scan(&pak_files, false, &CancellationToken::new(), |_, _| {}).await.ok_or(PakSeekError::Cancelled)?;
+    Ok(statistics::analyze_storage(&preview::share_assets(assets)))
+}
+
+/// Query of `GET /localization`
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct LocalizationQuery {
+    /// Culture the others are compared with (default `en` when present, otherwise the
+    /// culture with the most strings)
+    source: Option<String>,
+}
+
+/// GET /localization - Cultures of the .locres files in the loaded paks, with their string
+/// counts and the keys of the source culture each one lacks
+///
+/// Files that can't be read or parsed are listed in `errors`; the others are still counted.
+#[utoipa::path(
+    get, path = "/localization", tag = "assets",
+    params(LocalizationQuery),
+    responses(
+        (status = 200, description = "Localization coverage per culture", body = localization::LocalizationSummary),
+        (status = 400, description = "The source culture has no .locres files", body = ErrorResponse),
+    )
+)]
+async fn get_localization_summary_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(query): Query<LocalizationQuery>,
+) -> Result<Json<localization::LocalizationSummary>, PakSeekError> {
+    Ok(Json(localization_summary(&state, query.source.as_deref()).await?))
+}
+
+/// Query of `GET /localization/{culture}`
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct LocalizationEntriesQuery {
+    /// Matched against key and value, case-insensitively
+    search: Option<String>,
+    offset: Option<usize>,
+    /// Page size (default 200, at most 5000)
+    limit: Option<usize>,
+}
+
+/// GET /localization/{culture} - A page of a culture's strings, sorted by target, namespace
+/// and key
+#[utoipa::path(
+    get, path = "/localization/{culture}", tag = "assets",
+    params(("culture" = String, Path, description = "Culture folder of the .locres files (`en`, `pt-BR`)"), LocalizationEntriesQuery),
+    responses(
+        (status = 200, description = "The culture's strings", body = localization::CultureEntries),
+        (status = 404, description = "No .locres files for the culture", body = ErrorResponse),
+    )
+)]
+async fn get_localization_entries_http(
+    Path(culture): Path<String>,
+    Query(query): Query<LocalizationEntriesQuery>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<localization::CultureEntries>, PakSeekError> {
+    Ok(Json(localization_entries(&state, &culture, &query).await?))
+}
+
+/// `localization::summarize` of the .locres files in the loaded paks
+async fn localization_summary(
+    state: &AppState,
+    source: Option<&str>,
+) -> Result<localization::LocalizationSummary, PakSeekError> {
+    let (files, errors) = localization::read_locres(&state.snapshot().await, None).await;
+    Ok(localization::summarize(&files, source, errors)?)
+}
+
+/// `localization::culture_entries` of the loaded .locres files of `culture`
+async fn localization_entries(
+    state: &AppState,
+    culture: &str,
+    query: &LocalizationEntriesQuery,
+) -> Result<localization::CultureEntries, PakSeekError> {
+    let (files, errors) = localization::read_locres(&state.snapshot().await, Some(culture)).await;
+    if files.is_empty() && errors.is_empty() {
+        let message = format!("No .locres files for culture {}", culture);
+        return Err(ApiError::new(ErrorCode::AssetNotFound, message).with_context(serde_json::json!({ "culture": culture })).into());
+    }
+    Ok(localization::culture_entries(
+        &files,
+        culture,
+        query.search.as_deref(),
+        query.offset.unwrap_or(0),
+        query.limit.unwrap_or(localization::DEFAULT_ENTRY_LIMIT),
+        errors,
+    ))
+}
+
+/// Query of `GET /duplicates`
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DuplicatesQuery {
+    /// Smallest uncompressed size considered, in bytes
+    min_size: Option<u64>,
+    /// Bytes that may be read to hash entries the index has no hash for (default 0)
+    read_budget: Option<u64>,
+}
+
+/// GET /duplicates - Loaded assets with the same size and SHA-1, most reclaimable space first
+#[utoipa::path(
+    get, path = "/duplicates", tag = "assets",
+    params(DuplicatesQuery),
+    responses((status = 200, description = "Groups of identical assets", body = duplicates::DuplicateReport))
+)]
+async fn find_duplicates_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(query): Query<DuplicatesQuery>,
+) -> Result<Json<duplicates::DuplicateReport>, PakSeekError> {
+    // A client hanging up drops the handler, which stops the hashing with it
+    let cancel = CancellationToken::new();
+    Ok(Json(find_duplicates_in(&state, query.min_size.unwrap_or(0), query.read_budget.unwrap_or(0), &cancel).await?))
+}
+
+/// Groups the loaded assets by size and SHA-1
+///
+/// Same-size entries the index has no full hash for are read and hashed, largest first,
+/// while `read_budget` bytes allow. The listing is only locked to pick the candidates.
+/// Fails with `PakSeekError::Cancelled` when `cancel` fires while hashing.
+async fn find_duplicates_in(
+    state: &AppState,
+    min_size: u64,
+    read_budget: u64,
+    cancel: &CancellationToken,
+) -> Result<duplicates::DuplicateReport, PakSeekError> {
+    let mut candidates = duplicates::same_size_candidates(&state.assets.lock().await, min_size);
+
+    let hashed_sizes: std::collections::HashSet<u64> =
+        candidates.iter().filter(|candidate| candidate.sha1.is_some()).map(|candidate| candidate.size).collect();
+    let mut unhashed: Vec<&mut duplicates::Candidate> =
+        candidates.iter_mut().filter(|candidate| candidate.sha1.is_none() && candidate.pak_file.is_some()).collect();
+    unhashed.sort_by_key(|candidate| std::cmp::Reverse(candidate.size));
+    let (mut hashed_on_demand, mut bytes_read) = (0, 0u64);
+    for same_size in unhashed.chunk_by_mut(|a, b| a.size == b.size) {
+        // Reading one copy only pays off if another of its size has a hash already
+        let size = same_size[0].size;
+        let needed: u64 = if hashed_sizes.contains(&size) { 1 } else { 2 };
+        if (same_size.len() as u64) < needed || bytes_read + size * needed > read_budget {
+            continue;
+        }
+        for candidate in same_size.iter_mut() {
+            if bytes_read + size > read_budget {
+                break;
+            }
+            let pak_file = candidate.pak_file.clone().unwrap_or_default();
+            match hash_entry(&candidate.path, &pak_file, cancel).await {
+                Ok((sha1, read)) => {
+                    candidate.sha1 = Some(sha1);
+                    hashed_on_demand += 1;
+                    bytes_read += read;
+                }
+                Err(PakSeekError::Cancelled) => return Err(PakSeekError::Cancelled),
+                Err(e) => info!("Not hashing {} in {}: {}", candidate.path, pak_file, e),
+            }
+        }
+    }
+
+    let unhashed = candidates.iter().filter(|candidate| candidate.sha1.is_none()).count();
+    let groups = duplicates::group(candidates);
+    Ok(duplicates::DuplicateReport {
+        reclaimable_bytes: groups.iter().map(|group| group.reclaimable_bytes).sum(),
+        groups,
+        hashed_on_demand,
+        bytes_read,
+        unhashed,
+    })
+}
+
+/// SHA-1 of an entry's extracted bytes as lowercase hex, and how many bytes were read
+///
+/// Fails with `PakSeekError::Cancelled` when `cancel` fires, checked between blocks.
+async fn hash_entry(path: &str, pak_file: &str, cancel: &CancellationToken) -> Result<(String, u64), PakSeekError> {
+    use sha1::{Digest, Sha1};
+    use tokio::io::AsyncReadExt;
+
+    let parser = PakParser::new(pak_file);
+    let entry = extractable_entry(&parser, path, cancel).await?;
+    let failed = |e: &dyn std::fmt::Display| {
+        ApiError::new(ErrorCode::ExtractFailed, format!("Failed to read the entry: {:#}", e))
+            .with_context(serde_json::json!({ "path": path, "pak_file": pak_file }))
+    };
+    let mut reader = parser.extract_stream(path, 0..entry.uncompressed_size).await.map_err(|e| failed(&e))?;
+    let mut hasher = Sha1::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut read_total = 0u64;
+    loop {
+        Cancelled::check(cancel)?;
+        let read = reader.read(&mut buffer).await.map_err(|e| failed(&e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        read_total += read as u64;
+    }
+    let sha1 = pak_parser::to_hex(&hasher.finalize());
+    Ok((sha1, read_total))
+}
+
+/// Query of `GET /assets/stream`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AssetStreamQuery {
+    /// Pak or folder to scan on the fly instead of listing the loaded assets
+    path: Option<String>,
+    /// With `path`, also read class and tags from each package
+    #[serde(default)]
+    deep: bool,
+}
+
+/// Bytes buffered between the task producing a stream and the client; producers wait
+/// while it's full, so a slow client holds the listing back instead of filling memory
+const STREAM_BUFFER: usize = 64 * 1024;
+
+/// Loaded assets serialized per write to the stream
+const STREAM_CHUNK: usize = 1000;
+
+/// GET /assets/stream - Streams every matching asset as newline-delimited JSON
+///
+/// One `Asset` object per line, in scan order (fuzzy searches aren't ranked here), with
+/// the filters of `GET /assets` applied before sending. With `?path=...`, the paks there are parsed one at a time and their assets
+/// streamed as each is done, without touching the loaded index; paks that fail to parse
+/// are logged and skipped. A scan replacing the index mid-stream changes what the rest of
+/// the stream lists.
+#[utoipa::path(
+    get, path = "/assets/stream", tag = "assets",
+    params(AssetFilter, AssetStreamQuery),
+    responses(
+        (status = 200, description = "One JSON `Asset` per line", body = Asset, content_type = "application/x-ndjson"),
+        (status = 400, description = "Invalid path or no paks in it", body = ErrorResponse),
+    )
+)]
+async fn stream_assets_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(filter): Query<AssetFilter>,
+    Query(query): Query<AssetStreamQuery>,
+) -> Result<Response, PakSeekError> {
+    let matcher = filter
+        .matcher()
+        .map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e).with_context(serde_json::json!({ "search": filter.search })))?;
+    let pak_files = match &query.path {
+        Some(path) => {
+            let context = serde_json::json!({ "path": path });
+            let pak_files = find_scan_targets(path)
+                .await
+                .map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e).with_context(context.clone()))?;
+            if pak_files.is_empty() {
+                return Err(ApiError::new(ErrorCode::NoPaksFound, format!("No .pak files found in {}", path)).with_context(context).into());
+            }
+            Some(pak_files)
+        }
+        None => None,
+    };
+
+    let (mut writer, reader) = tokio::io::duplex(STREAM_BUFFER);
+    tokio::spawn(async move {
+        let written = match pak_files {
+            Some(pak_files) => stream_scanned_assets(&mut writer, &pak_files, query.deep, &matcher).await,
+            None => stream_loaded_assets(&mut writer, &state, &matcher).await,
+        };
+        // Fails once the client hangs up, which just ends the stream
+        if let Err(e) = written {
+            info!("Asset stream ended early: {}", e);
+        }
+    });
+
+    let body = axum::body::Body::from_stream(tokio_util::io::ReaderStream::new(reader));
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], body).into_response())
+}
+
+/// Writes the loaded assets matching `matcher`, as listed when the stream started
+async fn stream_loaded_assets(
+    writer: &mut (impl tokio::io::AsyncWrite + Unpin),
+    state: &AppState,
+    matcher: &AssetMatcher,
+) -> std::io::Result<()> {
+    let assets = state.snapshot().await;
+    for chunk in assets.chunks(STREAM_CHUNK) {
+        let lines = ndjson_lines(chunk.iter().map(Arc::as_ref).filter(|asset| matcher.matches(asset)))?;
+        writer.write_all(&lines).await?;
+    }
+    writer.shutdown().await
+}
+
+/// Parses `pak_files` one by one, writing each pak's assets matching `matcher` as it's done
+async fn stream_scanned_assets(
+    writer: &mut (impl tokio::io::AsyncWrite + Unpin),
+    pak_files: &[String],
+    deep_scan: bool,
+    matcher: &AssetMatcher,
+) -> std::io::Result<()> {
+    for pak_file in pak_files {
+        match parse_pak_assets(pak_file, deep_scan, &CancellationToken::new()).await {
+            Ok(assets) => writer.write_all(&ndjson_lines(assets.iter().filter(|asset| matcher.matches(asset)))?).await?,
+            Err(e) => warn!("Skipping {} in asset stream: {:#}", pak_file, e),
+        }
+    }
+    writer.shutdown().await
+}
+
+/// One JSON object per line
+fn ndjson_lines<'a>(assets: impl Iterator<Item = &'a Asset>) -> std::io::Result<Vec<u8>> {
+    let mut lines = Vec::new();
+    for asset in assets {
+        serde_json::to_writer(&mut lines, asset)?;
+        lines.push(b'\n');
+    }
+    Ok(lines)
+}
+
+/// Query of `GET /assets/detail`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AssetDetailQuery {
+    /// Asset id from the listing; takes precedence over `path`
+    id: Option<String>,
+    /// Exact asset path inside its pak
+    path: Option<String>,
+    /// Pak/container path, when the same asset path is mounted from several paks
+    pak: Option<String>,
+}
+
+/// GET /assets/detail?id=... or ?path=... - Returns one asset with its pak entry, preview
+/// cache status and direct dependencies/dependents
+///
+/// Unknown ids and paths are 404 and paths in several paks without `pak=...` are 409.
+#[utoipa::path(
+    get, path = "/assets/detail", tag = "assets",
+    params(AssetDetailQuery),
+    responses(
+        (status = 200, description = "The asset and its pak entry", body = AssetDetails),
+        (status = 404, description = "Unknown path", body = ErrorResponse),
+        (status = 409, description = "Path in several paks", body = ErrorResponse),
+    )
+)]
+async fn get_asset_details_http(
+    Query(query): Query<AssetDetailQuery>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<AssetDetails>, PakSeekError> {
+    asset_details(&state, query.id.as_deref(), query.path.as_deref(), query.pak.as_deref())
+        .await
+        .map(Json)
+        .map_err(|e| ApiError::asset(e, query.id.as_deref().or(query.path.as_deref()).unwrap_or_default()).into())
+}
+
+/// Query of `GET /assets/tree`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AssetTreeQuery {
+    /// Folder to list ("Game/UI"); the top of the tree when omitted
+    root: Option<String>,
+    /// Levels of subfolders and files to include, 1 by default and at most 8; 0 for the
+    /// folder's totals only
+    depth: Option<u32>,
+}
+
+/// GET /assets/tree?root=Game/UI&depth=1 - Returns a folder of the loaded assets with its
+/// subfolders and the files directly inside
+///
+/// Every folder carries the count and size of all assets beneath it, listed or not, so a
+/// tree view can expand one level per request. Responses carry an ETag; `If-None-Match`
+/// with it gets 304 until the next scan.
+#[utoipa::path(
+    get, path = "/assets/tree", tag = "assets",
+    params(AssetTreeQuery),
+    responses(
+        (status = 200, description = "The folder and its contents", body = asset_tree::TreeFolder),
+        (status = 304, description = "Unchanged since the ETag in `If-None-Match`"),
+        (status = 404, description = "No assets under `root`", body = ErrorResponse),
+    )
+)]
+async fn get_asset_tree_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Query(query): Query<AssetTreeQuery>,
+    RawQuery(raw_query): RawQuery,
+    headers: HeaderMap,
+) -> Result<Response, PakSeekError> {
+    let (assets, etag) = state.snapshot_with_etag("/assets/tree", raw_query.as_deref()).await;
+    if etag_matches(&headers, &etag) {
+        return Ok(not_modified(etag));
+    }
+    let folder = asset_folder(&assets, query.root.as_deref(), query.depth.unwrap_or(1))?;
+    Ok(([(header::ETAG, etag)], Json(folder)).into_response())
+}
+
+/// The folder of the listing at `root` (the top when None), `depth` levels down
+fn asset_folder(assets: &AssetIndex, root: Option<&str>, depth: u32) -> Result<asset_tree::TreeFolder, ApiError> {
+    let root = root.unwrap_or_default();
+    assets.folder(root, depth).ok_or_else(|| {
+        ApiError::new(ErrorCode::AssetNotFound, format!("No assets under {}", root))
+            .with_context(serde_json::json!({ "root": root }))
+    })
+}
+
+/// Looks an asset up by id or path and gathers what the listing leaves out
+async fn asset_details(
+    state: &AppState,
+    id: Option<&str>,
+    path: Option<&str>,
+    pak_file: Option<&str>,
+) -> Result<AssetDetails, preview::utils::AssetLookupError> {
+    let asset = state.assets.lock().await.lookup(id, path, pak_file, None)?.clone();
+
+    let entry = match &asset.pak_file {
+        Some(pak_file) => match PakParser::new(&**pak_file).get_file_info(&asset.path).await {
+            Ok(entry) => entry.map(|entry| EntryDetails::new(pak_file, entry)),
+            Err(e) => {
+                warn!("Failed to read entry {} from {}: {}", asset.path, pak_file, e);
+                None
+            }
+        },
+        None => None,
+    };
+    let preview_cached = preview_cache::CacheKey::for_asset(&asset, &PreviewOptions::default())
+        .is_some_and(|key| preview_cache::global().contains(&key));
+
+    // The graph may know the asset by package name or by asset name
+    let (dependencies, dependents) = {
+        let map = state.dependencies.lock().await;
+        let mut dependencies = std::collections::BTreeSet::new();
+        let mut dependents = std::collections::BTreeSet::new();
+        for key in dependency_map::asset_keys(&asset) {
+            dependencies.extend(map.get_dependencies(&key));
+            dependents.extend(map.get_reverse_dependencies(&key));
+        }
+        (dependencies.into_iter().collect(), dependents.into_iter().collect())
+    };
+
+    Ok(AssetDetails { asset, entry, preview_cached, dependencies, dependents })
+}
+
+/// Query parameters identifying the asset for `GET /preview`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PreviewLookup {
+    /// Asset id from the listing; takes precedence over the other keys
+    id: Option<String>,
+    /// Exact asset path inside its pak
+    path: Option<String>,
+    /// Pak/container path, when the same asset path is mounted from several paks
+    pak: Option<String>,
+    /// Display-name fallback for older clients
+    name: Option<String>,
+}
+
+/// GET /preview?path=... - Returns preview data for an asset looked up by exact path
+///
+/// `path` must be URL-encoded; add `pak=...` when the path exists in multiple paks.
+/// Query params: `?points=256&channel_mode=per_channel` for audio waveforms,
+/// `?search=menu` to filter localization entries, `?actor_limit=20` for level previews,
+/// `?lines=500` for text previews, `?size=256` for image previews (embedded thumbnails
+/// are used up to their own size), `?timeout_ms=30000` to override the 15s timeout,
+/// `?as_url=true` to get a link to `/preview/raw` instead of inline Base64 (with the
+/// request's token in it, when it had one)
+#[utoipa::path(
+    get, path = "/preview", tag = "previews",
+    params(PreviewLookup, PreviewOptions),
+    responses(
+        (status = 200, description = "The preview", body = PreviewResponse),
+        (status = 404, description = "Unknown asset", body = ErrorResponse),
+        (status = 409, description = "Path in several paks", body = ErrorResponse),
+        (status = 429, description = "Too many requests in flight; retry after `Retry-After` seconds", body = ErrorResponse),
+    )
+)]
+async fn get_preview_by_path_http(
+    Query(lookup): Query<PreviewLookup>,
+    Query(options): Query<PreviewOptions>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PreviewResponse>, PakSeekError> {
+    let asset = {
+        let assets = state.assets.lock().await;
+        assets.lookup(lookup.id.as_deref(), lookup.path.as_deref(), lookup.pak.as_deref(), lookup.name.as_deref())
+            .cloned()
+            .map_err(|e| {
+                let key = lookup.id.as_deref().or(lookup.path.as_deref()).or(lookup.name.as_deref());
+                ApiError::asset(e, key.unwrap_or_default())
+            })?
+    };
+
+    let mut preview_data = preview_and_record_dependencies(&state, &asset, &options, None).await;
+    if options.as_url.unwrap_or(false) {
+        preview::utils::link_raw_data(&mut preview_data, &asset, bearer_token(&headers));
+    }
+    Ok(Json(preview_data))
+}
+
+/// Query of `GET /preview/raw`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct RawPreviewQuery {
+    /// Asset id from the listing; takes precedence over `path`
+    id: Option<String>,
+    /// Exact asset path (URL-encoded)
+    path: Option<String>,
+    pak: Option<String>,
+    /// Required output format ("png", "svg", "image/png", ...)
+    format: Option<String>,
+}
+
+/// GET /preview/raw?path=...&format=png - Returns the preview bytes themselves
+///
+/// Uses the same pipeline and cache as `GET /preview`. Responds 422 when the asset has
+/// no binary preview and 406 when it can't be produced in the requested format. The API
+/// token may come as `?token=` here, as in the links `?as_url=true` hands out.
+#[utoipa::path(
+    get, path = "/preview/raw", tag = "previews",
+    params(RawPreviewQuery, PreviewOptions),
+    responses(
+        (status = 200, description = "Preview bytes, typed by their MIME type", content_type = "application/octet-stream"),
+        (status = 404, description = "Unknown asset", body = ErrorResponse),
+        (status = 406, description = "Not available in the requested format", body = ErrorResponse),
+        (status = 409, description = "Path in several paks", body = ErrorResponse),
+        (status = 422, description = "No binary preview", body = ErrorResponse),
+        (status = 429, description = "Too many requests in flight; retry after `Retry-After` seconds", body = ErrorResponse),
+    )
+)]
+async fn get_raw_preview_http(
+    Query(query): Query<RawPreviewQuery>,
+    Query(options): Query<PreviewOptions>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Response, PakSeekError> {
+    let asset = {
+        let assets = state.assets.lock().await;
+        assets.lookup(query.id.as_deref(), query.path.as_deref(), query.pak.as_deref(), None)
+            .cloned()
+            .map_err(|e| ApiError::asset(e, query.id.as_deref().or(query.path.as_deref()).unwrap_or_default()))?
+    };
+
+    let preview_data = preview_and_record_dependencies(&state, &asset, &options, None).await;
+    let (mime, bytes) = preview::utils::raw_payload(&preview_data)
+        .ok_or_else(|| PreviewError::NoBinaryPreview { path: asset.path.clone() })?;
+    if let Some(format) = &query.format {
+        if !preview::utils::mime_matches_format(&mime, format) {
+            let (path, requested) = (asset.path.clone(), format.clone());
+            return Err(PreviewError::FormatUnavailable { path, requested, available: mime }.into());
+        }
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, mime),
+            (header::CONTENT_LENGTH, bytes.len().to_string()),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// Query of `GET /extract`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ExtractQuery {
+    /// Asset id from the listing; takes precedence over `path` and `pak`
+    id: Option<String>,
+    /// Exact entry path inside the pak (URL-encoded)
+    path: Option<String>,
+    /// Pak to read from, one of a mount's; looked up among the scanned assets when omitted
+    pak: Option<String>,
+}
+
+/// Entry path and pak of the asset with `id`, or else of the entry at `path`
+///
+/// Without an id the pak is `pak` when given, which has to be a pak of a mount, and
+/// looked up among the scanned assets otherwise.
+async fn asset_location(
+    state: &AppState,
+    id: Option<&str>,
+    path: Option<&str>,
+    pak: Option<&str>,
+) -> Result<(String, String), ApiError> {
+    if let Some(id) = id {
+        let assets = state.assets.lock().await;
+        let asset = assets.lookup(Some(id), None, None, None).map_err(|e| ApiError::asset(e, id))?;
+        let pak_file = asset.pak_file.clone().ok_or_else(|| {
+            ApiError::new(ErrorCode::AssetNotInPak, "Asset is not stored in a pak").with_context(serde_json::json!({ "id": id }))
+        })?;
+        return Ok((asset.path.clone(), pak_file.to_string()));
+    }
+    let path = path.ok_or_else(|| {
+        ApiError::new(ErrorCode::InvalidRequest, "Either an asset id or a path is required")
+            .with_context(serde_json::json!({ "field": "path" }))
+    })?;
+    let pak_file = match pak {
+        Some(pak) => mounted_pak(state, pak)?,
+        None => asset_pak_file(state, path).await?,
+    };
+    Ok((path.to_string(), pak_file))
+}
+
+/// Pak holding the asset at `path`, from the scanned assets
+async fn asset_pak_file(state: &AppState, path: &str) -> Result<String, ApiError> {
+    let assets = state.assets.lock().await;
+    let asset = assets.find(Some(path), None, None).map_err(|e| ApiError::asset(e, path))?;
+    asset.pak_file.as_deref().map(str::to_string).ok_or_else(|| {
+        ApiError::new(ErrorCode::AssetNotInPak, "Asset is not stored in a pak").with_context(serde_json::json!({ "path": path }))
+    })
+}
+
+/// The entry at `path` of a pak, if it can be extracted: known, not encrypted, and
+/// compressed with a method that can be decompressed
+async fn extractable_entry(parser: &PakParser, path: &str, cancel: &CancellationToken) -> Result<PakEntry, PakSeekError> {
+    let pak = parser.parse(cancel).await.map_err(|e| PakSeekError::pak_open(&parser.path, &e))?;
+    let (pak_file, path) = (parser.path.clone(), path.to_string());
+    let wanted = normalize_asset_path(&path);
+    let Some(entry) = pak.entries.iter().find(|entry| normalize_asset_path(&entry.filename) == wanted).cloned() else {
+        return Err(PakError::EntryNotFound { pak_file, path }.into());
+    };
+    if entry.is_encrypted && !pak.has_key() {
+        return Err(PakError::EntryEncrypted { pak_file, path }.into());
+    }
+    if !entry.compression_method.is_supported() {
+        let method = format!("{:?}", entry.compression_method);
+        return Err(PakError::DecompressorUnavailable { pak_file, path, method }.into());
+    }
+    Ok(entry)
+}
+
+/// GET /extract?id=... or ?path=...&pak=... - Downloads an entry's decompressed bytes
+///
+/// The entry is streamed, never buffered whole, and a single `Range: bytes=...` is honoured
+/// for resumable downloads. Encrypted entries are 409 until a key for their pak is in the
+/// settings, and Oodle-compressed ones 501 until the Oodle library is.
+#[utoipa::path(
+    get, path = "/extract", tag = "assets",
+    params(ExtractQuery, ("Range" = Option<String>, Header, description = "A single `bytes=` range")),
+    responses(
+        (status = 200, description = "The whole entry", content_type = "application/octet-stream"),
+        (status = 206, description = "The requested range", content_type = "application/octet-stream"),
+        (status = 404, description = "Unknown asset or entry", body = ErrorResponse),
+        (status = 400, description = "Neither id nor path given", body = ErrorResponse),
+        (status = 403, description = "`pak` isn't a pak of any mount", body = ErrorResponse),
+        (status = 409, description = "Encrypted entry or path in several paks", body = ErrorResponse),
+        (status = 416, description = "Range outside the entry"),
+        (status = 500, description = "The pak couldn't be read", body = ErrorResponse),
+        (status = 501, description = "The entry's compression can't be decompressed", body = ErrorResponse),
+        (status = 429, description = "Too many requests in flight; retry after `Retry-After` seconds", body = ErrorResponse),
+    )
+)]
+async fn extract_http(
+    Query(query): Query<ExtractQuery>,
+    headers: HeaderMap,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Response, PakSeekError> {
+    let (path, pak_file) = asset_location(&state, query.id.as_deref(), query.path.as_deref(), query.pak.as_deref()).await?;
+    let context = |pak_file: &str| serde_json::json!({ "path": path, "pak_file": pak_file });
+
+    let parser = PakParser::new(&pak_file);
+    let entry = extractable_entry(&parser, &path, &CancellationToken::new()).await?;
+
+    let size = entry.uncompressed_size;
+    let requested = headers.get(header::RANGE).and_then(|value| value.to_str().ok());
+    let range = match requested.map(|value| parse_byte_range(value, size)) {
+        Some(Err(())) => {
+            return Ok((StatusCode::RANGE_NOT_SATISFIABLE, [(header::CONTENT_RANGE, format!("bytes */{}", size))]).into_response());
+        }
+        Some(Ok(range)) => range,
+        None => None,
+    };
+    let (status, bytes) = match &range {
+        Some(range) => (StatusCode::PARTIAL_CONTENT, range.clone()),
+        None => (StatusCode::OK, 0..size),
+    };
+
+    let reader = parser
+        .extract_stream(&path, bytes.clone())
+        .await
+        .map_err(|e| {
+            ApiError::new(ErrorCode::ExtractFailed, format!("Failed to extract: {:#}", e)).with_context(context(&pak_file))
+        })?;
+    let body = axum::body::Body::from_stream(tokio_util::io::ReaderStream::new(reader));
+
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(&path).replace('"', "");
+    let mut response = (
+        status,
+        [
+            (header::CONTENT_TYPE, content_type_for_path(&path).to_string()),
+            (header::CONTENT_LENGTH, (bytes.end - bytes.start).to_string()),
+            (header::ACCEPT_RANGES, "bytes".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+        ],
+        body,
+    )
+        .into_response();
+    if range.is_some() {
+        let content_range = format!("bytes {}-{}/{}", bytes.start, bytes.end.saturating_sub(1), size);
+        if let Ok(value) = HeaderValue::from_str(&content_range) {
+            response.headers_mut().insert(header::CONTENT_RANGE, value);
+        }
+    }
+    Ok(response)
+}
+
+/// What `extract_asset` wrote
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExtractResult {
+    pub destination: String,
+    pub bytes_written: u64,
+    /// Whether the bytes match the entry's SHA-1; None when the index has no hash for it
+    pub hash_verified: Option<bool>,
+}
+
+/// Extracts the entry at `path` of `pak_file` to the file `destination`
+///
+/// Parent directories are created. The bytes go to a sibling `.part` file that's renamed
+/// over `destination` once complete, so a failed extraction never leaves half a file (or
+/// clobbers the old one). An existing `destination` is only replaced with `overwrite`.
+/// `cancel` is checked between blocks; a cancelled extraction removes its `.part` file
+/// and fails with `PakSeekError::Cancelled`.
+async fn extract_to_file(
+    path: &str,
+    pak_file: &str,
+    destination: &std::path::Path,
+    overwrite: bool,
+    cancel: &CancellationToken,
+) -> Result<ExtractResult, PakSeekError> {
+    let started = std::time::Instant::now();
+    let result = write_entry_to_file(path, pak_file, destination, overwrite, cancel).await;
+    diagnostics::record(diagnostics::Operation::Extract, path, started.elapsed(), result.is_ok());
+    result
+}
+
+async fn write_entry_to_file(
+    path: &str,
+    pak_file: &str,
+    destination: &std::path::Path,
+    overwrite: bool,
+    cancel: &CancellationToken,
+) -> Result<ExtractResult, PakSeekError> {
+    let shown = destination.to_string_lossy().into_owned();
+    let context = serde_json::json!({ "path": path, "pak_file": pak_file, "destination": shown });
+    if !overwrite && tokio::fs::try_exists(destination).await.unwrap_or(false) {
+        return Err(ApiError::new(ErrorCode::DestinationExists, format!("{} already exists", shown)).with_context(context).into());
+    }
+
+    let parser = PakParser::new(pak_file);
+    let entry = extractable_entry(&parser, path, cancel).await?;
+    let failed = |stage: &str, e: &dyn std::fmt::Display| {
+        ApiError::new(ErrorCode::ExtractFailed, format!("Failed to {}: {:#}", stage, e)).with_context(context.clone())
+    };
+    let mut reader = parser
+        .extract_stream(path, 0..entry.uncompressed_size)
+        .await
+        .map_err(|e| failed("extract", &e))?;
+
+    if let Some(parent) = destination.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| failed("create the destination folder", &e))?;
+    }
+    let mut partial = destination.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = std::path::PathBuf::from(partial);
+
+    let (bytes_written, digest) = match write_partial(&mut reader, &partial, cancel).await {
+        Ok(written) => written,
+        Err(e) => {
+            if cancel.is_cancelled() {
+                info!("Extraction of {} from {} cancelled", path, pak_file);
+                return Err(Cancelled.into());
+            }
+            return Err(failed("write the file", &e).into());
+        }
+    };
+    if let Err(e) = tokio::fs::rename(&partial, destination).await {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(failed("move the file into place", &e).into());
+    }
+
+    let hash_verified = entry.sha1_hash.map(|expected| expected == digest);
+    if hash_verified == Some(false) {
+        warn!("{} from {} doesn't match its SHA-1", path, pak_file);
+    }
+    info!("Extracted {} from {} to {} ({} bytes)", path, pak_file, shown, bytes_written);
+    Ok(ExtractResult { destination: shown, bytes_written, hash_verified })
+}
+
+/// Copies `reader` to a new file at `partial`, returning the bytes written and their SHA-1
+///
+/// `cancel` is checked before every block. On failure, cancellation included, the partial
+/// file is removed again.
+async fn write_partial(
+    reader: &mut (impl tokio::io::AsyncRead + Unpin),
+    partial: &std::path::Path,
+    cancel: &CancellationToken,
+) -> std::io::Result<(u64, [u8; 20])> {
+    use sha1::{Digest, Sha1};
+    use tokio::io::AsyncReadExt;
+
+    let written = async {
+        let mut file = tokio::fs::File::create(partial).await?;
+        let mut hasher = Sha1::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut bytes_written = 0u64;
+        loop {
+            Cancelled::check(cancel).map_err(std::io::Error::other)?;
+            let read = reader.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            file.write_all(&buffer[..read]).await?;
+            bytes_written += read as u64;
+        }
+        file.flush().await?;
+        Ok((bytes_written, hasher.finalize().into()))
+    }
+    .await;
+    if written.is_err() {
+        let _ = tokio::fs::remove_file(partial).await;
+    }
+    written
+}
+
+/// What `convert_texture` wrote
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConvertedTexture {
+    /// Where the image went; ends in .dds when the pixel format had to be kept as is
+    pub destination: String,
+    pub format: texture_decode::ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    /// Index of the mip written, 0 being the largest one cooked
+    pub mip: u32,
+    /// UE pixel format of the texture ("PF_DXT5", "PF_BC7", ...)
+    pub pixel_format: String,
+    pub bytes_written: u64,
+}
+
+/// Decodes one mip of the texture at `path` of `pak_file` and writes it to `destination`
+///
+/// Without `mip` the largest mip with data is used. Pixel formats that can't be decoded
+/// (BC6H, ASTC, float formats) are written as DDS instead of PNG or TGA, with the
+/// extension of `destination` changed to .dds.
+async fn convert_texture_to_file(
+    path: &str,
+    pak_file: &str,
+    destination: &std::path::Path,
+    format: texture_decode::ImageFormat,
+    mip: Option<u32>,
+    overwrite: bool,
+) -> Result<ConvertedTexture, PakSeekError> {
+    use texture_parser::MipPayload;
+    use texture_decode::ImageFormat;
+
+    let unreadable = |e: &dyn std::fmt::Display| PreviewError::TextureUnreadable {
+        path: path.to_string(),
+        pak_file: pak_file.to_string(),
+        message: format!("{:#}", e),
+    };
+    let extract_failed = |path: &str, source: anyhow::Error| PakError::Extract { pak_file: pak_file.to_string(), path: path.to_string(), source };
+    let pak = pak_parser::OpenPak::open(pak_file).await.map_err(|e| PakError::open(pak_file, &e))?;
+    if pak.entry(path).is_none() {
+        return Err(PakError::EntryNotFound { pak_file: pak_file.to_string(), path: path.to_string() }.into());
+    }
+    let package = pak.extract_package(path).await.map_err(|e| extract_failed(path, e))?;
+    let texture = texture_parser::parse_texture_mips(&package).map_err(|e| unreadable(&e))?;
+
+    let index = match mip {
+        Some(mip) => mip as usize,
+        None => texture.mips.iter().position(|m| m.payload != MipPayload::Missing).ok_or_else(|| unreadable(&"Texture has no mip data"))?,
+    };
+    let Some(selected) = texture.mips.get(index).filter(|m| m.payload != MipPayload::Missing) else {
+        let message = format!("Mip {} isn't stored; the texture has {} mips", index, texture.mips.len());
+        return Err(ApiError::new(ErrorCode::InvalidRequest, message).with_context(serde_json::json!({ "field": "mip", "mips": texture.mips.len() })).into());
+    };
+    // Offsets and sizes come from the file, so their sum may not fit
+    let range = |offset: u64, size: u64| {
+        let end = offset.checked_add(size)?;
+        Some(usize::try_from(offset).ok()?..usize::try_from(end).ok()?)
+    };
+    let data = match selected.payload {
+        MipPayload::Package { offset, size } => range(offset, size)
+            .and_then(|range| package.get(range))
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| unreadable(&format!("Mip {} lies outside the package", index)))?,
+        MipPayload::Sibling { extension, offset, size } => {
+            let stem = path.rsplit_once('.').map_or(path, |(stem, _)| stem);
+            let sibling = format!("{}.{}", stem, extension);
+            if pak.entry(&sibling).is_none() {
+                return Err(unreadable(&format!("Mip {} is stored in {}, which isn't in the pak", index, sibling)).into());
+            }
+            let bytes = pak.extract_file(&sibling).await.map_err(|e| extract_failed(&sibling, e))?;
+            range(offset, size).and_then(|range| bytes.get(range)).map(<[u8]>::to_vec).ok_or_else(|| unreadable(&format!("Mip {} lies outside {}", index, sibling)))?
+        }
+        MipPayload::Missing => unreachable!(),
+    };
+    drop(package);
+
+    let (width, height) = (selected.width, selected.height);
+    let pixel_format = texture.info.pixel_format.clone();
+    let srgb = texture.info.srgb.unwrap_or(false);
+    // The mip plus its decoded RGBA pixels
+    let decoded_size = u64::from(width) * u64::from(height) * 4 + data.len() as u64;
+    let _lease = resource_limit::global().acquire(decoded_size).await;
+    let (format, encoded) = tokio::task::spawn_blocking(move || -> anyhow::Result<(ImageFormat, Vec<u8>)> {
+        let image = match format {
+            ImageFormat::Dds => None,
+            _ => texture_decode::decode(&pixel_format, width, height, &data)?,
+        };
+        match (image, format) {
+            (Some(image), ImageFormat::Png) => Ok((format, texture_decode::encode_png(&image)?)),
+            (Some(image), _) => Ok((format, texture_decode::encode_tga(&image)?)),
+            (None, _) => Ok((ImageFormat::Dds, texture_decode::encode_dds(&pixel_format, width, height, srgb, &data)?)),
+        }
+    })
+    .await
+    .map_err(|e| unreadable(&e))?
+    .map_err(|e| unreadable(&e))?;
+
+    let destination = match format {
+        ImageFormat::Dds if destination.extension().is_some_and(|ext| !ext.eq_ignore_ascii_case("dds")) => destination.with_extension("dds"),
+        _ => destination.to_path_buf(),
+    };
+    let shown = destination.to_string_lossy().into_owned();
+    let context = serde_json::json!({ "path": path, "pak_file": pak_file, "destination": shown });
+    if !overwrite && tokio::fs::try_exists(&destination).await.unwrap_or(false) {
+        return Err(ApiError::new(ErrorCode::DestinationExists, format!("{} already exists", shown)).with_context(context).into());
+    }
+    let failed = |stage: &str, e: &dyn std::fmt::Display| {
+        ApiError::new(ErrorCode::ExtractFailed, format!("Failed to {}: {:#}", stage, e)).with_context(context.clone())
+    };
+    if let Some(parent) = destination.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| failed("create the destination folder", &e))?;
+    }
+    let mut partial = destination.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = std::path::PathBuf::from(partial);
+    let written = match tokio::fs::write(&partial, &encoded).await {
+        Ok(()) => tokio::fs::rename(&partial, &destination).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = written {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(failed("write the file", &e).into());
+    }
+
+    info!("Converted mip {} ({}x{}) of {} from {} to {} as {}", index, width, height, path, pak_file, shown, format.extension());
+    Ok(ConvertedTexture {
+        destination: shown,
+        format,
+        width,
+        height,
+        mip: index as u32,
+        pixel_format: texture.info.pixel_format,
+        bytes_written: encoded.len() as u64,
+    })
+}
+
+/// An entry `extract_assets` couldn't extract
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FailedExtraction {
+    pub path: String,
+    pub reason: ApiError,
+}
+
+/// What an `extract_assets` run did
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ExtractionSummary {
+    pub job_id: String,
+    /// Files written
+    pub extracted: usize,
+    pub failed: Vec<FailedExtraction>,
+    /// Entries left out because no decryption key is configured for them
+    pub skipped_encrypted: usize,
+    pub total_bytes: u64,
+    /// Stopped by `cancel_job`; entries not done by then are in none of the counts
+    pub cancelled: bool,
+}
+
+/// Payload of `extract://progress`, sent as each entry of an `extract_assets` run is done
+#[derive(Serialize, Clone)]
+struct ExtractProgressEvent {
+    job_id: String,
+    /// The entry just handled
+    path: String,
+    done: usize,
+    total: usize,
+    /// Bytes written for this entry
+    bytes: u64,
+    /// Bytes written so far
+    total_bytes: u64,
+    /// Why the entry wasn't extracted
+    error: Option<ApiError>,
+}
+
+/// The entries a selection of asset paths and folders covers, with their paks
+///
+/// An asset covers all its parts, a part's own path only that part, and a folder every
+/// asset under it. Paths in several paks, or in none, are failures.
+fn extraction_targets(assets: &AssetIndex, selection: &[String]) -> (Vec<(String, String)>, Vec<FailedExtraction>) {
+    let mut paths = std::collections::BTreeSet::new();
+    let mut failed = Vec::new();
+    for item in selection {
+        if let Some(asset) = assets.with_path(item).next() {
+            if asset.path == *item {
+                paths.extend(asset.part_paths());
+            } else {
+                paths.insert(item.clone());
+            }
+            continue;
+        }
+        let folder = format!("{}/", item.trim_end_matches(['/', '\\']));
+        let before = paths.len();
+        paths.extend(assets.iter().filter(|asset| asset.path.starts_with(&folder)).flat_map(|asset| asset.part_paths()));
+        if paths.len() == before {
+            failed.push(FailedExtraction { path: item.clone(), reason: ApiError::asset(preview::utils::AssetLookupError::NotFound, item) });
+        }
+    }
+
+    let mut targets = Vec::with_capacity(paths.len());
+    for path in paths {
+        let path = path.as_str();
+        let pak_file = assets.find(Some(path), None, None).map_err(|e| ApiError::asset(e, path)).and_then(|asset| {
+            asset.pak_file.clone().ok_or_else(|| {
+                ApiError::new(ErrorCode::AssetNotInPak, "Asset is not stored in a pak").with_context(serde_json::json!({ "path": path }))
+            })
+        });
+        match pak_file {
+            Ok(pak_file) => targets.push((path.to_string(), pak_file.to_string())),
+            Err(reason) => failed.push(FailedExtraction { path: path.to_string(), reason }),
+        }
+    }
+    (targets, failed)
+}
+
+/// Where each entry of a bulk extraction goes under `destination`
+///
+/// With `preserve_paths` the pak-relative folders are kept (`..` and roots dropped, so
+/// nothing lands outside `destination`); otherwise only file names are, and names taken
+/// earlier in the run get " (2)", " (3)", ... appended.
+fn extraction_outputs(destination: &std::path::Path, paths: &[&str], preserve_paths: bool) -> Vec<std::path::PathBuf> {
+    let mut taken = std::collections::HashSet::new();
+    paths
+        .iter()
+        .map(|path| {
+            let parts: Vec<&str> = path.split(['/', '\\']).filter(|part| !part.is_empty() && *part != "." && *part != "..").collect();
+            let relative: std::path::PathBuf = if preserve_paths {
+                parts.iter().filter(|part| !part.ends_with(':')).collect()
+            } else {
+                parts.last().copied().unwrap_or("unnamed").into()
+            };
+            let mut output = destination.join(&relative);
+            // Compared lowercased, since Windows and macOS file systems ignore case
+            let mut n = 1;
+            while !taken.insert(output.to_string_lossy().to_lowercase()) {
+                n += 1;
+                let stem = relative.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+                let name = match relative.extension() {
+                    Some(extension) => format!("{} ({}).{}", stem, n, extension.to_string_lossy()),
+                    None => format!("{} ({})", stem, n),
+                };
+                output = destination.join(relative.with_file_name(name));
+            }
+            output
+        })
+        .collect()
+}
+
+/// Extracts the entries of `selection` under `destination`, `extract_concurrency` (from
+/// the config) at a time
+///
+/// `on_entry` gets each entry as it's done. When `cancel` fires, entries not started are
+/// left out and ones in progress stop, their partial files removed.
+async fn extract_selection(
+    state: &AppState,
+    selection: &[String],
+    destination: &std::path::Path,
+    preserve_paths: bool,
+    overwrite: bool,
+    cancel: &CancellationToken,
+    mut on_entry: impl FnMut(ExtractProgressEvent),
+) -> ExtractionSummary {
+    let (targets, failed) = {
+        let assets = state.assets.lock().await;
+        extraction_targets(&assets, selection)
+    };
+    let paths: Vec<&str> = targets.iter().map(|(path, _)| path.as_str()).collect();
+    let outputs = extraction_outputs(destination, &paths, preserve_paths);
+    let mut summary = ExtractionSummary { failed, ..ExtractionSummary::default() };
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(state.config.extract_concurrency));
+    let mut tasks = tokio::task::JoinSet::new();
+    for ((path, pak_file), output) in targets.into_iter().zip(outputs) {
+        let semaphore = semaphore.clone();
+        let cancel = cancel.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            if cancel.is_cancelled() {
+                return (path, None);
+            }
+            let result = extract_to_file(&path, &pak_file, &output, overwrite, &cancel).await;
+            (path, Some(result))
+        });
+    }
+
+    let total = tasks.len();
+    let mut done = 0;
+    while let Some(joined) = tasks.join_next().await {
+        let (path, result) = match joined {
+            Ok((_, None | Some(Err(PakSeekError::Cancelled)))) => continue,
+            Ok((path, Some(result))) => (path, result),
+            Err(e) => {
+                warn!("Extraction task failed: {}", e);
+                continue;
+            }
+        };
+        done += 1;
+        let (bytes, error) = match result.map_err(|e| e.to_api_error()) {
+            Ok(extracted) => {
+                summary.extracted += 1;
+                summary.total_bytes += extracted.bytes_written;
+                (extracted.bytes_written, None)
+            }
+            Err(reason) if reason.code == ErrorCode::EntryEncrypted => {
+                summary.skipped_encrypted += 1;
+                (0, Some(reason))
+            }
+            Err(reason) => {
+                summary.failed.push(FailedExtraction { path: path.clone(), reason: reason.clone() });
+                (0, Some(reason))
+            }
+        };
+        on_entry(ExtractProgressEvent { job_id: String::new(), path, done, total, bytes, total_bytes: summary.total_bytes, error });
+    }
+    summary.cancelled = cancel.is_cancelled();
+    summary
+}
+
+/// Parses a single-range `Range: bytes=...` header for an entry of `size` bytes
+///
+/// `Ok(None)` means the header is ignored and the whole entry sent (other units, several
+/// ranges, malformed); `Err(())` means the range lies outside the entry.
+fn parse_byte_range(value: &str, size: u64) -> Result<Option<std::ops::Range<u64>>, ()> {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    let Some((start, end)) = spec.split_once('-').filter(|_| !spec.contains(',')) else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        // "bytes=-500" is the last 500 bytes
+        let Ok(suffix) = end.parse::<u64>() else {
+            return Ok(None);
+        };
+        if suffix == 0 || size == 0 {
+            return Err(());
+        }
+        return Ok(Some(size.saturating_sub(suffix)..size));
+    }
+
+    let Ok(start) = start.parse::<u64>() else {
+        return Ok(None);
+    };
+    let end = match end {
+        "" => size,
+        end => match end.parse::<u64>() {
+            Ok(last) if last >= start => last.saturating_add(1).min(size),
+            _ => return Ok(None),
+        },
+    };
+    if start >= size {
+        return Err(());
+    }
+    Ok(Some(start..end))
+}
+
+/// Best-effort MIME type of a pak entry from its extension
+fn content_type_for_path(path: &str) -> &'static str {
+    let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "bmp" => "image/bmp",
+        "tga" => "image/x-tga",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "json" | "uproject" | "uplugin" => "application/json",
+        "xml" => "application/xml",
+        "ini" | "txt" | "log" | "csv" => "text/plain; charset=utf-8",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Body of `POST /previews`
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct BatchPreviewRequest {
+    /// Exact asset paths to preview
+    paths: Vec<String>,
+    /// Pak to look every path up in, when paths exist in several paks
+    pak: Option<String>,
+    /// Options shared by every preview in the batch (size, format, ...)
+    #[serde(default)]
+    options: PreviewOptions,
+    /// Previews generated at once (default 4, max 16)
+    concurrency: Option<usize>,
+}
+
+/// POST /previews - Generates previews for many assets at once (thumbnail grids)
+///
+/// Always succeeds as a whole; each result carries either a preview or an error.
+#[utoipa::path(
+    post, path = "/previews", tag = "previews",
+    request_body = BatchPreviewRequest,
+    responses(
+        (status = 200, description = "One result per requested path, in order", body = Vec<preview::BatchPreviewResult>),
+        (status = 429, description = "Too many requests in flight; retry after `Retry-After` seconds", body = ErrorResponse),
+    )
+)]
+async fn get_previews_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Json(request): Json<BatchPreviewRequest>,
+) -> Result<Json<Vec<preview::BatchPreviewResult>>, PakSeekError> {
+    let items = {
+        let assets = state.assets.lock().await;
+        resolve_batch_items(&assets, request.paths, request.pak.as_deref())
+    };
+
+    Ok(Json(preview::run_preview_batch(items, &request.options, request.concurrency).await))
+}
+
+/// Resolves each requested path to its asset, keeping lookup failures per item
+fn resolve_batch_items(assets: &AssetIndex, paths: Vec<String>, pak: Option<&str>) -> Vec<(String, Result<Arc<Asset>, String>)> {
+    paths
+        .into_iter()
+        .map(|path| {
+            let asset = assets.find(Some(&path), pak, None)
+                .cloned()
+                .map_err(|e| e.to_string());
+            (path, asset)
+        })
+        .collect()
+}
+
+/// GET /preview/{asset_name} - Returns preview data for an asset by display name
+///
+/// Kept for compatibility; names can collide, prefer `GET /preview?path=...`.
+#[utoipa::path(
+    get, path = "/preview/{asset_name}", tag = "previews",
+    params(("asset_name" = String, Path, description = "Display name of the asset"), PreviewOptions),
+    responses(
+        (status = 200, description = "The preview", body = PreviewResponse),
+        (status = 404, description = "Unknown asset", body = ErrorResponse),
+        (status = 429, description = "Too many requests in flight; retry after `Retry-After` seconds", body = ErrorResponse),
+    )
+)]
+async fn get_preview_http(
+    Path(asset_name): Path<String>,
+    Query(options): Query<PreviewOptions>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PreviewResponse>, PakSeekError> {
+    let asset = {
+        let assets = state.assets.lock().await;
+        assets.find(None, None, Some(&asset_name))
+            .cloned()
+            .map_err(|e| ApiError::asset(e, &asset_name))?
+    };
+
+    let mut preview_data = preview_and_record_dependencies(&state, &asset, &options, None).await;
+    if options.as_url.unwrap_or(false) {
+        preview::utils::link_raw_data(&mut preview_data, &asset, bearer_token(&headers));
+    }
+    Ok(Json(preview_data))
+}
+
+/// Generates a preview and records any dependencies it discovered
+async fn preview_and_record_dependencies(
+    state: &AppState,
+    asset: &Asset,
+    options: &PreviewOptions,
+    request_id: Option<String>,
+) -> PreviewResponse {
+    let started = std::time::Instant::now();
+    let preview_data = preview::run_preview(asset, options, request_id).await;
+    let ok = !matches!(preview_data.preview_type, PreviewType::Unsupported { .. });
+    diagnostics::record(diagnostics::Operation::Preview, &asset.path, started.elapsed(), ok);
+
+    // Material previews surface texture references the pak index can't see
+    let discovered = preview::discovered_dependencies(&preview_data);
+    if !discovered.is_empty() {
+        let mut dependencies = state.dependencies.lock().await;
+        for dependency in &discovered {
+            dependencies.add_dependency(&asset.name, dependency);
+        }
+        dependencies.optimize();
+        state.touch();
+    }
+
+    preview_data
+}
+
+/// Query of `GET /dependencies`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DependencyQuery {
+    /// Keep only `hard` or `soft` edges
+    kind: Option<DependencyKind>,
+    /// Comma-separated assets to walk outward from
+    roots: Option<String>,
+    depth: Option<u32>,
+    /// Comma-separated asset types to keep
+    types: Option<String>,
+    prefix: Option<String>,
+    /// Keep only assets whose name matches
+    search: Option<String>,
+    /// `substring` (default), `regex` or `fuzzy`
+    #[serde(default)]
+    search_mode: search::SearchMode,
+    #[serde(default)]
+    pass_through: bool,
+}
+
+impl DependencyQuery {
+    fn subgraph_filter(&self) -> SubgraphFilter {
+        let split = |list: &str| list.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect();
+        SubgraphFilter {
+            roots: self.roots.as_deref().map(split).unwrap_or_default(),
+            max_depth: self.depth,
+            include_types: self.types.as_deref().map(split),
+            path_prefix: self.prefix.clone(),
+            search: self.search.clone(),
+            search_mode: self.search_mode,
+            keep_pass_through: self.pass_through,
+        }
+    }
+}
+
+/// GET /dependencies - Returns asset dependency mapping (`?kind=soft` for soft references only,
+/// `?roots=/Game/Maps/Arena&depth=2&types=Texture2D&prefix=/Game/Props/` for a subgraph,
+/// `&search=_[DN]$&search_mode=regex` to keep matching assets).
+/// Supports ETag / `If-None-Match` like `GET /assets`.
+#[utoipa::path(
+    get, path = "/dependencies", tag = "dependencies",
+    params(DependencyQuery),
+    responses(
+        (status = 200, description = "The dependency map or a subgraph of it", body = DependencyResponse),
+        (status = 304, description = "Unchanged since the ETag in `If-None-Match`"),
+        (status = 400, description = "Invalid search regex", body = ErrorResponse),
+    )
+)]
+async fn get_dependencies_http(
+    Query(query): Query<DependencyQuery>,
+    RawQuery(raw_query): RawQuery,
+    headers: HeaderMap,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Response, PakSeekError> {
+    let dependencies = state.dependencies.lock().await;
+    let etag = state.etag("/dependencies", raw_query.as_deref());
+    if etag_matches(&headers, &etag) {
+        return Ok(not_modified(etag));
+    }
+    let dependencies = match query.kind {
+        Some(kind) => dependencies.filter_by_kind(&[kind]),
+        None => dependencies.clone(),
+    };
+
+    let filter = query.subgraph_filter();
+    let response = if filter.is_empty() {
+        DependencyResponse { dependencies, pruned: None, tree: None }
+    } else {
+        let (dependencies, summary) = dependencies.subgraph_with_summary(&filter).map_err(|e| {
+            ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_context(serde_json::json!({ "search": filter.search }))
+        })?;
+        DependencyResponse { dependencies, pruned: Some(summary), tree: None }
+    };
+    Ok(([(header::ETAG, etag)], Json(response)).into_response())
+}
+
+/// Query for GET /dependencies/export
+#[derive(Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DependencyExportQuery {
+    format: String,
+    root: Option<String>,
+    depth: Option<u32>,
+    max_nodes: Option<usize>,
+}
+
+/// GET /dependencies/export - Dependency graph as a document
+/// (`?format=mermaid&root=/Game/Maps/Arena&depth=2` for a focused chart)
+#[utoipa::path(
+    get, path = "/dependencies/export", tag = "dependencies",
+    params(DependencyExportQuery),
+    responses(
+        (status = 200, description = "The graph in the requested format", content_type = "text/plain"),
+        (status = 400, description = "Unknown format", body = ErrorResponse),
+    )
+)]
+async fn export_dependencies_http(
+    Query(query): Query<DependencyExportQuery>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Response, PakSeekError> {
+    let options = ExportOptions {
+        root: query.root,
+        max_depth: query.depth,
+        max_nodes: query.max_nodes,
+    };
+    let contents = state
+        .dependencies
+        .lock()
+        .await
+        .export_with_options(&query.format, &options)
+        .map_err(|e| ApiError::new(ErrorCode::InvalidRequest, format!("{:#}", e)))?;
+
+    let content_type = match query.format.to_lowercase().as_str() {
+        "json" | "cytoscape" => "application/json",
+        "graphml" => "application/xml",
+        "csv" => "text/csv",
+        _ => "text/plain; charset=utf-8",
+    };
+    Ok(([(header::CONTENT_TYPE, content_type)], contents).into_response())
+}
+
+/// GET /dependencies/validate - Cycles, empty lists and references to assets missing
+/// from the scanned index
+#[utoipa::path(
+    get, path = "/dependencies/validate", tag = "dependencies",
+    responses((status = 200, description = "Problems found in the graph", body = Vec<ValidationIssue>))
+)]
+async fn validate_dependencies_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<Vec<ValidationIssue>>, PakSeekError> {
+    let assets = state.assets.lock().await;
+    let dependencies = state.dependencies.lock().await;
+    Ok(Json(dependencies.validate_against_assets(&assets)))
+}
+
+/// Query of `GET /dependencies/{asset}`
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DependencyAnalysisQuery {
+    /// Levels of the trees (default 5)
+    depth: Option<u32>,
+    /// Build only this tree; the other is left empty
+    direction: Option<TreeDirection>,
+}
+
+/// GET /dependencies/{asset} - Dependency analysis of one asset
+///
+/// `asset` is a package name ("/Game/Maps/Arena"), an asset path or an asset name,
+/// URL-encoded. Footprints and statistics use the sizes of the scanned assets.
+#[utoipa::path(
+    get, path = "/dependencies/{asset}", tag = "dependencies",
+    params(("asset" = String, Path, description = "Package name, asset path or asset name"), DependencyAnalysisQuery),
+    responses(
+        (status = 200, description = "Dependency analysis of the asset", body = DependencyAnalysis),
+        (status = 404, description = "Asset not in the graph", body = ErrorResponse),
+    )
+)]
+async fn analyze_dependencies_http(
+    Path(asset): Path<String>,
+    Query(query): Query<DependencyAnalysisQuery>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<DependencyAnalysis>, PakSeekError> {
+    let assets = state.assets.lock().await;
+    let mut dependencies = state.dependencies.lock().await.clone();
+
+    let key = if dependencies.contains(&asset) {
+        Some(asset.clone())
+    } else {
+        // Asset paths and names map to the package name the graph uses
+        assets
+            .find(Some(&asset), None, None)
+            .or_else(|_| assets.find(None, None, Some(&asset)))
+            .ok()
+            .and_then(|found| dependency_map::asset_keys(found).find(|key| dependencies.contains(key)))
+    };
+    let Some(key) = key else {
+        return Err(DependencyError::NotInGraph { asset }.into());
+    };
+
+    dependencies.annotate_containers(&assets);
+    let sizes = dependency_map::utils::package_sizes(assets.iter().map(|a| (a.path.as_str(), a.size)));
+    let all_assets: Vec<String> = sizes.keys().cloned().collect();
+    let mut analysis =
+        dependencies.analyze_asset_dependencies_to_depth(&key, &all_assets, &sizes, query.depth.unwrap_or(5));
+    match query.direction {
+        Some(TreeDirection::Forward) => analysis.reverse_dependency_tree = DependencyTree::default(),
+        Some(TreeDirection::Reverse) => analysis.dependency_tree = DependencyTree::default(),
+        None => {}
+    }
+    Ok(Json(analysis))
+}
+
+/// Body of `POST /scan`
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct ScanRequest {
+    /// A .pak file or a folder of them
+    path: String,
+    /// Read every package for per-type metadata (slower)
+    #[serde(default)]
+    deep: bool,
+}
+
+/// POST /scan - Scans a folder into the server state in the background
+///
+/// Responds 202 with the job, whose progress is at `GET /scan/{job_id}`. The assets and
+/// dependencies served by the other endpoints are replaced in one step when it's done.
+#[utoipa::path(
+    post, path = "/scan", tag = "scans",
+    request_body = ScanRequest,
+    responses(
+        (status = 202, description = "The scan job", body = ScanProgress),
+        (status = 400, description = "Invalid path or no paks in it", body = ErrorResponse),
+        (status = 429, description = "Too many requests in flight; retry after `Retry-After` seconds", body = ErrorResponse),
+    )
+)]
+async fn start_scan_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    turn: Option<axum::Extension<route_limit::RouteTurn>>,
+    Json(request): Json<ScanRequest>,
+) -> Result<(StatusCode, Json<ScanProgress>), PakSeekError> {
+    let context = serde_json::json!({ "path": request.path });
+    let pak_files = find_scan_targets(&request.path)
+        .await
+        .map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e).with_context(context.clone()))?;
+    if pak_files.is_empty() {
+        let message = format!("No .pak files found in {}", request.path);
+        return Err(ApiError::new(ErrorCode::NoPaksFound, message).with_context(context).into());
+    }
+
+    let (progress, cancel) = state.register_scan(&request.path, pak_files.len());
+    info!("Scan {} started for {} ({} paks)", progress.job_id, progress.path, pak_files.len());
+
+    let started = progress.clone();
+    tokio::spawn(async move {
+        // Counts against the scan limit until the job is done
+        let _turn = turn;
+        let done = run_scan(&state, &started, pak_files, request.deep, &cancel, |event| state.publish(event)).await;
+        state.publish(ProgressEvent { job_id: done.job_id.clone(), update: ProgressUpdate::finished(&done) });
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(progress)))
+}
+
+/// Scans the paks of a job registered with `register_scan` and swaps its results in
+///
+/// Each pak goes to `send` as a `PakScanned` event as it's done. A cancelled scan keeps
+/// the listing as it was. Returns the job's final progress.
+async fn run_scan(
+    state: &AppState,
+    progress: &ScanProgress,
+    pak_files: Vec<String>,
+    deep: bool,
+    cancel: &CancellationToken,
+    mut send: impl FnMut(ProgressEvent),
+) -> ScanProgress {
+    let job_id = progress.job_id.as_str();
+    let on_pak = pak_scanned_events(job_id, pak_files.len(), |event| {
+        if let ProgressUpdate::PakScanned { paks_done, assets_found, error, .. } = &event.update {
+            state.update_scan(job_id, |progress| {
+                progress.paks_done = *paks_done;
+                progress.assets_found = *assets_found;
+                progress.errors.extend(error.clone());
+            });
+        }
+        send(event);
+    });
+    let started = std::time::Instant::now();
+    let scanned = load_scan(&pak_files, deep, cancel, on_pak).await;
+    diagnostics::record(diagnostics::Operation::Scan, &progress.path, started.elapsed(), scanned.is_some());
+    state.cancellations.lock().unwrap().remove(job_id);
+
+    match scanned {
+        Some((assets, dependencies)) => {
+            let assets_found = assets.len();
+            state.replace_with_scan(&progress.path, deep, pak_files, assets, dependencies).await;
+            state.update_scan(job_id, |progress| {
+                progress.status = ScanStatus::Completed;
+                progress.paks_done = progress.paks_total;
+                progress.assets_found = assets_found;
+            });
+            info!("Scan {} finished with {} assets", job_id, assets_found);
+        }
+        None => {
+            state.update_scan(job_id, |progress| progress.status = ScanStatus::Cancelled);
+            info!("Scan {} cancelled", job_id);
+        }
+    }
+    let done = state.scans.lock().unwrap().get(job_id).cloned();
+    done.unwrap_or_else(|| progress.clone())
+}
+
+/// GET /scan/{job_id} - Progress of a scan started with `POST /scan`
+#[utoipa::path(
+    get, path = "/scan/{job_id}", tag = "scans",
+    params(("job_id" = String, Path)),
+    responses(
+        (status = 200, description = "Progress of the job", body = ScanProgress),
+        (status = 404, description = "Unknown job", body = ErrorResponse),
+    )
+)]
+async fn get_scan_http(
+    Path(job_id): Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<ScanProgress>, PakSeekError> {
+    let progress = state.scans.lock().unwrap().get(&job_id).cloned();
+    progress.map(Json).ok_or_else(|| {
+        ApiError::new(ErrorCode::JobNotFound, "No scan with this id").with_context(serde_json::json!({ "job_id": job_id })).into()
+    })
+}
+
+/// Body of `POST /mounts`
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct MountRequest {
+    /// Folder of paks, or a single .pak or .utoc
+    path: String,
+    /// Higher wins when mounts have the same asset path (default 0; ties go to the newer mount)
+    #[serde(default)]
+    priority: i32,
+    /// Also read class and tags from each package
+    #[serde(default)]
+    deep: bool,
+}
+
+/// GET /mounts - The mounts the listing is made of, with their scan progress
+#[utoipa::path(
+    get, path = "/mounts", tag = "mounts",
+    responses((status = 200, description = "Mounts in registration order", body = Vec<mounts::Mount>))
+)]
+async fn get_mounts_http(axum::extract::State(state): axum::extract::State<AppState>) -> Json<Vec<mounts::Mount>> {
+    Json(state.mounts.lock().unwrap().list())
+}
+
+/// POST /mounts - Adds a folder or container to the listing and scans it in the background
+///
+/// Its assets join the listing once the scan is done; progress goes to `GET /ws` clients
+/// with the mount id as `job_id`.
+#[utoipa::path(
+    post, path = "/mounts", tag = "mounts",
+    request_body = MountRequest,
+    responses(
+        (status = 202, description = "The mount, still scanning", body = mounts::Mount),
+        (status = 400, description = "Invalid path or no paks in it", body = ErrorResponse),
+        (status = 409, description = "Path already mounted", body = ErrorResponse),
+    )
+)]
+async fn add_mount_http(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Json(request): Json<MountRequest>,
+) -> Result<(StatusCode, Json<mounts::Mount>), PakSeekError> {
+    let events = state.clone();
+    let (mount, _) = add_mount_to(&state, request, move |event| events.publish(event)).await?;
+    Ok((StatusCode::ACCEPTED, Json(mount)))
+}
+
+/// DELETE /mounts/{id} - Takes a mount's assets and dependency edges out of the listing
+#[utoipa::path(
+    delete, path = "/mounts/{id}", tag = "mounts",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, description = "The removed mount", body = mounts::Mount),
+        (status = 404, description = "Unknown mount", body = ErrorResponse),
+    )
+)]
+async fn remove_mount_http(
+    Path(id): Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<mounts::Mount>, PakSeekError> {
+    Ok(Json(remove_mount_from(&state, &id).await?))
+}
+
+/// Paks a mount path stands for
+///
+/// IoStore containers can't be listed on their own, so a .utoc stands for the .pak UE
+/// ships next to it with the same name.
+async fn mount_targets(path: &str) -> Result<Vec<String>, ApiError> {
+    let context = serde_json::json!({ "path": path });
+    let pak_files = if path.to_lowercase().ends_with(".utoc") {
+        let pak_file = std::path::Path::new(path).with_extension("pak");
+        if !pak_file.is_file() {
+            let message = format!("No .pak next to {} to list it from", path);
+            return Err(ApiError::new(ErrorCode::NoPaksFound, message).with_context(context));
+        }
+        vec![pak_file.to_string_lossy().into_owned()]
+    } else {
+        find_scan_targets(path).await.map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e).with_context(context.clone()))?
+    };
+    if pak_files.is_empty() {
+        return Err(ApiError::new(ErrorCode::NoPaksFound, format!("No .pak files found in {}", path)).with_context(context));
+    }
+    Ok(pak_files)
+}
+
+/// Registers a mount and starts scanning it, sending its progress to `emit`
+///
+/// The first mount takes over from whatever a single-folder scan or the mock data left.
+/// The returned task finishes once the mount's assets are in the listing.
+async fn add_mount_to(
+    state: &AppState,
+    request: MountRequest,
+    emit: impl Fn(ProgressEvent) + Send + Sync + 'static,
+) -> Result<(mounts::Mount, tokio::task::JoinHandle<()>), ApiError> {
+    let pak_files = mount_targets(&request.path).await?;
+    let (mount, first) = {
+        let mut mounts = state.mounts.lock().unwrap();
+        if mounts.has_path(&request.path) {
+            let message = format!("{} is already mounted", request.path);
+            return Err(ApiError::new(ErrorCode::MountExists, message).with_context(serde_json::json!({ "path": request.path })));
+        }
+        let first = mounts.is_empty();
+        (mounts.add(&request.path, request.priority, request.deep, pak_files.clone()), first)
+    };
+    if first {
+        *state.dependencies.lock().await = DependencyMap::new();
+        state.rebuild_from_mounts().await;
+    }
+    info!("Mount {} added for {} ({} paks)", mount.id, mount.path, pak_files.len());
+    // Removing the mount (or `cancel_scan` with its id) stops the scan
+    let cancel = CancellationToken::new();
+    state.cancellations.lock().unwrap().insert(mount.id.clone(), cancel.clone());
+
+    let state = state.clone();
+    let id = mount.id.clone();
+    let task = tokio::spawn(async move {
+        let on_pak = pak_scanned_events(&id, pak_files.len(), |event| {
+            if let ProgressUpdate::PakScanned { paks_done, error, .. } = &event.update {
+                state.mounts.lock().unwrap().update(&id, |mount| {
+                    mount.paks_done = *paks_done;
+                    mount.errors.extend(error.clone());
+                });
+            }
+            emit(event);
+        });
+        let started = std::time::Instant::now();
+        let scanned = load_scan(&pak_files, request.deep, &cancel, on_pak).await;
+        diagnostics::record(diagnostics::Operation::Scan, &request.path, started.elapsed(), scanned.is_some());
+        state.cancellations.lock().unwrap().remove(&id);
+        let Some((assets, _)) = scanned else {
+            // Cancelled without being removed: drop it, since a partial mount would be misleading
+            if let Ok(mount) = remove_mount_from(&state, &id).await {
+                emit(ProgressEvent { job_id: id.clone(), update: ProgressUpdate::ScanCancelled { assets_found: mount.assets } });
+            }
+            return;
+        };
+        let assets_found = assets.len();
+        if !state.mounts.lock().unwrap().complete(&id, assets) {
+            return;
+        }
+
+        // Checked again under the graph lock, so a removal racing this can't leave edges behind
+        let graphs = cached_pak_dependencies(&pak_files).await;
+        {
+            let mut dependencies = state.dependencies.lock().await;
+            if state.mounts.lock().unwrap().get(&id).is_none() {
+                return;
+            }
+            for (pak_file, pak_dependencies) in &graphs {
+                dependencies.add_assets_from(pak_file, pak_dependencies);
+            }
+        }
+        state.rebuild_from_mounts().await;
+        emit(ProgressEvent { job_id: id.clone(), update: ProgressUpdate::ScanCompleted { assets_found } });
+        info!("Mount {} ready with {} assets", id, assets_found);
+    });
+    Ok((mount, task))
+}
+
+/// Removes a mount, its dependency edges and its assets; the other mounts aren't rescanned
+async fn remove_mount_from(state: &AppState, id: &str) -> Result<mounts::Mount, ApiError> {
+    state.cancel_job(id);
+    state.watchers.lock().unwrap().remove(id);
+    let mount = {
+        let mut dependencies = state.dependencies.lock().await;
+        let mount = state.mounts.lock().unwrap().remove(id).ok_or_else(|| {
+            ApiError::new(ErrorCode::MountNotFound, format!("No mount {}", id)).with_context(serde_json::json!({ "id": id }))
+        })?;
+        for pak_file in &mount.pak_files {
+            dependencies.remove_assets_from(pak_file);
+        }
+        mount
+    };
+    state.rebuild_from_mounts().await;
+    info!("Mount {} removed ({})", mount.id, mount.path);
+    Ok(mount)
+}
+
+/// GET /ws - WebSocket of `ProgressEvent`s (JSON text frames) for jobs started over HTTP
+///
+/// Clients get the events sent after they connect; closing the socket only stops the
+/// forwarding, the jobs carry on.
+#[utoipa::path(
+    get, path = "/ws", tag = "scans",
+    params(("token" = Option<String>, Query, description = "API token, when browsers can't send `Authorization`")),
+    responses((status = 101, description = "WebSocket of `ProgressEvent` JSON messages"))
+)]
+async fn progress_ws(
+    ws: WebSocketUpgrade,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Response {
+    let events = state.events.subscribe();
+    ws.on_upgrade(move |socket| forward_progress(socket, events))
+}
+
+async fn forward_progress(mut socket: WebSocket, mut events: broadcast::Receiver<ProgressEvent>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(json) = serde_json::to_string(&event) else { continue };
+                    if socket.send(Message::Text(json)).await.is_err() {
+                        break;
+                    }
+                }
+                // A slow client misses events rather than holding up the jobs
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Progress socket fell behind, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// GET /health - Health check endpoint
+///
+/// `uptime_secs` counts from when the server started listening; `profile` is the active
+/// game profile; `state` has what is loaded and what the process uses, as `get_status`
+/// reports it.
+#[utoipa::path(
+    get, path = "/health", tag = "server", security(()),
+    responses((status = 200, description = "Server status, address, uptime, route limit counters and loaded state", body = Object))
+)]
+async fn health_check(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    addr: SocketAddr,
+    started: std::time::Instant,
+    limits: Arc<route_limit::ApiLimits>,
+) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "healthy",
+        "timestamp": chrono::Utc::now(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "addr": addr.to_string(),
+        "port": addr.port(),
+        "uptime_secs": started.elapsed().as_secs(),
+        "limits": limits.metrics(),
+        "profile": settings::global().get().active_profile,
+        "state": status_report(&state).await
+    }))
+}
+
+/// What is loaded and what the process uses, for `/health` and the status bar
+#[derive(Serialize, Deserialize, Clone, utoipa::ToSchema)]
+pub struct StatusReport {
+    pub mounts: usize,
+    /// Paks across the mounts
+    pub paks: usize,
+    /// Assets in the listing, after dedup across mounts
+    pub assets: usize,
+    /// Scans and mount scans still running
+    pub scans: Vec<ScanProgress>,
+    pub preview_cache: preview_cache::CacheStats,
+    /// Parsed pak indexes currently held open
+    pub open_pak_handles: usize,
+    /// Decompression slots and buffer budget in use
+    pub resources: resource_limit::ResourceMetrics,
+    /// Resident memory of the process, where the platform reports it
+    pub rss_bytes: Option<u64>,
+    /// Since the process started
+    pub uptime_secs: u64,
+}
+
+async fn status_report(state: &AppState) -> StatusReport {
+    let assets = state.assets.lock().await.len();
+    let (mounts, paks, mut scans) = {
+        let mounts = state.mounts.lock().unwrap().list();
+        let paks = mounts.iter().map(|mount| mount.pak_files.len()).sum();
+        let scanning = mounts.iter().filter(|mount| mount.status == ScanStatus::Running).map(|mount| ScanProgress {
+            job_id: mount.id.clone(),
+            path: mount.path.clone(),
+            status: mount.status,
+            paks_done: mount.paks_done,
+            paks_total: mount.pak_files.len(),
+            assets_found: mount.assets,
+            errors: mount.errors.clone(),
+        });
+        (mounts.len(), paks, scanning.collect::<Vec<_>>())
+    };
+    let jobs = state.scans.lock().unwrap();
+    scans.extend(jobs.values().filter(|progress| progress.status == ScanStatus::Running).cloned());
+    drop(jobs);
+
+    StatusReport {
+        mounts,
+        paks,
+        assets,
+        scans,
+        preview_cache: preview_cache::global().stats(),
+        open_pak_handles: pak_parser::open_pak_handles(),
+        resources: state.limiter.metrics(),
+        rss_bytes: memory_stats::memory_stats().map(|stats| stats.physical_mem as u64),
+        uptime_secs: PROCESS_STARTED.get().map_or(0, |started| started.elapsed().as_secs()),
+    }
+}
+
+// ============================================================================
+// TAURI COMMANDS (for frontend integration)
+// ============================================================================
+
+/// Simple test command to verify frontend-backend communication
+#[tauri::command]
+async fn test_command(message: String) -> Result<String, PakSeekError> {
+    debug!("test_command called with message: {}", message);
+    Ok(format!("Backend received: {}", message))
+}
+
+/// Tauri command to list the loaded assets with optional filtering
+///
+/// Queries what the last scan (`start_scan`) or the mounts put in the state, the mock data
+/// until then. `filters` takes the query parameters of `GET /assets` (`types`, `min_size`,
+/// `encrypted`, ...), with `asset_type` and `search` filling in `type` and `search`. `sort`
+/// (name, size, type or path, `:desc` for descending), `offset` and `limit` (default 500,
+/// at most 5000) apply after filtering; `facets` is counted as for `GET /assets`.
+#[tauri::command]
+async fn list_assets(
+    asset_type: Option<String>,
+    search: Option<String>,
+    filters: Option<AssetFilter>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<AssetsResponse, PakSeekError> {
+    let mut filter = filters.unwrap_or_default();
+    filter.asset_type = filter.asset_type.or(asset_type);
+    filter.search = filter.search.or(search);
+    let query = AssetQuery { offset, limit, sort };
+    let assets = state.snapshot().await;
+    Ok(state.query_assets(&query, &filter, &assets).await.map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e))?)
+}
+
+/// Tauri command to list a folder of the loaded assets for the tree view
+///
+/// Returns the folder at `root` (the top when None) with its subfolders and files `depth`
+/// levels down (at most 8), as `GET /assets/tree` does; every folder has the count and
+/// size of all assets beneath it.
+#[tauri::command]
+async fn get_asset_tree(
+    root: Option<String>,
+    depth: u32,
+    state: tauri::State<'_, AppState>,
+) -> Result<asset_tree::TreeFolder, PakSeekError> {
+    Ok(asset_folder(&*state.snapshot().await, root.as_deref(), depth)?)
+}
+
+/// Tauri command to get totals and breakdowns of the loaded assets for the dashboard
+#[tauri::command]
+async fn get_statistics(state: tauri::State<'_, AppState>) -> Result<statistics::ScanStatistics, PakSeekError> {
+    Ok(statistics::compute(&state.snapshot().await))
+}
+
+/// Tauri command to break the bytes of the loaded assets (or of `folder`, scanned without
+/// loading it) down by type and extension, as `GET /stats/storage` does
+#[tauri::command]
+async fn analyze_storage(
+    folder: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<statistics::StorageReport, PakSeekError> {
+    storage_report(&state, folder.as_deref()).await
+}
+
+/// Tauri command to list which containers of `folder` are encrypted, the key GUIDs they need
+/// and whether the configured keys open them, as `GET /audit/encryption` does
+///
+/// Errors are `ApiError` JSON: `INVALID_REQUEST` for a missing folder, `NO_PAKS_FOUND` when
+/// it has no containers.
+#[tauri::command]
+async fn audit_encryption(folder: String) -> Result<encryption_audit::EncryptionAudit, PakSeekError> {
+    let audit = encryption_audit::audit(&folder, &settings::global().get()).await?;
+    info!("Encryption audit of {}: {} of {} containers locked", folder, audit.locked, audit.containers.len());
+    Ok(audit)
+}
+
+/// Tauri command to list the loaded assets by `pakchunkN` chunk, as `GET /stats/chunks` does
+#[tauri::command]
+async fn get_chunk_summary(state: tauri::State<'_, AppState>) -> Result<Vec<statistics::ChunkSummary>, PakSeekError> {
+    Ok(statistics::chunk_summary(&state.snapshot().await))
+}
+
+/// Tauri command to get string counts and missing keys per culture of the loaded .locres
+/// files, as `GET /localization` does
+#[tauri::command]
+async fn get_localization_summary(
+    source_culture: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<localization::LocalizationSummary, PakSeekError> {
+    localization_summary(&state, source_culture.as_deref()).await
+}
+
+/// Tauri command to browse a culture's strings, as `GET /localization/{culture}` does
+#[tauri::command]
+async fn get_localization_entries(
+    culture: String,
+    search: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<localization::CultureEntries, PakSeekError> {
+    localization_entries(&state, &culture, &LocalizationEntriesQuery { search, offset, limit }).await
+}
+
+/// Tauri command to find loaded assets with the same content, for the duplicates tab
+///
+/// Assets smaller than `min_size` are left out. Entries the index has no hash for are read
+/// to hash them, up to `read_budget` bytes in all (none by default). `job_id` (made up
+/// when omitted) is what `cancel_job` takes to stop the hashing.
+#[tauri::command]
+async fn find_duplicates(
+    min_size: Option<u64>,
+    read_budget: Option<u64>,
+    job_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<duplicates::DuplicateReport, PakSeekError> {
+    let (job_id, cancel) = state.register_job(job_id)?;
+    let report = find_duplicates_in(&state, min_size.unwrap_or(0), read_budget.unwrap_or(0), &cancel).await;
+    state.finish_job(&job_id);
+    report
+}
+
+/// What `export_asset_list` wrote
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetListExport {
+    pub destination: String,
+    pub rows: usize,
+    pub bytes_written: u64,
+}
+
+/// Tauri command to write the loaded assets to a file for spreadsheets and reports
+///
+/// `format` is csv, json or markdown (name, path, type, size, compressed size, compression,
+/// encrypted and pak of each asset). `filter` and `sort` work as in `list_assets`, without
+/// paging: every matching asset is written. Rows are streamed to the file; the listing
+/// stays locked meanwhile so the export matches a single scan.
+#[tauri::command]
+async fn export_asset_list(
+    destination: String,
+    format: String,
+    filter: Option<AssetFilter>,
+    sort: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<AssetListExport, PakSeekError> {
+    let format = asset_export::ExportFormat::parse(&format).map_err(|e| {
+        ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_context(serde_json::json!({ "field": "format" }))
+    })?;
+    let query = AssetQuery { sort, ..Default::default() };
+    let assets = state.snapshot().await;
+    let (matching, _) = query.matching(&filter.unwrap_or_default(), &assets, None)?;
+    let matching = matching.into_iter().map(|(asset, _)| &**asset);
+    let (rows, bytes_written) = asset_export::write_asset_list(matching, format, std::path::Path::new(&destination))
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    info!("Exported {} assets to {}", rows, destination);
+    Ok(AssetListExport { destination, rows, bytes_written })
+}
+
+/// Options of `start_scan`
+#[derive(Debug, Default, Deserialize)]
+struct ScanOptions {
+    /// Read each package to fill `metadata` (texture size and format, mesh LODs, sound
+    /// duration, blueprint parent); shallow scans only read headers
+    #[serde(default)]
+    deep: bool,
+}
+
+/// Payload of `scan://progress`, sent as each pak of a `start_scan` job is done
+#[derive(Serialize, Clone)]
+struct ScanProgressEvent {
+    job_id: String,
+    paks_done: usize,
+    paks_total: usize,
+    assets_found: usize,
+    /// The pak just scanned
+    current_pak: String,
+    /// Why the pak couldn't be read
+    error: Option<ApiError>,
+}
+
+/// Tauri command to scan a folder or .pak into the state in the background
+///
+/// Returns the job id at once. Progress goes out as `scan://progress` events and the
+/// final `ScanProgress` (completed or cancelled) as `scan://done`; the assets are then
+/// in the state for `list_assets` and the other commands.
+#[tauri::command]
+async fn start_scan(
+    folder: String,
+    options: Option<ScanOptions>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, PakSeekError> {
+    let context = serde_json::json!({ "path": folder });
+    let pak_files = find_scan_targets(&folder)
+        .await
+        .map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e).with_context(context.clone()))?;
+    if pak_files.is_empty() {
+        let message = format!("No .pak files found in {}", folder);
+        return Err(ApiError::new(ErrorCode::NoPaksFound, message).with_context(context).into());
+    }
+    let deep = options.unwrap_or_default().deep;
+    let (progress, cancel) = state.register_scan(&folder, pak_files.len());
+    info!("Scan {} started for {} ({} paks)", progress.job_id, folder, pak_files.len());
+    if let Err(e) = settings::global().push_recent_folder(&folder) {
+        warn!("Couldn't add {} to the recent folders: {}", folder, e);
+    }
+
+    let state = state.inner().clone();
+    let job_id = progress.job_id.clone();
+    tokio::spawn(async move {
+        let done = run_scan(&state, &progress, pak_files, deep, &cancel, |event| {
+            if let ProgressUpdate::PakScanned { pak_file, paks_done, paks_total, assets_found, error } = event.update {
+                let event = ScanProgressEvent { job_id: event.job_id, paks_done, paks_total, assets_found, current_pak: pak_file, error };
+                let _ = app.emit("scan://progress", &event);
+            }
+        })
+        .await;
+        let _ = app.emit("scan://done", &done);
+    });
+    Ok(job_id)
+}
+
+/// Tauri command to stop a scan started with `start_scan` (or `POST /scan`, or a mount's)
+///
+/// The scan stops before its next pak, or within the pak between chunks of its index,
+/// and leaves the listing as it was.
+#[tauri::command]
+async fn cancel_scan(job_id: String, state: tauri::State<'_, AppState>) -> Result<(), PakSeekError> {
+    if state.cancel_job(&job_id) {
+        Ok(())
+    } else {
+        let message = format!("No running scan {}", job_id);
+        Err(ApiError::new(ErrorCode::JobNotFound, message).with_context(serde_json::json!({ "job_id": job_id })).into())
+    }
+}
+
+/// Tauri command to stop any running job: a scan, a mount's scan, an `extract_assets` run
+/// or a `find_duplicates` one
+#[tauri::command]
+async fn cancel_job(job_id: String, state: tauri::State<'_, AppState>) -> Result<(), PakSeekError> {
+    if state.cancel_job(&job_id) {
+        Ok(())
+    } else {
+        let message = format!("No running job {}", job_id);
+        Err(ApiError::new(ErrorCode::JobNotFound, message).with_context(serde_json::json!({ "job_id": job_id })).into())
+    }
+}
+
+/// The .pak files a scan of `folder` covers: the file itself, or the paks in a directory
+async fn find_scan_targets(folder: &str) -> Result<Vec<String>, String> {
+    // Check if the path exists first
+    let path = std::path::Path::new(folder);
+    if !path.exists() {
+        let error_msg = format!("Path does not exist: {}", folder);
+        warn!("{}", error_msg);
+        return Err(error_msg);
+    }
+    
+    let pak_files = if path.is_file() && folder.to_lowercase().ends_with(".pak") {
+        // Single .pak file provided
+        debug!("Scanning the single pak {}", folder);
+        vec![folder.to_string()]
+    } else if path.is_dir() {
+        // Directory provided - scan for .pak files
+        debug!("Looking for .pak files in {}", folder);
+        match pak_parser::utils::find_pak_files(folder).await {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Failed to scan directory '{}': {}; listing it by hand", folder, e);
+                
+                // Fallback: try manual directory scan
+                match std::fs::read_dir(folder) {
+                    Ok(entries) => {
+                        let mut manual_pak_files = Vec::new();
+                        for entry in entries {
+                            if let Ok(entry) = entry {
+                                let entry_path = entry.path();
+                                tracing::trace!("Found file: {:?}", entry_path);
+                                if let Some(ext) = entry_path.extension() {
+                                    if ext == "pak" {
+                                        if let Some(path_str) = entry_path.to_str() {
+                                            manual_pak_files.push(path_str.to_string());
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        manual_pak_files
+                    },
+                    Err(dir_err) => {
+                        let final_error = format!("Failed to read directory '{}': {}", folder, dir_err);
+                        warn!("{}", final_error);
+                        return Err(final_error);
+                    }
+                }
+            }
+        }
+    } else {
+        let error_msg = format!("Path is neither a .pak file nor a directory: {}", folder);
+        warn!("{}", error_msg);
+        return Err(error_msg);
+    };
+    
+    debug!("Found {} .pak files in {}: {:?}", pak_files.len(), folder, pak_files);
+    Ok(pak_files)
+}
+
+/// A `load_scan` callback that reports each pak as a `PakScanned` event of `job_id`
+fn pak_scanned_events(
+    job_id: &str,
+    paks_total: usize,
+    mut send: impl FnMut(ProgressEvent),
+) -> impl FnMut(&str, Result<usize, &anyhow::Error>) {
+    let job_id = job_id.to_string();
+    let (mut paks_done, mut assets_found) = (0, 0);
+    move |pak_file, result| {
+        paks_done += 1;
+        let error = match result {
+            Ok(assets) => {
+                assets_found += assets;
+                None
+            }
+            Err(e) => Some(PakSeekError::from(PakError::open(pak_file, e)).to_api_error()),
+        };
+        let update = ProgressUpdate::PakScanned { pak_file: pak_file.to_string(), paks_done, paks_total, assets_found, error };
+        send(ProgressEvent { job_id: job_id.clone(), update });
+    }
+}
+
+/// Assets and known dependency graphs of a set of paks
+///
+/// Unchanged paks reuse the index saved by an earlier scan. `on_pak` is called as each
+/// pak is done, with its asset count or why it couldn't be read. None when `cancel` fired
+/// first; nothing is saved then.
+async fn load_scan(
+    pak_files: &[String],
+    deep_scan: bool,
+    cancel: &CancellationToken,
+    on_pak: impl FnMut(&str, Result<usize, &anyhow::Error>),
+) -> Option<(Vec<Asset>, DependencyMap)> {
+    let digest = scan_cache::pak_set_digest(pak_files, if deep_scan { "deep" } else { "" });
+    let all_assets = match scan_cache::cached_assets(&digest).await {
+        Some(assets) => {
+            info!("Using the cached index of {} assets", assets.len());
+            assets
+        }
+        None => scan_cache::store_assets(&digest, scan_pak_assets(pak_files, deep_scan, cancel, on_pak).await?).await,
+    };
+
+    // Graphs already built for these paks carry over, by pak so one can be swapped out later;
+    // the rest fill in as previews run
+    let mut dependencies = DependencyMap::new();
+    for (pak_file, pak_dependencies) in cached_pak_dependencies(pak_files).await {
+        dependencies.add_assets_from(&pak_file, &pak_dependencies);
+    }
+    Some((all_assets, dependencies))
+}
+
+/// Dependency graphs saved for each of `pak_files`, for those that have one
+async fn cached_pak_dependencies(pak_files: &[String]) -> Vec<(String, DependencyMap)> {
+    let mut graphs = Vec::new();
+    for pak_file in pak_files {
+        let digest = scan_cache::pak_set_digest(std::slice::from_ref(pak_file), "");
+        if let Some(pak_dependencies) = scan_cache::cached_dependencies(&digest).await {
+            graphs.push((pak_file.clone(), pak_dependencies));
+        }
+    }
+    graphs
+}
+
+/// Tauri command to add a folder, .pak or .utoc to the listing
+///
+/// Scans it, sending `progress` events under the mount id, and returns the mount once
+/// its assets are listed. Higher `priority` wins when mounts have the same asset path.
+#[tauri::command]
+async fn add_mount(
+    path: String,
+    priority: Option<i32>,
+    deep_scan: Option<bool>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<mounts::Mount, PakSeekError> {
+    let request = MountRequest { path, priority: priority.unwrap_or(0), deep: deep_scan.unwrap_or(false) };
+    let (mount, task) = add_mount_to(&state, request, move |event| {
+        let _ = app.emit("progress", &event);
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    task.await.map_err(|e| format!("Scan of {} failed: {}", mount.path, e))?;
+    let scanned = state.mounts.lock().unwrap().get(&mount.id).cloned();
+    Ok(scanned.ok_or_else(|| format!("{} was removed while scanning", mount.path))?)
+}
+
+/// Tauri command to take a mount's assets and dependency edges out of the listing
+#[tauri::command]
+async fn remove_mount(id: String, state: tauri::State<'_, AppState>) -> Result<mounts::Mount, PakSeekError> {
+    Ok(remove_mount_from(&state, &id).await?)
+}
+
+/// What a dropped path was taken for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DroppedKind {
+    Pak,
+    Utoc,
+    Folder,
+    Unsupported,
+}
+
+/// What became of one path dropped onto the window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedPath {
+    pub path: String,
+    pub kind: DroppedKind,
+    /// The mount it was added as, also the job id of its scan
+    pub mount_id: Option<String>,
+    /// Why it wasn't opened
+    pub warning: Option<String>,
+}
+
+impl DroppedPath {
+    fn warning(path: String, kind: DroppedKind, warning: String) -> Self {
+        DroppedPath { path, kind, mount_id: None, warning: Some(warning) }
+    }
+}
+
+/// Whether a dropped path can be opened, and as what
+fn classify_dropped(path: &std::path::Path) -> DroppedKind {
+    if path.is_dir() {
+        return DroppedKind::Folder;
+    }
+    match path.extension().and_then(|extension| extension.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("pak") if path.is_file() => DroppedKind::Pak,
+        Some("utoc") if path.is_file() => DroppedKind::Utoc,
+        _ => DroppedKind::Unsupported,
+    }
+}
+
+/// Adds each dropped folder, .pak and .utoc as a mount and starts scanning it
+///
+/// Paths that can't be opened (unsupported, a .utoc without its .ucas, already mounted)
+/// get a warning and the others go ahead. Each scan reports like a `start_scan` job, under
+/// the mount id.
+async fn open_dropped(state: &AppState, app: &tauri::AppHandle, paths: Vec<String>) -> Vec<DroppedPath> {
+    let mut opened = Vec::with_capacity(paths.len());
+    for path in paths {
+        let kind = classify_dropped(std::path::Path::new(&path));
+        match kind {
+            DroppedKind::Unsupported => {
+                let warning = if path.to_lowercase().ends_with(".ucas") {
+                    "Drop the .utoc next to it to open an IoStore container".to_string()
+                } else {
+                    "Not a folder, .pak or .utoc".to_string()
+                };
+                opened.push(DroppedPath::warning(path, kind, warning));
+                continue;
+            }
+            DroppedKind::Utoc if !std::path::Path::new(&path).with_extension("ucas").is_file() => {
+                opened.push(DroppedPath::warning(path, kind, "Its .ucas is missing".to_string()));
+                continue;
+            }
+            _ => {}
+        }
+        let request = MountRequest { path: path.clone(), priority: 0, deep: false };
+        match add_mount_to(state, request, emit_as_scan(app.clone(), state.clone(), path.clone())).await {
+            Ok((mount, _)) => {
+                info!("Opened dropped {} as mount {}", path, mount.id);
+                opened.push(DroppedPath { path, kind, mount_id: Some(mount.id), warning: None });
+            }
+            Err(e) => opened.push(DroppedPath::warning(path, kind, e.message)),
+        }
+    }
+    opened
+}
+
+/// Sends a mount's scan progress as `start_scan` does, with the mount id as job id:
+/// `scan://progress` for each pak and the final `ScanProgress` as `scan://done`
+fn emit_as_scan(app: tauri::AppHandle, state: AppState, path: String) -> impl Fn(ProgressEvent) + Send + Sync + 'static {
+    let progress: std::sync::Mutex<Option<ScanProgress>> = std::sync::Mutex::new(None);
+    move |event| {
+        let mut progress = progress.lock().unwrap();
+        let job = progress.get_or_insert_with(|| ScanProgress {
+            job_id: event.job_id.clone(),
+            path: path.clone(),
+            status: ScanStatus::Running,
+            paks_done: 0,
+            paks_total: 0,
+            assets_found: 0,
+            errors: Vec::new(),
+        });
+        match event.update {
+            ProgressUpdate::PakScanned { pak_file, paks_done, paks_total, assets_found, error } => {
+                job.paks_done = paks_done;
+                job.paks_total = paks_total;
+                job.assets_found = assets_found;
+                job.errors.extend(error.clone());
+                let event = ScanProgressEvent { job_id: event.job_id, paks_done, paks_total, assets_found, current_pak: pak_file, error };
+                let _ = app.emit("scan://progress", &event);
+            }
+            ProgressUpdate::ScanCompleted { assets_found } => {
+                // Paks read from the index cache send no progress, so take the count from the mount
+                if let Some(mount) = state.mounts.lock().unwrap().get(&event.job_id) {
+                    job.paks_total = mount.pak_files.len();
+                }
+                job.status = ScanStatus::Completed;
+                job.paks_done = job.paks_total;
+                job.assets_found = assets_found;
+                let _ = app.emit("scan://done", &*job);
+            }
+            ProgressUpdate::ScanCancelled { assets_found } => {
+                job.status = ScanStatus::Cancelled;
+                job.assets_found = assets_found;
+                let _ = app.emit("scan://done", &*job);
+            }
+        }
+    }
+}
+
+/// Tauri command to open paths dropped onto the window (or picked together) as mounts
+///
+/// Each folder, .pak and .utoc is added as a mount and scanned in the background, sending
+/// `scan://progress` and `scan://done` as `start_scan` does, with the mount id as job id.
+/// Returns what became of each path; the ones that can't be opened carry a `warning`.
+/// Drops onto the window are handled without the UI, which gets this as `drop://opened`.
+#[tauri::command]
+async fn open_dropped_paths(
+    paths: Vec<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DroppedPath>, PakSeekError> {
+    Ok(open_dropped(&state, &app, paths).await)
+}
+
+/// Sent as `assets://changed` once a watched mount's changed paks are rescanned
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetsChangedEvent {
+    pub mount_id: String,
+    /// Paks created, rewritten or deleted; deleted ones are no longer among the mount's paks
+    pub pak_files: Vec<String>,
+}
+
+/// Tauri command to keep a mount up to date as its paks are written, replaced or deleted
+///
+/// `path` is the mount's path as added. After `folder_watch::DEBOUNCE` without further
+/// changes, each changed pak is rescanned on its own, once it has finished being written,
+/// and `assets://changed` is emitted. Errors are `ApiError` JSON (`NOT_MOUNTED`, `WATCH_FAILED`).
+#[tauri::command]
+async fn watch_folder(path: String, app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<mounts::Mount, PakSeekError> {
+    let mount = mount_at(&state, &path)?;
+    if mount.watched {
+        return Ok(mount);
+    }
+    let (watcher, mut changes) = folder_watch::watch(std::path::Path::new(&mount.path)).map_err(|e| {
+        let error = ApiError::new(ErrorCode::WatchFailed, format!("Failed to watch {}: {}", mount.path, e));
+        PakSeekError::from(error.with_context(serde_json::json!({ "path": mount.path })))
+    })?;
+    state.watchers.lock().unwrap().insert(mount.id.clone(), watcher);
+    let mut mounts = state.mounts.lock().unwrap();
+    mounts.update(&mount.id, |mount| mount.watched = true);
+    let mount = mounts.get(&mount.id).cloned().unwrap_or(mount);
+    drop(mounts);
+    info!("Watching {} for mount {}", mount.path, mount.id);
+
+    let state = state.inner().clone();
+    let mount_id = mount.id.clone();
+    tokio::spawn(async move {
+        // Ends once the watcher is dropped by `unwatch_folder` or the mount's removal
+        while let Some(batch) = folder_watch::next_batch(&mut changes).await {
+            let mut pak_files = Vec::new();
+            for pak_file in batch {
+                if rescan_pak(&state, &mount_id, &pak_file).await {
+                    pak_files.push(pak_file);
+                }
+            }
+            if !pak_files.is_empty() {
+                state.rebuild_from_mounts().await;
+                info!("Rescanned {} changed paks of mount {}", pak_files.len(), mount_id);
+                let _ = app.emit("assets://changed", &AssetsChangedEvent { mount_id: mount_id.clone(), pak_files });
+            }
+        }
+    });
+    Ok(mount)
+}
+
+/// Tauri command to stop keeping a mount up to date; the mount and its assets stay
+#[tauri::command]
+async fn unwatch_folder(path: String, state: tauri::State<'_, AppState>) -> Result<mounts::Mount, PakSeekError> {
+    let mount = mount_at(&state, &path)?;
+    state.watchers.lock().unwrap().remove(&mount.id);
+    let mut mounts = state.mounts.lock().unwrap();
+    mounts.update(&mount.id, |mount| mount.watched = false);
+    Ok(mounts.get(&mount.id).cloned().unwrap_or(mount))
+}
+
+/// The mount added for `path`
+fn mount_at(state: &AppState, path: &str) -> Result<mounts::Mount, ApiError> {
+    state.mounts.lock().unwrap().by_path(path).cloned().ok_or_else(|| {
+        ApiError::new(ErrorCode::NotMounted, format!("{} is not mounted", path)).with_context(serde_json::json!({ "path": path }))
+    })
+}
+
+/// Rescans one pak of a mount after it changed on disk, leaving the mount's other paks alone
+///
+/// Waits for a pak being written to settle first. A deleted pak takes its assets and
+/// dependency edges with it. False when nothing changed: the pak didn't settle, or the
+/// mount has been removed meanwhile. The listing is rebuilt by the caller.
+async fn rescan_pak(state: &AppState, mount_id: &str, pak_file: &str) -> bool {
+    let Some(deep) = state.mounts.lock().unwrap().get(mount_id).map(|mount| mount.deep) else {
+        return false;
+    };
+    let path = std::path::Path::new(pak_file);
+    let (assets, error) = if path.exists() {
+        if folder_watch::wait_until_settled(path).await.is_none() {
+            warn!("{} kept changing, leaving it for its next change", pak_file);
+            return false;
+        }
+        let mut error = None;
+        let pak_files = [pak_file.to_string()];
+        let assets = scan_pak_assets(&pak_files, deep, &CancellationToken::new(), |pak_file, result| {
+            error = result.err().map(|e| PakSeekError::from(PakError::open(pak_file, e)).to_api_error());
+        })
+        .await
+        .unwrap_or_default();
+        (Some(assets), error)
+    } else {
+        (None, None)
+    };
+
+    let graphs = if assets.is_some() { cached_pak_dependencies(&[pak_file.to_string()]).await } else { Vec::new() };
+    // Under the graph lock, as when adding a mount, so a removal racing this leaves no edges
+    let mut dependencies = state.dependencies.lock().await;
+    if !state.mounts.lock().unwrap().replace_pak(mount_id, pak_file, assets, error) {
+        return false;
+    }
+    dependencies.remove_assets_from(pak_file);
+    for (pak_file, pak_dependencies) in &graphs {
+        dependencies.add_assets_from(pak_file, pak_dependencies);
+    }
+    true
+}
+
+/// Tauri command to extract an asset to a file, e.g. one picked in a save dialog
+///
+/// The asset is `asset_id` when given, else `asset_path` in `pak_path`, which has to be a
+/// pak of a mount, the pak being looked up among the scanned assets when omitted. An
+/// existing file at `destination` is only replaced with `overwrite`. Errors are `ApiError`
+/// JSON, with codes such as `ENTRY_ENCRYPTED` (no AES key), `DECOMPRESSOR_UNAVAILABLE`
+/// (Oodle), `NOT_MOUNTED` and `DESTINATION_EXISTS` for the UI to explain.
+#[tauri::command]
+async fn extract_asset(
+    asset_id: Option<String>,
+    asset_path: Option<String>,
+    pak_path: Option<String>,
+    destination: String,
+    overwrite: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ExtractResult, PakSeekError> {
+    let (asset_path, pak_file) = asset_location(&state, asset_id.as_deref(), asset_path.as_deref(), pak_path.as_deref())
+        .await?;
+    extract_to_file(&asset_path, &pak_file, std::path::Path::new(&destination), overwrite.unwrap_or(false), &CancellationToken::new())
+        .await
+}
+
+/// Tauri command to save a texture as an image: `format` is "png", "tga" or "dds"
+///
+/// `mip` picks a mip by index, 0 being the largest one cooked; the largest with data by
+/// default. Textures whose pixel format can't be decoded are saved as DDS.
+#[tauri::command]
+async fn convert_texture(
+    asset_path: String,
+    pak_path: Option<String>,
+    destination: String,
+    format: String,
+    mip: Option<u32>,
+    overwrite: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ConvertedTexture, PakSeekError> {
+    let format = texture_decode::ImageFormat::parse(&format).map_err(|e| {
+        PakSeekError::from(ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_context(serde_json::json!({ "field": "format" })))
+    })?;
+    let pak_file = match pak_path {
+        Some(pak_path) => pak_path,
+        None => asset_pak_file(&state, &asset_path).await?,
+    };
+    convert_texture_to_file(&asset_path, &pak_file, std::path::Path::new(&destination), format, mip, overwrite.unwrap_or(false))
+        .await
+}
+
+/// Tauri command to extract many assets, or whole folders of them, under `destination`
+///
+/// Entries are extracted a few at a time, each reported as an `extract://progress` event.
+/// `job_id` (made up when omitted) tags the events and is what `cancel_job` takes. With
+/// `preserve_paths` the pak folders are recreated under `destination`; otherwise files go
+/// side by side, with numeric suffixes on clashing names. Existing files are only replaced
+/// with `overwrite`.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn extract_assets(
+    selection: Vec<String>,
+    destination: String,
+    preserve_paths: bool,
+    overwrite: Option<bool>,
+    job_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<ExtractionSummary, PakSeekError> {
+    let (job_id, cancel) = state.register_job(job_id)?;
+    info!("Extraction {} started for {} selected paths to {}", job_id, selection.len(), destination);
+
+    let destination = std::path::Path::new(&destination);
+    let mut summary = extract_selection(&state, &selection, destination, preserve_paths, overwrite.unwrap_or(false), &cancel, |event| {
+        let _ = app.emit("extract://progress", &ExtractProgressEvent { job_id: job_id.clone(), ..event });
+    })
+    .await;
+    state.finish_job(&job_id);
+
+    info!(
+        "Extraction {} {} with {} files ({} bytes), {} failed, {} encrypted",
+        job_id,
+        if summary.cancelled { "cancelled" } else { "finished" },
+        summary.extracted,
+        summary.total_bytes,
+        summary.failed.len(),
+        summary.skipped_encrypted
+    );
+    summary.job_id = job_id;
+    Ok(summary)
+}
+
+/// The registered pak `pak_path` refers to, as the mount lists it
+///
+/// Paths are compared canonicalized, so `..` or a symlink can't pass off a file outside
+/// the mounts as one of their paks.
+fn mounted_pak(state: &AppState, pak_path: &str) -> Result<String, ApiError> {
+    let not_mounted = || {
+        ApiError::new(ErrorCode::NotMounted, format!("{} is not a pak of any mount", pak_path))
+            .with_context(serde_json::json!({ "pak_file": pak_path }))
+    };
+    let wanted = std::fs::canonicalize(pak_path).map_err(|_| not_mounted())?;
+    let mounts = state.mounts.lock().unwrap().list();
+    mounts
+        .iter()
+        .flat_map(|mount| mount.pak_files.iter())
+        .find(|pak_file| std::fs::canonicalize(pak_file).is_ok_and(|pak_file| pak_file == wanted))
+        .cloned()
+        .ok_or_else(not_mounted)
+}
+
+/// Characters left as they are in the path of a `file://` URI
+const URI_PATH: &percent_encoding::AsciiSet =
+    &percent_encoding::NON_ALPHANUMERIC.remove(b'/').remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// Shows `path` selected in the OS file manager
+///
+/// On Linux, file managers that implement `org.freedesktop.FileManager1` select it;
+/// with others its folder is opened instead.
+async fn reveal_in_file_manager(app: &tauri::AppHandle, path: &std::path::Path) -> anyhow::Result<()> {
+    use tauri_plugin_shell::ShellExt;
+    let shell = app.shell();
+    if cfg!(target_os = "windows") {
+        shell.command("explorer").arg(format!("/select,{}", path.display())).spawn()?;
+    } else if cfg!(target_os = "macos") {
+        shell.command("open").arg("-R").arg(path).spawn()?;
+    } else {
+        let uri = format!("file://{}", percent_encoding::utf8_percent_encode(&path.to_string_lossy(), URI_PATH));
+        let shown = shell
+            .command("dbus-send")
+            .args([
+                "--session",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+                &format!("array:string:{}", uri),
+                "string:",
+            ])
+            .output()
+            .await;
+        if !shown.is_ok_and(|output| output.status.success()) {
+            shell.command("xdg-open").arg(path.parent().unwrap_or(path)).spawn()?;
+        }
+    }
+    Ok(())
+}
+
+/// Extensions `extract_and_open` hands to the OS default application: images, audio,
+/// video, fonts and plain text, none of which the OS runs
+const OPENABLE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "bmp", "gif", "tga", "dds", "webp", "wav", "ogg", "mp3", "flac", "mp4", "webm", "ttf", "otf",
+    "txt", "json", "csv", "ini", "xml", "log", "md",
+];
+
+/// Whether `path` has an extension the OS default application can safely be launched for
+fn is_openable(path: &str) -> bool {
+    path.rsplit_once('.')
+        .is_some_and(|(_, extension)| OPENABLE_EXTENSIONS.iter().any(|known| extension.eq_ignore_ascii_case(known)))
+}
+
+/// Opens `path` with the OS default application for its extension
+fn open_with_default_app(app: &tauri::AppHandle, path: &std::path::Path) -> anyhow::Result<()> {
+    use tauri_plugin_shell::ShellExt;
+    let shell = app.shell();
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    shell.command(program).arg(path).spawn()?;
+    Ok(())
+}
+
+/// Tauri command to show a pak selected in the OS file manager
+///
+/// Only paks of a mount (or of the last scan) can be revealed.
+#[tauri::command]
+async fn reveal_pak(pak_path: String, app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<(), PakSeekError> {
+    let pak_file = mounted_pak(&state, &pak_path)?;
+    let path = std::fs::canonicalize(&pak_file).unwrap_or_else(|_| pak_file.clone().into());
+    reveal_in_file_manager(&app, &path).await.map_err(|e| {
+        PakSeekError::from(
+            ApiError::new(ErrorCode::OpenFailed, format!("Failed to open the file manager: {:#}", e))
+                .with_context(serde_json::json!({ "pak_file": pak_file })),
+        )
+    })
+}
+
+/// Where `extract_and_open` writes, one folder per opened file
+fn opened_file_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join("pakseek").join("opened")
+}
+
+/// Tauri command to extract an asset to a temp folder and open it with the OS default
+/// application for its extension
+///
+/// The pak must belong to a mount (or the last scan). The file keeps its name, so the right
+/// application is picked, and is removed when the app exits. Only images, audio, video,
+/// fonts and text are opened; anything else (executables and scripts a modded pak could
+/// carry) is shown selected in the file manager instead. Returns where it was written.
+#[tauri::command]
+async fn extract_and_open(
+    asset_path: String,
+    pak_path: String,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, PakSeekError> {
+    let pak_file = mounted_pak(&state, &pak_path)?;
+    let folder = opened_file_dir().join(uuid::Uuid::new_v4().simple().to_string());
+    let name = asset_path.rsplit(['/', '\\']).find(|part| !part.is_empty() && *part != "..").unwrap_or("asset");
+    state.opened_files.lock().unwrap().push(folder.clone());
+    let extracted = extract_to_file(&asset_path, &pak_file, &folder.join(name), true, &CancellationToken::new()).await?;
+
+    let destination = std::path::Path::new(&extracted.destination);
+    let opened = if is_openable(&extracted.destination) {
+        open_with_default_app(&app, destination)
+    } else {
+        reveal_in_file_manager(&app, destination).await
+    };
+    opened.map_err(|e| {
+        PakSeekError::from(
+            ApiError::new(ErrorCode::OpenFailed, format!("Failed to open {}: {:#}", extracted.destination, e))
+                .with_context(serde_json::json!({ "path": asset_path, "destination": extracted.destination })),
+        )
+    })?;
+    Ok(extracted.destination)
+}
+
+/// Header-level details of a container, as `get_pak_info` returns them
+#[derive(Serialize, Deserialize, Clone, utoipa::ToSchema)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum ContainerInfo {
+    Pak(pak_parser::PakInfo),
+    /// A .utoc/.ucas (IoStore) container
+    Utoc(utoc_parser::TocInfo),
+}
+
+/// Entry count and uncompressed bytes of the loaded assets from `pak_file`
+async fn listed_totals(state: &AppState, pak_file: &str) -> Option<(u64, u64)> {
+    let assets = state.assets.lock().await;
+    let listed = assets.iter().filter(|asset| asset.pak_file.as_deref() == Some(pak_file));
+    let (count, bytes) = listed.fold((0u64, 0u64), |(count, bytes), asset| (count + 1, bytes + asset.size));
+    (count > 0).then_some((count, bytes))
+}
+
+/// Tauri command to get a .pak's or .utoc's header-level details
+///
+/// Only the footer (or TOC header) and a few bytes after it are read, so it answers at
+/// once for any size of container. Uncompressed totals come from the listing and are
+/// null for containers that aren't loaded.
+#[tauri::command]
+async fn get_pak_info(path: String, state: tauri::State<'_, AppState>) -> Result<ContainerInfo, PakSeekError> {
+    if path.to_lowercase().ends_with(".utoc") {
+        let parser = UtocUcasParser::new(&path).map_err(|e| ContainerError::open(&path, &e))?;
+        let mut info = parser.info().await.map_err(|e| ContainerError::open(&path, &e))?;
+        // A container's assets are listed under the .pak that mounts it
+        let pak_file = std::path::Path::new(&path).with_extension("pak");
+        info.total_uncompressed_bytes = listed_totals(&state, &pak_file.to_string_lossy()).await.map(|(_, bytes)| bytes);
+        return Ok(ContainerInfo::Utoc(info));
+    }
+
+    let mut info = PakParser::new(&path).info().await.map_err(|e| PakError::open(&path, &e))?;
+    if let Some((count, bytes)) = listed_totals(&state, &path).await {
+        info.entry_count = info.entry_count.or(Some(count));
+        info.total_uncompressed_bytes = Some(bytes);
+    }
+    Ok(ContainerInfo::Pak(info))
+}
+
+/// Tauri command to get what is loaded and what the process uses, as `/health` reports it
+#[tauri::command]
+async fn get_status(state: tauri::State<'_, AppState>) -> Result<StatusReport, PakSeekError> {
+    Ok(status_report(&state).await)
+}
+
+/// What to capture when the app is slow or uses too much memory
+async fn diagnostics_report(state: &AppState) -> diagnostics::Diagnostics {
+    use diagnostics::StructureSize;
+
+    let mut structures = {
+        let assets = state.assets.lock().await;
+        vec![
+            StructureSize::of::<Asset>("assets", assets.len()),
+            StructureSize::of::<(String, Vec<usize>)>("asset_paths", assets.distinct_paths()),
+        ]
+    };
+    {
+        let dependencies = state.dependencies.lock().await;
+        let edges = dependencies.dependencies.values().map(Vec::len).sum();
+        structures.push(StructureSize::of::<(String, Vec<String>)>("dependency_assets", dependencies.dependencies.len()));
+        structures.push(StructureSize::of::<String>("dependency_edges", edges));
+        structures.push(StructureSize::of::<(String, dependency_map::AssetNode)>("dependency_nodes", dependencies.nodes.len()));
+    }
+    let mounts = state.mounts.lock().unwrap().list();
+    let mounted_assets = mounts.iter().map(|mount| mount.assets).sum();
+    structures.push(StructureSize::of::<mounts::Mount>("mounts", mounts.len()));
+    structures.push(StructureSize::of::<Asset>("mounted_assets", mounted_assets));
+    structures.sort_by_key(|structure| std::cmp::Reverse(structure.approx_bytes));
+
+    let memory = memory_stats::memory_stats();
+    let open_paks = pak_parser::open_pak_paths();
+    diagnostics::Diagnostics {
+        generated_at: chrono::Utc::now(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        platform: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        active_profile: settings::global().get().active_profile,
+        uptime_secs: PROCESS_STARTED.get().map_or(0, |started| started.elapsed().as_secs()),
+        rss_bytes: memory.map(|stats| stats.physical_mem as u64),
+        virtual_bytes: memory.map(|stats| stats.virtual_mem as u64),
+        open_pak_handles: open_paks.len(),
+        open_paks,
+        pak_index_reads: pak_parser::index_reads(),
+        pak_index_cache_hits: pak_parser::index_cache_hits(),
+        preview_cache: preview_cache::global().stats(),
+        structures,
+        timings: diagnostics::timings(),
+    }
+}
+
+/// Tauri command to get memory use, open pak handles, the sizes of the in-memory
+/// structures and the last scan, preview and extraction timings, for bug reports
+#[tauri::command]
+async fn get_diagnostics(state: tauri::State<'_, AppState>) -> Result<diagnostics::Diagnostics, PakSeekError> {
+    Ok(diagnostics_report(&state).await)
+}
+
+/// Tauri command to write the diagnostics and the recent log lines to a zip at `path`,
+/// to attach to a bug report; returns the zip's size in bytes
+#[tauri::command]
+async fn export_diagnostics(path: String, state: tauri::State<'_, AppState>) -> Result<u64, PakSeekError> {
+    let report = diagnostics_report(&state).await;
+    let destination = std::path::PathBuf::from(&path);
+    let bytes = tokio::task::spawn_blocking(move || diagnostics::write_bundle(&report, &destination))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+    info!("Exported diagnostics to {} ({} bytes)", path, bytes);
+    Ok(bytes)
+}
+
+/// Tauri command to change what gets logged: a level ("debug") or filter directives
+/// ("info,pakseek=trace"); lasts until the app exits
+#[tauri::command]
+async fn set_log_level(level: String) -> Result<(), PakSeekError> {
+    logging::set_filter(&level).map_err(|e| {
+        PakSeekError::from(
+            ApiError::new(ErrorCode::InvalidRequest, format!("{:#}", e)).with_context(serde_json::json!({ "field": "level" })),
+        )
+    })?;
+    info!("Log level set to {}", level);
+    Ok(())
+}
+
+/// Tauri command to get the last `lines` lines of the log file (200 by default), oldest first
+#[tauri::command]
+async fn get_recent_logs(lines: Option<usize>) -> Result<Vec<String>, PakSeekError> {
+    let lines = lines.unwrap_or(200);
+    Ok(tokio::task::spawn_blocking(move || logging::recent_lines(lines))
+        .await
+        .map_err(|e| e.to_string())??)
+}
+
+/// Tauri command to list the mounts with their pak and asset counts and scan status
+#[tauri::command]
+async fn get_mounts(state: tauri::State<'_, AppState>) -> Result<Vec<mounts::Mount>, PakSeekError> {
+    Ok(state.mounts.lock().unwrap().list())
+}
+
+/// Tauri command to get one asset with its pak entry, preview cache status and direct
+/// dependencies/dependents
+#[tauri::command]
+async fn get_asset_details(
+    id: Option<String>,
+    path: Option<String>,
+    pak_file: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<AssetDetails, PakSeekError> {
+    asset_details(&state, id.as_deref(), path.as_deref(), pak_file.as_deref())
+        .await
+        .map_err(|e| ApiError::asset(e, id.as_deref().or(path.as_deref()).unwrap_or_default()).into())
+}
+
+/// Tauri command to get preview data for a specific asset
+///
+/// Looks the asset up by `id`, or else by exact `path` (plus `pak_file` when the path is
+/// in several paks); `asset_name` is a fallback for older callers. Pass a `request_id` to be able to
+/// `cancel_preview` it; the response echoes the id. With `as_file`, binary previews are
+/// written to a temp file and returned as its path, for use with the asset protocol.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn get_preview(
+    asset_name: Option<String>,
+    id: Option<String>,
+    path: Option<String>,
+    pak_file: Option<String>,
+    options: Option<PreviewOptions>,
+    request_id: Option<String>,
+    as_file: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<PreviewResponse, PakSeekError> {
+    info!("Getting preview for asset: {:?} (id: {:?}, name: {:?})", path, id, asset_name);
+    
+    let options = options.unwrap_or_default();
+    
+    // Copy the asset out so a rescan isn't held up by the preview
+    let asset = {
+        let assets = state.assets.lock().await;
+        assets.lookup(id.as_deref(), path.as_deref(), pak_file.as_deref(), asset_name.as_deref())
+            .cloned()
+            .map_err(|e| ApiError::asset(e, id.as_deref().or(path.as_deref()).or(asset_name.as_deref()).unwrap_or_default()))?
+    };
+    let mut preview_data = preview_and_record_dependencies(&state, &asset, &options, request_id).await;
+
+    if as_file.unwrap_or(false) {
+        if let Some((mime, bytes)) = preview::utils::raw_payload(&preview_data) {
+            let file = write_preview_file(&asset, &mime, &bytes).await.map_err(|e| PreviewError::Failed {
+                path: asset.path.clone(),
+                message: format!("Failed to write preview file: {}", e),
+            })?;
+            preview_data.data = PreviewData::File { path: file.to_string_lossy().into_owned(), mime_type: mime };
+        }
+    }
+    Ok(preview_data)
+}
+
+/// Where `as_file` previews are written; removed when the app exits
+fn preview_file_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join("pakseek").join("previews")
+}
+
+/// Writes preview bytes under the temp directory, one file per asset, and returns the path
+async fn write_preview_file(asset: &Asset, mime: &str, bytes: &[u8]) -> std::io::Result<std::path::PathBuf> {
+    let dir = preview_file_dir();
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let stem: String = asset
+        .pak_file
+        .as_deref()
+        .into_iter()
+        .chain(std::iter::once(asset.path.as_str()))
+        .flat_map(|part| part.chars().chain(std::iter::once('_')))
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let file = dir.join(format!("{}.{}", stem.trim_matches('_'), preview::utils::extension_for_mime(mime)));
+    tokio::fs::write(&file, bytes).await?;
+    Ok(file)
+}
+
+/// Tauri command to generate previews for many assets at once
+///
+/// Individual failures are reported per item instead of failing the call.
+#[tauri::command]
+async fn get_previews(
+    paths: Vec<String>,
+    pak_file: Option<String>,
+    options: Option<PreviewOptions>,
+    concurrency: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<preview::BatchPreviewResult>, PakSeekError> {
+    info!("Getting {} previews", paths.len());
+
+    let items = {
+        let assets = state.assets.lock().await;
+        resolve_batch_items(&assets, paths, pak_file.as_deref())
+    };
+    Ok(preview::run_preview_batch(items, &options.unwrap_or_default(), concurrency).await)
+}
+
+/// Tauri command to cancel an in-flight preview started with the given request id
+#[tauri::command]
+async fn cancel_preview(request_id: String) -> Result<bool, PakSeekError> {
+    info!("Cancelling preview request: {}", request_id);
+    Ok(preview::cancel_preview(&request_id))
+}
+
+/// Tauri command to export a DataTable/CurveTable as CSV or JSON
+///
+/// `asset_path` is the path inside `pak_file`, or a loose cooked .uasset on disk when no pak
+/// is given. Writes to `output_path` (default: the asset name in the working directory)
+/// and returns the path written.
+#[tauri::command]
+async fn export_datatable(
+    asset_path: String,
+    format: String,
+    pak_file: Option<String>,
+    output_path: Option<String>,
+) -> Result<String, PakSeekError> {
+    info!("Exporting table {} as {}", asset_path, format);
+
+    let format = table_parser::TableFormat::parse(&format).map_err(|e| {
+        ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_context(serde_json::json!({ "field": "format" }))
+    })?;
+    let asset = Asset {
+        id: preview::asset_id(pak_file.as_deref(), &asset_path),
+        name: extract_asset_name(&asset_path, &settings::global().get().name_prefixes),
+        file_name: file_name_of(&asset_path),
+        path: asset_path.clone(),
+        asset_type: AssetType::DataTable,
+        size: 0,
+        // Not interned: the path comes from the caller, not from a scan
+        pak_file: pak_file.as_deref().map(|pak| intern::lookup(pak).unwrap_or_else(|| Arc::from(pak))),
+        compressed_size: None,
+        compression_method: None,
+        is_encrypted: None,
+        hash: None,
+        type_confidence: TypeConfidence::Heuristic,
+        parts: Vec::new(),
+        chunk_id: pak_file.as_deref().and_then(pak_parser::chunk_id),
+        tags: HashMap::new(),
+        last_modified: chrono::Utc::now(),
+        metadata: None,
+    };
+
+    let failed = |e: anyhow::Error| {
+        if error::needs_mappings(&e) {
+            PreviewError::MappingsRequired { path: asset_path.clone() }
+        } else {
+            PreviewError::Failed { path: asset_path.clone(), message: format!("{:#}", e) }
+        }
+    };
+    let data = preview::load_package_bytes(&asset).await.map_err(failed)?;
+    let table = table_parser::parse_table(&data).map_err(failed)?;
+
+    let contents = match format {
+        table_parser::TableFormat::Csv => table_parser::to_csv(&table),
+        table_parser::TableFormat::Json => table_parser::to_json(&table).map_err(|e| e.to_string())?,
+    };
+    let output_path = output_path.unwrap_or_else(|| format!("{}.{}", asset.name, format.extension()));
+
+    tokio::fs::write(&output_path, contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", output_path, e))?;
+
+    info!("Exported {} rows to {}", table.rows.len(), output_path);
+    Ok(output_path)
+}
+
+/// Tauri command to report preview cache usage for the settings screen
+#[tauri::command]
+async fn get_preview_cache_stats() -> Result<serde_json::Value, PakSeekError> {
+    Ok(preview::utils::get_cache_stats().await?)
+}
+
+/// Tauri command to delete all cached previews; returns how many were removed
+#[tauri::command]
+async fn clear_preview_cache() -> Result<usize, PakSeekError> {
+    Ok(preview::utils::clear_preview_cache().await?)
+}
+
+/// Tauri command to change the preview cache size budget, saving it in the settings
+#[tauri::command]
+async fn set_preview_cache_budget(max_size_mb: u64) -> Result<(), PakSeekError> {
+    info!("Setting preview cache budget to {} MB", max_size_mb);
+    let patch = settings::SettingsPatch { preview_cache_mb: Some(max_size_mb), ..Default::default() };
+    settings::global().update(patch)?;
+    preview_cache::global().set_budget(max_size_mb * 1024 * 1024).await;
+    Ok(())
+}
+
+/// Tauri command to get the settings; AES keys are redacted unless `reveal_keys` is set
+#[tauri::command]
+async fn get_settings(reveal_keys: Option<bool>) -> Result<settings::Settings, PakSeekError> {
+    let current = settings::global().get();
+    Ok(if reveal_keys.unwrap_or(false) { current } else { current.redacted() })
+}
+
+/// Tauri command to get the folders recently scanned with `start_scan` and the favorites
+///
+/// Folders that no longer exist are kept, with `exists: false`.
+#[tauri::command]
+async fn get_recent_folders() -> Result<settings::RecentFolders, PakSeekError> {
+    Ok(settings::global().get().folders())
+}
+
+/// Tauri command to add a folder to the favorites; returns the folders as `get_recent_folders` does
+#[tauri::command]
+async fn pin_favorite(path: String) -> Result<settings::RecentFolders, PakSeekError> {
+    Ok(settings::global().pin_favorite(&path)?.folders())
+}
+
+/// Tauri command to remove a folder from the favorites; returns the folders as `get_recent_folders` does
+#[tauri::command]
+async fn unpin_favorite(path: String) -> Result<settings::RecentFolders, PakSeekError> {
+    Ok(settings::global().unpin_favorite(&path)?.folders())
+}
+
+/// Tauri command to change and save settings; returns them as `get_settings` does
+///
+/// Keys and the Oodle library apply from the next extraction, the cache budget at once.
+/// Errors are `ApiError` JSON: `INVALID_REQUEST` with the `field` at fault, or
+/// `SETTINGS_WRITE_FAILED`; either way nothing changes.
+#[tauri::command]
+async fn update_settings(patch: settings::SettingsPatch, reveal_keys: Option<bool>) -> Result<settings::Settings, PakSeekError> {
+    let previous = settings::global().get();
+    let updated = settings::global().update(patch)?;
+    if updated.preview_cache_mb != previous.preview_cache_mb {
+        preview_cache::global().set_budget(updated.preview_cache_mb * 1024 * 1024).await;
+    }
+    info!("Saved settings to {}", settings::global().path().display());
+    Ok(if reveal_keys.unwrap_or(false) { updated } else { updated.redacted() })
+}
+
+/// Tauri command to set the AES key of one container (.pak, .utoc or .ucas path), of a key
+/// GUID, or the default key ("*"); a null `key` removes it
+///
+/// Keys are looked up in that order: the container's own, its GUID's, then the default.
+/// Returns the keys as `list_pak_keys` does. Errors are `ApiError` JSON as for `update_settings`.
+#[tauri::command]
+async fn set_pak_key(pak_path_or_guid: String, key: Option<String>) -> Result<Vec<settings::KeyAssignment>, PakSeekError> {
+    let updated = settings::global().set_pak_key(&pak_path_or_guid, key.as_deref())?;
+    info!("Saved the key for {} to {}", pak_path_or_guid, settings::global().path().display());
+    Ok(updated.redacted().key_assignments())
+}
+
+/// Tauri command to list the configured AES keys and what they apply to; keys are redacted
+/// unless `reveal_keys` is set
+#[tauri::command]
+async fn list_pak_keys(reveal_keys: Option<bool>) -> Result<Vec<settings::KeyAssignment>, PakSeekError> {
+    let current = settings::global().get();
+    Ok(if reveal_keys.unwrap_or(false) { current } else { current.redacted() }.key_assignments())
+}
+
+/// Tauri command to set the .usmap mappings file unversioned properties are read with
+/// (DataTables, metadata, dependencies of cooked UE5 packages); an empty or null `path`
+/// forgets it
+///
+/// The file is parsed before it's saved, so a broken one is `INVALID_REQUEST` and nothing
+/// changes. Returns what the file holds.
+#[tauri::command]
+async fn set_mappings_file(path: Option<String>) -> Result<Option<usmap::MappingsInfo>, PakSeekError> {
+    let path = path.map(|path| path.trim().to_string()).filter(|path| !path.is_empty());
+    let info = match path.clone() {
+        Some(path) => {
+            let loaded = tokio::task::spawn_blocking(move || {
+                let path = std::path::Path::new(&path);
+                usmap::load(path).map(|mappings| mappings.info(path))
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+            let info = loaded.map_err(|e| {
+                ApiError::new(ErrorCode::InvalidRequest, format!("{:#}", e)).with_context(serde_json::json!({ "field": "path" }))
+            })?;
+            Some(info)
+        }
+        None => None,
+    };
+
+    let patch = settings::SettingsPatch { usmap_path: Some(path.unwrap_or_default()), ..Default::default() };
+    settings::global().update(patch)?;
+    match &info {
+        Some(info) => info!("Using {} structs and {} enums from {}", info.structs, info.enums, info.path),
+        None => info!("Cleared the .usmap mappings file"),
+    }
+    Ok(info)
+}
+
+/// Tauri command to get what the configured .usmap mappings file holds; null when none is set
+#[tauri::command]
+async fn get_mappings_info() -> Result<Option<usmap::MappingsInfo>, PakSeekError> {
+    let Some(path) = settings::global().get().usmap_path else {
+        return Ok(None);
+    };
+    let mappings = tokio::task::spawn_blocking(usmap::current).await.map_err(|e| e.to_string())??;
+    Ok(mappings.map(|mappings| mappings.info(&path)))
+}
+
+/// Tauri command to list the game profiles and which one is active; keys are redacted
+/// unless `reveal_keys` is set
+#[tauri::command]
+async fn list_profiles(reveal_keys: Option<bool>) -> Result<settings::ProfileList, PakSeekError> {
+    let current = settings::global().get();
+    Ok(if reveal_keys.unwrap_or(false) { current } else { current.redacted() }.profile_list())
+}
+
+/// Tauri command to save a game profile under `name`
+///
+/// Without `profile`, the profile gets the keys, Oodle library and mappings in use and
+/// the paths mounted now. Errors are `ApiError` JSON: `PROFILE_EXISTS`, `INVALID_REQUEST`
+/// with the `field` at fault, or `SETTINGS_WRITE_FAILED`. Returns the profiles as
+/// `list_profiles` does.
+#[tauri::command]
+async fn create_profile(
+    name: String,
+    profile: Option<settings::ProfileSpec>,
+    state: tauri::State<'_, AppState>,
+) -> Result<settings::ProfileList, PakSeekError> {
+    let profile = match profile {
+        Some(spec) => spec.into_profile()?,
+        None => {
+            let mount_folders = state.mounts.lock().unwrap().list().into_iter().map(|mount| mount.path).collect();
+            settings::Profile::from_settings(&settings::global().get(), None, mount_folders)
+        }
+    };
+    let updated = settings::global().create_profile(&name, profile)?;
+    info!("Saved profile {} to {}", name.trim(), settings::global().path().display());
+    Ok(updated.redacted().profile_list())
+}
+
+/// What `activate_profile` did
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProfileActivation {
+    pub active: String,
+    pub profile: settings::Profile,
+    /// Mounts of the profile's folders, still scanning; empty without `rescan`
+    pub mounts: Vec<mounts::Mount>,
+    /// Folders that couldn't be mounted
+    pub errors: Vec<ApiError>,
+}
+
+/// Tauri command to switch to the game profile `name`: its keys, Oodle library and
+/// mappings apply from the next extraction or preview
+///
+/// With `rescan`, the current mounts are removed and the profile's folders mounted in
+/// their place; their scans send `progress` events under the mount ids and go on after
+/// this returns. Errors are `ApiError` JSON: `PROFILE_NOT_FOUND` or `SETTINGS_WRITE_FAILED`.
+#[tauri::command]
+async fn activate_profile(
+    name: String,
+    rescan: Option<bool>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<ProfileActivation, PakSeekError> {
+    let updated = settings::global().activate_profile(&name)?;
+    let profile = updated.profiles[&name].clone();
+    info!("Activated profile {}", name);
+
+    let mut started = Vec::new();
+    let mut errors = Vec::new();
+    if rescan.unwrap_or(false) {
+        let ids: Vec<String> = state.mounts.lock().unwrap().list().into_iter().map(|mount| mount.id).collect();
+        for id in ids {
+            if let Err(e) = remove_mount_from(&state, &id).await {
+                warn!("Failed to remove mount {} for profile {}: {}", id, name, e.message);
+            }
+        }
+        for folder in &profile.mount_folders {
+            let request = MountRequest { path: folder.clone(), priority: 0, deep: false };
+            let app = app.clone();
+            match add_mount_to(&state, request, move |event| {
+                let _ = app.emit("progress", &event);
+            })
+            .await
+            {
+                Ok((mount, _)) => started.push(mount),
+                Err(e) => {
+                    warn!("Failed to mount {} for profile {}: {}", folder, name, e.message);
+                    errors.push(e);
+                }
+            }
+        }
+    }
+    Ok(ProfileActivation { active: name, profile: profile.redacted(), mounts: started, errors })
+}
+
+/// Whether a key decrypts a container, from `test_key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyTest {
+    pub pak_path: String,
+    pub valid: bool,
+    /// GUID of the key the container needs, to save a valid key under; None for the default key
+    pub encryption_key_guid: Option<String>,
+}
+
+/// Tauri command to check a candidate AES key against a .pak, .utoc or .ucas without saving it
+///
+/// The key has to decrypt the container's index: for a pak, its SHA-1 must then match the
+/// footer's. Errors are `ApiError` JSON: `INVALID_REQUEST` for a malformed key, else
+/// `PAK_NOT_FOUND` or `PAK_READ_FAILED`, which includes containers that aren't encrypted.
+#[tauri::command]
+async fn test_key(pak_path: String, key: String) -> Result<KeyTest, PakSeekError> {
+    let Some(bytes) = settings::parse_key(&key) else {
+        let error = ApiError::new(ErrorCode::InvalidRequest, "Not a 32-byte AES key in hex or base64".to_string())
+            .with_context(serde_json::json!({ "field": "key" }));
+        return Err(PakSeekError::from(error));
+    };
+    let lower = pak_path.to_ascii_lowercase();
+    let tested = if lower.ends_with(".utoc") || lower.ends_with(".ucas") {
+        async {
+            let parser = UtocUcasParser::new(std::path::Path::new(&pak_path).with_extension("utoc"))?;
+            let valid = parser.test_key(&bytes).await?;
+            anyhow::Ok((valid, parser.info().await?.encryption_key_guid))
+        }
+        .await
+    } else {
+        let parser = PakParser::new(&pak_path);
+        async { anyhow::Ok((parser.test_key(&bytes).await?, parser.info().await?.encryption_key_guid)) }.await
+    };
+    let (valid, encryption_key_guid) = tested.map_err(|e| PakError::open(&pak_path, &e))?;
+    info!("Key test for {}: {}", pak_path, if valid { "valid" } else { "invalid" });
+    Ok(KeyTest { pak_path, valid, encryption_key_guid })
+}
+
+/// Tauri command to get dependency information
+///
+/// With `pak_file`, the graph comes from the pak's AssetRegistry.bin (keyed by package
+/// name, e.g. "/Game/Maps/Arena") when it has one. `kind` keeps only hard or soft edges,
+/// and `filter` cuts the graph down to a subgraph small enough to draw. For one asset,
+/// `direction` picks what it uses ("forward", the default) or what uses it ("reverse"),
+/// and the response includes that tree `depth` levels deep (default 5).
+#[tauri::command]
+async fn get_dependencies(
+    asset_name: Option<String>,
+    pak_file: Option<String>,
+    kind: Option<DependencyKind>,
+    filter: Option<SubgraphFilter>,
+    direction: Option<TreeDirection>,
+    depth: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<DependencyResponse, PakSeekError> {
+    info!("Getting dependencies for asset: {:?}", asset_name);
+    
+    let mut dependencies = load_dependency_map(&state, pak_file.as_deref()).await;
+    if let Some(kind) = kind {
+        dependencies = dependencies.filter_by_kind(&[kind]);
+    }
+    let pruned = match filter.filter(|f| !f.is_empty()) {
+        Some(filter) => {
+            let (subgraph, summary) =
+                dependencies.subgraph_with_summary(&filter).map_err(|source| DependencyError::InvalidFilter { source })?;
+            dependencies = subgraph;
+            Some(summary)
+        }
+        None => None,
+    };
+    
+    match asset_name {
+        Some(name) => {
+            // Return dependencies for specific asset
+            let direction = direction.unwrap_or_default();
+            let mut filtered = DependencyMap::new();
+            match direction {
+                TreeDirection::Forward => {
+                    filtered.dependencies.entry(name.clone()).or_default();
+                    for dep in dependencies.get_dependencies(&name) {
+                        filtered.add_typed_dependency(&name, &dep, dependencies.dependency_kind(&name, &dep));
+                    }
+                }
+                TreeDirection::Reverse => {
+                    for dependent in dependencies.get_reverse_dependencies(&name) {
+                        filtered.add_typed_dependency(&dependent, &name, dependencies.dependency_kind(&dependent, &name));
+                    }
+                }
+            }
+            let tree = dependencies.build_tree(&name, depth.unwrap_or(5), direction);
+            
+            Ok(DependencyResponse {
+                dependencies: filtered,
+                pruned,
+                tree: Some(tree),
+            })
+        },
+        None => {
+            // Return all dependencies
+            Ok(DependencyResponse {
+                dependencies,
+                pruned,
+                tree: None,
+            })
+        }
+    }
+}
+
+/// The pak's dependency graph (registry or package references, cached on disk), or the
+/// graph of the last scan without a pak
+async fn load_dependency_map(state: &AppState, pak_file: Option<&str>) -> DependencyMap {
+    let Some(pak_file) = pak_file else {
+        return state.dependencies.lock().await.clone();
+    };
+
+    // Unchanged paks reuse the map saved by an earlier build
+    let digest = scan_cache::pak_set_digest(&[pak_file.to_string()], "");
+    if let Some(dependencies) = scan_cache::cached_dependencies(&digest).await {
+        return dependencies;
+    }
+    match dependency_map::utils::build_from_pak(pak_file).await {
+        Ok(dependencies) => {
+            scan_cache::store_dependencies(&digest, &dependencies).await;
+            dependencies
+        }
+        Err(e) => {
+            warn!("No dependency graph for {}: {:#}", pak_file, e);
+            DependencyMap::new()
+        }
+    }
+}
+
+/// Build order of the dependency graph, or the cycles that prevent one
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BuildOrderResponse {
+    /// Every asset, dependencies first; empty when there are cycles
+    pub order: Vec<String>,
+    pub cycles: Vec<Vec<String>>,
+}
+
+/// Tauri command to order assets so each comes after everything it depends on
+#[tauri::command]
+async fn get_build_order(
+    pak_file: Option<String>,
+    kind: Option<DependencyKind>,
+    state: tauri::State<'_, AppState>,
+) -> Result<BuildOrderResponse, PakSeekError> {
+    let mut dependencies = load_dependency_map(&state, pak_file.as_deref()).await;
+    if let Some(kind) = kind {
+        dependencies = dependencies.filter_by_kind(&[kind]);
+    }
+
+    Ok(match dependencies.topological_sort() {
+        Ok(order) => BuildOrderResponse { order, cycles: Vec::new() },
+        Err(cycles) => BuildOrderResponse { order: Vec::new(), cycles },
+    })
+}
+
+/// Tauri command to list everything that would break if an asset were removed
+#[tauri::command]
+async fn analyze_removal_impact(
+    asset_name: String,
+    pak_file: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<RemovalImpact, PakSeekError> {
+    info!("Analyzing removal impact of: {}", asset_name);
+    let dependencies = load_dependency_map(&state, pak_file.as_deref()).await;
+    Ok(dependencies.analyze_removal_impact(&asset_name))
+}
+
+/// Tauri command to summarize the dependency graph of a pak
+///
+/// Footprints use the uncompressed sizes of the pak's entries, totalled per package.
+/// Assets from the last scan tell which pak each node lives in, for the per-pak summary.
+#[tauri::command]
+async fn get_dependency_statistics(
+    pak_file: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<DependencyStatistics, PakSeekError> {
+    info!("Getting dependency statistics for: {:?}", pak_file);
+    let mut dependencies = load_dependency_map(&state, pak_file.as_deref()).await;
+    dependencies.annotate_containers(&state.assets.lock().await);
+
+    let sizes = match &pak_file {
+        Some(pak_file) => {
+            let pak = PakParser::new(pak_file).parse(&CancellationToken::new()).await.map_err(|e| PakError::open(pak_file, &e))?;
+            dependency_map::utils::package_sizes(pak.entries.iter().map(|e| (e.filename.as_str(), e.uncompressed_size)))
+        }
+        None => HashMap::new(),
+    };
+    let all_assets: Vec<String> = sizes.keys().cloned().collect();
+
+    Ok(dependencies.generate_statistics_with_sizes(&all_assets, &sizes))
+}
+
+/// Tauri command to export the dependency graph of a pak
+///
+/// `format` is one of json, dot, csv, graphml, cytoscape, mermaid or order; `options`
+/// picks the root and limits of a mermaid chart. Node sizes and pak of origin come
+/// from the pak's entries. Writes to `output_path` (default:
+/// "dependencies.<format>" in the working directory) and returns the path written.
+#[tauri::command]
+async fn export_dependencies(
+    format: String,
+    pak_file: Option<String>,
+    output_path: Option<String>,
+    options: Option<ExportOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, PakSeekError> {
+    info!("Exporting dependencies as {}", format);
+    let mut dependencies = load_dependency_map(&state, pak_file.as_deref()).await;
+
+    if let Some(pak_file) = &pak_file {
+        let pak = PakParser::new(pak_file).parse(&CancellationToken::new()).await.map_err(|e| PakError::open(pak_file, &e))?;
+        let sizes = dependency_map::utils::package_sizes(pak.entries.iter().map(|e| (e.filename.as_str(), e.uncompressed_size)));
+        for (package, size) in sizes {
+            let node = dependencies.node_mut(&package);
+            node.size = Some(size);
+            node.pak_file = Some(pak_file.clone());
+        }
+    }
+
+    let contents = dependencies
+        .export_with_options(&format, &options.unwrap_or_default())
+        .map_err(|source| DependencyError::Export { format: format.clone(), source })?;
+    let extension = match format.to_lowercase().as_str() {
+        "cytoscape" => "json".to_string(),
+        "order" => "txt".to_string(),
+        "mermaid" => "mmd".to_string(),
+        other => other.to_string(),
+    };
+    let output_path = output_path.unwrap_or_else(|| format!("dependencies.{}", extension));
+
+    tokio::fs::write(&output_path, contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", output_path, e))?;
+    Ok(output_path)
+}
+
+/// Tauri command to merge a dependency file (.csv, .json, .yaml) into the loaded map
+///
+/// Returns how many edges were new and how many the map already had.
+#[tauri::command]
+async fn import_dependencies(path: String, state: tauri::State<'_, AppState>) -> Result<MergeSummary, PakSeekError> {
+    info!("Importing dependencies from: {}", path);
+    let data = tokio::fs::read(&path)
+        .await
+        .map_err(|e| DependencyError::Import { path: path.clone(), source: e.into() })?;
+
+    let extension = std::path::Path::new(&path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase);
+    let imported = match extension.as_deref() {
+        Some("csv") => DependencyMap::import_from_csv(data.as_slice()),
+        Some("json") => DependencyMap::import_from_json(data.as_slice()),
+        Some("yaml") | Some("yml") => std::str::from_utf8(&data)
+            .map_err(anyhow::Error::from)
+            .and_then(DependencyMap::import_from_yaml),
+        _ => return Err(DependencyError::UnsupportedFile { path }.into()),
+    }
+    .map_err(|source| DependencyError::Import { path: path.clone(), source })?;
+
+    let summary = {
+        let mut dependencies = state.dependencies.lock().await;
+        let summary = dependencies.merge(&imported);
+        state.touch();
+        summary
+    };
+    info!("Imported {} new edges ({} already present)", summary.edges_added, summary.edges_already_present);
+    Ok(summary)
+}
+
+/// Pak-level and dependency-level differences between two builds
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VersionComparison {
+    pub pak_diff: pak_parser::utils::PakDiff,
+    pub dependency_diff: dependency_map::diff::DependencyDiff,
+}
+
+/// A build's paks and the dependency graph merged across them
+async fn scan_build(folder: &str) -> Result<(Vec<PakEntry>, DependencyMap), String> {
+    let path = std::path::Path::new(folder);
+    let pak_files = if path.is_file() {
+        vec![folder.to_string()]
+    } else if path.is_dir() {
+        pak_parser::utils::find_pak_files(folder)
+            .await
+            .map_err(|e| format!("Failed to scan directory '{}': {}", folder, e))?
+    } else {
+        return Err(format!("Path does not exist: {}", folder));
+    };
+
+    let mut entries = Vec::new();
+    let mut maps = Vec::new();
+    for pak_file in &pak_files {
+        let pak = PakParser::new(pak_file)
+            .parse(&CancellationToken::new())
+            .await
+            .map_err(|e| format!("Failed to parse {}: {}", pak_file, e))?;
+        entries.extend(pak.entries);
+        match dependency_map::utils::build_from_pak(pak_file).await {
+            Ok(map) => maps.push(map),
+            Err(e) => warn!("No dependency graph for {}: {:#}", pak_file, e),
         }
     }
-    
-    let mut filtered_assets = all_assets.clone();
+    Ok((entries, dependency_map::utils::merge_dependency_maps(maps)))
+}
 
-    // Apply filters if provided
-    if let Some(asset_type) = asset_type {
-        filtered_assets.retain(|asset| asset.asset_type == asset_type);
-    }
+/// Tauri command to compare two builds (a pak file or a folder of paks each)
+///
+/// Returns which files were added, removed or changed, and which dependency edges
+/// appeared or disappeared.
+#[tauri::command]
+async fn compare_versions(folder_a: String, folder_b: String) -> Result<VersionComparison, PakSeekError> {
+    compare_builds(&folder_a, &folder_b).await
+}
 
-    if let Some(search) = search {
-        let search_lower = search.to_lowercase();
-        filtered_assets.retain(|asset| {
-            asset.name.to_lowercase().contains(&search_lower) ||
-            asset.path.to_lowercase().contains(&search_lower)
-        });
-    }
+/// What `compare_versions` (and `--headless diff`) reports for two builds
+async fn compare_builds(folder_a: &str, folder_b: &str) -> Result<VersionComparison, PakSeekError> {
+    info!("Comparing builds: {} -> {}", folder_a, folder_b);
+    let ((old_entries, old_map), (new_entries, new_map)) = tokio::try_join!(scan_build(folder_a), scan_build(folder_b))?;
 
-    eprintln!("=== DEBUG: Returning {} total assets, {} filtered", all_assets.len(), filtered_assets.len());
-    Ok(AssetsResponse {
-        assets: filtered_assets.clone(),
-        total: all_assets.len(),
-        filtered: filtered_assets.len(),
-    })
+    let comparison = VersionComparison {
+        pak_diff: pak_parser::utils::diff_entries(&old_entries, &new_entries),
+        dependency_diff: dependency_map::diff::compare(&old_map, &new_map),
+    };
+    info!(
+        "Builds differ by {} added / {} removed edges",
+        comparison.dependency_diff.totals.added_edges, comparison.dependency_diff.totals.removed_edges
+    );
+    Ok(comparison)
 }
 
-/// Tauri command to get preview data for a specific asset
+/// Tauri command to check the dependency graph against the scanned assets
+///
+/// Reports cycles, empty dependency lists and, per referencing asset, the references to
+/// assets in no scanned pak (or only in encrypted ones).
 #[tauri::command]
-async fn get_preview(asset_name: String) -> Result<PreviewResponse, String> {
-    info!("Getting preview for asset: {}", asset_name);
-    
-    let assets = create_mock_assets();
-    
-    if let Some(asset) = assets.iter().find(|a| a.name == asset_name) {
-        let preview_data = generate_preview_data(asset).await;
-        Ok(preview_data)
-    } else {
-        Err(format!("Asset not found: {}", asset_name))
-    }
+async fn validate_dependencies(
+    pak_file: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ValidationIssue>, PakSeekError> {
+    let dependencies = load_dependency_map(&state, pak_file.as_deref()).await;
+    Ok(dependencies.validate_against_assets(&state.assets.lock().await))
 }
 
-/// Tauri command to get dependency information
+/// Registry classes whose assets are kept in a build even when no map references them
+const ROOT_ASSET_CLASSES: &[&str] = &["PrimaryAssetLabel", "GameFeatureData"];
+
+/// Tauri command to find the packages of a pak that no root asset needs
+///
+/// `roots` defaults to every map in the pak plus the PrimaryAssetLabel and
+/// GameFeatureData assets listed in its AssetRegistry.bin.
 #[tauri::command]
-async fn get_dependencies(asset_name: Option<String>) -> Result<DependencyResponse, String> {
-    info!("Getting dependencies for asset: {:?}", asset_name);
-    
-    let dependencies = create_mock_dependencies();
-    
-    match asset_name {
-        Some(name) => {
-            // Return dependencies for specific asset
-            let asset_deps = dependencies.dependencies.get(&name).cloned().unwrap_or_default();
-            let mut filtered_deps = HashMap::new();
-            filtered_deps.insert(name, asset_deps);
-            
-            Ok(DependencyResponse {
-                dependencies: DependencyMap { dependencies: filtered_deps },
-            })
-        },
+async fn find_unused_assets(pak_file: String, roots: Option<Vec<String>>) -> Result<UnreachableReport, PakSeekError> {
+    info!("Finding unused assets in: {}", pak_file);
+    let pak = PakParser::new(&pak_file).parse(&CancellationToken::new()).await.map_err(|e| PakError::open(&pak_file, &e))?;
+    let dependencies = dependency_map::utils::build_from_pak(&pak_file).await.map_err(|e| format!("{:#}", e))?;
+
+    let sizes = dependency_map::utils::package_sizes(pak.entries.iter().map(|e| (e.filename.as_str(), e.uncompressed_size)));
+    let packages = |extensions: &[&str]| -> Vec<String> {
+        let mut packages: Vec<String> = pak
+            .entries
+            .iter()
+            .filter(|e| extensions.iter().any(|ext| e.filename.to_lowercase().ends_with(ext)))
+            .filter_map(|e| asset_registry::package_name_for_path(&e.filename))
+            .collect();
+        packages.sort();
+        packages.dedup();
+        packages
+    };
+    let all_assets = packages(&[".uasset", ".umap"]);
+
+    let roots = match roots {
+        Some(roots) => roots,
         None => {
-            // Return all dependencies
-            Ok(DependencyResponse {
-                dependencies,
-            })
+            let mut roots = packages(&[".umap"]);
+            if let Some(registry) = asset_registry::cached_registry(&pak_file).await {
+                roots.extend(
+                    registry
+                        .assets
+                        .iter()
+                        .filter(|a| ROOT_ASSET_CLASSES.contains(&a.class.as_str()))
+                        .map(|a| a.package_name.clone()),
+                );
+            }
+            roots.sort();
+            roots.dedup();
+            roots
         }
-    }
+    };
+
+    Ok(dependencies.unreachable_report(&roots, &all_assets, &sizes))
+}
+
+/// Tauri command to delete the saved asset indexes, pak indexes and dependency maps
+///
+/// Returns how many cache files were removed.
+#[tauri::command]
+async fn clear_index_cache() -> Result<usize, PakSeekError> {
+    info!("Clearing scan cache");
+    Ok(scan_cache::clear()?)
+}
+
+/// Bearer token the HTTP API was started with
+pub struct ApiToken(pub String);
+
+/// Tauri command to get the token needed to call the HTTP API
+#[tauri::command]
+async fn get_api_token(token: tauri::State<'_, ApiToken>) -> Result<String, PakSeekError> {
+    Ok(token.0.clone())
 }
 
 /// Tauri command to get application information
 #[tauri::command]
-async fn get_app_info() -> Result<serde_json::Value, String> {
+async fn get_app_info() -> Result<serde_json::Value, PakSeekError> {
     Ok(serde_json::json!({
         "name": env!("CARGO_PKG_NAME"),
         "version": env!("CARGO_PKG_VERSION"),
@@ -400,16 +5065,150 @@ async fn get_app_info() -> Result<serde_json::Value, String> {
 // RESPONSE TYPES
 // ============================================================================
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, utoipa::ToSchema)]
 pub struct AssetsResponse {
-    pub assets: Vec<Asset>,
+    #[schema(value_type = Vec<Asset>)]
+    pub assets: Vec<Arc<Asset>>,
     pub total: usize,
     pub filtered: usize,
+    /// Position of the first returned asset among the filtered ones
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default)]
+    pub limit: usize,
+    /// Fuzzy match score of each returned asset, higher is closer (fuzzy search only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scores: Option<Vec<i64>>,
+    /// Counts by type, pak and extension for the sidebar's filters
+    #[serde(default)]
+    pub facets: AssetFacets,
+}
+
+/// Progress of a folder scan (`POST /scan`, `list_assets`, or a mount in status reports)
+#[derive(Serialize, Deserialize, Clone, utoipa::ToSchema)]
+pub struct ScanProgress {
+    pub job_id: String,
+    pub path: String,
+    pub status: ScanStatus,
+    pub paks_done: usize,
+    pub paks_total: usize,
+    pub assets_found: usize,
+    /// Paks that couldn't be read (`context.pak_file`)
+    pub errors: Vec<ApiError>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanStatus {
+    Running,
+    /// The results are in the server state
+    Completed,
+    /// Stopped by `cancel_scan`; the listing is as it was before
+    Cancelled,
 }
 
+/// Where the API server ended up listening
 #[derive(Serialize, Deserialize, Clone)]
+pub struct ApiServerInfo {
+    pub addr: String,
+    pub port: u16,
+}
+
+/// Progress of a long-running operation
+///
+/// The same events go to `GET /ws` clients for jobs started over HTTP and to the
+/// frontend as Tauri `progress` events for commands.
+#[derive(Serialize, Deserialize, Clone, Debug, utoipa::ToSchema)]
+pub struct ProgressEvent {
+    pub job_id: String,
+    #[serde(flatten)]
+    pub update: ProgressUpdate,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, utoipa::ToSchema)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressUpdate {
+    /// A pak was read (cached scans skip these); `assets_found` is the running total
+    PakScanned {
+        pak_file: String,
+        paks_done: usize,
+        paks_total: usize,
+        assets_found: usize,
+        error: Option<ApiError>,
+    },
+    /// The scan is done and its assets are in use
+    ScanCompleted { assets_found: usize },
+    /// The scan was cancelled; `assets_found` were scanned before it stopped
+    ScanCancelled { assets_found: usize },
+}
+
+impl ProgressUpdate {
+    /// The last event of a scan job that ended as `done`
+    fn finished(done: &ScanProgress) -> Self {
+        match done.status {
+            ScanStatus::Cancelled => ProgressUpdate::ScanCancelled { assets_found: done.assets_found },
+            _ => ProgressUpdate::ScanCompleted { assets_found: done.assets_found },
+        }
+    }
+}
+
+/// One asset with what the listing leaves out
+#[derive(Serialize, Deserialize, Clone, utoipa::ToSchema)]
+pub struct AssetDetails {
+    #[schema(value_type = Asset)]
+    pub asset: Arc<Asset>,
+    /// The asset's entry in its pak; missing when the pak can't be read
+    pub entry: Option<EntryDetails>,
+    /// Whether a preview with default options is in the preview cache
+    pub preview_cached: bool,
+    pub dependencies: Vec<String>,
+    pub dependents: Vec<String>,
+}
+
+/// Where and how an asset is stored in its pak
+///
+/// The pak index parser doesn't keep per-block sizes, so compression is summarized by
+/// method and overall ratio.
+#[derive(Serialize, Deserialize, Clone, utoipa::ToSchema)]
+pub struct EntryDetails {
+    pub pak_file: String,
+    pub offset: u64,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub compression_method: CompressionMethod,
+    /// Compressed size over uncompressed size (1.0 when stored uncompressed)
+    pub compression_ratio: f64,
+    pub is_encrypted: bool,
+}
+
+impl EntryDetails {
+    fn new(pak_file: &str, entry: PakEntry) -> Self {
+        let compression_ratio = if entry.uncompressed_size == 0 {
+            1.0
+        } else {
+            entry.compressed_size as f64 / entry.uncompressed_size as f64
+        };
+        EntryDetails {
+            pak_file: pak_file.to_string(),
+            offset: entry.offset,
+            compressed_size: entry.compressed_size,
+            uncompressed_size: entry.uncompressed_size,
+            compression_method: entry.compression_method,
+            compression_ratio,
+            is_encrypted: entry.is_encrypted,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, utoipa::ToSchema)]
 pub struct DependencyResponse {
     pub dependencies: DependencyMap,
+    /// What a subgraph filter left out, when one was applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruned: Option<SubgraphSummary>,
+    /// Tree of the requested asset in the requested direction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree: Option<DependencyTree>,
 }
 
 // ============================================================================
@@ -417,11 +5216,15 @@ pub struct DependencyResponse {
 // ============================================================================
 
 /// Creates mock asset data for development and testing
+///
+/// Ids are left empty for `AssetIndex::new` to fill in.
 fn create_mock_assets() -> Vec<Asset> {
     vec![
         Asset {
+            id: String::new(),
             name: "PlayerCharacterMesh".to_string(),
-            asset_type: "mesh".to_string(),
+            file_name: "PlayerCharacterMesh.uasset".to_string(),
+            asset_type: AssetType::StaticMesh,
             size: 2_457_600, // ~2.4MB
             path: "/Game/Characters/Player/PlayerCharacterMesh.uasset".to_string(),
             last_modified: chrono::Utc::now() - chrono::Duration::days(5),
@@ -435,10 +5238,16 @@ fn create_mock_assets() -> Vec<Asset> {
             compression_method: None,
             is_encrypted: None,
             hash: None,
+            type_confidence: TypeConfidence::Heuristic,
+            parts: Vec::new(),
+            chunk_id: None,
+            tags: HashMap::new(),
         },
         Asset {
+            id: String::new(),
             name: "MainMenuBackground".to_string(),
-            asset_type: "texture".to_string(),
+            file_name: "MainMenuBackground.uasset".to_string(),
+            asset_type: AssetType::Texture,
             size: 4_194_304, // 4MB
             path: "/Game/UI/Textures/MainMenuBackground.uasset".to_string(),
             last_modified: chrono::Utc::now() - chrono::Duration::days(2),
@@ -452,10 +5261,16 @@ fn create_mock_assets() -> Vec<Asset> {
             compression_method: None,
             is_encrypted: None,
             hash: None,
+            type_confidence: TypeConfidence::Heuristic,
+            parts: Vec::new(),
+            chunk_id: None,
+            tags: HashMap::new(),
         },
         Asset {
+            id: String::new(),
             name: "AmbientForestLoop".to_string(),
-            asset_type: "audio".to_string(),
+            file_name: "AmbientForestLoop.uasset".to_string(),
+            asset_type: AssetType::Sound,
             size: 1_048_576, // 1MB
             path: "/Game/Audio/Ambient/AmbientForestLoop.uasset".to_string(),
             last_modified: chrono::Utc::now() - chrono::Duration::days(1),
@@ -470,10 +5285,16 @@ fn create_mock_assets() -> Vec<Asset> {
             compression_method: None,
             is_encrypted: None,
             hash: None,
+            type_confidence: TypeConfidence::Heuristic,
+            parts: Vec::new(),
+            chunk_id: None,
+            tags: HashMap::new(),
         },
         Asset {
+            id: String::new(),
             name: "WeaponSwordMaterial".to_string(),
-            asset_type: "material".to_string(),
+            file_name: "WeaponSwordMaterial.uasset".to_string(),
+            asset_type: AssetType::Material,
             size: 512_000, // 512KB
             path: "/Game/Weapons/Materials/WeaponSwordMaterial.uasset".to_string(),
             last_modified: chrono::Utc::now() - chrono::Duration::days(3),
@@ -486,10 +5307,16 @@ fn create_mock_assets() -> Vec<Asset> {
             compression_method: None,
             is_encrypted: None,
             hash: None,
+            type_confidence: TypeConfidence::Heuristic,
+            parts: Vec::new(),
+            chunk_id: None,
+            tags: HashMap::new(),
         },
         Asset {
+            id: String::new(),
             name: "ExplosionParticles".to_string(),
-            asset_type: "particle_system".to_string(),
+            file_name: "ExplosionParticles.uasset".to_string(),
+            asset_type: AssetType::Particle,
             size: 768_000, // 768KB
             path: "/Game/VFX/Particles/ExplosionParticles.uasset".to_string(),
             last_modified: chrono::Utc::now() - chrono::Duration::hours(12),
@@ -503,6 +5330,10 @@ fn create_mock_assets() -> Vec<Asset> {
             compression_method: None,
             is_encrypted: None,
             hash: None,
+            type_confidence: TypeConfidence::Heuristic,
+            parts: Vec::new(),
+            chunk_id: None,
+            tags: HashMap::new(),
         },
     ]
 }
@@ -534,84 +5365,917 @@ fn create_mock_dependencies() -> DependencyMap {
         "ExplosionSound".to_string(),
     ]);
     
-    DependencyMap { dependencies: deps }
+    DependencyMap { dependencies: deps, ..DependencyMap::new() }
+}
+
+/// Parses each pak and converts its entries to assets, reading metadata on deep scans
+///
+/// `on_pak` gets each pak's asset count, or the reason it was skipped, as it finishes.
+/// Returns None once `cancel` fires.
+async fn scan_pak_assets(
+    pak_files: &[String],
+    deep_scan: bool,
+    cancel: &CancellationToken,
+    mut on_pak: impl FnMut(&str, Result<usize, &anyhow::Error>),
+) -> Option<Vec<Asset>> {
+    let mut all_assets = Vec::new();
+    
+    // Parse each .pak file and extract asset information (without size limits)
+    for pak_path in pak_files {
+        if cancel.is_cancelled() {
+            return None;
+        }
+        // Size for the log only; paks of any size are scanned
+        if let Ok(metadata) = std::fs::metadata(pak_path) {
+            debug!("Scanning {} ({:.2} MB)", pak_path, metadata.len() as f64 / (1024.0 * 1024.0));
+        }
+        
+        match parse_pak_assets(pak_path, deep_scan, cancel).await {
+            Err(_) if cancel.is_cancelled() => return None,
+            Ok(pak_assets) => {
+                on_pak(pak_path, Ok(pak_assets.len()));
+                all_assets.extend(pak_assets);
+            }
+            Err(e) => {
+                warn!("Failed to parse .pak file {}: {:#}", pak_path, e);
+                on_pak(pak_path, Err(&e));
+                // Continue processing other pak files instead of failing completely
+            }
+        }
+    }
+
+    (!cancel.is_cancelled()).then_some(all_assets)
+}
+
+/// Pak entries converted between checks for cancellation
+const SCAN_CHUNK: usize = 1000;
+
+/// Lists the assets of one pak, typed from its asset registry or package headers
+///
+/// A package's `.uexp`, `.ubulk` and `.uptnl` entries are parts of its asset rather than
+/// assets of their own (`merge_package_parts`). With `deep_scan`, every package's
+/// metadata is read too; failures there are logged and leave the assets without it.
+/// Fails between chunks of the index once `cancel` fires.
+async fn parse_pak_assets(pak_path: &str, deep_scan: bool, cancel: &CancellationToken) -> anyhow::Result<Vec<Asset>> {
+    let parser = pak_parser::PakParser::new(pak_path);
+    let pak_file = parser.parse(cancel).await?;
+    debug!("Parsed {} with {} entries", pak_path, pak_file.entries.len());
+    let mut pak_assets = Vec::with_capacity(pak_file.entries.len());
+    // The cooked asset registry already knows every class and tag
+    let registry = asset_registry::cached_registry(pak_path).await;
+    let registry_assets = registry.as_ref().map(|r| r.assets_by_package());
+    let name_prefixes = settings::global().get().name_prefixes;
+    let chunk_id = pak_parser::chunk_id(pak_path);
+    // Entries without a timestamp of their own share the pak's, so rescans keep it
+    let pak_modified = pak_file.modified.unwrap_or_else(chrono::Utc::now);
+    // Convert pak entries to our Asset format
+    for (index, entry) in pak_file.entries.into_iter().enumerate() {
+        if index % SCAN_CHUNK == 0 {
+            Cancelled::check(cancel)?;
+        }
+        let registered = registry_assets.as_ref().and_then(|by_package| {
+            let package_name = asset_registry::package_name_for_path(&entry.filename)?;
+            by_package.get(package_name.as_str()).copied()
+        });
+
+        // Prefer the real export class; fall back to the path heuristic
+        let class = match registered {
+            Some(registered) => Some(registered.class.clone()),
+            None => detect_asset_class(&parser, &entry).await,
+        };
+        let (determined_type, type_confidence) = match class {
+            Some(class) => (AssetType::from(class.as_str()), TypeConfidence::Class),
+            None => (determine_asset_type(&entry.filename), TypeConfidence::Heuristic),
+        };
+        
+        let asset = Asset {
+            id: preview::asset_id(Some(pak_path), &entry.filename),
+            name: extract_asset_name(&entry.filename, &name_prefixes),
+            file_name: file_name_of(&entry.filename),
+            path: entry.filename.clone(),
+            asset_type: determined_type,
+            size: entry.uncompressed_size,
+            pak_file: Some(intern::intern(pak_path)),
+            compressed_size: Some(entry.compressed_size),
+            compression_method: Some(intern::intern(&format!("{:?}", entry.compression_method))),
+            is_encrypted: Some(entry.is_encrypted),
+            hash: entry.sha1_hash.as_ref().map(|hash| pak_parser::to_hex(hash)),
+            type_confidence,
+            last_modified: entry.timestamp.unwrap_or(pak_modified),
+            metadata: registered.map(|r| serde_json::json!({ "class": r.class })),
+            parts: Vec::new(),
+            chunk_id,
+            tags: registered.map(|r| r.tags.clone().into_iter().collect()).unwrap_or_default(),
+        };
+        
+        pak_assets.push(asset);
+    }
+    let mut pak_assets = merge_package_parts(pak_assets);
+
+    if deep_scan && !cancel.is_cancelled() {
+        if let Err(e) = asset_metadata::populate_metadata(pak_path, &mut pak_assets, settings::global().get().scan_concurrency).await {
+            warn!("Deep scan of {} failed: {:#}", pak_path, e);
+        }
+    }
+    Ok(pak_assets)
+}
+
+/// Extensions of the entries a cooked package is split into, headers first
+const PACKAGE_EXTENSIONS: [&str; 5] = ["uasset", "umap", "uexp", "ubulk", "uptnl"];
+
+/// Folds each package's `.uexp`, `.ubulk` and `.uptnl` entries into the asset of its
+/// `.uasset` or `.umap` header, summing their sizes and listing every entry in `parts`
+///
+/// Headers without a `.uexp` (older engines) become single-part assets; entries whose
+/// header isn't among `assets` stay assets of their own. Order is kept otherwise.
+fn merge_package_parts(assets: Vec<Asset>) -> Vec<Asset> {
+    // Position of each package's header in the result, by lowercase stem
+    let mut headers: HashMap<String, Option<usize>> = assets
+        .iter()
+        .filter_map(|asset| package_part(&asset.path).filter(|(_, rank)| *rank < 2))
+        .map(|(stem, _)| (stem, None))
+        .collect();
+    let mut merged: Vec<Asset> = Vec::with_capacity(assets.len());
+    let mut parts: Vec<(String, usize, Asset)> = Vec::new();
+    for mut asset in assets {
+        match package_part(&asset.path) {
+            Some((stem, rank)) if rank < 2 && headers.get(&stem) == Some(&None) => {
+                asset.parts = vec![AssetPart::of(&asset)];
+                headers.insert(stem, Some(merged.len()));
+                merged.push(asset);
+            }
+            Some((stem, rank)) if rank >= 2 && headers.contains_key(&stem) => parts.push((stem, rank, asset)),
+            _ => merged.push(asset),
+        }
+    }
+
+    parts.sort_by_key(|(_, rank, _)| *rank);
+    for (stem, _, part) in parts {
+        let Some(header) = headers[&stem].map(|i| &mut merged[i]) else {
+            continue;
+        };
+        header.size += part.size;
+        header.compressed_size = header.compressed_size.zip(part.compressed_size).map(|(a, b)| a + b);
+        header.is_encrypted = header.is_encrypted.zip(part.is_encrypted).map(|(a, b)| a || b);
+        header.parts.push(AssetPart::of(&part));
+    }
+    merged
+}
+
+/// Lowercase path without the extension and rank of the extension in
+/// `PACKAGE_EXTENSIONS`, for the entries of split packages
+fn package_part(path: &str) -> Option<(String, usize)> {
+    let (stem, extension) = path.rsplit_once('.')?;
+    let rank = PACKAGE_EXTENSIONS.iter().position(|known| extension.eq_ignore_ascii_case(known))?;
+    Some((stem.to_lowercase(), rank))
+}
+
+/// Reads the class of a package's primary export from its header, classic or zen
+///
+/// Native classes of zen packages resolve through the engine classes `ScriptObjects`
+/// knows. Returns None when the entry can't be read (encrypted, unsupported compression),
+/// isn't a package or its class can't be resolved, so callers fall back to `determine_asset_type`.
+async fn detect_asset_class(parser: &PakParser, entry: &PakEntry) -> Option<String> {
+    let lower = entry.filename.to_lowercase();
+    if entry.is_encrypted || !(lower.ends_with(".uasset") || lower.ends_with(".umap")) {
+        return None;
+    }
+
+    let header = parser.extract_file(&entry.filename).await.ok()?;
+    if zen_package::ZenPackage::is_zen(&header) {
+        let package = zen_package::ZenPackage::parse(&header).ok()?;
+        return package.export_class_name(package.primary_export()?, zen_package::ScriptObjects::builtin());
+    }
+    let package = uasset_parser::Package::parse(&header).ok()?;
+    let export = package.primary_export()?;
+    Some(package.export_class_name(export))
 }
 
 /// Determines the asset type based on file extension and path patterns
-fn determine_asset_type(filename: &str) -> String {
+fn determine_asset_type(filename: &str) -> AssetType {
     let path = std::path::Path::new(filename);
     
     // Get file extension
     if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
         match extension.to_lowercase().as_str() {
-            "umap" => "Map".to_string(),
+            "umap" => AssetType::Map,
             "uasset" => {
                 // For .uasset files, try to determine type from path patterns
                 let filename_lower = filename.to_lowercase();
                 if filename_lower.contains("/textures/") || filename_lower.contains("_diffuse") 
                    || filename_lower.contains("_normal") || filename_lower.contains("_roughness") {
-                    "Texture2D".to_string()
+                    AssetType::Texture
                 } else if filename_lower.contains("/materials/") || filename_lower.contains("_mat") {
-                    "Material".to_string()
+                    AssetType::Material
                 } else if filename_lower.contains("/meshes/") || filename_lower.contains("_mesh") 
                           || filename_lower.contains("/models/") {
-                    "Static Mesh".to_string()
-                } else if filename_lower.contains("/blueprints/") || filename_lower.contains("bp_") {
-                    "Blueprint".to_string()
-                } else if filename_lower.contains("/ui/") || filename_lower.contains("wbp_") {
-                    "Widget Blueprint".to_string()
+                    AssetType::StaticMesh
+                } else if filename_lower.contains("/datatables/") || filename_lower.contains("/dt_") {
+                    AssetType::DataTable
+                } else if filename_lower.contains("/curvetables/") || filename_lower.contains("/ct_") {
+                    AssetType::other("curve_table")
+                } else if filename_lower.contains("/stringtables/") || filename_lower.contains("/st_") {
+                    AssetType::other("string_table")
+                } else if filename_lower.contains("/fonts/") {
+                    AssetType::Font
+                } else if filename_lower.contains("/brushes/") || filename_lower.contains("/sb_") {
+                    AssetType::other("slate_brush")
+                } else if filename_lower.contains("/blueprints/") || filename_lower.contains("bp_")
+                          || filename_lower.contains("/ui/") || filename_lower.contains("wbp_") {
+                    AssetType::Blueprint
                 } else if filename_lower.contains("/sounds/") || filename_lower.contains("/audio/") {
-                    "Sound Wave".to_string()
+                    AssetType::Sound
                 } else if filename_lower.contains("/animations/") || filename_lower.contains("_anim") {
-                    "Animation".to_string()
-                } else if filename_lower.contains("/particles/") || filename_lower.contains("_particles") {
-                    "Particle System".to_string()
+                    AssetType::Animation
+                } else if filename_lower.contains("/particles/") || filename_lower.contains("_particles")
+                          || filename_lower.contains("/niagara/") || filename_lower.contains("/ns_")
+                          || filename_lower.contains("/ps_") {
+                    AssetType::Particle
                 } else {
-                    "Asset".to_string() // Generic asset type
+                    AssetType::other("asset") // Generic asset type
                 }
             },
-            "uexp" => "Asset Data".to_string(),
-            "ubulk" => "Asset Bulk Data".to_string(),
-            "pak" => "Package".to_string(),
-            _ => "Unknown".to_string(),
+            "locres" => AssetType::other("localization"),
+            "ufont" | "ttf" | "otf" => AssetType::Font,
+            "ini" | "uproject" | "uplugin" => AssetType::other("config"),
+            "json" | "txt" | "csv" | "xml" | "md" => AssetType::other("text"),
+            "lua" | "usf" | "ush" | "py" => AssetType::other("script"),
+            "uexp" => AssetType::other("asset_data"),
+            "ubulk" => AssetType::other("asset_bulk_data"),
+            "pak" => AssetType::other("package"),
+            _ => AssetType::other("unknown"),
         }
     } else {
-        "Unknown".to_string()
+        AssetType::other("unknown")
     }
 }
 
-/// Extracts a clean asset name from the full file path
-fn extract_asset_name(filename: &str) -> String {
-    let path = std::path::Path::new(filename);
-    
-    // Get the file stem (filename without extension)
-    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-        // Remove common Unreal Engine prefixes
-        let cleaned = stem
-            .strip_prefix("BP_").unwrap_or(stem)
-            .strip_prefix("WBP_").unwrap_or(stem)
-            .strip_prefix("T_").unwrap_or(stem)
-            .strip_prefix("M_").unwrap_or(stem)
-            .strip_prefix("SM_").unwrap_or(stem)
-            .strip_prefix("SK_").unwrap_or(stem)
-            .strip_prefix("A_").unwrap_or(stem)
-            .strip_prefix("S_").unwrap_or(stem);
-        
-        // Convert underscores to spaces and title case
-        cleaned
-            .replace('_', " ")
-            .split_whitespace()
-            .map(|word| {
-                let mut chars = word.chars();
-                match chars.next() {
-                    None => String::new(),
-                    Some(first) => first.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase(),
-                }
-            })
-            .collect::<Vec<_>>()
-            .join(" ")
+/// UE naming-convention prefixes `extract_asset_name` drops, besides `Settings::name_prefixes`
+const ASSET_NAME_PREFIXES: [&str; 8] = ["BP_", "WBP_", "T_", "M_", "SM_", "SK_", "A_", "S_"];
+
+/// Display name of an asset: its file stem without a naming-convention prefix, in title case
+///
+/// Only the outermost prefix goes ("BP_SM_Thing" becomes "Sm Thing"), and none when nothing
+/// would be left of the name. Prefixes match case-sensitively.
+fn extract_asset_name(filename: &str, extra_prefixes: &[String]) -> String {
+    let Some(stem) = std::path::Path::new(filename).file_stem().and_then(|s| s.to_str()) else {
+        return filename.to_string();
+    };
+    let cleaned = ASSET_NAME_PREFIXES
+        .into_iter()
+        .chain(extra_prefixes.iter().map(String::as_str))
+        .find_map(|prefix| stem.strip_prefix(prefix).filter(|rest| !rest.trim_matches('_').is_empty()))
+        .unwrap_or(stem);
+
+    // Convert underscores to spaces and title case
+    cleaned
+        .replace('_', " ")
+        .split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => first.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The last component of a pak path, extension included
+fn file_name_of(path: &str) -> String {
+    file_name_str(path).to_string()
+}
+
+/// The last component of a path, with either separator
+fn file_name_str(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// Extension of a path's file name, without the dot
+fn extension_of(path: &str) -> Option<&str> {
+    file_name_str(path).rsplit_once('.').map(|(_, extension)| extension)
+}
+
+/// `text` in ASCII lowercase, borrowed when it already is
+fn ascii_lowercase(text: &str) -> Cow<'_, str> {
+    if text.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(text.to_ascii_lowercase())
     } else {
-        // Fallback to the filename itself
-        filename.to_string()
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use std::io::Read;
+    use tower::ServiceExt;
+
+    /// Configuration with the mock assets and dependencies, and nothing written to disk
+    fn mock_config() -> config::Config {
+        config::Config {
+            path: std::path::PathBuf::from("pakseek.toml"),
+            server: server_config::ServerConfig::default(),
+            mock_data: true,
+            extract_concurrency: 1,
+            index_cache: false,
+            decompression_slots: resource_limit::default_slots(),
+            buffer_budget: resource_limit::DEFAULT_BUFFER_BUDGET,
+            sql_index: false,
+        }
+    }
+
+    /// Router over the mock assets and dependencies, as the server builds it
+    fn mock_router() -> Router {
+        let config = mock_config();
+        let server = config.server.clone();
+        api_router(AppState::new(config), &server, SocketAddr::from(([127, 0, 0, 1], 0)))
+    }
+
+    /// Router over `assets` instead of the mock ones
+    fn router_over(assets: Vec<Asset>) -> Router {
+        let config = config::Config { mock_data: false, ..mock_config() };
+        let server = config.server.clone();
+        let state = AppState::new(config);
+        *state.assets.try_lock().unwrap() = Arc::new(AssetIndex::new(assets));
+        api_router(state, &server, SocketAddr::from(([127, 0, 0, 1], 0)))
+    }
+
+    async fn get(router: &Router, uri: &str, headers: &[(header::HeaderName, &str)]) -> Response {
+        let mut request = Request::get(uri);
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        router.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    async fn body_bytes(response: Response) -> Vec<u8> {
+        axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()
+    }
+
+    #[test]
+    fn asset_names_drop_one_prefix() {
+        let cases = [
+            ("Game/Blueprints/BP_Door.uasset", "Door"),
+            ("Game/UI/WBP_Main_Menu.uasset", "Main Menu"),
+            ("Game/Textures/T_Rock_D.uasset", "Rock D"),
+            ("Game/Materials/M_Rock.uasset", "Rock"),
+            ("Game/Meshes/SM_Rock.uasset", "Rock"),
+            ("Game/Meshes/SK_Hero.uasset", "Hero"),
+            ("Game/Animations/A_Run.uasset", "Run"),
+            ("Game/Sounds/S_Explosion.uasset", "Explosion"),
+            ("Game/Blueprints/BP_SM_Thing.uasset", "Sm Thing"),
+            ("Game/Meshes/SM_.uasset", "Sm"),
+            ("Game/Meshes/sm_rock.uasset", "Sm Rock"),
+            ("Game/Maps/Arena.umap", "Arena"),
+            ("Game/Niagara/NS_Fire.uasset", "Ns Fire"),
+        ];
+        for (filename, expected) in cases {
+            assert_eq!(extract_asset_name(filename, &[]), expected, "{}", filename);
+        }
+        assert_eq!(extract_asset_name("Game/Niagara/NS_Fire.uasset", &["NS_".to_string()]), "Fire");
+    }
+
+    /// `count` assets over 40 paks; `shared` interns the pak paths and compression methods
+    /// the way scans do, otherwise every asset gets copies of its own
+    fn synthetic_assets(count: usize, shared: bool) -> Vec<Asset> {
+        let templates = create_mock_assets();
+        let text = |value: &str| if shared { intern::intern(value) } else { Arc::from(value) };
+        (0..count)
+            .map(|i| {
+                let pak = format!("C:/Games/Arena/Content/Paks/pakchunk{}-Windows.pak", i % 40);
+                let mut asset = templates[i % templates.len()].clone();
+                asset.name = format!("Asset {}", i);
+                asset.file_name = format!("SM_Asset_{}.uasset", i);
+                asset.path = format!("Arena/Content/Props/Set{}/SM_Asset_{}.uasset", i % 500, i);
+                asset.pak_file = Some(text(&pak));
+                asset.compression_method = Some(text(if i % 3 == 0 { "Oodle" } else { "Zlib" }));
+                asset.last_modified = chrono::DateTime::UNIX_EPOCH;
+                asset
+            })
+            .collect()
+    }
+
+    fn resident_bytes() -> usize {
+        memory_stats::memory_stats().map_or(0, |stats| stats.physical_mem)
+    }
+
+    #[test]
+    fn shared_assets_serialize_like_owned() {
+        let owned = synthetic_assets(1000, false);
+        let shared = synthetic_assets(1000, true);
+        assert!(Arc::ptr_eq(shared[0].pak_file.as_ref().unwrap(), shared[40].pak_file.as_ref().unwrap()));
+        assert_eq!(serde_json::to_vec(&owned).unwrap(), serde_json::to_vec(&shared).unwrap());
+
+        let filter = AssetFilter { asset_type: Some("static_mesh".to_string()), ..Default::default() };
+        let owned = AssetQuery::default().apply(&filter, &AssetIndex::new(owned)).unwrap();
+        let shared = AssetQuery::default().apply(&filter, &AssetIndex::new(shared)).unwrap();
+        assert_eq!(serde_json::to_vec(&owned).unwrap(), serde_json::to_vec(&shared).unwrap());
+    }
+
+    /// Listing memory and filter latency at 200k assets, before and after sharing
+    ///
+    /// "Before" is how `/assets` used to run: assets owning their strings, with the listing
+    /// cloned twice per request and the matches cloned again. "After" filters the shared
+    /// index and copies nothing but `Arc`s. Run with
+    /// `cargo test --release shared_listing_benchmark -- --ignored --nocapture`. On a Linux
+    /// build machine the assets took 278 MB resident owned and 256 MB shared, and a
+    /// `type=static_mesh` request about 700 ms before and 30 ms after.
+    #[test]
+    #[ignore = "benchmark"]
+    fn shared_listing_benchmark() {
+        const ASSETS: usize = 200_000;
+        const REQUESTS: u32 = 10;
+
+        let start = resident_bytes();
+        let owned = synthetic_assets(ASSETS, false);
+        let owned_bytes = resident_bytes().saturating_sub(start);
+        let start = resident_bytes();
+        let shared = synthetic_assets(ASSETS, true);
+        let shared_bytes = resident_bytes().saturating_sub(start);
+        let shared = AssetIndex::new(shared);
+
+        let filter = AssetFilter { asset_type: Some("static_mesh".to_string()), ..Default::default() };
+        let matcher = filter.matcher().unwrap();
+        let started = std::time::Instant::now();
+        let mut owned_matches = 0;
+        for _ in 0..REQUESTS {
+            let listing = owned.clone();
+            let copy = listing.clone();
+            let matches: Vec<Asset> = copy.iter().filter(|asset| matcher.matches(asset)).cloned().collect();
+            owned_matches = matches.len();
+        }
+        let owned_latency = started.elapsed() / REQUESTS;
+
+        let started = std::time::Instant::now();
+        let mut shared_response = None;
+        for _ in 0..REQUESTS {
+            shared_response = Some(AssetQuery::default().apply(&filter, &shared).unwrap());
+        }
+        let shared_latency = started.elapsed() / REQUESTS;
+        assert_eq!(shared_response.unwrap().filtered, owned_matches);
+
+        println!("{} assets: {} MB owned, {} MB shared", ASSETS, owned_bytes >> 20, shared_bytes >> 20);
+        println!("filter request: {:?} owned, {:?} shared", owned_latency, shared_latency);
+    }
+
+    #[test]
+    fn openapi_covers_every_route() {
+        // Routes as `api_router` registers them, read from its source
+        let source = include_str!("main.rs");
+        let body = source.split("fn api_router(").nth(1).and_then(|rest| rest.split("\n}\n").next()).unwrap();
+        let route = regex::Regex::new(r#"\.route\("([^"]+)", (.+)\)"#).unwrap();
+        let method = regex::Regex::new(r"(?:^|[ .])(get|post|delete)\(").unwrap();
+        let mut registered = Vec::new();
+        for caps in route.captures_iter(body) {
+            let path = caps[1].split('/').map(|part| match part.strip_prefix(':') {
+                Some(param) => format!("{{{}}}", param),
+                None => part.to_string(),
+            });
+            let path = path.collect::<Vec<_>>().join("/");
+            for handler in method.captures_iter(&caps[2]) {
+                registered.push((path.clone(), handler[1].to_string()));
+            }
+        }
+        assert!(registered.len() > 20);
+
+        let spec = serde_json::to_value(<ApiDoc as utoipa::OpenApi>::openapi()).unwrap();
+        let undocumented: Vec<&(String, String)> = registered
+            .iter()
+            .filter(|(path, _)| !matches!(path.as_str(), "/openapi.json" | "/docs"))
+            .filter(|(path, method)| spec["paths"][path][method].is_null())
+            .collect();
+        assert!(undocumented.is_empty(), "routes missing from the spec: {:?}", undocumented);
+    }
+
+    #[tokio::test]
+    async fn sql_listings_match_in_memory_ones() {
+        let mut assets = synthetic_assets(3000, true);
+        for (i, asset) in assets.iter_mut().enumerate() {
+            asset.size = (i as u64 * 7919) % 100_000;
+            asset.is_encrypted = Some(i % 7 == 0);
+            if i % 10 == 0 {
+                asset.path = asset.path.replace(".uasset", ".umap");
+            }
+            if i % 11 == 0 {
+                asset.pak_file = None;
+            }
+            asset.chunk_id = asset.pak_file.as_deref().and_then(pak_parser::chunk_id);
+        }
+        let index = Arc::new(AssetIndex::new(assets));
+        let dir = std::env::temp_dir().join(format!("pakseek-sql-{}", uuid::Uuid::new_v4()));
+        let sql = Arc::new(sql_index::SqlIndex::open(&dir.join("assets.sqlite")).unwrap());
+        sql.want(&index);
+        assert_eq!(sql.sync(&index).unwrap().unwrap().assets_written, 3000);
+        assert!(sql.mirrors(&index));
+
+        let [in_memory, in_sql] = [None, Some(sql.clone())].map(|sql_index| {
+            let config = config::Config { mock_data: false, ..mock_config() };
+            let server = config.server.clone();
+            let state = AppState { sql_index, ..AppState::new(config) };
+            *state.assets.try_lock().unwrap() = index.clone();
+            api_router(state, &server, SocketAddr::from(([127, 0, 0, 1], 0)))
+        });
+        let queries = [
+            "",
+            "limit=40&offset=1000",
+            "sort=name&limit=100",
+            "sort=name:desc&limit=100&offset=200",
+            "sort=size&limit=100",
+            "sort=size:desc&limit=100",
+            "sort=path&limit=100&offset=2900",
+            "sort=type:desc&limit=100",
+            "search=set1",
+            "search=SM_ASSET_12&sort=size",
+            "search=props%5Cset4",
+            "search=7",
+            "search=no%20such%20asset",
+            "types=static_mesh,texture&limit=5000",
+            "type=material&encrypted=true",
+            "pak=pakchunk3-Windows.pak",
+            "pak=C:/Games/Arena/Content/Paks/pakchunk7-Windows.pak&sort=size:desc",
+            "extension=.umap&compression=OODLE",
+            "extension=uasset&min_size=20000&max_size=29999",
+            "encrypted=false&search=asset%202&sort=name&limit=30",
+        ];
+        for query in queries {
+            let uri = format!("/assets?{}", query);
+            let expected: serde_json::Value = serde_json::from_slice(&body_bytes(get(&in_memory, &uri, &[]).await).await).unwrap();
+            let actual: serde_json::Value = serde_json::from_slice(&body_bytes(get(&in_sql, &uri, &[]).await).await).unwrap();
+            for key in ["total", "filtered", "offset", "limit", "scores", "facets"] {
+                assert_eq!(actual[key], expected[key], "{} {}", query, key);
+            }
+            let ids = |response: &serde_json::Value| {
+                response["assets"].as_array().unwrap().iter().map(|asset| asset["id"].clone()).collect::<Vec<_>>()
+            };
+            assert_eq!(ids(&actual), ids(&expected), "{}", query);
+            assert_eq!(actual, expected, "{}", query);
+        }
+
+        // The SQL path answered those rather than falling back
+        let filter = sql_index::Filter { search: Some("set1".to_string()), ..Default::default() };
+        let page = sql.query(&index, &filter, None, 0, 5000).unwrap().unwrap();
+        let matching = index.iter().filter(|asset| asset.path.to_lowercase().contains("set1") || asset.name.contains("set1"));
+        assert_eq!(page.ids, matching.map(|asset| asset.id.to_string()).collect::<Vec<_>>());
+        drop((in_memory, in_sql, sql));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn registry_tags_filter_and_list() {
+        let mut assets = synthetic_assets(300, false);
+        for (i, asset) in assets.iter_mut().enumerate() {
+            if i % 3 == 0 {
+                asset.tags.insert("PrimaryAssetType".to_string(), if i % 2 == 0 { "Map" } else { "Item" }.to_string());
+                asset.tags.insert("CookTag".to_string(), format!("cooked {}", i));
+            }
+            if i % 5 == 0 {
+                asset.tags.insert("NativeParentClass".to_string(), "/Script/Engine.Actor".to_string());
+            }
+        }
+        let count = |keep: &dyn Fn(usize) -> bool| (0..300).filter(|&i| keep(i)).count();
+        let cases: Vec<(&str, usize)> = vec![
+            ("tag:PrimaryAssetType=Map", count(&|i| i % 6 == 0)),
+            ("tag:primaryassettype=ITEM", count(&|i| i % 3 == 0 && i % 2 == 1)),
+            ("tag:PrimaryAssetType", count(&|i| i % 3 == 0)),
+            ("tag:PrimaryAssetType=Map%20tag:NativeParentClass", count(&|i| i % 30 == 0)),
+            ("tag:NativeParentClass=/Script/Engine.Actor%20Set1", count(&|i| i % 5 == 0 && (i % 500).to_string().starts_with('1'))),
+            ("tag:RowStructure", 0),
+        ];
+
+        let router = router_over(assets);
+        for (search, expected) in cases {
+            let response = get(&router, &format!("/assets?limit=5000&search={}", search), &[]).await;
+            assert_eq!(response.status(), StatusCode::OK, "{}", search);
+            let response: serde_json::Value = serde_json::from_slice(&body_bytes(response).await).unwrap();
+            assert_eq!(response["filtered"], expected, "{}", search);
+            assert_eq!(response["assets"].as_array().unwrap().len(), expected, "{}", search);
+        }
+
+        // Listings carry the listed tags only; the detail view has them all
+        let response = get(&router, "/assets?limit=1&search=tag:CookTag", &[]).await;
+        let response: serde_json::Value = serde_json::from_slice(&body_bytes(response).await).unwrap();
+        let listed = &response["assets"][0];
+        assert_eq!(listed["tags"], serde_json::json!({ "PrimaryAssetType": "Map", "NativeParentClass": "/Script/Engine.Actor" }));
+        let id = listed["id"].as_str().unwrap();
+        let details = get(&router, &format!("/assets/detail?id={}", id), &[]).await;
+        let details: serde_json::Value = serde_json::from_slice(&body_bytes(details).await).unwrap();
+        assert_eq!(details["asset"]["tags"]["CookTag"], "cooked 0");
+        assert_eq!(details["asset"]["tags"].as_object().unwrap().len(), 3);
+
+        let untagged = get(&router, "/assets?limit=2", &[]).await;
+        let untagged: serde_json::Value = serde_json::from_slice(&body_bytes(untagged).await).unwrap();
+        assert!(untagged["assets"][1].get("tags").is_none());
+
+        let invalid = get(&router, "/assets?search=tag:=Map", &[]).await;
+        assert_eq!(invalid.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn preview_links_load_through_the_token_check() {
+        let mut config = mock_config();
+        config.server.token = Some("s3cret/+token".to_string());
+        let server = config.server.clone();
+        let router = api_router(AppState::new(config), &server, SocketAddr::from(([127, 0, 0, 1], 0)));
+        let bearer = [(header::AUTHORIZATION, "Bearer s3cret/+token")];
+
+        let listing = get(&router, "/assets?type=texture&limit=1", &bearer).await;
+        let listing: serde_json::Value = serde_json::from_slice(&body_bytes(listing).await).unwrap();
+        let id = listing["assets"][0]["id"].as_str().unwrap().to_string();
+        let preview = get(&router, &format!("/preview?id={}&as_url=true", id), &bearer).await;
+        assert_eq!(preview.status(), StatusCode::OK);
+        let preview: serde_json::Value = serde_json::from_slice(&body_bytes(preview).await).unwrap();
+        assert_eq!(preview["data"]["format"], "url", "{}", preview);
+        let url = preview["data"]["url"].as_str().unwrap();
+        assert!(url.starts_with("/preview/raw?") && url.ends_with("&token=s3cret%2F%2Btoken"), "{}", url);
+
+        // As an <img> loads it: no header
+        let raw = get(&router, url, &[]).await;
+        assert_eq!(raw.status(), StatusCode::OK);
+        assert!(!body_bytes(raw).await.is_empty());
+
+        let without_token = url.split("&token=").next().unwrap();
+        assert_eq!(get(&router, without_token, &[]).await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(get(&router, &format!("{}&token=wrong", without_token), &[]).await.status(), StatusCode::UNAUTHORIZED);
+        // Other routes still need the header
+        let listing_by_query = get(&router, "/assets?token=s3cret%2F%2Btoken", &[]).await;
+        assert_eq!(listing_by_query.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn combined_filters_narrow_the_listing() {
+        let mut assets = synthetic_assets(2000, true);
+        for (i, asset) in assets.iter_mut().enumerate() {
+            asset.size = (i as u64 % 100) * 1000;
+            asset.is_encrypted = Some(i % 7 == 0);
+            if i % 10 == 0 {
+                asset.path = asset.path.replace(".uasset", ".umap");
+            }
+        }
+        let count = |keep: &dyn Fn(usize, &Asset) -> bool| assets.iter().enumerate().filter(|&(i, asset)| keep(i, asset)).count();
+        let types = [AssetType::StaticMesh, AssetType::Texture];
+        let cases: Vec<(&str, usize)> = vec![
+            ("types=static_mesh,texture", count(&|_, a| types.contains(&a.asset_type))),
+            ("types=static_mesh,texture&compression=oodle", count(&|i, a| types.contains(&a.asset_type) && i % 3 == 0)),
+            ("min_size=20000&max_size=29000&encrypted=true", count(&|i, a| (20000..=29000).contains(&a.size) && i % 7 == 0)),
+            ("encrypted=false&extension=.umap", count(&|i, _| i % 7 != 0 && i % 10 == 0)),
+            ("pak=pakchunk3-Windows.pak&compression=ZLIB", count(&|i, _| i % 40 == 3 && i % 3 != 0)),
+            ("type=material&pak=pakchunk3-Windows.pak&min_size=50000", count(&|i, a| a.asset_type == AssetType::Material && i % 40 == 3 && a.size >= 50000)),
+        ];
+
+        let router = router_over(assets.clone());
+        for (query, expected) in cases {
+            assert!(expected > 0, "{} matches nothing", query);
+            let response = get(&router, &format!("/assets?limit=5000&{}", query), &[]).await;
+            let response: serde_json::Value = serde_json::from_slice(&body_bytes(response).await).unwrap();
+            assert_eq!(response["total"], 2000, "{}", query);
+            assert_eq!(response["filtered"], expected, "{}", query);
+            assert_eq!(response["assets"].as_array().unwrap().len(), expected, "{}", query);
+        }
+    }
+
+    #[test]
+    fn search_matches_either_separator() {
+        let index = AssetIndex::new(create_mock_assets());
+        for search in ["UI/Textures", "UI\\Textures", "ui\\textures\\main"] {
+            let filter = AssetFilter { search: Some(search.to_string()), ..Default::default() };
+            let response = AssetQuery::default().apply(&filter, &index).unwrap();
+            assert_eq!(response.filtered, 1, "{}", search);
+            assert_eq!(response.assets[0].path, "/Game/UI/Textures/MainMenuBackground.uasset");
+        }
+    }
+
+    #[test]
+    fn facets_count_filtered_assets() {
+        let mut assets = synthetic_assets(2000, true);
+        for (i, asset) in assets.iter_mut().enumerate() {
+            asset.chunk_id = Some(i as u32 % 40);
+        }
+        let index = AssetIndex::new(assets);
+        let sum = |counts: &[(String, usize)]| counts.iter().map(|(_, count)| count).sum::<usize>();
+        let count_of = |counts: &[(String, usize)], key: &str| counts.iter().find(|(k, _)| k == key).map_or(0, |(_, count)| *count);
+        let filter = |query: &str| serde_json::from_value::<AssetFilter>(serde_json::Value::Object(
+            query.split('&').map(|pair| pair.split_once('=').unwrap()).map(|(k, v)| (k.to_string(), v.into())).collect(),
+        ));
+
+        // Without their own filters, every dimension adds up to the filtered count
+        let response = AssetQuery::default().apply(&filter("compression=oodle").unwrap(), &index).unwrap();
+        assert_eq!(response.filtered, 667);
+        for counts in [&response.facets.by_type, &response.facets.by_pak, &response.facets.by_extension, &response.facets.by_chunk] {
+            assert_eq!(sum(counts), response.filtered);
+        }
+        assert!(response.facets.by_type.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        // A dimension's own filter is left out of its counts, but applies to the others
+        let response = AssetQuery::default().apply(&filter("compression=oodle&type=texture").unwrap(), &index).unwrap();
+        let by_type = &response.facets.by_type;
+        assert_eq!(sum(by_type), 667);
+        assert_eq!(count_of(by_type, "texture"), response.filtered);
+        assert_eq!(sum(&response.facets.by_pak), response.filtered);
+
+        let response = AssetQuery::default()
+            .apply(&filter("type=texture&pak=pakchunk1-Windows.pak").unwrap(), &index)
+            .unwrap();
+        assert_eq!(count_of(&response.facets.by_pak, "pakchunk1-Windows.pak"), response.filtered);
+        assert_eq!(count_of(&response.facets.by_type, "texture"), response.filtered);
+        assert_eq!(sum(&response.facets.by_pak), 400);
+        assert_eq!(sum(&response.facets.by_type), 50);
+        assert_eq!(count_of(&response.facets.by_chunk, "1"), response.filtered);
+    }
+
+    #[tokio::test]
+    async fn asset_stream_sends_one_filtered_asset_per_line() {
+        use axum::body::HttpBody;
+
+        let router = router_over(synthetic_assets(5000, true));
+        let response = get(&router, "/assets/stream?type=static_mesh", &[]).await;
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/x-ndjson");
+
+        // Parse lines as their frames arrive rather than collecting the body first
+        let mut body = response.into_body();
+        let (mut pending, mut frames) = (Vec::new(), 0);
+        let mut ids = std::collections::HashSet::new();
+        while let Some(frame) = std::future::poll_fn(|cx| std::pin::Pin::new(&mut body).poll_frame(cx)).await {
+            frames += 1;
+            pending.extend_from_slice(&frame.unwrap().into_data().unwrap());
+            while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                let asset: Asset = serde_json::from_slice(&line).unwrap();
+                assert_eq!(asset.asset_type, AssetType::StaticMesh);
+                assert!(ids.insert(asset.id));
+            }
+        }
+        assert!(pending.is_empty());
+        assert_eq!(ids.len(), 1000);
+        assert!(frames > 1);
+    }
+
+    /// Held by tests that parse paks, so the index read counters only see their own reads
+    static PAK_SCANS: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    /// A fresh folder holding paks with the given contents
+    fn pak_folder(paks: &[&[u8]]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("pakseek-paks-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (i, contents) in paks.iter().enumerate() {
+            std::fs::write(dir.join(format!("pakchunk{}-Windows.pak", i)), contents).unwrap();
+        }
+        dir
+    }
+
+    #[tokio::test]
+    async fn rescans_read_indexes_from_the_cache() {
+        let _scans = PAK_SCANS.lock().await;
+        let dir = pak_folder(&[b"first pak", b"second pak", b"third pak"]);
+        let pak_files = find_scan_targets(dir.to_str().unwrap()).await.unwrap();
+        assert_eq!(pak_files.len(), 3);
+        let cancel = CancellationToken::new();
+
+        let reads = pak_parser::index_reads();
+        let first = scan_pak_assets(&pak_files, false, &cancel, |_, _| {}).await.unwrap();
+        assert_eq!(pak_parser::index_reads() - reads, 3);
+
+        let (reads, hits) = (pak_parser::index_reads(), pak_parser::index_cache_hits());
+        let second = scan_pak_assets(&pak_files, false, &cancel, |_, _| {}).await.unwrap();
+        assert_eq!(pak_parser::index_reads() - reads, 0);
+        assert_eq!(pak_parser::index_cache_hits() - hits, 3);
+        assert_eq!(serde_json::to_value(&first).unwrap(), serde_json::to_value(&second).unwrap());
+
+        // A rewritten pak gets a new key; the others still come from the cache
+        std::fs::write(&pak_files[1], b"second pak, patched").unwrap();
+        let reads = pak_parser::index_reads();
+        scan_pak_assets(&pak_files, false, &cancel, |_, _| {}).await.unwrap();
+        assert_eq!(pak_parser::index_reads() - reads, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rescans_keep_the_pak_modification_time() {
+        let _scans = PAK_SCANS.lock().await;
+        let dir = pak_folder(&[&[0; 64]]);
+        let pak = dir.join("pakchunk0-Windows.pak");
+        let modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        std::fs::File::options().write(true).open(&pak).unwrap().set_modified(modified).unwrap();
+
+        let cancel = CancellationToken::new();
+        let first = parse_pak_assets(pak.to_str().unwrap(), false, &cancel).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let second = parse_pak_assets(pak.to_str().unwrap(), false, &cancel).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!first.is_empty());
+        let dates = |assets: &[Asset]| assets.iter().map(|asset| asset.last_modified).collect::<Vec<_>>();
+        assert_eq!(dates(&first), dates(&second));
+        assert!(first.iter().all(|asset| asset.last_modified == chrono::DateTime::<chrono::Utc>::from(modified)));
+    }
+
+    /// Body of a `GET` sent over a plain connection, once the server closes it
+    async fn http_get(addr: SocketAddr, path: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", path, addr);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn server_restarts_on_the_same_port() {
+        let mut config = mock_config();
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        config.server.addr = SocketAddr::from(([127, 0, 0, 1], port));
+
+        let state = AppState::new(config.clone());
+        for _ in 0..2 {
+            let listener = config.server.bind().await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            assert_eq!(addr.port(), port, "the previous server should have released the port");
+            let (signal, shutdown) = watch::channel(false);
+            let server = tokio::spawn(serve_api(listener, api_router(state.clone(), &config.server, addr), shutdown));
+
+            let response = http_get(addr, "/health").await;
+            assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+            assert!(response.contains("\"uptime_secs\""));
+
+            signal.send(true).unwrap();
+            server.await.unwrap().unwrap();
+            assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn matching_etag_gets_not_modified() {
+        let router = mock_router();
+        for uri in ["/assets", "/dependencies"] {
+            let first = get(&router, uri, &[]).await;
+            assert_eq!(first.status(), StatusCode::OK);
+            let etag = first.headers()[header::ETAG].to_str().unwrap().to_string();
+
+            let second = get(&router, uri, &[(header::IF_NONE_MATCH, &etag)]).await;
+            assert_eq!(second.status(), StatusCode::NOT_MODIFIED, "{}", uri);
+            assert!(body_bytes(second).await.is_empty());
+
+            let other = get(&router, uri, &[(header::IF_NONE_MATCH, "W/\"stale\"")]).await;
+            assert_eq!(other.status(), StatusCode::OK, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn compressed_responses_decode_to_the_same_json() {
+        let router = mock_router();
+        let plain = body_bytes(get(&router, "/assets", &[]).await).await;
+
+        let gzip = get(&router, "/assets", &[(header::ACCEPT_ENCODING, "gzip")]).await;
+        assert_eq!(gzip.headers()[header::CONTENT_ENCODING], "gzip");
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(body_bytes(gzip).await.as_slice()).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, plain);
+
+        let br = get(&router, "/assets", &[(header::ACCEPT_ENCODING, "br")]).await;
+        assert_eq!(br.headers()[header::CONTENT_ENCODING], "br");
+        let mut decoded = Vec::new();
+        brotli_decompressor::Decompressor::new(body_bytes(br).await.as_slice(), 4096).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, plain);
+    }
+
+    /// Endless zeros that fire `cancel` once the first block has been handed out
+    struct CancelAfterFirstBlock(CancellationToken);
+
+    impl tokio::io::AsyncRead for CancelAfterFirstBlock {
+        fn poll_read(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            buf.put_slice(&vec![0u8; buf.remaining()]);
+            self.0.cancel();
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_extractions_leave_no_partial_files() {
+        let dir = pak_folder(&[]);
+        let cancel = CancellationToken::new();
+        let partial = dir.join("Player.uasset.part");
+        let result = write_partial(&mut CancelAfterFirstBlock(cancel.clone()), &partial, &cancel).await;
+        assert!(result.is_err());
+        assert!(cancel.is_cancelled());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        let _scans = PAK_SCANS.lock().await;
+        let pak_dir = pak_folder(&[b"placeholder pak"]);
+        let pak = pak_dir.join("pakchunk0-Windows.pak");
+        let destination = dir.join("Player.uasset");
+        let result =
+            extract_to_file("Content/Characters/Player.uasset", pak.to_str().unwrap(), &destination, false, &cancel).await;
+        assert!(matches!(result, Err(PakSeekError::Cancelled)));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&pak_dir).unwrap();
     }
 }
\ No newline at end of file
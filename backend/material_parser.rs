@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use anyhow::{Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// Scalar material parameter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalarParameter {
+    pub name: String,
+    pub value: f64,
+}
+
+/// Vector (linear color) material parameter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorParameter {
+    pub name: String,
+    pub value: Value,
+}
+
+/// Texture material parameter and the texture it points at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureParameter {
+    pub name: String,
+    pub texture: Option<String>,
+}
+
+/// Static switch parameter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticSwitch {
+    pub name: String,
+    pub value: bool,
+}
+
+/// The parts of a Material / MaterialInstance that matter for inspection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialInfo {
+    pub class: String,
+    pub parent: Option<String>,
+    pub blend_mode: Option<String>,
+    pub shading_model: Option<String>,
+    pub scalar_parameters: Vec<ScalarParameter>,
+    pub vector_parameters: Vec<VectorParameter>,
+    pub texture_parameters: Vec<TextureParameter>,
+    pub static_switches: Vec<StaticSwitch>,
+    pub texture_references: Vec<String>,
+}
+
+/// Material classes this parser understands
+pub fn is_material_class(class: &str) -> bool {
+    matches!(
+        class,
+        "Material" | "MaterialInstanceConstant" | "MaterialInstanceDynamic" | "MaterialFunction"
+    )
+}
+
+/// Parses a Material or MaterialInstanceConstant package
+///
+/// Instances expose their parameter overrides as tagged properties; base
+/// materials are limited to blend mode, shading model, static switches and the
+/// textures they reference, since their expression graph is editor-only.
+pub fn parse_material(data: &[u8]) -> Result<MaterialInfo> {
+    let package = Package::parse(data)?;
+    let export = package
+        .exports
+        .iter()
+        .find(|e| is_material_class(&package.export_class_name(e)))
+        .context("Package has no Material export")?;
+    let class = package.export_class_name(export);
+
+    let export_data = package.export_data(data, export)?;
+    let mut reader = ArchiveReader::new(export_data);
+    let properties = uasset_parser::read_properties(&package, &mut reader, &class)?;
+
+    let overrides = properties.get("BasePropertyOverrides");
+    let overridden = |flag: &str, field: &str| {
+        overrides
+            .filter(|o| o.get(flag).and_then(Value::as_bool).unwrap_or(false))
+            .and_then(|o| o.get(field))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    let blend_mode = properties
+        .get("BlendMode")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| overridden("bOverride_BlendMode", "BlendMode"));
+    let shading_model = properties
+        .get("ShadingModel")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| overridden("bOverride_ShadingModel", "ShadingModel"));
+
+    let scalar_parameters = parameter_entries(&properties, "ScalarParameterValues")
+        .filter_map(|(name, value)| Some(ScalarParameter { name, value: value.as_f64()? }))
+        .collect();
+    let vector_parameters = parameter_entries(&properties, "VectorParameterValues")
+        .map(|(name, value)| VectorParameter { name, value: value.clone() })
+        .collect();
+    let texture_parameters: Vec<TextureParameter> = parameter_entries(&properties, "TextureParameterValues")
+        .map(|(name, value)| TextureParameter {
+            name,
+            texture: value.as_str().map(str::to_string),
+        })
+        .collect();
+
+    let static_parameters = properties
+        .get("StaticParameters")
+        .or_else(|| properties.get("StaticParametersRuntime"));
+    let static_switches = static_parameters
+        .and_then(|p| p.get("StaticSwitchParameters"))
+        .and_then(Value::as_array)
+        .map(|switches| {
+            switches
+                .iter()
+                .filter_map(|s| {
+                    Some(StaticSwitch {
+                        name: parameter_name(s)?,
+                        value: s.get("Value").and_then(Value::as_bool).unwrap_or(false),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut texture_references: Vec<String> = package
+        .imports
+        .iter()
+        .enumerate()
+        .filter(|(_, import)| import.class_name.starts_with("Texture") || import.class_name.contains("VirtualTexture"))
+        .filter_map(|(i, _)| package.import_object_path(-(i as i32) - 1))
+        .chain(texture_parameters.iter().filter_map(|p| p.texture.clone()))
+        .collect();
+    texture_references.sort();
+    texture_references.dedup();
+
+    Ok(MaterialInfo {
+        class,
+        parent: properties.get("Parent").and_then(Value::as_str).map(str::to_string),
+        blend_mode,
+        shading_model,
+        scalar_parameters,
+        vector_parameters,
+        texture_parameters,
+        static_switches,
+        texture_references,
+    })
+}
+
+/// Name of a material parameter entry (ParameterInfo.Name, or the legacy ParameterName)
+fn parameter_name(entry: &Value) -> Option<String> {
+    entry
+        .get("ParameterInfo")
+        .and_then(|info| info.get("Name"))
+        .or_else(|| entry.get("ParameterName"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Iterates (name, ParameterValue) pairs of a *ParameterValues array property
+fn parameter_entries<'a>(
+    properties: &'a serde_json::Map<String, Value>,
+    key: &str,
+) -> impl Iterator<Item = (String, &'a Value)> {
+    properties
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| Some((parameter_name(entry)?, entry.get("ParameterValue")?)))
+}
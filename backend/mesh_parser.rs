@@ -0,0 +1,824 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use anyhow::{bail, Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// Axis-aligned bounding box in glTF space (meters, Y-up)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BoundingBox {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl BoundingBox {
+    /// Computes the bounds of a set of positions
+    pub fn from_positions(positions: &[[f32; 3]]) -> Option<Self> {
+        let first = *positions.first()?;
+        let mut bounds = BoundingBox { min: first, max: first };
+        for position in positions {
+            for (axis, &value) in position.iter().enumerate() {
+                bounds.min[axis] = bounds.min[axis].min(value);
+                bounds.max[axis] = bounds.max[axis].max(value);
+            }
+        }
+        Some(bounds)
+    }
+}
+
+/// A contiguous range of the index buffer drawn with one material slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeshSection {
+    pub material_index: u32,
+    pub first_index: u32,
+    pub num_triangles: u32,
+}
+
+/// LOD geometry pulled out of a mesh asset, already converted to glTF space
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeshGeometry {
+    pub positions: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+    pub sections: Vec<MeshSection>,
+    pub materials: Vec<String>,
+}
+
+impl MeshGeometry {
+    pub fn vertex_count(&self) -> u32 {
+        self.positions.len() as u32
+    }
+
+    pub fn triangle_count(&self) -> u32 {
+        (self.indices.len() / 3) as u32
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::from_positions(&self.positions)
+    }
+}
+
+/// Unreal units are centimeters in a Z-up left-handed frame; glTF is meters, Y-up, right-handed.
+/// Swapping Y and Z converts both the up axis and the handedness.
+fn to_gltf_space(x: f32, y: f32, z: f32) -> [f32; 3] {
+    [x * 0.01, z * 0.01, y * 0.01]
+}
+
+/// Parses LOD0 geometry out of a cooked StaticMesh package
+///
+/// Supports UE4.23+ cooked layouts where LOD0 buffers are inlined in the export.
+/// Streamed LODs (buffers in .ubulk) are reported as errors for now.
+pub fn parse_static_mesh(data: &[u8]) -> Result<MeshGeometry> {
+    let package = Package::parse(data)?;
+    let export = package
+        .exports
+        .iter()
+        .find(|e| package.export_class_name(e) == "StaticMesh")
+        .context("Package has no StaticMesh export")?;
+
+    let export_data = package.export_data(data, export)?;
+    let mut reader = ArchiveReader::new(export_data);
+
+    let lod_count = read_static_mesh_header(&package, &mut reader)?;
+    if lod_count == 0 {
+        bail!("StaticMesh has no LODs");
+    }
+
+    let (sections, positions, indices) = read_lod_resources(&mut reader)?;
+
+    Ok(MeshGeometry {
+        positions,
+        indices,
+        sections,
+        materials: material_slot_names(&package),
+    })
+}
+
+/// Number of cooked render LODs of a StaticMesh, without reading any geometry
+pub fn static_mesh_lod_count(data: &[u8]) -> Result<usize> {
+    let package = Package::parse(data)?;
+    let export = package
+        .exports
+        .iter()
+        .find(|e| package.export_class_name(e) == "StaticMesh")
+        .context("Package has no StaticMesh export")?;
+
+    let mut reader = ArchiveReader::new(package.export_data(data, export)?);
+    read_static_mesh_header(&package, &mut reader)
+}
+
+/// Reads a StaticMesh export up to its render data, returning the LOD count
+fn read_static_mesh_header(package: &Package, reader: &mut ArchiveReader) -> Result<usize> {
+    uasset_parser::read_property_tags(package, reader)?;
+    uasset_parser::skip_object_guid(reader)?;
+
+    reader.skip(2)?; // FStripDataFlags
+    let cooked = reader.read_bool()?;
+    if !cooked {
+        bail!("StaticMesh was not saved with cooked render data");
+    }
+    reader.read_i32()?; // BodySetup
+    reader.read_i32()?; // NavCollision
+    reader.read_guid()?; // LightingGuid
+    let sockets = reader.read_count(4)?;
+    reader.skip(sockets * 4)?;
+
+    reader.read_count(1)
+}
+
+/// Material names in slot order, approximated by the order materials appear in the import table
+pub fn material_slot_names(package: &Package) -> Vec<String> {
+    package
+        .imports
+        .iter()
+        .filter(|i| i.class_name.starts_with("Material"))
+        .map(|i| i.object_name.clone())
+        .collect()
+}
+
+type LodBuffers = (Vec<MeshSection>, Vec<[f32; 3]>, Vec<u32>);
+
+/// Reads FStaticMeshLODResources for LOD0
+///
+/// FStaticMeshSection grew optional bool fields across engine versions, so the
+/// section stride is probed: the layout that leaves a plausible MaxDeviation and
+/// cooked-out/inlined flags after the section array wins.
+fn read_lod_resources(reader: &mut ArchiveReader) -> Result<LodBuffers> {
+    reader.skip(2)?; // FStripDataFlags
+    let section_count = reader.read_count(28)?;
+    let sections_start = reader.position();
+
+    let mut last_error = None;
+    for stride in [28usize, 32, 36, 40] {
+        reader.seek(sections_start)?;
+        match read_lod_with_section_stride(reader, section_count, stride) {
+            Ok(buffers) => return Ok(buffers),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Unrecognized LOD layout")))
+}
+
+fn read_lod_with_section_stride(reader: &mut ArchiveReader, section_count: usize, stride: usize) -> Result<LodBuffers> {
+    let mut sections = Vec::with_capacity(section_count);
+    for _ in 0..section_count {
+        let start = reader.position();
+        let material_index = reader.read_i32()?;
+        let first_index = reader.read_i32()?;
+        let num_triangles = reader.read_i32()?;
+        if material_index < 0 || first_index < 0 || num_triangles < 0 {
+            bail!("Implausible section with stride {}", stride);
+        }
+        sections.push(MeshSection {
+            material_index: material_index as u32,
+            first_index: first_index as u32,
+            num_triangles: num_triangles as u32,
+        });
+        reader.seek(start + stride)?;
+    }
+
+    let max_deviation = reader.read_f32()?;
+    if !max_deviation.is_finite() || !(0.0..1.0e6).contains(&max_deviation) {
+        bail!("Implausible MaxDeviation with section stride {}", stride);
+    }
+
+    let cooked_out = reader.read_u32()?;
+    let inlined = reader.read_u32()?;
+    if cooked_out > 1 || inlined > 1 {
+        bail!("Implausible LOD flags with section stride {}", stride);
+    }
+    if cooked_out == 1 {
+        bail!("LOD0 was cooked out of this platform's data");
+    }
+    if inlined == 0 {
+        bail!("LOD0 buffers are streamed from bulk data, which is not supported yet");
+    }
+
+    reader.skip(2)?; // FStripDataFlags for the buffers
+    let positions = read_position_buffer(reader)?;
+    skip_static_mesh_vertex_buffer(reader)?;
+    skip_color_vertex_buffer(reader)?;
+    let indices = read_index_buffer(reader)?;
+
+    let total_indices: u64 = sections
+        .iter()
+        .map(|s| s.first_index as u64 + s.num_triangles as u64 * 3)
+        .max()
+        .unwrap_or(0);
+    if total_indices > indices.len() as u64 {
+        bail!("Sections reference {} indices but the buffer has {}", total_indices, indices.len());
+    }
+    if let Some(bad) = indices.iter().find(|&&i| i as usize >= positions.len()) {
+        bail!("Index {} out of range for {} vertices", bad, positions.len());
+    }
+
+    Ok((sections, positions, indices))
+}
+
+/// Reads a bulk-serialized TResourceArray header and returns (element size, count)
+fn read_bulk_array_header(reader: &mut ArchiveReader) -> Result<(usize, usize)> {
+    let element_size = reader.read_i32()?;
+    if element_size <= 0 {
+        bail!("Invalid bulk element size {}", element_size);
+    }
+    let count = reader.read_count(element_size as usize)?;
+    Ok((element_size as usize, count))
+}
+
+fn read_position_buffer(reader: &mut ArchiveReader) -> Result<Vec<[f32; 3]>> {
+    let stride = reader.read_u32()?;
+    let num_vertices = reader.read_u32()? as usize;
+    let (element_size, count) = read_bulk_array_header(reader)?;
+    if stride != 12 || element_size != 12 || count != num_vertices {
+        bail!("Unexpected position buffer layout (stride {}, element {}, count {})", stride, element_size, count);
+    }
+
+    let mut positions = Vec::with_capacity(count);
+    for _ in 0..count {
+        let x = reader.read_f32()?;
+        let y = reader.read_f32()?;
+        let z = reader.read_f32()?;
+        positions.push(to_gltf_space(x, y, z));
+    }
+    Ok(positions)
+}
+
+fn skip_static_mesh_vertex_buffer(reader: &mut ArchiveReader) -> Result<()> {
+    reader.skip(2)?; // FStripDataFlags
+    reader.read_u32()?; // NumTexCoords
+    reader.read_u32()?; // NumVertices
+    reader.read_bool()?; // bUseFullPrecisionUVs
+    reader.read_bool()?; // bUseHighPrecisionTangentBasis
+    for _ in 0..2 {
+        let (element_size, count) = read_bulk_array_header(reader)?;
+        reader.skip(element_size * count)?;
+    }
+    Ok(())
+}
+
+fn skip_color_vertex_buffer(reader: &mut ArchiveReader) -> Result<()> {
+    reader.skip(2)?; // FStripDataFlags
+    reader.read_u32()?; // Stride
+    let num_vertices = reader.read_u32()?;
+    if num_vertices > 0 {
+        let (element_size, count) = read_bulk_array_header(reader)?;
+        reader.skip(element_size * count)?;
+    }
+    Ok(())
+}
+
+fn read_index_buffer(reader: &mut ArchiveReader) -> Result<Vec<u32>> {
+    let is_32bit = reader.read_bool()?;
+    let (element_size, count) = read_bulk_array_header(reader)?;
+    let bytes = reader.read_bytes(element_size * count)?;
+
+    let indices = if is_32bit {
+        bytes
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    } else {
+        bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]) as u32)
+            .collect()
+    };
+    Ok(indices)
+}
+
+/// Reduces a mesh below `triangle_budget` by vertex clustering on a uniform grid
+///
+/// The grid is coarsened until the budget is met; returns None when even a very
+/// coarse grid can't get under budget.
+pub fn decimate_by_clustering(geometry: &MeshGeometry, triangle_budget: u32) -> Option<MeshGeometry> {
+    if geometry.triangle_count() <= triangle_budget {
+        return Some(geometry.clone());
+    }
+
+    let bounds = geometry.bounding_box()?;
+    let extent = (0..3)
+        .map(|axis| bounds.max[axis] - bounds.min[axis])
+        .fold(0.0f32, f32::max)
+        .max(f32::EPSILON);
+
+    let mut resolution = 256u32;
+    while resolution >= 4 {
+        let cell_size = extent / resolution as f32;
+        let clustered = cluster_vertices(geometry, &bounds, cell_size);
+        if clustered.triangle_count() <= triangle_budget {
+            return Some(clustered);
+        }
+        resolution /= 2;
+    }
+
+    None
+}
+
+fn cluster_vertices(geometry: &MeshGeometry, bounds: &BoundingBox, cell_size: f32) -> MeshGeometry {
+    let mut cells: HashMap<[i32; 3], u32> = HashMap::new();
+    let mut sums: Vec<([f32; 3], u32)> = Vec::new();
+
+    let remap: Vec<u32> = geometry
+        .positions
+        .iter()
+        .map(|p| {
+            let key = [
+                ((p[0] - bounds.min[0]) / cell_size) as i32,
+                ((p[1] - bounds.min[1]) / cell_size) as i32,
+                ((p[2] - bounds.min[2]) / cell_size) as i32,
+            ];
+            let cell = *cells.entry(key).or_insert_with(|| {
+                sums.push(([0.0; 3], 0));
+                (sums.len() - 1) as u32
+            });
+            let (sum, count) = &mut sums[cell as usize];
+            for (total, value) in sum.iter_mut().zip(p) {
+                *total += value;
+            }
+            *count += 1;
+            cell
+        })
+        .collect();
+
+    let positions = sums
+        .iter()
+        .map(|(sum, count)| {
+            let n = *count as f32;
+            [sum[0] / n, sum[1] / n, sum[2] / n]
+        })
+        .collect();
+
+    let mut indices = Vec::new();
+    let mut sections = Vec::with_capacity(geometry.sections.len());
+    for section in &geometry.sections {
+        let first_index = indices.len() as u32;
+        let start = section.first_index as usize;
+        let end = start + section.num_triangles as usize * 3;
+        for triangle in geometry.indices[start..end].chunks_exact(3) {
+            let [a, b, c] = [remap[triangle[0] as usize], remap[triangle[1] as usize], remap[triangle[2] as usize]];
+            if a != b && b != c && a != c {
+                indices.extend_from_slice(&[a, b, c]);
+            }
+        }
+        sections.push(MeshSection {
+            material_index: section.material_index,
+            first_index,
+            num_triangles: (indices.len() as u32 - first_index) / 3,
+        });
+    }
+
+    MeshGeometry {
+        positions,
+        indices,
+        sections,
+        materials: geometry.materials.clone(),
+    }
+}
+
+/// Serializes mesh geometry as a binary glTF 2.0 (.glb) container
+///
+/// Emits one primitive per section, sharing a single position accessor.
+pub fn export_glb(geometry: &MeshGeometry, name: &str) -> Result<Vec<u8>> {
+    let bounds = geometry.bounding_box().context("Mesh has no vertices")?;
+
+    let mut bin = Vec::with_capacity(geometry.positions.len() * 12 + geometry.indices.len() * 4);
+    for position in &geometry.positions {
+        for component in position {
+            bin.extend_from_slice(&component.to_le_bytes());
+        }
+    }
+    let positions_length = bin.len();
+    for index in &geometry.indices {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    let indices_length = bin.len() - positions_length;
+
+    let mut accessors = vec![serde_json::json!({
+        "bufferView": 0,
+        "componentType": 5126, // FLOAT
+        "count": geometry.positions.len(),
+        "type": "VEC3",
+        "min": bounds.min,
+        "max": bounds.max,
+    })];
+
+    let mut primitives = Vec::new();
+    for section in geometry.sections.iter().filter(|s| s.num_triangles > 0) {
+        accessors.push(serde_json::json!({
+            "bufferView": 1,
+            "byteOffset": section.first_index as usize * 4,
+            "componentType": 5125, // UNSIGNED_INT
+            "count": section.num_triangles as usize * 3,
+            "type": "SCALAR",
+        }));
+        let mut primitive = serde_json::json!({
+            "attributes": { "POSITION": 0 },
+            "indices": accessors.len() - 1,
+        });
+        if (section.material_index as usize) < geometry.materials.len().max(1) {
+            primitive["material"] = serde_json::json!(section.material_index);
+        }
+        primitives.push(primitive);
+    }
+
+    let material_names: Vec<String> = if geometry.materials.is_empty() {
+        vec!["DefaultMaterial".to_string()]
+    } else {
+        geometry.materials.clone()
+    };
+
+    let document = serde_json::json!({
+        "asset": { "version": "2.0", "generator": concat!("PakSeek ", env!("CARGO_PKG_VERSION")) },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0, "name": name }],
+        "meshes": [{ "name": name, "primitives": primitives }],
+        "materials": material_names.iter().map(|m| serde_json::json!({ "name": m })).collect::<Vec<_>>(),
+        "buffers": [{ "byteLength": bin.len() }],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": positions_length, "target": 34962 },
+            { "buffer": 0, "byteOffset": positions_length, "byteLength": indices_length, "target": 34963 },
+        ],
+        "accessors": accessors,
+    });
+
+    let mut json = serde_json::to_vec(&document)?;
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    while bin.len() % 4 != 0 {
+        bin.push(0);
+    }
+
+    let total_length = 12 + 8 + json.len() + 8 + bin.len();
+    let mut glb = Vec::with_capacity(total_length);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(total_length as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(&json);
+    glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"BIN\0");
+    glb.extend_from_slice(&bin);
+
+    Ok(glb)
+}
+
+/// Bone of a skeletal mesh reference skeleton
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoneInfo {
+    pub name: String,
+    pub parent_index: i32,
+}
+
+/// Geometry counts for one skeletal mesh LOD; None when the LOD is streamed or unreadable
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SkeletalLod {
+    pub vertices: Option<u32>,
+    pub triangles: Option<u32>,
+    pub sections: Option<u32>,
+}
+
+/// Metadata parsed from a SkeletalMesh package
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkeletalMeshInfo {
+    pub skeleton: Option<String>,
+    pub bones: Vec<BoneInfo>,
+    pub lods: Vec<SkeletalLod>,
+    pub morph_targets: Vec<String>,
+    pub materials: Vec<String>,
+    #[serde(skip)]
+    pub lod0_geometry: Option<MeshGeometry>,
+}
+
+/// Parses bones, LODs, morph targets and the Skeleton reference of a cooked SkeletalMesh
+///
+/// Property-backed fields (Skeleton, MorphTargets, LODInfo) are always read; the
+/// reference skeleton and LOD0 render data are read on a best-effort basis since
+/// their native layout shifts between engine versions.
+pub fn parse_skeletal_mesh(data: &[u8]) -> Result<SkeletalMeshInfo> {
+    let package = Package::parse(data)?;
+    let export = package
+        .exports
+        .iter()
+        .find(|e| package.export_class_name(e) == "SkeletalMesh")
+        .context("Package has no SkeletalMesh export")?;
+
+    let export_data = package.export_data(data, export)?;
+    let mut reader = ArchiveReader::new(export_data);
+    let tags = uasset_parser::read_property_tags(&package, &mut reader)?;
+
+    let skeleton = uasset_parser::find_property(&tags, "Skeleton")
+        .and_then(|t| t.object_index(export_data))
+        .and_then(|index| package.object_path(index));
+    let morph_targets = uasset_parser::find_property(&tags, "MorphTargets")
+        .map(|t| {
+            t.object_array(export_data)
+                .into_iter()
+                .filter_map(|index| package.object_path(index))
+                .collect()
+        })
+        .unwrap_or_default();
+    let lod_info_count = uasset_parser::find_property(&tags, "LODInfo")
+        .and_then(|t| t.array_count(export_data))
+        .unwrap_or(0);
+
+    let mut info = SkeletalMeshInfo {
+        skeleton,
+        bones: Vec::new(),
+        lods: Vec::new(),
+        morph_targets,
+        materials: material_slot_names(&package),
+        lod0_geometry: None,
+    };
+
+    match read_skeletal_native_data(&package, &mut reader) {
+        Ok((bones, lods, geometry)) => {
+            info.bones = bones;
+            info.lods = lods;
+            info.lod0_geometry = geometry.map(|mut g| {
+                g.materials = info.materials.clone();
+                g
+            });
+        }
+        Err(e) => tracing::debug!("Skeletal mesh native data unreadable: {:#}", e),
+    }
+
+    // LODInfo always has one entry per LOD, even when render data couldn't be read
+    while info.lods.len() < lod_info_count {
+        info.lods.push(SkeletalLod {
+            vertices: None,
+            triangles: None,
+            sections: None,
+        });
+    }
+
+    Ok(info)
+}
+
+type SkeletalNativeData = (Vec<BoneInfo>, Vec<SkeletalLod>, Option<MeshGeometry>);
+
+fn read_skeletal_native_data(package: &Package, reader: &mut ArchiveReader) -> Result<SkeletalNativeData> {
+    let lwc = package.uses_large_world_coordinates();
+
+    uasset_parser::skip_object_guid(reader)?;
+    reader.skip(2)?; // FStripDataFlags
+    reader.skip(if lwc { 56 } else { 28 })?; // ImportedBounds (FBoxSphereBounds)
+
+    // FSkeletalMaterial's size depends on engine version; the stride that leaves
+    // a valid reference skeleton behind it is the right one
+    let material_count = reader.read_count(4)?;
+    let materials_start = reader.position();
+    let mut bones = None;
+    for stride in [36usize, 28, 44, 12, 8] {
+        reader.seek(materials_start)?;
+        if reader.skip(material_count * stride).is_err() {
+            continue;
+        }
+        if let Ok(found) = read_reference_skeleton(package, reader, lwc) {
+            bones = Some(found);
+            break;
+        }
+    }
+    let bones = bones.context("Could not locate the reference skeleton")?;
+
+    let mut lods = Vec::new();
+    let mut geometry = None;
+    if reader.read_bool()? {
+        let lod_count = reader.read_count(1)?;
+        match read_skeletal_lod0(package, reader) {
+            Ok((lod, lod_geometry)) => {
+                lods.push(lod);
+                geometry = lod_geometry;
+            }
+            Err(e) => tracing::debug!("Skeletal LOD0 render data unreadable: {:#}", e),
+        }
+        while lods.len() < lod_count {
+            lods.push(SkeletalLod {
+                vertices: None,
+                triangles: None,
+                sections: None,
+            });
+        }
+    }
+
+    Ok((bones, lods, geometry))
+}
+
+fn read_reference_skeleton(package: &Package, reader: &mut ArchiveReader, lwc: bool) -> Result<Vec<BoneInfo>> {
+    let bone_count = reader.read_count(12)?;
+    if bone_count == 0 {
+        bail!("Empty reference skeleton");
+    }
+
+    let mut bones = Vec::with_capacity(bone_count);
+    for index in 0..bone_count {
+        let name = package.read_name(reader)?;
+        let parent_index = reader.read_i32()?;
+        let valid_parent = if index == 0 {
+            parent_index == -1
+        } else {
+            parent_index >= 0 && (parent_index as usize) < index
+        };
+        if !valid_parent {
+            bail!("Bone {} has invalid parent {}", name, parent_index);
+        }
+        bones.push(BoneInfo { name, parent_index });
+    }
+
+    let pose_count = reader.read_count(40)?;
+    if pose_count != bone_count {
+        bail!("Bone pose count {} does not match bone count {}", pose_count, bone_count);
+    }
+    reader.skip(pose_count * if lwc { 80 } else { 40 })?; // FTransform per bone
+
+    let name_map_count = reader.read_count(12)?;
+    reader.skip(name_map_count * 12)?; // FName -> bone index
+
+    Ok(bones)
+}
+
+/// Optional fields of FSkelMeshRenderSection that appeared in different engine versions
+#[derive(Debug, Clone, Copy)]
+struct SkelSectionLayout {
+    tangent_mask_channel: bool,
+    visible_in_ray_tracing: bool,
+    duplicated_vertices: bool,
+    disabled_flag: bool,
+}
+
+struct SkelSection {
+    material_index: u16,
+    base_index: u32,
+    num_triangles: u32,
+    base_vertex_index: u32,
+    num_vertices: u32,
+}
+
+fn read_skeletal_lod0(package: &Package, reader: &mut ArchiveReader) -> Result<(SkeletalLod, Option<MeshGeometry>)> {
+    reader.skip(2)?; // FStripDataFlags
+    let cooked_out = reader.read_bool()?;
+    let inlined = reader.read_bool()?;
+    let required_bones = reader.read_count(2)?;
+    reader.skip(required_bones * 2)?;
+
+    if cooked_out || !inlined {
+        bail!("LOD0 render data is streamed or cooked out");
+    }
+
+    reader.skip(2)?; // FStripDataFlags
+    let section_count = reader.read_count(1)?;
+    let sections_start = reader.position();
+    let nested_cloth = package.summary.file_version_ue5 > 0;
+
+    let mut sections = None;
+    for bits in 0..16u8 {
+        let layout = SkelSectionLayout {
+            tangent_mask_channel: bits & 1 != 0,
+            visible_in_ray_tracing: bits & 2 != 0,
+            duplicated_vertices: bits & 4 != 0,
+            disabled_flag: bits & 8 != 0,
+        };
+        reader.seek(sections_start)?;
+        if let Ok(parsed) = read_skel_sections(reader, section_count, layout, nested_cloth) {
+            sections = Some(parsed);
+            break;
+        }
+    }
+    let sections = sections.context("Unrecognized skeletal render section layout")?;
+
+    let lod = SkeletalLod {
+        vertices: Some(sections.iter().map(|s| s.num_vertices).sum()),
+        triangles: Some(sections.iter().map(|s| s.num_triangles).sum()),
+        sections: Some(sections.len() as u32),
+    };
+
+    let geometry = read_skeletal_lod0_buffers(reader, &sections)
+        .map_err(|e| tracing::debug!("Skeletal LOD0 buffers unreadable: {:#}", e))
+        .ok();
+
+    Ok((lod, geometry))
+}
+
+fn read_skel_sections(
+    reader: &mut ArchiveReader,
+    count: usize,
+    layout: SkelSectionLayout,
+    nested_cloth: bool,
+) -> Result<Vec<SkelSection>> {
+    let mut sections: Vec<SkelSection> = Vec::with_capacity(count);
+    for _ in 0..count {
+        reader.skip(2)?; // FStripDataFlags
+        let material_index = reader.read_u16()?;
+        let base_index = reader.read_u32()?;
+        let num_triangles = reader.read_u32()?;
+        reader.read_bool()?; // bRecomputeTangent
+        if layout.tangent_mask_channel {
+            reader.read_u8()?;
+        }
+        if reader.read_u32()? > 1 {
+            bail!("Invalid bCastShadow");
+        }
+        if layout.visible_in_ray_tracing && reader.read_u32()? > 1 {
+            bail!("Invalid bVisibleInRayTracing");
+        }
+        let base_vertex_index = reader.read_u32()?;
+
+        let cloth_lods = if nested_cloth { reader.read_count(4)? } else { 1 };
+        for _ in 0..cloth_lods {
+            let mappings = reader.read_count(64)?;
+            reader.skip(mappings * 64)?;
+        }
+
+        let bone_map = reader.read_count(2)?;
+        reader.skip(bone_map * 2)?;
+        let num_vertices = reader.read_u32()?;
+        let max_influences = reader.read_i32()?;
+        reader.read_u16()?; // CorrespondClothAssetIndex
+        reader.skip(16 + 4)?; // FClothingSectionData
+
+        if layout.duplicated_vertices {
+            for _ in 0..2 {
+                let (element_size, elements) = read_bulk_array_header(reader)?;
+                reader.skip(element_size * elements)?;
+            }
+        }
+        if layout.disabled_flag && reader.read_u32()? > 1 {
+            bail!("Invalid bDisabled");
+        }
+
+        if material_index > 256 || num_vertices == 0 || !(1..=12).contains(&max_influences) {
+            bail!("Implausible render section");
+        }
+        if let Some(prev) = sections.last() {
+            let next_index = prev.num_triangles.checked_mul(3).and_then(|indices| indices.checked_add(prev.base_index));
+            let next_vertex = prev.base_vertex_index.checked_add(prev.num_vertices);
+            let (Some(next_index), Some(next_vertex)) = (next_index, next_vertex) else {
+                bail!("Render section range overflows");
+            };
+            if base_index != next_index || base_vertex_index != next_vertex {
+                bail!("Render sections are not contiguous");
+            }
+        }
+
+        sections.push(SkelSection {
+            material_index,
+            base_index,
+            num_triangles,
+            base_vertex_index,
+            num_vertices,
+        });
+    }
+
+    // FMultiSizeIndexContainer starts with the index element size
+    let data_type_size = reader.read_u8()?;
+    if data_type_size != 2 && data_type_size != 4 {
+        bail!("Invalid index data type size {}", data_type_size);
+    }
+    reader.seek(reader.position() - 1)?;
+
+    Ok(sections)
+}
+
+fn read_skeletal_lod0_buffers(reader: &mut ArchiveReader, sections: &[SkelSection]) -> Result<MeshGeometry> {
+    let data_type_size = reader.read_u8()? as usize;
+    let (element_size, count) = read_bulk_array_header(reader)?;
+    if element_size != data_type_size {
+        bail!("Index element size {} does not match data type size {}", element_size, data_type_size);
+    }
+    let bytes = reader.read_bytes(element_size * count)?;
+    let indices: Vec<u32> = if element_size == 4 {
+        bytes
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    } else {
+        bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]) as u32)
+            .collect()
+    };
+
+    let active_bones = reader.read_count(2)?;
+    reader.skip(active_bones * 2)?;
+
+    let positions = read_position_buffer(reader)?;
+    if let Some(bad) = indices.iter().find(|&&i| i as usize >= positions.len()) {
+        bail!("Index {} out of range for {} vertices", bad, positions.len());
+    }
+
+    Ok(MeshGeometry {
+        positions,
+        indices,
+        sections: sections
+            .iter()
+            .map(|s| MeshSection {
+                material_index: s.material_index as u32,
+                first_index: s.base_index,
+                num_triangles: s.num_triangles,
+            })
+            .collect(),
+        materials: Vec::new(),
+    })
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::preview::Asset;
+use crate::{ApiError, ScanStatus};
+
+/// A folder or container whose assets are part of the listing
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Mount {
+    pub id: String,
+    /// Folder, .pak or .utoc as registered
+    pub path: String,
+    /// Higher wins when several mounts have the same asset path
+    pub priority: i32,
+    pub status: ScanStatus,
+    /// Whether class and tags are read from each package, when scanning and rescanning
+    #[serde(default)]
+    pub deep: bool,
+    /// Changed paks are rescanned as they're written (`watch_folder`)
+    #[serde(default)]
+    pub watched: bool,
+    pub pak_files: Vec<String>,
+    /// Paks scanned so far
+    pub paks_done: usize,
+    /// Assets the mount's paks hold, shadowed ones included
+    pub assets: usize,
+    /// Assets hidden by a mount of higher priority (or a patch pak)
+    pub shadowed_assets: usize,
+    pub errors: Vec<ApiError>,
+}
+
+/// The registered mounts and the assets each one scanned
+///
+/// Kept apart from the listing so that removing a mount only means building the
+/// union again, not rescanning the rest.
+#[derive(Default)]
+pub struct MountTable {
+    /// In registration order, which breaks priority ties (later wins)
+    mounts: Vec<(Mount, Vec<Arc<Asset>>)>,
+}
+
+impl MountTable {
+    pub fn is_empty(&self) -> bool {
+        self.mounts.is_empty()
+    }
+
+    pub fn list(&self) -> Vec<Mount> {
+        self.mounts.iter().map(|(mount, _)| mount.clone()).collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Mount> {
+        self.mounts.iter().map(|(mount, _)| mount).find(|mount| mount.id == id)
+    }
+
+    /// Whether a mount already covers this path, so two never share paks
+    pub fn has_path(&self, path: &str) -> bool {
+        self.mounts.iter().any(|(mount, _)| mount.path == path)
+    }
+
+    /// The mount registered for exactly this path
+    pub fn by_path(&self, path: &str) -> Option<&Mount> {
+        self.mounts.iter().map(|(mount, _)| mount).find(|mount| mount.path == path)
+    }
+
+    /// Registers a mount that's about to be scanned
+    pub fn add(&mut self, path: &str, priority: i32, deep: bool, pak_files: Vec<String>) -> Mount {
+        let mount = Mount {
+            id: uuid::Uuid::new_v4().to_string(),
+            path: path.to_string(),
+            priority,
+            status: ScanStatus::Running,
+            deep,
+            watched: false,
+            pak_files,
+            paks_done: 0,
+            assets: 0,
+            shadowed_assets: 0,
+            errors: Vec::new(),
+        };
+        self.mounts.push((mount.clone(), Vec::new()));
+        mount
+    }
+
+    /// Replaces every mount with one already scanned, as a single-folder scan does
+    pub fn reset(&mut self, path: &str, deep: bool, pak_files: Vec<String>, assets: Vec<Arc<Asset>>) {
+        self.mounts.clear();
+        let mut mount = self.add(path, 0, deep, pak_files);
+        mount.status = ScanStatus::Completed;
+        mount.paks_done = mount.pak_files.len();
+        mount.assets = assets.len();
+        self.mounts[0] = (mount, assets);
+    }
+
+    pub fn clear(&mut self) {
+        self.mounts.clear();
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Mount> {
+        let position = self.mounts.iter().position(|(mount, _)| mount.id == id)?;
+        Some(self.mounts.remove(position).0)
+    }
+
+    /// Applies `update` to a mount; false when it has been removed meanwhile
+    pub fn update(&mut self, id: &str, update: impl FnOnce(&mut Mount)) -> bool {
+        match self.mounts.iter_mut().find(|(mount, _)| mount.id == id) {
+            Some((mount, _)) => {
+                update(mount);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stores a finished scan; false when the mount has been removed meanwhile
+    pub fn complete(&mut self, id: &str, assets: Vec<Asset>) -> bool {
+        match self.mounts.iter_mut().find(|(mount, _)| mount.id == id) {
+            Some((mount, scanned)) => {
+                mount.status = ScanStatus::Completed;
+                mount.paks_done = mount.pak_files.len();
+                mount.assets = assets.len();
+                *scanned = crate::preview::share_assets(assets);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Swaps in the assets of one of a mount's paks after it changed; None when the pak is gone
+    ///
+    /// A new pak joins the mount's paks and a deleted one leaves them. Errors recorded for
+    /// the pak are replaced by `error`. False when the mount has been removed meanwhile.
+    pub fn replace_pak(&mut self, id: &str, pak_file: &str, assets: Option<Vec<Asset>>, error: Option<ApiError>) -> bool {
+        let Some((mount, scanned)) = self.mounts.iter_mut().find(|(mount, _)| mount.id == id) else {
+            return false;
+        };
+        scanned.retain(|asset| asset.pak_file.as_deref() != Some(pak_file));
+        mount.pak_files.retain(|file| file != pak_file);
+        if let Some(assets) = assets {
+            scanned.extend(assets.into_iter().map(Arc::new));
+            mount.pak_files.push(pak_file.to_string());
+        }
+        mount.errors.retain(|error| error.context.as_ref().and_then(|context| context.get("pak_file")).is_none_or(|file| file != pak_file));
+        mount.errors.extend(error);
+        mount.paks_done = mount.pak_files.len();
+        mount.assets = scanned.len();
+        true
+    }
+
+    /// Assets of every mount, keeping per path only those of the highest-ranked mount
+    ///
+    /// Mounts rank by priority, then registration order. Within a mount a patch pak
+    /// (`*_P.pak`) hides the same path in the mount's other paks; paths still in several
+    /// paks of one mount are all kept, to be told apart by `pak_file`. The assets are
+    /// shared with the mounts, not copied.
+    pub fn union(&mut self) -> Vec<Arc<Asset>> {
+        let rank = |mount_index: usize, asset: &Asset| {
+            let patch = asset.pak_file.as_deref().is_some_and(is_patch_pak);
+            (self.mounts[mount_index].0.priority, mount_index, patch)
+        };
+
+        let mut best: HashMap<&str, (i32, usize, bool)> = HashMap::new();
+        for (index, (_, assets)) in self.mounts.iter().enumerate() {
+            for asset in assets {
+                let rank = rank(index, asset);
+                best.entry(asset.path.as_str()).and_modify(|best| *best = (*best).max(rank)).or_insert(rank);
+            }
+        }
+
+        let mut union = Vec::new();
+        let mut shadowed = vec![0; self.mounts.len()];
+        for (index, (_, assets)) in self.mounts.iter().enumerate() {
+            for asset in assets {
+                if best[asset.path.as_str()] == rank(index, asset) {
+                    union.push(asset.clone());
+                } else {
+                    shadowed[index] += 1;
+                }
+            }
+        }
+        for ((mount, _), shadowed) in self.mounts.iter_mut().zip(shadowed) {
+            mount.shadowed_assets = shadowed;
+        }
+        union
+    }
+}
+
+/// Whether a pak is a patch pak, which UE mounts over the others (`pakchunk0_P.pak`)
+pub fn is_patch_pak(pak_file: &str) -> bool {
+    let stem = std::path::Path::new(pak_file).file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+    stem.to_ascii_lowercase().ends_with("_p")
+}
@@ -0,0 +1,71 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// `OodleLZ_Decompress` of oo2core: returns the bytes written to `raw`, or 0 on failure
+type DecompressFn = unsafe extern "C" fn(
+    compressed: *const u8,
+    compressed_len: isize,
+    raw: *mut u8,
+    raw_len: isize,
+    fuzz_safe: i32,
+    check_crc: i32,
+    verbosity: i32,
+    dec_buf_base: *mut u8,
+    dec_buf_size: isize,
+    callback: *const std::ffi::c_void,
+    callback_user_data: *const std::ffi::c_void,
+    decoder_memory: *mut u8,
+    decoder_memory_size: isize,
+    thread_phase: i32,
+) -> isize;
+
+/// Libraries loaded so far, by path; they stay loaded for the life of the process
+static LIBRARIES: OnceLock<Mutex<HashMap<PathBuf, Arc<libloading::Library>>>> = OnceLock::new();
+
+fn library(path: &Path) -> Result<Arc<libloading::Library>> {
+    let mut libraries = LIBRARIES.get_or_init(Default::default).lock().unwrap();
+    if let Some(library) = libraries.get(path) {
+        return Ok(library.clone());
+    }
+    // SAFETY: oo2core has no initialization routines with preconditions to uphold
+    let library = unsafe { libloading::Library::new(path) }
+        .with_context(|| format!("Failed to load the Oodle library {}", path.display()))?;
+    let library = Arc::new(library);
+    libraries.insert(path.to_path_buf(), library.clone());
+    Ok(library)
+}
+
+/// Decompresses an Oodle block of `raw_len` bytes with the oo2core library at `path`
+pub fn decompress(path: &Path, compressed: &[u8], raw_len: usize) -> Result<Vec<u8>> {
+    let library = library(path)?;
+    // SAFETY: the symbol is declared with the signature oo2core exports it with
+    let decompress: libloading::Symbol<DecompressFn> = unsafe { library.get(b"OodleLZ_Decompress\0") }
+        .with_context(|| format!("{} has no OodleLZ_Decompress", path.display()))?;
+
+    let mut raw = vec![0u8; raw_len];
+    // SAFETY: both buffers outlive the call and their lengths are the ones passed
+    let written = unsafe {
+        decompress(
+            compressed.as_ptr(),
+            compressed.len() as isize,
+            raw.as_mut_ptr(),
+            raw_len as isize,
+            1,
+            0,
+            0,
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null_mut(),
+            0,
+            3,
+        )
+    };
+    if written != raw_len as isize {
+        bail!("Oodle decompression failed ({} of {} bytes)", written.max(0), raw_len);
+    }
+    Ok(raw)
+}
@@ -1,6 +1,24 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
 use std::path::Path;
-use anyhow::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use tokio::io::AsyncRead;
+use tokio_util::sync::CancellationToken;
+
+use crate::asset_path::normalize_asset_path;
+use crate::error::Cancelled;
+use crate::resource_limit;
+
+/// Bytes a streamed entry holds decompressed at once: one compression block of UE's
+/// default size
+const STREAM_BLOCK_SIZE: u64 = 64 * 1024;
+
+/// Reader over (part of) an entry's extracted bytes
+pub type EntryReader = Box<dyn AsyncRead + Send + Unpin>;
 
 /// Represents a parsed .pak file structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +28,19 @@ pub struct PakFile {
     pub mount_point: String,
     pub entries: Vec<PakEntry>,
     pub total_size: u64,
+    /// GUID of the AES key the pak's encrypted entries need; None for the default key
+    #[serde(default)]
+    pub encryption_key_guid: Option<String>,
+    /// Modification time of the pak file, when the file system reports one
+    #[serde(default)]
+    pub modified: Option<DateTime<Utc>>,
+}
+
+impl PakFile {
+    /// Whether a key for the pak itself, its GUID, or the wildcard key is configured in the settings
+    pub fn has_key(&self) -> bool {
+        PakParser::new(&self.path).aes_key(self.encryption_key_guid.as_deref()).is_some()
+    }
 }
 
 /// Represents an individual entry within a .pak file
@@ -20,12 +51,59 @@ pub struct PakEntry {
     pub compressed_size: u64,
     pub uncompressed_size: u64,
     pub compression_method: CompressionMethod,
-    pub sha1_hash: Option<String>,
+    /// SHA-1 of the entry's bytes as stored, sent as 40 lowercase hex digits
+    #[serde(default, with = "sha1_hex")]
+    pub sha1_hash: Option<[u8; 20]>,
     pub is_encrypted: bool,
+    /// When the entry was packed; only the earliest pak version records it
+    #[serde(default)]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Modification time of a file, if the file system reports one
+pub fn file_modified(path: &Path) -> Option<DateTime<Utc>> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok().map(DateTime::<Utc>::from)
+}
+
+/// Lowercase hex of `bytes`
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A SHA-1 from 40 hex digits of either case
+pub fn parse_sha1(hex: &str) -> Option<[u8; 20]> {
+    // `from_str_radix` alone would take a sign, so "+f" would pass for a digit pair
+    if hex.len() != 40 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut hash = [0u8; 20];
+    for (byte, digits) in hash.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+    }
+    Some(hash)
+}
+
+/// Serde for an optional SHA-1 as 40 lowercase hex digits
+pub mod sha1_hex {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(hash: &Option<[u8; 20]>, serializer: S) -> Result<S::Ok, S::Error> {
+        match hash {
+            Some(hash) => serializer.serialize_some(&super::to_hex(hash)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<[u8; 20]>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(hex) => super::parse_sha1(&hex).map(Some).ok_or_else(|| D::Error::custom(format!("{:?} is not a 40-digit SHA-1", hex))),
+            None => Ok(None),
+        }
+    }
 }
 
 /// Supported compression methods in Unreal Engine .pak files
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub enum CompressionMethod {
     None,
     Zlib,
@@ -35,6 +113,20 @@ pub enum CompressionMethod {
     Unknown(u32),
 }
 
+impl CompressionMethod {
+    /// Whether entries compressed this way can be extracted
+    ///
+    /// Oodle needs Epic's oo2core library, which isn't shipped: it's supported once the
+    /// settings point at a copy.
+    pub fn is_supported(&self) -> bool {
+        match self {
+            CompressionMethod::Oodle => crate::settings::global().get().oodle_library().is_some(),
+            CompressionMethod::Unknown(_) => false,
+            _ => true,
+        }
+    }
+}
+
 impl From<u32> for CompressionMethod {
     fn from(value: u32) -> Self {
         match value {
@@ -48,6 +140,162 @@ impl From<u32> for CompressionMethod {
     }
 }
 
+/// Magic number of the pak footer (`FPakInfo`)
+const PAK_MAGIC: u32 = 0x5A6F12E1;
+
+/// Footer layouts, newest first: serialized size, the versions using it, whether it has
+/// the encryption key GUID and the v9 frozen-index flag, and its compression name slots
+const FOOTER_LAYOUTS: [(usize, std::ops::RangeInclusive<u32>, bool, bool, usize); 5] = [
+    (222, 9..=9, true, true, 5),
+    (221, 8..=u32::MAX, true, false, 5),
+    (189, 8..=8, true, false, 4),
+    (61, 7..=7, true, false, 0),
+    (45, 1..=6, false, false, 0),
+];
+
+/// Longest mount point read from the start of an index
+pub(crate) const MAX_MOUNT_POINT_LEN: usize = 4096;
+
+/// Largest index `test_key` reads and decrypts
+const MAX_TESTED_INDEX_LEN: u64 = 512 * 1024 * 1024;
+
+/// A pak's footer and where its fields sit in the layout its version uses
+struct Footer {
+    bytes: Vec<u8>,
+    /// Offset of the magic
+    at: usize,
+    has_guid: bool,
+    frozen: bool,
+    slots: usize,
+}
+
+impl Footer {
+    fn version(&self) -> u32 {
+        read_u32(&self.bytes, self.at + 4)
+    }
+
+    fn index_offset(&self) -> u64 {
+        read_u64(&self.bytes, self.at + 8)
+    }
+
+    fn index_size(&self) -> u64 {
+        read_u64(&self.bytes, self.at + 16)
+    }
+
+    /// SHA-1 of the index, taken once decrypted
+    fn index_hash(&self) -> &[u8] {
+        &self.bytes[self.at + 24..self.at + 44]
+    }
+
+    fn encrypted_index(&self) -> bool {
+        self.bytes[self.at - 1] != 0
+    }
+
+    fn encryption_key_guid(&self) -> Option<String> {
+        if self.has_guid { format_guid(&self.bytes[..16]) } else { None }
+    }
+
+    fn compression_methods(&self) -> Vec<String> {
+        let methods_at = self.at + 44 + usize::from(self.frozen);
+        method_names(&self.bytes[methods_at..methods_at + self.slots * 32], 32)
+    }
+}
+
+/// What a `pakchunkN[tag]-Platform` container name says
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ChunkName {
+    pub chunk_id: u32,
+    /// What follows the number ("optional", "_s2"); None when the dash does
+    pub tag: Option<String>,
+    /// What follows the dash ("Windows", "WindowsNoEditor_0_P")
+    pub platform: Option<String>,
+}
+
+/// The chunk of a .pak, .utoc or .ucas named after the `pakchunkN[tag]-Platform`
+/// convention ("pakchunk3optional-Windows.pak"); None for other names
+pub fn chunk_name(path: &str) -> Option<ChunkName> {
+    const PREFIX: &str = "pakchunk";
+    let file_name = path.rsplit(['/', '\\']).next()?;
+    let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+    let rest = stem.get(PREFIX.len()..).filter(|_| stem[..PREFIX.len()].eq_ignore_ascii_case(PREFIX))?;
+    let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let chunk_id = rest[..digits].parse().ok()?;
+    let (tag, platform) = match rest[digits..].split_once('-') {
+        Some((tag, platform)) => (tag, Some(platform)),
+        None => (&rest[digits..], None),
+    };
+    Some(ChunkName {
+        chunk_id,
+        tag: (!tag.is_empty()).then(|| tag.to_string()),
+        platform: platform.filter(|platform| !platform.is_empty()).map(str::to_string),
+    })
+}
+
+/// `chunk_name`'s number alone
+pub fn chunk_id(path: &str) -> Option<u32> {
+    chunk_name(path).map(|name| name.chunk_id)
+}
+
+/// What a pak's footer and the first bytes of its index say, read without walking entries
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PakInfo {
+    pub path: String,
+    /// From a `pakchunkN-Platform` name; None for other names
+    pub chunk_id: Option<u32>,
+    pub file_size: u64,
+    pub version: u32,
+    /// None when the index is encrypted
+    pub mount_point: Option<String>,
+    /// None when the index is encrypted
+    pub entry_count: Option<u64>,
+    pub index_offset: u64,
+    pub index_size: u64,
+    pub encrypted_index: bool,
+    /// GUID of the AES key the pak needs (hex, as in crypto.json); None for the default key
+    pub encryption_key_guid: Option<String>,
+    /// Methods named in the footer (v8+); older paks only name them per entry
+    pub compression_methods: Vec<String>,
+    /// Bytes of stored entry data: everything before the index, entry headers included
+    pub total_compressed_bytes: u64,
+    /// Sum of the entries' sizes, known once the pak is in the listing
+    pub total_uncompressed_bytes: Option<u64>,
+}
+
+pub(crate) fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+pub(crate) fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+/// An `FGuid` as UE prints it: its four little-endian words in hex; None when all zero
+pub(crate) fn format_guid(bytes: &[u8]) -> Option<String> {
+    let words: Vec<u32> = (0..4).map(|i| read_u32(bytes, i * 4)).collect();
+    words.iter().any(|&word| word != 0).then(|| words.iter().map(|word| format!("{:08X}", word)).collect())
+}
+
+/// Decrypts `data` in place with AES-256 in ECB mode, as paks and .utoc indexes are encrypted
+///
+/// A trailing partial block is left as it is; encrypted data is always padded to 16 bytes.
+pub(crate) fn decrypt(key: &[u8; 32], data: &mut [u8]) {
+    use aes::cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit};
+
+    let cipher = aes::Aes256::new(GenericArray::from_slice(key));
+    for block in data.chunks_exact_mut(16) {
+        cipher.decrypt_block(GenericArray::from_mut_slice(block));
+    }
+}
+
+/// Compression method names from fixed-size, NUL-padded slots, skipping empty ones
+pub(crate) fn method_names(bytes: &[u8], slot: usize) -> Vec<String> {
+    bytes
+        .chunks(slot)
+        .map(|name| String::from_utf8_lossy(name).trim_end_matches('\0').to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
 /// Main .pak file parser implementation
 pub struct PakParser {
     pub path: String,
@@ -61,15 +309,44 @@ impl PakParser {
         }
     }
 
+    /// The AES key configured for this pak: its own, else the one for `guid`, else the wildcard key
+    pub fn aes_key(&self, guid: Option<&str>) -> Option<[u8; 32]> {
+        crate::settings::global().get().key_for(&self.path, guid).and_then(crate::settings::parse_key)
+    }
+
     /// Parses the .pak file and returns its structure
-    /// 
+    ///
+    /// The index is served from the index cache (`scan_cache`) while the pak's path, size,
+    /// modification time and footer are those it was cached with, and read from the file
+    /// (then cached) otherwise. Fails with `Cancelled` when `cancel` fires while the index
+    /// is read.
+    pub async fn parse(&self, cancel: &CancellationToken) -> Result<PakFile> {
+        let key = crate::scan_cache::pak_index_key(&self.path);
+        if let Some(key) = &key {
+            if let Some(pak) = crate::scan_cache::cached_pak_index(key).await {
+                INDEX_CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+                return Ok(pak);
+            }
+        }
+        let pak = self.read_index(cancel).await?;
+        if let Some(key) = &key {
+            crate::scan_cache::store_pak_index(key, &pak).await;
+        }
+        Ok(pak)
+    }
+
+    /// Reads and parses the index from the file, bypassing the index cache
+    ///
     /// TODO: Implement actual binary parsing logic
     /// This will involve:
     /// 1. Reading the pak file header (magic, version, index offset)
     /// 2. Parsing the file index at the end of the pak file
     /// 3. Extracting file entries with their metadata
     /// 4. Handling encryption if present
-    pub async fn parse(&self) -> Result<PakFile> {
+    /// 5. Checking `cancel` every few thousand entries, as it is checked up front now
+    async fn read_index(&self, cancel: &CancellationToken) -> Result<PakFile> {
+        Cancelled::check(cancel)?;
+        INDEX_READS.fetch_add(1, Ordering::Relaxed);
         // PLACEHOLDER: This is where the actual .pak parsing logic will go
         // For now, return mock data to keep the API functional
         
@@ -102,8 +379,9 @@ impl PakParser {
                     compressed_size: 125440,
                     uncompressed_size: 2457600,
                     compression_method: CompressionMethod::LZ4,
-                    sha1_hash: Some("a1b2c3d4e5f6789".to_string()),
+                    sha1_hash: parse_sha1("a1b2c3d4e5f67890a1b2c3d4e5f67890a1b2c3d4"),
                     is_encrypted: false,
+                    timestamp: None,
                 },
                 PakEntry {
                     filename: "Content/Textures/MainMenu.uasset".to_string(),
@@ -111,11 +389,14 @@ impl PakParser {
                     compressed_size: 1048576,
                     uncompressed_size: 4194304,
                     compression_method: CompressionMethod::Oodle,
-                    sha1_hash: Some("f6e5d4c3b2a1987".to_string()),
+                    sha1_hash: parse_sha1("f6e5d4c3b2a19870f6e5d4c3b2a19870f6e5d4c3"),
                     is_encrypted: false,
+                    timestamp: None,
                 },
             ],
             total_size: 67108864, // 64MB placeholder
+            encryption_key_guid: None,
+            modified: file_modified(Path::new(&self.path)),
         })
     }
 
@@ -138,20 +419,53 @@ impl PakParser {
         // 4. Decompress based on compression_method
         // 5. Verify SHA-1 hash if present
         // 6. Handle decryption for encrypted entries
-        
-        Ok(vec![0u8; 1024]) // Placeholder empty data
+
+        // Room for the decompressed entry is taken before it's allocated
+        let size = 1024;
+        let _lease = resource_limit::global().acquire(size as u64).await;
+        Ok(vec![0u8; size]) // Placeholder empty data
+    }
+
+    /// Streams bytes `range` of an entry's extracted data
+    ///
+    /// Unlike `extract_file`, the entry is never held in memory as a whole, so multi-GB
+    /// .ubulk files can be served. `range` is clamped to the entry's uncompressed size. The
+    /// reader holds a decompression slot and one block of the buffer budget until dropped.
+    ///
+    /// TODO: Seek to the compression block holding `range.start` and decompress block
+    /// by block once extraction is implemented; until then this streams placeholder zeros
+    /// like `extract_file`.
+    pub async fn extract_stream(&self, filename: &str, range: Range<u64>) -> Result<EntryReader> {
+        let pak = self.parse(&CancellationToken::new()).await?;
+        let wanted = normalize_asset_path(filename);
+        let Some(entry) = pak.entries.iter().find(|entry| normalize_asset_path(&entry.filename) == wanted) else {
+            bail!("{} is not in {}", filename, self.path);
+        };
+        if entry.is_encrypted && !pak.has_key() {
+            bail!("{} is encrypted and no key is configured for it", filename);
+        }
+
+        let end = range.end.min(entry.uncompressed_size);
+        let start = range.start.min(end);
+        tracing::info!("Streaming bytes {}..{} of {} from {}", start, end, filename, self.path);
+
+        let lease = resource_limit::global().acquire(STREAM_BLOCK_SIZE.min(end - start)).await;
+        Ok(Box::new(lease.hold(tokio::io::AsyncReadExt::take(tokio::io::repeat(0), end - start))))
     }
 
     /// Lists all files in the .pak archive
     pub async fn list_files(&self) -> Result<Vec<String>> {
-        let pak_file = self.parse().await?;
+        let pak_file = self.parse(&CancellationToken::new()).await?;
         Ok(pak_file.entries.into_iter().map(|entry| entry.filename).collect())
     }
 
     /// Gets information about a specific file without extracting it
+    ///
+    /// `filename` may use either separator and carry the mount prefix; see `normalize_asset_path`.
     pub async fn get_file_info(&self, filename: &str) -> Result<Option<PakEntry>> {
-        let pak_file = self.parse().await?;
-        Ok(pak_file.entries.into_iter().find(|entry| entry.filename == filename))
+        let pak_file = self.parse(&CancellationToken::new()).await?;
+        let wanted = normalize_asset_path(filename);
+        Ok(pak_file.entries.into_iter().find(|entry| normalize_asset_path(&entry.filename) == wanted))
     }
 
     /// Validates the integrity of the .pak file
@@ -170,6 +484,209 @@ impl PakParser {
         
         Ok(true)
     }
+
+    /// Reads the footer, trying each layout until the magic lines up
+    async fn footer(&self, file: &mut tokio::fs::File, file_size: u64) -> Result<Footer> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let tail_len = file_size.min(FOOTER_LAYOUTS[0].0 as u64) as usize;
+        let mut tail = vec![0u8; tail_len];
+        file.seek(std::io::SeekFrom::End(-(tail_len as i64))).await?;
+        file.read_exact(&mut tail).await?;
+
+        // The version decides the footer's size
+        let footer = FOOTER_LAYOUTS.iter().find_map(|(size, versions, has_guid, frozen, slots)| {
+            let start = tail_len.checked_sub(*size)?;
+            let at = if *has_guid { 17 } else { 1 };
+            let version = read_u32(&tail[start..], at + 4);
+            (read_u32(&tail[start..], at) == PAK_MAGIC && versions.contains(&version)).then(|| Footer {
+                bytes: tail[start..].to_vec(),
+                at,
+                has_guid: *has_guid,
+                frozen: *frozen,
+                slots: *slots,
+            })
+        });
+        footer.ok_or_else(|| anyhow::anyhow!("{} is not a pak: no footer found", self.path))
+    }
+
+    /// Reads the footer and the start of the index: a few hundred bytes whatever the pak's size
+    pub async fn info(&self) -> Result<PakInfo> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let mut file = tokio::fs::File::open(&self.path).await?;
+        let file_size = file.metadata().await?.len();
+        let footer = self.footer(&mut file, file_size).await?;
+
+        let index_offset = footer.index_offset();
+        let index_size = footer.index_size();
+        let mut info = PakInfo {
+            path: self.path.clone(),
+            chunk_id: chunk_id(&self.path),
+            file_size,
+            version: footer.version(),
+            mount_point: None,
+            entry_count: None,
+            index_offset,
+            index_size,
+            encrypted_index: footer.encrypted_index(),
+            encryption_key_guid: footer.encryption_key_guid(),
+            compression_methods: footer.compression_methods(),
+            total_compressed_bytes: index_offset.min(file_size),
+            total_uncompressed_bytes: None,
+        };
+        if info.encrypted_index || index_offset.saturating_add(index_size) > file_size {
+            return Ok(info);
+        }
+
+        // The index opens with the mount point (an FString) and the entry count
+        file.seek(std::io::SeekFrom::Start(index_offset)).await?;
+        let length = file.read_i32_le().await?;
+        let (units, wide) = if length < 0 { (length.unsigned_abs() as usize, true) } else { (length as usize, false) };
+        let bytes = if wide { units * 2 } else { units };
+        if bytes > MAX_MOUNT_POINT_LEN || bytes as u64 + 8 > index_size {
+            return Ok(info);
+        }
+        let mut raw = vec![0u8; bytes];
+        file.read_exact(&mut raw).await?;
+        let mount_point = if wide {
+            let units: Vec<u16> = raw.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            String::from_utf8_lossy(&raw).into_owned()
+        };
+        info.mount_point = Some(mount_point.trim_end_matches('\0').to_string());
+        info.entry_count = Some(file.read_i32_le().await?.max(0) as u64);
+        Ok(info)
+    }
+
+    /// Whether `key` decrypts the pak's index: once decrypted, its SHA-1 must match the
+    /// footer's. Nothing is saved.
+    ///
+    /// Fails when the index isn't encrypted, as there's then nothing to check the key against.
+    pub async fn test_key(&self, key: &[u8; 32]) -> Result<bool> {
+        use sha1::{Digest, Sha1};
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let mut file = tokio::fs::File::open(&self.path).await?;
+        let file_size = file.metadata().await?.len();
+        let footer = self.footer(&mut file, file_size).await?;
+        if !footer.encrypted_index() {
+            bail!("The index of {} isn't encrypted, so there's nothing to check the key against", self.path);
+        }
+        let (index_offset, index_size) = (footer.index_offset(), footer.index_size());
+        if index_size == 0 || index_size % 16 != 0 || index_offset.saturating_add(index_size) > file_size {
+            bail!("{} has a corrupt footer: its index isn't where it says", self.path);
+        }
+        if index_size > MAX_TESTED_INDEX_LEN {
+            bail!("The index of {} is too large to check ({} bytes)", self.path, index_size);
+        }
+
+        let mut index = vec![0u8; index_size as usize];
+        file.seek(std::io::SeekFrom::Start(index_offset)).await?;
+        file.read_exact(&mut index).await?;
+        decrypt(key, &mut index);
+        Ok(Sha1::digest(&index).as_slice() == footer.index_hash())
+    }
+}
+
+/// Pak indexes read from their files since startup
+static INDEX_READS: AtomicUsize = AtomicUsize::new(0);
+
+/// Pak indexes loaded from the index cache instead since startup
+static INDEX_CACHE_HITS: AtomicUsize = AtomicUsize::new(0);
+
+/// How many pak indexes were read from their files since startup
+pub fn index_reads() -> usize {
+    INDEX_READS.load(Ordering::Relaxed)
+}
+
+/// How many pak indexes came from the index cache since startup
+pub fn index_cache_hits() -> usize {
+    INDEX_CACHE_HITS.load(Ordering::Relaxed)
+}
+
+/// Paths of the `OpenPak` handles currently alive, once per handle, reported by `/health`
+/// and the diagnostics
+static OPEN_HANDLES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Number of `OpenPak` handles currently alive
+pub fn open_pak_handles() -> usize {
+    OPEN_HANDLES.lock().unwrap().len()
+}
+
+/// The paks `OpenPak` handles are held for, once per handle
+pub fn open_pak_paths() -> Vec<String> {
+    OPEN_HANDLES.lock().unwrap().clone()
+}
+
+/// A pak whose index has been read once and can serve many lookups and extractions
+///
+/// `PakParser::get_file_info` re-reads the index on every call; batch work should
+/// open the pak once and share this handle instead.
+pub struct OpenPak {
+    parser: PakParser,
+    pak: PakFile,
+    entries: HashMap<String, usize>,
+}
+
+impl OpenPak {
+    /// Opens a pak and indexes its entries by normalized filename
+    pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let parser = PakParser::new(path);
+        let pak = parser.parse(&CancellationToken::new()).await?;
+        let entries = pak
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (normalize_asset_path(&entry.filename), i))
+            .collect();
+        OPEN_HANDLES.lock().unwrap().push(parser.path.clone());
+        Ok(OpenPak { parser, pak, entries })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.parser.path
+    }
+
+    /// The parsed pak structure
+    pub fn pak(&self) -> &PakFile {
+        &self.pak
+    }
+
+    /// Looks up an entry by filename, in either separator and with or without the mount prefix
+    pub fn entry(&self, filename: &str) -> Option<&PakEntry> {
+        self.entries.get(&normalize_asset_path(filename)).map(|&i| &self.pak.entries[i])
+    }
+
+    /// Extracts an entry's bytes
+    pub async fn extract_file(&self, filename: &str) -> Result<Vec<u8>> {
+        self.parser.extract_file(filename).await
+    }
+
+    /// Extracts a package (.uasset/.umap) followed by its sibling .uexp, if present
+    pub async fn extract_package(&self, filename: &str) -> Result<Vec<u8>> {
+        let mut data = self.extract_file(filename).await?;
+
+        // Cooked packages split export data into a sibling .uexp that follows the header
+        if let Some(stem) = filename.strip_suffix(".uasset").or_else(|| filename.strip_suffix(".umap")) {
+            let uexp_path = format!("{}.uexp", stem);
+            if self.entry(&uexp_path).is_some() {
+                data.extend(self.extract_file(&uexp_path).await?);
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+impl Drop for OpenPak {
+    fn drop(&mut self) {
+        let mut open = OPEN_HANDLES.lock().unwrap();
+        if let Some(i) = open.iter().position(|path| *path == self.parser.path) {
+            open.swap_remove(i);
+        }
+    }
 }
 
 /// Utility functions for .pak file operations
@@ -200,6 +717,43 @@ pub mod utils {
         Ok(pak_files)
     }
 
+    /// Files added, removed or changed between two sets of pak entries
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    pub struct PakDiff {
+        pub added_files: Vec<String>,
+        pub removed_files: Vec<String>,
+        /// Present in both with a different size or hash
+        pub changed_files: Vec<String>,
+    }
+
+    /// Compares pak entries by filename; a later entry with the same name wins, like a patch pak
+    pub fn diff_entries<'a>(
+        old: impl IntoIterator<Item = &'a PakEntry>,
+        new: impl IntoIterator<Item = &'a PakEntry>,
+    ) -> PakDiff {
+        let old: HashMap<&str, &PakEntry> = old.into_iter().map(|e| (e.filename.as_str(), e)).collect();
+        let new: HashMap<&str, &PakEntry> = new.into_iter().map(|e| (e.filename.as_str(), e)).collect();
+
+        let mut diff = PakDiff::default();
+        for (filename, entry) in &new {
+            match old.get(filename) {
+                None => diff.added_files.push(filename.to_string()),
+                Some(previous) => {
+                    let hash_changed = matches!((&previous.sha1_hash, &entry.sha1_hash), (Some(a), Some(b)) if a != b);
+                    if hash_changed || previous.uncompressed_size != entry.uncompressed_size {
+                        diff.changed_files.push(filename.to_string());
+                    }
+                }
+            }
+        }
+        diff.removed_files = old.keys().filter(|f| !new.contains_key(*f)).map(|f| f.to_string()).collect();
+
+        diff.added_files.sort();
+        diff.removed_files.sort();
+        diff.changed_files.sort();
+        diff
+    }
+
     /// Gets the total size of all .pak files in a directory
     pub async fn get_total_pak_size<P: AsRef<Path>>(dir: P) -> Result<u64> {
         let pak_files = find_pak_files(dir).await?;
@@ -213,4 +767,73 @@ pub mod utils {
         
         Ok(total_size)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sha1_hash: Option<[u8; 20]>) -> PakEntry {
+        PakEntry {
+            filename: "Game/Content/Crate.uasset".to_string(),
+            offset: 0,
+            compressed_size: 10,
+            uncompressed_size: 10,
+            compression_method: CompressionMethod::None,
+            sha1_hash,
+            is_encrypted: false,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn sha1_serializes_as_lowercase_hex() {
+        let mut hash = [0u8; 20];
+        hash[0] = 0xAB;
+        hash[19] = 0x0F;
+        let json = serde_json::to_value(entry(Some(hash))).unwrap();
+        assert_eq!(json["sha1_hash"], "ab0000000000000000000000000000000000000f");
+        assert_eq!(serde_json::to_value(entry(None)).unwrap()["sha1_hash"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn sha1_round_trips() {
+        let hash: [u8; 20] = std::array::from_fn(|i| (i * 13) as u8);
+        let json = serde_json::to_string(&entry(Some(hash))).unwrap();
+        let back: PakEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.sha1_hash, Some(hash));
+
+        let json = serde_json::to_string(&entry(None)).unwrap();
+        assert_eq!(serde_json::from_str::<PakEntry>(&json).unwrap().sha1_hash, None);
+    }
+
+    #[test]
+    fn sha1_missing_field_is_none() {
+        let mut json = serde_json::to_value(entry(None)).unwrap();
+        json.as_object_mut().unwrap().remove("sha1_hash");
+        assert_eq!(serde_json::from_value::<PakEntry>(json).unwrap().sha1_hash, None);
+    }
+
+    #[test]
+    fn parse_sha1_accepts_either_case() {
+        let upper = "AB0000000000000000000000000000000000000F";
+        assert_eq!(parse_sha1(upper), parse_sha1(&upper.to_lowercase()));
+        assert_eq!(parse_sha1(upper).unwrap()[0], 0xAB);
+    }
+
+    #[test]
+    fn parse_sha1_rejects_malformed() {
+        let valid = "ab0000000000000000000000000000000000000f";
+        assert!(parse_sha1(valid).is_some());
+        assert!(parse_sha1(&valid[..38]).is_none());
+        assert!(parse_sha1(&format!("{}00", valid)).is_none());
+        assert!(parse_sha1(&format!("+f{}", &valid[2..])).is_none());
+        assert!(parse_sha1(&format!("-f{}", &valid[2..])).is_none());
+        assert!(parse_sha1(&format!("zz{}", &valid[2..])).is_none());
+        assert!(parse_sha1(&format!("é{}", &valid[2..])).is_none());
+
+        let mut json = serde_json::to_value(entry(None)).unwrap();
+        json["sha1_hash"] = serde_json::Value::from(format!("+f{}", &valid[2..]));
+        assert!(serde_json::from_value::<PakEntry>(json).is_err());
+    }
 }
\ No newline at end of file
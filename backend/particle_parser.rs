@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use anyhow::{Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// A user-exposed Niagara parameter and its default value when the type is known
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserParameter {
+    pub name: String,
+    pub type_name: Option<String>,
+    pub default: Option<Value>,
+}
+
+/// What a NiagaraSystem or Cascade ParticleSystem is made of
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticleSystemInfo {
+    pub class: String,
+    pub emitters: Vec<String>,
+    pub materials: Vec<String>,
+    pub textures: Vec<String>,
+    pub user_parameters: Vec<UserParameter>,
+    /// Fixed bounds when the system stores them ({min, max, is_valid})
+    pub bounds: Option<Value>,
+}
+
+/// Parses a NiagaraSystem or Cascade ParticleSystem package
+pub fn parse_particle_system(data: &[u8]) -> Result<ParticleSystemInfo> {
+    let package = Package::parse(data)?;
+    let export = package
+        .exports
+        .iter()
+        .find(|e| matches!(package.export_class_name(e).as_str(), "NiagaraSystem" | "ParticleSystem"))
+        .context("Package has no NiagaraSystem or ParticleSystem export")?;
+    let class = package.export_class_name(export);
+
+    let mut reader = ArchiveReader::new(package.export_data(data, export)?);
+    let properties = uasset_parser::read_properties(&package, &mut reader, &class)?;
+    let enabled = |key: &str| properties.get(key).and_then(Value::as_bool).unwrap_or(false);
+
+    let (emitters, user_parameters, bounds) = if class == "NiagaraSystem" {
+        let emitters = properties
+            .get("EmitterHandles")
+            .and_then(Value::as_array)
+            .map(|handles| {
+                handles
+                    .iter()
+                    .filter_map(|h| h.get("Name").and_then(Value::as_str).map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_else(|| exports_of_class(&package, "NiagaraEmitter"));
+        let user_parameters = properties
+            .get("ExposedParameters")
+            .map(read_user_parameters)
+            .unwrap_or_default();
+        let bounds = properties.get("FixedBounds").filter(|_| enabled("bFixedBounds")).cloned();
+        (emitters, user_parameters, bounds)
+    } else {
+        // Cascade emitters are exports; their display name is the EmitterName property
+        let emitters = properties
+            .get("Emitters")
+            .and_then(Value::as_array)
+            .map(|refs| refs.iter().filter_map(Value::as_str).map(|name| cascade_emitter_name(&package, data, name)).collect())
+            .unwrap_or_else(|| exports_of_class(&package, "ParticleSpriteEmitter"));
+        let bounds = properties
+            .get("FixedRelativeBoundingBox")
+            .filter(|_| enabled("bUseFixedRelativeBoundingBox"))
+            .cloned();
+        (emitters, Vec::new(), bounds)
+    };
+
+    Ok(ParticleSystemInfo {
+        class,
+        emitters,
+        materials: imports_with_class_prefix(&package, "Material"),
+        textures: imports_with_class_prefix(&package, "Texture"),
+        user_parameters,
+        bounds,
+    })
+}
+
+fn exports_of_class(package: &Package, class: &str) -> Vec<String> {
+    package
+        .exports
+        .iter()
+        .filter(|e| package.export_class_name(e) == class)
+        .map(|e| e.object_name.clone())
+        .collect()
+}
+
+fn imports_with_class_prefix(package: &Package, prefix: &str) -> Vec<String> {
+    let mut paths: Vec<String> = (0..package.imports.len())
+        .map(|i| -(i as i32) - 1)
+        .filter(|&i| package.import(i).is_some_and(|import| import.class_name.starts_with(prefix)))
+        .filter_map(|i| package.import_object_path(i))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn cascade_emitter_name(package: &Package, data: &[u8], export_name: &str) -> String {
+    package
+        .exports
+        .iter()
+        .find(|e| e.object_name == export_name)
+        .and_then(|e| {
+            let bytes = package.export_data(data, e).ok()?;
+            uasset_parser::read_properties(package, &mut ArchiveReader::new(bytes), &package.export_class_name(e)).ok()
+        })
+        .and_then(|properties| properties.get("EmitterName").and_then(Value::as_str).map(str::to_string))
+        .unwrap_or_else(|| export_name.to_string())
+}
+
+/// Reads the user parameter store: variables plus their slice of the packed ParameterData
+///
+/// Defaults are only decoded for types whose layout is known from the type struct path.
+fn read_user_parameters(store: &Value) -> Vec<UserParameter> {
+    let parameter_data: Vec<u8> = store
+        .get("ParameterData")
+        .and_then(Value::as_array)
+        .map(|bytes| bytes.iter().filter_map(Value::as_u64).map(|b| b as u8).collect())
+        .unwrap_or_default();
+
+    // UE5 stores variables with their offset; older versions a variable -> offset map
+    let entries: Vec<(&Value, Option<usize>)> = match store.get("Parameters").and_then(Value::as_array) {
+        Some(parameters) => parameters
+            .iter()
+            .map(|p| (p, p.get("Offset").and_then(Value::as_u64).map(|o| o as usize)))
+            .collect(),
+        None => store
+            .get("SortedParameterOffsets")
+            .and_then(Value::as_array)
+            .map(|pairs| {
+                pairs
+                    .iter()
+                    .filter_map(|pair| Some((pair.get("Key")?, pair.get("Value").and_then(Value::as_u64).map(|o| o as usize))))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|(variable, offset)| {
+            let name = variable.get("Name").and_then(Value::as_str)?.to_string();
+            let type_name = variable
+                .get("TypeDef")
+                .and_then(|t| t.get("Struct").or_else(|| t.get("ClassStructOrEnum")))
+                .and_then(Value::as_str)
+                .map(|path| path.rsplit(['.', '/']).next().unwrap_or(path).to_string());
+            let default = match (&type_name, offset) {
+                (Some(type_name), Some(offset)) => decode_default(type_name, parameter_data.get(offset..).unwrap_or_default()),
+                _ => None,
+            };
+            Some(UserParameter { name, type_name, default })
+        })
+        .collect()
+}
+
+fn decode_default(type_name: &str, bytes: &[u8]) -> Option<Value> {
+    let floats = |count: usize| -> Option<Value> {
+        let values: Vec<f32> = bytes
+            .chunks_exact(4)
+            .take(count)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        (values.len() == count).then(|| serde_json::json!(values))
+    };
+    let word = bytes.get(..4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    match type_name {
+        "NiagaraFloat" => floats(1).and_then(|v| v.get(0).cloned()),
+        "NiagaraInt32" => word.map(Value::from),
+        "NiagaraBool" => word.map(|w| Value::Bool(w != 0)),
+        "Vector2f" | "Vector2D" => floats(2),
+        "Vector3f" | "Vector" | "NiagaraPosition" => floats(3),
+        "Vector4f" | "Vector4" | "Quat4f" | "Quat" | "LinearColor" => floats(4),
+        _ => None,
+    }
+}
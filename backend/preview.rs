@@ -1,36 +1,310 @@
 use serde::{Deserialize, Serialize};
+use anyhow::Context;
 use base64::{Engine as _, engine::general_purpose};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::anim_parser;
+use crate::asset_path::normalize_asset_path;
+use crate::asset_type::AssetType;
+use crate::blueprint_parser;
+use crate::level_parser;
+use crate::localization_parser;
+use crate::material_parser;
+use crate::mesh_parser::{self, BoundingBox, SkeletalLod};
+use crate::pak_parser::OpenPak;
+use crate::particle_parser;
+use crate::preview_cache::{self, CacheKey};
+use crate::resource_limit;
+use crate::table_parser;
+use crate::texture_decode;
+use crate::texture_parser::{self, MipPayload, TextureMip};
+use crate::ui_parser;
+use crate::uasset_parser::Package;
 
 /// Represents an asset in the system
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Asset {
+    /// Stable id from the container and entry path (`asset_id`); unique within a scan
+    #[serde(default)]
+    pub id: String,
+    /// Display name: the file stem without its naming-convention prefix, in title case
     pub name: String,
-    pub asset_type: String,
+    /// The file name as stored, extension included ("SM_Rock.uasset")
+    #[serde(default)]
+    pub file_name: String,
+    /// Snake_case kind ("texture", "static_mesh", "curve_table", ...)
+    pub asset_type: AssetType,
     pub size: u64,
     pub path: String,
     pub last_modified: DateTime<Utc>,
     pub metadata: Option<serde_json::Value>,
     // Additional fields for pak file information
-    pub pak_file: Option<String>,
+    /// Interned, like `compression_method`: the assets of a pak share one copy
+    #[serde(default, deserialize_with = "crate::intern::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub pak_file: Option<Arc<str>>,
     pub compressed_size: Option<u64>,
-    pub compression_method: Option<String>,
+    #[serde(default, deserialize_with = "crate::intern::deserialize_option")]
+    #[schema(value_type = Option<String>)]
+    pub compression_method: Option<Arc<str>>,
     pub is_encrypted: Option<bool>,
-    pub hash: Option<Vec<u8>>,
+    /// SHA-1 from the pak index, as 40 lowercase hex digits
+    pub hash: Option<String>,
+    /// Whether `asset_type` came from the package's export class or a path guess
+    #[serde(default)]
+    pub type_confidence: TypeConfidence,
+    /// Entries of a package split into `.uasset`/`.umap`, `.uexp`, `.ubulk` and `.uptnl`,
+    /// the header first; `size` and `compressed_size` are their sums. Empty for files
+    /// that aren't packages.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<AssetPart>,
+    /// Chunk of the asset's pak when it's named `pakchunkN-Platform` (`pak_parser::chunk_id`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_id: Option<u32>,
+    /// Tags of the package in the cooked asset registry (PrimaryAssetType, NativeParentClass,
+    /// RowStructure, ...); listings keep only `Settings::listed_tags` of them
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
+}
+
+/// One entry of a package split over several files
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AssetPart {
+    /// As stored, without the dot ("uexp")
+    pub extension: String,
+    pub size: u64,
+    pub compressed_size: Option<u64>,
+}
+
+impl AssetPart {
+    /// The part an asset listed for a single entry stands for
+    pub fn of(asset: &Asset) -> Self {
+        let extension = asset.path.rsplit_once('.').map(|(_, extension)| extension).unwrap_or_default();
+        AssetPart { extension: extension.to_string(), size: asset.size, compressed_size: asset.compressed_size }
+    }
+}
+
+impl Asset {
+    /// Paths of the entries the asset is made of: its parts, or just its own path
+    pub fn part_paths(&self) -> Vec<String> {
+        let Some((stem, _)) = self.path.rsplit_once('.').filter(|_| !self.parts.is_empty()) else {
+            return vec![self.path.clone()];
+        };
+        self.parts.iter().map(|part| format!("{}.{}", stem, part.extension)).collect()
+    }
+
+    /// Whether the asset or one of its parts has this extension (without the dot)
+    pub fn has_extension(&self, extension: &str) -> bool {
+        if self.parts.is_empty() {
+            let file_name = self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path);
+            return file_name.rsplit_once('.').is_some_and(|(_, ext)| ext.eq_ignore_ascii_case(extension));
+        }
+        self.parts.iter().any(|part| part.extension.eq_ignore_ascii_case(extension))
+    }
+
+    /// The asset as listings send it, with only the tags named in `listed`
+    ///
+    /// Shares the asset when it has no other tags.
+    pub fn with_listed_tags(self: &Arc<Self>, listed: &[String]) -> Arc<Self> {
+        let is_listed = |key: &String| listed.iter().any(|listed| listed.eq_ignore_ascii_case(key));
+        if self.tags.keys().all(is_listed) {
+            return self.clone();
+        }
+        let mut asset = Asset::clone(self);
+        asset.tags.retain(|key, _| is_listed(key));
+        Arc::new(asset)
+    }
+}
+
+/// Hex digits kept of the SHA-256 behind an asset id
+const ASSET_ID_LEN: usize = 16;
+
+/// Id of the entry at `path` in `pak_file`: the first hex digits of a SHA-256 of both
+///
+/// The same entry of the same container gets the same id on every scan. `AssetIndex`
+/// replaces the rare id that collides with an earlier asset's.
+pub fn asset_id(pak_file: Option<&str>, path: &str) -> String {
+    salted_asset_id(pak_file, path, 0)
+}
+
+/// `asset_id` with a counter mixed in, to move a colliding id elsewhere
+fn salted_asset_id(pak_file: Option<&str>, path: &str, salt: u32) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(pak_file.unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(path.as_bytes());
+    if salt > 0 {
+        hasher.update([0]);
+        hasher.update(salt.to_le_bytes());
+    }
+    let digest = hasher.finalize();
+    crate::pak_parser::to_hex(&digest[..ASSET_ID_LEN / 2])
+}
+
+/// `assets`, each moved behind an `Arc`
+///
+/// Built in a new vector: collecting in place would keep the assets' whole allocation
+/// alive to hold pointers a fraction of their size.
+pub fn share_assets(assets: Vec<Asset>) -> Vec<Arc<Asset>> {
+    let mut shared = Vec::with_capacity(assets.len());
+    shared.extend(assets.into_iter().map(Arc::new));
+    shared
+}
+
+/// Scanned assets with lookups by id and by exact path, and their folder tree
+///
+/// Derefs to the asset slice, so it can stand in wherever a listing is expected.
+#[derive(Debug, Clone, Default)]
+pub struct AssetIndex {
+    /// Shared with the mounts and handed out to responses without copying
+    assets: Vec<Arc<Asset>>,
+    /// Position in `assets` of each id
+    by_id: HashMap<String, usize>,
+    /// Positions in `assets` of each normalized path, parts' paths included; several when
+    /// the path is in more than one pak
+    by_path: HashMap<String, Vec<usize>>,
+    tree: crate::asset_tree::AssetTree,
+}
+
+impl AssetIndex {
+    /// Indexes `assets`, giving ids to those without and fresh ids to those whose id an
+    /// earlier asset already has
+    pub fn new(assets: Vec<Asset>) -> Self {
+        Self::from_shared(share_assets(assets))
+    }
+
+    /// `new` for assets held elsewhere too; only an asset whose id changes is copied
+    pub fn from_shared(mut assets: Vec<Arc<Asset>>) -> Self {
+        let mut by_id: HashMap<String, usize> = HashMap::with_capacity(assets.len());
+        let mut by_path: HashMap<String, Vec<usize>> = HashMap::with_capacity(assets.len());
+        for (i, asset) in assets.iter_mut().enumerate() {
+            if asset.id.is_empty() {
+                let id = asset_id(asset.pak_file.as_deref(), &asset.path);
+                Arc::make_mut(asset).id = id;
+            }
+            let mut salt = 0;
+            while by_id.contains_key(&asset.id) {
+                salt += 1;
+                let id = salted_asset_id(asset.pak_file.as_deref(), &asset.path, salt);
+                Arc::make_mut(asset).id = id;
+            }
+            if salt > 0 {
+                tracing::warn!("Asset id collision for {} in {:?}; using {}", asset.path, asset.pak_file, asset.id);
+            }
+            by_id.insert(asset.id.clone(), i);
+            for path in asset.part_paths() {
+                by_path.entry(normalize_asset_path(&path)).or_default().push(i);
+            }
+        }
+        let tree = crate::asset_tree::AssetTree::new(&assets);
+        AssetIndex { assets, by_id, by_path, tree }
+    }
+
+    /// The folder at `root` with its subfolders and files `depth` levels down, None when
+    /// no asset is under it; see `AssetTree::listing`
+    pub fn folder(&self, root: &str, depth: u32) -> Option<crate::asset_tree::TreeFolder> {
+        self.tree.listing(&self.assets, root, depth)
+    }
+
+    /// The asset with this id
+    pub fn with_id(&self, id: &str) -> Option<&Arc<Asset>> {
+        self.by_id.get(id).map(|&i| &self.assets[i])
+    }
+
+    /// Assets at this path, or with a part at it, one per pak that has it
+    ///
+    /// Separators, the mount prefix and the case of the root don't matter; see
+    /// `normalize_asset_path`.
+    pub fn with_path<'a>(&'a self, path: &str) -> impl Iterator<Item = &'a Arc<Asset>> + 'a {
+        let positions = self.by_path.get(&normalize_asset_path(path)).map(Vec::as_slice).unwrap_or_default();
+        positions.iter().map(|&i| &self.assets[i])
+    }
+
+    /// `find`, except that an `id` wins over the other keys
+    pub fn lookup(
+        &self,
+        id: Option<&str>,
+        path: Option<&str>,
+        pak_file: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<&Arc<Asset>, utils::AssetLookupError> {
+        match id {
+            Some(id) => self.with_id(id).ok_or(utils::AssetLookupError::NotFound),
+            None => self.find(path, pak_file, name),
+        }
+    }
+
+    /// `utils::find_asset`, with path lookups served from the index
+    pub fn find(
+        &self,
+        path: Option<&str>,
+        pak_file: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<&Arc<Asset>, utils::AssetLookupError> {
+        let Some(path) = path else {
+            return utils::find_asset(&self.assets, None, pak_file, name);
+        };
+
+        let matches: Vec<&Arc<Asset>> = self
+            .with_path(path)
+            .filter(|a| pak_file.is_none() || a.pak_file.as_deref() == pak_file)
+            .collect();
+        match matches.as_slice() {
+            [] => Err(utils::AssetLookupError::NotFound),
+            [asset] => Ok(asset),
+            many => Err(utils::AssetLookupError::Ambiguous {
+                paks: many.iter().filter_map(|a| a.pak_file.as_deref().map(str::to_string)).collect(),
+            }),
+        }
+    }
+
+    /// Paths in the lookup table: the assets' and their parts', less those in several paks
+    pub fn distinct_paths(&self) -> usize {
+        self.by_path.len()
+    }
+}
+
+impl std::ops::Deref for AssetIndex {
+    type Target = [Arc<Asset>];
+
+    fn deref(&self) -> &[Arc<Asset>] {
+        &self.assets
+    }
+}
+
+/// How `Asset::asset_type` was determined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeConfidence {
+    /// Read from the primary export's class in the package header
+    Class,
+    /// Guessed from the file extension and folder/prefix naming
+    #[default]
+    Heuristic,
 }
 
 /// Response structure for preview data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct PreviewResponse {
     pub asset_name: String,
     pub preview_type: PreviewType,
     pub data: PreviewData,
     pub metadata: Option<serde_json::Value>,
     pub generated_at: DateTime<Utc>,
+    /// Caller-supplied (or generated) id used to correlate and cancel preview requests
+    #[serde(default)]
+    pub request_id: Option<String>,
 }
 
 /// Types of previews that can be generated
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "type")]
 pub enum PreviewType {
     #[serde(rename = "image")]
@@ -40,13 +314,37 @@ pub enum PreviewType {
     #[serde(rename = "text")]
     Text { encoding: String, lines: u32 },
     #[serde(rename = "model")]
-    Model { vertices: u32, triangles: u32, materials: Vec<String> },
+    Model {
+        vertices: u32,
+        triangles: u32,
+        materials: Vec<String>,
+        #[serde(default)]
+        bounding_box: Option<BoundingBox>,
+    },
+    #[serde(rename = "skeletal")]
+    Skeletal { bones: u32, lods: Vec<SkeletalLod> },
+    #[serde(rename = "material")]
+    Material { parent: Option<String>, parameters: u32, textures: u32 },
+    #[serde(rename = "blueprint")]
+    Blueprint { parent_class: Option<String>, components: u32, interfaces: u32 },
+    #[serde(rename = "data_table")]
+    DataTable { row_struct: Option<String>, rows: u32 },
+    #[serde(rename = "localization")]
+    Localization { entries: u32, namespaces: u32 },
+    #[serde(rename = "level")]
+    Level { actors: u32, classes: u32 },
+    #[serde(rename = "font")]
+    Font { family: Option<String>, style: Option<String>, format: Option<String> },
+    #[serde(rename = "animation")]
+    Animation { duration: f32, frames: u32, frame_rate: f32, skeleton: Option<String> },
+    #[serde(rename = "particle_system")]
+    ParticleSystem { emitters: u32, user_parameters: u32 },
     #[serde(rename = "unsupported")]
     Unsupported { reason: String },
 }
 
 /// Preview data variants
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "format")]
 pub enum PreviewData {
     #[serde(rename = "base64")]
@@ -57,12 +355,401 @@ pub enum PreviewData {
     Text { content: String },
     #[serde(rename = "url")]
     Url { url: String },
+    /// Preview bytes written to a local file, for the Tauri asset protocol
+    #[serde(rename = "file")]
+    File { path: String, mime_type: String },
+}
+
+/// Caller-supplied options that tune how a preview is generated
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(default)]
+pub struct PreviewOptions {
+    /// Number of waveform buckets for audio previews
+    pub points: Option<usize>,
+    /// Which channel(s) the audio waveform is computed from
+    pub channel_mode: Option<ChannelMode>,
+    /// Meshes above this many triangles are decimated or reduced to their bounds
+    pub triangle_budget: Option<u32>,
+    /// Case-insensitive filter applied to localization keys and values
+    pub search: Option<String>,
+    /// Maximum actor names listed per class in level previews
+    pub actor_limit: Option<usize>,
+    /// Maximum lines returned by text previews
+    pub lines: Option<u32>,
+    /// Requested image size in pixels (longest edge); embedded thumbnails are used up to their own size
+    pub size: Option<u32>,
+    /// Generation timeout in milliseconds (not part of the cache key)
+    #[serde(skip_serializing)]
+    pub timeout_ms: Option<u64>,
+    /// Return binary data as a link to `GET /preview/raw` instead of inline Base64 (not part of the cache key)
+    #[serde(skip_serializing)]
+    pub as_url: Option<bool>,
+}
+
+/// Channel selection for audio waveform generation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelMode {
+    #[default]
+    Mixdown,
+    Left,
+    Right,
+    PerChannel,
+}
+
+/// Decoded PCM audio with interleaved samples normalized to [-1.0, 1.0]
+#[derive(Debug, Clone)]
+pub struct DecodedAudio {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub samples: Vec<f32>,
+}
+
+/// Default number of waveform buckets when the caller doesn't specify one
+pub const DEFAULT_WAVEFORM_POINTS: usize = 128;
+
+/// Upper bound on requested waveform buckets to keep payloads reasonable
+pub const MAX_WAVEFORM_POINTS: usize = 8192;
+
+/// Default triangle budget for mesh previews
+pub const DEFAULT_TRIANGLE_BUDGET: u32 = 200_000;
+
+/// Default number of actor names listed per class in level previews
+pub const DEFAULT_ACTOR_LIMIT: usize = 50;
+
+/// Default number of lines returned by text previews
+pub const DEFAULT_TEXT_LINES: u32 = 200;
+
+/// Bytes shown when a text entry turns out to be binary
+const HEX_DUMP_BYTES: usize = 1024;
+
+/// Default time a preview may take before it is abandoned
+pub const DEFAULT_PREVIEW_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Stage of preview generation, reported when a preview times out or is cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewPhase {
+    Queued,
+    Extraction,
+    Decode,
+    Encode,
+}
+
+impl std::fmt::Display for PreviewPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let phase = match self {
+            PreviewPhase::Queued => "queued",
+            PreviewPhase::Extraction => "extraction",
+            PreviewPhase::Decode => "decode",
+            PreviewPhase::Encode => "encode",
+        };
+        f.write_str(phase)
+    }
+}
+
+/// Cancellation flag and current phase of one in-flight preview
+#[derive(Clone, Default)]
+pub struct PreviewCancellation {
+    inner: Arc<CancellationInner>,
+}
+
+#[derive(Default)]
+struct CancellationInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+    phase: std::sync::Mutex<Option<PreviewPhase>>,
+}
+
+impl PreviewCancellation {
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn phase(&self) -> PreviewPhase {
+        self.inner.phase.lock().unwrap().unwrap_or(PreviewPhase::Queued)
+    }
+
+    async fn cancelled(&self) {
+        let notified = self.inner.notify.notified();
+        if !self.is_cancelled() {
+            notified.await;
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_PREVIEW: PreviewCancellation;
+    /// Paks opened once for a whole preview batch, by pak path
+    static OPEN_PAKS: Arc<HashMap<Arc<str>, Arc<OpenPak>>>;
+}
+
+/// Previews currently running, by request id
+static RUNNING_PREVIEWS: OnceLock<std::sync::Mutex<HashMap<String, PreviewCancellation>>> = OnceLock::new();
+
+fn running_previews() -> &'static std::sync::Mutex<HashMap<String, PreviewCancellation>> {
+    RUNNING_PREVIEWS.get_or_init(Default::default)
 }
 
-/// Generates preview data for an asset
-pub async fn generate_preview_data(asset: &Asset) -> PreviewResponse {
+/// Records entry into a phase and bails out if the running preview was cancelled
+///
+/// A no-op outside [`run_preview`], so the loaders can be reused by other commands.
+fn checkpoint(phase: PreviewPhase) -> anyhow::Result<()> {
+    CURRENT_PREVIEW
+        .try_with(|token| {
+            *token.inner.phase.lock().unwrap() = Some(phase);
+            if token.is_cancelled() {
+                anyhow::bail!("Preview cancelled before {}", phase);
+            }
+            Ok(())
+        })
+        .unwrap_or(Ok(()))
+}
+
+/// Cancels a running preview; returns false if no preview with that id is running
+pub fn cancel_preview(request_id: &str) -> bool {
+    match running_previews().lock().unwrap().get(request_id) {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Generates a preview under a timeout, cancellable through [`cancel_preview`]
+///
+/// Timeouts and cancellations produce an Unsupported preview naming the phase that was
+/// running instead of leaving the caller waiting. The response carries the request id.
+pub async fn run_preview(asset: &Asset, options: &PreviewOptions, request_id: Option<String>) -> PreviewResponse {
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let timeout = options.timeout_ms.map(Duration::from_millis).unwrap_or(DEFAULT_PREVIEW_TIMEOUT);
+
+    let token = PreviewCancellation::default();
+    running_previews().lock().unwrap().insert(request_id.clone(), token.clone());
+
+    let generation = CURRENT_PREVIEW.scope(token.clone(), generate_preview_data(asset, options));
+    let mut response = tokio::select! {
+        result = tokio::time::timeout(timeout, generation) => match result {
+            Ok(_) if token.is_cancelled() => {
+                unsupported_preview(asset, format!("Preview cancelled during {}", token.phase()))
+            }
+            Ok(response) => response,
+            Err(_) => {
+                tracing::warn!("Preview of {} timed out during {}", asset.path, token.phase());
+                unsupported_preview(
+                    asset,
+                    format!("Preview timed out after {:.1}s during {}", timeout.as_secs_f32(), token.phase()),
+                )
+            }
+        },
+        _ = token.cancelled() => unsupported_preview(asset, format!("Preview cancelled during {}", token.phase())),
+    };
+
+    running_previews().lock().unwrap().remove(&request_id);
+    response.request_id = Some(request_id);
+    response
+}
+
+/// Default number of previews generated at once by a batch
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Upper bound on batch concurrency
+pub const MAX_BATCH_CONCURRENCY: usize = 16;
+
+/// Outcome of one item in a preview batch; failures never fail the whole batch
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchPreviewResult {
+    pub path: String,
+    pub preview: Option<PreviewResponse>,
+    pub error: Option<String>,
+}
+
+/// Generates previews for many assets concurrently, opening each pak only once
+///
+/// `items` pairs each requested path with its resolved asset or the lookup error.
+/// Results keep the request order.
+pub async fn run_preview_batch(
+    items: Vec<(String, Result<Arc<Asset>, String>)>,
+    options: &PreviewOptions,
+    concurrency: Option<usize>,
+) -> Vec<BatchPreviewResult> {
+    let concurrency = concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY).clamp(1, MAX_BATCH_CONCURRENCY);
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
+
+    let mut paks = HashMap::new();
+    for pak_path in items.iter().filter_map(|(_, asset)| asset.as_ref().ok()?.pak_file.clone()) {
+        if paks.contains_key(&pak_path) {
+            continue;
+        }
+        match OpenPak::open(&*pak_path).await {
+            Ok(pak) => {
+                paks.insert(pak_path, Arc::new(pak));
+            }
+            Err(e) => tracing::warn!("Failed to open {} for batch preview: {:#}", pak_path, e),
+        }
+    }
+    let paks = Arc::new(paks);
+
+    let paths: Vec<String> = items.iter().map(|(path, _)| path.clone()).collect();
+    let mut results: Vec<Option<BatchPreviewResult>> = vec![None; items.len()];
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, (path, asset)) in items.into_iter().enumerate() {
+        let asset = match asset {
+            Ok(asset) => asset,
+            Err(error) => {
+                results[index] = Some(BatchPreviewResult { path, preview: None, error: Some(error) });
+                continue;
+            }
+        };
+
+        let semaphore = semaphore.clone();
+        let paks = paks.clone();
+        let options = options.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let preview = OPEN_PAKS.scope(paks, run_preview(&asset, &options, None)).await;
+            let result = match &preview.preview_type {
+                PreviewType::Unsupported { reason } => {
+                    BatchPreviewResult { path, error: Some(reason.clone()), preview: None }
+                }
+                _ => BatchPreviewResult { path, preview: Some(preview), error: None },
+            };
+            (index, result)
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => results[index] = Some(result),
+            Err(e) => tracing::error!("Batch preview task failed: {}", e),
+        }
+    }
+
+    results
+        .into_iter()
+        .zip(paths)
+        .map(|(result, path)| {
+            result.unwrap_or_else(|| BatchPreviewResult {
+                path,
+                preview: None,
+                error: Some("Preview task failed".to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Generates preview data for an asset, serving it from the preview cache when possible
+pub async fn generate_preview_data(asset: &Asset, options: &PreviewOptions) -> PreviewResponse {
+    let cache_key = CacheKey::for_asset(asset, options);
+    if let Some(key) = &cache_key {
+        if let Some(cached) = preview_cache::global().get(key).await {
+            tracing::debug!("Preview cache hit for {}", asset.path);
+            return cached;
+        }
+    }
+
+    // One lease for the whole preview, sized by the uncompressed asset, so a grid of
+    // previews waits its turn instead of decompressing everything at once
+    let response = resource_limit::global().run(asset.size, generate_uncached_preview(asset, options)).await;
+
+    if let Some(key) = &cache_key {
+        if let Err(e) = preview_cache::global().put(key, &response).await {
+            tracing::warn!("Failed to cache preview for {}: {:#}", asset.path, e);
+        }
+    }
+    response
+}
+
+async fn generate_uncached_preview(asset: &Asset, options: &PreviewOptions) -> PreviewResponse {
     let preview_type = determine_preview_type(asset);
-    let data = generate_preview_content(asset, &preview_type).await;
+
+    match preview_type {
+        PreviewType::Image { .. } if utils::is_slate_brush(&asset.asset_type) => {
+            return generate_slate_brush_preview(asset, options)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read Slate brush: {:#}", e)));
+        }
+        PreviewType::Image { .. } => {
+            match generate_thumbnail_preview(asset, options).await {
+                Ok(Some(response)) => return response,
+                Ok(None) => {}
+                Err(e) => tracing::debug!("No embedded thumbnail for {}: {:#}", asset.name, e),
+            }
+            let (preview_type, data, source) = texture_image(asset, options).await;
+            return PreviewResponse {
+                asset_name: asset.name.clone(),
+                preview_type,
+                data,
+                metadata: with_source(asset.metadata.clone(), source),
+                generated_at: Utc::now(),
+                request_id: None,
+            };
+        }
+        PreviewType::Animation { .. } => {
+            return generate_animation_preview(asset)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read animation: {:#}", e)));
+        }
+        PreviewType::ParticleSystem { .. } => {
+            return generate_particle_system_preview(asset)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read particle system: {:#}", e)));
+        }
+        PreviewType::Font { .. } => {
+            return generate_font_preview(asset)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read font: {:#}", e)));
+        }
+        PreviewType::Model { .. } => match generate_static_mesh_preview(asset, options).await {
+            Ok(response) => return response,
+            Err(e) => tracing::warn!("Falling back to placeholder mesh preview for {}: {:#}", asset.name, e),
+        },
+        PreviewType::Skeletal { .. } => {
+            return generate_skeletal_mesh_preview(asset)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read skeletal mesh: {:#}", e)));
+        }
+        PreviewType::Material { .. } => {
+            return generate_material_preview(asset)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read material: {:#}", e)));
+        }
+        PreviewType::Blueprint { .. } => {
+            return generate_blueprint_preview(asset)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read blueprint: {:#}", e)));
+        }
+        PreviewType::DataTable { .. } => {
+            return generate_table_preview(asset)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read table: {:#}", e)));
+        }
+        PreviewType::Localization { .. } => {
+            return generate_localization_preview(asset, options)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read localization data: {:#}", e)));
+        }
+        PreviewType::Text { .. } => {
+            return generate_text_file_preview(asset, options)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read text content: {:#}", e)));
+        }
+        PreviewType::Level { .. } => {
+            return generate_level_preview(asset, options)
+                .await
+                .unwrap_or_else(|e| unsupported_preview(asset, format!("Failed to read level: {:#}", e)));
+        }
+        _ => {}
+    }
+
+    let data = generate_preview_content(asset, &preview_type, options).await;
     
     PreviewResponse {
         asset_name: asset.name.clone(),
@@ -70,23 +757,826 @@ pub async fn generate_preview_data(asset: &Asset) -> PreviewResponse {
         data,
         metadata: asset.metadata.clone(),
         generated_at: Utc::now(),
+        request_id: None,
+    }
+}
+
+/// Returns the package's embedded editor thumbnail when it covers the requested size
+///
+/// Far cheaper than decoding the texture. None when there is no thumbnail or
+/// `options.size` is larger than it, so the caller falls back to a full decode.
+async fn generate_thumbnail_preview(asset: &Asset, options: &PreviewOptions) -> anyhow::Result<Option<PreviewResponse>> {
+    let data = load_package_bytes(asset).await?;
+    let package = Package::parse(&data)?;
+    let Some(thumbnail) = package.thumbnails(&data)?.into_iter().max_by_key(|t| t.width.max(t.height)) else {
+        return Ok(None);
+    };
+    if options.size.is_some_and(|size| size > thumbnail.width.max(thumbnail.height)) {
+        return Ok(None);
+    }
+    checkpoint(PreviewPhase::Encode)?;
+
+    tracing::info!(
+        "Using embedded thumbnail for: {} ({}x{})",
+        asset.name,
+        thumbnail.width,
+        thumbnail.height
+    );
+
+    let mut metadata = with_source(asset.metadata.clone(), "embedded_thumbnail");
+    if let Some(serde_json::Value::Object(map)) = metadata.as_mut() {
+        map.insert("thumbnail_object".to_string(), serde_json::json!(thumbnail.object_path));
+    }
+
+    Ok(Some(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Image {
+            format: thumbnail.mime.trim_start_matches("image/").to_string(),
+            width: thumbnail.width,
+            height: thumbnail.height,
+        },
+        data: PreviewData::Base64 {
+            content: format!("data:{};base64,{}", thumbnail.mime, general_purpose::STANDARD.encode(&thumbnail.data)),
+        },
+        metadata,
+        generated_at: Utc::now(),
+        request_id: None,
+    }))
+}
+
+/// Longest edge of a decoded texture preview when the request doesn't give a size
+const DEFAULT_TEXTURE_PREVIEW_SIZE: u32 = 512;
+
+/// Decodes a texture into a PNG preview, or falls back to the placeholder image
+///
+/// The placeholder is used when the texture can't be read or its pixel format has no
+/// decoder (BC6H, ASTC, float formats). Also returns the `source` to record for it.
+async fn texture_image(asset: &Asset, options: &PreviewOptions) -> (PreviewType, PreviewData, &'static str) {
+    match decode_texture_png(asset, options.size.unwrap_or(DEFAULT_TEXTURE_PREVIEW_SIZE)).await {
+        Ok((width, height, png)) => (
+            PreviewType::Image { format: "PNG".to_string(), width, height },
+            PreviewData::Base64 {
+                content: format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(&png)),
+            },
+            "decoded",
+        ),
+        Err(e) => {
+            tracing::debug!("Showing a placeholder for texture {}: {:#}", asset.name, e);
+            (
+                PreviewType::Image { format: "SVG".to_string(), width: 512, height: 512 },
+                generate_image_preview(asset, "SVG", 512, 512).await,
+                "placeholder",
+            )
+        }
+    }
+}
+
+/// Decodes the stored mip of a texture nearest `size` (longest edge) and encodes it as PNG
+async fn decode_texture_png(asset: &Asset, size: u32) -> anyhow::Result<(u32, u32, Vec<u8>)> {
+    let package = load_package_bytes(asset).await?;
+    let texture = texture_parser::parse_texture_mips(&package)?;
+    let (index, mip) = preview_mip(&texture.mips, size).context("Texture has no mip data")?;
+    let data = match mip.payload {
+        MipPayload::Package { offset, size } => byte_range(&package, offset, size),
+        MipPayload::Sibling { extension, offset, size } => {
+            let sibling = load_sibling_file(asset, extension)
+                .await
+                .with_context(|| format!("Mip {} is stored in a .{} file that can't be read", index, extension))?;
+            byte_range(&sibling, offset, size)
+        }
+        MipPayload::Missing => None,
     }
+    .with_context(|| format!("Mip {} lies outside its file", index))?;
+    drop(package);
+    checkpoint(PreviewPhase::Encode)?;
+
+    let (width, height) = (mip.width, mip.height);
+    let pixel_format = texture.info.pixel_format.clone();
+    let png = tokio::task::spawn_blocking(move || encode_mip_png(&pixel_format, width, height, &data)).await??;
+    Ok((width, height, png))
+}
+
+/// The largest stored mip no bigger than `size` on its longest edge, else the smallest stored one
+fn preview_mip(mips: &[TextureMip], size: u32) -> Option<(usize, &TextureMip)> {
+    let stored: Vec<_> = mips.iter().enumerate().filter(|(_, mip)| mip.payload != MipPayload::Missing).collect();
+    stored
+        .iter()
+        .find(|(_, mip)| mip.width.max(mip.height) <= size)
+        .or(stored.last())
+        .copied()
+}
+
+/// Copies bytes `offset..offset + size` of `data`; both come from the file, so their sum may not fit
+fn byte_range(data: &[u8], offset: u64, size: u64) -> Option<Vec<u8>> {
+    let end = offset.checked_add(size)?;
+    data.get(usize::try_from(offset).ok()?..usize::try_from(end).ok()?).map(<[u8]>::to_vec)
+}
+
+/// Decodes one mip to PNG; fails for pixel formats without a decoder
+fn encode_mip_png(pixel_format: &str, width: u32, height: u32, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let image = texture_decode::decode(pixel_format, width, height, data)?
+        .with_context(|| format!("No decoder for {}", pixel_format))?;
+    texture_decode::encode_png(&image)
+}
+
+/// Records where an image preview came from ("embedded_thumbnail", "decoded" or "placeholder") in its metadata
+fn with_source(metadata: Option<serde_json::Value>, source: &str) -> Option<serde_json::Value> {
+    match metadata {
+        Some(serde_json::Value::Object(mut map)) => {
+            map.insert("source".to_string(), serde_json::json!(source));
+            Some(serde_json::Value::Object(map))
+        }
+        Some(other) => Some(other),
+        None => Some(serde_json::json!({ "source": source })),
+    }
+}
+
+/// Builds an Unsupported preview response carrying the given reason
+fn unsupported_preview(asset: &Asset, reason: String) -> PreviewResponse {
+    let data = PreviewData::Json {
+        content: serde_json::json!({
+            "error": reason,
+            "asset_type": asset.asset_type,
+            "suggested_action": "Use external viewer or convert to supported format"
+        }),
+    };
+
+    PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Unsupported { reason },
+        data,
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    }
+}
+
+/// Extracts the package bytes for an asset from its pak (.uasset followed by its .uexp)
+///
+/// Assets without a pak are read as loose cooked files from disk.
+pub async fn load_package_bytes(asset: &Asset) -> anyhow::Result<Vec<u8>> {
+    checkpoint(PreviewPhase::Extraction)?;
+    let data = extract_package_bytes(asset).await?;
+    checkpoint(PreviewPhase::Decode)?;
+    Ok(data)
+}
+
+async fn extract_package_bytes(asset: &Asset) -> anyhow::Result<Vec<u8>> {
+    let Some(pak_path) = asset.pak_file.as_deref() else {
+        return load_loose_package_bytes(&asset.path).await;
+    };
+    open_pak(pak_path).await?.extract_package(&asset.path).await
+}
+
+/// Returns the batch's shared handle for a pak, or opens it for this preview alone
+async fn open_pak(pak_path: &str) -> anyhow::Result<Arc<OpenPak>> {
+    let shared = OPEN_PAKS.try_with(|paks| paks.get(pak_path).cloned()).ok().flatten();
+    match shared {
+        Some(pak) => Ok(pak),
+        None => Ok(Arc::new(OpenPak::open(pak_path).await?)),
+    }
+}
+
+/// Reads a loose cooked package from disk, appending its sibling .uexp when present
+async fn load_loose_package_bytes(path: &str) -> anyhow::Result<Vec<u8>> {
+    let mut data = tokio::fs::read(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Asset {} is not in a pak and could not be read from disk: {}", path, e))?;
+
+    let uexp_path = std::path::Path::new(path).with_extension("uexp");
+    if let Ok(uexp) = tokio::fs::read(&uexp_path).await {
+        data.extend(uexp);
+    }
+
+    Ok(data)
+}
+
+/// Summarizes a SkeletalMesh: bones, LODs, morph targets, materials and its Skeleton
+async fn generate_skeletal_mesh_preview(asset: &Asset) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let info = mesh_parser::parse_skeletal_mesh(&data)?;
+    checkpoint(PreviewPhase::Encode)?;
+
+    tracing::info!(
+        "Generating skeletal mesh preview for: {} ({} bones, {} LODs)",
+        asset.name,
+        info.bones.len(),
+        info.lods.len()
+    );
+
+    // LOD0 geometry is exported without skinning, purely for a static look at the mesh
+    let lod0_gltf = match &info.lod0_geometry {
+        Some(geometry) => mesh_parser::export_glb(geometry, &asset.name)
+            .map(|glb| format!("data:model/gltf-binary;base64,{}", general_purpose::STANDARD.encode(glb)))
+            .ok(),
+        None => None,
+    };
+
+    let content = serde_json::json!({
+        "type": "skeletal_mesh_preview",
+        "asset_name": asset.name,
+        "skeleton": info.skeleton,
+        "bone_count": info.bones.len(),
+        "bones": info.bones,
+        "lod_count": info.lods.len(),
+        "lods": info.lods,
+        "morph_targets": info.morph_targets,
+        "materials": info.materials,
+        "lod0_gltf": lod0_gltf,
+    });
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Skeletal {
+            bones: info.bones.len() as u32,
+            lods: info.lods,
+        },
+        data: PreviewData::Json { content },
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Summarizes a Material or MaterialInstance: parent, parameters, blend/shading and textures
+async fn generate_material_preview(asset: &Asset) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let info = material_parser::parse_material(&data)?;
+    checkpoint(PreviewPhase::Encode)?;
+
+    let parameters = info.scalar_parameters.len()
+        + info.vector_parameters.len()
+        + info.texture_parameters.len()
+        + info.static_switches.len();
+
+    tracing::info!(
+        "Generating material preview for: {} ({} parameters, {} textures)",
+        asset.name,
+        parameters,
+        info.texture_references.len()
+    );
+
+    let content = serde_json::json!({
+        "type": "material_preview",
+        "asset_name": asset.name,
+        "class": info.class,
+        "parent": info.parent,
+        "blend_mode": info.blend_mode,
+        "shading_model": info.shading_model,
+        "scalar_parameters": info.scalar_parameters,
+        "vector_parameters": info.vector_parameters,
+        "texture_parameters": info.texture_parameters,
+        "static_switches": info.static_switches,
+        "texture_references": info.texture_references,
+    });
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Material {
+            parent: info.parent,
+            parameters: parameters as u32,
+            textures: info.texture_references.len() as u32,
+        },
+        data: PreviewData::Json { content },
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Summarizes a Blueprint: parent class, components, interfaces and everything it imports
+async fn generate_blueprint_preview(asset: &Asset) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let info = blueprint_parser::parse_blueprint(&data)?;
+    checkpoint(PreviewPhase::Encode)?;
+
+    tracing::info!(
+        "Generating blueprint preview for: {} ({} components, {} references)",
+        asset.name,
+        info.components.len(),
+        info.references.len()
+    );
+
+    let content = serde_json::json!({
+        "type": "blueprint_preview",
+        "asset_name": asset.name,
+        "generated_class": info.generated_class,
+        "class_type": info.class_type,
+        "parent_class": info.parent_class,
+        "components": info.components,
+        "interfaces": info.interfaces,
+        "functions": info.functions,
+        "references": info.references,
+    });
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Blueprint {
+            parent_class: info.parent_class,
+            components: info.components.len() as u32,
+            interfaces: info.interfaces.len() as u32,
+        },
+        data: PreviewData::Json { content },
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Lists the rows of a DataTable or CurveTable
+async fn generate_table_preview(asset: &Asset) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let table = table_parser::parse_table(&data)?;
+    checkpoint(PreviewPhase::Encode)?;
+
+    tracing::info!("Generating table preview for: {} ({} rows)", asset.name, table.rows.len());
+
+    let content = serde_json::json!({
+        "type": "data_table_preview",
+        "asset_name": asset.name,
+        "class": table.class,
+        "row_struct": table.row_struct,
+        "row_count": table.rows.len(),
+        "rows": table.rows,
+    });
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::DataTable {
+            rows: table.rows.len() as u32,
+            row_struct: table.row_struct,
+        },
+        data: PreviewData::Json { content },
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Lists namespace/key/value triples from a .locres file or StringTable asset
+async fn generate_localization_preview(asset: &Asset, options: &PreviewOptions) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let mut table = if asset.path.to_lowercase().ends_with(".locres") {
+        localization_parser::parse_locres(&data)?
+    } else {
+        localization_parser::parse_string_table(&data)?
+    };
+    checkpoint(PreviewPhase::Encode)?;
+
+    let total_entries = table.entries.len();
+    if let Some(search) = options.search.as_deref().filter(|s| !s.is_empty()) {
+        table.filter(search);
+    }
+
+    tracing::info!(
+        "Generating localization preview for: {} ({} of {} entries)",
+        asset.name,
+        table.entries.len(),
+        total_entries
+    );
+
+    let namespaces = table.namespace_count();
+    let content = serde_json::json!({
+        "type": "localization_preview",
+        "asset_name": asset.name,
+        "source": table.source,
+        "version": table.version,
+        "search": options.search,
+        "total_entries": total_entries,
+        "matched_entries": table.entries.len(),
+        "entries": table.entries,
+    });
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Localization {
+            entries: total_entries as u32,
+            namespaces: namespaces as u32,
+        },
+        data: PreviewData::Json { content },
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Summarizes a level: actors by class, streaming sublevels, partition cells and imports
+async fn generate_level_preview(asset: &Asset, options: &PreviewOptions) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let actor_limit = options.actor_limit.unwrap_or(DEFAULT_ACTOR_LIMIT);
+    let level = level_parser::parse_level(&data, actor_limit)?;
+    checkpoint(PreviewPhase::Encode)?;
+
+    tracing::info!(
+        "Generating level preview for: {} ({} actors, {} classes)",
+        asset.name,
+        level.total_actors,
+        level.actor_classes.len()
+    );
+
+    let content = serde_json::json!({
+        "type": "level_preview",
+        "asset_name": asset.name,
+        "world": level.world,
+        "total_actors": level.total_actors,
+        "actor_class_count": level.actor_classes.len(),
+        "actor_limit": actor_limit,
+        "actor_classes": level.actor_classes,
+        "streaming_levels": level.streaming_levels,
+        "world_partition_cells": level.world_partition_cells,
+        "referenced_packages": level.referenced_packages,
+    });
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Level {
+            actors: level.total_actors as u32,
+            classes: level.actor_classes.len() as u32,
+        },
+        data: PreviewData::Json { content },
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Summarizes an AnimSequence: length, frames, frame rate, skeleton and curves
+async fn generate_animation_preview(asset: &Asset) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let info = anim_parser::parse_animation(&data)?;
+    checkpoint(PreviewPhase::Encode)?;
+
+    tracing::info!(
+        "Generating animation preview for: {} ({:?}s, {:?} frames)",
+        asset.name,
+        info.duration,
+        info.frames
+    );
+
+    let content = serde_json::json!({
+        "type": "animation_preview",
+        "asset_name": asset.name,
+        "class": info.class,
+        "duration": info.duration,
+        "frames": info.frames,
+        "frame_rate": info.frame_rate,
+        "rate_scale": info.rate_scale,
+        "skeleton": info.skeleton,
+        "curves": info.curves,
+        "additive_type": info.additive_type,
+        "bone_compression": info.bone_compression,
+        "curve_compression": info.curve_compression,
+        "raw_data_stripped": info.raw_data_stripped,
+    });
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Animation {
+            duration: info.duration.unwrap_or_default() as f32,
+            frames: info.frames.unwrap_or_default(),
+            frame_rate: info.frame_rate.unwrap_or_default() as f32,
+            skeleton: info.skeleton,
+        },
+        data: PreviewData::Json { content },
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Summarizes a Niagara or Cascade system: emitters, materials, textures, user parameters and bounds
+async fn generate_particle_system_preview(asset: &Asset) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let info = particle_parser::parse_particle_system(&data)?;
+    checkpoint(PreviewPhase::Encode)?;
+
+    tracing::info!(
+        "Generating particle system preview for: {} ({} emitters)",
+        asset.name,
+        info.emitters.len()
+    );
+
+    let content = serde_json::json!({
+        "type": "particle_system_preview",
+        "asset_name": asset.name,
+        "class": info.class,
+        "emitter_count": info.emitters.len(),
+        "emitters": info.emitters,
+        "materials": info.materials,
+        "textures": info.textures,
+        "user_parameters": info.user_parameters,
+        "bounds": info.bounds,
+    });
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::ParticleSystem {
+            emitters: info.emitters.len() as u32,
+            user_parameters: info.user_parameters.len() as u32,
+        },
+        data: PreviewData::Json { content },
+        metadata: asset.metadata.clone(),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Returns the embedded TTF/OTF of a font asset as base64, with family/style metadata
+///
+/// Cooked FontFaces usually keep their data in a sibling `.ufont`; composite Fonts
+/// only reference FontFaces, which are listed instead.
+async fn generate_font_preview(asset: &Asset) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+
+    let font = if ui_parser::is_font_payload(&data) {
+        Some(data.clone())
+    } else if let Some(font) = ui_parser::find_embedded_font(&data) {
+        Some(font.to_vec())
+    } else {
+        load_sibling_file(asset, "ufont").await.filter(|font| ui_parser::is_font_payload(font))
+    };
+
+    let Some(font) = font else {
+        let faces = ui_parser::font_face_references(&data)?;
+        if faces.is_empty() {
+            anyhow::bail!("No embedded font data, .ufont file or FontFace references found");
+        }
+        return Ok(PreviewResponse {
+            asset_name: asset.name.clone(),
+            preview_type: PreviewType::Font { family: None, style: None, format: None },
+            data: PreviewData::Json {
+                content: serde_json::json!({
+                    "type": "font_preview",
+                    "asset_name": asset.name,
+                    "font_faces": faces,
+                }),
+            },
+            metadata: asset.metadata.clone(),
+            generated_at: Utc::now(),
+            request_id: None,
+        });
+    };
+
+    let info = ui_parser::read_font_info(&font)?;
+    checkpoint(PreviewPhase::Encode)?;
+    tracing::info!("Generating font preview for: {} ({:?} {:?})", asset.name, info.family, info.style);
+
+    let mut metadata = asset.metadata.clone().unwrap_or_else(|| serde_json::json!({}));
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert("family".to_string(), serde_json::json!(info.family));
+        object.insert("style".to_string(), serde_json::json!(info.style));
+        object.insert("full_name".to_string(), serde_json::json!(info.full_name));
+        object.insert("font_size_bytes".to_string(), serde_json::json!(font.len()));
+    }
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Font {
+            family: info.family,
+            style: info.style,
+            format: Some(info.format),
+        },
+        data: PreviewData::Base64 {
+            content: format!("data:{};base64,{}", info.mime, general_purpose::STANDARD.encode(&font)),
+        },
+        metadata: Some(metadata),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Shows the texture behind a Slate brush, or the brush settings if the texture isn't in the pak
+async fn generate_slate_brush_preview(asset: &Asset, options: &PreviewOptions) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let brush = ui_parser::parse_slate_brush(&data)?;
+    checkpoint(PreviewPhase::Encode)?;
+
+    let texture = match &brush.resource_object {
+        Some(path) => resolve_object_asset(asset, path).await,
+        None => None,
+    };
+
+    let mut metadata = asset.metadata.clone().unwrap_or_else(|| serde_json::json!({}));
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert("brush".to_string(), serde_json::json!(brush));
+    }
+
+    let (preview_type, data) = match &texture {
+        Some(texture) => {
+            tracing::info!("Previewing Slate brush {} through texture {}", asset.name, texture.path);
+            let (preview_type, data, source) = texture_image(texture, options).await;
+            if let Some(object) = metadata.as_object_mut() {
+                object.insert("source".to_string(), serde_json::json!(source));
+            }
+            (preview_type, data)
+        }
+        None => (
+            PreviewType::Unsupported {
+                reason: "Brush resource is not a texture in this pak".to_string(),
+            },
+            PreviewData::Json {
+                content: serde_json::json!({
+                    "type": "slate_brush_preview",
+                    "asset_name": asset.name,
+                    "brush": brush,
+                }),
+            },
+        ),
+    };
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type,
+        data,
+        metadata: Some(metadata),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Reads the file next to an asset's package with another extension (e.g. `.ufont`, `.ubulk`)
+async fn load_sibling_file(asset: &Asset, extension: &str) -> Option<Vec<u8>> {
+    let sibling = std::path::Path::new(&asset.path).with_extension(extension);
+    let sibling = sibling.to_string_lossy().replace('\\', "/");
+
+    match asset.pak_file.as_deref() {
+        Some(pak_path) => {
+            let pak = open_pak(pak_path).await.ok()?;
+            pak.entry(&sibling)?;
+            pak.extract_file(&sibling).await.ok()
+        }
+        None => tokio::fs::read(&sibling).await.ok(),
+    }
+}
+
+/// Finds the pak entry for an object path ("/Game/UI/T_Icon.T_Icon") in the asset's pak
+pub async fn resolve_object_asset(asset: &Asset, object_path: &str) -> Option<Asset> {
+    let suffix = utils::object_path_to_file_suffix(object_path)?.to_lowercase();
+    let pak = open_pak(asset.pak_file.as_deref()?).await.ok()?;
+    let path = pak
+        .pak()
+        .entries
+        .iter()
+        .map(|entry| entry.filename.clone())
+        .find(|f| f.to_lowercase().ends_with(&suffix))?;
+
+    Some(Asset {
+        id: asset_id(asset.pak_file.as_deref(), &path),
+        name: std::path::Path::new(&path).file_stem()?.to_string_lossy().into_owned(),
+        file_name: std::path::Path::new(&path).file_name()?.to_string_lossy().into_owned(),
+        path,
+        asset_type: AssetType::Texture,
+        size: 0,
+        last_modified: asset.last_modified,
+        metadata: None,
+        pak_file: asset.pak_file.clone(),
+        compressed_size: None,
+        compression_method: None,
+        is_encrypted: None,
+        hash: None,
+        type_confidence: TypeConfidence::Heuristic,
+        parts: Vec::new(),
+        chunk_id: asset.chunk_id,
+        tags: HashMap::new(),
+    })
+}
+
+/// Asset references discovered while building a preview (e.g. textures used by a material)
+pub fn discovered_dependencies(response: &PreviewResponse) -> Vec<String> {
+    match (&response.preview_type, &response.data) {
+        (PreviewType::Material { .. }, PreviewData::Json { content }) => content
+            .get("texture_references")
+            .and_then(|t| t.as_array())
+            .map(|refs| refs.iter().filter_map(|r| r.as_str()).map(|r| r.to_string()).collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Builds a glTF preview from the real LOD0 geometry of a StaticMesh asset
+async fn generate_static_mesh_preview(asset: &Asset, options: &PreviewOptions) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let geometry = mesh_parser::parse_static_mesh(&data)?;
+    checkpoint(PreviewPhase::Encode)?;
+    let bounding_box = geometry.bounding_box();
+    let budget = options.triangle_budget.unwrap_or(DEFAULT_TRIANGLE_BUDGET);
+
+    tracing::info!(
+        "Generating mesh preview for: {} ({} vertices, {} triangles)",
+        asset.name,
+        geometry.vertex_count(),
+        geometry.triangle_count()
+    );
+
+    let reduced = mesh_parser::decimate_by_clustering(&geometry, budget);
+    let decimated = reduced
+        .as_ref()
+        .map(|r| r.triangle_count() < geometry.triangle_count())
+        .unwrap_or(false);
+
+    let data = match &reduced {
+        Some(reduced) => {
+            let glb = mesh_parser::export_glb(reduced, &asset.name)?;
+            PreviewData::Base64 {
+                content: format!("data:model/gltf-binary;base64,{}", general_purpose::STANDARD.encode(glb)),
+            }
+        }
+        None => PreviewData::Json {
+            content: serde_json::json!({
+                "type": "model_preview",
+                "asset_name": asset.name,
+                "bounding_box": bounding_box,
+            }),
+        },
+    };
+
+    let mut metadata = asset.metadata.clone().unwrap_or_else(|| serde_json::json!({}));
+    if let Some(object) = metadata.as_object_mut() {
+        object.insert("source".to_string(), serde_json::json!("decoded"));
+        object.insert("triangle_budget".to_string(), serde_json::json!(budget));
+        object.insert("decimated".to_string(), serde_json::json!(decimated));
+        object.insert("bounding_box_only".to_string(), serde_json::json!(reduced.is_none()));
+        if let Some(reduced) = reduced.as_ref().filter(|_| decimated) {
+            object.insert("preview_vertices".to_string(), serde_json::json!(reduced.vertex_count()));
+            object.insert("preview_triangles".to_string(), serde_json::json!(reduced.triangle_count()));
+        }
+    }
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type: PreviewType::Model {
+            vertices: geometry.vertex_count(),
+            triangles: geometry.triangle_count(),
+            materials: geometry.materials.clone(),
+            bounding_box,
+        },
+        data,
+        metadata: Some(metadata),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
 }
 
 /// Determines the appropriate preview type based on asset type
 fn determine_preview_type(asset: &Asset) -> PreviewType {
-    match asset.asset_type.as_str() {
-        "texture" | "image" => PreviewType::Image {
+    match &asset.asset_type {
+        AssetType::Texture => PreviewType::Image {
             format: "PNG".to_string(),
             width: 512,
             height: 512,
         },
-        "audio" | "sound" => PreviewType::Audio {
+        AssetType::Sound => PreviewType::Audio {
             format: "WAV".to_string(),
             duration: 30.0,
             sample_rate: 44100,
         },
-        "mesh" | "static_mesh" | "skeletal_mesh" => {
+        AssetType::SkeletalMesh => PreviewType::Skeletal {
+            bones: 0,
+            lods: Vec::new(),
+        },
+        AssetType::Material | AssetType::MaterialInstance => PreviewType::Material {
+            parent: None,
+            parameters: 0,
+            textures: 0,
+        },
+        AssetType::Blueprint => PreviewType::Blueprint {
+            parent_class: None,
+            components: 0,
+            interfaces: 0,
+        },
+        asset_type if *asset_type == AssetType::DataTable || asset_type.is_other(&["curve_table"]) => PreviewType::DataTable {
+            row_struct: None,
+            rows: 0,
+        },
+        asset_type if asset_type.is_other(&["localization", "string_table"]) => PreviewType::Localization {
+            entries: 0,
+            namespaces: 0,
+        },
+        AssetType::Map => PreviewType::Level {
+            actors: 0,
+            classes: 0,
+        },
+        AssetType::Animation => PreviewType::Animation {
+            duration: 0.0,
+            frames: 0,
+            frame_rate: 0.0,
+            skeleton: None,
+        },
+        AssetType::Particle => PreviewType::ParticleSystem {
+            emitters: 0,
+            user_parameters: 0,
+        },
+        AssetType::Font => PreviewType::Font {
+            family: None,
+            style: None,
+            format: None,
+        },
+        asset_type if utils::is_slate_brush(asset_type) => PreviewType::Image {
+            format: "PNG".to_string(),
+            width: 512,
+            height: 512,
+        },
+        AssetType::StaticMesh => {
             let materials = if let Some(metadata) = &asset.metadata {
                 metadata.get("materials")
                     .and_then(|m| m.as_array())
@@ -111,11 +1601,12 @@ fn determine_preview_type(asset: &Asset) -> PreviewType {
                     .and_then(|v| v.as_u64())
                     .unwrap_or(500) as u32,
                 materials,
+                bounding_box: None,
             }
         },
-        "text" | "script" | "config" => PreviewType::Text {
+        asset_type if asset_type.is_other(&["text", "script", "config"]) => PreviewType::Text {
             encoding: "UTF-8".to_string(),
-            lines: 100,
+            lines: 0,
         },
         _ => PreviewType::Unsupported {
             reason: format!("Preview not supported for asset type: {}", asset.asset_type),
@@ -124,19 +1615,76 @@ fn determine_preview_type(asset: &Asset) -> PreviewType {
 }
 
 /// Generates the actual preview content
-async fn generate_preview_content(asset: &Asset, preview_type: &PreviewType) -> PreviewData {
+async fn generate_preview_content(asset: &Asset, preview_type: &PreviewType, options: &PreviewOptions) -> PreviewData {
     match preview_type {
         PreviewType::Image { format, width, height } => {
             generate_image_preview(asset, format, *width, *height).await
         },
         PreviewType::Audio { format, duration, sample_rate } => {
-            generate_audio_preview(asset, format, *duration, *sample_rate).await
+            generate_audio_preview(asset, format, *duration, *sample_rate, options).await
         },
-        PreviewType::Model { vertices, triangles, materials } => {
+        PreviewType::Model { vertices, triangles, materials, .. } => {
             generate_model_preview(asset, *vertices, *triangles, materials).await
         },
-        PreviewType::Text { encoding, lines } => {
-            generate_text_preview(asset, encoding, *lines).await
+        PreviewType::Text { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Text content could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::Skeletal { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Skeletal mesh data could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::Material { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Material data could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::Blueprint { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Blueprint data could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::DataTable { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Table data could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::Localization { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Localization data could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::Level { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Level data could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::Animation { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Animation data could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::ParticleSystem { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Particle system data could not be read",
+                "asset_type": asset.asset_type,
+            }),
+        },
+        PreviewType::Font { .. } => PreviewData::Json {
+            content: serde_json::json!({
+                "error": "Font data could not be read",
+                "asset_type": asset.asset_type,
+            }),
         },
         PreviewType::Unsupported { reason } => {
             PreviewData::Json {
@@ -150,20 +1698,10 @@ async fn generate_preview_content(asset: &Asset, preview_type: &PreviewType) ->
     }
 }
 
-/// Generates a placeholder image preview
-/// 
-/// TODO: Implement actual image processing
-/// This should:
-/// 1. Extract texture data from .uasset/.uexp files
-/// 2. Handle various texture formats (DXT1, DXT5, BC7, etc.)
-/// 3. Generate thumbnails at requested dimensions
-/// 4. Handle HDR and special texture types
+/// Generates the placeholder image shown for textures that can't be decoded
 async fn generate_image_preview(asset: &Asset, _format: &str, width: u32, height: u32) -> PreviewData {
     tracing::info!("Generating image preview for: {} ({}x{})", asset.name, width, height);
 
-    // PLACEHOLDER: Generate a simple colored rectangle as base64 PNG
-    // TODO: Replace with actual texture extraction and conversion
-    
     // Create a simple placeholder image pattern
     let placeholder_svg = format!(
         "<svg width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">\
@@ -182,7 +1720,6 @@ async fn generate_image_preview(asset: &Asset, _format: &str, width: u32, height
         width, height, width - 20, height - 20, asset.name, width, height, asset.size / 1024
     );
 
-    // Convert SVG to base64 (in a real implementation, this would be a proper image)
     let base64_content = general_purpose::STANDARD.encode(placeholder_svg.as_bytes());
     
     PreviewData::Base64 { 
@@ -198,21 +1735,36 @@ async fn generate_image_preview(asset: &Asset, _format: &str, width: u32, height
 /// 2. Handle various audio formats (OGG, WAV, etc.)
 /// 3. Generate waveform visualizations
 /// 4. Create audio snippets for preview
-async fn generate_audio_preview(asset: &Asset, _format: &str, duration: f32, sample_rate: u32) -> PreviewData {
+async fn generate_audio_preview(
+    asset: &Asset,
+    _format: &str,
+    duration: f32,
+    sample_rate: u32,
+    options: &PreviewOptions,
+) -> PreviewData {
     tracing::info!("Generating audio preview for: {} ({}s @ {}Hz)", asset.name, duration, sample_rate);
 
-    // PLACEHOLDER: Return JSON with audio metadata and waveform data
-    // TODO: Replace with actual audio extraction and waveform generation
-    
+    // PLACEHOLDER: Synthesize PCM until real audio extraction lands
+    // TODO: Replace with decoded samples from the SoundWave bulk data
+    let audio = generate_placeholder_audio(sample_rate, 2);
+
+    let points = options.points.unwrap_or(DEFAULT_WAVEFORM_POINTS);
+    let channel_mode = options.channel_mode.unwrap_or_default();
+    let peaks = compute_waveform_peaks(&audio, points, channel_mode);
+
     PreviewData::Json {
         content: serde_json::json!({
             "type": "audio_preview",
             "asset_name": asset.name,
             "duration": duration,
-            "sample_rate": sample_rate,
-            "channels": 2,
+            "sample_rate": audio.sample_rate,
+            "channels": audio.channels,
             "format": "placeholder",
-            "waveform": generate_placeholder_waveform(128), // 128 data points
+            "waveform": {
+                "points": peaks.first().map(|series| series.len()).unwrap_or(0),
+                "channel_mode": channel_mode,
+                "peaks": peaks,
+            },
             "metadata": {
                 "bitrate": "320 kbps",
                 "compression": "OGG Vorbis",
@@ -276,42 +1828,187 @@ async fn generate_model_preview(asset: &Asset, vertices: u32, triangles: u32, ma
     }
 }
 
-/// Generates a placeholder text preview
-/// 
-/// TODO: Implement actual text file processing
-/// This should handle various text-based assets like blueprints, configs, etc.
-async fn generate_text_preview(asset: &Asset, _encoding: &str, lines: u32) -> PreviewData {
-    tracing::info!("Generating text preview for: {} ({} lines)", asset.name, lines);
+/// Reads a text-like entry and returns its first lines, or a hex dump if it turns out to be binary
+async fn generate_text_file_preview(asset: &Asset, options: &PreviewOptions) -> anyhow::Result<PreviewResponse> {
+    let data = load_package_bytes(asset).await?;
+    let max_lines = options.lines.unwrap_or(DEFAULT_TEXT_LINES).max(1);
 
-    // PLACEHOLDER: Generate sample text content
-    // TODO: Replace with actual file content extraction
-    
-    let placeholder_content = format!(
-        "// Preview of {}\n// Asset Type: {}\n// Size: {} bytes\n// Last Modified: {}\n\n{}\n\n// ... ({} more lines)",
-        asset.name,
-        asset.asset_type,
-        asset.size,
-        asset.last_modified.format("%Y-%m-%d %H:%M:%S UTC"),
-        "// This is a placeholder preview.\n// Actual content extraction not yet implemented.\n// The real implementation would parse the asset file\n// and extract readable text content.",
-        lines - 10
-    );
+    let (preview_type, content, mut details) = match decode_text(&data) {
+        Some((text, encoding)) => {
+            let total_lines = text.lines().count();
+            let shown: Vec<&str> = text.lines().take(max_lines as usize).collect();
+            tracing::info!("Generating text preview for: {} ({} lines, {})", asset.name, total_lines, encoding);
+
+            let details = serde_json::json!({
+                "encoding": encoding,
+                "total_lines": total_lines,
+                "lines_shown": shown.len(),
+                "truncated": shown.len() < total_lines,
+            });
+            let preview_type = PreviewType::Text {
+                encoding: encoding.to_string(),
+                lines: total_lines as u32,
+            };
+            (preview_type, shown.join("\n"), details)
+        }
+        None => {
+            let dumped = &data[..data.len().min(HEX_DUMP_BYTES)];
+            tracing::info!("Text preview for {} looks binary; showing hex dump", asset.name);
+
+            let content = hex_dump(dumped);
+            let details = serde_json::json!({
+                "encoding": "binary",
+                "binary": true,
+                "total_bytes": data.len(),
+                "bytes_shown": dumped.len(),
+                "truncated": dumped.len() < data.len(),
+            });
+            let preview_type = PreviewType::Text {
+                encoding: "hex".to_string(),
+                lines: content.lines().count() as u32,
+            };
+            (preview_type, content, details)
+        }
+    };
+
+    let mut metadata = asset.metadata.clone().unwrap_or_else(|| serde_json::json!({}));
+    if let (Some(object), Some(extra)) = (metadata.as_object_mut(), details.as_object_mut()) {
+        object.append(extra);
+    }
+
+    Ok(PreviewResponse {
+        asset_name: asset.name.clone(),
+        preview_type,
+        data: PreviewData::Text { content },
+        metadata: Some(metadata),
+        generated_at: Utc::now(),
+        request_id: None,
+    })
+}
+
+/// Decodes text bytes, detecting UTF-8 and UTF-16 (with or without BOM)
+///
+/// Returns None when the data looks binary: more than 1% NUL bytes once UTF-16 is ruled out.
+fn decode_text(data: &[u8]) -> Option<(String, &'static str)> {
+    if let Some(rest) = data.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return Some((String::from_utf8_lossy(rest).into_owned(), "UTF-8 (BOM)"));
+    }
+    if let Some(rest) = data.strip_prefix(&[0xFF, 0xFE]) {
+        return Some((decode_utf16(rest, u16::from_le_bytes), "UTF-16LE (BOM)"));
+    }
+    if let Some(rest) = data.strip_prefix(&[0xFE, 0xFF]) {
+        return Some((decode_utf16(rest, u16::from_be_bytes), "UTF-16BE (BOM)"));
+    }
+
+    let sample = &data[..data.len().min(8192)];
+    if sample.is_empty() {
+        return Some((String::new(), "UTF-8"));
+    }
+
+    // BOM-less UTF-16LE ASCII text: NULs in nearly every odd byte and almost none in even ones
+    let odd_nuls = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    let even_nuls = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+    let pairs = sample.len() / 2;
+    if pairs > 0 && odd_nuls * 10 >= pairs * 9 && even_nuls * 20 < pairs {
+        return Some((decode_utf16(data, u16::from_le_bytes), "UTF-16LE"));
+    }
+
+    if (odd_nuls + even_nuls) * 100 > sample.len() {
+        return None;
+    }
 
-    PreviewData::Text { 
-        content: placeholder_content 
+    match std::str::from_utf8(data) {
+        Ok(text) => Some((text.to_string(), "UTF-8")),
+        // Legacy ANSI configs: every byte maps to the code point of the same value
+        Err(_) => Some((data.iter().map(|&b| b as char).collect(), "ISO-8859-1")),
     }
 }
 
-/// Generates placeholder waveform data for audio previews
-fn generate_placeholder_waveform(points: usize) -> Vec<f32> {
-    (0..points)
-        .map(|i| {
-            let t = i as f32 / points as f32;
-            // Generate a simple sine wave with some noise
-            (t * std::f32::consts::PI * 4.0).sin() * 0.8 + 
-            (t * std::f32::consts::PI * 8.0).sin() * 0.3 +
-            (t * std::f32::consts::PI * 16.0).sin() * 0.1
+fn decode_utf16(data: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = data.chunks_exact(2).map(|c| from_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Classic 16-bytes-per-row hex dump with an ASCII column
+fn hex_dump(data: &[u8]) -> String {
+    data.chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|", row * 16, hex.join(" "), ascii)
         })
-        .collect()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Computes min/max peak pairs per bucket for each requested channel series
+///
+/// Returns one series for mixdown/left/right and one series per channel for
+/// `PerChannel`. The bucket count is clamped to the number of frames so short
+/// clips never produce interpolated buckets.
+pub fn compute_waveform_peaks(audio: &DecodedAudio, points: usize, mode: ChannelMode) -> Vec<Vec<[f32; 2]>> {
+    let channels = audio.channels.max(1) as usize;
+    let frames = audio.samples.len() / channels;
+    let points = points.clamp(1, MAX_WAVEFORM_POINTS).min(frames);
+
+    if points == 0 {
+        return Vec::new();
+    }
+
+    let frame_value = |frame: usize, channel: usize| audio.samples[frame * channels + channel];
+    let mixdown = |frame: usize| {
+        (0..channels).map(|c| frame_value(frame, c)).sum::<f32>() / channels as f32
+    };
+
+    let bucket_peaks = |sample_at: &dyn Fn(usize) -> f32| -> Vec<[f32; 2]> {
+        (0..points)
+            .map(|bucket| {
+                let start = bucket * frames / points;
+                let end = ((bucket + 1) * frames / points).max(start + 1);
+                (start..end).fold([f32::MAX, f32::MIN], |[min, max], frame| {
+                    let value = sample_at(frame);
+                    [min.min(value), max.max(value)]
+                })
+            })
+            .collect()
+    };
+
+    match mode {
+        ChannelMode::Mixdown => vec![bucket_peaks(&mixdown)],
+        ChannelMode::Left => vec![bucket_peaks(&|frame| frame_value(frame, 0))],
+        ChannelMode::Right => vec![bucket_peaks(&|frame| frame_value(frame, channels.min(2) - 1))],
+        ChannelMode::PerChannel => (0..channels)
+            .map(|channel| bucket_peaks(&|frame| frame_value(frame, channel)))
+            .collect(),
+    }
+}
+
+/// Generates placeholder PCM audio for previews until real decoding exists
+fn generate_placeholder_audio(sample_rate: u32, channels: u16) -> DecodedAudio {
+    const FRAMES: usize = 4096;
+
+    let samples = (0..FRAMES)
+        .flat_map(|i| {
+            let t = i as f32 / FRAMES as f32;
+            // Generate a simple sine wave with some noise, phase-shifted per channel
+            (0..channels).map(move |c| {
+                let phase = c as f32 * 0.5;
+                (t * std::f32::consts::PI * 4.0 + phase).sin() * 0.8 +
+                (t * std::f32::consts::PI * 8.0).sin() * 0.3 +
+                (t * std::f32::consts::PI * 160.0).sin() * 0.1
+            })
+        })
+        .collect();
+
+    DecodedAudio {
+        sample_rate,
+        channels,
+        samples,
+    }
 }
 
 /// Generates placeholder wireframe data for 3D model previews
@@ -341,41 +2038,218 @@ fn generate_placeholder_wireframe(vertices: u32, triangles: u32) -> serde_json::
 pub mod utils {
     use super::*;
 
+    /// Why an asset lookup failed
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum AssetLookupError {
+        NotFound,
+        /// The path exists in several paks; pass one of these to disambiguate
+        Ambiguous { paks: Vec<String> },
+    }
+
+    impl std::fmt::Display for AssetLookupError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                AssetLookupError::NotFound => write!(f, "Asset not found"),
+                AssetLookupError::Ambiguous { paks } => {
+                    write!(f, "Asset path exists in multiple paks, specify one of: {}", paks.join(", "))
+                }
+            }
+        }
+    }
+
+    /// Finds an asset by exact path (optionally within one pak), falling back to display name
+    ///
+    /// Display names are not unique ("T_Rock_01" and "SM_Rock_01" both become "Rock 01"),
+    /// so the name lookup only exists for older callers.
+    pub fn find_asset<'a>(
+        assets: &'a [Arc<Asset>],
+        path: Option<&str>,
+        pak_file: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<&'a Arc<Asset>, AssetLookupError> {
+        let in_pak = |asset: &Asset| pak_file.is_none() || asset.pak_file.as_deref() == pak_file;
+
+        let Some(path) = path else {
+            let name = name.ok_or(AssetLookupError::NotFound)?;
+            return assets
+                .iter()
+                .find(|a| a.name == name && in_pak(a))
+                .ok_or(AssetLookupError::NotFound);
+        };
+
+        let matches: Vec<&Arc<Asset>> = assets.iter().filter(|a| a.path == path && in_pak(a)).collect();
+        match matches.as_slice() {
+            [] => Err(AssetLookupError::NotFound),
+            [asset] => Ok(asset),
+            many => Err(AssetLookupError::Ambiguous {
+                paks: many.iter().filter_map(|a| a.pak_file.as_deref().map(str::to_string)).collect(),
+            }),
+        }
+    }
+
+    /// Splits a `data:<mime>;base64,<payload>` URL into its MIME type and decoded bytes
+    pub fn decode_data_url(url: &str) -> Option<(String, Vec<u8>)> {
+        let (header, payload) = url.strip_prefix("data:")?.split_once(',')?;
+        let mime = header.strip_suffix(";base64")?;
+        let bytes = general_purpose::STANDARD.decode(payload).ok()?;
+        Some((mime.to_string(), bytes))
+    }
+
+    /// The binary payload of a preview (image, font, ...) and its MIME type, if it has one
+    pub fn raw_payload(response: &PreviewResponse) -> Option<(String, Vec<u8>)> {
+        match &response.data {
+            PreviewData::Base64 { content } => decode_data_url(content),
+            _ => None,
+        }
+    }
+
+    /// Relative URL of the `GET /preview/raw` route serving an asset's preview bytes
+    pub fn raw_preview_url(asset: &Asset) -> String {
+        use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+        let mut url = format!("/preview/raw?path={}", utf8_percent_encode(&asset.path, NON_ALPHANUMERIC));
+        if let Some(pak) = &asset.pak_file {
+            url.push_str(&format!("&pak={}", utf8_percent_encode(pak, NON_ALPHANUMERIC)));
+        }
+        url
+    }
+
+    /// Replaces inline Base64 data with a link to the raw route; other data is left as is
+    ///
+    /// `token` is the API token the request came with. The link carries it as `?token=`,
+    /// since an `<img>` loading the link can't send it as a header.
+    pub fn link_raw_data(response: &mut PreviewResponse, asset: &Asset, token: Option<&str>) {
+        use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+        if matches!(response.data, PreviewData::Base64 { .. }) {
+            let mut url = raw_preview_url(asset);
+            if let Some(token) = token {
+                url.push_str(&format!("&token={}", utf8_percent_encode(token, NON_ALPHANUMERIC)));
+            }
+            response.data = PreviewData::Url { url };
+        }
+    }
+
+    /// Returns true if a MIME type satisfies a requested format ("png", "svg", "image/png", ...)
+    pub fn mime_matches_format(mime: &str, format: &str) -> bool {
+        let format = format.trim().to_ascii_lowercase();
+        if format.contains('/') {
+            return mime.eq_ignore_ascii_case(&format);
+        }
+        let subtype = mime.split_once('/').map_or(mime, |(_, subtype)| subtype);
+        let subtype = subtype.split('+').next().unwrap_or(subtype);
+        subtype.eq_ignore_ascii_case(&format) || (format == "jpg" && subtype.eq_ignore_ascii_case("jpeg"))
+    }
+
+    /// File extension to use when writing a preview payload of this MIME type
+    pub fn extension_for_mime(mime: &str) -> &'static str {
+        match mime {
+            "image/png" => "png",
+            "image/jpeg" => "jpg",
+            "image/svg+xml" => "svg",
+            "image/webp" => "webp",
+            "audio/wav" | "audio/x-wav" => "wav",
+            "audio/ogg" => "ogg",
+            "font/ttf" => "ttf",
+            "font/otf" => "otf",
+            "font/collection" => "ttc",
+            "model/gltf-binary" => "glb",
+            _ => "bin",
+        }
+    }
+
     /// Determines if an asset type supports preview generation
-    pub fn supports_preview(asset_type: &str) -> bool {
-        matches!(asset_type, 
-            "texture" | "image" | 
-            "audio" | "sound" | 
-            "mesh" | "static_mesh" | "skeletal_mesh" |
-            "text" | "script" | "config"
-        )
+    pub fn supports_preview(asset_type: &AssetType) -> bool {
+        matches!(asset_type, AssetType::Texture | AssetType::Sound | AssetType::StaticMesh | AssetType::SkeletalMesh)
+            || asset_type.is_other(&["text", "script", "config"])
+    }
+
+    /// Returns true for Slate brush asset types, which preview through their texture
+    pub fn is_slate_brush(asset_type: &AssetType) -> bool {
+        asset_type.is_other(&["slate_brush", "slate_brush_asset"])
+    }
+
+    /// Maps an object path to the package file suffix it lives at inside a pak
+    ///
+    /// `/Game/UI/T_Icon.T_Icon` -> `Content/UI/T_Icon.uasset`,
+    /// `/MyPlugin/Icons/T_Icon` -> `MyPlugin/Content/Icons/T_Icon.uasset`
+    pub fn object_path_to_file_suffix(object_path: &str) -> Option<String> {
+        let package = object_path.split(['.', ':']).next()?;
+        let (root, rest) = package.strip_prefix('/')?.split_once('/')?;
+        if root == "Game" {
+            Some(format!("Content/{}.uasset", rest))
+        } else {
+            Some(format!("{}/Content/{}.uasset", root, rest))
+        }
     }
 
     /// Gets the estimated preview generation time for an asset
     pub fn estimate_preview_time(asset: &Asset) -> f32 {
-        match asset.asset_type.as_str() {
-            "texture" | "image" => (asset.size as f32 / 1_000_000.0).max(0.1), // ~1 second per MB
-            "audio" | "sound" => (asset.size as f32 / 2_000_000.0).max(0.5), // ~0.5 seconds per MB
-            "mesh" | "static_mesh" | "skeletal_mesh" => (asset.size as f32 / 500_000.0).max(1.0), // ~2 seconds per MB
-            "text" | "script" | "config" => 0.1, // Very fast for text
+        match &asset.asset_type {
+            AssetType::Texture => (asset.size as f32 / 1_000_000.0).max(0.1), // ~1 second per MB
+            AssetType::Sound => (asset.size as f32 / 2_000_000.0).max(0.5), // ~0.5 seconds per MB
+            AssetType::StaticMesh | AssetType::SkeletalMesh => (asset.size as f32 / 500_000.0).max(1.0), // ~2 seconds per MB
+            asset_type if asset_type.is_other(&["text", "script", "config"]) => 0.1, // Very fast for text
             _ => 0.5, // Default estimate
         }
     }
 
-    /// Clears cached preview data (placeholder for future caching implementation)
-    pub async fn clear_preview_cache() -> anyhow::Result<()> {
+    /// Deletes all cached previews and resets cache counters; returns how many were removed
+    pub async fn clear_preview_cache() -> anyhow::Result<usize> {
         tracing::info!("Clearing preview cache...");
-        // TODO: Implement preview caching and cleanup
-        Ok(())
+        preview_cache::global().clear().await
     }
 
-    /// Gets preview cache statistics (placeholder for future caching implementation)
+    /// Gets preview cache statistics (entries, bytes, budget and hit rate since startup)
     pub async fn get_cache_stats() -> anyhow::Result<serde_json::Value> {
-        Ok(serde_json::json!({
-            "cached_previews": 0,
-            "cache_size_bytes": 0,
-            "hit_rate": 0.0,
-            "last_cleanup": null
-        }))
-    }
-}
\ No newline at end of file
+        Ok(serde_json::to_value(preview_cache::global().stats())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mip(width: u32, payload: MipPayload) -> TextureMip {
+        TextureMip { width, height: width / 2, depth: 1, payload }
+    }
+
+    #[test]
+    fn preview_mip_picks_the_largest_stored_mip_that_fits() {
+        let stored = |offset| MipPayload::Package { offset, size: 1 };
+        let mips = [mip(1024, MipPayload::Missing), mip(512, stored(0)), mip(256, stored(1)), mip(128, stored(2))];
+        let picked = |size| preview_mip(&mips, size).map(|(index, _)| index);
+        // The 1024 mip isn't cooked, so nothing larger than 512 can be picked
+        assert_eq!(picked(2048), Some(1));
+        assert_eq!(picked(300), Some(2));
+        assert_eq!(picked(64), Some(3));
+        assert!(preview_mip(&mips[..1], 512).is_none());
+    }
+
+    #[test]
+    fn mips_decode_to_png() {
+        // 2x1 BGRA: opaque red, then half-transparent blue
+        let png = encode_mip_png("PF_B8G8R8A8", 2, 1, &[0, 0, 255, 255, 255, 0, 0, 128]).unwrap();
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((frame.width, frame.height), (2, 1));
+        assert_eq!(pixels[..8], [255, 0, 0, 255, 0, 0, 255, 128]);
+
+        let error = encode_mip_png("PF_BC6H", 4, 4, &[0; 16]).unwrap_err();
+        assert!(error.to_string().contains("No decoder for PF_BC6H"), "{:#}", error);
+        assert!(encode_mip_png("PF_B8G8R8A8", 2, 2, &[0; 8]).is_err());
+    }
+
+    #[tokio::test]
+    async fn undecodable_textures_are_labelled_as_placeholders() {
+        let mut asset = crate::create_mock_assets().into_iter().find(|a| a.asset_type == AssetType::Texture).unwrap();
+        asset.pak_file = None;
+        asset.path = std::env::temp_dir().join("pakseek-missing-texture/T_Missing.uasset").to_string_lossy().into_owned();
+
+        let response = generate_uncached_preview(&asset, &PreviewOptions::default()).await;
+        assert_eq!(response.metadata.unwrap()["source"], "placeholder");
+        assert!(matches!(response.preview_type, PreviewType::Image { ref format, .. } if format == "SVG"));
+        assert!(matches!(response.data, PreviewData::Base64 { ref content } if content.starts_with("data:image/svg+xml;")));
+    }
+}
@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use crate::preview::{Asset, PreviewOptions, PreviewResponse, PreviewType};
+
+/// Default size budget for cached previews
+pub const DEFAULT_CACHE_BUDGET_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Identifies one generated preview: which entry, which version of it, and how it was rendered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey {
+    pub pak_path: String,
+    pub entry_path: String,
+    pub entry_hash: String,
+    pub options: String,
+}
+
+impl CacheKey {
+    /// Builds the key for an asset, or None if the asset can't be cached safely
+    ///
+    /// Only pak entries with a recorded SHA-1 are cached, so a rebuilt pak never serves stale previews.
+    pub fn for_asset(asset: &Asset, options: &PreviewOptions) -> Option<Self> {
+        let hash = asset.hash.as_ref()?;
+        Some(CacheKey {
+            pak_path: asset.pak_file.as_deref()?.to_string(),
+            entry_path: asset.path.clone(),
+            entry_hash: hash.clone(),
+            options: serde_json::to_string(options).ok()?,
+        })
+    }
+
+    /// File name of the cached preview (hex SHA-256 of the key parts)
+    fn file_name(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [&self.pak_path, &self.entry_path, &self.entry_hash, &self.options] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let digest: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}.json", digest)
+    }
+}
+
+/// Cache usage reported to the settings screen
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CacheStats {
+    pub cached_previews: usize,
+    pub cache_size_bytes: u64,
+    pub budget_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+    pub directory: String,
+}
+
+struct CacheEntry {
+    size: u64,
+    last_access: SystemTime,
+}
+
+#[derive(Default)]
+struct CacheIndex {
+    entries: HashMap<String, CacheEntry>,
+    total_bytes: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// Disk-backed preview cache with LRU eviction under a byte budget
+pub struct PreviewCache {
+    dir: PathBuf,
+    budget_bytes: Mutex<u64>,
+    index: Mutex<CacheIndex>,
+}
+
+impl PreviewCache {
+    /// Opens (or creates) a cache directory, indexing previews left from earlier runs
+    pub fn open(dir: PathBuf, budget_bytes: u64) -> Self {
+        let mut index = CacheIndex::default();
+        if let Ok(entries) = std::fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let Ok(metadata) = entry.metadata() else { continue };
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if !metadata.is_file() || !name.ends_with(".json") {
+                    continue;
+                }
+                index.total_bytes += metadata.len();
+                index.entries.insert(
+                    name,
+                    CacheEntry {
+                        size: metadata.len(),
+                        last_access: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    },
+                );
+            }
+        }
+
+        PreviewCache {
+            dir,
+            budget_bytes: Mutex::new(budget_bytes),
+            index: Mutex::new(index),
+        }
+    }
+
+    /// Returns a cached preview and records the hit or miss
+    pub async fn get(&self, key: &CacheKey) -> Option<PreviewResponse> {
+        let name = key.file_name();
+        let cached = match tokio::fs::read(self.dir.join(&name)).await {
+            Ok(bytes) => serde_json::from_slice::<PreviewResponse>(&bytes).ok(),
+            Err(_) => None,
+        };
+
+        let mut index = self.index.lock().unwrap();
+        match (&cached, index.entries.get_mut(&name)) {
+            (Some(_), Some(entry)) => {
+                entry.last_access = SystemTime::now();
+                index.hits += 1;
+            }
+            _ => index.misses += 1,
+        }
+        cached
+    }
+
+    /// Whether a preview is stored for the key, without counting a hit or miss
+    pub fn contains(&self, key: &CacheKey) -> bool {
+        self.index.lock().unwrap().entries.contains_key(&key.file_name())
+    }
+
+    /// Stores a preview, then evicts least recently used previews until the budget is met
+    ///
+    /// Unsupported results are not cached; they are often caused by transient failures.
+    pub async fn put(&self, key: &CacheKey, response: &PreviewResponse) -> anyhow::Result<()> {
+        if matches!(response.preview_type, PreviewType::Unsupported { .. }) {
+            return Ok(());
+        }
+
+        let bytes = serde_json::to_vec(response)?;
+        let budget = *self.budget_bytes.lock().unwrap();
+        if bytes.len() as u64 > budget {
+            return Ok(());
+        }
+
+        let name = key.file_name();
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(self.dir.join(&name), &bytes).await?;
+
+        let evicted = {
+            let mut index = self.index.lock().unwrap();
+            if let Some(previous) = index.entries.insert(
+                name,
+                CacheEntry { size: bytes.len() as u64, last_access: SystemTime::now() },
+            ) {
+                index.total_bytes -= previous.size;
+            }
+            index.total_bytes += bytes.len() as u64;
+            Self::evict(&mut index, budget)
+        };
+
+        for name in evicted {
+            let _ = tokio::fs::remove_file(self.dir.join(name)).await;
+        }
+        Ok(())
+    }
+
+    /// Changes the size budget, evicting immediately if the cache is now over it
+    pub async fn set_budget(&self, budget_bytes: u64) {
+        *self.budget_bytes.lock().unwrap() = budget_bytes;
+        let evicted = Self::evict(&mut self.index.lock().unwrap(), budget_bytes);
+        for name in evicted {
+            let _ = tokio::fs::remove_file(self.dir.join(name)).await;
+        }
+    }
+
+    /// Deletes every cached preview and resets the hit/miss counters
+    pub async fn clear(&self) -> anyhow::Result<usize> {
+        let names: Vec<String> = {
+            let mut index = self.index.lock().unwrap();
+            let names = index.entries.drain().map(|(name, _)| name).collect();
+            *index = CacheIndex::default();
+            names
+        };
+
+        for name in &names {
+            match tokio::fs::remove_file(self.dir.join(name)).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(names.len())
+    }
+
+    /// Current entry count, size, budget and hit rate since startup
+    pub fn stats(&self) -> CacheStats {
+        let index = self.index.lock().unwrap();
+        let lookups = index.hits + index.misses;
+        CacheStats {
+            cached_previews: index.entries.len(),
+            cache_size_bytes: index.total_bytes,
+            budget_bytes: *self.budget_bytes.lock().unwrap(),
+            hits: index.hits,
+            misses: index.misses,
+            hit_rate: if lookups == 0 { 0.0 } else { index.hits as f64 / lookups as f64 },
+            directory: self.dir.to_string_lossy().into_owned(),
+        }
+    }
+
+    /// Removes least recently used entries from the index; returns the file names to delete
+    fn evict(index: &mut CacheIndex, budget: u64) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while index.total_bytes > budget {
+            let Some(oldest) = index
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_access)
+                .map(|(name, _)| name.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = index.entries.remove(&oldest) {
+                index.total_bytes -= entry.size;
+            }
+            evicted.push(oldest);
+        }
+        evicted
+    }
+}
+
+static CACHE: OnceLock<PreviewCache> = OnceLock::new();
+
+/// The process-wide preview cache under the platform cache directory
+///
+/// The budget comes from the settings; `PAKSEEK_PREVIEW_CACHE_MB` overrides it at startup.
+/// `update_settings` resizes the cache when it changes.
+pub fn global() -> &'static PreviewCache {
+    CACHE.get_or_init(|| {
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("pakseek")
+            .join("previews");
+        let budget = std::env::var("PAKSEEK_PREVIEW_CACHE_MB")
+            .ok()
+            .and_then(|mb| mb.parse::<u64>().ok())
+            .unwrap_or_else(|| crate::settings::global().get().preview_cache_mb)
+            * 1024
+            * 1024;
+        PreviewCache::open(dir, budget)
+    })
+}
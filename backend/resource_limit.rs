@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+
+/// Bytes of decompressed buffers in flight at once unless configured otherwise
+pub const DEFAULT_BUFFER_BUDGET: u64 = 1024 * 1024 * 1024;
+
+/// Decompressions running at once unless configured otherwise: one per core
+pub fn default_slots() -> usize {
+    std::thread::available_parallelism().map_or(4, |cores| cores.get())
+}
+
+/// Slots for blocking decompression and a budget for the buffers it fills, shared by
+/// scans, extractions and previews
+///
+/// Work takes a `ResourceLease` for the bytes it's about to decompress before allocating
+/// them, and waits while every slot is taken or the bytes would go over the budget. A
+/// lease for more than the whole budget is cut down to it, so it runs once the others
+/// are done rather than never.
+pub struct ResourceLimiter {
+    slots: usize,
+    permits: Arc<Semaphore>,
+    budget: u64,
+    in_use: AtomicU64,
+    peak: AtomicU64,
+    waited_total: AtomicU64,
+    freed: Notify,
+}
+
+/// Counters of the limiter as reported by `get_status` and `/health`
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ResourceMetrics {
+    pub slots: usize,
+    pub slots_in_use: usize,
+    pub budget_bytes: u64,
+    pub bytes_in_use: u64,
+    /// Most bytes in use at once since startup
+    pub peak_bytes: u64,
+    /// Leases that had to wait for a slot or for room in the budget since startup
+    pub waited_total: u64,
+}
+
+/// A slot and bytes of the budget, given back when dropped
+pub struct ResourceLease {
+    held: Option<(Arc<ResourceLimiter>, OwnedSemaphorePermit, u64)>,
+}
+
+tokio::task_local! {
+    /// Set while a task runs under the lease `ResourceLimiter::run` took for it, which
+    /// already covers what the task decompresses
+    static COVERED: ();
+}
+
+impl ResourceLimiter {
+    /// A limit of 0 slots would stop all work; it's treated as 1
+    pub fn new(slots: usize, budget: u64) -> Self {
+        let slots = slots.max(1);
+        Self {
+            slots,
+            permits: Arc::new(Semaphore::new(slots)),
+            budget,
+            in_use: AtomicU64::new(0),
+            peak: AtomicU64::new(0),
+            waited_total: AtomicU64::new(0),
+            freed: Notify::new(),
+        }
+    }
+
+    /// Takes a slot and `bytes` of the budget, waiting until both are free
+    ///
+    /// Inside `run` the lease is empty and never waits, since `run` holds one for the task.
+    /// Elsewhere, don't take a lease while holding another: with every slot held that way,
+    /// none is ever given back.
+    pub async fn acquire(self: &Arc<Self>, bytes: u64) -> ResourceLease {
+        if COVERED.try_with(|_| ()).is_ok() {
+            return ResourceLease { held: None };
+        }
+        let bytes = bytes.min(self.budget);
+        let mut waited = false;
+        let permit = match self.permits.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                waited = true;
+                // The semaphore is never closed
+                self.permits.clone().acquire_owned().await.expect("resource semaphore closed")
+            }
+        };
+        loop {
+            // Registered before the check, so a lease given back in between still wakes us
+            let freed = self.freed.notified();
+            tokio::pin!(freed);
+            freed.as_mut().enable();
+            if self.try_reserve(bytes) {
+                break;
+            }
+            waited = true;
+            freed.await;
+        }
+        if waited {
+            self.waited_total.fetch_add(1, Ordering::Relaxed);
+        }
+        ResourceLease { held: Some((self.clone(), permit, bytes)) }
+    }
+
+    /// Runs `work` under a lease of `bytes`, for work that decompresses through several
+    /// calls (a preview extracting a package and its siblings) and would otherwise hold
+    /// a slot while waiting for another
+    pub async fn run<F: Future>(self: &Arc<Self>, bytes: u64, work: F) -> F::Output {
+        let _lease = self.acquire(bytes).await;
+        COVERED.scope((), work).await
+    }
+
+    fn try_reserve(&self, bytes: u64) -> bool {
+        let reserved = self.in_use.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |in_use| {
+            (in_use + bytes <= self.budget).then_some(in_use + bytes)
+        });
+        match reserved {
+            Ok(before) => {
+                self.peak.fetch_max(before + bytes, Ordering::SeqCst);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn metrics(&self) -> ResourceMetrics {
+        ResourceMetrics {
+            slots: self.slots,
+            slots_in_use: self.slots - self.permits.available_permits(),
+            budget_bytes: self.budget,
+            bytes_in_use: self.in_use.load(Ordering::SeqCst),
+            peak_bytes: self.peak.load(Ordering::SeqCst),
+            waited_total: self.waited_total.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl ResourceLease {
+    /// `reader` holding this lease until it's dropped, for entries streamed block by block
+    pub fn hold<R>(self, reader: R) -> LeasedReader<R> {
+        LeasedReader { inner: reader, _lease: self }
+    }
+}
+
+impl Drop for ResourceLease {
+    fn drop(&mut self) {
+        if let Some((limiter, permit, bytes)) = self.held.take() {
+            limiter.in_use.fetch_sub(bytes, Ordering::SeqCst);
+            drop(permit);
+            limiter.freed.notify_waiters();
+        }
+    }
+}
+
+/// A reader that keeps its lease for as long as it's read from
+pub struct LeasedReader<R> {
+    inner: R,
+    _lease: ResourceLease,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for LeasedReader<R> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+static LIMITER: OnceLock<Arc<ResourceLimiter>> = OnceLock::new();
+
+/// Makes a limiter of `slots` and `budget` the process-wide one and returns it;
+/// `AppState::new` does this at startup
+pub fn init(slots: usize, budget: u64) -> Arc<ResourceLimiter> {
+    if LIMITER.set(Arc::new(ResourceLimiter::new(slots, budget))).is_err() {
+        warn!("Resource limits were already set; keeping those");
+    }
+    global().clone()
+}
+
+/// The process-wide limiter, for the parsers and previews that don't see `AppState`
+pub fn global() -> &'static Arc<ResourceLimiter> {
+    LIMITER.get_or_init(|| Arc::new(ResourceLimiter::new(default_slots(), DEFAULT_BUFFER_BUDGET)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::AsyncReadExt;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn concurrent_extractions_stay_within_budget() {
+        let limiter = Arc::new(ResourceLimiter::new(6, 16 * MB));
+        let held = Arc::new(AtomicU64::new(0));
+        let most_held = Arc::new(AtomicU64::new(0));
+        let tasks: Vec<_> = (0..200u64)
+            .map(|i| {
+                let (limiter, held, most_held) = (limiter.clone(), held.clone(), most_held.clone());
+                tokio::spawn(async move {
+                    // 64 KiB to 5 MiB, like a mix of small packages and large textures
+                    let size = 64 * 1024 + (i * 7919 * 1024) % (5 * MB);
+                    let lease = limiter.acquire(size).await;
+                    most_held.fetch_max(held.fetch_add(size, Ordering::SeqCst) + size, Ordering::SeqCst);
+                    let mut reader = lease.hold(tokio::io::repeat(0).take(size));
+                    let mut buffer = Vec::new();
+                    reader.read_to_end(&mut buffer).await.unwrap();
+                    assert_eq!(buffer.len() as u64, size);
+                    held.fetch_sub(size, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let metrics = limiter.metrics();
+        assert!(most_held.load(Ordering::SeqCst) <= 16 * MB);
+        assert!(metrics.peak_bytes <= 16 * MB);
+        assert!(metrics.waited_total > 0);
+        assert_eq!(metrics.bytes_in_use, 0);
+        assert_eq!(metrics.slots_in_use, 0);
+    }
+
+    #[tokio::test]
+    async fn slots_bound_concurrent_leases() {
+        let limiter = Arc::new(ResourceLimiter::new(2, 16 * MB));
+        let first = limiter.acquire(MB).await;
+        let _second = limiter.acquire(MB).await;
+        assert_eq!(limiter.metrics().slots_in_use, 2);
+        assert!(tokio::time::timeout(Duration::from_millis(50), limiter.acquire(MB)).await.is_err());
+
+        drop(first);
+        let _third = tokio::time::timeout(Duration::from_secs(1), limiter.acquire(MB)).await.unwrap();
+        assert_eq!(limiter.metrics().bytes_in_use, 2 * MB);
+    }
+
+    #[tokio::test]
+    async fn leases_wait_for_room_in_the_budget() {
+        let limiter = Arc::new(ResourceLimiter::new(4, 4 * MB));
+        let first = limiter.acquire(3 * MB).await;
+        assert!(tokio::time::timeout(Duration::from_millis(50), limiter.acquire(2 * MB)).await.is_err());
+
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(2 * MB).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(first);
+        let _second = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+        assert_eq!(limiter.metrics().bytes_in_use, 2 * MB);
+        assert_eq!(limiter.metrics().peak_bytes, 3 * MB);
+    }
+
+    #[tokio::test]
+    async fn oversized_lease_takes_the_whole_budget() {
+        let limiter = Arc::new(ResourceLimiter::new(4, 4 * MB));
+        let lease = limiter.acquire(10 * MB).await;
+        assert_eq!(limiter.metrics().bytes_in_use, 4 * MB);
+        assert!(tokio::time::timeout(Duration::from_millis(50), limiter.acquire(1)).await.is_err());
+        drop(lease);
+        assert_eq!(limiter.metrics().bytes_in_use, 0);
+    }
+
+    #[tokio::test]
+    async fn leases_inside_run_are_covered() {
+        let limiter = Arc::new(ResourceLimiter::new(1, 4 * MB));
+        let nested = limiter.run(4 * MB, async {
+            let lease = limiter.acquire(4 * MB).await;
+            assert!(lease.held.is_none());
+            limiter.metrics().bytes_in_use
+        });
+        assert_eq!(tokio::time::timeout(Duration::from_secs(1), nested).await.unwrap(), 4 * MB);
+        assert_eq!(limiter.metrics().slots_in_use, 0);
+    }
+
+    #[test]
+    fn zero_slots_mean_one() {
+        assert_eq!(ResourceLimiter::new(0, MB).metrics().slots, 1);
+    }
+}
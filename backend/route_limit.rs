@@ -0,0 +1,141 @@
+use axum::extract::{Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::server_config::{ConcurrencyLimit, RouteLimits};
+use crate::{ApiError, ErrorCode};
+
+/// Seconds a rejected client is told to wait before retrying
+const RETRY_AFTER_SECS: u64 = 1;
+
+/// A turn of a limited route, held until the request (or the job it started) is done
+///
+/// Handlers that hand work to a background task take this from the request extensions
+/// and move it there, so the turn lasts as long as the work.
+#[derive(Clone)]
+pub struct RouteTurn {
+    _permit: Arc<OwnedSemaphorePermit>,
+}
+
+/// A place in a route's queue, given back however the wait ends (client gone included)
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Concurrency limit of one group of routes, with counters for `/health`
+pub struct RouteLimit {
+    name: &'static str,
+    limit: ConcurrencyLimit,
+    permits: Arc<Semaphore>,
+    queued: AtomicUsize,
+    queued_total: AtomicU64,
+    rejected_total: AtomicU64,
+}
+
+/// Counters of a route limit as reported by `/health`
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteLimitMetrics {
+    pub concurrency: usize,
+    pub queue: usize,
+    pub in_flight: usize,
+    pub queued: usize,
+    /// Requests that had to wait for a turn since startup
+    pub queued_total: u64,
+    /// Requests answered 429 since startup
+    pub rejected_total: u64,
+}
+
+impl RouteLimit {
+    pub fn new(name: &'static str, limit: ConcurrencyLimit) -> Self {
+        // A limit of 0 would shut the routes; treat it as 1
+        let limit = ConcurrencyLimit { concurrency: limit.concurrency.max(1), ..limit };
+        Self {
+            name,
+            limit,
+            permits: Arc::new(Semaphore::new(limit.concurrency)),
+            queued: AtomicUsize::new(0),
+            queued_total: AtomicU64::new(0),
+            rejected_total: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a turn, waiting in the queue when all are taken and it has room
+    pub async fn acquire(&self) -> Result<RouteTurn, ApiError> {
+        if let Ok(permit) = self.permits.clone().try_acquire_owned() {
+            return Ok(RouteTurn { _permit: Arc::new(permit) });
+        }
+
+        let position = self.queued.fetch_add(1, Ordering::SeqCst);
+        let _slot = QueueSlot(&self.queued);
+        if position >= self.limit.queue {
+            self.rejected_total.fetch_add(1, Ordering::Relaxed);
+            let message = format!("Too many {} requests in flight, retry later", self.name);
+            return Err(ApiError::new(ErrorCode::TooManyRequests, message)
+                .with_context(serde_json::json!({ "route": self.name, "concurrency": self.limit.concurrency })));
+        }
+        self.queued_total.fetch_add(1, Ordering::Relaxed);
+        let permit = self.permits.clone().acquire_owned().await;
+        // The semaphore is never closed
+        Ok(RouteTurn { _permit: Arc::new(permit.expect("route semaphore closed")) })
+    }
+
+    pub fn metrics(&self) -> RouteLimitMetrics {
+        RouteLimitMetrics {
+            concurrency: self.limit.concurrency,
+            queue: self.limit.queue,
+            in_flight: self.limit.concurrency - self.permits.available_permits(),
+            queued: self.queued.load(Ordering::SeqCst),
+            queued_total: self.queued_total.load(Ordering::Relaxed),
+            rejected_total: self.rejected_total.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The limited route groups of the API server
+pub struct ApiLimits {
+    pub preview: Arc<RouteLimit>,
+    pub extract: Arc<RouteLimit>,
+    pub scan: Arc<RouteLimit>,
+}
+
+impl ApiLimits {
+    pub fn new(limits: &RouteLimits) -> Self {
+        Self {
+            preview: Arc::new(RouteLimit::new("preview", limits.preview)),
+            extract: Arc::new(RouteLimit::new("extract", limits.extract)),
+            scan: Arc::new(RouteLimit::new("scan", limits.scan)),
+        }
+    }
+
+    pub fn metrics(&self) -> serde_json::Value {
+        serde_json::json!({
+            "preview": self.preview.metrics(),
+            "extract": self.extract.metrics(),
+            "scan": self.scan.metrics(),
+        })
+    }
+}
+
+/// Runs the request in a turn of `limit`, answering 429 with `Retry-After` when there's none
+///
+/// The turn goes into the request extensions as a `RouteTurn` for handlers to keep.
+pub async fn limit_concurrency(State(limit): State<Arc<RouteLimit>>, mut request: Request, next: Next) -> Response {
+    match limit.acquire().await {
+        Ok(turn) => {
+            request.extensions_mut().insert(turn.clone());
+            let response = next.run(request).await;
+            drop(turn);
+            response
+        }
+        Err(error) => ([(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())], error).into_response(),
+    }
+}
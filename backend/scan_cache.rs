@@ -0,0 +1,253 @@
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+
+use crate::dependency_map::DependencyMap;
+use crate::pak_parser::PakFile;
+use crate::preview::Asset;
+
+/// Leading bytes of every scan cache file
+const MAGIC: &[u8; 4] = b"PSKC";
+
+/// Bump when `Asset`, `DependencyMap`, `PakFile` (with `PakEntry`) or the encoding changes;
+/// older files are rebuilt
+pub const FORMAT_VERSION: u32 = 8;
+
+/// Bytes at the end of a pak hashed into its index key: enough for the footer of any version
+const FOOTER_BYTES: u64 = 256;
+
+/// Off with `--no-index-cache`: nothing is read from or written to the cache
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Turns the cache on or off for the rest of the process
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Where scan results are kept between launches; tests keep theirs out of the user's
+pub fn cache_dir() -> PathBuf {
+    let root = if cfg!(test) {
+        std::env::temp_dir().join("pakseek-test")
+    } else {
+        dirs::cache_dir().unwrap_or_else(std::env::temp_dir).join("pakseek")
+    };
+    root.join("index")
+}
+
+/// Identifies a set of paks by path, size and modification time (hex SHA-256)
+///
+/// Any pak being added, removed, replaced or touched gives a new digest. `variant`
+/// separates results of the same paks scanned differently (e.g. "deep").
+pub fn pak_set_digest(pak_files: &[String], variant: &str) -> String {
+    let mut pak_files: Vec<&String> = pak_files.iter().collect();
+    pak_files.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(variant.as_bytes());
+    for pak_file in pak_files {
+        let metadata = std::fs::metadata(pak_file).ok();
+        let size = metadata.as_ref().map_or(0, |m| m.len());
+        let mtime = metadata
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos());
+        hasher.update([0]);
+        hasher.update(pak_file.as_bytes());
+        hasher.update([0]);
+        hasher.update(size.to_le_bytes());
+        hasher.update(mtime.to_le_bytes());
+    }
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Identifies one pak's index by path, size, modification time and a hash of its footer
+/// (hex SHA-256); None when the file can't be read
+///
+/// The footer says where the index is and holds its hash, so a pak rewritten in place
+/// within the same second still gets a new key.
+pub fn pak_index_key(pak_file: &str) -> Option<String> {
+    let mut file = std::fs::File::open(pak_file).ok()?;
+    let metadata = file.metadata().ok()?;
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos());
+    let tail_len = metadata.len().min(FOOTER_BYTES);
+    let mut footer = vec![0u8; tail_len as usize];
+    file.seek(SeekFrom::End(-(tail_len as i64))).ok()?;
+    file.read_exact(&mut footer).ok()?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"pak-index");
+    hasher.update([0]);
+    hasher.update(pak_file.as_bytes());
+    hasher.update([0]);
+    hasher.update(metadata.len().to_le_bytes());
+    hasher.update(mtime.to_le_bytes());
+    hasher.update(&footer);
+    Some(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Writes `value` as MessagePack behind a magic and version header
+///
+/// The file is written next to `path` and renamed into place, so readers never see half of it.
+pub fn write_versioned<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let mut data = Vec::with_capacity(4096);
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    rmp_serde::encode::write_named(&mut data, value)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, &data).with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Reads a file written by `write_versioned`; wrong magic, version or contents are errors
+pub fn read_versioned<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if data.len() < 8 || &data[..4] != MAGIC {
+        bail!("{} is not a scan cache file", path.display());
+    }
+    let version = u32::from_le_bytes(data[4..8].try_into()?);
+    if version != FORMAT_VERSION {
+        bail!("{} has cache format {} (expected {})", path.display(), version, FORMAT_VERSION);
+    }
+    Ok(rmp_serde::from_slice(&data[8..])?)
+}
+
+/// Saves a scanned asset index
+pub fn save_assets(path: &Path, assets: &[Asset]) -> Result<()> {
+    write_versioned(path, &assets)
+}
+
+/// Loads an asset index written by `save_assets`
+pub fn load_assets(path: &Path) -> Result<Vec<Asset>> {
+    read_versioned(path)
+}
+
+fn assets_path(digest: &str) -> PathBuf {
+    cache_dir().join(format!("{}.assets", digest))
+}
+
+fn dependencies_path(digest: &str) -> PathBuf {
+    cache_dir().join(format!("{}.deps", digest))
+}
+
+fn pak_index_path(key: &str) -> PathBuf {
+    cache_dir().join(format!("{}.pakidx", key))
+}
+
+/// The cached index of a pak, if there is a readable one for this `pak_index_key`
+pub async fn cached_pak_index(key: &str) -> Option<PakFile> {
+    if !enabled() {
+        return None;
+    }
+    load_or_discard(pak_index_path(key), read_versioned).await
+}
+
+/// Caches the parsed index of a pak; failures are logged, not returned
+pub async fn store_pak_index(key: &str, pak: &PakFile) {
+    if !enabled() {
+        return;
+    }
+    let (path, pak) = (pak_index_path(key), pak.clone());
+    let written = tokio::task::spawn_blocking(move || write_versioned(&path, &pak)).await;
+    if let Ok(Err(e)) = written {
+        tracing::warn!("Failed to cache pak index: {:#}", e);
+    }
+}
+
+/// The cached asset index of a pak set, if there is a readable one
+pub async fn cached_assets(digest: &str) -> Option<Vec<Asset>> {
+    if !enabled() {
+        return None;
+    }
+    let path = assets_path(digest);
+    load_or_discard(path, load_assets).await
+}
+
+/// Caches the asset index of a pak set; failures are logged, not returned
+pub async fn store_assets(digest: &str, assets: Vec<Asset>) -> Vec<Asset> {
+    if !enabled() {
+        return assets;
+    }
+    let path = assets_path(digest);
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = save_assets(&path, &assets) {
+            tracing::warn!("Failed to cache asset index: {:#}", e);
+        }
+        assets
+    })
+    .await
+    .expect("cache write task panicked")
+}
+
+/// The cached dependency map of a pak set, if there is a readable one
+pub async fn cached_dependencies(digest: &str) -> Option<DependencyMap> {
+    if !enabled() {
+        return None;
+    }
+    let path = dependencies_path(digest);
+    load_or_discard(path, |path| DependencyMap::load(path)).await
+}
+
+/// Caches the dependency map of a pak set; failures are logged, not returned
+pub async fn store_dependencies(digest: &str, dependencies: &DependencyMap) {
+    if !enabled() {
+        return;
+    }
+    if let Err(e) = dependencies.save(dependencies_path(digest)) {
+        tracing::warn!("Failed to cache dependency map: {:#}", e);
+    }
+}
+
+/// Loads a cache file off the async runtime, deleting it when it's corrupt or outdated
+async fn load_or_discard<T: Send + 'static>(path: PathBuf, load: fn(&Path) -> Result<T>) -> Option<T> {
+    if !path.exists() {
+        return None;
+    }
+    tokio::task::spawn_blocking(move || match load(&path) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("Discarding scan cache {}: {:#}", path.display(), e);
+            let _ = std::fs::remove_file(&path);
+            None
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Deletes every cached asset index, pak index and dependency map, returning how many
+/// files were removed
+pub fn clear() -> Result<usize> {
+    let entries = match std::fs::read_dir(cache_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        if entry.file_type().is_ok_and(|t| t.is_file()) {
+            std::fs::remove_file(entry.path())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,155 @@
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+
+/// Longest search pattern accepted, in bytes
+pub const MAX_PATTERN_LEN: usize = 1024;
+
+/// Memory a compiled regex may take; larger programs are rejected at compile time
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// How a search term is matched against names and paths
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Case-insensitive substring
+    #[default]
+    Substring,
+    /// Case-insensitive regular expression, matching anywhere unless anchored
+    Regex,
+    /// Characters in order with gaps allowed, scored like skim/fzf
+    Fuzzy,
+}
+
+/// A search term compiled for its mode
+///
+/// Regexes are compiled once with size and nesting caps. The regex engine matches in time
+/// linear in the input (no backtracking), so a pattern that compiled can't hang a listing
+/// the way catastrophic backtracking would; the caps bound the other factor, the pattern.
+pub enum TextMatcher {
+    /// Lowercased term
+    Substring(String),
+    Regex(regex::Regex),
+    Fuzzy { pattern: String, matcher: Box<SkimMatcherV2> },
+}
+
+impl TextMatcher {
+    /// Compiles `pattern`; invalid or oversized regexes give the compile error
+    pub fn new(pattern: &str, mode: SearchMode) -> Result<Self, String> {
+        if pattern.len() > MAX_PATTERN_LEN {
+            return Err(format!("Search pattern is longer than {} bytes", MAX_PATTERN_LEN));
+        }
+        Ok(match mode {
+            SearchMode::Substring => TextMatcher::Substring(pattern.to_lowercase()),
+            SearchMode::Regex => {
+                let regex = regex::RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .size_limit(REGEX_SIZE_LIMIT)
+                    .dfa_size_limit(REGEX_SIZE_LIMIT)
+                    .nest_limit(64)
+                    .build()
+                    .map_err(|e| format!("Invalid regex: {}", e))?;
+                TextMatcher::Regex(regex)
+            }
+            SearchMode::Fuzzy => TextMatcher::Fuzzy {
+                pattern: pattern.to_string(),
+                matcher: Box::new(SkimMatcherV2::default().ignore_case()),
+            },
+        })
+    }
+
+    /// Best score of any of `texts`, or None when none matches
+    ///
+    /// Only fuzzy matches are scored (higher is closer); the other modes score 0.
+    pub fn score<'a>(&self, texts: impl IntoIterator<Item = &'a str>) -> Option<i64> {
+        match self {
+            TextMatcher::Substring(term) => texts.into_iter().any(|text| text.to_lowercase().contains(term)).then_some(0),
+            TextMatcher::Regex(regex) => texts.into_iter().any(|text| regex.is_match(text)).then_some(0),
+            TextMatcher::Fuzzy { pattern, matcher } => {
+                texts.into_iter().filter_map(|text| matcher.fuzzy_match(text, pattern)).max()
+            }
+        }
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.score([text]).is_some()
+    }
+
+    pub fn is_fuzzy(&self) -> bool {
+        matches!(self, TextMatcher::Fuzzy { .. })
+    }
+}
+
+/// A `tag:Key=Value` term of a search: the asset needs that registry tag, with that value
+/// when one is given (`tag:Key` alone asks for the tag with any value)
+///
+/// Keys and values compare case-insensitively; values can't contain spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagTerm {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl TagTerm {
+    pub fn matches<'a>(&self, tags: impl IntoIterator<Item = (&'a String, &'a String)>) -> bool {
+        tags.into_iter().any(|(key, value)| {
+            key.eq_ignore_ascii_case(&self.key) && self.value.as_ref().is_none_or(|wanted| value.to_lowercase() == *wanted)
+        })
+    }
+}
+
+/// The `tag:` terms of a search, and the rest of it; the search is returned as it is when
+/// it has no tag terms, and as None when they were all it had
+pub fn split_tag_terms(search: &str) -> Result<(Vec<TagTerm>, Option<String>), String> {
+    if !search.split_whitespace().any(|word| word.starts_with("tag:")) {
+        return Ok((Vec::new(), Some(search.to_string())));
+    }
+    let mut terms = Vec::new();
+    let mut rest = Vec::new();
+    for word in search.split_whitespace() {
+        let Some(term) = word.strip_prefix("tag:") else {
+            rest.push(word);
+            continue;
+        };
+        let (key, value) = match term.split_once('=') {
+            Some((key, value)) => (key, Some(value.to_lowercase())),
+            None => (term, None),
+        };
+        if key.is_empty() {
+            return Err(format!("Tag term {:?} has no key (tag:Key=Value)", word));
+        }
+        terms.push(TagTerm { key: key.to_string(), value });
+    }
+    Ok((terms, (!rest.is_empty()).then(|| rest.join(" "))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn tag_terms_split_from_the_search() {
+        assert_eq!(split_tag_terms("rock wall").unwrap(), (Vec::new(), Some("rock wall".to_string())));
+        let (terms, rest) = split_tag_terms("tag:PrimaryAssetType=Map  rock tag:RowStructure").unwrap();
+        assert_eq!(
+            terms,
+            vec![
+                TagTerm { key: "PrimaryAssetType".to_string(), value: Some("map".to_string()) },
+                TagTerm { key: "RowStructure".to_string(), value: None },
+            ]
+        );
+        assert_eq!(rest.as_deref(), Some("rock"));
+        assert_eq!(split_tag_terms("tag:Key=").unwrap().0[0].value.as_deref(), Some(""));
+        assert_eq!(split_tag_terms("tag:Key").unwrap().1, None);
+        assert!(split_tag_terms("tag:=Map").is_err());
+    }
+
+    #[test]
+    fn tag_terms_match_case_insensitively() {
+        let tags: HashMap<String, String> = [("PrimaryAssetType".to_string(), "Map".to_string())].into();
+        let (terms, _) = split_tag_terms("tag:primaryassettype=MAP tag:PrimaryAssetType tag:RowStructure tag:PrimaryAssetType=Item").unwrap();
+        let matches: Vec<bool> = terms.iter().map(|term| term.matches(&tags)).collect();
+        assert_eq!(matches, [true, true, false, false]);
+    }
+}
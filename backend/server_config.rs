@@ -0,0 +1,189 @@
+use anyhow::{Context, Result};
+use axum::http::{HeaderValue, Method, Uri};
+use serde::Deserialize;
+use std::net::{IpAddr, SocketAddr};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tracing::{info, warn};
+
+/// Where the API server listens unless configured otherwise
+pub const DEFAULT_ADDR: ([u8; 4], u16) = ([127, 0, 0, 1], 3001);
+
+/// Origins allowed by default: the bundled frontend and the Vite dev server
+pub const DEFAULT_ORIGINS: &[&str] = &["tauri://localhost", "http://localhost:1420"];
+
+/// Ports tried, counting the preferred one, before giving up on binding
+pub const PORT_ATTEMPTS: u16 = 10;
+
+/// Address, CORS origins and access token of the HTTP API server
+#[derive(Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    pub addr: SocketAddr,
+    /// Allowed browser origins; "*" allows any
+    pub origins: Vec<String>,
+    /// When set, every route but `/health` needs `Authorization: Bearer <token>`
+    pub token: Option<String>,
+    pub limits: RouteLimits,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::from(DEFAULT_ADDR),
+            origins: DEFAULT_ORIGINS.iter().map(|o| o.to_string()).collect(),
+            token: None,
+            limits: RouteLimits::default(),
+        }
+    }
+}
+
+/// How many requests of a kind run at once, and how many more may wait for a turn
+///
+/// Requests beyond both get 429 with `Retry-After`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct ConcurrencyLimit {
+    pub concurrency: usize,
+    #[serde(default)]
+    pub queue: usize,
+}
+
+/// `[server.limits]` table, e.g. `preview = { concurrency = 4, queue = 16 }`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct RouteLimits {
+    /// `/preview`, `/preview/raw`, `/preview/{asset_name}` and `/previews` together
+    pub preview: ConcurrencyLimit,
+    pub extract: ConcurrencyLimit,
+    /// Scan jobs running at once; a job holds its turn until it finishes
+    pub scan: ConcurrencyLimit,
+}
+
+impl Default for RouteLimits {
+    fn default() -> Self {
+        Self {
+            preview: ConcurrencyLimit { concurrency: 8, queue: 32 },
+            extract: ConcurrencyLimit { concurrency: 4, queue: 16 },
+            scan: ConcurrencyLimit { concurrency: 1, queue: 0 },
+        }
+    }
+}
+
+impl std::fmt::Debug for ServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerConfig")
+            .field("addr", &self.addr)
+            .field("origins", &self.origins)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// `[server]` table, e.g. `addr = "0.0.0.0:8080"` and `origins = ["http://localhost:5173"]`
+#[derive(Default, Deserialize)]
+pub struct ServerSection {
+    addr: Option<String>,
+    origins: Option<Vec<String>>,
+    token: Option<String>,
+    limits: Option<RouteLimits>,
+}
+
+impl ServerConfig {
+    /// Defaults, overridden by the `[server]` table read from `source`, then by
+    /// `PAKSEEK_ADDR`, `PAKSEEK_ORIGINS` (comma-separated) and `PAKSEEK_API_TOKEN`
+    ///
+    /// Route limits only come from the file.
+    ///
+    /// Invalid values are logged and skipped.
+    pub fn load(section: ServerSection, source: &str) -> Self {
+        let mut config = Self::default();
+        config.apply(section.addr.as_deref(), section.origins, source);
+        config.apply_token(section.token);
+        config.limits = section.limits.unwrap_or_default();
+
+        let addr = std::env::var("PAKSEEK_ADDR").ok();
+        let origins = std::env::var("PAKSEEK_ORIGINS")
+            .ok()
+            .map(|list| list.split(',').map(str::trim).filter(|o| !o.is_empty()).map(str::to_string).collect());
+        config.apply(addr.as_deref(), origins, "the environment");
+        config.apply_token(std::env::var("PAKSEEK_API_TOKEN").ok());
+        config
+    }
+
+    fn apply_token(&mut self, token: Option<String>) {
+        if let Some(token) = token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+            self.token = Some(token);
+        }
+    }
+
+    fn apply(&mut self, addr: Option<&str>, origins: Option<Vec<String>>, source: &str) {
+        if let Some(addr) = addr {
+            match parse_addr(addr, self.addr) {
+                Some(parsed) => self.addr = parsed,
+                None => warn!("Ignoring invalid API address {:?} from {}", addr, source),
+            }
+        }
+        if let Some(origins) = origins {
+            self.origins = origins;
+        }
+    }
+
+    /// CORS for the configured origins; invalid ones are logged and left out
+    pub fn cors(&self) -> CorsLayer {
+        let cors = CorsLayer::new()
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_headers(Any);
+        if self.origins.iter().any(|origin| origin == "*") {
+            return cors.allow_origin(Any);
+        }
+
+        let origins: Vec<HeaderValue> = self
+            .origins
+            .iter()
+            .filter_map(|origin| {
+                let valid = origin.parse::<Uri>().is_ok_and(|uri| uri.scheme().is_some() && uri.authority().is_some());
+                let value = valid.then(|| HeaderValue::from_str(origin).ok()).flatten();
+                if value.is_none() {
+                    warn!("Skipping invalid CORS origin {:?}", origin);
+                }
+                value
+            })
+            .collect();
+        cors.allow_origin(AllowOrigin::list(origins))
+    }
+
+    /// Binds the configured address, moving up one port at a time while it's taken
+    pub async fn bind(&self) -> Result<tokio::net::TcpListener> {
+        let mut last_error = None;
+        for offset in 0..PORT_ATTEMPTS {
+            let Some(port) = self.addr.port().checked_add(offset) else { break };
+            let addr = SocketAddr::new(self.addr.ip(), port);
+            match tokio::net::TcpListener::bind(addr).await {
+                Ok(listener) => {
+                    if offset > 0 {
+                        info!("Port {} is taken, using {}", self.addr.port(), port);
+                    }
+                    return Ok(listener);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => last_error = Some(e),
+                Err(e) => return Err(e).with_context(|| format!("Failed to bind {}", addr)),
+            }
+        }
+        let error = last_error.map_or_else(|| anyhow::anyhow!("no port to try"), anyhow::Error::from);
+        Err(error.context(format!("Ports {} to {} are taken", self.addr.port(), self.addr.port().saturating_add(PORT_ATTEMPTS - 1))))
+    }
+}
+
+/// "host:port", a bare port (keeping the host of `current`) or a bare host (keeping its port)
+fn parse_addr(value: &str, current: SocketAddr) -> Option<SocketAddr> {
+    let value = value.trim();
+    let value = match value.strip_prefix("localhost") {
+        Some(port) => format!("127.0.0.1{}", port),
+        None => value.to_string(),
+    };
+    if let Ok(addr) = value.parse::<SocketAddr>() {
+        return Some(addr);
+    }
+    if let Ok(port) = value.trim_start_matches(':').parse::<u16>() {
+        return Some(SocketAddr::new(current.ip(), port));
+    }
+    value.parse::<IpAddr>().ok().map(|ip| SocketAddr::new(ip, current.port()))
+}
@@ -0,0 +1,669 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use tracing::warn;
+
+use crate::api_error::{ApiError, ErrorCode};
+use crate::preview_cache::DEFAULT_CACHE_BUDGET_BYTES;
+
+/// Key of the AES key used for paks without a key GUID, or whose GUID has no key of its own
+pub const WILDCARD_KEY: &str = "*";
+
+/// Folders kept in `last_folders`
+pub const MAX_RECENT_FOLDERS: usize = 15;
+
+/// What key values are replaced with when settings are sent back without `reveal_keys`
+///
+/// Sending it back in a patch keeps the stored key.
+pub const REDACTED: &str = "<redacted>";
+
+/// Per-user settings, stored in the `[settings]` table of `pakseek.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// AES-256 keys ("0x" and 64 hex digits) by key GUID (32 hex digits, as in crypto.json), or "*"
+    pub aes_keys: BTreeMap<String, String>,
+    /// AES keys for single containers by path (.pak, .utoc or .ucas), ahead of `aes_keys`
+    pub pak_keys: BTreeMap<String, String>,
+    /// Oodle library (oo2core_9_win64.dll, liboo2corelinux64.so.9, ...) to decompress with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oodle_path: Option<PathBuf>,
+    /// Mappings (.usmap) to read the unversioned properties of cooked UE5 packages with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usmap_path: Option<PathBuf>,
+    pub preview_cache_mb: u64,
+    /// Paks whose assets are read at once during deep scans
+    pub scan_concurrency: usize,
+    /// Folders scanned most recently, newest first
+    pub last_folders: Vec<String>,
+    /// Folders pinned with `pin_favorite`, in pinning order
+    pub favorites: Vec<String>,
+    /// Naming-convention prefixes ("PFX_") dropped from display names, besides the UE ones
+    pub name_prefixes: Vec<String>,
+    /// Asset registry tags kept on the assets of listings; the detail view has them all
+    pub listed_tags: Vec<String>,
+    /// Profile whose keys, Oodle library and mappings are the ones above; changes to those
+    /// are saved to it too
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    /// Game profiles by name
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            aes_keys: BTreeMap::new(),
+            pak_keys: BTreeMap::new(),
+            oodle_path: None,
+            usmap_path: None,
+            preview_cache_mb: DEFAULT_CACHE_BUDGET_BYTES / (1024 * 1024),
+            scan_concurrency: crate::asset_metadata::DEFAULT_SCAN_CONCURRENCY,
+            last_folders: Vec::new(),
+            favorites: Vec::new(),
+            name_prefixes: Vec::new(),
+            listed_tags: crate::asset_registry::DEFAULT_LISTED_TAGS.iter().map(|tag| tag.to_string()).collect(),
+            active_profile: None,
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// A copy with every AES key replaced by `REDACTED`, the profiles' too
+    pub fn redacted(&self) -> Self {
+        let mut settings = self.clone();
+        for key in settings.aes_keys.values_mut().chain(settings.pak_keys.values_mut()) {
+            *key = REDACTED.to_string();
+        }
+        settings.profiles = settings.profiles.iter().map(|(name, profile)| (name.clone(), profile.redacted())).collect();
+        settings
+    }
+
+    /// The profiles and which one is active, as `list_profiles` returns them
+    pub fn profile_list(&self) -> ProfileList {
+        ProfileList { active: self.active_profile.clone(), profiles: self.profiles.clone() }
+    }
+
+    /// Copies the keys, Oodle library and mappings into the active profile, so what's
+    /// changed while it's active is there the next time it's activated
+    fn sync_active_profile(&mut self) {
+        let Some(profile) = self.active_profile.as_ref().and_then(|name| self.profiles.get_mut(name)) else {
+            return;
+        };
+        profile.aes_keys = self.aes_keys.clone();
+        profile.pak_keys = self.pak_keys.clone();
+        profile.oodle_path = self.oodle_path.clone();
+        profile.usmap_path = self.usmap_path.clone();
+    }
+
+    /// The key for paks encrypted with `guid` (None for the default key), else the wildcard key
+    pub fn aes_key(&self, guid: Option<&str>) -> Option<&str> {
+        guid.and_then(|guid| self.aes_keys.get(&guid.to_ascii_uppercase()))
+            .or_else(|| self.aes_keys.get(WILDCARD_KEY))
+            .map(String::as_str)
+    }
+
+    /// The key for the container at `path`: its own key, else the one for `guid`, else the
+    /// wildcard key
+    ///
+    /// A key set for any of a container's .pak, .utoc and .ucas applies to all three, since
+    /// an IoStore container and its .pak stub share one key.
+    pub fn key_for(&self, path: &str, guid: Option<&str>) -> Option<&str> {
+        self.pak_keys
+            .iter()
+            .find(|(pak, _)| same_path(&container_stem(pak), &container_stem(path)))
+            .map(|(_, key)| key.as_str())
+            .or_else(|| self.aes_key(guid))
+    }
+
+    /// Every key assignment, per container first, then by GUID, with the wildcard key last
+    pub fn key_assignments(&self) -> Vec<KeyAssignment> {
+        let pak = self.pak_keys.iter().map(|(pak, key)| KeyAssignment { target: pak.clone(), scope: KeyScope::Pak, key: key.clone() });
+        let guid = self.aes_keys.iter().filter(|(guid, _)| *guid != WILDCARD_KEY).map(|(guid, key)| KeyAssignment {
+            target: guid.clone(),
+            scope: KeyScope::Guid,
+            key: key.clone(),
+        });
+        let default = self.aes_keys.get(WILDCARD_KEY).map(|key| KeyAssignment {
+            target: WILDCARD_KEY.to_string(),
+            scope: KeyScope::Default,
+            key: key.clone(),
+        });
+        pak.chain(guid).chain(default).collect()
+    }
+
+    /// The configured Oodle library, if it exists
+    pub fn oodle_library(&self) -> Option<&Path> {
+        self.oodle_path.as_deref().filter(|path| path.is_file())
+    }
+
+    /// The recent and favorite folders, flagging the ones that no longer exist
+    pub fn folders(&self) -> RecentFolders {
+        let entries = |folders: &[String]| {
+            folders
+                .iter()
+                .map(|path| FolderEntry {
+                    path: path.clone(),
+                    exists: Path::new(path).is_dir(),
+                    pinned: self.favorites.iter().any(|favorite| same_path(favorite, path)),
+                })
+                .collect()
+        };
+        RecentFolders { recent: entries(&self.last_folders), favorites: entries(&self.favorites) }
+    }
+}
+
+/// What a key set with `set_pak_key` applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyScope {
+    /// One container, by path
+    Pak,
+    /// Every container encrypted with a key GUID
+    Guid,
+    /// Containers with no key of their own ("*")
+    Default,
+}
+
+/// An AES key and what it's used for, as listed by `list_pak_keys`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyAssignment {
+    /// Container path, key GUID or "*"
+    pub target: String,
+    pub scope: KeyScope,
+    pub key: String,
+}
+
+/// What one game needs: its keys, mappings and Oodle library, and the folders it's in
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Engine version the game was cooked with ("5.3"), for the user's reference
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_version: Option<String>,
+    /// AES keys by key GUID or "*", as in `Settings::aes_keys`
+    pub aes_keys: BTreeMap<String, String>,
+    pub pak_keys: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oodle_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usmap_path: Option<PathBuf>,
+    /// Folders (or paks) mounted when the profile is activated with a rescan
+    pub mount_folders: Vec<String>,
+}
+
+impl Profile {
+    /// The keys, Oodle library and mappings of `settings`, with the folders given
+    pub fn from_settings(settings: &Settings, engine_version: Option<String>, mount_folders: Vec<String>) -> Self {
+        Self {
+            engine_version,
+            aes_keys: settings.aes_keys.clone(),
+            pak_keys: settings.pak_keys.clone(),
+            oodle_path: settings.oodle_path.clone(),
+            usmap_path: settings.usmap_path.clone(),
+            mount_folders,
+        }
+    }
+
+    /// A copy with every AES key replaced by `REDACTED`
+    pub fn redacted(&self) -> Self {
+        let mut profile = self.clone();
+        for key in profile.aes_keys.values_mut().chain(profile.pak_keys.values_mut()) {
+            *key = REDACTED.to_string();
+        }
+        profile
+    }
+}
+
+/// A profile to create with `create_profile`; paths have to exist, except the folders
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProfileSpec {
+    pub engine_version: Option<String>,
+    /// Keys by GUID or "*", in hex ("0x" optional) or base64
+    pub aes_keys: BTreeMap<String, String>,
+    pub oodle_path: Option<String>,
+    pub usmap_path: Option<String>,
+    pub mount_folders: Vec<String>,
+}
+
+impl ProfileSpec {
+    /// The profile with its keys normalized; invalid values are `InvalidRequest`, naming the field
+    pub fn into_profile(self) -> Result<Profile, ApiError> {
+        let mut aes_keys = BTreeMap::new();
+        for (guid, key) in self.aes_keys {
+            let guid = normalize_guid(&guid)
+                .ok_or_else(|| invalid("aes_keys", format!("{:?} is neither a key GUID nor \"*\"", guid)))?;
+            let key = normalize_key(&key)
+                .ok_or_else(|| invalid("aes_keys", format!("The key for {} isn't a 32-byte AES key in hex or base64", guid)))?;
+            aes_keys.insert(guid, key);
+        }
+        let file = |field: &str, path: Option<String>| -> Result<Option<PathBuf>, ApiError> {
+            let Some(path) = path.map(|path| path.trim().to_string()).filter(|path| !path.is_empty()) else {
+                return Ok(None);
+            };
+            if !Path::new(&path).is_file() {
+                return Err(invalid(field, format!("{} is not a file", path)));
+            }
+            Ok(Some(PathBuf::from(path)))
+        };
+        let mut mount_folders: Vec<String> = Vec::with_capacity(self.mount_folders.len());
+        for folder in self.mount_folders.iter().map(|folder| folder.trim()).filter(|folder| !folder.is_empty()) {
+            if !mount_folders.iter().any(|kept| same_path(kept, folder)) {
+                mount_folders.push(folder.to_string());
+            }
+        }
+        Ok(Profile {
+            engine_version: self.engine_version.map(|version| version.trim().to_string()).filter(|version| !version.is_empty()),
+            aes_keys,
+            pak_keys: BTreeMap::new(),
+            oodle_path: file("oodle_path", self.oodle_path)?,
+            usmap_path: file("usmap_path", self.usmap_path)?,
+            mount_folders,
+        })
+    }
+}
+
+/// The game profiles, as `list_profiles` returns them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileList {
+    /// Name of the active profile, if one was activated
+    pub active: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// A recent or favorite folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderEntry {
+    pub path: String,
+    /// False once the folder is moved or deleted; it's kept so the UI can gray it out
+    pub exists: bool,
+    pub pinned: bool,
+}
+
+/// Folders to offer when picking what to scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentFolders {
+    /// Newest first, at most `MAX_RECENT_FOLDERS`
+    pub recent: Vec<FolderEntry>,
+    pub favorites: Vec<FolderEntry>,
+}
+
+/// Whether two paths are the same, ignoring trailing separators (and case on Windows)
+fn same_path(a: &str, b: &str) -> bool {
+    let trim = |path: &str| path.trim_end_matches(['/', '\\']).replace('\\', "/");
+    if cfg!(windows) {
+        trim(a).eq_ignore_ascii_case(&trim(b))
+    } else {
+        trim(a) == trim(b)
+    }
+}
+
+/// `path` without its .pak, .utoc or .ucas extension
+fn container_stem(path: &str) -> String {
+    let lower = path.to_ascii_lowercase();
+    match [".pak", ".utoc", ".ucas"].iter().find(|extension| lower.ends_with(*extension)) {
+        Some(extension) => path[..path.len() - extension.len()].to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// Changes to apply with `update_settings`; absent fields are left as they are
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SettingsPatch {
+    /// Keys to add or replace by GUID or "*"; null removes one. Hex ("0x" optional) or base64.
+    pub aes_keys: Option<BTreeMap<String, Option<String>>>,
+    /// Empty to forget the configured library
+    pub oodle_path: Option<String>,
+    /// Empty to forget the mappings file
+    pub usmap_path: Option<String>,
+    pub preview_cache_mb: Option<u64>,
+    pub scan_concurrency: Option<usize>,
+    pub last_folders: Option<Vec<String>>,
+    /// Replaces the list; blank entries are dropped
+    pub name_prefixes: Option<Vec<String>>,
+    /// Replaces the list; blank entries are dropped, and an empty list keeps no tags
+    pub listed_tags: Option<Vec<String>>,
+}
+
+impl SettingsPatch {
+    /// `settings` with the patch applied; invalid values are `InvalidRequest`, naming the field
+    fn apply(self, settings: &Settings) -> Result<Settings, ApiError> {
+        let mut updated = settings.clone();
+        for (guid, key) in self.aes_keys.unwrap_or_default() {
+            let guid = normalize_guid(&guid)
+                .ok_or_else(|| invalid("aes_keys", format!("{:?} is neither a key GUID nor \"*\"", guid)))?;
+            match key {
+                None => {
+                    updated.aes_keys.remove(&guid);
+                }
+                Some(key) if key == REDACTED => {}
+                Some(key) => {
+                    let key = normalize_key(&key)
+                        .ok_or_else(|| invalid("aes_keys", format!("The key for {} isn't a 32-byte AES key in hex or base64", guid)))?;
+                    updated.aes_keys.insert(guid, key);
+                }
+            }
+        }
+        if let Some(path) = self.oodle_path {
+            let path = path.trim();
+            updated.oodle_path = (!path.is_empty()).then(|| PathBuf::from(path));
+            if let Some(path) = updated.oodle_path.as_deref().filter(|path| !path.is_file()) {
+                return Err(invalid("oodle_path", format!("{} is not a file", path.display())));
+            }
+        }
+        if let Some(path) = self.usmap_path {
+            let path = path.trim();
+            updated.usmap_path = (!path.is_empty()).then(|| PathBuf::from(path));
+            if let Some(path) = updated.usmap_path.as_deref().filter(|path| !path.is_file()) {
+                return Err(invalid("usmap_path", format!("{} is not a file", path.display())));
+            }
+        }
+        if let Some(mb) = self.preview_cache_mb {
+            updated.preview_cache_mb = mb;
+        }
+        if let Some(concurrency) = self.scan_concurrency {
+            if concurrency == 0 {
+                return Err(invalid("scan_concurrency", "Scan concurrency must be at least 1".to_string()));
+            }
+            updated.scan_concurrency = concurrency;
+        }
+        if let Some(folders) = self.last_folders {
+            updated.last_folders = folders;
+        }
+        if let Some(prefixes) = self.name_prefixes {
+            let mut kept: Vec<String> = Vec::with_capacity(prefixes.len());
+            for prefix in prefixes.iter().map(|prefix| prefix.trim()).filter(|prefix| !prefix.is_empty()) {
+                if prefix.contains(['/', '\\', '.']) {
+                    return Err(invalid("name_prefixes", format!("{:?} isn't a file name prefix", prefix)));
+                }
+                if !kept.iter().any(|kept| kept == prefix) {
+                    kept.push(prefix.to_string());
+                }
+            }
+            updated.name_prefixes = kept;
+        }
+        if let Some(tags) = self.listed_tags {
+            let mut kept: Vec<String> = Vec::with_capacity(tags.len());
+            for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+                if !kept.iter().any(|kept| kept.eq_ignore_ascii_case(tag)) {
+                    kept.push(tag.to_string());
+                }
+            }
+            updated.listed_tags = kept;
+        }
+        Ok(updated)
+    }
+}
+
+fn invalid(field: &str, message: String) -> ApiError {
+    ApiError::new(ErrorCode::InvalidRequest, message).with_context(json!({ "field": field }))
+}
+
+/// "*", or a GUID as 32 hex digits (dashes, braces and "0x" allowed), uppercased
+fn normalize_guid(guid: &str) -> Option<String> {
+    let guid = guid.trim();
+    if guid == WILDCARD_KEY {
+        return Some(guid.to_string());
+    }
+    let hex: String = guid
+        .trim_start_matches("0x")
+        .chars()
+        .filter(|c| !matches!(c, '-' | '{' | '}'))
+        .collect();
+    (hex.len() == 32 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| hex.to_ascii_uppercase())
+}
+
+/// A 32-byte key from hex ("0x" optional) or base64, as "0x" and uppercase hex
+fn normalize_key(key: &str) -> Option<String> {
+    parse_key(key).map(|bytes| format!("0x{}", bytes.iter().map(|b| format!("{:02X}", b)).collect::<String>()))
+}
+
+/// The bytes of a 32-byte AES key in hex ("0x" optional) or base64
+pub fn parse_key(key: &str) -> Option<[u8; 32]> {
+    let key = key.trim();
+    let hex = key.strip_prefix("0x").or_else(|| key.strip_prefix("0X")).unwrap_or(key);
+    let bytes = if hex.len() == 64 && hex.is_ascii() {
+        (0..32).map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)).collect::<Result<Vec<u8>, _>>().ok()?
+    } else {
+        base64::engine::general_purpose::STANDARD.decode(key).ok()?
+    };
+    bytes.try_into().ok()
+}
+
+/// Settings shared by the whole process, and the config file they're saved to
+///
+/// Readers take a copy with `get`, so changes apply from the next read without restarting.
+pub struct SettingsStore {
+    path: PathBuf,
+    current: RwLock<Settings>,
+}
+
+impl SettingsStore {
+    /// `settings`, read from the `[settings]` table of the config file at `path`
+    pub fn new(path: PathBuf, settings: Settings) -> Self {
+        Self { path, current: RwLock::new(settings) }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Applies `patch`, saves the result and makes it current; returns the new settings
+    ///
+    /// Nothing changes when a value is invalid or the file can't be written.
+    pub fn update(&self, patch: SettingsPatch) -> Result<Settings, ApiError> {
+        self.modify(|current| patch.apply(current))
+    }
+
+    /// Moves `folder` to the front of `last_folders`, dropping the oldest beyond `MAX_RECENT_FOLDERS`
+    pub fn push_recent_folder(&self, folder: &str) -> Result<Settings, ApiError> {
+        self.modify(|current| {
+            let mut updated = current.clone();
+            updated.last_folders.retain(|recent| !same_path(recent, folder));
+            updated.last_folders.insert(0, folder.to_string());
+            updated.last_folders.truncate(MAX_RECENT_FOLDERS);
+            Ok(updated)
+        })
+    }
+
+    /// Adds `folder` to the favorites, unless it's already one
+    pub fn pin_favorite(&self, folder: &str) -> Result<Settings, ApiError> {
+        self.modify(|current| {
+            let mut updated = current.clone();
+            if !updated.favorites.iter().any(|favorite| same_path(favorite, folder)) {
+                updated.favorites.push(folder.to_string());
+            }
+            Ok(updated)
+        })
+    }
+
+    pub fn unpin_favorite(&self, folder: &str) -> Result<Settings, ApiError> {
+        self.modify(|current| {
+            let mut updated = current.clone();
+            updated.favorites.retain(|favorite| !same_path(favorite, folder));
+            Ok(updated)
+        })
+    }
+
+    /// Sets the key of a container (by path), of a key GUID, or the wildcard key ("*");
+    /// None removes it
+    ///
+    /// Anything that isn't a GUID or "*" has to be an existing .pak, .utoc or .ucas.
+    pub fn set_pak_key(&self, target: &str, key: Option<&str>) -> Result<Settings, ApiError> {
+        let key = key
+            .map(|key| normalize_key(key).ok_or_else(|| invalid("key", "Not a 32-byte AES key in hex or base64".to_string())))
+            .transpose()?;
+        let target = target.trim();
+        if let Some(guid) = normalize_guid(target) {
+            return self.modify(|current| {
+                let mut updated = current.clone();
+                match key {
+                    Some(key) => updated.aes_keys.insert(guid, key),
+                    None => updated.aes_keys.remove(&guid),
+                };
+                Ok(updated)
+            });
+        }
+        let is_container = matches!(
+            Path::new(target).extension().and_then(|extension| extension.to_str()).map(str::to_ascii_lowercase).as_deref(),
+            Some("pak" | "utoc" | "ucas")
+        );
+        if !is_container || (key.is_some() && !Path::new(target).is_file()) {
+            return Err(invalid("target", format!("{} is neither a key GUID, \"*\" nor a .pak, .utoc or .ucas file", target)));
+        }
+        self.modify(|current| {
+            let mut updated = current.clone();
+            updated.pak_keys.retain(|pak, _| !same_path(&container_stem(pak), &container_stem(target)));
+            if let Some(key) = key {
+                updated.pak_keys.insert(target.to_string(), key);
+            }
+            Ok(updated)
+        })
+    }
+
+    /// Adds the profile `name`; a name already taken is `PROFILE_EXISTS`
+    pub fn create_profile(&self, name: &str, profile: Profile) -> Result<Settings, ApiError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(invalid("name", "Profiles need a name".to_string()));
+        }
+        self.modify(|current| {
+            if current.profiles.contains_key(name) {
+                let message = format!("There's a profile named {} already", name);
+                return Err(ApiError::new(ErrorCode::ProfileExists, message).with_context(json!({ "name": name })));
+            }
+            let mut updated = current.clone();
+            updated.profiles.insert(name.to_string(), profile);
+            Ok(updated)
+        })
+    }
+
+    /// Makes `name` the active profile: its keys, Oodle library and mappings replace the
+    /// ones in use, which are kept only if they were another profile's
+    pub fn activate_profile(&self, name: &str) -> Result<Settings, ApiError> {
+        self.modify(|current| {
+            let profile = current.profiles.get(name).ok_or_else(|| {
+                let profiles: Vec<&String> = current.profiles.keys().collect();
+                ApiError::new(ErrorCode::ProfileNotFound, format!("No profile named {}", name))
+                    .with_context(json!({ "name": name, "profiles": profiles }))
+            })?;
+            let mut updated = current.clone();
+            updated.aes_keys = profile.aes_keys.clone();
+            updated.pak_keys = profile.pak_keys.clone();
+            updated.oodle_path = profile.oodle_path.clone();
+            updated.usmap_path = profile.usmap_path.clone();
+            updated.active_profile = Some(name.to_string());
+            Ok(updated)
+        })
+    }
+
+    /// Saves what `change` makes of the current settings and makes it current
+    fn modify(&self, change: impl FnOnce(&Settings) -> Result<Settings, ApiError>) -> Result<Settings, ApiError> {
+        let mut current = self.current.write().unwrap();
+        let mut updated = change(&current)?;
+        updated.sync_active_profile();
+        self.save(&updated).map_err(|e| {
+            ApiError::new(ErrorCode::SettingsWriteFailed, format!("{:#}", e))
+                .with_context(json!({ "path": self.path.display().to_string() }))
+        })?;
+        *current = updated.clone();
+        Ok(updated)
+    }
+
+    /// Writes the current settings, for when they came from somewhere other than the file
+    pub fn save_current(&self) -> Result<()> {
+        self.save(&self.get())
+    }
+
+    /// Replaces the `[settings]` table of the config file, keeping the rest of it and its
+    /// comments as they are
+    ///
+    /// Writes through a temporary file created readable only by the user, since it holds
+    /// keys. A file that doesn't parse is left alone rather than overwritten.
+    fn save(&self, settings: &Settings) -> Result<()> {
+        let mut document = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents
+                .parse::<toml_edit::DocumentMut>()
+                .with_context(|| format!("{} has an error to fix first", self.path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml_edit::DocumentMut::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        };
+        let table = settings_table(settings)?;
+        match document.get_mut("settings").and_then(toml_edit::Item::as_table_mut) {
+            Some(existing) => {
+                existing.clear();
+                for (key, item) in table {
+                    existing.insert(&key, item);
+                }
+            }
+            None => document["settings"] = toml_edit::Item::Table(table),
+        }
+        let contents = document.to_string();
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let temp = self.path.with_extension("toml.part");
+        // The mode only applies to new files, so a leftover from an interrupted save goes first
+        let _ = std::fs::remove_file(&temp);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&temp)
+            .and_then(|mut file| std::io::Write::write_all(&mut file, contents.as_bytes()))
+            .with_context(|| format!("Failed to write {}", temp.display()))?;
+        std::fs::rename(&temp, &self.path).with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// `settings` as a TOML table, its non-empty maps as subtables (`[settings.aes_keys]`,
+/// `[settings.profiles.Name]`, ...)
+fn settings_table(settings: &Settings) -> Result<toml_edit::Table> {
+    let mut table = toml_edit::ser::to_document(settings)?.as_table().clone();
+    expand_maps(&mut table);
+    Ok(table)
+}
+
+fn expand_maps(table: &mut toml_edit::Table) {
+    for (_, item) in table.iter_mut() {
+        if let Some(map) = item.as_inline_table().filter(|map| !map.is_empty()).cloned() {
+            let mut subtable = map.into_table();
+            expand_maps(&mut subtable);
+            *item = toml_edit::Item::Table(subtable);
+        }
+    }
+}
+
+static SETTINGS: OnceLock<SettingsStore> = OnceLock::new();
+
+/// Makes `store` the process-wide settings; `config::Config::load` does this at startup
+pub fn init(store: SettingsStore) {
+    if SETTINGS.set(store).is_err() {
+        warn!("Settings were already loaded; keeping those");
+    }
+}
+
+/// The process-wide settings, from the `[settings]` table of `pakseek.toml`
+pub fn global() -> &'static SettingsStore {
+    SETTINGS.get_or_init(|| {
+        let path = crate::config::config_path();
+        let settings = crate::config::read_file(path).settings.unwrap_or_default();
+        SettingsStore::new(path.to_path_buf(), settings)
+    })
+}
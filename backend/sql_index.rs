@@ -0,0 +1,581 @@
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, Transaction};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use tracing::warn;
+
+use crate::preview::{Asset, AssetIndex};
+
+/// Schema changes, applied in order; `PRAGMA user_version` is how many a file has had
+///
+/// Only append. A change to what the rows hold should end with `DELETE FROM paks;` and the
+/// rows beneath, so the next sync writes every pak again.
+const MIGRATIONS: &[&str] = &[r#"
+    CREATE TABLE paks (
+        id INTEGER PRIMARY KEY,
+        -- '' for assets outside any pak
+        pak_file TEXT NOT NULL UNIQUE,
+        -- Position of the pak's first asset in the listing
+        start INTEGER NOT NULL,
+        -- SHA-256 of the pak's rows and their positions from `start`, to tell which paks
+        -- a rescan changed
+        digest TEXT NOT NULL
+    );
+    CREATE TABLE assets (
+        id INTEGER PRIMARY KEY,
+        pak_id INTEGER NOT NULL REFERENCES paks(id),
+        -- Position in the listing
+        pos INTEGER NOT NULL,
+        asset_id TEXT NOT NULL,
+        path TEXT NOT NULL,
+        name TEXT NOT NULL,
+        asset_type TEXT NOT NULL,
+        size INTEGER NOT NULL,
+        compressed_size INTEGER,
+        compression TEXT,
+        encrypted INTEGER NOT NULL,
+        -- File name of the pak, NULL outside any
+        pak_name TEXT,
+        hash TEXT
+    );
+    CREATE INDEX assets_pak ON assets(pak_id);
+    CREATE INDEX assets_pos ON assets(pos);
+    CREATE INDEX assets_type ON assets(asset_type);
+    CREATE INDEX assets_size ON assets(size);
+    CREATE TABLE asset_extensions (
+        asset INTEGER NOT NULL,
+        extension TEXT NOT NULL,
+        PRIMARY KEY (asset, extension)
+    ) WITHOUT ROWID;
+    CREATE INDEX asset_extensions_extension ON asset_extensions(extension);
+    -- Lowercased name and path with "/" separators, as the in-memory search compares them
+    CREATE VIRTUAL TABLE assets_fts USING fts5(name, path, tokenize = 'trigram case_sensitive 1');
+"#];
+
+/// Where the database is kept: beside the scan cache, not in it, since clearing that
+/// removes every file there
+pub fn default_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("pakseek")
+        .join("assets.sqlite")
+}
+
+/// The listing mirrored into SQLite, so a search or filter over hundreds of thousands of
+/// assets is an indexed query rather than a pass over all of them
+///
+/// The rows follow the listing by a sync after each scan or mount change; only the paks
+/// whose assets changed are written again. Until a sync of the current listing is done,
+/// `query` says so and listings are served from memory.
+pub struct SqlIndex {
+    conn: Mutex<Connection>,
+    /// The listing the rows hold
+    synced: Mutex<Weak<AssetIndex>>,
+    /// The newest listing handed to `want`, the only one worth syncing
+    latest: Mutex<Weak<AssetIndex>>,
+}
+
+/// What `sync` changed
+#[derive(Debug, Default)]
+pub struct SyncSummary {
+    pub paks_written: usize,
+    pub paks_unchanged: usize,
+    pub paks_removed: usize,
+    pub assets_written: usize,
+}
+
+/// Filters of a listing, as `AssetMatcher` holds them; every one given must match
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// `AssetType::as_str` of any type that matches; empty for any type
+    pub types: Vec<String>,
+    /// Lowercase, with "/" separators; found anywhere in the name or path
+    pub search: Option<String>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// Matched case-insensitively
+    pub compression: Option<String>,
+    pub encrypted: Option<bool>,
+    /// Full path or file name of the pak
+    pub pak: Option<String>,
+    /// Without the dot, in ASCII lowercase
+    pub extension: Option<String>,
+}
+
+/// A page of a listing with the counts `AssetsResponse` needs
+#[derive(Debug, Default)]
+pub struct Page {
+    /// Ids of the page's assets, in order
+    pub ids: Vec<String>,
+    /// Assets passing the filter
+    pub filtered: usize,
+    /// Assets passing every filter but their own dimension's, by type, pak file name and
+    /// extension
+    pub by_type: HashMap<String, usize>,
+    pub by_pak: HashMap<String, usize>,
+    pub by_extension: HashMap<String, usize>,
+}
+
+/// The filter each facet leaves out
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Facet {
+    Type,
+    Pak,
+    Extension,
+}
+
+impl SqlIndex {
+    /// Opens (or creates) the database at `path` and brings its schema up to date
+    ///
+    /// A file from a newer PakSeek is thrown away and started over; the rows are only a
+    /// copy of what the next scan finds.
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let mut conn = Connection::open(path)?;
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > MIGRATIONS.len() {
+            warn!("{} is from a newer version (schema {}); starting it over", path.display(), version);
+            drop(conn);
+            for suffix in ["", "-wal", "-shm"] {
+                let mut file = path.as_os_str().to_owned();
+                file.push(suffix);
+                let _ = std::fs::remove_file(file);
+            }
+            conn = Connection::open(path)?;
+        }
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        migrate(&mut conn)?;
+        conn.create_collation("natural_order", crate::natural_cmp)?;
+        Ok(SqlIndex {
+            conn: Mutex::new(conn),
+            synced: Mutex::new(Weak::new()),
+            latest: Mutex::new(Weak::new()),
+        })
+    }
+
+    /// Marks `assets` as the listing to sync, so a sync of an older one still waiting to
+    /// run is skipped
+    pub fn want(&self, assets: &Arc<AssetIndex>) {
+        *self.latest.lock().unwrap() = Arc::downgrade(assets);
+    }
+
+    /// Whether the rows hold `assets`
+    pub fn mirrors(&self, assets: &Arc<AssetIndex>) -> bool {
+        std::ptr::eq(self.synced.lock().unwrap().as_ptr(), Arc::as_ptr(assets))
+    }
+
+    /// Makes the rows match `assets`, writing only the paks whose assets changed; None
+    /// when a newer listing was handed to `want` since, as that one's sync will do
+    ///
+    /// Blocking; run it off the async workers.
+    pub fn sync(&self, assets: &Arc<AssetIndex>) -> rusqlite::Result<Option<SyncSummary>> {
+        let mut conn = self.conn.lock().unwrap();
+        if !std::ptr::eq(self.latest.lock().unwrap().as_ptr(), Arc::as_ptr(assets)) {
+            return Ok(None);
+        }
+        *self.synced.lock().unwrap() = Weak::new();
+
+        // Paks in the order their first asset comes in the listing, with the positions of their assets
+        let mut groups: Vec<(&str, Vec<(usize, &Asset)>)> = Vec::new();
+        let mut group_of: HashMap<&str, usize> = HashMap::new();
+        for (pos, asset) in assets.iter().enumerate() {
+            let pak_file = asset.pak_file.as_deref().unwrap_or("");
+            let group = *group_of.entry(pak_file).or_insert_with(|| {
+                groups.push((pak_file, Vec::new()));
+                groups.len() - 1
+            });
+            groups[group].1.push((pos, asset));
+        }
+
+        let tx = conn.transaction()?;
+        let mut existing: HashMap<String, (i64, i64, String)> = HashMap::new();
+        {
+            let mut stmt = tx.prepare("SELECT id, pak_file, start, digest FROM paks")?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(1)?, (row.get(0)?, row.get(2)?, row.get(3)?))))?;
+            for row in rows {
+                let (pak_file, pak) = row?;
+                existing.insert(pak_file, pak);
+            }
+        }
+
+        let mut summary = SyncSummary::default();
+        for (pak_file, (pak_id, _, _)) in &existing {
+            if !group_of.contains_key(pak_file.as_str()) {
+                delete_rows(&tx, *pak_id)?;
+                tx.execute("DELETE FROM paks WHERE id = ?1", [pak_id])?;
+                summary.paks_removed += 1;
+            }
+        }
+        for (pak_file, group) in &groups {
+            let start = group[0].0 as i64;
+            let digest = digest(group);
+            let pak_id = match existing.get(*pak_file) {
+                // Same rows in the same layout; only where the pak starts may have moved
+                Some((pak_id, old_start, old)) if *old == digest => {
+                    if start != *old_start {
+                        tx.execute("UPDATE assets SET pos = pos + ?1 WHERE pak_id = ?2", params![start - old_start, pak_id])?;
+                        tx.execute("UPDATE paks SET start = ?1 WHERE id = ?2", params![start, pak_id])?;
+                    }
+                    summary.paks_unchanged += 1;
+                    continue;
+                }
+                Some((pak_id, _, _)) => {
+                    delete_rows(&tx, *pak_id)?;
+                    tx.execute("UPDATE paks SET start = ?1, digest = ?2 WHERE id = ?3", params![start, digest, pak_id])?;
+                    *pak_id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO paks (pak_file, start, digest) VALUES (?1, ?2, ?3)",
+                        params![pak_file, start, digest],
+                    )?;
+                    tx.last_insert_rowid()
+                }
+            };
+            insert_rows(&tx, pak_id, group)?;
+            summary.paks_written += 1;
+            summary.assets_written += group.len();
+        }
+        tx.commit()?;
+        *self.synced.lock().unwrap() = Arc::downgrade(assets);
+        Ok(Some(summary))
+    }
+
+    /// The page of `assets` passing `filter`, sorted by `sort` (`name`, `size`, `type` or
+    /// `path`, and whether descending) then by listing order; None when the rows don't
+    /// hold `assets`
+    ///
+    /// Blocking; run it off the async workers.
+    pub fn query(
+        &self,
+        assets: &Arc<AssetIndex>,
+        filter: &Filter,
+        sort: Option<(&str, bool)>,
+        offset: usize,
+        limit: usize,
+    ) -> rusqlite::Result<Option<Page>> {
+        let conn = self.conn.lock().unwrap();
+        // Checked with the connection held, so a sync can't start in between
+        if !self.mirrors(assets) {
+            return Ok(None);
+        }
+        const FROM: &str = "FROM assets a JOIN paks p ON p.id = a.pak_id";
+
+        let (clause, mut values) = conditions(filter, None);
+        let filtered: i64 = conn.query_row(
+            &format!("SELECT count(*) {} {}", FROM, clause),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        let order = match sort {
+            Some((field, descending)) => {
+                let column = match field {
+                    "size" => "a.size",
+                    "type" => "a.asset_type",
+                    "path" => "a.path COLLATE natural_order",
+                    _ => "a.name COLLATE natural_order",
+                };
+                format!("{}{}, ", column, if descending { " DESC" } else { "" })
+            }
+            None => String::new(),
+        };
+        values.push(Value::Integer(limit as i64));
+        values.push(Value::Integer(offset as i64));
+        let mut stmt = conn.prepare(&format!(
+            "SELECT a.asset_id {} {} ORDER BY {}a.pos LIMIT ? OFFSET ?",
+            FROM, clause, order
+        ))?;
+        let ids = stmt.query_map(params_from_iter(values.iter()), |row| row.get(0))?.collect::<Result<_, _>>()?;
+
+        let by_type = counts(&conn, &format!("SELECT a.asset_type, count(*) {}", FROM), "a.asset_type", filter, Facet::Type)?;
+        let by_pak = counts(&conn, &format!("SELECT a.pak_name, count(*) {}", FROM), "a.pak_name", filter, Facet::Pak)?;
+        let by_extension = counts(
+            &conn,
+            &format!("SELECT e.extension, count(*) {} JOIN asset_extensions e ON e.asset = a.id", FROM),
+            "e.extension",
+            filter,
+            Facet::Extension,
+        )?;
+        Ok(Some(Page { ids, filtered: filtered as usize, by_type, by_pak, by_extension }))
+    }
+}
+
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Extensions an asset is found by, as the `extension` filter matches them
+fn extensions(asset: &Asset) -> Vec<String> {
+    let mut extensions: Vec<String> = match asset.parts.as_slice() {
+        [] => crate::extension_of(&asset.path).into_iter().map(str::to_ascii_lowercase).collect(),
+        parts => parts.iter().map(|part| part.extension.to_ascii_lowercase()).collect(),
+    };
+    extensions.sort();
+    extensions.dedup();
+    extensions
+}
+
+/// Hex SHA-256 of what the rows of a pak's assets hold, with their positions from the first
+fn digest(assets: &[(usize, &Asset)]) -> String {
+    let start = assets.first().map_or(0, |(pos, _)| *pos);
+    let mut hasher = Sha256::new();
+    for (pos, asset) in assets {
+        let offset = (pos - start).to_string();
+        let fields = [
+            offset.as_str(),
+            asset.id.as_str(),
+            &asset.path,
+            &asset.name,
+            asset.asset_type.as_str(),
+            &asset.size.to_string(),
+            &asset.compressed_size.map(|size| size.to_string()).unwrap_or_default(),
+            asset.compression_method.as_deref().unwrap_or(""),
+            if asset.is_encrypted.unwrap_or(false) { "1" } else { "0" },
+            asset.hash.as_deref().unwrap_or(""),
+            &extensions(asset).join(","),
+        ];
+        for field in fields {
+            hasher.update(field.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update([1]);
+    }
+    crate::pak_parser::to_hex(&hasher.finalize())
+}
+
+fn delete_rows(tx: &Transaction, pak_id: i64) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM assets_fts WHERE rowid IN (SELECT id FROM assets WHERE pak_id = ?1)", [pak_id])?;
+    tx.execute("DELETE FROM asset_extensions WHERE asset IN (SELECT id FROM assets WHERE pak_id = ?1)", [pak_id])?;
+    tx.execute("DELETE FROM assets WHERE pak_id = ?1", [pak_id])?;
+    Ok(())
+}
+
+fn insert_rows(tx: &Transaction, pak_id: i64, assets: &[(usize, &Asset)]) -> rusqlite::Result<()> {
+    let mut insert_asset = tx.prepare(
+        "INSERT INTO assets (pak_id, pos, asset_id, path, name, asset_type, size, compressed_size, compression, encrypted, pak_name, hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+    )?;
+    let mut insert_text = tx.prepare("INSERT INTO assets_fts (rowid, name, path) VALUES (?1, ?2, ?3)")?;
+    let mut insert_extension = tx.prepare("INSERT OR IGNORE INTO asset_extensions (asset, extension) VALUES (?1, ?2)")?;
+    for (pos, asset) in assets {
+        insert_asset.execute(params![
+            pak_id,
+            pos,
+            asset.id,
+            asset.path,
+            asset.name,
+            asset.asset_type.as_str(),
+            asset.size,
+            asset.compressed_size,
+            asset.compression_method.as_deref(),
+            asset.is_encrypted.unwrap_or(false),
+            asset.pak_file.as_deref().map(crate::file_name_str),
+            asset.hash,
+        ])?;
+        let row = tx.last_insert_rowid();
+        insert_text.execute(params![row, asset.name.to_lowercase(), crate::with_forward_slashes(&asset.path).to_lowercase()])?;
+        for extension in extensions(asset) {
+            insert_extension.execute(params![row, extension])?;
+        }
+    }
+    Ok(())
+}
+
+/// The WHERE clause of `filter` (empty when nothing is filtered) with its parameters,
+/// leaving out the `except` facet's filter
+fn conditions(filter: &Filter, except: Option<Facet>) -> (String, Vec<Value>) {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if !filter.types.is_empty() && except != Some(Facet::Type) {
+        conditions.push(format!("a.asset_type IN ({})", vec!["?"; filter.types.len()].join(", ")));
+        values.extend(filter.types.iter().map(|asset_type| Value::Text(asset_type.clone())));
+    }
+    if let Some(pak) = filter.pak.as_ref().filter(|_| except != Some(Facet::Pak)) {
+        conditions.push("a.pak_name IS NOT NULL AND (p.pak_file = ? OR a.pak_name = ?)".to_string());
+        values.extend([Value::Text(pak.clone()), Value::Text(pak.clone())]);
+    }
+    if let Some(extension) = filter.extension.as_ref().filter(|_| except != Some(Facet::Extension)) {
+        conditions.push("a.id IN (SELECT asset FROM asset_extensions WHERE extension = ?)".to_string());
+        values.push(Value::Text(extension.clone()));
+    }
+    if let Some(min_size) = filter.min_size {
+        conditions.push("a.size >= ?".to_string());
+        values.push(Value::Integer(min_size.min(i64::MAX as u64) as i64));
+    }
+    if let Some(max_size) = filter.max_size {
+        conditions.push("a.size <= ?".to_string());
+        values.push(Value::Integer(max_size.min(i64::MAX as u64) as i64));
+    }
+    if let Some(compression) = &filter.compression {
+        conditions.push("a.compression = ? COLLATE NOCASE".to_string());
+        values.push(Value::Text(compression.clone()));
+    }
+    if let Some(encrypted) = filter.encrypted {
+        conditions.push("a.encrypted = ?".to_string());
+        values.push(Value::Integer(encrypted as i64));
+    }
+    match filter.search.as_deref() {
+        None | Some("") => {}
+        // Trigrams can't find fewer than three characters
+        Some(search) if search.chars().count() < 3 => {
+            conditions.push("a.id IN (SELECT rowid FROM assets_fts WHERE instr(name, ?) > 0 OR instr(path, ?) > 0)".to_string());
+            values.extend([Value::Text(search.to_string()), Value::Text(search.to_string())]);
+        }
+        Some(search) => {
+            conditions.push("a.id IN (SELECT rowid FROM assets_fts WHERE assets_fts MATCH ?)".to_string());
+            values.push(Value::Text(format!("\"{}\"", search.replace('"', "\"\""))));
+        }
+    }
+    if conditions.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!("WHERE {}", conditions.join(" AND ")), values)
+    }
+}
+
+/// Counts by `key` of the rows `select` finds, filtered by everything but `facet`
+fn counts(conn: &Connection, select: &str, key: &str, filter: &Filter, facet: Facet) -> rusqlite::Result<HashMap<String, usize>> {
+    let (clause, values) = conditions(filter, Some(facet));
+    let clause = match (clause.is_empty(), facet) {
+        (true, Facet::Pak) => "WHERE a.pak_name IS NOT NULL".to_string(),
+        (false, Facet::Pak) => format!("{} AND a.pak_name IS NOT NULL", clause),
+        _ => clause,
+    };
+    let mut stmt = conn.prepare(&format!("{} {} GROUP BY {}", select, clause, key))?;
+    let rows = stmt.query_map(params_from_iter(values.iter()), |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))?;
+    rows.collect()
+}
+
+/// Opens the database at `default_path`, or logs why it can't be
+pub fn open_default() -> Option<Arc<SqlIndex>> {
+    let path = default_path();
+    match SqlIndex::open(&path) {
+        Ok(index) => Some(Arc::new(index)),
+        Err(e) => {
+            warn!("Failed to open the SQL asset index at {}: {}; listings are searched in memory", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `per_pak` assets in each of `paks`, in pak order
+    fn listing(paks: &[&str], per_pak: usize) -> Arc<AssetIndex> {
+        let templates = crate::create_mock_assets();
+        let mut assets = Vec::new();
+        for pak in paks {
+            for i in 0..per_pak {
+                let mut asset = templates[i % templates.len()].clone();
+                asset.id = String::new();
+                asset.path = format!("Game/Content/{}/Asset_{}.uasset", pak, i);
+                asset.pak_file = Some(Arc::from(format!("C:/Game/Paks/{}.pak", pak)));
+                assets.push(asset);
+            }
+        }
+        Arc::new(AssetIndex::new(assets))
+    }
+
+    fn temp_database() -> PathBuf {
+        std::env::temp_dir().join(format!("pakseek-sql-{}", uuid::Uuid::new_v4())).join("assets.sqlite")
+    }
+
+    fn sync(index: &SqlIndex, assets: &Arc<AssetIndex>) -> SyncSummary {
+        index.want(assets);
+        index.sync(assets).unwrap().unwrap()
+    }
+
+    fn all_ids(index: &SqlIndex, assets: &Arc<AssetIndex>) -> Vec<String> {
+        index.query(assets, &Filter::default(), None, 0, usize::MAX >> 1).unwrap().unwrap().ids
+    }
+
+    #[test]
+    fn rescans_write_only_changed_paks() {
+        let path = temp_database();
+        let index = SqlIndex::open(&path).unwrap();
+        let first = listing(&["a", "b", "c"], 50);
+        let summary = sync(&index, &first);
+        assert_eq!((summary.paks_written, summary.paks_unchanged, summary.assets_written), (3, 0, 150));
+
+        // The same listing again, rebuilt as a rescan would
+        let again = listing(&["a", "b", "c"], 50);
+        let summary = sync(&index, &again);
+        assert_eq!((summary.paks_written, summary.paks_unchanged, summary.paks_removed), (0, 3, 0));
+        assert!(!index.mirrors(&first));
+        assert_eq!(all_ids(&index, &again), again.iter().map(|asset| asset.id.clone()).collect::<Vec<_>>());
+
+        // b grows, so c moves along without being written; a is gone
+        let owned = |assets: Arc<AssetIndex>| assets.iter().map(|asset| (**asset).clone()).collect::<Vec<_>>();
+        let changed = Arc::new(AssetIndex::new([owned(listing(&["b"], 60)), owned(listing(&["c"], 50))].concat()));
+        let summary = sync(&index, &changed);
+        assert_eq!((summary.paks_written, summary.paks_unchanged, summary.paks_removed), (1, 1, 1));
+        assert_eq!(summary.assets_written, 60);
+        assert_eq!(all_ids(&index, &changed), changed.iter().map(|asset| asset.id.clone()).collect::<Vec<_>>());
+
+        let filter = Filter { pak: Some("c.pak".to_string()), ..Default::default() };
+        let page = index.query(&changed, &filter, None, 10, 5).unwrap().unwrap();
+        assert_eq!(page.filtered, 50);
+        assert_eq!(page.ids, changed[70..75].iter().map(|asset| asset.id.clone()).collect::<Vec<_>>());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn stale_listings_are_not_synced_or_queried() {
+        let path = temp_database();
+        let index = SqlIndex::open(&path).unwrap();
+        let (older, newer) = (listing(&["a"], 10), listing(&["b"], 10));
+        index.want(&older);
+        index.want(&newer);
+        assert!(index.sync(&older).unwrap().is_none());
+        assert!(index.query(&older, &Filter::default(), None, 0, 10).unwrap().is_none());
+        assert_eq!(index.sync(&newer).unwrap().unwrap().assets_written, 10);
+        assert!(index.query(&older, &Filter::default(), None, 0, 10).unwrap().is_none());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn reopening_keeps_rows_and_migrates_once() {
+        let path = temp_database();
+        let assets = listing(&["a", "b"], 20);
+        sync(&SqlIndex::open(&path).unwrap(), &assets);
+
+        let reopened = SqlIndex::open(&path).unwrap();
+        let version: usize = reopened.conn.lock().unwrap().query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        let summary = sync(&reopened, &assets);
+        assert_eq!((summary.paks_written, summary.paks_unchanged), (0, 2));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn databases_from_newer_versions_start_over() {
+        let path = temp_database();
+        let assets = listing(&["a"], 20);
+        sync(&SqlIndex::open(&path).unwrap(), &assets);
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.pragma_update(None, "user_version", MIGRATIONS.len() + 1).unwrap();
+        }
+
+        let reopened = SqlIndex::open(&path).unwrap();
+        let version: usize = reopened.conn.lock().unwrap().query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        assert_eq!(sync(&reopened, &assets).paks_written, 1);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,591 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::preview::Asset;
+
+/// Largest assets listed in `ScanStatistics::largest`
+pub const LARGEST_ASSETS: usize = 20;
+
+/// Overview of the loaded assets for the dashboard
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ScanStatistics {
+    pub total_assets: usize,
+    /// Uncompressed bytes
+    pub total_size: u64,
+    /// Stored bytes, for the assets whose compressed size is known
+    pub total_compressed_size: u64,
+    pub encrypted_assets: usize,
+    /// Largest total size first
+    pub by_type: Vec<GroupStatistics>,
+    /// Largest total size first
+    pub by_pak: Vec<GroupStatistics>,
+    /// Most assets first
+    pub by_compression: Vec<GroupStatistics>,
+    /// The `LARGEST_ASSETS` largest assets, largest first
+    #[schema(value_type = Vec<Asset>)]
+    pub largest: Vec<Arc<Asset>>,
+}
+
+/// Assets sharing a type, pak or compression method
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GroupStatistics {
+    /// None for the assets without one (no pak, unknown compression)
+    pub key: Option<String>,
+    pub count: usize,
+    pub size: u64,
+    pub compressed_size: u64,
+}
+
+#[derive(Default)]
+struct Group {
+    count: usize,
+    size: u64,
+    compressed_size: u64,
+}
+
+impl Group {
+    fn add(&mut self, asset: &Asset) {
+        self.count += 1;
+        self.size += asset.size;
+        self.compressed_size += asset.compressed_size.unwrap_or(0);
+    }
+}
+
+/// Computes the statistics in one pass over `assets`
+///
+/// Groups are keyed by borrowed strings and the largest assets kept as positions in a
+/// bounded heap, so only those `LARGEST_ASSETS` are shared with the result.
+pub fn compute(assets: &[Arc<Asset>]) -> ScanStatistics {
+    let mut by_type: HashMap<Option<&str>, Group> = HashMap::new();
+    let mut by_pak: HashMap<Option<&str>, Group> = HashMap::new();
+    let mut by_compression: HashMap<Option<&str>, Group> = HashMap::new();
+    let mut largest: BinaryHeap<Reverse<(u64, Reverse<usize>)>> = BinaryHeap::with_capacity(LARGEST_ASSETS + 1);
+    let (mut total_size, mut total_compressed_size, mut encrypted_assets) = (0, 0, 0);
+
+    for (index, asset) in assets.iter().enumerate() {
+        total_size += asset.size;
+        total_compressed_size += asset.compressed_size.unwrap_or(0);
+        if asset.is_encrypted == Some(true) {
+            encrypted_assets += 1;
+        }
+        by_type.entry(Some(asset.asset_type.as_str())).or_default().add(asset);
+        by_pak.entry(asset.pak_file.as_deref()).or_default().add(asset);
+        by_compression.entry(asset.compression_method.as_deref()).or_default().add(asset);
+
+        // Ties go to the asset scanned first
+        largest.push(Reverse((asset.size, Reverse(index))));
+        if largest.len() > LARGEST_ASSETS {
+            largest.pop();
+        }
+    }
+
+    let groups = |groups: HashMap<Option<&str>, Group>| -> Vec<GroupStatistics> {
+        groups
+            .into_iter()
+            .map(|(key, group)| GroupStatistics {
+                key: key.map(str::to_string),
+                count: group.count,
+                size: group.size,
+                compressed_size: group.compressed_size,
+            })
+            .collect()
+    };
+    let mut by_type = groups(by_type);
+    let mut by_pak = groups(by_pak);
+    let mut by_compression = groups(by_compression);
+    by_type.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.key.cmp(&b.key)));
+    by_pak.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.key.cmp(&b.key)));
+    by_compression.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+
+    let largest = largest
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((_, Reverse(index)))| assets[index].clone())
+        .collect();
+
+    ScanStatistics {
+        total_assets: assets.len(),
+        total_size,
+        total_compressed_size,
+        encrypted_assets,
+        by_type,
+        by_pak,
+        by_compression,
+        largest,
+    }
+}
+
+/// Content folders listed per chunk in `ChunkSummary::folders`
+pub const CHUNK_FOLDERS: usize = 20;
+
+/// What one chunk of a game split over `pakchunkN` paks holds
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ChunkSummary {
+    /// None for the assets of paks outside the `pakchunkN` naming, and loose files
+    pub chunk_id: Option<u32>,
+    /// File names of the chunk's paks
+    pub paks: Vec<String>,
+    pub asset_count: usize,
+    /// Uncompressed bytes
+    pub total_size: u64,
+    /// Stored bytes, for the assets whose compressed size is known
+    pub total_compressed_size: u64,
+    /// The chunk's `CHUNK_FOLDERS` top-level content folders with the most assets
+    pub folders: Vec<ChunkFolder>,
+}
+
+/// A top-level content folder ("Game/Content/Audio") and the chunk's assets beneath it
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ChunkFolder {
+    pub path: String,
+    pub asset_count: usize,
+    pub total_size: u64,
+}
+
+/// The loaded assets by chunk, in chunk order with the unchunked ones last
+pub fn chunk_summary(assets: &[Arc<Asset>]) -> Vec<ChunkSummary> {
+    #[derive(Default)]
+    struct Chunk<'a> {
+        paks: BTreeSet<&'a str>,
+        totals: Group,
+        folders: HashMap<&'a str, Group>,
+    }
+
+    let mut chunks: HashMap<Option<u32>, Chunk> = HashMap::new();
+    for asset in assets {
+        let chunk = chunks.entry(asset.chunk_id).or_default();
+        if let Some(pak_file) = &asset.pak_file {
+            chunk.paks.insert(pak_file.rsplit(['/', '\\']).next().unwrap_or(pak_file));
+        }
+        chunk.totals.add(asset);
+        chunk.folders.entry(content_folder(&asset.path)).or_default().add(asset);
+    }
+
+    let mut summaries: Vec<ChunkSummary> = chunks
+        .into_iter()
+        .map(|(chunk_id, chunk)| {
+            let mut folders: Vec<ChunkFolder> = chunk
+                .folders
+                .into_iter()
+                .map(|(path, group)| ChunkFolder { path: path.to_string(), asset_count: group.count, total_size: group.size })
+                .collect();
+            folders.sort_by(|a, b| b.asset_count.cmp(&a.asset_count).then_with(|| a.path.cmp(&b.path)));
+            folders.truncate(CHUNK_FOLDERS);
+            ChunkSummary {
+                chunk_id,
+                paks: chunk.paks.into_iter().map(str::to_string).collect(),
+                asset_count: chunk.totals.count,
+                total_size: chunk.totals.size,
+                total_compressed_size: chunk.totals.compressed_size,
+                folders,
+            }
+        })
+        .collect();
+    summaries.sort_by_key(|summary| (summary.chunk_id.is_none(), summary.chunk_id));
+    summaries
+}
+
+/// The top-level content folder of an entry path: down to the folder inside the first
+/// "Content" ("Game/Content/Audio" for "Game/Content/Audio/JA/Hello.uasset"), or the first
+/// two folders of paths without one; "/" for files at the top
+fn content_folder(path: &str) -> &str {
+    let path = path.trim_start_matches("../").trim_start_matches(['/', '.']);
+    let depth = path.split('/').position(|segment| segment.eq_ignore_ascii_case("Content")).map_or(2, |at| at + 2);
+    match path.match_indices('/').map(|(at, _)| at).take(depth).last() {
+        Some(end) => &path[..end],
+        None => "/",
+    }
+}
+
+/// Upper bounds of the entry size histogram's buckets; the last bucket has none
+pub const SIZE_BUCKETS: [u64; 5] = [4 << 10, 64 << 10, 1 << 20, 16 << 20, 256 << 20];
+
+/// Stored-to-raw ratio at or above which a compressed entry counts as incompressible
+pub const INCOMPRESSIBLE_RATIO: f64 = 0.98;
+
+/// Entries smaller than this aren't flagged as incompressible: headers dominate them
+pub const INCOMPRESSIBLE_MIN_SIZE: u64 = 64 << 10;
+
+/// Incompressible entries listed in `StorageReport::incompressible`, largest first
+pub const INCOMPRESSIBLE_LISTED: usize = 100;
+
+/// Where the bytes of a set of assets go, for `GET /stats/storage` and `analyze_storage`
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct StorageReport {
+    pub total: StorageGroup,
+    /// Largest stored size first
+    pub by_type: Vec<StorageGroup>,
+    /// Lowercase without the dot; largest stored size first
+    pub by_extension: Vec<StorageGroup>,
+    /// Stored bytes of every copy of an entry beyond the first, by content hash
+    pub duplicate_bytes: u64,
+    /// Entries marked compressed whose stored size is within 2% of their raw size
+    pub incompressible_count: usize,
+    pub incompressible_bytes: u64,
+    /// The `INCOMPRESSIBLE_LISTED` largest of them
+    pub incompressible: Vec<IncompressibleEntry>,
+}
+
+/// Sizes of the assets sharing a type or extension (or of all of them, for the total)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct StorageGroup {
+    /// Empty for the total
+    pub key: String,
+    pub count: usize,
+    /// Raw bytes
+    pub size: u64,
+    /// Stored bytes, for the assets whose compressed size is known
+    pub compressed_size: u64,
+    /// Stored over raw bytes of the assets whose compressed size is known; None when
+    /// there are none
+    pub ratio: Option<f64>,
+    /// Stored bytes of copies beyond the first of content found elsewhere
+    pub duplicate_bytes: u64,
+    /// Entries by raw size, one bucket per `SIZE_BUCKETS` bound and one above the last
+    pub histogram: Vec<SizeBucket>,
+    /// By the pixel format (or other `format`) the deep scan found, largest first; empty
+    /// when none was read
+    pub formats: Vec<FormatShare>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SizeBucket {
+    /// Inclusive
+    pub min: u64,
+    /// Exclusive; None for the last bucket
+    pub max: Option<u64>,
+    pub count: usize,
+    pub size: u64,
+}
+
+/// A `format` of a type's assets with its share of the type's raw bytes
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FormatShare {
+    pub format: String,
+    pub count: usize,
+    pub size: u64,
+    /// Of the type's raw bytes, 0 to 1
+    pub share: f64,
+}
+
+/// An entry that gains (almost) nothing from its compression
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct IncompressibleEntry {
+    pub path: String,
+    pub pak_file: Option<String>,
+    pub asset_type: String,
+    pub compression_method: String,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub ratio: f64,
+}
+
+#[derive(Default)]
+struct StorageCounts<'a> {
+    count: usize,
+    size: u64,
+    compressed_size: u64,
+    /// Raw bytes of the assets whose compressed size is known
+    known_size: u64,
+    duplicate_bytes: u64,
+    buckets: [(usize, u64); SIZE_BUCKETS.len() + 1],
+    formats: HashMap<&'a str, (usize, u64)>,
+}
+
+impl<'a> StorageCounts<'a> {
+    fn add(&mut self, asset: &'a Asset, duplicate: bool) {
+        self.count += 1;
+        self.size += asset.size;
+        if let Some(compressed_size) = asset.compressed_size {
+            self.compressed_size += compressed_size;
+            self.known_size += asset.size;
+        }
+        if duplicate {
+            self.duplicate_bytes += asset.compressed_size.unwrap_or(asset.size);
+        }
+        let bucket = SIZE_BUCKETS.iter().position(|&max| asset.size < max).unwrap_or(SIZE_BUCKETS.len());
+        self.buckets[bucket].0 += 1;
+        self.buckets[bucket].1 += asset.size;
+        if let Some(format) = asset.metadata.as_ref().and_then(|metadata| metadata.get("format")).and_then(|format| format.as_str()) {
+            let share = self.formats.entry(format).or_default();
+            share.0 += 1;
+            share.1 += asset.size;
+        }
+    }
+
+    fn finish(self, key: &str) -> StorageGroup {
+        let histogram = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, &(count, size))| SizeBucket {
+                min: if i == 0 { 0 } else { SIZE_BUCKETS[i - 1] },
+                max: SIZE_BUCKETS.get(i).copied(),
+                count,
+                size,
+            })
+            .collect();
+        let mut formats: Vec<FormatShare> = self
+            .formats
+            .into_iter()
+            .map(|(format, (count, size))| FormatShare {
+                format: format.to_string(),
+                count,
+                size,
+                share: if self.size == 0 { 0.0 } else { size as f64 / self.size as f64 },
+            })
+            .collect();
+        formats.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.format.cmp(&b.format)));
+        StorageGroup {
+            key: key.to_string(),
+            count: self.count,
+            size: self.size,
+            compressed_size: self.compressed_size,
+            ratio: (self.known_size > 0).then(|| self.compressed_size as f64 / self.known_size as f64),
+            duplicate_bytes: self.duplicate_bytes,
+            histogram,
+            formats,
+        }
+    }
+}
+
+/// Breaks the bytes of `assets` down by type and extension in one pass
+///
+/// Copies are told apart by the SHA-1 the pak index keeps for each entry; assets without
+/// one are never counted as copies. An asset split over several files counts towards
+/// the extension of each part.
+pub fn analyze_storage(assets: &[Arc<Asset>]) -> StorageReport {
+    let mut total = StorageCounts::default();
+    let mut by_type: HashMap<&str, StorageCounts> = HashMap::new();
+    let mut by_extension: HashMap<String, StorageCounts> = HashMap::new();
+    let mut seen_hashes: HashSet<&str> = HashSet::new();
+    let mut incompressible: Vec<&Asset> = Vec::new();
+    let (mut incompressible_count, mut incompressible_bytes) = (0, 0);
+
+    for asset in assets {
+        let duplicate = asset.hash.as_deref().is_some_and(|hash| !seen_hashes.insert(hash));
+        total.add(asset, duplicate);
+        by_type.entry(asset.asset_type.as_str()).or_default().add(asset, duplicate);
+        let mut extensions: Vec<String> = match asset.parts.as_slice() {
+            [] => crate::extension_of(&asset.path).map(str::to_ascii_lowercase).into_iter().collect(),
+            parts => parts.iter().map(|part| part.extension.to_ascii_lowercase()).collect(),
+        };
+        extensions.sort();
+        extensions.dedup();
+        for extension in extensions {
+            by_extension.entry(extension).or_default().add(asset, duplicate);
+        }
+        if is_incompressible(asset) {
+            incompressible_count += 1;
+            incompressible_bytes += asset.compressed_size.unwrap_or(0);
+            incompressible.push(asset);
+        }
+    }
+
+    let sorted = |groups: Vec<StorageGroup>| {
+        let mut groups = groups;
+        groups.sort_by(|a, b| b.compressed_size.cmp(&a.compressed_size).then_with(|| b.size.cmp(&a.size)).then_with(|| a.key.cmp(&b.key)));
+        groups
+    };
+    incompressible.sort_by(|a, b| b.compressed_size.cmp(&a.compressed_size).then_with(|| a.path.cmp(&b.path)));
+    incompressible.truncate(INCOMPRESSIBLE_LISTED);
+
+    StorageReport {
+        duplicate_bytes: total.duplicate_bytes,
+        total: total.finish(""),
+        by_type: sorted(by_type.into_iter().map(|(key, counts)| counts.finish(key)).collect()),
+        by_extension: sorted(by_extension.into_iter().map(|(key, counts)| counts.finish(&key)).collect()),
+        incompressible_count,
+        incompressible_bytes,
+        incompressible: incompressible
+            .into_iter()
+            .map(|asset| {
+                let compressed_size = asset.compressed_size.unwrap_or(0);
+                IncompressibleEntry {
+                    path: asset.path.clone(),
+                    pak_file: asset.pak_file.as_deref().map(str::to_string),
+                    asset_type: asset.asset_type.as_str().to_string(),
+                    compression_method: asset.compression_method.as_deref().unwrap_or_default().to_string(),
+                    size: asset.size,
+                    compressed_size,
+                    ratio: compressed_size as f64 / asset.size as f64,
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Whether an entry is marked compressed but stored at (nearly) its raw size
+fn is_incompressible(asset: &Asset) -> bool {
+    let (Some(method), Some(compressed_size)) = (asset.compression_method.as_deref(), asset.compressed_size) else {
+        return false;
+    };
+    !method.eq_ignore_ascii_case("none")
+        && asset.size >= INCOMPRESSIBLE_MIN_SIZE
+        && compressed_size as f64 >= asset.size as f64 * INCOMPRESSIBLE_RATIO
+}
+
+/// The report as a "Storage" section for the markdown report
+pub fn storage_markdown(report: &StorageReport) -> String {
+    let mut section = String::from("## Storage\n\n");
+    section.push_str(&format!(
+        "- **Raw Size**: {}\n- **Stored Size**: {}\n",
+        human_size(report.total.size),
+        human_size(report.total.compressed_size)
+    ));
+    if let Some(ratio) = report.total.ratio {
+        section.push_str(&format!("- **Compression Ratio**: {:.1}%\n", ratio * 100.0));
+    }
+    section.push_str(&format!("- **Duplicate Content**: {}\n", human_size(report.duplicate_bytes)));
+    section.push_str(&format!(
+        "- **Incompressible Entries**: {} ({})\n\n",
+        report.incompressible_count,
+        human_size(report.incompressible_bytes)
+    ));
+
+    for (title, groups) in [("Type", &report.by_type), ("Extension", &report.by_extension)] {
+        section.push_str(&format!("### By {}\n\n", title));
+        section.push_str(&format!("| {} | Assets | Raw | Stored | Ratio | Duplicates |\n", title));
+        section.push_str("|---|---|---|---|---|---|\n");
+        for group in groups {
+            section.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                group.key,
+                group.count,
+                human_size(group.size),
+                human_size(group.compressed_size),
+                group.ratio.map_or("-".to_string(), |ratio| format!("{:.1}%", ratio * 100.0)),
+                human_size(group.duplicate_bytes)
+            ));
+        }
+        section.push('\n');
+    }
+
+    let formats: Vec<&StorageGroup> = report.by_type.iter().filter(|group| !group.formats.is_empty()).collect();
+    if !formats.is_empty() {
+        section.push_str("### Formats\n\n");
+        for group in formats {
+            let shares: Vec<String> = group
+                .formats
+                .iter()
+                .map(|format| format!("{} {:.0}%", format.format, format.share * 100.0))
+                .collect();
+            section.push_str(&format!("- **{}**: {}\n", group.key, shares.join(", ")));
+        }
+        section.push('\n');
+    }
+
+    if !report.incompressible.is_empty() {
+        section.push_str("### Incompressible Entries\n\n");
+        section.push_str("| Path | Compression | Raw | Stored |\n|---|---|---|---|\n");
+        for entry in &report.incompressible {
+            section.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                entry.path,
+                entry.compression_method,
+                human_size(entry.size),
+                human_size(entry.compressed_size)
+            ));
+        }
+        section.push('\n');
+    }
+    section
+}
+
+/// Bytes in the largest unit that keeps the number at least 1 ("1.5 GB", in 1024s)
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset_type::AssetType;
+
+    /// `count` assets over 10 paks and three types; every fifth one encrypted
+    fn synthetic_assets(count: usize) -> Vec<Arc<Asset>> {
+        let template: Asset = serde_json::from_value(serde_json::json!({
+            "name": "Crate",
+            "asset_type": "static_mesh",
+            "size": 0,
+            "path": "Game/Props/SM_Crate.uasset",
+            "last_modified": "2024-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        let paks: Vec<Arc<str>> = (0..10).map(|i| Arc::from(format!("Paks/pakchunk{}-Windows.pak", i))).collect();
+        let compressions: [Arc<str>; 2] = [Arc::from("Oodle"), Arc::from("Zlib")];
+        (0..count)
+            .map(|i| {
+                let mut asset = template.clone();
+                asset.path = format!("Game/Props/SM_Crate_{}.uasset", i);
+                asset.asset_type = [AssetType::StaticMesh, AssetType::Texture, AssetType::Sound][i % 3].clone();
+                asset.size = (i as u64 * 7919) % 100_000;
+                asset.compressed_size = Some(asset.size / 2);
+                asset.pak_file = Some(paks[i % 10].clone());
+                asset.compression_method = (i % 4 != 0).then(|| compressions[i % 2].clone());
+                asset.is_encrypted = Some(i % 5 == 0);
+                Arc::new(asset)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn totals_and_groups_add_up() {
+        let assets = synthetic_assets(1000);
+        let stats = compute(&assets);
+
+        assert_eq!(stats.total_assets, 1000);
+        assert_eq!(stats.total_size, assets.iter().map(|a| a.size).sum::<u64>());
+        assert_eq!(stats.total_compressed_size, assets.iter().map(|a| a.size / 2).sum::<u64>());
+        assert_eq!(stats.encrypted_assets, 200);
+        for groups in [&stats.by_type, &stats.by_pak, &stats.by_compression] {
+            assert_eq!(groups.iter().map(|g| g.count).sum::<usize>(), 1000);
+            assert_eq!(groups.iter().map(|g| g.size).sum::<u64>(), stats.total_size);
+        }
+        assert_eq!(stats.by_type.len(), 3);
+        assert_eq!(stats.by_pak.len(), 10);
+        assert!(stats.by_type.windows(2).all(|pair| pair[0].size >= pair[1].size));
+
+        // Zlib for odd, none for every fourth, Oodle for the other even ones
+        let keys: Vec<Option<&str>> = stats.by_compression.iter().map(|g| g.key.as_deref()).collect();
+        assert_eq!(keys, vec![Some("Zlib"), None, Some("Oodle")]);
+        assert_eq!(stats.by_compression.iter().map(|g| g.count).collect::<Vec<_>>(), vec![500, 250, 250]);
+    }
+
+    #[test]
+    fn largest_are_sorted_and_shared() {
+        let assets = synthetic_assets(1000);
+        let stats = compute(&assets);
+
+        let mut sizes: Vec<u64> = assets.iter().map(|a| a.size).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(stats.largest.len(), LARGEST_ASSETS);
+        assert_eq!(stats.largest.iter().map(|a| a.size).collect::<Vec<_>>(), sizes[..LARGEST_ASSETS]);
+        assert!(stats.largest.iter().all(|a| assets.iter().any(|b| Arc::ptr_eq(a, b))));
+    }
+
+    #[test]
+    fn large_state_within_budget() {
+        let assets = synthetic_assets(100_000);
+        let started = std::time::Instant::now();
+        let stats = compute(&assets);
+        let elapsed = started.elapsed();
+
+        assert_eq!(stats.total_assets, 100_000);
+        // A single unoptimized pass takes a few tens of milliseconds
+        assert!(elapsed < std::time::Duration::from_secs(2), "took {:?}", elapsed);
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use anyhow::{bail, Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// One row of a DataTable or CurveTable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRow {
+    pub name: String,
+    pub values: serde_json::Map<String, Value>,
+}
+
+/// Rows of a DataTable (or curves of a CurveTable) with the struct that shapes them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub class: String,
+    /// Row struct path for DataTables, "RichCurve"/"SimpleCurve" for CurveTables
+    pub row_struct: Option<String>,
+    pub rows: Vec<TableRow>,
+}
+
+/// Output formats supported by the table exporter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    Csv,
+    Json,
+}
+
+impl TableFormat {
+    /// Parses a user supplied format name ("csv" / "json")
+    pub fn parse(format: &str) -> Result<Self> {
+        match format.to_lowercase().as_str() {
+            "csv" => Ok(TableFormat::Csv),
+            "json" => Ok(TableFormat::Json),
+            other => bail!("Unsupported table export format: {} (expected csv or json)", other),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Json => "json",
+        }
+    }
+}
+
+/// Parses a cooked DataTable or CurveTable package into rows
+///
+/// Unversioned tables are read with the .usmap in settings, and fail with
+/// `MappingsRequired` when there is none.
+pub fn parse_table(data: &[u8]) -> Result<TableInfo> {
+    let package = Package::parse(data)?;
+
+    let (export, class) = package
+        .exports
+        .iter()
+        .map(|e| (e, package.export_class_name(e)))
+        .find(|(_, class)| class.ends_with("DataTable") || class == "CurveTable")
+        .context("Package has no DataTable or CurveTable export")?;
+
+    let mut reader = ArchiveReader::new(package.export_data(data, export)?);
+    let properties = uasset_parser::read_properties(&package, &mut reader, &class)?;
+    uasset_parser::skip_object_guid(&mut reader)?;
+
+    let row_count = reader.read_count(1)?;
+    let row_struct = if class == "CurveTable" {
+        // ECurveTableMode: Empty, SimpleCurves, RichCurves
+        match reader.read_u8()? {
+            1 => Some("SimpleCurve".to_string()),
+            2 => Some("RichCurve".to_string()),
+            _ => None,
+        }
+    } else {
+        properties.get("RowStruct").and_then(Value::as_str).map(str::to_string)
+    };
+
+    // "/Script/Game.ItemRow" is the ItemRow struct
+    let row_struct_name = row_struct.as_deref().and_then(|path| path.rsplit(['.', '/']).next()).unwrap_or("None");
+    let mut rows = Vec::with_capacity(row_count);
+    for _ in 0..row_count {
+        let name = package.read_name(&mut reader)?;
+        let values = uasset_parser::read_properties(&package, &mut reader, row_struct_name)
+            .with_context(|| format!("Failed to read row {}", name))?;
+        rows.push(TableRow { name, values });
+    }
+
+    Ok(TableInfo { class, row_struct, rows })
+}
+
+/// Renders table rows as CSV: a Name column followed by every property seen, in first-seen order
+///
+/// Strings are written as-is; structs, arrays and other values are written as compact JSON.
+pub fn to_csv(table: &TableInfo) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for row in &table.rows {
+        for key in row.values.keys() {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+
+    let mut csv = String::new();
+    let header: Vec<String> = std::iter::once("Name").chain(columns.iter().copied()).map(csv_field).collect();
+    csv.push_str(&header.join(","));
+    csv.push('\n');
+
+    for row in &table.rows {
+        let mut fields = vec![csv_field(&row.name)];
+        for column in &columns {
+            let field = match row.values.get(*column) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            };
+            fields.push(csv_field(&field));
+        }
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Renders table rows as a JSON document (row struct plus rows)
+pub fn to_json(table: &TableInfo) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "class": table.class,
+        "row_struct": table.row_struct,
+        "rows": table.rows,
+    }))?)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,191 @@
+//! Runs `--headless` commands of the built binary against paks written per test
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Exit code, stdout and stderr of `pakseek --headless <args>`
+fn pakseek(args: &[&str]) -> (i32, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_unreal-asset-explorer"))
+        .arg("--headless")
+        .args(args)
+        .arg("--no-index-cache")
+        .env("RUST_LOG", "error")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    (output.status.code().unwrap(), stdout, String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+/// `pakseek` for a command that prints JSON
+fn pakseek_json(args: &[&str]) -> (i32, Value) {
+    let (code, stdout, stderr) = pakseek(args);
+    let json = serde_json::from_str(&stdout).unwrap_or_else(|e| panic!("{}: {}\nstderr: {}", e, stdout, stderr));
+    (code, json)
+}
+
+/// A fresh folder holding `count` paks
+fn pak_folder(count: usize) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pakseek-cli-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for i in 0..count {
+        std::fs::write(dir.join(format!("pakchunk{}-Windows.pak", i)), pak_with_footer(i as u32)).unwrap();
+    }
+    dir
+}
+
+/// A v6 pak: an index holding just the mount point and entry count, then the footer
+fn pak_with_footer(entries: u32) -> Vec<u8> {
+    let mut pak = Vec::new();
+    let mount_point = b"../../../\0";
+    pak.extend_from_slice(&(mount_point.len() as i32).to_le_bytes());
+    pak.extend_from_slice(mount_point);
+    pak.extend_from_slice(&entries.to_le_bytes());
+    let index_size = pak.len() as u64;
+
+    pak.push(0); // Index not encrypted
+    pak.extend_from_slice(&0x5A6F12E1u32.to_le_bytes());
+    pak.extend_from_slice(&6u32.to_le_bytes());
+    pak.extend_from_slice(&0u64.to_le_bytes());
+    pak.extend_from_slice(&index_size.to_le_bytes());
+    pak.extend_from_slice(&[0; 20]);
+    pak
+}
+
+fn path(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+#[test]
+fn scan_prints_every_pak_and_asset() {
+    let dir = pak_folder(2);
+    let (code, report) = pakseek_json(&["scan", path(&dir), "--json"]);
+    assert_eq!(code, 0);
+    assert_eq!(report["paks"], 2);
+    assert_eq!(report["errors"].as_array().unwrap().len(), 0);
+    let assets = report["assets"].as_array().unwrap();
+    assert!(!assets.is_empty());
+    for asset in assets {
+        let pak = asset["pak_file"].as_str().unwrap();
+        assert!(Path::new(pak).starts_with(&dir), "{}", pak);
+        assert!(asset["path"].as_str().unwrap().starts_with("Content/"));
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn scan_without_paks_fails() {
+    let dir = pak_folder(0);
+    let (code, stdout, stderr) = pakseek(&["scan", path(&dir)]);
+    assert_eq!(code, 1);
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("No .pak files found"), "{}", stderr);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn list_applies_the_filter() {
+    let dir = pak_folder(1);
+    let pak = dir.join("pakchunk0-Windows.pak");
+    let (code, all) = pakseek_json(&["list", path(&pak)]);
+    assert_eq!(code, 0);
+    let all = all.as_array().unwrap();
+    assert!(all.len() > 1);
+
+    let (code, filtered) = pakseek_json(&["list", path(&pak), "--filter", "content/*/player.*"]);
+    assert_eq!(code, 0);
+    let filtered = filtered.as_array().unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0]["filename"], "Content/Characters/Player.uasset");
+
+    let (code, table, _) = pakseek(&["list", path(&pak), "--pretty"]);
+    assert_eq!(code, 0);
+    assert!(table.starts_with("Path"));
+    assert_eq!(table.lines().count(), all.len() + 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn extract_writes_matching_entries_once() {
+    let dir = pak_folder(1);
+    let pak = dir.join("pakchunk0-Windows.pak");
+    let destination = dir.join("out");
+    let (code, summary) = pakseek_json(&["extract", path(&pak), path(&destination), "--pattern", "player"]);
+    assert_eq!(code, 0);
+    assert_eq!(summary["extracted"], 1);
+    assert_eq!(summary["failed"].as_array().unwrap().len(), 0);
+    let written = destination.join("Content/Characters/Player.uasset");
+    assert_eq!(std::fs::metadata(&written).unwrap().len(), summary["total_bytes"].as_u64().unwrap());
+
+    // Without --overwrite the existing file is a failure
+    let (code, summary) = pakseek_json(&["extract", path(&pak), path(&destination), "--pattern", "player"]);
+    assert_eq!(code, 1);
+    assert_eq!(summary["extracted"], 0);
+    assert_eq!(summary["failed"][0]["path"], "Content/Characters/Player.uasset");
+
+    let (code, summary) = pakseek_json(&["extract", path(&pak), path(&destination), "--pattern", "player", "--overwrite"]);
+    assert_eq!(code, 0);
+    assert_eq!(summary["extracted"], 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn validate_reports_the_footer() {
+    let dir = pak_folder(1);
+    let (code, report) = pakseek_json(&["validate", path(&dir.join("pakchunk0-Windows.pak"))]);
+    assert_eq!(code, 0);
+    assert_eq!(report["valid"], true);
+    assert_eq!(report["info"]["version"], 6);
+    assert_eq!(report["info"]["mount_point"], "../../../");
+    assert_eq!(report["info"]["encrypted_index"], false);
+
+    let garbage = dir.join("garbage.pak");
+    std::fs::write(&garbage, b"not a pak").unwrap();
+    let (code, stdout, stderr) = pakseek(&["validate", path(&garbage)]);
+    assert_eq!(code, 1);
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("no footer found"), "{}", stderr);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn deps_exports_the_requested_format() {
+    let dir = pak_folder(2);
+    let (code, graph) = pakseek_json(&["deps", path(&dir)]);
+    assert_eq!(code, 0);
+    assert!(graph.is_object());
+
+    let (code, csv, _) = pakseek(&["deps", path(&dir), "--format", "csv"]);
+    assert_eq!(code, 0);
+    assert!(csv.starts_with("Asset,Dependency,Kind"));
+
+    let (code, stdout, _) = pakseek(&["deps", path(&dir), "--format", "xlsx"]);
+    assert_eq!(code, 1);
+    assert!(stdout.is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn diff_of_a_build_with_itself_is_empty() {
+    let dir = pak_folder(2);
+    let (code, comparison) = pakseek_json(&["diff", path(&dir), path(&dir)]);
+    assert_eq!(code, 0);
+    for list in ["added_files", "removed_files", "changed_files"] {
+        assert_eq!(comparison["pak_diff"][list].as_array().unwrap().len(), 0, "{}", list);
+    }
+    assert_eq!(comparison["dependency_diff"]["totals"]["added_edges"], 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bad_command_lines_exit_with_usage() {
+    for args in [&["bogus"][..], &[], &["scan"], &["list", "a.pak", "--deep"], &["scan", "x", "--filter"]] {
+        let (code, stdout, stderr) = pakseek(args);
+        assert_eq!(code, 2, "{:?}", args);
+        assert!(stdout.is_empty());
+        assert!(stderr.contains("Usage: pakseek --headless"), "{:?}", args);
+    }
+    let (code, stdout, _) = pakseek(&["--help"]);
+    assert_eq!(code, 0);
+    assert!(stdout.starts_with("Usage:"));
+}
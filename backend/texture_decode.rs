@@ -0,0 +1,544 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Image files `convert_texture` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Png,
+    Tga,
+    /// The mip's bytes as stored, behind a DDS header
+    Dds,
+}
+
+impl ImageFormat {
+    /// Parses a user supplied format name ("png", "tga", "dds")
+    pub fn parse(format: &str) -> Result<Self> {
+        match format.to_lowercase().as_str() {
+            "png" => Ok(ImageFormat::Png),
+            "tga" => Ok(ImageFormat::Tga),
+            "dds" => Ok(ImageFormat::Dds),
+            other => bail!("Unsupported image format: {} (expected png, tga or dds)", other),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Tga => "tga",
+            ImageFormat::Dds => "dds",
+        }
+    }
+}
+
+/// 8-bit RGBA pixels, rows top to bottom
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// How a UE pixel format is stored: blocks of `block_width` x `block_height` pixels
+struct Layout {
+    block_width: u32,
+    block_height: u32,
+    block_bytes: usize,
+    /// DXGI_FORMAT of the linear and the sRGB variant
+    dxgi: u32,
+    dxgi_srgb: u32,
+}
+
+const fn blocks(block_bytes: usize, dxgi: u32, dxgi_srgb: u32) -> Layout {
+    Layout { block_width: 4, block_height: 4, block_bytes, dxgi, dxgi_srgb }
+}
+
+const fn pixels(pixel_bytes: usize, dxgi: u32) -> Layout {
+    Layout { block_width: 1, block_height: 1, block_bytes: pixel_bytes, dxgi, dxgi_srgb: dxgi }
+}
+
+fn layout(pixel_format: &str) -> Option<Layout> {
+    let layout = match pixel_format {
+        "PF_DXT1" => blocks(8, 71, 72),
+        "PF_DXT3" => blocks(16, 74, 75),
+        "PF_DXT5" => blocks(16, 77, 78),
+        "PF_BC4" => blocks(8, 80, 80),
+        "PF_BC5" => blocks(16, 83, 83),
+        "PF_BC6H" => blocks(16, 95, 95),
+        "PF_BC7" => blocks(16, 98, 99),
+        "PF_B8G8R8A8" => Layout { dxgi_srgb: 91, ..pixels(4, 87) },
+        "PF_R8G8B8A8" => Layout { dxgi_srgb: 29, ..pixels(4, 28) },
+        "PF_G8" => pixels(1, 61),
+        "PF_A8" => pixels(1, 65),
+        "PF_R8G8" => pixels(2, 49),
+        "PF_G16" => pixels(2, 56),
+        "PF_R16F" => pixels(2, 54),
+        "PF_R32_FLOAT" => pixels(4, 41),
+        "PF_FloatR11G11B10" | "PF_FloatRGB" => pixels(4, 26),
+        "PF_FloatRGBA" => pixels(8, 10),
+        "PF_A16B16G16R16" => pixels(8, 11),
+        "PF_A32B32G32R32F" => pixels(16, 2),
+        // LDR ASTC; DXGI numbers them in the order of the block sizes below
+        astc => {
+            let (width, height) = astc.strip_prefix("PF_ASTC_")?.split_once('x')?;
+            let block = (width.parse::<u32>().ok()?, height.parse::<u32>().ok()?);
+            let sizes = [(4, 4), (5, 4), (5, 5), (6, 5), (6, 6), (8, 5), (8, 6), (8, 8), (10, 5), (10, 6), (10, 8), (10, 10), (12, 10), (12, 12)];
+            let dxgi = 134 + 4 * sizes.iter().position(|&size| size == block)? as u32;
+            Layout { block_width: block.0, block_height: block.1, block_bytes: 16, dxgi, dxgi_srgb: dxgi + 1 }
+        }
+    };
+    Some(layout)
+}
+
+/// Bytes of one slice (or cube face) of a `width` x `height` mip; None for unknown formats
+pub fn slice_size(pixel_format: &str, width: u32, height: u32) -> Option<usize> {
+    let layout = layout(pixel_format)?;
+    Some(width.div_ceil(layout.block_width) as usize * height.div_ceil(layout.block_height) as usize * layout.block_bytes)
+}
+
+/// Decodes the first slice of a mip; None when there is no decoder for `pixel_format`
+///
+/// BC5 normal maps get their blue channel rebuilt from red and green.
+pub fn decode(pixel_format: &str, width: u32, height: u32, data: &[u8]) -> Result<Option<RgbaImage>> {
+    let Some(size) = slice_size(pixel_format, width, height) else {
+        return Ok(None);
+    };
+    let data = data
+        .get(..size)
+        .with_context(|| format!("{}x{} {} needs {} bytes, got {}", width, height, pixel_format, size, data.len()))?;
+    let mut image = RgbaImage { width, height, pixels: vec![0; width as usize * height as usize * 4] };
+
+    let pixel: fn(&[u8]) -> [u8; 4] = match pixel_format {
+        "PF_B8G8R8A8" => |p| [p[2], p[1], p[0], p[3]],
+        "PF_R8G8B8A8" => |p| [p[0], p[1], p[2], p[3]],
+        "PF_G8" => |p| [p[0], p[0], p[0], 255],
+        "PF_R8G8" => |p| [p[0], p[1], 0, 255],
+        "PF_G16" => |p| [p[1], p[1], p[1], 255],
+        _ => {
+            let block: fn(&[u8], &mut [[u8; 4]; 16]) = match pixel_format {
+                "PF_DXT1" => |b, out| decode_bc1(b, out, true),
+                "PF_DXT3" => decode_bc2,
+                "PF_DXT5" => decode_bc3,
+                "PF_BC4" => decode_bc4,
+                "PF_BC5" => decode_bc5,
+                "PF_BC7" => decode_bc7,
+                _ => return Ok(None),
+            };
+            let block_bytes = if matches!(pixel_format, "PF_DXT1" | "PF_BC4") { 8 } else { 16 };
+            let blocks_wide = width.div_ceil(4) as usize;
+            let mut texels = [[0u8; 4]; 16];
+            for (i, bytes) in data.chunks_exact(block_bytes).enumerate() {
+                block(bytes, &mut texels);
+                let (block_x, block_y) = ((i % blocks_wide) * 4, (i / blocks_wide) * 4);
+                for (j, texel) in texels.iter().enumerate() {
+                    let (x, y) = (block_x + j % 4, block_y + j / 4);
+                    if x < width as usize && y < height as usize {
+                        let at = (y * width as usize + x) * 4;
+                        image.pixels[at..at + 4].copy_from_slice(texel);
+                    }
+                }
+            }
+            return Ok(Some(image));
+        }
+    };
+    let pixel_bytes = data.len() / (width as usize * height as usize);
+    for (out, bytes) in image.pixels.chunks_exact_mut(4).zip(data.chunks_exact(pixel_bytes)) {
+        out.copy_from_slice(&pixel(bytes));
+    }
+    Ok(Some(image))
+}
+
+fn rgb565(color: u16) -> [u8; 3] {
+    let (r, g, b) = ((color >> 11) & 0x1f, (color >> 5) & 0x3f, color & 0x1f);
+    [((r << 3) | (r >> 2)) as u8, ((g << 2) | (g >> 4)) as u8, ((b << 3) | (b >> 2)) as u8]
+}
+
+/// BC1 colors; `punch_through` allows the 3-color mode with transparent black (BC2/BC3 have no such mode)
+fn decode_bc1(block: &[u8], out: &mut [[u8; 4]; 16], punch_through: bool) {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let (e0, e1) = (rgb565(c0), rgb565(c1));
+    let mix = |a: u8, b: u8, wa: u16, wb: u16| ((a as u16 * wa + b as u16 * wb) / (wa + wb)) as u8;
+    let mut palette = [[0u8; 4]; 4];
+    palette[0] = [e0[0], e0[1], e0[2], 255];
+    palette[1] = [e1[0], e1[1], e1[2], 255];
+    if c0 > c1 || !punch_through {
+        palette[2] = [mix(e0[0], e1[0], 2, 1), mix(e0[1], e1[1], 2, 1), mix(e0[2], e1[2], 2, 1), 255];
+        palette[3] = [mix(e0[0], e1[0], 1, 2), mix(e0[1], e1[1], 1, 2), mix(e0[2], e1[2], 1, 2), 255];
+    } else {
+        palette[2] = [mix(e0[0], e1[0], 1, 1), mix(e0[1], e1[1], 1, 1), mix(e0[2], e1[2], 1, 1), 255];
+    }
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    for (i, texel) in out.iter_mut().enumerate() {
+        *texel = palette[(indices >> (2 * i) & 3) as usize];
+    }
+}
+
+/// A BC4 channel: two endpoints and 3-bit indices into 8 values
+fn decode_bc4_channel(block: &[u8]) -> [u8; 16] {
+    let (a0, a1) = (block[0] as u32, block[1] as u32);
+    let mut values = [0u8; 8];
+    values[0] = a0 as u8;
+    values[1] = a1 as u8;
+    if a0 > a1 {
+        for i in 1..7 {
+            values[i + 1] = (((7 - i as u32) * a0 + i as u32 * a1) / 7) as u8;
+        }
+    } else {
+        for i in 1..5 {
+            values[i + 1] = (((5 - i as u32) * a0 + i as u32 * a1) / 5) as u8;
+        }
+        values[7] = 255;
+    }
+    let indices = block[2..8].iter().rev().fold(0u64, |bits, &b| (bits << 8) | b as u64);
+    std::array::from_fn(|i| values[(indices >> (3 * i) & 7) as usize])
+}
+
+fn decode_bc2(block: &[u8], out: &mut [[u8; 4]; 16]) {
+    decode_bc1(&block[8..], out, false);
+    for (i, texel) in out.iter_mut().enumerate() {
+        texel[3] = (block[i / 2] >> (4 * (i % 2)) & 0xf) * 17;
+    }
+}
+
+fn decode_bc3(block: &[u8], out: &mut [[u8; 4]; 16]) {
+    decode_bc1(&block[8..], out, false);
+    for (texel, alpha) in out.iter_mut().zip(decode_bc4_channel(block)) {
+        texel[3] = alpha;
+    }
+}
+
+fn decode_bc4(block: &[u8], out: &mut [[u8; 4]; 16]) {
+    for (texel, red) in out.iter_mut().zip(decode_bc4_channel(block)) {
+        *texel = [red, red, red, 255];
+    }
+}
+
+fn decode_bc5(block: &[u8], out: &mut [[u8; 4]; 16]) {
+    let (red, green) = (decode_bc4_channel(block), decode_bc4_channel(&block[8..]));
+    for (i, texel) in out.iter_mut().enumerate() {
+        let (x, y) = (red[i] as f32 / 127.5 - 1.0, green[i] as f32 / 127.5 - 1.0);
+        let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+        *texel = [red[i], green[i], ((z + 1.0) * 127.5).round() as u8, 255];
+    }
+}
+
+/// Field sizes of a BC7 mode
+struct Bc7Mode {
+    subsets: usize,
+    partition_bits: u32,
+    rotation_bits: u32,
+    selection_bits: u32,
+    color_bits: u32,
+    alpha_bits: u32,
+    /// One P-bit per endpoint, or one shared by the two endpoints of a subset
+    endpoint_pbits: bool,
+    shared_pbits: bool,
+    index_bits: u32,
+    /// Size of the second index set of modes 4 and 5, 0 elsewhere
+    index_bits2: u32,
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn bc7_mode(subsets: usize, partition_bits: u32, rotation_bits: u32, selection_bits: u32, color_bits: u32, alpha_bits: u32, pbits: (bool, bool), index_bits: (u32, u32)) -> Bc7Mode {
+    Bc7Mode {
+        subsets,
+        partition_bits,
+        rotation_bits,
+        selection_bits,
+        color_bits,
+        alpha_bits,
+        endpoint_pbits: pbits.0,
+        shared_pbits: pbits.1,
+        index_bits: index_bits.0,
+        index_bits2: index_bits.1,
+    }
+}
+
+const BC7_MODES: [Bc7Mode; 8] = [
+    bc7_mode(3, 4, 0, 0, 4, 0, (true, false), (3, 0)),
+    bc7_mode(2, 6, 0, 0, 6, 0, (false, true), (3, 0)),
+    bc7_mode(3, 6, 0, 0, 5, 0, (false, false), (2, 0)),
+    bc7_mode(2, 6, 0, 0, 7, 0, (true, false), (2, 0)),
+    bc7_mode(1, 0, 2, 1, 5, 6, (false, false), (2, 3)),
+    bc7_mode(1, 0, 2, 0, 7, 8, (false, false), (2, 2)),
+    bc7_mode(1, 0, 0, 0, 7, 7, (true, false), (4, 0)),
+    bc7_mode(2, 6, 0, 0, 5, 5, (true, false), (2, 0)),
+];
+
+/// Subset of each texel in the 2-subset partitions, one bit per texel
+const BC7_PARTITIONS_2: [u16; 64] = [
+    0xcccc, 0x8888, 0xeeee, 0xecc8, 0xc880, 0xfeec, 0xfec8, 0xec80, 0xc800, 0xffec, 0xfe80, 0xe800, 0xffe8, 0xff00, 0xfff0, 0xf000,
+    0xf710, 0x008e, 0x7100, 0x08ce, 0x008c, 0x7310, 0x3100, 0x8cce, 0x088c, 0x3110, 0x6666, 0x366c, 0x17e8, 0x0ff0, 0x718e, 0x399c,
+    0xaaaa, 0xf0f0, 0x5a5a, 0x33cc, 0x3c3c, 0x55aa, 0x9696, 0xa55a, 0x73ce, 0x13c8, 0x324c, 0x3bdc, 0x6996, 0xc33c, 0x9966, 0x0660,
+    0x0272, 0x04e4, 0x4e40, 0x2720, 0xc936, 0x936c, 0x39c6, 0x639c, 0x9336, 0x9cc6, 0x817e, 0xe718, 0xccf0, 0x0fcc, 0x7744, 0xee22,
+];
+
+/// Subset of each texel in the 3-subset partitions
+const BC7_PARTITIONS_3: [[u8; 16]; 64] = [
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 1, 2, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 2, 0, 0, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 1, 0, 1, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2],
+    [0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0, 2, 2, 2, 0],
+    [0, 0, 0, 1, 0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2],
+    [0, 1, 1, 1, 0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0],
+    [0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1],
+    [0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2, 0, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 0, 1, 2, 2, 2, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 0, 0, 1, 1, 0, 0, 2, 2, 1, 0, 2, 2, 1, 0],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1, 0, 0, 0, 0],
+    [0, 0, 1, 2, 0, 0, 1, 2, 1, 1, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1, 0, 1, 1, 0],
+    [0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1],
+    [0, 0, 2, 2, 1, 1, 0, 2, 1, 1, 0, 2, 0, 0, 2, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 0, 0, 2, 2, 2, 2, 2],
+    [0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 0, 0, 2, 0, 0, 0, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 2, 0, 0, 2, 2, 0, 2, 2, 2],
+    [0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0],
+    [0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0],
+    [0, 1, 2, 0, 2, 0, 1, 2, 1, 2, 0, 1, 0, 1, 2, 0],
+    [0, 0, 1, 1, 2, 2, 0, 0, 1, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0, 1, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 0, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 1, 1],
+    [0, 2, 2, 0, 1, 2, 2, 1, 0, 2, 2, 0, 1, 2, 2, 1],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 0, 1, 0, 1],
+    [0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 2, 2, 2, 0, 1, 1, 1],
+    [0, 0, 0, 2, 1, 1, 1, 2, 0, 0, 0, 2, 1, 1, 1, 2],
+    [0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2],
+    [0, 0, 0, 2, 1, 1, 1, 2, 1, 1, 1, 2, 0, 0, 0, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2],
+    [0, 0, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2],
+    [0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1],
+    [0, 2, 2, 2, 1, 2, 2, 2, 0, 2, 2, 2, 1, 2, 2, 2],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 1, 2, 0, 1, 1, 2, 2, 0, 1, 2, 2, 2, 0],
+];
+
+/// Anchor texel of the second subset in the 2-subset partitions
+const BC7_ANCHORS_2: [u8; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 2, 8, 2, 2, 8, 8, 15, 2, 8, 2, 2, 8, 8, 2, 2,
+    15, 15, 6, 8, 2, 8, 15, 15, 2, 8, 2, 2, 2, 15, 15, 6, 6, 2, 6, 8, 15, 15, 2, 2, 15, 15, 15, 15, 15, 2, 2, 15,
+];
+
+/// Anchor texels of the second and third subsets in the 3-subset partitions
+const BC7_ANCHORS_3: [[u8; 64]; 2] = [
+    [
+        3, 3, 15, 15, 8, 3, 15, 15, 8, 8, 6, 6, 6, 5, 3, 3, 3, 3, 8, 15, 3, 3, 6, 10, 5, 8, 8, 6, 8, 5, 15, 15,
+        8, 15, 3, 5, 6, 10, 8, 15, 15, 3, 15, 5, 15, 15, 15, 15, 3, 15, 5, 5, 5, 8, 5, 10, 5, 10, 8, 13, 15, 12, 3, 3,
+    ],
+    [
+        15, 8, 8, 3, 15, 15, 3, 8, 15, 15, 15, 15, 15, 15, 15, 8, 15, 8, 15, 3, 15, 8, 15, 8, 3, 15, 6, 10, 15, 15, 10, 8,
+        15, 3, 15, 10, 10, 8, 9, 10, 6, 15, 8, 15, 3, 6, 6, 8, 15, 3, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 3, 15, 15, 8,
+    ],
+];
+
+fn bc7_weights(bits: u32) -> &'static [u32] {
+    match bits {
+        2 => &[0, 21, 43, 64],
+        3 => &[0, 9, 18, 27, 37, 46, 55, 64],
+        _ => &[0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64],
+    }
+}
+
+/// Reads a BC7 block's fields, least significant bit first
+struct BitReader<'a> {
+    block: &'a [u8],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, bits: u32) -> u32 {
+        let mut value = 0;
+        for i in 0..bits {
+            let bit = (self.block[self.position / 8] >> (self.position % 8)) & 1;
+            value |= (bit as u32) << i;
+            self.position += 1;
+        }
+        value
+    }
+}
+
+fn decode_bc7(block: &[u8], out: &mut [[u8; 4]; 16]) {
+    let Some(mode) = (0..8).find(|&mode| block[0] & (1 << mode) != 0) else {
+        // Reserved mode: transparent black
+        *out = [[0; 4]; 16];
+        return;
+    };
+    let Bc7Mode { subsets, partition_bits, rotation_bits, selection_bits, color_bits, alpha_bits, endpoint_pbits, shared_pbits, index_bits, index_bits2 } =
+        BC7_MODES[mode];
+    let mut reader = BitReader { block, position: mode + 1 };
+    let partition = reader.read(partition_bits) as usize;
+    let rotation = reader.read(rotation_bits);
+    let selection = reader.read(selection_bits);
+
+    let mut endpoints = [[0u32; 4]; 6];
+    for channel in 0..3 {
+        for endpoint in endpoints.iter_mut().take(subsets * 2) {
+            endpoint[channel] = reader.read(color_bits);
+        }
+    }
+    for endpoint in endpoints.iter_mut().take(subsets * 2) {
+        endpoint[3] = reader.read(alpha_bits);
+    }
+    let mut precision = [color_bits, color_bits, color_bits, alpha_bits];
+    if endpoint_pbits || shared_pbits {
+        let pbits: Vec<u32> = if endpoint_pbits {
+            (0..subsets * 2).map(|_| reader.read(1)).collect()
+        } else {
+            (0..subsets).map(|_| reader.read(1)).flat_map(|bit| [bit, bit]).collect()
+        };
+        let channels = if alpha_bits > 0 { 4 } else { 3 };
+        for (endpoint, pbit) in endpoints.iter_mut().zip(pbits) {
+            for value in endpoint.iter_mut().take(channels) {
+                *value = (*value << 1) | pbit;
+            }
+        }
+        for bits in precision.iter_mut().take(channels) {
+            *bits += 1;
+        }
+    }
+    for endpoint in endpoints.iter_mut().take(subsets * 2) {
+        for (value, &bits) in endpoint.iter_mut().zip(&precision) {
+            *value = match bits {
+                0 => 255,
+                8 => *value,
+                bits => (*value << (8 - bits)) | (*value >> (2 * bits - 8)),
+            };
+        }
+    }
+
+    let subset = |texel: usize| match subsets {
+        1 => 0,
+        2 => (BC7_PARTITIONS_2[partition] >> texel & 1) as usize,
+        _ => BC7_PARTITIONS_3[partition][texel] as usize,
+    };
+    let is_anchor = |texel: usize| {
+        texel == 0
+            || (subsets == 2 && texel == BC7_ANCHORS_2[partition] as usize)
+            || (subsets == 3 && (texel == BC7_ANCHORS_3[0][partition] as usize || texel == BC7_ANCHORS_3[1][partition] as usize))
+    };
+    // Anchor texels drop the top bit of their index, which is always 0
+    let indices: [u32; 16] = std::array::from_fn(|texel| reader.read(index_bits - is_anchor(texel) as u32));
+    let indices2: [u32; 16] = std::array::from_fn(|texel| match index_bits2 {
+        0 => 0,
+        bits => reader.read(bits - (texel == 0) as u32),
+    });
+
+    for (texel, out) in out.iter_mut().enumerate() {
+        let s = subset(texel);
+        let (e0, e1) = (endpoints[2 * s], endpoints[2 * s + 1]);
+        let (color_weight, alpha_weight) = match (index_bits2, selection) {
+            (0, _) => {
+                let weight = bc7_weights(index_bits)[indices[texel] as usize];
+                (weight, weight)
+            }
+            (_, 0) => (bc7_weights(index_bits)[indices[texel] as usize], bc7_weights(index_bits2)[indices2[texel] as usize]),
+            _ => (bc7_weights(index_bits2)[indices2[texel] as usize], bc7_weights(index_bits)[indices[texel] as usize]),
+        };
+        let interpolate = |channel: usize, weight: u32| (((64 - weight) * e0[channel] + weight * e1[channel] + 32) >> 6) as u8;
+        let mut texel = [interpolate(0, color_weight), interpolate(1, color_weight), interpolate(2, color_weight), interpolate(3, alpha_weight)];
+        if rotation > 0 {
+            texel.swap(3, rotation as usize - 1);
+        }
+        *out = texel;
+    }
+}
+
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&image.pixels)?;
+    writer.finish()?;
+    Ok(out)
+}
+
+/// Uncompressed 32-bit TGA, stored top to bottom
+pub fn encode_tga(image: &RgbaImage) -> Result<Vec<u8>> {
+    let (Ok(width), Ok(height)) = (u16::try_from(image.width), u16::try_from(image.height)) else {
+        bail!("TGA can't hold {}x{} images", image.width, image.height);
+    };
+    let mut out = Vec::with_capacity(18 + image.pixels.len());
+    out.extend_from_slice(&[0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    out.extend_from_slice(&[32, 0x28]); // 8 alpha bits, top-left origin
+    for pixel in image.pixels.chunks_exact(4) {
+        out.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+    }
+    Ok(out)
+}
+
+/// Writes the first slice of a mip as stored behind a DDS header
+///
+/// DXT1/3/5 get a legacy header most tools read; everything else, ASTC included, a DX10
+/// header with its DXGI format.
+pub fn encode_dds(pixel_format: &str, width: u32, height: u32, srgb: bool, data: &[u8]) -> Result<Vec<u8>> {
+    let layout = layout(pixel_format).with_context(|| format!("{} has no DDS equivalent", pixel_format))?;
+    let size = slice_size(pixel_format, width, height).unwrap_or_default();
+    let data = data
+        .get(..size)
+        .with_context(|| format!("{}x{} {} needs {} bytes, got {}", width, height, pixel_format, size, data.len()))?;
+    let compressed = layout.block_width > 1;
+    let four_cc = match pixel_format {
+        "PF_DXT1" => *b"DXT1",
+        "PF_DXT3" => *b"DXT3",
+        "PF_DXT5" => *b"DXT5",
+        _ => *b"DX10",
+    };
+
+    const DDSD_CAPS_HEIGHT_WIDTH_PIXELFORMAT: u32 = 0x1 | 0x2 | 0x4 | 0x1000;
+    const DDSD_PITCH: u32 = 0x8;
+    const DDSD_LINEARSIZE: u32 = 0x80000;
+    const DDPF_FOURCC: u32 = 0x4;
+    const DDSCAPS_TEXTURE: u32 = 0x1000;
+    const D3D10_RESOURCE_DIMENSION_TEXTURE2D: u32 = 3;
+
+    let mut header = vec![124, DDSD_CAPS_HEIGHT_WIDTH_PIXELFORMAT | if compressed { DDSD_LINEARSIZE } else { DDSD_PITCH }, height, width];
+    header.push(if compressed { size as u32 } else { width * layout.block_bytes as u32 });
+    header.extend([0, 1]); // Depth, mip count
+    header.extend([0; 11]);
+    header.extend([32, DDPF_FOURCC, u32::from_le_bytes(four_cc), 0, 0, 0, 0, 0]);
+    header.extend([DDSCAPS_TEXTURE, 0, 0, 0, 0]);
+    if &four_cc == b"DX10" {
+        let dxgi = if srgb { layout.dxgi_srgb } else { layout.dxgi };
+        header.extend([dxgi, D3D10_RESOURCE_DIMENSION_TEXTURE2D, 0, 1, 0]);
+    }
+
+    let mut out = Vec::with_capacity(4 + header.len() * 4 + data.len());
+    out.extend_from_slice(b"DDS ");
+    for value in header {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out.extend_from_slice(data);
+    Ok(out)
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use anyhow::{bail, Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// First UE5 object version; cooked UE5 platform data starts with a derived-data placeholder
+const VER_UE5_INITIAL_VERSION: i32 = 1000;
+
+/// FTexturePlatformData packed-data flag: two extra u32s follow the pixel format
+const PACKED_HAS_OPT_DATA: u32 = 1 << 30;
+
+// FByteBulkData flags
+const BULKDATA_PAYLOAD_AT_END_OF_FILE: u32 = 0x1;
+const BULKDATA_SERIALIZE_COMPRESSED_ZLIB: u32 = 0x2;
+const BULKDATA_UNUSED: u32 = 0x20;
+const BULKDATA_FORCE_INLINE_PAYLOAD: u32 = 0x40;
+const BULKDATA_PAYLOAD_IN_SEPARATE_FILE: u32 = 0x100;
+const BULKDATA_OPTIONAL_PAYLOAD: u32 = 0x800;
+const BULKDATA_SIZE_64BIT: u32 = 0x2000;
+const BULKDATA_MEMORY_MAPPED_PAYLOAD: u32 = 0x4000;
+const BULKDATA_NO_OFFSET_FIX_UP: u32 = 0x10000;
+
+/// Header facts of a cooked texture: size and pixel format of its first platform data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureInfo {
+    pub class: String,
+    pub width: u32,
+    pub height: u32,
+    /// "PF_DXT5", "PF_BC7", "PF_B8G8R8A8", ...
+    pub pixel_format: String,
+    pub mip_levels: Option<u32>,
+    pub compression_settings: Option<String>,
+    pub srgb: Option<bool>,
+}
+
+/// Where the bytes of one mip are stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MipPayload {
+    /// Bytes `offset..offset + size` of the package (.uasset followed by .uexp)
+    Package { offset: u64, size: u64 },
+    /// Bytes `offset..offset + size` of the file next to the package with `extension`
+    /// ("ubulk", "m.ubulk" or "uptnl")
+    Sibling { extension: &'static str, offset: u64, size: u64 },
+    /// Not cooked for this platform, or left out of the cooked data
+    Missing,
+}
+
+/// One mip of a cooked texture, largest first
+#[derive(Debug, Clone)]
+pub struct TextureMip {
+    pub width: u32,
+    pub height: u32,
+    /// Slices of an array or volume texture, 6 faces for a cube
+    pub depth: u32,
+    pub payload: MipPayload,
+}
+
+/// A cooked texture's header and the location of each of its mips
+#[derive(Debug, Clone)]
+pub struct CookedTexture {
+    pub info: TextureInfo,
+    pub mips: Vec<TextureMip>,
+}
+
+/// Returns true for UE texture classes whose cooked data starts with FTexturePlatformData
+pub fn is_texture_class(class: &str) -> bool {
+    matches!(class, "Texture2D" | "TextureCube" | "Texture2DArray" | "TextureCubeArray" | "VolumeTexture" | "LightMapTexture2D" | "ShadowMapTexture2D")
+}
+
+/// Reads a cooked texture's properties and the header of its first platform data
+///
+/// Only the header is read; mip data is left untouched.
+pub fn parse_texture(data: &[u8]) -> Result<TextureInfo> {
+    let package = Package::parse(data)?;
+    let (info, _) = read_platform_data(&package, data)?;
+    Ok(info)
+}
+
+/// Reads a cooked texture's header and where each of its mips is stored
+///
+/// Mips are listed from `FirstMipToSerialize` down, so the first one can be smaller than
+/// `info.width` x `info.height` when the largest mips aren't cooked.
+pub fn parse_texture_mips(data: &[u8]) -> Result<CookedTexture> {
+    let package = Package::parse(data)?;
+    let (info, reader) = read_platform_data(&package, data)?;
+    let (mut reader, export_offset) = reader.context("Unrecognized mip table layout")?;
+
+    // Before UE5 each mip starts with a bCooked flag
+    let cooked_flag = package.summary.file_version_ue5 < VER_UE5_INITIAL_VERSION;
+    let count = reader.read_count(if cooked_flag { 32 } else { 28 })?;
+    let mut mips = Vec::with_capacity(count);
+    for i in 0..count {
+        if cooked_flag {
+            reader.read_i32()?;
+        }
+        let payload = read_bulk_data(&package, &mut reader, export_offset, data.len() as u64)
+            .with_context(|| format!("Failed to read the bulk data of mip {}", i))?;
+        let width = reader.read_i32()?;
+        let height = reader.read_i32()?;
+        let depth = reader.read_i32()?;
+        if width <= 0 || height <= 0 || depth <= 0 || width as u32 > info.width || height as u32 > info.height {
+            bail!("Mip {} has an implausible size {}x{}x{}", i, width, height, depth);
+        }
+        mips.push(TextureMip { width: width as u32, height: height as u32, depth: depth as u32, payload });
+    }
+    Ok(CookedTexture { info, mips })
+}
+
+/// Reads the texture export up to its mip count; with the reader positioned there and the
+/// export's offset in the package, unless the fields before the mips weren't recognized
+fn read_platform_data<'a>(package: &Package, data: &'a [u8]) -> Result<(TextureInfo, Option<(ArchiveReader<'a>, u64)>)> {
+    let export = package
+        .exports
+        .iter()
+        .find(|e| is_texture_class(&package.export_class_name(e)))
+        .context("Package has no texture export")?;
+    let class = package.export_class_name(export);
+
+    let mut reader = ArchiveReader::new(package.export_data(data, export)?);
+    let properties = uasset_parser::read_properties(package, &mut reader, &class)?;
+    uasset_parser::skip_object_guid(&mut reader)?;
+
+    reader.skip(2)?; // UTexture FStripDataFlags
+    reader.skip(2)?; // UTexture2D FStripDataFlags
+    if !reader.read_bool()? {
+        bail!("Texture was not saved with cooked platform data");
+    }
+
+    let format_name = package.read_name(&mut reader)?;
+    if format_name == "None" {
+        bail!("Texture has no cooked platform data");
+    }
+    reader.read_i64()?; // SkipOffset
+    if package.summary.file_version_ue5 >= VER_UE5_INITIAL_VERSION {
+        reader.skip(16)?; // Derived-data placeholder
+    }
+
+    let width = reader.read_i32()?;
+    let height = reader.read_i32()?;
+    let packed = reader.read_u32()?;
+    let pixel_format = reader.read_fstring()?;
+    if width <= 0 || height <= 0 || !pixel_format.starts_with("PF_") {
+        bail!("Unrecognized texture platform data layout");
+    }
+
+    // Mip count is best-effort; the fields before it vary between engine versions
+    let mips_start = (|| -> Result<usize> {
+        if packed & PACKED_HAS_OPT_DATA != 0 {
+            reader.skip(8)?; // ExtData, NumMipsInTail
+        }
+        reader.read_i32()?; // FirstMipToSerialize
+        Ok(reader.position())
+    })()
+    .ok();
+    let mip_levels = mips_start.and_then(|start| {
+        let count = reader.read_count(1).ok();
+        reader.seek(start).ok().and(count)
+    });
+
+    let info = TextureInfo {
+        class,
+        width: width as u32,
+        height: height as u32,
+        pixel_format,
+        mip_levels: mip_levels.map(|count| count as u32),
+        compression_settings: properties.get("CompressionSettings").and_then(Value::as_str).map(str::to_string),
+        srgb: properties.get("SRGB").and_then(Value::as_bool),
+    };
+    Ok((info, mips_start.map(|_| (reader, export.serial_offset as u64))))
+}
+
+/// Reads an FByteBulkData header, skipping the payload when it's stored inline
+fn read_bulk_data(package: &Package, reader: &mut ArchiveReader, export_offset: u64, package_len: u64) -> Result<MipPayload> {
+    let flags = reader.read_u32()?;
+    let size = if flags & BULKDATA_SIZE_64BIT != 0 {
+        reader.read_i64()?; // Element count
+        reader.read_i64()?
+    } else {
+        reader.read_i32()?; // Element count
+        reader.read_i32()? as i64
+    };
+    let offset = reader.read_i64()?;
+    let size = u64::try_from(size).context("Negative bulk data size")?;
+    if flags & BULKDATA_UNUSED != 0 || size == 0 {
+        return Ok(MipPayload::Missing);
+    }
+    if flags & BULKDATA_SERIALIZE_COMPRESSED_ZLIB != 0 {
+        bail!("Zlib-compressed bulk data is not supported");
+    }
+    let stored_elsewhere = flags & (BULKDATA_PAYLOAD_AT_END_OF_FILE | BULKDATA_PAYLOAD_IN_SEPARATE_FILE) != 0;
+    if flags & BULKDATA_FORCE_INLINE_PAYLOAD != 0 || !stored_elsewhere {
+        let offset = export_offset + reader.position() as u64;
+        reader.skip(usize::try_from(size)?)?;
+        return Ok(MipPayload::Package { offset, size });
+    }
+
+    // Offsets are into the package as if .uasset, .uexp and .ubulk were one file, unless fixed up at cook time
+    let mut offset = u64::try_from(offset).context("Negative bulk data offset")?;
+    if flags & BULKDATA_NO_OFFSET_FIX_UP == 0 {
+        offset += package.summary.bulk_data_start_offset.max(0) as u64;
+    }
+    if flags & BULKDATA_PAYLOAD_IN_SEPARATE_FILE != 0 {
+        let extension = if flags & BULKDATA_OPTIONAL_PAYLOAD != 0 {
+            "uptnl"
+        } else if flags & BULKDATA_MEMORY_MAPPED_PAYLOAD != 0 {
+            "m.ubulk"
+        } else {
+            "ubulk"
+        };
+        if flags & BULKDATA_NO_OFFSET_FIX_UP == 0 {
+            offset = offset.checked_sub(package_len).context("Bulk data offset lies before the .ubulk")?;
+        }
+        return Ok(MipPayload::Sibling { extension, offset, size });
+    }
+    Ok(MipPayload::Package { offset, size })
+}
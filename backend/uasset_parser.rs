@@ -0,0 +1,1628 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+
+use crate::error::MappingsRequired;
+use crate::usmap::{Mappings, PropertyType};
+
+/// Magic tag at the start of every classic (non-zen) package
+pub const PACKAGE_FILE_TAG: u32 = 0x9E2A83C1;
+
+/// Package flag set when editor-only data has been stripped (cooked packages)
+pub const PKG_FILTER_EDITOR_ONLY: u32 = 0x8000_0000;
+
+/// Package flag set when properties are serialized without tags (needs .usmap)
+pub const PKG_UNVERSIONED_PROPERTIES: u32 = 0x0000_2000;
+
+// Object versions that change the layout of the structures we read
+const VER_UE4_WORLD_LEVEL_INFO: i32 = 224;
+const VER_UE4_ADDED_CHUNKID_TO_ASSETDATA_AND_UPACKAGE: i32 = 278;
+const VER_UE4_PACKAGE_SUMMARY_HAS_COMPATIBLE_ENGINE_VERSION: i32 = 444;
+const VER_UE4_ENGINE_VERSION_OBJECT: i32 = 336;
+const VER_UE4_LOAD_FOR_EDITOR_GAME: i32 = 365;
+const VER_UE4_ADD_STRING_ASSET_REFERENCES_MAP: i32 = 384;
+const VER_UE4_STRUCT_GUID_IN_PROPERTY_TAG: i32 = 441;
+const VER_UE4_ADDED_PACKAGE_SUMMARY_LOCALIZATION_ID: i32 = 516;
+const VER_UE4_COOKED_ASSETS_IN_EDITOR_SUPPORT: i32 = 485;
+const VER_UE4_PROPERTY_GUID_IN_PROPERTY_TAG: i32 = 503;
+const VER_UE4_NAME_HASHES_SERIALIZED: i32 = 504;
+const VER_UE4_PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS: i32 = 507;
+const VER_UE4_TEMPLATE_INDEX_IN_COOKED_EXPORTS: i32 = 508;
+const VER_UE4_SERIALIZE_TEXT_IN_PACKAGES: i32 = 459;
+const VER_UE4_ADDED_SEARCHABLE_NAMES: i32 = 510;
+const VER_UE4_64BIT_EXPORTMAP_SERIALSIZES: i32 = 511;
+const VER_UE4_ADDED_PACKAGE_OWNER: i32 = 518;
+const VER_UE4_NON_OUTER_PACKAGE_IMPORT: i32 = 520;
+
+const VER_UE5_NAMES_REFERENCED_FROM_EXPORT_DATA: i32 = 1001;
+const VER_UE5_PAYLOAD_TOC: i32 = 1002;
+const VER_UE5_OPTIONAL_RESOURCES: i32 = 1003;
+const VER_UE5_LARGE_WORLD_COORDINATES: i32 = 1004;
+const VER_UE5_REMOVE_OBJECT_EXPORT_PACKAGE_GUID: i32 = 1005;
+const VER_UE5_TRACK_OBJECT_EXPORT_IS_INHERITED: i32 = 1006;
+const VER_UE5_FSOFTOBJECTPATH_REMOVE_ASSET_PATH_FNAMES: i32 = 1007;
+const VER_UE5_ADD_SOFTOBJECTPATH_LIST: i32 = 1008;
+const VER_UE5_DATA_RESOURCES: i32 = 1009;
+const VER_UE5_SCRIPT_SERIALIZATION_OFFSET: i32 = 1010;
+const VER_UE5_PROPERTY_TAG_EXTENSION_AND_OVERRIDABLE_SERIALIZATION: i32 = 1011;
+const VER_UE5_PROPERTY_TAG_COMPLETE_TYPE_NAME: i32 = 1012;
+const VER_UE5_METADATA_SERIALIZATION_OFFSET: i32 = 1014;
+const VER_UE5_VERSE_CELLS: i32 = 1015;
+const VER_UE5_PACKAGE_SAVED_HASH: i32 = 1016;
+
+/// Little-endian cursor over serialized Unreal archive bytes
+pub struct ArchiveReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ArchiveReader<'a> {
+    /// Creates a reader positioned at the start of the buffer
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Current read offset
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Moves the cursor to an absolute offset
+    pub fn seek(&mut self, pos: usize) -> Result<()> {
+        if pos > self.data.len() {
+            bail!("Seek to {} past end of buffer ({} bytes)", pos, self.data.len());
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    /// Number of bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    /// Reads `len` raw bytes
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.remaining() {
+            bail!("Unexpected end of data at offset {} (wanted {} bytes)", self.pos, len);
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Skips `len` bytes
+    pub fn skip(&mut self, len: usize) -> Result<()> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.read_bytes(N)?);
+        Ok(out)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_f64(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.read_array()?))
+    }
+
+    /// Reads a 32-bit FArchive bool (anything non-zero is true)
+    pub fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read_u32()? != 0)
+    }
+
+    /// Reads a 16-byte FGuid
+    pub fn read_guid(&mut self) -> Result<[u8; 16]> {
+        self.read_array()
+    }
+
+    /// Reads an array element count, rejecting negative or absurd values
+    pub fn read_count(&mut self, element_size: usize) -> Result<usize> {
+        let count = self.read_i32()?;
+        self.check_count(count, element_size)
+            .with_context(|| format!("Bad array count at offset {}", self.pos - 4))
+    }
+
+    /// `count` as a length, when it isn't negative and the data left can hold that many
+    /// elements of at least `element_size` bytes; for counts read elsewhere, like the
+    /// summary's, before reserving room for them
+    pub fn check_count(&self, count: i32, element_size: usize) -> Result<usize> {
+        if count < 0 {
+            bail!("Negative count {}", count);
+        }
+        let count = count as usize;
+        if count.saturating_mul(element_size.max(1)) > self.remaining() {
+            bail!("Count {} exceeds the {} bytes left", count, self.remaining());
+        }
+        Ok(count)
+    }
+
+    /// Reads an FString (ANSI when the length is positive, UTF-16 when negative)
+    pub fn read_fstring(&mut self) -> Result<String> {
+        let len = self.read_i32()?;
+        match len {
+            0 => Ok(String::new()),
+            len if len > 0 => {
+                let bytes = self.read_bytes(len as usize)?;
+                let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+                Ok(String::from_utf8_lossy(bytes).into_owned())
+            }
+            len => {
+                let chars = len.unsigned_abs() as usize;
+                let bytes = self.read_bytes(chars * 2)?;
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .take_while(|&u| u != 0)
+                    .collect();
+                Ok(String::from_utf16_lossy(&units))
+            }
+        }
+    }
+}
+
+/// Engine version recorded in the package summary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EngineVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    pub changelist: u32,
+    pub branch: String,
+}
+
+/// Custom version entry (GUID + version number)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomVersion {
+    pub guid: [u8; 16],
+    pub version: i32,
+}
+
+/// Parsed FPackageFileSummary of a classic .uasset/.umap package
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageSummary {
+    pub legacy_file_version: i32,
+    pub file_version_ue4: i32,
+    pub file_version_ue5: i32,
+    pub file_version_licensee: i32,
+    pub custom_versions: Vec<CustomVersion>,
+    pub total_header_size: i32,
+    pub folder_name: String,
+    pub package_flags: u32,
+    pub name_count: i32,
+    pub name_offset: i32,
+    pub soft_object_paths_count: i32,
+    pub soft_object_paths_offset: i32,
+    pub gatherable_text_data_count: i32,
+    pub gatherable_text_data_offset: i32,
+    pub export_count: i32,
+    pub export_offset: i32,
+    pub import_count: i32,
+    pub import_offset: i32,
+    pub depends_offset: i32,
+    pub soft_package_references_count: i32,
+    pub soft_package_references_offset: i32,
+    pub searchable_names_offset: i32,
+    pub thumbnail_table_offset: i32,
+    pub saved_by_engine_version: EngineVersion,
+    pub compatible_engine_version: EngineVersion,
+    pub asset_registry_data_offset: i32,
+    pub bulk_data_start_offset: i64,
+    pub world_tile_info_data_offset: i32,
+    pub chunk_ids: Vec<i32>,
+    pub preload_dependency_count: i32,
+    pub preload_dependency_offset: i32,
+}
+
+impl PackageSummary {
+    /// Returns true when editor-only data was stripped at cook time
+    pub fn is_filter_editor_only(&self) -> bool {
+        self.package_flags & PKG_FILTER_EDITOR_ONLY != 0
+    }
+
+    /// Returns true when properties were saved without tags
+    pub fn has_unversioned_properties(&self) -> bool {
+        self.package_flags & PKG_UNVERSIONED_PROPERTIES != 0
+    }
+
+    /// Looks up a custom version by GUID
+    pub fn custom_version(&self, guid: &[u8; 16]) -> Option<i32> {
+        self.custom_versions
+            .iter()
+            .find(|v| &v.guid == guid)
+            .map(|v| v.version)
+    }
+}
+
+/// FName as stored in serialized data: name map index plus instance number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameRef {
+    pub index: i32,
+    pub number: i32,
+}
+
+/// Entry of the import map (FObjectImport)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectImport {
+    pub class_package: String,
+    pub class_name: String,
+    pub outer_index: i32,
+    pub object_name: String,
+    pub package_name: Option<String>,
+    pub import_optional: bool,
+}
+
+/// Entry of the export map (FObjectExport)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectExport {
+    pub class_index: i32,
+    pub super_index: i32,
+    pub template_index: i32,
+    pub outer_index: i32,
+    pub object_name: String,
+    pub object_flags: u32,
+    pub serial_size: i64,
+    pub serial_offset: i64,
+    pub forced_export: bool,
+    pub not_for_client: bool,
+    pub not_for_server: bool,
+    pub is_asset: bool,
+}
+
+/// An editor thumbnail stored in the package's thumbnail table (FObjectThumbnail)
+#[derive(Debug, Clone)]
+pub struct EmbeddedThumbnail {
+    pub object_class: String,
+    pub object_path: String,
+    pub width: u32,
+    pub height: u32,
+    /// "image/png", or "image/jpeg" for thumbnails saved JPEG-compressed
+    pub mime: &'static str,
+    pub data: Vec<u8>,
+}
+
+/// A parsed classic package: summary, name map, imports and exports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Package {
+    pub summary: PackageSummary,
+    pub names: Vec<String>,
+    pub imports: Vec<ObjectImport>,
+    pub exports: Vec<ObjectExport>,
+    /// UE5.1+ table of soft object paths referenced by index from export data
+    pub soft_object_paths: Vec<String>,
+}
+
+impl Package {
+    /// Parses the package header from the combined .uasset (+ .uexp) bytes
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let mut reader = ArchiveReader::new(data);
+        let summary = read_summary(&mut reader).context("Failed to read package summary")?;
+
+        let names = read_name_map(&mut reader, &summary).context("Failed to read name map")?;
+
+        let mut package = Package {
+            summary,
+            names,
+            imports: Vec::new(),
+            exports: Vec::new(),
+            soft_object_paths: Vec::new(),
+        };
+
+        package.imports = package.read_import_map(&mut reader).context("Failed to read import map")?;
+        package.exports = package.read_export_map(&mut reader).context("Failed to read export map")?;
+        package.soft_object_paths = package
+            .read_soft_object_path_list(&mut reader)
+            .context("Failed to read soft object path list")?;
+
+        Ok(package)
+    }
+
+    /// Reads an FName and resolves it against the name map
+    pub fn read_name(&self, reader: &mut ArchiveReader) -> Result<String> {
+        let index = reader.read_i32()?;
+        let number = reader.read_i32()?;
+        self.resolve_name(NameRef { index, number })
+    }
+
+    /// Resolves an FName reference to its display string
+    pub fn resolve_name(&self, name: NameRef) -> Result<String> {
+        let base = usize::try_from(name.index)
+            .ok()
+            .and_then(|i| self.names.get(i))
+            .with_context(|| format!("Name index {} out of range ({} names)", name.index, self.names.len()))?;
+        if name.number > 0 {
+            Ok(format!("{}_{}", base, name.number - 1))
+        } else {
+            Ok(base.clone())
+        }
+    }
+
+    /// Returns the import referenced by a negative package index
+    pub fn import(&self, package_index: i32) -> Option<&ObjectImport> {
+        if package_index < 0 {
+            self.imports.get((-package_index - 1) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the export referenced by a positive package index
+    pub fn export(&self, package_index: i32) -> Option<&ObjectExport> {
+        if package_index > 0 {
+            self.exports.get((package_index - 1) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Class name of an export (resolves the class import or export)
+    pub fn export_class_name(&self, export: &ObjectExport) -> String {
+        if let Some(import) = self.import(export.class_index) {
+            import.object_name.clone()
+        } else if let Some(class_export) = self.export(export.class_index) {
+            class_export.object_name.clone()
+        } else {
+            "Class".to_string()
+        }
+    }
+
+    /// The main asset export: the one flagged as an asset, else the first top-level export
+    pub fn primary_export(&self) -> Option<&ObjectExport> {
+        self.exports
+            .iter()
+            .find(|e| e.is_asset)
+            .or_else(|| self.exports.iter().find(|e| e.outer_index == 0))
+    }
+
+    /// Builds the full object path of an import ("/Game/Foo/Bar.Bar", "/Script/Engine.Texture2D")
+    pub fn import_object_path(&self, package_index: i32) -> Option<String> {
+        let mut segments = Vec::new();
+        let mut current = package_index;
+        // Outer chains are shallow; the bound protects against malformed loops
+        for _ in 0..32 {
+            let import = self.import(current)?;
+            segments.push(import.object_name.as_str());
+            if import.outer_index == 0 {
+                break;
+            }
+            current = import.outer_index;
+        }
+        segments.reverse();
+
+        let mut path = segments.first()?.to_string();
+        for (depth, segment) in segments.iter().enumerate().skip(1) {
+            path.push(if depth == 1 { '.' } else { ':' });
+            path.push_str(segment);
+        }
+        Some(path)
+    }
+
+    /// Display path of any object reference: full path for imports, name for exports
+    pub fn object_path(&self, package_index: i32) -> Option<String> {
+        if package_index < 0 {
+            self.import_object_path(package_index)
+        } else {
+            self.export(package_index).map(|e| e.object_name.clone())
+        }
+    }
+
+    /// Returns true when the package stores doubles for vectors (UE5 large world coordinates)
+    pub fn uses_large_world_coordinates(&self) -> bool {
+        self.summary.file_version_ue5 >= VER_UE5_LARGE_WORLD_COORDINATES
+    }
+
+    /// Package names referenced by the import table (imports without an outer)
+    pub fn imported_packages(&self) -> Vec<String> {
+        let mut packages: Vec<String> = self
+            .imports
+            .iter()
+            .filter(|i| i.outer_index == 0 && i.class_name == "Package")
+            .map(|i| i.object_name.clone())
+            .collect();
+        packages.sort();
+        packages.dedup();
+        packages
+    }
+
+    /// Packages listed in the summary's soft package reference table
+    pub fn soft_package_references(&self, data: &[u8]) -> Result<Vec<String>> {
+        let count = self.summary.soft_package_references_count;
+        if count <= 0 || self.summary.soft_package_references_offset <= 0 {
+            return Ok(Vec::new());
+        }
+        let mut reader = ArchiveReader::new(data);
+        reader.seek(self.summary.soft_package_references_offset as usize)?;
+        (0..count).map(|_| self.read_name(&mut reader)).collect()
+    }
+
+    /// Returns the serialized bytes of an export
+    pub fn export_data<'a>(&self, data: &'a [u8], export: &ObjectExport) -> Result<&'a [u8]> {
+        let start = usize::try_from(export.serial_offset).context("Negative export offset")?;
+        let size = usize::try_from(export.serial_size).context("Negative export size")?;
+        data.get(start..start + size).with_context(|| {
+            format!(
+                "Export {} data ({} bytes at {}) lies outside the package ({} bytes) - is the .uexp missing?",
+                export.object_name,
+                size,
+                start,
+                data.len()
+            )
+        })
+    }
+
+    /// Reads the thumbnail table; empty when the package has none (cooking usually strips it)
+    pub fn thumbnails(&self, data: &[u8]) -> Result<Vec<EmbeddedThumbnail>> {
+        let Ok(offset) = usize::try_from(self.summary.thumbnail_table_offset) else {
+            return Ok(Vec::new());
+        };
+        if offset == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut reader = ArchiveReader::new(data);
+        reader.seek(offset)?;
+        let count = reader.read_count(12)?;
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            let object_class = reader.read_fstring()?;
+            let object_path = reader.read_fstring()?;
+            let file_offset = reader.read_i32()?;
+            entries.push((object_class, object_path, file_offset));
+        }
+
+        let mut thumbnails = Vec::new();
+        for (object_class, object_path, file_offset) in entries {
+            reader.seek(usize::try_from(file_offset).context("Negative thumbnail offset")?)?;
+            let width = reader.read_i32()?;
+            // UE5 flags JPEG-compressed thumbnails with a negative height
+            let height = reader.read_i32()?.unsigned_abs();
+            let len = reader.read_count(1)?;
+            let image = reader.read_bytes(len)?;
+
+            let mime = match image.get(..4) {
+                Some([0x89, b'P', b'N', b'G']) => "image/png",
+                Some([0xFF, 0xD8, 0xFF, _]) => "image/jpeg",
+                _ => continue,
+            };
+            if width <= 0 || height == 0 {
+                continue;
+            }
+            thumbnails.push(EmbeddedThumbnail {
+                object_class,
+                object_path,
+                width: width as u32,
+                height,
+                mime,
+                data: image.to_vec(),
+            });
+        }
+        Ok(thumbnails)
+    }
+
+    fn read_soft_object_path_list(&self, reader: &mut ArchiveReader) -> Result<Vec<String>> {
+        let summary = &self.summary;
+        if summary.soft_object_paths_count <= 0 {
+            return Ok(Vec::new());
+        }
+        reader.seek(summary.soft_object_paths_offset as usize)?;
+
+        // An FName or FString at the least
+        let count = reader.check_count(summary.soft_object_paths_count, 4)?;
+        let mut paths = Vec::with_capacity(count);
+        for _ in 0..count {
+            paths.push(self.read_soft_object_path_inline(reader)?);
+        }
+        Ok(paths)
+    }
+
+    /// Reads an FSoftObjectPath as stored in place ("/Game/Foo/Bar.Bar:SubObject")
+    fn read_soft_object_path_inline(&self, reader: &mut ArchiveReader) -> Result<String> {
+        let asset_path = if self.summary.file_version_ue5 >= VER_UE5_FSOFTOBJECTPATH_REMOVE_ASSET_PATH_FNAMES {
+            let package_name = self.read_name(reader)?;
+            let asset_name = self.read_name(reader)?;
+            if asset_name == "None" {
+                package_name
+            } else {
+                format!("{}.{}", package_name, asset_name)
+            }
+        } else {
+            self.read_name(reader)?
+        };
+        let sub_path = reader.read_fstring()?;
+
+        Ok(match (asset_path.as_str(), sub_path.is_empty()) {
+            ("None", _) => String::new(),
+            (_, true) => asset_path,
+            (_, false) => format!("{}:{}", asset_path, sub_path),
+        })
+    }
+
+    /// Reads an FSoftObjectPath from export data, resolving UE5.1+ path list indices
+    pub fn read_soft_object_path(&self, reader: &mut ArchiveReader) -> Result<String> {
+        if self.soft_object_paths.is_empty() {
+            self.read_soft_object_path_inline(reader)
+        } else {
+            let index = reader.read_i32()?;
+            usize::try_from(index)
+                .ok()
+                .and_then(|i| self.soft_object_paths.get(i))
+                .cloned()
+                .with_context(|| format!("Soft object path index {} out of range", index))
+        }
+    }
+
+    fn read_import_map(&self, reader: &mut ArchiveReader) -> Result<Vec<ObjectImport>> {
+        let summary = &self.summary;
+        reader.seek(summary.import_offset as usize)?;
+
+        // Three FNames and the outer index at the least
+        let count = reader.check_count(summary.import_count, 28)?;
+        let mut imports = Vec::with_capacity(count);
+        for _ in 0..count {
+            let class_package = self.read_name(reader)?;
+            let class_name = self.read_name(reader)?;
+            let outer_index = reader.read_i32()?;
+            let object_name = self.read_name(reader)?;
+            let package_name = if summary.file_version_ue4 >= VER_UE4_NON_OUTER_PACKAGE_IMPORT
+                && !summary.is_filter_editor_only()
+            {
+                Some(self.read_name(reader)?)
+            } else {
+                None
+            };
+            let import_optional = summary.file_version_ue5 >= VER_UE5_OPTIONAL_RESOURCES && reader.read_bool()?;
+
+            imports.push(ObjectImport {
+                class_package,
+                class_name,
+                outer_index,
+                object_name,
+                package_name,
+                import_optional,
+            });
+        }
+
+        Ok(imports)
+    }
+
+    fn read_export_map(&self, reader: &mut ArchiveReader) -> Result<Vec<ObjectExport>> {
+        let summary = &self.summary;
+        let ue4 = summary.file_version_ue4;
+        let ue5 = summary.file_version_ue5;
+        reader.seek(summary.export_offset as usize)?;
+
+        // Indices, name, flags, 32-bit serial size and offset and the bools at the least
+        let count = reader.check_count(summary.export_count, 48)?;
+        let mut exports = Vec::with_capacity(count);
+        for _ in 0..count {
+            let class_index = reader.read_i32()?;
+            let super_index = reader.read_i32()?;
+            let template_index = if ue4 >= VER_UE4_TEMPLATE_INDEX_IN_COOKED_EXPORTS {
+                reader.read_i32()?
+            } else {
+                0
+            };
+            let outer_index = reader.read_i32()?;
+            let object_name = self.read_name(reader)?;
+            let object_flags = reader.read_u32()?;
+
+            let (serial_size, serial_offset) = if ue4 >= VER_UE4_64BIT_EXPORTMAP_SERIALSIZES {
+                (reader.read_i64()?, reader.read_i64()?)
+            } else {
+                (reader.read_i32()? as i64, reader.read_i32()? as i64)
+            };
+
+            let forced_export = reader.read_bool()?;
+            let not_for_client = reader.read_bool()?;
+            let not_for_server = reader.read_bool()?;
+
+            if ue5 < VER_UE5_REMOVE_OBJECT_EXPORT_PACKAGE_GUID {
+                reader.read_guid()?;
+            }
+            if ue5 >= VER_UE5_TRACK_OBJECT_EXPORT_IS_INHERITED {
+                reader.read_bool()?; // bIsInheritedInstance
+            }
+            reader.read_u32()?; // PackageFlags
+            if ue4 >= VER_UE4_LOAD_FOR_EDITOR_GAME {
+                reader.read_bool()?; // bNotAlwaysLoadedForEditorGame
+            }
+            let is_asset = ue4 >= VER_UE4_COOKED_ASSETS_IN_EDITOR_SUPPORT && reader.read_bool()?;
+            if ue5 >= VER_UE5_OPTIONAL_RESOURCES {
+                reader.read_bool()?; // bGeneratePublicHash
+            }
+            if ue4 >= VER_UE4_PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS {
+                reader.skip(5 * 4)?; // FirstExportDependency + four dependency counts
+            }
+            if ue5 >= VER_UE5_SCRIPT_SERIALIZATION_OFFSET {
+                reader.skip(2 * 8)?; // ScriptSerializationStart/EndOffset
+            }
+
+            exports.push(ObjectExport {
+                class_index,
+                super_index,
+                template_index,
+                outer_index,
+                object_name,
+                object_flags,
+                serial_size,
+                serial_offset,
+                forced_export,
+                not_for_client,
+                not_for_server,
+                is_asset,
+            });
+        }
+
+        Ok(exports)
+    }
+}
+
+/// Reads and validates the package file summary
+pub fn read_summary(reader: &mut ArchiveReader) -> Result<PackageSummary> {
+    let tag = reader.read_u32()?;
+    if tag != PACKAGE_FILE_TAG {
+        bail!("Not a classic Unreal package (tag 0x{:08X})", tag);
+    }
+
+    let mut summary = PackageSummary {
+        legacy_file_version: reader.read_i32()?,
+        ..Default::default()
+    };
+    let legacy = summary.legacy_file_version;
+    if !(-9..=-1).contains(&legacy) {
+        bail!("Unsupported legacy file version {}", legacy);
+    }
+
+    if legacy != -4 {
+        reader.read_i32()?; // LegacyUE3Version
+    }
+    summary.file_version_ue4 = reader.read_i32()?;
+    if legacy <= -8 {
+        summary.file_version_ue5 = reader.read_i32()?;
+    }
+    summary.file_version_licensee = reader.read_i32()?;
+
+    if summary.file_version_ue4 == 0 && summary.file_version_ue5 == 0 {
+        bail!("Unversioned package header; engine version cannot be determined");
+    }
+
+    if legacy <= -2 {
+        let count = reader.read_count(20)?;
+        for _ in 0..count {
+            if legacy == -2 {
+                let key = reader.read_i32()?;
+                let version = reader.read_i32()?;
+                let mut guid = [0u8; 16];
+                guid[..4].copy_from_slice(&key.to_le_bytes());
+                summary.custom_versions.push(CustomVersion { guid, version });
+            } else {
+                let guid = reader.read_guid()?;
+                let version = reader.read_i32()?;
+                if (-5..=-3).contains(&legacy) {
+                    reader.read_fstring()?; // FriendlyName
+                }
+                summary.custom_versions.push(CustomVersion { guid, version });
+            }
+        }
+    }
+
+    let ue4 = summary.file_version_ue4;
+    let ue5 = summary.file_version_ue5;
+
+    if ue5 >= VER_UE5_PACKAGE_SAVED_HASH {
+        reader.skip(20)?; // SavedHash
+    }
+    summary.total_header_size = reader.read_i32()?;
+    summary.folder_name = reader.read_fstring()?;
+    summary.package_flags = reader.read_u32()?;
+    summary.name_count = reader.read_i32()?;
+    summary.name_offset = reader.read_i32()?;
+
+    if ue5 >= VER_UE5_ADD_SOFTOBJECTPATH_LIST {
+        summary.soft_object_paths_count = reader.read_i32()?;
+        summary.soft_object_paths_offset = reader.read_i32()?;
+    }
+    if ue4 >= VER_UE4_ADDED_PACKAGE_SUMMARY_LOCALIZATION_ID && !summary.is_filter_editor_only() {
+        reader.read_fstring()?; // LocalizationId
+    }
+    if ue4 >= VER_UE4_SERIALIZE_TEXT_IN_PACKAGES {
+        summary.gatherable_text_data_count = reader.read_i32()?;
+        summary.gatherable_text_data_offset = reader.read_i32()?;
+    }
+
+    summary.export_count = reader.read_i32()?;
+    summary.export_offset = reader.read_i32()?;
+    summary.import_count = reader.read_i32()?;
+    summary.import_offset = reader.read_i32()?;
+    if ue5 >= VER_UE5_VERSE_CELLS {
+        reader.skip(4 * 4)?; // Cell export/import counts and offsets
+    }
+    if ue5 >= VER_UE5_METADATA_SERIALIZATION_OFFSET {
+        reader.read_i32()?; // MetaDataOffset
+    }
+    summary.depends_offset = reader.read_i32()?;
+
+    if ue4 >= VER_UE4_ADD_STRING_ASSET_REFERENCES_MAP {
+        summary.soft_package_references_count = reader.read_i32()?;
+        summary.soft_package_references_offset = reader.read_i32()?;
+    }
+    if ue4 >= VER_UE4_ADDED_SEARCHABLE_NAMES {
+        summary.searchable_names_offset = reader.read_i32()?;
+    }
+    summary.thumbnail_table_offset = reader.read_i32()?;
+
+    if ue5 < VER_UE5_PACKAGE_SAVED_HASH {
+        reader.read_guid()?; // Guid
+    }
+    if ue4 >= VER_UE4_ADDED_PACKAGE_OWNER && !summary.is_filter_editor_only() {
+        reader.read_guid()?; // PersistentGuid
+        if ue4 < VER_UE4_NON_OUTER_PACKAGE_IMPORT {
+            reader.read_guid()?; // OwnerPersistentGuid
+        }
+    }
+
+    let generations = reader.read_count(8)?;
+    reader.skip(generations * 8)?;
+
+    if ue4 >= VER_UE4_ENGINE_VERSION_OBJECT {
+        summary.saved_by_engine_version = read_engine_version(reader)?;
+    } else {
+        reader.read_i32()?; // EngineChangelist
+    }
+    if ue4 >= VER_UE4_PACKAGE_SUMMARY_HAS_COMPATIBLE_ENGINE_VERSION {
+        summary.compatible_engine_version = read_engine_version(reader)?;
+    }
+
+    reader.read_u32()?; // CompressionFlags
+    if reader.read_i32()? != 0 {
+        bail!("Package-level compressed chunks are not supported");
+    }
+    reader.read_u32()?; // PackageSource
+
+    let additional_packages = reader.read_count(4)?;
+    for _ in 0..additional_packages {
+        reader.read_fstring()?;
+    }
+    if legacy > -7 {
+        reader.read_i32()?; // NumTextureAllocations
+    }
+
+    summary.asset_registry_data_offset = reader.read_i32()?;
+    summary.bulk_data_start_offset = reader.read_i64()?;
+
+    if ue4 >= VER_UE4_WORLD_LEVEL_INFO {
+        summary.world_tile_info_data_offset = reader.read_i32()?;
+    }
+    if ue4 >= VER_UE4_ADDED_CHUNKID_TO_ASSETDATA_AND_UPACKAGE {
+        let count = reader.read_count(4)?;
+        for _ in 0..count {
+            summary.chunk_ids.push(reader.read_i32()?);
+        }
+    }
+    if ue4 >= VER_UE4_PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS {
+        summary.preload_dependency_count = reader.read_i32()?;
+        summary.preload_dependency_offset = reader.read_i32()?;
+    }
+    if ue5 >= VER_UE5_NAMES_REFERENCED_FROM_EXPORT_DATA {
+        reader.read_i32()?; // NamesReferencedFromExportDataCount
+    }
+    if ue5 >= VER_UE5_PAYLOAD_TOC {
+        reader.read_i64()?; // PayloadTocOffset
+    }
+    if ue5 >= VER_UE5_DATA_RESOURCES {
+        reader.read_i32()?; // DataResourceOffset
+    }
+
+    Ok(summary)
+}
+
+fn read_engine_version(reader: &mut ArchiveReader) -> Result<EngineVersion> {
+    Ok(EngineVersion {
+        major: reader.read_u16()?,
+        minor: reader.read_u16()?,
+        patch: reader.read_u16()?,
+        changelist: reader.read_u32()?,
+        branch: reader.read_fstring()?,
+    })
+}
+
+fn read_name_map(reader: &mut ArchiveReader, summary: &PackageSummary) -> Result<Vec<String>> {
+    reader.seek(summary.name_offset as usize)?;
+
+    // An FString length at the least
+    let count = reader.check_count(summary.name_count, 4)?;
+    let mut names = Vec::with_capacity(count);
+    for _ in 0..count {
+        names.push(reader.read_fstring()?);
+        if summary.file_version_ue4 >= VER_UE4_NAME_HASHES_SERIALIZED {
+            reader.read_u32()?; // Non-case-preserving + case-preserving hashes
+        }
+    }
+    Ok(names)
+}
+
+/// Header of a tagged property (FPropertyTag)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyTag {
+    pub name: String,
+    pub property_type: String,
+    pub size: usize,
+    pub array_index: i32,
+    /// Struct name for StructProperty, enum name for Byte/EnumProperty
+    pub struct_name: Option<String>,
+    /// Inner type for Array/Set/MapProperty (key type for maps)
+    pub inner_type: Option<String>,
+    /// Value type for MapProperty
+    pub value_type: Option<String>,
+    pub bool_value: bool,
+    /// Offset of the serialized value within the buffer being read
+    pub value_offset: usize,
+}
+
+/// Reads the tagged property headers of an object, skipping over each value
+///
+/// Stops at the terminating "None" tag. Fails with `MappingsRequired` for unversioned
+/// properties, which have no tags.
+pub fn read_property_tags(package: &Package, reader: &mut ArchiveReader) -> Result<Vec<PropertyTag>> {
+    if package.summary.has_unversioned_properties() {
+        return Err(MappingsRequired.into());
+    }
+
+    let mut tags = Vec::new();
+    loop {
+        let Some(tag) = read_property_tag(package, reader)? else {
+            break;
+        };
+        reader.skip(tag.size)?;
+        tags.push(tag);
+    }
+    Ok(tags)
+}
+
+/// Reads a single property tag header, or None at the "None" terminator
+pub fn read_property_tag(package: &Package, reader: &mut ArchiveReader) -> Result<Option<PropertyTag>> {
+    let ue4 = package.summary.file_version_ue4;
+    let ue5 = package.summary.file_version_ue5;
+
+    let name = package.read_name(reader)?;
+    if name == "None" {
+        return Ok(None);
+    }
+
+    let mut tag = PropertyTag {
+        name,
+        property_type: String::new(),
+        size: 0,
+        array_index: 0,
+        struct_name: None,
+        inner_type: None,
+        value_type: None,
+        bool_value: false,
+        value_offset: 0,
+    };
+
+    if ue5 >= VER_UE5_PROPERTY_TAG_COMPLETE_TYPE_NAME {
+        let type_name = read_type_name(package, reader)?;
+        tag.property_type = type_name.name.clone();
+        match tag.property_type.as_str() {
+            "StructProperty" | "ByteProperty" | "EnumProperty" => {
+                tag.struct_name = type_name.params.first().map(|p| p.name.clone());
+            }
+            "ArrayProperty" | "SetProperty" | "OptionalProperty" => {
+                tag.inner_type = type_name.params.first().map(|p| p.name.clone());
+            }
+            "MapProperty" => {
+                tag.inner_type = type_name.params.first().map(|p| p.name.clone());
+                tag.value_type = type_name.params.get(1).map(|p| p.name.clone());
+            }
+            _ => {}
+        }
+        tag.size = read_tag_size(reader)?;
+
+        let flags = reader.read_u8()?;
+        if flags & 0x01 != 0 {
+            tag.array_index = reader.read_i32()?;
+        }
+        if flags & 0x02 != 0 {
+            reader.read_guid()?;
+        }
+        if flags & 0x04 != 0 {
+            skip_property_extensions(reader)?;
+        }
+        tag.bool_value = flags & 0x10 != 0;
+    } else {
+        tag.property_type = package.read_name(reader)?;
+        tag.size = read_tag_size(reader)?;
+        tag.array_index = reader.read_i32()?;
+
+        match tag.property_type.as_str() {
+            "StructProperty" => {
+                tag.struct_name = Some(package.read_name(reader)?);
+                if ue4 >= VER_UE4_STRUCT_GUID_IN_PROPERTY_TAG {
+                    reader.read_guid()?;
+                }
+            }
+            "BoolProperty" => tag.bool_value = reader.read_u8()? != 0,
+            "ByteProperty" | "EnumProperty" => tag.struct_name = Some(package.read_name(reader)?),
+            "ArrayProperty" | "SetProperty" | "OptionalProperty" => {
+                tag.inner_type = Some(package.read_name(reader)?)
+            }
+            "MapProperty" => {
+                tag.inner_type = Some(package.read_name(reader)?);
+                tag.value_type = Some(package.read_name(reader)?);
+            }
+            _ => {}
+        }
+
+        if ue4 >= VER_UE4_PROPERTY_GUID_IN_PROPERTY_TAG && reader.read_u8()? != 0 {
+            reader.read_guid()?;
+        }
+        if ue5 >= VER_UE5_PROPERTY_TAG_EXTENSION_AND_OVERRIDABLE_SERIALIZATION {
+            skip_property_extensions(reader)?;
+        }
+    }
+
+    tag.value_offset = reader.position();
+    Ok(Some(tag))
+}
+
+impl PropertyTag {
+    /// Raw serialized value bytes of this property within `data`
+    pub fn value<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        data.get(self.value_offset..self.value_offset + self.size).unwrap_or(&[])
+    }
+
+    /// Package index stored by an ObjectProperty
+    pub fn object_index(&self, data: &[u8]) -> Option<i32> {
+        if self.property_type != "ObjectProperty" {
+            return None;
+        }
+        ArchiveReader::new(self.value(data)).read_i32().ok()
+    }
+
+    /// Element count of an Array/Set property
+    pub fn array_count(&self, data: &[u8]) -> Option<usize> {
+        if !matches!(self.property_type.as_str(), "ArrayProperty" | "SetProperty") {
+            return None;
+        }
+        let mut reader = ArchiveReader::new(self.value(data));
+        if self.property_type == "SetProperty" {
+            reader.read_i32().ok()?; // Elements to remove
+        }
+        reader.read_i32().ok().and_then(|c| usize::try_from(c).ok())
+    }
+
+    /// Package indices stored by an ArrayProperty of ObjectProperty
+    pub fn object_array(&self, data: &[u8]) -> Vec<i32> {
+        if self.inner_type.as_deref() != Some("ObjectProperty") {
+            return Vec::new();
+        }
+        let mut reader = ArchiveReader::new(self.value(data));
+        let Ok(count) = reader.read_count(4) else {
+            return Vec::new();
+        };
+        (0..count).filter_map(|_| reader.read_i32().ok()).collect()
+    }
+}
+
+/// Finds a property by name in a list of tags
+pub fn find_property<'a>(tags: &'a [PropertyTag], name: &str) -> Option<&'a PropertyTag> {
+    tags.iter().find(|t| t.name == name)
+}
+
+fn read_tag_size(reader: &mut ArchiveReader) -> Result<usize> {
+    let size = reader.read_i32()?;
+    if size < 0 || size as usize > reader.remaining() {
+        bail!("Invalid property size {} at offset {}", size, reader.position() - 4);
+    }
+    Ok(size as usize)
+}
+
+fn skip_property_extensions(reader: &mut ArchiveReader) -> Result<()> {
+    let extensions = reader.read_u8()?;
+    if extensions & 0x02 != 0 {
+        reader.read_u8()?; // OverridableOperation
+        reader.read_bool()?; // bExperimentalOverridableLogic
+    }
+    Ok(())
+}
+
+/// Node of a UE 5.4+ complete property type name (FPropertyTypeName)
+#[derive(Debug, Clone)]
+pub struct TypeName {
+    pub name: String,
+    pub params: Vec<TypeName>,
+}
+
+fn read_type_name(package: &Package, reader: &mut ArchiveReader) -> Result<TypeName> {
+    let name = package.read_name(reader)?;
+    let param_count = reader.read_count(12)?;
+    let mut params = Vec::with_capacity(param_count);
+    for _ in 0..param_count {
+        params.push(read_type_name(package, reader)?);
+    }
+    Ok(TypeName { name, params })
+}
+
+/// Skips an object's property block when the caller only needs the native data after it
+///
+/// Unversioned packages can only be skipped when their header declares no values,
+/// since value sizes are unknown without mappings.
+pub fn skip_properties(package: &Package, reader: &mut ArchiveReader) -> Result<()> {
+    if !package.summary.has_unversioned_properties() {
+        read_property_tags(package, reader)?;
+        return Ok(());
+    }
+
+    // FUnversionedHeader fragments: skip count (7 bits), has-zeroes, is-last, value count (7 bits)
+    loop {
+        let fragment = reader.read_u16()?;
+        if fragment >> 9 != 0 {
+            return Err(MappingsRequired.into());
+        }
+        if fragment & 0x100 != 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Skips the UObject serialized tail that follows tagged properties (object GUID)
+pub fn skip_object_guid(reader: &mut ArchiveReader) -> Result<()> {
+    if reader.read_bool()? {
+        reader.read_guid()?;
+    }
+    Ok(())
+}
+
+/// Where a value is being read from; bools and bytes are encoded differently inside containers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueContext {
+    Property,
+    Container,
+}
+
+/// Reads all properties of a `struct_name` object (its class, for an export) and decodes
+/// their values to JSON
+///
+/// Tagged values that can't be decoded (unknown native structs, exotic containers) are
+/// skipped using the tag size and reported as `{"unreadable": "<type>"}` so one
+/// odd property never hides the rest. Unversioned properties are read with the .usmap
+/// in settings, and fail with `MappingsRequired` when there is none.
+pub fn read_properties(
+    package: &Package,
+    reader: &mut ArchiveReader,
+    struct_name: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    if !package.summary.has_unversioned_properties() {
+        return read_tagged_properties(package, reader);
+    }
+    let mappings = crate::usmap::current()?.ok_or(MappingsRequired)?;
+    read_unversioned_properties(package, reader, &mappings, struct_name)
+}
+
+fn read_tagged_properties(package: &Package, reader: &mut ArchiveReader) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let mut properties = serde_json::Map::new();
+    while let Some(tag) = read_property_tag(package, reader)? {
+        let end = tag.value_offset + tag.size;
+        let value = match read_tagged_value(package, reader, &tag) {
+            Ok(value) if reader.position() == end => value,
+            _ => serde_json::json!({ "unreadable": tag.property_type }),
+        };
+        reader.seek(end)?;
+
+        let key = if tag.array_index > 0 {
+            format!("{}[{}]", tag.name, tag.array_index)
+        } else {
+            tag.name.clone()
+        };
+        properties.insert(key, value);
+    }
+    Ok(properties)
+}
+
+fn read_tagged_value(package: &Package, reader: &mut ArchiveReader, tag: &PropertyTag) -> Result<serde_json::Value> {
+    use serde_json::Value;
+
+    match tag.property_type.as_str() {
+        "BoolProperty" => Ok(Value::Bool(tag.bool_value)),
+        "ByteProperty" if tag.size == 1 => Ok(Value::from(reader.read_u8()?)),
+        "StructProperty" => read_struct_value(package, reader, tag.struct_name.as_deref().unwrap_or("None")),
+        "ArrayProperty" => {
+            let inner = tag.inner_type.as_deref().unwrap_or("None");
+            let count = reader.read_count(1)?;
+            let mut struct_name = tag.struct_name.clone();
+
+            // UE4-style arrays of structs carry an inner tag naming the struct
+            if inner == "StructProperty" && package.summary.file_version_ue5 < VER_UE5_PROPERTY_TAG_COMPLETE_TYPE_NAME {
+                let inner_tag = read_property_tag(package, reader)?.context("Missing inner struct tag")?;
+                struct_name = inner_tag.struct_name;
+            }
+
+            // Byte arrays hold raw bytes unless each element is an enum FName
+            if inner == "ByteProperty" && tag.size == 4 + count {
+                return Ok(Value::from(reader.read_bytes(count)?.to_vec()));
+            }
+
+            let mut items = Vec::with_capacity(count);
+            for _ in 0..count {
+                items.push(match (inner, struct_name.as_deref()) {
+                    ("StructProperty", Some(name)) => read_struct_value(package, reader, name)?,
+                    _ => read_value(package, reader, inner, ValueContext::Container)?,
+                });
+            }
+            Ok(Value::Array(items))
+        }
+        "SetProperty" => {
+            let inner = tag.inner_type.as_deref().unwrap_or("None");
+            let removed = reader.read_count(1)?;
+            for _ in 0..removed {
+                read_value(package, reader, inner, ValueContext::Container)?;
+            }
+            let count = reader.read_count(1)?;
+            let mut items = Vec::with_capacity(count);
+            for _ in 0..count {
+                items.push(read_value(package, reader, inner, ValueContext::Container)?);
+            }
+            Ok(Value::Array(items))
+        }
+        "MapProperty" => {
+            let key_type = tag.inner_type.as_deref().unwrap_or("None");
+            let value_type = tag.value_type.as_deref().unwrap_or("None");
+            let removed = reader.read_count(1)?;
+            for _ in 0..removed {
+                read_value(package, reader, key_type, ValueContext::Container)?;
+            }
+            let count = reader.read_count(1)?;
+            let mut entries = Vec::with_capacity(count);
+            for _ in 0..count {
+                let key = read_value(package, reader, key_type, ValueContext::Container)?;
+                let value = read_value(package, reader, value_type, ValueContext::Container)?;
+                entries.push(serde_json::json!({ "key": key, "value": value }));
+            }
+            Ok(Value::Array(entries))
+        }
+        other => read_value(package, reader, other, ValueContext::Property),
+    }
+}
+
+fn read_value(package: &Package, reader: &mut ArchiveReader, property_type: &str, context: ValueContext) -> Result<serde_json::Value> {
+    use serde_json::Value;
+
+    Ok(match property_type {
+        "BoolProperty" if context == ValueContext::Container => Value::Bool(reader.read_u8()? != 0),
+        "Int8Property" => Value::from(reader.read_u8()? as i8),
+        "Int16Property" => Value::from(reader.read_u16()? as i16),
+        "IntProperty" => Value::from(reader.read_i32()?),
+        "Int64Property" => Value::from(reader.read_i64()?),
+        "UInt16Property" => Value::from(reader.read_u16()?),
+        "UInt32Property" => Value::from(reader.read_u32()?),
+        "UInt64Property" => Value::from(reader.read_u64()?),
+        "FloatProperty" => Value::from(reader.read_f32()?),
+        "DoubleProperty" => Value::from(reader.read_f64()?),
+        "StrProperty" => Value::String(reader.read_fstring()?),
+        "NameProperty" | "EnumProperty" | "ByteProperty" => Value::String(package.read_name(reader)?),
+        "TextProperty" => read_text_value(package, reader)?,
+        "ObjectProperty" | "ClassProperty" | "WeakObjectProperty" | "InterfaceProperty" => {
+            let index = reader.read_i32()?;
+            package.object_path(index).map(Value::String).unwrap_or(Value::Null)
+        }
+        "SoftObjectProperty" | "SoftClassProperty" => Value::String(package.read_soft_object_path(reader)?),
+        "StructProperty" => {
+            // Structs inside maps/sets have no recorded type; only tagged layouts are readable
+            Value::Object(read_tagged_properties(package, reader)?)
+        }
+        other => bail!("Unsupported property type {}", other),
+    })
+}
+
+fn read_text_value(package: &Package, reader: &mut ArchiveReader) -> Result<serde_json::Value> {
+    reader.read_u32()?; // Flags
+    let history_type = reader.read_u8()? as i8;
+    Ok(match history_type {
+        -1 => {
+            let has_invariant = reader.read_bool()?;
+            serde_json::Value::String(if has_invariant { reader.read_fstring()? } else { String::new() })
+        }
+        0 => {
+            let namespace = reader.read_fstring()?;
+            let key = reader.read_fstring()?;
+            let source = reader.read_fstring()?;
+            serde_json::json!({ "namespace": namespace, "key": key, "source": source })
+        }
+        11 => {
+            let table = package.read_name(reader)?;
+            let key = reader.read_fstring()?;
+            serde_json::json!({ "string_table": table, "key": key })
+        }
+        other => bail!("Unsupported FText history type {}", other),
+    })
+}
+
+/// Decodes native (binary) structs we know about, or falls back to nested tagged properties
+fn read_struct_value(package: &Package, reader: &mut ArchiveReader, struct_name: &str) -> Result<serde_json::Value> {
+    match read_native_struct(package, reader, struct_name)? {
+        Some(value) => Ok(value),
+        None => Ok(serde_json::Value::Object(read_tagged_properties(package, reader)?)),
+    }
+}
+
+/// Decodes a struct with a native (binary) layout, or None for structs serialized as properties
+fn read_native_struct(package: &Package, reader: &mut ArchiveReader, struct_name: &str) -> Result<Option<serde_json::Value>> {
+    let lwc = package.uses_large_world_coordinates();
+    let real = |reader: &mut ArchiveReader| -> Result<f64> {
+        if lwc {
+            reader.read_f64()
+        } else {
+            reader.read_f32().map(f64::from)
+        }
+    };
+
+    Ok(Some(match struct_name {
+        "Vector" | "Rotator" => serde_json::json!([real(reader)?, real(reader)?, real(reader)?]),
+        "Vector2D" => serde_json::json!([real(reader)?, real(reader)?]),
+        "Vector4" | "Quat" | "Plane" => serde_json::json!([real(reader)?, real(reader)?, real(reader)?, real(reader)?]),
+        "Vector3f" => serde_json::json!([reader.read_f32()?, reader.read_f32()?, reader.read_f32()?]),
+        "LinearColor" => serde_json::json!([reader.read_f32()?, reader.read_f32()?, reader.read_f32()?, reader.read_f32()?]),
+        "Color" => {
+            let [b, g, r, a] = [reader.read_u8()?, reader.read_u8()?, reader.read_u8()?, reader.read_u8()?];
+            serde_json::json!([r, g, b, a])
+        }
+        "IntPoint" => serde_json::json!([reader.read_i32()?, reader.read_i32()?]),
+        "IntVector" => serde_json::json!([reader.read_i32()?, reader.read_i32()?, reader.read_i32()?]),
+        "Guid" => serde_json::Value::String(format_guid(&reader.read_guid()?)),
+        "DateTime" | "Timespan" => serde_json::Value::from(reader.read_i64()?),
+        "FrameNumber" => serde_json::Value::from(reader.read_i32()?),
+        "FrameRate" => serde_json::json!({ "numerator": reader.read_i32()?, "denominator": reader.read_i32()? }),
+        "Box" => {
+            let min = [real(reader)?, real(reader)?, real(reader)?];
+            let max = [real(reader)?, real(reader)?, real(reader)?];
+            let valid = reader.read_u8()? != 0;
+            serde_json::json!({ "min": min, "max": max, "is_valid": valid })
+        }
+        "SoftObjectPath" | "SoftClassPath" | "StringAssetReference" | "StringClassReference" => {
+            serde_json::Value::String(package.read_soft_object_path(reader)?)
+        }
+        "GameplayTag" => serde_json::Value::String(package.read_name(reader)?),
+        "RichCurveKey" => {
+            let [interp_mode, tangent_mode, tangent_weight_mode] = [reader.read_u8()?, reader.read_u8()?, reader.read_u8()?];
+            serde_json::json!({
+                "interp_mode": interp_mode,
+                "tangent_mode": tangent_mode,
+                "tangent_weight_mode": tangent_weight_mode,
+                "time": reader.read_f32()?,
+                "value": reader.read_f32()?,
+                "arrive_tangent": reader.read_f32()?,
+                "arrive_tangent_weight": reader.read_f32()?,
+                "leave_tangent": reader.read_f32()?,
+                "leave_tangent_weight": reader.read_f32()?,
+            })
+        }
+        "SimpleCurveKey" => serde_json::json!({ "time": reader.read_f32()?, "value": reader.read_f32()? }),
+        _ => return Ok(None),
+    }))
+}
+
+/// Reads the unversioned properties of a `struct_name` object with the layout `mappings` gives it
+///
+/// The header lists which property slots are serialized, and which of those hold zero
+/// values (written as nothing): those get their type's default. Values carry no size, so
+/// one that can't be decoded fails the whole object.
+fn read_unversioned_properties(
+    package: &Package,
+    reader: &mut ArchiveReader,
+    mappings: &Mappings,
+    struct_name: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let layout = mappings
+        .layout(struct_name)
+        .with_context(|| format!("{} is not in the .usmap mappings", struct_name))?;
+
+    // FUnversionedHeader fragments: skip count (7 bits), has-zeroes, is-last, value count (7 bits)
+    let mut fragments = Vec::new();
+    loop {
+        let fragment = reader.read_u16()?;
+        fragments.push(fragment);
+        if fragment & 0x100 != 0 {
+            break;
+        }
+    }
+    let zero_count: usize = fragments.iter().filter(|&&f| f & 0x80 != 0).map(|&f| (f >> 9) as usize).sum();
+    let zeroes = read_zero_mask(reader, zero_count)?;
+
+    let mut properties = serde_json::Map::new();
+    let mut slot = 0usize;
+    let mut zero_index = 0usize;
+    for fragment in fragments {
+        slot += (fragment & 0x7F) as usize;
+        for _ in 0..fragment >> 9 {
+            let is_zero = fragment & 0x80 != 0 && {
+                zero_index += 1;
+                zeroes[zero_index - 1]
+            };
+            let (property, element) = layout
+                .get(slot)
+                .copied()
+                .flatten()
+                .with_context(|| format!("{} has no property slot {}", struct_name, slot))?;
+            let value = if is_zero {
+                zero_value(&property.property_type)
+            } else {
+                read_mapped_value(package, reader, mappings, &property.property_type)
+                    .with_context(|| format!("Failed to read {}.{}", struct_name, property.name))?
+            };
+
+            let key = if element > 0 {
+                format!("{}[{}]", property.name, element)
+            } else {
+                property.name.clone()
+            };
+            properties.insert(key, value);
+            slot += 1;
+        }
+    }
+    Ok(properties)
+}
+
+/// Reads the bit per value that says it's zero: a byte or a word when they fit, else dwords
+fn read_zero_mask(reader: &mut ArchiveReader, count: usize) -> Result<Vec<bool>> {
+    let words: Vec<u32> = match count {
+        0 => Vec::new(),
+        1..=8 => vec![reader.read_u8()? as u32],
+        9..=16 => vec![reader.read_u16()? as u32],
+        _ => (0..count.div_ceil(32)).map(|_| reader.read_u32()).collect::<Result<_>>()?,
+    };
+    Ok((0..count).map(|i| words[i / 32] & (1 << (i % 32)) != 0).collect())
+}
+
+/// Value of a property the unversioned header marks as zero
+fn zero_value(property_type: &PropertyType) -> serde_json::Value {
+    use serde_json::Value;
+
+    match property_type {
+        PropertyType::Bool => Value::Bool(false),
+        PropertyType::Float | PropertyType::Double => Value::from(0.0),
+        PropertyType::Name => Value::String("None".to_string()),
+        PropertyType::Str | PropertyType::Text | PropertyType::Utf8Str | PropertyType::AnsiStr => Value::String(String::new()),
+        PropertyType::Array(_) | PropertyType::Set(_) | PropertyType::Map(..) | PropertyType::MulticastDelegate => Value::Array(Vec::new()),
+        PropertyType::Struct(_) => Value::Object(serde_json::Map::new()),
+        PropertyType::Enum { inner, name } => zero_value(inner).as_u64().map_or(Value::Null, |value| Value::String(format!("{}::{}", name, value))),
+        PropertyType::Object
+        | PropertyType::Interface
+        | PropertyType::WeakObject
+        | PropertyType::LazyObject
+        | PropertyType::SoftObject
+        | PropertyType::Delegate
+        | PropertyType::FieldPath
+        | PropertyType::Optional(_)
+        | PropertyType::Unknown(_) => Value::Null,
+        _ => Value::from(0),
+    }
+}
+
+/// Reads a value whose type comes from the mappings rather than a property tag
+fn read_mapped_value(
+    package: &Package,
+    reader: &mut ArchiveReader,
+    mappings: &Mappings,
+    property_type: &PropertyType,
+) -> Result<serde_json::Value> {
+    use serde_json::Value;
+
+    let read = |reader: &mut ArchiveReader, inner: &PropertyType| read_mapped_value(package, reader, mappings, inner);
+    Ok(match property_type {
+        PropertyType::Bool => Value::Bool(reader.read_u8()? != 0),
+        PropertyType::Byte => Value::from(reader.read_u8()?),
+        PropertyType::Int8 => Value::from(reader.read_u8()? as i8),
+        PropertyType::Int16 => Value::from(reader.read_u16()? as i16),
+        PropertyType::Int => Value::from(reader.read_i32()?),
+        PropertyType::Int64 => Value::from(reader.read_i64()?),
+        PropertyType::UInt16 => Value::from(reader.read_u16()?),
+        PropertyType::UInt32 => Value::from(reader.read_u32()?),
+        PropertyType::UInt64 => Value::from(reader.read_u64()?),
+        PropertyType::Float => Value::from(reader.read_f32()?),
+        PropertyType::Double => Value::from(reader.read_f64()?),
+        PropertyType::Str | PropertyType::Utf8Str | PropertyType::AnsiStr => Value::String(reader.read_fstring()?),
+        PropertyType::Name => Value::String(package.read_name(reader)?),
+        PropertyType::Text => read_text_value(package, reader)?,
+        PropertyType::Object | PropertyType::Interface | PropertyType::WeakObject => {
+            let index = reader.read_i32()?;
+            package.object_path(index).map(Value::String).unwrap_or(Value::Null)
+        }
+        PropertyType::LazyObject => Value::String(format_guid(&reader.read_guid()?)),
+        PropertyType::SoftObject => Value::String(package.read_soft_object_path(reader)?),
+        PropertyType::Delegate => read_delegate(package, reader)?,
+        PropertyType::MulticastDelegate => {
+            let count = reader.read_count(8)?;
+            Value::Array((0..count).map(|_| read_delegate(package, reader)).collect::<Result<_>>()?)
+        }
+        PropertyType::FieldPath => {
+            let count = reader.read_count(8)?;
+            let path: Vec<String> = (0..count).map(|_| package.read_name(reader)).collect::<Result<_>>()?;
+            reader.read_i32()?; // Owner
+            Value::String(path.join("."))
+        }
+        PropertyType::Struct(name) => match read_native_struct(package, reader, name)? {
+            Some(value) => value,
+            None => Value::Object(read_unversioned_properties(package, reader, mappings, name)?),
+        },
+        PropertyType::Enum { inner, name } => {
+            let value = read(reader, inner)?;
+            let number = value.as_u64().or_else(|| value.as_i64().map(|v| v as u64));
+            match number.and_then(|number| mappings.enum_value(name, number)) {
+                Some(entry) if entry.contains("::") => Value::String(entry.to_string()),
+                Some(entry) => Value::String(format!("{}::{}", name, entry)),
+                None => value,
+            }
+        }
+        PropertyType::Array(inner) => {
+            let count = reader.read_count(1)?;
+            Value::Array((0..count).map(|_| read(reader, inner)).collect::<Result<_>>()?)
+        }
+        PropertyType::Set(inner) => {
+            let removed = reader.read_count(1)?;
+            for _ in 0..removed {
+                read(reader, inner)?;
+            }
+            let count = reader.read_count(1)?;
+            Value::Array((0..count).map(|_| read(reader, inner)).collect::<Result<_>>()?)
+        }
+        PropertyType::Map(key_type, value_type) => {
+            let removed = reader.read_count(1)?;
+            for _ in 0..removed {
+                read(reader, key_type)?;
+            }
+            let count = reader.read_count(1)?;
+            let mut entries = Vec::with_capacity(count);
+            for _ in 0..count {
+                let key = read(reader, key_type)?;
+                let value = read(reader, value_type)?;
+                entries.push(serde_json::json!({ "key": key, "value": value }));
+            }
+            Value::Array(entries)
+        }
+        PropertyType::Optional(inner) => {
+            if reader.read_bool()? {
+                read(reader, inner)?
+            } else {
+                Value::Null
+            }
+        }
+        PropertyType::Unknown(id) => bail!("Unsupported .usmap property type {}", id),
+    })
+}
+
+/// Reads a bound delegate: the object it's bound to and the function's name
+fn read_delegate(package: &Package, reader: &mut ArchiveReader) -> Result<serde_json::Value> {
+    let object = package.object_path(reader.read_i32()?);
+    let function = package.read_name(reader)?;
+    Ok(serde_json::json!({ "object": object, "function": function }))
+}
+
+/// Formats a GUID the way Unreal prints it (four uppercase hex dwords)
+pub fn format_guid(guid: &[u8; 16]) -> String {
+    guid.chunks_exact(4)
+        .map(|c| format!("{:08X}", u32::from_le_bytes([c[0], c[1], c[2], c[3]])))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts a test package's summary declares
+    #[derive(Default)]
+    struct Counts {
+        names: i32,
+        imports: i32,
+        exports: i32,
+        soft_object_paths: i32,
+    }
+
+    /// A UE5.1 package: its summary, then `names` ("None" first) where the summary says
+    /// the name map starts; the import, export and soft object path tables all start
+    /// after the names
+    fn package(counts: Counts, names: &[&str]) -> Vec<u8> {
+        let mut summary: Vec<u8> = Vec::new();
+        let i32s = |out: &mut Vec<u8>, values: &[i32]| values.iter().for_each(|value| out.extend_from_slice(&value.to_le_bytes()));
+        let engine_version = |out: &mut Vec<u8>| {
+            out.extend_from_slice(&[5, 0, 1, 0, 0, 0]);
+            i32s(out, &[0, 0]); // Changelist, empty branch
+        };
+        summary.extend_from_slice(&PACKAGE_FILE_TAG.to_le_bytes());
+        i32s(&mut summary, &[-8, 864, 522, VER_UE5_ADD_SOFTOBJECTPATH_LIST, 0]);
+        i32s(&mut summary, &[0]); // Custom versions
+        // Header size, empty folder name, package flags; the offsets are patched below
+        i32s(&mut summary, &[0, 0, 0, counts.names, 0, counts.soft_object_paths, 0]);
+        i32s(&mut summary, &[0]); // LocalizationId
+        i32s(&mut summary, &[0, 0, counts.exports, 0, counts.imports, 0]);
+        i32s(&mut summary, &[0, 0, 0, 0, 0]); // Depends, soft package references, searchable names, thumbnails
+        summary.extend_from_slice(&[0; 32]); // Guid, PersistentGuid
+        i32s(&mut summary, &[0]); // Generations
+        engine_version(&mut summary);
+        engine_version(&mut summary);
+        i32s(&mut summary, &[0, 0, 0, 0]); // Compression, chunks, source, additional packages
+        i32s(&mut summary, &[0, 0, 0, 0, 0, 0, 0, 0, 0]); // Registry, bulk data, world tile, chunk ids, preload, names, payload TOC
+
+        let tables = summary.len() as i32;
+        let mut names_map = Vec::new();
+        for name in names {
+            i32s(&mut names_map, &[name.len() as i32 + 1]);
+            names_map.extend_from_slice(name.as_bytes());
+            names_map.push(0);
+            i32s(&mut names_map, &[0]); // Hashes
+        }
+        let after_names = tables + names_map.len() as i32;
+        // Name map, soft object path list, export map and import map offsets
+        for (at, offset) in [(44, tables), (52, after_names), (72, after_names), (80, after_names)] {
+            summary[at..at + 4].copy_from_slice(&offset.to_le_bytes());
+        }
+        summary.extend(names_map);
+        summary
+    }
+
+    #[test]
+    fn empty_package_parses() {
+        let package = Package::parse(&package(Counts { names: 1, ..Default::default() }, &["None"])).unwrap();
+        assert_eq!(package.names, ["None"]);
+        assert!(package.imports.is_empty() && package.exports.is_empty() && package.soft_object_paths.is_empty());
+    }
+
+    #[test]
+    fn oversized_table_counts_fail_without_allocating() {
+        for bad in [i32::MAX, -1, 1_000] {
+            let cases = [
+                ("name map", Counts { names: bad, ..Default::default() }),
+                ("import map", Counts { names: 1, imports: bad, ..Default::default() }),
+                ("export map", Counts { names: 1, exports: bad, ..Default::default() }),
+                ("soft object path list", Counts { names: 1, soft_object_paths: bad.max(1), ..Default::default() }),
+            ];
+            for (table, counts) in cases {
+                let error = Package::parse(&package(counts, &["None"])).unwrap_err();
+                assert!(format!("{:#}", error).contains(&format!("Failed to read {}", table)), "{} {}: {:#}", table, bad, error);
+            }
+        }
+    }
+
+    #[test]
+    fn negative_soft_object_path_count_means_none() {
+        let counts = Counts { names: 1, soft_object_paths: -1, ..Default::default() };
+        assert!(Package::parse(&package(counts, &["None"])).unwrap().soft_object_paths.is_empty());
+    }
+
+    #[test]
+    fn check_count_bounds_by_remaining_data() {
+        let data = [0u8; 100];
+        let reader = ArchiveReader::new(&data);
+        assert_eq!(reader.check_count(25, 4).unwrap(), 25);
+        assert!(reader.check_count(26, 4).is_err());
+        assert!(reader.check_count(-1, 4).is_err());
+        assert!(reader.check_count(i32::MAX, usize::MAX).is_err());
+    }
+}
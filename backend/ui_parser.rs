@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use anyhow::{bail, Context, Result};
+
+use crate::uasset_parser::{self, ArchiveReader, Package};
+
+/// Naming facts read from an sfnt font's `name` table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontInfo {
+    pub family: Option<String>,
+    pub style: Option<String>,
+    pub full_name: Option<String>,
+    /// "TrueType", "OpenType" or "TrueType Collection"
+    pub format: String,
+    pub mime: String,
+}
+
+/// The texture (or material) a Slate brush draws
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlateBrushInfo {
+    pub resource_object: Option<String>,
+    pub image_size: Option<Value>,
+    pub draw_as: Option<String>,
+    pub tint: Option<Value>,
+}
+
+/// Returns true if the bytes start with a TrueType/OpenType/collection signature
+pub fn is_font_payload(data: &[u8]) -> bool {
+    matches!(data.get(..4), Some([0x00, 0x01, 0x00, 0x00]) | Some(b"OTTO") | Some(b"true") | Some(b"ttcf"))
+}
+
+/// Finds a TTF/OTF payload embedded in a FontFace or Font package
+///
+/// Inline font data is serialized as a byte array, so a font signature preceded
+/// by a length that fits the remaining bytes marks the payload.
+pub fn find_embedded_font(data: &[u8]) -> Option<&[u8]> {
+    (4..data.len().saturating_sub(12)).find_map(|start| {
+        let payload = &data[start..];
+        if !is_font_payload(payload) {
+            return None;
+        }
+        let len = i32::from_le_bytes(data[start - 4..start].try_into().ok()?);
+        let len = usize::try_from(len).ok().filter(|&l| l >= 12 && l <= payload.len())?;
+        let font = &payload[..len];
+        read_font_info(font).ok().map(|_| font)
+    })
+}
+
+/// FontFace assets referenced by a composite Font package
+pub fn font_face_references(data: &[u8]) -> Result<Vec<String>> {
+    let package = Package::parse(data)?;
+    Ok((0..package.imports.len())
+        .map(|i| -(i as i32) - 1)
+        .filter(|&i| package.import(i).is_some_and(|import| import.class_name == "FontFace"))
+        .filter_map(|i| package.import_object_path(i))
+        .collect())
+}
+
+/// Reads family/style names from a TrueType/OpenType font (first face of a collection)
+pub fn read_font_info(font: &[u8]) -> Result<FontInfo> {
+    let (format, mime, offset) = match font.get(..4) {
+        Some(b"ttcf") => {
+            let first_face = u32::from_be_bytes(font.get(12..16).context("Truncated font collection")?.try_into()?);
+            ("TrueType Collection", "font/collection", first_face as usize)
+        }
+        Some(b"OTTO") => ("OpenType", "font/otf", 0),
+        Some([0x00, 0x01, 0x00, 0x00]) | Some(b"true") => ("TrueType", "font/ttf", 0),
+        _ => bail!("Not a TrueType/OpenType font"),
+    };
+
+    let be16 = |pos: usize| -> Result<u16> {
+        Ok(u16::from_be_bytes(font.get(pos..pos + 2).context("Truncated font")?.try_into()?))
+    };
+    let be32 = |pos: usize| -> Result<u32> {
+        Ok(u32::from_be_bytes(font.get(pos..pos + 4).context("Truncated font")?.try_into()?))
+    };
+
+    let table_count = be16(offset + 4)? as usize;
+    let name_table = (0..table_count)
+        .map(|i| offset + 12 + i * 16)
+        .find(|&record| font.get(record..record + 4) == Some(b"name"))
+        .map(|record| be32(record + 8))
+        .transpose()?
+        .context("Font has no name table")? as usize;
+
+    let count = be16(name_table + 2)? as usize;
+    let strings = name_table + be16(name_table + 4)? as usize;
+
+    // Prefer Windows Unicode records, fall back to Mac Roman ones
+    let mut names: [Option<(u16, String)>; 5] = Default::default();
+    for i in 0..count {
+        let record = name_table + 6 + i * 12;
+        let platform = be16(record)?;
+        let name_id = be16(record + 6)? as usize;
+        if !matches!(name_id, 1 | 2 | 4) {
+            continue;
+        }
+        let len = be16(record + 8)? as usize;
+        let start = strings + be16(record + 10)? as usize;
+        let Some(bytes) = font.get(start..start + len) else {
+            continue;
+        };
+
+        let value = match platform {
+            0 | 3 => {
+                let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+                String::from_utf16_lossy(&units)
+            }
+            1 => bytes.iter().map(|&b| b as char).collect(),
+            _ => continue,
+        };
+        let rank = if platform == 3 { 2 } else { 1 };
+        if names[name_id].as_ref().is_none_or(|(best, _)| rank > *best) {
+            names[name_id] = Some((rank, value));
+        }
+    }
+    let [_, family, style, _, full_name] = names.map(|n| n.map(|(_, value)| value));
+
+    Ok(FontInfo {
+        family,
+        style,
+        full_name,
+        format: format.to_string(),
+        mime: mime.to_string(),
+    })
+}
+
+/// Reads the brush of a SlateBrushAsset (or any export with a tagged `Brush` struct)
+pub fn parse_slate_brush(data: &[u8]) -> Result<SlateBrushInfo> {
+    let package = Package::parse(data)?;
+    let export = package.primary_export().context("Package has no exports")?;
+
+    let mut reader = ArchiveReader::new(package.export_data(data, export)?);
+    let properties = uasset_parser::read_properties(&package, &mut reader, &package.export_class_name(export))?;
+    let brush = properties.get("Brush").unwrap_or(&Value::Null);
+
+    Ok(SlateBrushInfo {
+        resource_object: brush.get("ResourceObject").and_then(Value::as_str).map(str::to_string),
+        image_size: brush.get("ImageSize").cloned(),
+        draw_as: brush.get("DrawAs").and_then(Value::as_str).map(str::to_string),
+        tint: brush.get("TintColor").cloned(),
+    })
+}
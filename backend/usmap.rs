@@ -0,0 +1,340 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::uasset_parser::ArchiveReader;
+
+/// Magic number at the start of every .usmap file
+const USMAP_MAGIC: u16 = 0x30C4;
+
+/// .usmap format versions (EUsmapVersion)
+const USMAP_VERSION_PACKAGE_VERSIONING: u8 = 1;
+const USMAP_VERSION_LONG_FNAME: u8 = 2;
+const USMAP_VERSION_LARGE_ENUMS: u8 = 3;
+const USMAP_VERSION_EXPLICIT_ENUM_VALUES: u8 = 4;
+const USMAP_VERSION_LATEST: u8 = USMAP_VERSION_EXPLICIT_ENUM_VALUES;
+
+/// Name index meaning "none" (a struct without a super struct)
+const NO_NAME: u32 = u32::MAX;
+
+/// Super structs followed before a layout is considered cyclic
+const MAX_STRUCT_DEPTH: usize = 64;
+
+/// How the payload after the .usmap header is compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UsmapCompression {
+    None,
+    Oodle,
+    Brotli,
+}
+
+/// Type of a property in a mappings schema (EUsmapPropertyType), with its inner types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyType {
+    Byte,
+    Bool,
+    Int,
+    Float,
+    Object,
+    Name,
+    Delegate,
+    Double,
+    Array(Box<PropertyType>),
+    Struct(String),
+    Str,
+    Text,
+    Interface,
+    MulticastDelegate,
+    WeakObject,
+    LazyObject,
+    /// AssetObjectProperty and SoftObjectProperty
+    SoftObject,
+    UInt64,
+    UInt32,
+    UInt16,
+    Int64,
+    Int16,
+    Int8,
+    Map(Box<PropertyType>, Box<PropertyType>),
+    Set(Box<PropertyType>),
+    /// Enum stored as its underlying integer type (ByteProperty with an enum, or EnumProperty)
+    Enum { inner: Box<PropertyType>, name: String },
+    FieldPath,
+    Optional(Box<PropertyType>),
+    Utf8Str,
+    AnsiStr,
+    Unknown(u8),
+}
+
+/// A serialized property of a struct or class
+#[derive(Debug, Clone)]
+pub struct PropertySchema {
+    pub name: String,
+    /// Position among the struct's own properties, counting each static array element
+    pub index: u16,
+    /// Elements of a static array (1 for plain properties)
+    pub array_size: u8,
+    pub property_type: PropertyType,
+}
+
+/// A struct or class and the properties it serializes, without inherited ones
+#[derive(Debug, Clone)]
+pub struct StructSchema {
+    pub name: String,
+    pub super_struct: Option<String>,
+    /// Property slots of the struct itself, static array elements included
+    pub property_count: u16,
+    pub properties: Vec<PropertySchema>,
+}
+
+/// Property schemas of a game's classes, structs and enums, from a .usmap file
+///
+/// Needed to read unversioned properties, which are serialized without names or types.
+#[derive(Debug, Clone)]
+pub struct Mappings {
+    pub version: u8,
+    pub compression: UsmapCompression,
+    pub name_count: usize,
+    /// Values and names of each enum
+    pub enums: HashMap<String, Vec<(u64, String)>>,
+    pub structs: HashMap<String, StructSchema>,
+}
+
+/// What was loaded from a .usmap file, for the settings screen
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct MappingsInfo {
+    pub path: String,
+    pub version: u8,
+    pub compression: UsmapCompression,
+    pub names: usize,
+    pub enums: usize,
+    pub structs: usize,
+}
+
+impl Mappings {
+    pub fn info(&self, path: &Path) -> MappingsInfo {
+        MappingsInfo {
+            path: path.display().to_string(),
+            version: self.version,
+            compression: self.compression,
+            names: self.name_count,
+            enums: self.enums.len(),
+            structs: self.structs.len(),
+        }
+    }
+
+    /// Name of an enum's value, without the enum's prefix
+    pub fn enum_value(&self, enum_name: &str, value: u64) -> Option<&str> {
+        let entries = self.enums.get(enum_name)?;
+        entries.iter().find(|(v, _)| *v == value).map(|(_, name)| name.as_str())
+    }
+
+    /// Every property slot of `struct_name`, inherited ones first, as unversioned headers
+    /// index them: each slot is a property and the static array element it holds
+    ///
+    /// None when the struct, or one of its super structs, isn't in the mappings.
+    pub fn layout(&self, struct_name: &str) -> Option<Vec<Option<(&PropertySchema, u8)>>> {
+        let mut chain = Vec::new();
+        let mut next = Some(struct_name);
+        while let Some(name) = next {
+            if chain.len() == MAX_STRUCT_DEPTH {
+                return None;
+            }
+            let schema = self.structs.get(name)?;
+            next = schema.super_struct.as_deref();
+            chain.push(schema);
+        }
+
+        let mut slots = Vec::new();
+        for schema in chain.into_iter().rev() {
+            let start = slots.len();
+            slots.resize(start + schema.property_count as usize, None);
+            for property in &schema.properties {
+                for element in 0..property.array_size {
+                    if let Some(slot) = slots.get_mut(start + property.index as usize + element as usize) {
+                        *slot = Some((property, element));
+                    }
+                }
+            }
+        }
+        Some(slots)
+    }
+}
+
+/// Parses a .usmap file, decompressing its payload (Oodle needs the configured library)
+pub fn parse(data: &[u8]) -> Result<Mappings> {
+    let mut reader = ArchiveReader::new(data);
+    if reader.read_u16()? != USMAP_MAGIC {
+        bail!("Not a .usmap file");
+    }
+    let version = reader.read_u8()?;
+    if version > USMAP_VERSION_LATEST {
+        bail!("Unsupported .usmap version {}", version);
+    }
+    if version >= USMAP_VERSION_PACKAGE_VERSIONING && reader.read_i32()? != 0 {
+        reader.skip(8)?; // UE4 and UE5 file versions
+        let custom_versions = reader.read_count(20)?;
+        reader.skip(custom_versions * 20 + 4)?; // Custom versions and net CL
+    }
+
+    let method = reader.read_u8()?;
+    let compressed_size = reader.read_u32()? as usize;
+    let size = reader.read_u32()? as usize;
+    let compressed = reader.read_bytes(compressed_size).context("Truncated .usmap payload")?;
+    let (compression, payload) = match method {
+        0 if compressed_size == size => (UsmapCompression::None, compressed.to_vec()),
+        0 => bail!("Uncompressed .usmap payload is {} bytes, not {}", compressed_size, size),
+        1 => {
+            let library = crate::settings::global().get().oodle_library().map(Path::to_path_buf).context(
+                "The .usmap file is Oodle-compressed; set the Oodle library (oodle_path) in settings to read it",
+            )?;
+            (UsmapCompression::Oodle, crate::oodle::decompress(&library, compressed, size)?)
+        }
+        2 => {
+            let mut payload = Vec::with_capacity(size);
+            brotli_decompressor::BrotliDecompress(&mut std::io::Cursor::new(compressed), &mut payload)
+                .context("Invalid Brotli .usmap payload")?;
+            if payload.len() != size {
+                bail!("Brotli .usmap payload is {} bytes, not {}", payload.len(), size);
+            }
+            (UsmapCompression::Brotli, payload)
+        }
+        other => bail!("Unsupported .usmap compression method {}", other),
+    };
+
+    let mut mappings = parse_payload(&payload, version)?;
+    mappings.compression = compression;
+    Ok(mappings)
+}
+
+/// Reads the name table, enums and structs of a decompressed .usmap payload
+fn parse_payload(data: &[u8], version: u8) -> Result<Mappings> {
+    let mut reader = ArchiveReader::new(data);
+
+    let name_count = reader.read_u32()? as usize;
+    if name_count > reader.remaining() {
+        bail!("Name count {} exceeds the .usmap payload", name_count);
+    }
+    let mut names = Vec::with_capacity(name_count);
+    for _ in 0..name_count {
+        let len = if version >= USMAP_VERSION_LONG_FNAME { reader.read_u16()? as usize } else { reader.read_u8()? as usize };
+        names.push(String::from_utf8_lossy(reader.read_bytes(len)?).into_owned());
+    }
+    let name = |reader: &mut ArchiveReader| -> Result<String> {
+        let index = reader.read_u32()?;
+        names.get(index as usize).cloned().with_context(|| format!("Name index {} out of range", index))
+    };
+
+    let enum_count = reader.read_u32()?;
+    let mut enums = HashMap::new();
+    for _ in 0..enum_count {
+        let enum_name = name(&mut reader)?;
+        let entry_count = if version >= USMAP_VERSION_LARGE_ENUMS { reader.read_u16()? as usize } else { reader.read_u8()? as usize };
+        let mut entries = Vec::with_capacity(entry_count);
+        for i in 0..entry_count {
+            let value = if version >= USMAP_VERSION_EXPLICIT_ENUM_VALUES { reader.read_u64()? } else { i as u64 };
+            entries.push((value, name(&mut reader)?));
+        }
+        enums.insert(enum_name, entries);
+    }
+
+    let struct_count = reader.read_u32()?;
+    let mut structs = HashMap::new();
+    for _ in 0..struct_count {
+        let struct_name = name(&mut reader)?;
+        let super_struct = match reader.read_u32()? {
+            NO_NAME => None,
+            index => Some(names.get(index as usize).cloned().with_context(|| format!("Name index {} out of range", index))?),
+        };
+        let property_count = reader.read_u16()?;
+        let serialized_count = reader.read_u16()?;
+        let mut properties = Vec::with_capacity(serialized_count as usize);
+        for _ in 0..serialized_count {
+            let index = reader.read_u16()?;
+            let array_size = reader.read_u8()?;
+            let property_name = name(&mut reader)?;
+            let property_type = read_property_type(&mut reader, &name)
+                .with_context(|| format!("Failed to read {}.{}", struct_name, property_name))?;
+            properties.push(PropertySchema { name: property_name, index, array_size, property_type });
+        }
+        structs.insert(struct_name.clone(), StructSchema { name: struct_name, super_struct, property_count, properties });
+    }
+
+    Ok(Mappings { version, compression: UsmapCompression::None, name_count, enums, structs })
+}
+
+fn read_property_type(
+    reader: &mut ArchiveReader,
+    name: &dyn Fn(&mut ArchiveReader) -> Result<String>,
+) -> Result<PropertyType> {
+    let inner = |reader: &mut ArchiveReader| read_property_type(reader, name).map(Box::new);
+    Ok(match reader.read_u8()? {
+        0 => PropertyType::Byte,
+        1 => PropertyType::Bool,
+        2 => PropertyType::Int,
+        3 => PropertyType::Float,
+        4 => PropertyType::Object,
+        5 => PropertyType::Name,
+        6 => PropertyType::Delegate,
+        7 => PropertyType::Double,
+        8 => PropertyType::Array(inner(reader)?),
+        9 => PropertyType::Struct(name(reader)?),
+        10 => PropertyType::Str,
+        11 => PropertyType::Text,
+        12 => PropertyType::Interface,
+        13 => PropertyType::MulticastDelegate,
+        14 => PropertyType::WeakObject,
+        15 => PropertyType::LazyObject,
+        16 | 17 => PropertyType::SoftObject,
+        18 => PropertyType::UInt64,
+        19 => PropertyType::UInt32,
+        20 => PropertyType::UInt16,
+        21 => PropertyType::Int64,
+        22 => PropertyType::Int16,
+        23 => PropertyType::Int8,
+        24 => PropertyType::Map(inner(reader)?, inner(reader)?),
+        25 => PropertyType::Set(inner(reader)?),
+        26 => PropertyType::Enum { inner: inner(reader)?, name: name(reader)? },
+        27 => PropertyType::FieldPath,
+        28 => PropertyType::Optional(inner(reader)?),
+        29 => PropertyType::Utf8Str,
+        30 => PropertyType::AnsiStr,
+        other => PropertyType::Unknown(other),
+    })
+}
+
+/// Reads and parses the .usmap file at `path`
+pub fn load(path: &Path) -> Result<Mappings> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&data).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Mappings of the configured .usmap, and the file's path and modification time
+type LoadedMappings = (PathBuf, Option<SystemTime>, Arc<Mappings>);
+
+static LOADED: Mutex<Option<LoadedMappings>> = Mutex::new(None);
+
+/// The mappings of the .usmap file in settings (`usmap_path`), or None when none is set
+///
+/// The file is parsed once and read again when the setting or the file changes.
+pub fn current() -> Result<Option<Arc<Mappings>>> {
+    let Some(path) = crate::settings::global().get().usmap_path else {
+        return Ok(None);
+    };
+    let modified = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+
+    let mut loaded = LOADED.lock().unwrap();
+    if let Some((loaded_path, loaded_modified, mappings)) = loaded.as_ref() {
+        if *loaded_path == path && *loaded_modified == modified {
+            return Ok(Some(mappings.clone()));
+        }
+    }
+    let mappings = Arc::new(load(&path)?);
+    tracing::info!("Loaded {} structs and {} enums from {}", mappings.structs.len(), mappings.enums.len(), path.display());
+    *loaded = Some((path, modified, mappings.clone()));
+    Ok(Some(mappings))
+}
@@ -1,6 +1,58 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
-use anyhow::Result;
+use anyhow::{bail, Result};
+use tokio_util::sync::CancellationToken;
+
+use crate::asset_path::normalize_asset_path;
+use crate::error::Cancelled;
+use crate::resource_limit;
+use crate::pak_parser::{decrypt, format_guid, method_names, read_u32, read_u64, MAX_MOUNT_POINT_LEN};
+
+/// Magic at the start of every .utoc (`FIoStoreTocHeader::TocMagicImg`)
+const TOC_MAGIC: &[u8; 16] = b"-==--==--==--==-";
+
+/// Size of `FIoStoreTocHeader`, the same in every version so far
+const TOC_HEADER_SIZE: usize = 144;
+
+/// Most bytes of compression method names read from a .utoc
+const MAX_METHOD_NAMES_LEN: u64 = 64 * 1024;
+
+/// Largest directory index `test_key` reads and decrypts
+const MAX_TESTED_INDEX_LEN: u64 = 256 * 1024 * 1024;
+
+/// `EIoContainerFlags` by bit
+const CONTAINER_FLAGS: [(u8, &str); 5] = [(1, "Compressed"), (2, "Encrypted"), (4, "Signed"), (8, "Indexed"), (16, "OnDemand")];
+
+/// What a .utoc header says about its container, read without walking the table of contents
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TocInfo {
+    pub path: String,
+    pub ucas_path: String,
+    /// From a `pakchunkN-Platform` name; None for other names
+    pub chunk_id: Option<u32>,
+    pub file_size: u64,
+    /// `EIoStoreTocVersion` (1 = initial, 8 = UE 5.3+ IoHash)
+    pub version: u8,
+    /// Hex id of the container
+    pub container_id: String,
+    /// Names of the set `EIoContainerFlags` (Compressed, Encrypted, Signed, Indexed, OnDemand)
+    pub container_flags: Vec<String>,
+    pub encrypted: bool,
+    /// GUID of the AES key the container needs; None for the default key
+    pub encryption_key_guid: Option<String>,
+    pub chunk_count: u64,
+    pub compression_block_count: u64,
+    pub compression_block_size: u64,
+    pub directory_index_size: u64,
+    pub partition_count: u64,
+    pub partition_size: u64,
+    pub compression_methods: Vec<String>,
+    /// Size of the .ucas holding the chunks
+    pub total_compressed_bytes: u64,
+    /// Sum of the entries' sizes, known once the container is in the listing
+    pub total_uncompressed_bytes: Option<u64>,
+}
 
 /// Represents a parsed .utoc (Unreal Table of Contents) file
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +99,22 @@ pub struct UcasChunk {
     pub hash: Option<String>,
 }
 
+/// Where a .utoc's compression method names start, and their length in bytes
+fn method_names_range(header: &[u8; TOC_HEADER_SIZE]) -> (u64, u64) {
+    let version = header[16];
+    let header_size = u64::from(read_u32(header, 20));
+    let chunk_count = u64::from(read_u32(header, 24));
+    let block_count = u64::from(read_u32(header, 28));
+    let block_entry_size = u64::from(read_u32(header, 32));
+    // Versions 4 and 5 added perfect-hash tables between the chunk and block tables
+    let seed_count = if version >= 4 { u64::from(read_u32(header, 84)) } else { 0 };
+    let unhashed_count = if version >= 5 { u64::from(read_u32(header, 96)) } else { 0 };
+
+    // Chunk ids (12 bytes each), offsets and lengths (10), the hash tables, then blocks
+    let methods_at = header_size + chunk_count * 22 + seed_count * 4 + unhashed_count * 4 + block_count * block_entry_size;
+    (methods_at, u64::from(read_u32(header, 36)) * u64::from(read_u32(header, 40)))
+}
+
 /// Parser for .utoc/.ucas file pairs (used in UE5)
 pub struct UtocUcasParser {
     pub utoc_path: String,
@@ -79,6 +147,13 @@ impl UtocUcasParser {
         })
     }
 
+    /// The AES key configured for this container: its own, else the one for `guid`, else the
+    /// wildcard key
+    pub fn aes_key(&self, guid: Option<&str>) -> Option<[u8; 32]> {
+        let settings = crate::settings::global().get();
+        settings.key_for(&self.utoc_path, guid).and_then(crate::settings::parse_key)
+    }
+
     /// Parses the .utoc file to extract table of contents
     /// 
     /// TODO: Implement actual .utoc parsing logic
@@ -191,7 +266,10 @@ impl UtocUcasParser {
         // 3. Decompress the data (usually LZ4 or Oodle)
         // 4. Verify chunk hash if present
 
-        Ok(vec![0u8; 1024]) // Placeholder data
+        // Room for the decompressed chunk is taken before it's allocated
+        let size = 1024;
+        let _lease = resource_limit::global().acquire(size as u64).await;
+        Ok(vec![0u8; size]) // Placeholder data
     }
 
     /// Lists all chunks in the archive
@@ -227,12 +305,133 @@ impl UtocUcasParser {
         Ok(true)
     }
 
+    /// Reads the .utoc header and its compression method names: a few hundred bytes at most
+    pub async fn info(&self) -> Result<TocInfo> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let mut file = tokio::fs::File::open(&self.utoc_path).await?;
+        let file_size = file.metadata().await?.len();
+        let mut header = [0u8; TOC_HEADER_SIZE];
+        if file_size < TOC_HEADER_SIZE as u64 {
+            bail!("{} is too small for a .utoc header", self.utoc_path);
+        }
+        file.read_exact(&mut header).await?;
+        if &header[..16] != TOC_MAGIC {
+            bail!("{} is not a .utoc: bad magic", self.utoc_path);
+        }
+
+        let version = header[16];
+        let chunk_count = u64::from(read_u32(&header, 24));
+        let block_count = u64::from(read_u32(&header, 28));
+        let method_length = u64::from(read_u32(&header, 40));
+        let flags = header[80];
+
+        let (methods_at, methods_len) = method_names_range(&header);
+        let compression_methods = if methods_len > 0 && methods_len <= MAX_METHOD_NAMES_LEN && methods_at + methods_len <= file_size {
+            let mut names = vec![0u8; methods_len as usize];
+            file.seek(std::io::SeekFrom::Start(methods_at)).await?;
+            file.read_exact(&mut names).await?;
+            method_names(&names, method_length as usize)
+        } else {
+            Vec::new()
+        };
+
+        Ok(TocInfo {
+            path: self.utoc_path.clone(),
+            ucas_path: self.ucas_path.clone(),
+            chunk_id: crate::pak_parser::chunk_id(&self.utoc_path),
+            file_size,
+            version,
+            container_id: format!("{:016X}", read_u64(&header, 56)),
+            container_flags: CONTAINER_FLAGS.iter().filter(|(bit, _)| flags & bit != 0).map(|(_, name)| name.to_string()).collect(),
+            encrypted: flags & 2 != 0,
+            encryption_key_guid: format_guid(&header[64..80]),
+            chunk_count,
+            compression_block_count: block_count,
+            compression_block_size: u64::from(read_u32(&header, 44)),
+            directory_index_size: u64::from(read_u32(&header, 48)),
+            // Containers before version 3 are a single partition
+            partition_count: if version >= 3 { u64::from(read_u32(&header, 52)).max(1) } else { 1 },
+            partition_size: if version >= 3 { read_u64(&header, 88) } else { 0 },
+            compression_methods,
+            total_compressed_bytes: tokio::fs::metadata(&self.ucas_path).await.map(|m| m.len()).unwrap_or(0),
+            total_uncompressed_bytes: None,
+        })
+    }
+
+    /// Whether `key` decrypts the container's directory index. Nothing is saved.
+    ///
+    /// A .utoc keeps no hash of its directory index, so the key is taken as right when the
+    /// decrypted index opens with a well-formed mount point, which a wrong key all but never
+    /// produces. Fails for containers that aren't encrypted or have no directory index.
+    pub async fn test_key(&self, key: &[u8; 32]) -> Result<bool> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let mut file = tokio::fs::File::open(&self.utoc_path).await?;
+        let file_size = file.metadata().await?.len();
+        let mut header = [0u8; TOC_HEADER_SIZE];
+        if file_size < TOC_HEADER_SIZE as u64 {
+            bail!("{} is too small for a .utoc header", self.utoc_path);
+        }
+        file.read_exact(&mut header).await?;
+        if &header[..16] != TOC_MAGIC {
+            bail!("{} is not a .utoc: bad magic", self.utoc_path);
+        }
+        let flags = header[80];
+        if flags & 2 == 0 {
+            bail!("{} isn't encrypted, so there's nothing to check the key against", self.utoc_path);
+        }
+        let index_size = u64::from(read_u32(&header, 48));
+        if flags & 8 == 0 || index_size == 0 {
+            bail!("{} has no directory index to check the key against", self.utoc_path);
+        }
+        if index_size % 16 != 0 || index_size > MAX_TESTED_INDEX_LEN {
+            bail!("{} has a directory index of {} bytes, which can't be checked", self.utoc_path, index_size);
+        }
+
+        // Signed containers put two signatures and a SHA-1 per block before the index
+        let (methods_at, methods_len) = method_names_range(&header);
+        let mut index_at = methods_at + methods_len;
+        if flags & 4 != 0 {
+            file.seek(std::io::SeekFrom::Start(index_at)).await?;
+            let hash_size = u64::from(file.read_u32_le().await?);
+            index_at += 4 + hash_size * 2 + u64::from(read_u32(&header, 28)) * 20;
+        }
+        if index_at.saturating_add(index_size) > file_size {
+            bail!("{} is truncated: its directory index isn't where the header says", self.utoc_path);
+        }
+
+        let mut index = vec![0u8; index_size as usize];
+        file.seek(std::io::SeekFrom::Start(index_at)).await?;
+        file.read_exact(&mut index).await?;
+        decrypt(key, &mut index);
+
+        // The mount point: a NUL-terminated FString, UTF-16 when its length is negative
+        let length = i32::from_le_bytes(index[..4].try_into().unwrap());
+        let (units, wide) = (length.unsigned_abs() as usize, length < 0);
+        let bytes = if wide { units * 2 } else { units };
+        if units == 0 || bytes > MAX_MOUNT_POINT_LEN || 4 + bytes > index.len() {
+            return Ok(false);
+        }
+        let raw = &index[4..4 + bytes];
+        let mount_point = if wide {
+            let units: Vec<u16> = raw.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+            String::from_utf16(&units).ok()
+        } else {
+            String::from_utf8(raw.to_vec()).ok()
+        };
+        Ok(mount_point.is_some_and(|mount_point| {
+            mount_point.strip_suffix('\0').is_some_and(|text| !text.chars().any(char::is_control))
+        }))
+    }
+
     /// Extracts file data by combining chunks
     /// 
     /// TODO: Implement file reconstruction from chunks
     /// UE5 files are often split across multiple chunks that need to be
-    /// reassembled in the correct order
-    pub async fn extract_file_data(&self, file_chunks: &[u64]) -> Result<Vec<u8>> {
+    /// reassembled in the correct order. `cancel` is checked before each chunk; a
+    /// cancelled extraction fails with `Cancelled` and keeps nothing.
+    pub async fn extract_file_data(&self, file_chunks: &[u64], cancel: &CancellationToken) -> Result<Vec<u8>> {
         tracing::info!("Extracting file data from {} chunks", file_chunks.len());
 
         // PLACEHOLDER: Return empty data for now
@@ -243,6 +442,7 @@ impl UtocUcasParser {
 
         let mut combined_data = Vec::new();
         for chunk_id in file_chunks {
+            Cancelled::check(cancel)?;
             let chunk_data = self.extract_chunk(*chunk_id).await?;
             combined_data.extend(chunk_data);
         }
@@ -302,11 +502,9 @@ pub mod utils {
 
     /// Determines the container format version from file headers
     pub async fn detect_container_version<P: AsRef<Path>>(utoc_path: P) -> Result<u32> {
-        // PLACEHOLDER: Return default version
-        // TODO: Read actual file header to determine version
         tracing::info!("Detecting container version for: {}", utoc_path.as_ref().display());
-        
-        Ok(1) // Default to version 1
+        let info = UtocUcasParser::new(utoc_path)?.info().await?;
+        Ok(u32::from(info.version))
     }
 
     /// Compares two .utoc files for differences
@@ -342,10 +540,19 @@ pub mod utils {
             ));
         }
 
+        // Compare directory names, whichever separators and mount prefix each index uses
+        let names1: HashSet<String> = file1.directories.iter().map(|d| normalize_asset_path(&d.name)).collect();
+        let names2: HashSet<String> = file2.directories.iter().map(|d| normalize_asset_path(&d.name)).collect();
+        let mut only1: Vec<&String> = names1.difference(&names2).collect();
+        let mut only2: Vec<&String> = names2.difference(&names1).collect();
+        only1.sort();
+        only2.sort();
+        differences.extend(only1.into_iter().map(|name| format!("Directory only in first: {}", name)));
+        differences.extend(only2.into_iter().map(|name| format!("Directory only in second: {}", name)));
+
         // TODO: Add more detailed comparison logic
         // - Compare individual chunk IDs and offsets
-        // - Compare directory names and file counts
-        // - Compare file sizes and metadata
+        // - Compare file counts, sizes and metadata
 
         Ok(differences)
     }
@@ -0,0 +1,711 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::uasset_parser::ArchiveReader;
+
+/// Size of `FZenPackageSummary` up to UE 5.2, ending with the graph data offset
+const SUMMARY_SIZE_UE5_0: usize = 44;
+
+/// Size of `FZenPackageSummary` from UE 5.3, with dependency bundles and imported package names
+const SUMMARY_SIZE_UE5_3: usize = 52;
+
+/// Size of an `FExportMapEntry`
+const EXPORT_ENTRY_SIZE: usize = 72;
+
+/// Size of an `FScriptObjectEntry` in the global container
+const SCRIPT_OBJECT_ENTRY_SIZE: usize = 32;
+
+/// Bits of an `FMappedName` index below its type
+const MAPPED_NAME_INDEX_MASK: u32 = (1 << 30) - 1;
+
+/// Bits of an `FPackageObjectIndex` below its type
+const OBJECT_INDEX_MASK: u64 = (1 << 62) - 1;
+
+/// `EObjectFlags` read to find the primary export
+const RF_PUBLIC: u32 = 0x1;
+const RF_CLASS_DEFAULT_OBJECT: u32 = 0x10;
+
+/// Outer chains followed when building script object paths
+const MAX_OUTER_DEPTH: usize = 32;
+
+/// Engine classes whose script imports resolve without the global container
+const BUILTIN_SCRIPT_CLASSES: &[&str] = &[
+    "/Script/Engine.Texture2D",
+    "/Script/Engine.TextureCube",
+    "/Script/Engine.Texture2DArray",
+    "/Script/Engine.VolumeTexture",
+    "/Script/Engine.TextureRenderTarget2D",
+    "/Script/Engine.StaticMesh",
+    "/Script/Engine.SkeletalMesh",
+    "/Script/Engine.Skeleton",
+    "/Script/Engine.PhysicsAsset",
+    "/Script/Engine.Material",
+    "/Script/Engine.MaterialFunction",
+    "/Script/Engine.MaterialInstanceConstant",
+    "/Script/Engine.MaterialParameterCollection",
+    "/Script/Engine.BlueprintGeneratedClass",
+    "/Script/Engine.SoundWave",
+    "/Script/Engine.SoundCue",
+    "/Script/Engine.SoundClass",
+    "/Script/Engine.SoundAttenuation",
+    "/Script/Engine.AnimSequence",
+    "/Script/Engine.AnimMontage",
+    "/Script/Engine.BlendSpace",
+    "/Script/Engine.AnimBlueprintGeneratedClass",
+    "/Script/Engine.World",
+    "/Script/Engine.DataTable",
+    "/Script/Engine.CompositeDataTable",
+    "/Script/Engine.CurveTable",
+    "/Script/Engine.CurveFloat",
+    "/Script/Engine.StringTable",
+    "/Script/Engine.Font",
+    "/Script/Engine.FontFace",
+    "/Script/Engine.ParticleSystem",
+    "/Script/Engine.PrimaryAssetLabel",
+    "/Script/Engine.DataAsset",
+    "/Script/Engine.PrimaryDataAsset",
+    "/Script/Niagara.NiagaraSystem",
+    "/Script/Niagara.NiagaraEmitter",
+    "/Script/UMG.WidgetBlueprintGeneratedClass",
+    "/Script/MediaAssets.FileMediaSource",
+    "/Script/CoreUObject.Class",
+    "/Script/CoreUObject.Package",
+];
+
+/// A reference to an object from a zen package (`FPackageObjectIndex`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageObjectIndex {
+    Null,
+    /// One of the package's own exports
+    Export(u32),
+    /// A native object, keyed by the hash of its lowercase path ("/script/engine/texture2d")
+    ScriptImport(u64),
+    /// A public export of an imported package: the index into the imported packages and the
+    /// index into the header's imported export hashes
+    PackageImport { package: u32, export_hash: u32 },
+}
+
+impl PackageObjectIndex {
+    fn from_raw(raw: u64) -> Self {
+        let value = raw & OBJECT_INDEX_MASK;
+        match raw >> 62 {
+            0 => PackageObjectIndex::Export(value as u32),
+            1 => PackageObjectIndex::ScriptImport(value),
+            2 => PackageObjectIndex::PackageImport { package: (value >> 32) as u32, export_hash: value as u32 },
+            _ => PackageObjectIndex::Null,
+        }
+    }
+}
+
+/// One entry of a zen package's export map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZenExport {
+    pub object_name: String,
+    pub cooked_serial_offset: u64,
+    pub cooked_serial_size: u64,
+    pub outer_index: PackageObjectIndex,
+    pub class_index: PackageObjectIndex,
+    pub super_index: PackageObjectIndex,
+    pub template_index: PackageObjectIndex,
+    pub public_export_hash: u64,
+    pub object_flags: u32,
+    pub filter_flags: u8,
+}
+
+/// What an export bundle does with an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportCommand {
+    Create,
+    Serialize,
+}
+
+/// One step of the order exports are created and serialized in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ExportBundleEntry {
+    pub export_index: u32,
+    pub command: ExportCommand,
+}
+
+/// A parsed zen package header: the cooked form of packages in IoStore (.ucas) containers
+///
+/// Headers from UE 5.0 on are read. Names of imported packages are only stored in the
+/// header from UE 5.3; before that they live in the container header's store entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZenPackage {
+    /// Package name ("/Game/Maps/Arena")
+    pub name: String,
+    pub package_flags: u32,
+    pub header_size: u32,
+    pub cooked_header_size: u32,
+    pub names: Vec<String>,
+    pub imports: Vec<PackageObjectIndex>,
+    pub exports: Vec<ZenExport>,
+    pub export_bundle_entries: Vec<ExportBundleEntry>,
+    /// Names of the packages `PackageImport`s point into, in index order; empty before UE 5.3
+    pub imported_package_names: Vec<String>,
+    /// Ids of the same packages (`FPackageId`), hashed from their names
+    pub imported_package_ids: Vec<u64>,
+}
+
+/// Offsets of the sections `FZenPackageSummary` points to
+struct SummaryOffsets {
+    imported_export_hashes: usize,
+    import_map: usize,
+    export_map: usize,
+    export_bundle_entries: usize,
+    /// Graph data up to 5.2, dependency bundle headers from 5.3
+    after_export_bundles: usize,
+    imported_package_names: Option<usize>,
+}
+
+impl ZenPackage {
+    /// Whether `data` opens like a zen package header rather than a classic .uasset
+    pub fn is_zen(data: &[u8]) -> bool {
+        data.len() >= SUMMARY_SIZE_UE5_0
+            && u32::from_le_bytes(data[..4].try_into().unwrap()) <= 1
+            && u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize <= data.len()
+    }
+
+    /// Parses the header of a package chunk, or of a package's bytes however they were read
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if !Self::is_zen(data) {
+            bail!("Not a zen package header");
+        }
+        let mut reader = ArchiveReader::new(data);
+        let has_versioning_info = reader.read_u32()? != 0;
+        let header_size = reader.read_u32()?;
+        let name = (reader.read_u32()?, reader.read_u32()?);
+        let package_flags = reader.read_u32()?;
+        let cooked_header_size = reader.read_u32()?;
+
+        // The summary doesn't say its version, so the 5.3 layout is tried first: its extra
+        // offsets have to ascend and a name map has to fit before the first section
+        let (offsets, names) = [SUMMARY_SIZE_UE5_3, SUMMARY_SIZE_UE5_0]
+            .into_iter()
+            .find_map(|summary_size| {
+                let offsets = read_offsets(data, summary_size, header_size as usize).ok()?;
+                let mut reader = ArchiveReader::new(&data[..header_size as usize]);
+                reader.seek(summary_size).ok()?;
+                if has_versioning_info {
+                    skip_versioning_info(&mut reader).ok()?;
+                }
+                let names = read_name_batch(&mut reader).ok()?;
+                (reader.position() <= offsets.imported_export_hashes).then_some((offsets, names))
+            })
+            .context("Unrecognized zen package summary")?;
+
+        let header = &data[..header_size as usize];
+        let mut package = ZenPackage {
+            name: mapped_name(&names, name.0, name.1).unwrap_or_default(),
+            package_flags,
+            header_size,
+            cooked_header_size,
+            imports: Vec::new(),
+            exports: Vec::new(),
+            export_bundle_entries: Vec::new(),
+            imported_package_names: Vec::new(),
+            imported_package_ids: Vec::new(),
+            names,
+        };
+
+        let mut reader = ArchiveReader::new(&header[..offsets.export_map]);
+        reader.seek(offsets.import_map)?;
+        while reader.remaining() >= 8 {
+            package.imports.push(PackageObjectIndex::from_raw(reader.read_u64()?));
+        }
+
+        let mut reader = ArchiveReader::new(&header[..offsets.export_bundle_entries]);
+        reader.seek(offsets.export_map)?;
+        while reader.remaining() >= EXPORT_ENTRY_SIZE {
+            package.exports.push(package.read_export(&mut reader).context("Failed to read export map")?);
+        }
+
+        let mut reader = ArchiveReader::new(&header[..offsets.after_export_bundles]);
+        reader.seek(offsets.export_bundle_entries)?;
+        for _ in 0..package.exports.len() * 2 {
+            if reader.remaining() < 8 {
+                break;
+            }
+            let export_index = reader.read_u32()?;
+            let command = match reader.read_u32()? {
+                0 => ExportCommand::Create,
+                _ => ExportCommand::Serialize,
+            };
+            package.export_bundle_entries.push(ExportBundleEntry { export_index, command });
+        }
+
+        if let Some(at) = offsets.imported_package_names.filter(|&at| at < header.len()) {
+            let mut reader = ArchiveReader::new(header);
+            reader.seek(at)?;
+            package.imported_package_names = read_imported_package_names(&mut reader).context("Failed to read imported package names")?;
+            package.imported_package_ids = package.imported_package_names.iter().map(|name| package_id(name)).collect();
+        }
+        Ok(package)
+    }
+
+    fn read_export(&self, reader: &mut ArchiveReader) -> Result<ZenExport> {
+        let cooked_serial_offset = reader.read_u64()?;
+        let cooked_serial_size = reader.read_u64()?;
+        let object_name = mapped_name(&self.names, reader.read_u32()?, reader.read_u32()?).context("Export name out of range")?;
+        let export = ZenExport {
+            object_name,
+            cooked_serial_offset,
+            cooked_serial_size,
+            outer_index: PackageObjectIndex::from_raw(reader.read_u64()?),
+            class_index: PackageObjectIndex::from_raw(reader.read_u64()?),
+            super_index: PackageObjectIndex::from_raw(reader.read_u64()?),
+            template_index: PackageObjectIndex::from_raw(reader.read_u64()?),
+            public_export_hash: reader.read_u64()?,
+            object_flags: reader.read_u32()?,
+            filter_flags: reader.read_u8()?,
+        };
+        reader.skip(3)?;
+        Ok(export)
+    }
+
+    /// The export the package is named after, else its first public top-level export
+    pub fn primary_export(&self) -> Option<&ZenExport> {
+        let short_name = self.name.rsplit('/').next().unwrap_or(&self.name);
+        let top_level = || {
+            self.exports.iter().filter(|export| {
+                export.outer_index == PackageObjectIndex::Null && export.object_flags & RF_CLASS_DEFAULT_OBJECT == 0
+            })
+        };
+        top_level()
+            .find(|export| export.object_name.eq_ignore_ascii_case(short_name))
+            .or_else(|| top_level().find(|export| export.object_flags & RF_PUBLIC != 0))
+            .or_else(|| top_level().next())
+    }
+
+    /// Class of an export: a native class named by `script_objects`, or one of the package's
+    /// own exports (a blueprint's generated class). None for classes of other packages.
+    pub fn export_class_name(&self, export: &ZenExport, script_objects: &ScriptObjects) -> Option<String> {
+        match export.class_index {
+            PackageObjectIndex::ScriptImport(hash) => script_objects.object_name(hash).map(str::to_string),
+            PackageObjectIndex::Export(index) => self.exports.get(index as usize).map(|class| class.object_name.clone()),
+            PackageObjectIndex::PackageImport { .. } | PackageObjectIndex::Null => None,
+        }
+    }
+
+    /// Names of the packages imported from, as far as the header has them (UE 5.3+)
+    pub fn imported_packages(&self) -> Vec<String> {
+        let mut packages: Vec<String> = self
+            .imports
+            .iter()
+            .filter_map(|import| match import {
+                PackageObjectIndex::PackageImport { package, .. } => self.imported_package_names.get(*package as usize).cloned(),
+                _ => None,
+            })
+            .collect();
+        packages.sort();
+        packages.dedup();
+        packages
+    }
+}
+
+/// The section offsets of a summary that's `summary_size` bytes long, if they're in order
+fn read_offsets(data: &[u8], summary_size: usize, header_size: usize) -> Result<SummaryOffsets> {
+    let mut reader = ArchiveReader::new(data);
+    reader.seek(24)?;
+    let mut offsets = Vec::with_capacity(8);
+    while reader.position() < summary_size {
+        offsets.push(reader.read_u32()? as usize);
+    }
+    if offsets.windows(2).any(|pair| pair[0] > pair[1]) || offsets.last().is_some_and(|&last| last > header_size) {
+        bail!("Zen package summary offsets out of order");
+    }
+    if offsets[0] < summary_size {
+        bail!("Zen package sections overlap the summary");
+    }
+    Ok(SummaryOffsets {
+        imported_export_hashes: offsets[0],
+        import_map: offsets[1],
+        export_map: offsets[2],
+        export_bundle_entries: offsets[3],
+        after_export_bundles: offsets[4],
+        imported_package_names: (summary_size == SUMMARY_SIZE_UE5_3).then(|| offsets[6]),
+    })
+}
+
+/// Skips `FZenPackageVersioningInfo`: zen and file versions, licensee version, custom versions
+fn skip_versioning_info(reader: &mut ArchiveReader) -> Result<()> {
+    reader.skip(16)?;
+    let custom_versions = reader.read_count(20)?;
+    reader.skip(custom_versions * 20)
+}
+
+/// Reads a name batch: counts, hashes, 2-byte headers, then the strings, UTF-16 ones 2-aligned
+fn read_name_batch(reader: &mut ArchiveReader) -> Result<Vec<String>> {
+    let count = reader.read_u32()? as usize;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let string_bytes = reader.read_u32()? as usize;
+    let _hash_version = reader.read_u64()?;
+    if count.saturating_mul(10).saturating_add(string_bytes) > reader.remaining() {
+        bail!("Name batch of {} names exceeds remaining data", count);
+    }
+    reader.skip(count * 8)?;
+    let headers = reader.read_bytes(count * 2)?;
+    let strings = reader.read_bytes(string_bytes)?;
+
+    let mut names = Vec::with_capacity(count);
+    let mut at = 0;
+    for header in headers.chunks_exact(2) {
+        let wide = header[0] & 0x80 != 0;
+        let len = (usize::from(header[0] & 0x7F) << 8) | usize::from(header[1]);
+        if wide {
+            at += at & 1;
+            let bytes = strings.get(at..at + len * 2).context("Name batch strings truncated")?;
+            let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+            names.push(String::from_utf16_lossy(&units));
+            at += len * 2;
+        } else {
+            let bytes = strings.get(at..at + len).context("Name batch strings truncated")?;
+            names.push(String::from_utf8_lossy(bytes).into_owned());
+            at += len;
+        }
+    }
+    Ok(names)
+}
+
+/// `FZenPackageImportedPackageNamesContainer`: a name batch, then the names' numbers
+fn read_imported_package_names(reader: &mut ArchiveReader) -> Result<Vec<String>> {
+    let mut names = read_name_batch(reader)?;
+    let numbers = reader.read_count(4)?;
+    for name in names.iter_mut().take(numbers) {
+        let number = reader.read_i32()?;
+        if number > 0 {
+            *name = format!("{}_{}", name, number - 1);
+        }
+    }
+    Ok(names)
+}
+
+/// Text of an `FMappedName`: the name at its index, "_N" appended for a number N + 1
+fn mapped_name(names: &[String], index: u32, number: u32) -> Option<String> {
+    let name = names.get((index & MAPPED_NAME_INDEX_MASK) as usize)?;
+    Some(if number > 0 { format!("{}_{}", name, number - 1) } else { name.clone() })
+}
+
+/// Native objects by the hash their script imports carry, from the global container
+#[derive(Debug, Clone, Default)]
+pub struct ScriptObjects {
+    /// Object path ("/Script/Engine.Texture2D") by hash
+    paths: HashMap<u64, String>,
+}
+
+impl ScriptObjects {
+    /// Parses the ScriptObjects chunk of a game's global.utoc: a name batch, then an
+    /// `FScriptObjectEntry` per native object
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let mut reader = ArchiveReader::new(data);
+        let names = read_name_batch(&mut reader).context("Failed to read script object names")?;
+        let count = reader.read_count(SCRIPT_OBJECT_ENTRY_SIZE)?;
+        let mut entries = HashMap::with_capacity(count);
+        for _ in 0..count {
+            let name = mapped_name(&names, reader.read_u32()?, reader.read_u32()?).context("Script object name out of range")?;
+            let global_index = PackageObjectIndex::from_raw(reader.read_u64()?);
+            let outer_index = PackageObjectIndex::from_raw(reader.read_u64()?);
+            let _cdo_class_index = reader.read_u64()?;
+            if let PackageObjectIndex::ScriptImport(hash) = global_index {
+                entries.insert(hash, (name, outer_index));
+            }
+        }
+
+        let mut paths = HashMap::with_capacity(entries.len());
+        for (&hash, (name, outer)) in &entries {
+            let mut segments = vec![name.as_str()];
+            let mut outer = *outer;
+            while let PackageObjectIndex::ScriptImport(outer_hash) = outer {
+                let Some((outer_name, next)) = entries.get(&outer_hash).filter(|_| segments.len() < MAX_OUTER_DEPTH) else {
+                    break;
+                };
+                segments.push(outer_name);
+                outer = *next;
+            }
+            segments.reverse();
+            let mut path = segments[0].to_string();
+            for (depth, segment) in segments.iter().enumerate().skip(1) {
+                path.push(if depth == 1 { '.' } else { ':' });
+                path.push_str(segment);
+            }
+            paths.insert(hash, path);
+        }
+        Ok(Self { paths })
+    }
+
+    /// The engine classes most assets are instances of, for when the global container
+    /// can't be read
+    pub fn builtin() -> &'static ScriptObjects {
+        static BUILTIN: OnceLock<ScriptObjects> = OnceLock::new();
+        BUILTIN.get_or_init(|| ScriptObjects {
+            paths: BUILTIN_SCRIPT_CLASSES.iter().map(|path| (script_object_hash(path), path.to_string())).collect(),
+        })
+    }
+
+    /// Full path of the native object with this hash
+    pub fn object_path(&self, hash: u64) -> Option<&str> {
+        self.paths.get(&hash).map(String::as_str)
+    }
+
+    /// Name of the native object with this hash ("Texture2D")
+    pub fn object_name(&self, hash: u64) -> Option<&str> {
+        self.object_path(hash).map(|path| path.rsplit(['.', ':']).next().unwrap_or(path))
+    }
+}
+
+/// Hash a script import carries for an object path (`FPackageObjectIndex::FromScriptPath`)
+pub fn script_object_hash(object_path: &str) -> u64 {
+    let path: String = object_path.chars().map(|c| if c == '.' || c == ':' { '/' } else { c }).collect();
+    utf16_lower_hash(&path) & OBJECT_INDEX_MASK
+}
+
+/// `FPackageId` of a package name ("/Game/Maps/Arena")
+pub fn package_id(package_name: &str) -> u64 {
+    utf16_lower_hash(package_name)
+}
+
+/// CityHash64 of the lowercase text as UTF-16, the way UE hashes names and paths
+fn utf16_lower_hash(text: &str) -> u64 {
+    let bytes: Vec<u8> = text.to_lowercase().encode_utf16().flat_map(u16::to_le_bytes).collect();
+    city_hash64(&bytes)
+}
+
+const K0: u64 = 0xc3a5c85c97cb3127;
+const K1: u64 = 0xb492b66fbe98f273;
+const K2: u64 = 0x9ae16a3b2f90404f;
+const K_MUL: u64 = 0x9ddfea08eb382d69;
+
+fn fetch64(s: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(s[at..at + 8].try_into().unwrap())
+}
+
+fn fetch32(s: &[u8], at: usize) -> u64 {
+    u64::from(u32::from_le_bytes(s[at..at + 4].try_into().unwrap()))
+}
+
+fn shift_mix(v: u64) -> u64 {
+    v ^ (v >> 47)
+}
+
+fn hash_len16(u: u64, v: u64, mul: u64) -> u64 {
+    let a = shift_mix((u ^ v).wrapping_mul(mul));
+    let b = shift_mix((v ^ a).wrapping_mul(mul));
+    b.wrapping_mul(mul)
+}
+
+fn weak_hash_len32_with_seeds(s: &[u8], at: usize, a: u64, b: u64) -> (u64, u64) {
+    let (w, x, y, z) = (fetch64(s, at), fetch64(s, at + 8), fetch64(s, at + 16), fetch64(s, at + 24));
+    let a = a.wrapping_add(w);
+    let b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    let a = a.wrapping_add(x).wrapping_add(y);
+    let b = b.wrapping_add(a.rotate_right(44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+fn hash_len0to16(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len >= 8 {
+        let mul = K2.wrapping_add(len as u64 * 2);
+        let a = fetch64(s, 0).wrapping_add(K2);
+        let b = fetch64(s, len - 8);
+        let c = b.rotate_right(37).wrapping_mul(mul).wrapping_add(a);
+        let d = a.rotate_right(25).wrapping_add(b).wrapping_mul(mul);
+        return hash_len16(c, d, mul);
+    }
+    if len >= 4 {
+        let mul = K2.wrapping_add(len as u64 * 2);
+        let a = fetch32(s, 0);
+        return hash_len16((len as u64).wrapping_add(a << 3), fetch32(s, len - 4), mul);
+    }
+    if len > 0 {
+        let y = u32::from(s[0]).wrapping_add(u32::from(s[len >> 1]) << 8);
+        let z = (len as u32).wrapping_add(u32::from(s[len - 1]) << 2);
+        return shift_mix(u64::from(y).wrapping_mul(K2) ^ u64::from(z).wrapping_mul(K0)).wrapping_mul(K2);
+    }
+    K2
+}
+
+fn hash_len17to32(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s, 0).wrapping_mul(K1);
+    let b = fetch64(s, 8);
+    let c = fetch64(s, len - 8).wrapping_mul(mul);
+    let d = fetch64(s, len - 16).wrapping_mul(K2);
+    hash_len16(
+        a.wrapping_add(b).rotate_right(43).wrapping_add(c.rotate_right(30)).wrapping_add(d),
+        a.wrapping_add(b.wrapping_add(K2).rotate_right(18)).wrapping_add(c),
+        mul,
+    )
+}
+
+fn hash_len33to64(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s, 0).wrapping_mul(K2);
+    let b = fetch64(s, 8);
+    let c = fetch64(s, len - 24);
+    let d = fetch64(s, len - 32);
+    let e = fetch64(s, 16).wrapping_mul(K2);
+    let f = fetch64(s, 24).wrapping_mul(9);
+    let g = fetch64(s, len - 8);
+    let h = fetch64(s, len - 16).wrapping_mul(mul);
+    let u = a.wrapping_add(g).rotate_right(43).wrapping_add(b.rotate_right(30).wrapping_add(c).wrapping_mul(9));
+    let v = (a.wrapping_add(g) ^ d).wrapping_add(f).wrapping_add(1);
+    let w = u.wrapping_add(v).wrapping_mul(mul).swap_bytes().wrapping_add(h);
+    let x = e.wrapping_add(f).rotate_right(42).wrapping_add(c);
+    let y = v.wrapping_add(w).wrapping_mul(mul).swap_bytes().wrapping_add(g).wrapping_mul(mul);
+    let z = e.wrapping_add(f).wrapping_add(c);
+    let a = x.wrapping_add(z).wrapping_mul(mul).wrapping_add(y).swap_bytes().wrapping_add(b);
+    let b = shift_mix(z.wrapping_add(a).wrapping_mul(mul).wrapping_add(d).wrapping_add(h)).wrapping_mul(mul);
+    b.wrapping_add(x)
+}
+
+/// CityHash64 v1.1, which UE uses for package ids and script import hashes
+fn city_hash64(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len <= 16 {
+        return hash_len0to16(s);
+    }
+    if len <= 32 {
+        return hash_len17to32(s);
+    }
+    if len <= 64 {
+        return hash_len33to64(s);
+    }
+
+    let mut x = fetch64(s, len - 40);
+    let mut y = fetch64(s, len - 16).wrapping_add(fetch64(s, len - 56));
+    let mut z = hash_len16(fetch64(s, len - 48).wrapping_add(len as u64), fetch64(s, len - 24), K_MUL);
+    let mut v = weak_hash_len32_with_seeds(s, len - 64, len as u64, z);
+    let mut w = weak_hash_len32_with_seeds(s, len - 32, y.wrapping_add(K1), x);
+    x = x.wrapping_mul(K1).wrapping_add(fetch64(s, 0));
+
+    let mut at = 0;
+    let mut remaining = (len - 1) & !63;
+    loop {
+        x = x.wrapping_add(y).wrapping_add(v.0).wrapping_add(fetch64(s, at + 8)).rotate_right(37).wrapping_mul(K1);
+        y = y.wrapping_add(v.1).wrapping_add(fetch64(s, at + 48)).rotate_right(42).wrapping_mul(K1);
+        x ^= w.1;
+        y = y.wrapping_add(v.0).wrapping_add(fetch64(s, at + 40));
+        z = z.wrapping_add(w.0).rotate_right(33).wrapping_mul(K1);
+        v = weak_hash_len32_with_seeds(s, at, v.1.wrapping_mul(K1), x.wrapping_add(w.0));
+        w = weak_hash_len32_with_seeds(s, at + 32, z.wrapping_add(w.1), y.wrapping_add(fetch64(s, at + 16)));
+        std::mem::swap(&mut z, &mut x);
+        at += 64;
+        remaining -= 64;
+        if remaining == 0 {
+            break;
+        }
+    }
+    hash_len16(
+        hash_len16(v.0, w.0, K_MUL).wrapping_add(shift_mix(y).wrapping_mul(K1)).wrapping_add(z),
+        hash_len16(v.1, w.1, K_MUL).wrapping_add(x),
+        K_MUL,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset_type::AssetType;
+    use crate::dependency_map::{utils::extract_package_references, DependencyKind};
+
+    /// "/Game/Props/Crate" with a StaticMesh export "Crate" and its class default object,
+    /// importing from "/Game/Materials/M_Wood"; the UE 5.0 header can't name that package
+    const CRATE_UE5_0: &[u8] = include_bytes!("fixtures/zen/Crate_UE5_0.uasset");
+    const CRATE_UE5_3: &[u8] = include_bytes!("fixtures/zen/Crate_UE5_3.uasset");
+
+    #[test]
+    fn parses_names_classes_and_imports() {
+        for data in [CRATE_UE5_0, CRATE_UE5_3] {
+            assert!(ZenPackage::is_zen(data));
+            let package = ZenPackage::parse(data).unwrap();
+            assert_eq!(package.name, "/Game/Props/Crate");
+            assert_eq!(package.exports.len(), 2);
+            assert_eq!(package.export_bundle_entries.len(), 4);
+            assert_eq!(package.imports[0], PackageObjectIndex::ScriptImport(script_object_hash("/Script/Engine.StaticMesh")));
+            assert_eq!(package.imports[1], PackageObjectIndex::PackageImport { package: 0, export_hash: 0 });
+            assert_eq!(package.imports[2], PackageObjectIndex::Null);
+
+            let primary = package.primary_export().unwrap();
+            assert_eq!(primary.object_name, "Crate");
+            let class = package.export_class_name(primary, ScriptObjects::builtin()).unwrap();
+            assert_eq!(class, "StaticMesh");
+            assert_eq!(AssetType::from(class.as_str()), AssetType::StaticMesh);
+            let default_object = &package.exports[1];
+            assert_eq!(default_object.object_name, "Default__Crate");
+            assert_eq!(package.export_class_name(default_object, ScriptObjects::builtin()).as_deref(), Some("StaticMesh"));
+        }
+    }
+
+    #[test]
+    fn imported_package_names_from_ue5_3() {
+        let package = ZenPackage::parse(CRATE_UE5_3).unwrap();
+        assert_eq!(package.imported_packages(), vec!["/Game/Materials/M_Wood"]);
+        assert_eq!(package.imported_package_ids, vec![package_id("/Game/Materials/M_Wood")]);
+        assert_eq!(
+            extract_package_references(CRATE_UE5_3).unwrap(),
+            vec![("/Game/Materials/M_Wood".to_string(), DependencyKind::Hard)]
+        );
+
+        let package = ZenPackage::parse(CRATE_UE5_0).unwrap();
+        assert!(package.imported_packages().is_empty());
+        assert!(extract_package_references(CRATE_UE5_0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        assert!(ZenPackage::parse(&CRATE_UE5_3[..40]).is_err());
+        assert!(ZenPackage::parse(&[0xC1, 0x83, 0x2A, 0x9E, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn city_hash64_matches_reference_vectors() {
+        // Input and expected values of the CityHash 1.1 test suite: row i hashes the i bytes
+        // at offset i * i of a generated 1 MiB buffer, and the last row the whole buffer
+        let mut data = vec![0u8; 1 << 20];
+        let (mut a, mut b) = (9u64, 777u64);
+        for (i, byte) in data.iter_mut().enumerate() {
+            a = a.wrapping_add(b);
+            b = b.wrapping_add(a);
+            a = (a ^ (a >> 41)).wrapping_mul(K0);
+            b = (b ^ (b >> 41)).wrapping_mul(K0).wrapping_add(i as u64);
+            *byte = (b >> 37) as u8;
+        }
+        let vectors: [(usize, u64); 21] = [
+            (0, 0x9ae16a3b2f90404f),
+            (1, 0x541150e87f415e96),
+            (2, 0xf3786a4b25827c1),
+            (3, 0xef923a7a1af78eab),
+            (4, 0x11df592596f41d88),
+            (7, 0x1b5a063fb4c7f9f1),
+            (8, 0xa0f10149a0e538d6),
+            (15, 0x44473e03be306c88),
+            (16, 0x3ead5f21d344056),
+            (17, 0x6abbfde37ee03b5b),
+            (31, 0x55bdb0e71e3edebd),
+            (32, 0x782fa1b08b475e7),
+            (33, 0xc5dc19b876d37a80),
+            (63, 0x12807833c463737c),
+            (64, 0xe88419922b87176f),
+            (65, 0x105191e0ec8f7f60),
+            (127, 0xcbaa3cb8f64f54e0),
+            (128, 0xb2e23e8116c2ba9f),
+            (129, 0x8aa77f52d7868eb9),
+            (200, 0x7fc98006e25cac9),
+            (299, 0x5fb5e48ac7b7fa4f),
+        ];
+        for (i, expected) in vectors {
+            let input = if i == 299 { &data[..] } else { &data[i * i..i * i + i] };
+            assert_eq!(city_hash64(input), expected, "row {}", i);
+        }
+    }
+}
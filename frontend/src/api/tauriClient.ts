@@ -1,81 +1,1270 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type { 
   BackendAsset, 
   AssetsResponse, 
+  AssetDetails,
+  AssetSort,
+  AssetListFilters,
   PreviewResponse, 
   DependencyResponse, 
+  BuildOrderResponse,
+  RemovalImpact,
+  DependencyStatistics,
+  DependencyExportFormat,
+  DependencyExportOptions,
+  MergeSummary,
+  VersionComparison,
+  UnreachableReport,
+  ValidationIssue,
+  DependencyKind,
+  SubgraphFilter,
+  TreeDirection,
   AppInfo,
-  Asset 
+  Asset,
+  PreviewCacheStats,
+  BatchPreviewResult,
+  ProgressEvent,
+  ApiServerInfo,
+  Mount,
+  StatusReport,
+  ScanOptions,
+  ScanProgress,
+  ScanProgressEvent,
+  ApiError,
+  ExtractResult,
+  ExtractionSummary,
+  ExtractProgressEvent,
+  ContainerInfo,
+  Settings,
+  SettingsPatch,
+  RecentFolders,
+  AssetsChangedEvent,
+  AssetListExport,
+  AssetListFormat,
+  ScanStatistics,
+  StorageReport,
+  ChunkSummary,
+  EncryptionAudit,
+  LocalizationSummary,
+  CultureEntries,
+  DuplicateReport,
+  KeyAssignment,
+  MappingsInfo,
+  ProfileSpec,
+  ProfileList,
+  ProfileActivation,
+  KeyTest,
+  DroppedPath,
+  Diagnostics,
+  ImageFormat,
+  ConvertedTexture,
+  TreeFolder
 } from '../types';
 
 // Re-export types for convenience
 export type { AppInfo, PreviewResponse };
 
+/**
+ * Error of a failed command, carrying its `ApiError` so callers can act on `code`
+ */
+export class CommandError extends Error {
+  constructor(public readonly error: ApiError) {
+    super(error.message);
+    this.name = "CommandError";
+  }
+}
+
+/** The `ApiError` a command rejected with, or null for anything else */
+function parseCommandError(error: unknown): ApiError | null {
+  if (error && typeof error === "object" && typeof (error as ApiError).code === "string") {
+    return error as ApiError;
+  }
+  return null;
+}
+
+/** Message of a command's error: the `ApiError` message, or the error as text */
+function errorMessage(error: unknown): string {
+  return parseCommandError(error)?.message ?? String(error);
+}
+
 // ============================================================================
 // TAURI API CLIENT FUNCTIONS
 // ============================================================================
 
 /**
- * Lists assets with optional filtering
- * @param assetType - Filter by asset type (e.g., "texture", "mesh", "audio")
+ * Lists the loaded assets with optional filtering; scan a folder with `startScan` first
+ * @param assetType - Filter by asset type (e.g., "texture", "static_mesh", "sound"); class names also work
  * @param search - Search term to filter assets by name or path
- * @param targetFolder - Path to folder to scan for .pak files
+ * @param offset - Index of the first asset to return, after filtering and sorting
+ * @param limit - Page size (default 500, at most 5000)
+ * @param sort - Field to order by, e.g. "name" or "size:desc"; scan order when omitted
+ * @param filters - Narrower filters, all of which must match (types, size range, compression, ...)
  * @returns Promise with assets response
  */
 export async function listAssets(
   assetType?: string,
   search?: string,
-  targetFolder?: string
+  offset?: number,
+  limit?: number,
+  sort?: AssetSort,
+  filters?: AssetListFilters
 ): Promise<AssetsResponse> {
   try {
     const response = await invoke<AssetsResponse>("list_assets", {
-      asset_type: assetType,
+      assetType,
       search,
-      target_folder: targetFolder,
+      offset,
+      limit,
+      sort,
+      filters,
     });
     return response;
   } catch (error) {
     console.error("Failed to list assets:", error);
-    throw new Error(`Failed to list assets: ${error}`);
+    throw new Error(`Failed to list assets: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Adds a folder, .pak or .utoc to the listing and scans it
+ * @param path - Folder of paks, or a single container
+ * @param priority - Higher wins when mounts have the same asset path (default 0)
+ * @param deepScan - Also read class and tags from each package
+ * @returns Promise with the mount, once its assets are listed
+ */
+export async function addMount(path: string, priority?: number, deepScan?: boolean): Promise<Mount> {
+  try {
+    return await invoke<Mount>("add_mount", { path, priority, deepScan });
+  } catch (error) {
+    console.error(`Failed to mount ${path}:`, error);
+    throw new Error(`Failed to mount ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Takes a mount's assets and dependency edges out of the listing
+ * @param id - Id of the mount
+ * @returns Promise with the removed mount
+ */
+export async function removeMount(id: string): Promise<Mount> {
+  try {
+    return await invoke<Mount>("remove_mount", { id });
+  } catch (error) {
+    console.error(`Failed to remove mount ${id}:`, error);
+    throw new Error(`Failed to remove mount ${id}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Starts scanning a folder or .pak into the backend state in the background
+ * @param folder - Folder of .pak files, or a single .pak
+ * @param options - Scan options (deep scan)
+ * @returns Promise with the job id, as found in `scan://progress` and `scan://done` events
+ */
+export async function startScan(folder: string, options?: ScanOptions): Promise<string> {
+  try {
+    return await invoke<string>("start_scan", { folder, options });
+  } catch (error) {
+    console.error(`Failed to start scan of ${folder}:`, error);
+    throw new Error(`Failed to start scan of ${folder}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Cancels a running scan; the listing stays as it was
+ * @param jobId - Id returned by `startScan` (or a mount id)
+ */
+export async function cancelScan(jobId: string): Promise<void> {
+  try {
+    await invoke("cancel_scan", { jobId });
+  } catch (error) {
+    console.error(`Failed to cancel scan ${jobId}:`, error);
+    throw new Error(`Failed to cancel scan ${jobId}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Lists the mounts with their pak and asset counts and scan status
+ * @returns Promise with the mounts in registration order
+ */
+export async function getMounts(): Promise<Mount[]> {
+  try {
+    return await invoke<Mount[]>("get_mounts");
+  } catch (error) {
+    console.error("Failed to get mounts:", error);
+    throw new Error(`Failed to get mounts: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Extracts an asset to a file, e.g. one picked in a save dialog
+ * @param assetPath - Exact path of the asset inside its pak
+ * @param pakPath - Pak of a mount containing the asset; looked up among the scanned assets when omitted
+ * @param destination - File to write; parent folders are created
+ * @param overwrite - Replace the file if it exists
+ * @returns Promise with the bytes written and whether the hash verified
+ * @throws CommandError with `code` ENTRY_ENCRYPTED (no AES key), DECOMPRESSOR_UNAVAILABLE
+ *   (Oodle), DESTINATION_EXISTS, ...
+ */
+export async function extractAsset(
+  assetPath: string,
+  pakPath: string | undefined,
+  destination: string,
+  overwrite?: boolean
+): Promise<ExtractResult> {
+  try {
+    return await invoke<ExtractResult>("extract_asset", {
+      assetPath,
+      pakPath,
+      destination,
+      overwrite,
+    });
+  } catch (error) {
+    console.error(`Failed to extract ${assetPath}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to extract ${assetPath}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Extracts the asset with this id to a file, e.g. one picked in a save dialog
+ * @param id - The asset's `id` from the listing
+ * @param destination - File to write; parent folders are created
+ * @param overwrite - Replace the file if it exists
+ * @returns Promise with the bytes written and whether the hash verified
+ * @throws CommandError with `code` ASSET_NOT_FOUND, ENTRY_ENCRYPTED, DESTINATION_EXISTS, ...
+ */
+export async function extractAssetById(id: string, destination: string, overwrite?: boolean): Promise<ExtractResult> {
+  try {
+    return await invoke<ExtractResult>("extract_asset", { assetId: id, destination, overwrite });
+  } catch (error) {
+    console.error(`Failed to extract asset ${id}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to extract asset ${id}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Saves a texture as an image file at full resolution, or at the mip asked for
+ * @param assetPath - Exact path of the texture inside its pak
+ * @param pakPath - Pak containing the texture; looked up among the scanned assets when omitted
+ * @param destination - File to write; parent folders are created
+ * @param format - "png" or "tga"; pixel formats that can't be decoded (BC6H, ASTC) are saved as DDS
+ * @param mip - Mip index, 0 being the largest cooked; the largest with data when omitted
+ * @param overwrite - Replace the file if it exists
+ * @returns Promise with the file written, its format and dimensions
+ * @throws CommandError with `code` TEXTURE_UNREADABLE, DESTINATION_EXISTS, ...
+ */
+export async function convertTexture(
+  assetPath: string,
+  pakPath: string | undefined,
+  destination: string,
+  format: ImageFormat,
+  mip?: number,
+  overwrite?: boolean
+): Promise<ConvertedTexture> {
+  try {
+    return await invoke<ConvertedTexture>("convert_texture", {
+      assetPath,
+      pakPath,
+      destination,
+      format,
+      mip,
+      overwrite,
+    });
+  } catch (error) {
+    console.error(`Failed to convert ${assetPath}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to convert ${assetPath}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Extracts many assets, or whole folders of them, reporting each as `extract://progress`
+ * @param selection - Asset paths, or folders covering every asset under them
+ * @param destination - Folder to extract into
+ * @param preservePaths - Recreate the pak folders; otherwise clashing names get numeric suffixes
+ * @param overwrite - Replace existing files instead of reporting them as failed
+ * @param jobId - Id for the events and `cancelJob`; made up when omitted
+ * @returns Promise with how many files were extracted, which failed and why
+ */
+export async function extractAssets(
+  selection: string[],
+  destination: string,
+  preservePaths: boolean,
+  overwrite?: boolean,
+  jobId?: string
+): Promise<ExtractionSummary> {
+  try {
+    return await invoke<ExtractionSummary>("extract_assets", {
+      selection,
+      destination,
+      preservePaths,
+      overwrite,
+      jobId,
+    });
+  } catch (error) {
+    console.error(`Failed to extract to ${destination}:`, error);
+    throw new Error(`Failed to extract to ${destination}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Shows a pak selected in the OS file manager
+ * @param pakPath - Pak of a mount (or of the last scan)
+ * @throws CommandError with `code` NOT_MOUNTED or OPEN_FAILED
+ */
+export async function revealPak(pakPath: string): Promise<void> {
+  try {
+    await invoke("reveal_pak", { pakPath });
+  } catch (error) {
+    console.error(`Failed to reveal ${pakPath}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to reveal ${pakPath}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Extracts an asset to a temp folder and opens it with the OS default application;
+ * files other than images, audio, video, fonts and text are shown in the file manager instead
+ * @param assetPath - Exact path of the asset inside its pak
+ * @param pakPath - Pak of a mount (or of the last scan) containing the asset
+ * @returns Promise with where the file was written; it's removed when the app exits
+ * @throws CommandError with `code` NOT_MOUNTED, ENTRY_ENCRYPTED, OPEN_FAILED, ...
+ */
+export async function extractAndOpen(assetPath: string, pakPath: string): Promise<string> {
+  try {
+    return await invoke<string>("extract_and_open", { assetPath, pakPath });
+  } catch (error) {
+    console.error(`Failed to open ${assetPath}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to open ${assetPath}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Cancels a running job: a scan, a mount's scan, an `extractAssets` run or a `findDuplicates` one
+ * @param jobId - Id of the job
+ */
+export async function cancelJob(jobId: string): Promise<void> {
+  try {
+    await invoke("cancel_job", { jobId });
+  } catch (error) {
+    console.error(`Failed to cancel job ${jobId}:`, error);
+    throw new Error(`Failed to cancel job ${jobId}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets a .pak's or .utoc's header-level details (version, mount point, encryption, ...)
+ * @param path - Path of the .pak or .utoc
+ * @returns Promise with the details, tagged by `format`
+ */
+export async function getPakInfo(path: string): Promise<ContainerInfo> {
+  try {
+    return await invoke<ContainerInfo>("get_pak_info", { path });
+  } catch (error) {
+    console.error(`Failed to get info of ${path}:`, error);
+    throw new Error(`Failed to get info of ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets what is loaded (mounts, paks, assets, running scans) and what the process uses
+ * @returns Promise with preview cache stats, open pak handles, resident memory and uptime
+ */
+export async function getStatus(): Promise<StatusReport> {
+  try {
+    return await invoke<StatusReport>("get_status");
+  } catch (error) {
+    console.error("Failed to get status:", error);
+    throw new Error(`Failed to get status: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets memory use, open pak handles, in-memory structure sizes and recent operation timings
+ * @returns Promise with the diagnostics
+ */
+export async function getDiagnostics(): Promise<Diagnostics> {
+  try {
+    return await invoke<Diagnostics>("get_diagnostics");
+  } catch (error) {
+    console.error("Failed to get diagnostics:", error);
+    throw new Error(`Failed to get diagnostics: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Writes the diagnostics and recent log lines to a zip, to attach to a bug report
+ * @param path - Zip to write; it's replaced if it exists
+ * @returns Promise with the zip's size in bytes
+ */
+export async function exportDiagnostics(path: string): Promise<number> {
+  try {
+    return await invoke<number>("export_diagnostics", { path });
+  } catch (error) {
+    console.error(`Failed to export diagnostics to ${path}:`, error);
+    throw new Error(`Failed to export diagnostics to ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Changes what gets logged until the app exits
+ * @param level - A level ("debug") or filter directives ("info,pakseek=trace")
+ */
+export async function setLogLevel(level: string): Promise<void> {
+  try {
+    await invoke("set_log_level", { level });
+  } catch (error) {
+    console.error(`Failed to set the log level to ${level}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to set the log level to ${level}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets the end of the log file
+ * @param lines - How many lines, 200 by default
+ * @returns Promise with the lines, oldest first
+ */
+export async function getRecentLogs(lines?: number): Promise<string[]> {
+  try {
+    return await invoke<string[]>("get_recent_logs", { lines });
+  } catch (error) {
+    console.error('Failed to get the recent logs:', error);
+    throw new Error(`Failed to get the recent logs: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets one asset with its pak entry, preview cache status and direct dependencies/dependents
+ * @param path - Exact path of the asset inside its pak
+ * @param pakFile - Pak containing the asset, needed when the path exists in several paks
+ * @returns Promise with the asset details
+ */
+export async function getAssetDetails(path: string, pakFile?: string): Promise<AssetDetails> {
+  try {
+    return await invoke<AssetDetails>("get_asset_details", { path, pakFile });
+  } catch (error) {
+    console.error(`Failed to get details for ${path}:`, error);
+    throw new Error(`Failed to get details for ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets one asset by id with its pak entry, preview cache status and direct dependencies/dependents
+ * @param id - The asset's `id` from the listing
+ * @returns Promise with the asset details
+ */
+export async function getAssetDetailsById(id: string): Promise<AssetDetails> {
+  try {
+    return await invoke<AssetDetails>("get_asset_details", { id });
+  } catch (error) {
+    console.error(`Failed to get details for asset ${id}:`, error);
+    throw new Error(`Failed to get details for asset ${id}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Lists a folder of the loaded assets for the tree view, one level at a time by default
+ * @param root - Folder path like "Game/UI"; the top of the tree when omitted
+ * @param depth - Levels of subfolders and files to include (at most 8); 0 for totals only
+ * @returns Promise with the folder; every folder counts all assets beneath it
+ * @throws CommandError with code ASSET_NOT_FOUND when no asset is under `root`
+ */
+export async function getAssetTree(root?: string, depth = 1): Promise<TreeFolder> {
+  try {
+    return await invoke<TreeFolder>("get_asset_tree", { root, depth });
+  } catch (error) {
+    console.error(`Failed to list folder ${root ?? "/"}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to list folder ${root ?? "/"}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets totals and per-type, per-pak and per-compression breakdowns of the loaded assets
+ * @returns Promise with the statistics, including the 20 largest assets
+ */
+export async function getStatistics(): Promise<ScanStatistics> {
+  try {
+    return await invoke<ScanStatistics>("get_statistics");
+  } catch (error) {
+    console.error("Failed to get statistics:", error);
+    throw new Error(`Failed to get statistics: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Breaks raw and stored bytes down by asset type and extension, with compression ratios,
+ * duplicate content, size histograms and the entries that don't shrink
+ * @param folder - Install to scan and analyze instead of the loaded assets; the listing is left alone
+ * @returns Promise with the storage report
+ */
+export async function analyzeStorage(folder?: string): Promise<StorageReport> {
+  try {
+    return await invoke<StorageReport>("analyze_storage", { folder });
+  } catch (error) {
+    console.error("Failed to analyze storage:", error);
+    throw new Error(`Failed to analyze storage: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Lists the loaded assets by `pakchunkN` chunk, with the top-level content folders of each
+ * @returns Promise with the chunks in order, the assets of other paks last
+ */
+export async function getChunkSummary(): Promise<ChunkSummary[]> {
+  try {
+    return await invoke<ChunkSummary[]>("get_chunk_summary");
+  } catch (error) {
+    console.error("Failed to get the chunk summary:", error);
+    throw new Error(`Failed to get the chunk summary: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Reads the footer or TOC header of every .pak and .utoc in a folder, without scanning it,
+ * and says which are encrypted, which key each needs and whether a configured key works
+ * @param folder - Folder to audit, or a single .pak/.utoc
+ * @returns Promise with the containers and how many of them are locked
+ * @throws CommandError with `code` INVALID_REQUEST, NO_PAKS_FOUND, ...
+ */
+export async function auditEncryption(folder: string): Promise<EncryptionAudit> {
+  try {
+    return await invoke<EncryptionAudit>("audit_encryption", { folder });
+  } catch (error) {
+    console.error(`Failed to audit ${folder}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to audit ${folder}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets the cultures of the loaded .locres files with their string counts and the keys
+ * of the source culture each one lacks; unreadable files are listed in `errors`
+ * @param sourceCulture - Culture to compare with (default "en" when present, else the one with the most strings)
+ * @returns Promise with the localization summary
+ */
+export async function getLocalizationSummary(sourceCulture?: string): Promise<LocalizationSummary> {
+  try {
+    return await invoke<LocalizationSummary>("get_localization_summary", { sourceCulture });
+  } catch (error) {
+    console.error("Failed to get localization summary:", error);
+    throw new Error(`Failed to get localization summary: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets a page of a culture's strings, sorted by target, namespace and key
+ * @param culture - Culture folder of the .locres files ("en", "pt-BR")
+ * @param search - Matched against key and value, case-insensitively
+ * @param offset - Strings to skip
+ * @param limit - Page size (default 200, at most 5000)
+ * @returns Promise with the page of strings
+ */
+export async function getLocalizationEntries(
+  culture: string,
+  search?: string,
+  offset?: number,
+  limit?: number
+): Promise<CultureEntries> {
+  try {
+    return await invoke<CultureEntries>("get_localization_entries", { culture, search, offset, limit });
+  } catch (error) {
+    console.error("Failed to get localization entries:", error);
+    throw new Error(`Failed to get localization entries: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Writes the loaded assets to a file: name, path, type, sizes, compression, encryption and pak
+ * @param destination - File to write; it's replaced if it exists
+ * @param format - csv, json or markdown
+ * @param filters - Which assets, as in `listAssets`; all of them when omitted
+ * @param sort - Row order, as in `listAssets`
+ * @returns Promise with the rows and bytes written
+ */
+export async function exportAssetList(
+  destination: string,
+  format: AssetListFormat,
+  filters?: AssetListFilters,
+  sort?: AssetSort
+): Promise<AssetListExport> {
+  try {
+    return await invoke<AssetListExport>("export_asset_list", { destination, format, filter: filters, sort });
+  } catch (error) {
+    console.error(`Failed to export the asset list to ${destination}:`, error);
+    throw new Error(`Failed to export the asset list to ${destination}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Finds loaded assets with the same size and SHA-1
+ * @param minSize - Leave out assets smaller than this many bytes
+ * @param readBudget - Bytes that may be read to hash entries the index has no hash for (none by default)
+ * @param jobId - Id for `cancelJob` to stop the hashing; made up when omitted
+ * @returns Promise with the duplicate groups, most reclaimable bytes first
+ */
+export async function findDuplicates(minSize?: number, readBudget?: number, jobId?: string): Promise<DuplicateReport> {
+  try {
+    return await invoke<DuplicateReport>("find_duplicates", { minSize, readBudget, jobId });
+  } catch (error) {
+    console.error("Failed to find duplicates:", error);
+    throw new Error(`Failed to find duplicates: ${errorMessage(error)}`);
   }
 }
 
 /**
  * Gets preview data for a specific asset
- * @param assetName - Name of the asset to preview
+ * @param path - Exact path of the asset inside its pak
+ * @param pakFile - Pak containing the asset, needed when the path exists in several paks
+ * @param requestId - Id to pass to cancelPreview; echoed back in the response
+ * @param asFile - Write binary previews to a temp file and return its path (for convertFileSrc)
  * @returns Promise with preview response
  */
-export async function getPreview(assetName: string): Promise<PreviewResponse> {
+export async function getPreview(path: string, pakFile?: string, requestId?: string, asFile?: boolean): Promise<PreviewResponse> {
   try {
     const response = await invoke<PreviewResponse>("get_preview", {
-      assetName,
+      path,
+      pakFile,
+      requestId,
+      asFile,
     });
     return response;
   } catch (error) {
-    console.error(`Failed to get preview for ${assetName}:`, error);
-    throw new Error(`Failed to get preview for ${assetName}: ${error}`);
+    console.error(`Failed to get preview for ${path}:`, error);
+    throw new Error(`Failed to get preview for ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets preview data for the asset with this id
+ * @param id - The asset's `id` from the listing
+ * @param requestId - Id to pass to cancelPreview; echoed back in the response
+ * @param asFile - Write binary previews to a temp file and return its path (for convertFileSrc)
+ * @returns Promise with preview response
+ */
+export async function getPreviewById(id: string, requestId?: string, asFile?: boolean): Promise<PreviewResponse> {
+  try {
+    return await invoke<PreviewResponse>("get_preview", { id, requestId, asFile });
+  } catch (error) {
+    console.error(`Failed to get preview for asset ${id}:`, error);
+    throw new Error(`Failed to get preview for asset ${id}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets previews for many assets at once (e.g. a thumbnail grid)
+ * @param paths - Exact asset paths
+ * @param pakFile - Pak to look the paths up in, when paths exist in several paks
+ * @returns Promise with one result per path, each holding a preview or an error
+ */
+export async function getPreviews(paths: string[], pakFile?: string): Promise<BatchPreviewResult[]> {
+  try {
+    return await invoke<BatchPreviewResult[]>("get_previews", { paths, pakFile });
+  } catch (error) {
+    console.error("Failed to get previews:", error);
+    throw new Error(`Failed to get previews: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Cancels an in-flight preview
+ * @param requestId - Id that was passed to getPreview
+ * @returns Promise resolving to false if the preview had already finished
+ */
+export async function cancelPreview(requestId: string): Promise<boolean> {
+  try {
+    return await invoke<boolean>("cancel_preview", { requestId });
+  } catch (error) {
+    console.error(`Failed to cancel preview ${requestId}:`, error);
+    throw new Error(`Failed to cancel preview ${requestId}: ${errorMessage(error)}`);
   }
 }
 
 /**
  * Gets dependency information for an asset or all dependencies
  * @param assetName - Optional asset name to get dependencies for. If not provided, returns all dependencies
+ * @param pakFile - Pak whose AssetRegistry.bin should supply the graph (keys are package names)
+ * @param kind - Keep only hard or soft references
+ * @param filter - Cut the graph down to a subgraph small enough to draw
+ * @param direction - With an asset: what it uses ("forward") or what uses it ("reverse")
+ * @param depth - Levels of the asset's tree to include (default 5)
  * @returns Promise with dependency response
  */
 export async function getDependencies(
-  assetName?: string
+  assetName?: string,
+  pakFile?: string,
+  kind?: DependencyKind,
+  filter?: SubgraphFilter,
+  direction?: TreeDirection,
+  depth?: number
 ): Promise<DependencyResponse> {
   try {
     const response = await invoke<DependencyResponse>("get_dependencies", {
       assetName,
+      pakFile,
+      kind,
+      filter,
+      direction,
+      depth,
     });
     return response;
   } catch (error) {
     console.error("Failed to get dependencies:", error);
-    throw new Error(`Failed to get dependencies: ${error}`);
+    throw new Error(`Failed to get dependencies: ${errorMessage(error)}`);
   }
 }
 
+/**
+ * Orders assets so each comes after everything it depends on
+ * @param pakFile - Pak whose AssetRegistry.bin should supply the graph
+ * @param kind - Order by hard or soft references only
+ * @returns Promise with the build order, or the cycles that prevent one
+ */
+export async function getBuildOrder(
+  pakFile?: string,
+  kind?: DependencyKind
+): Promise<BuildOrderResponse> {
+  try {
+    return await invoke<BuildOrderResponse>("get_build_order", { pakFile, kind });
+  } catch (error) {
+    console.error("Failed to get build order:", error);
+    throw new Error(`Failed to get build order: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Lists everything that directly or transitively depends on an asset
+ * @param assetName - Asset that would be removed
+ * @param pakFile - Pak whose AssetRegistry.bin should supply the graph
+ * @returns Promise with the dependents grouped by depth
+ */
+export async function analyzeRemovalImpact(
+  assetName: string,
+  pakFile?: string
+): Promise<RemovalImpact> {
+  try {
+    return await invoke<RemovalImpact>("analyze_removal_impact", { assetName, pakFile });
+  } catch (error) {
+    console.error("Failed to analyze removal impact:", error);
+    throw new Error(`Failed to analyze removal impact: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Summarizes a pak's dependency graph, including the assets with the largest footprints
+ * @param pakFile - Pak whose AssetRegistry.bin and entry sizes should be used
+ * @returns Promise with the dependency statistics
+ */
+export async function getDependencyStatistics(pakFile?: string): Promise<DependencyStatistics> {
+  try {
+    return await invoke<DependencyStatistics>("get_dependency_statistics", { pakFile });
+  } catch (error) {
+    console.error("Failed to get dependency statistics:", error);
+    throw new Error(`Failed to get dependency statistics: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Exports the dependency graph to disk
+ * @param format - Export format; graphml and cytoscape carry node sizes and types
+ * @param pakFile - Pak whose AssetRegistry.bin and entries should supply the graph
+ * @param outputPath - Where to write the export; defaults to "dependencies.<format>"
+ * @param options - Root asset and limits of a mermaid chart
+ * @returns Promise with the path that was written
+ */
+export async function exportDependencies(
+  format: DependencyExportFormat,
+  pakFile?: string,
+  outputPath?: string,
+  options?: DependencyExportOptions
+): Promise<string> {
+  try {
+    return await invoke<string>("export_dependencies", { format, pakFile, outputPath, options });
+  } catch (error) {
+    console.error("Failed to export dependencies:", error);
+    throw new Error(`Failed to export dependencies: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Merges a dependency file (.csv, .json, .yaml) into the loaded dependency map
+ * @param path - File to import
+ * @returns Promise with how many edges were new and how many were already known
+ */
+export async function importDependencies(path: string): Promise<MergeSummary> {
+  try {
+    return await invoke<MergeSummary>("import_dependencies", { path });
+  } catch (error) {
+    console.error("Failed to import dependencies:", error);
+    throw new Error(`Failed to import dependencies: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Compares two builds of a game
+ * @param folderA - Older build: a .pak file or a folder of paks
+ * @param folderB - Newer build: a .pak file or a folder of paks
+ * @returns Promise with the file-level and dependency-level differences
+ */
+export async function compareVersions(folderA: string, folderB: string): Promise<VersionComparison> {
+  try {
+    return await invoke<VersionComparison>("compare_versions", { folderA, folderB });
+  } catch (error) {
+    console.error("Failed to compare versions:", error);
+    throw new Error(`Failed to compare versions: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Finds the packages of a pak that no map or primary asset label needs
+ * @param pakFile - Pak to check
+ * @param roots - Packages to treat as used; defaults to maps and primary asset labels
+ * @returns Promise with the unreachable packages and the bytes they take up
+ */
+export async function findUnusedAssets(pakFile: string, roots?: string[]): Promise<UnreachableReport> {
+  try {
+    return await invoke<UnreachableReport>("find_unused_assets", { pakFile, roots });
+  } catch (error) {
+    console.error("Failed to find unused assets:", error);
+    throw new Error(`Failed to find unused assets: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Checks the dependency graph against the scanned assets
+ * @param pakFile - Pak whose graph to check; defaults to the graph of the last scan
+ * @returns Promise with cycles, empty lists and references to missing assets
+ */
+export async function validateDependencies(pakFile?: string): Promise<ValidationIssue[]> {
+  try {
+    return await invoke<ValidationIssue[]>("validate_dependencies", { pakFile });
+  } catch (error) {
+    console.error("Failed to validate dependencies:", error);
+    throw new Error(`Failed to validate dependencies: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Exports a DataTable or CurveTable to disk
+ * @param assetPath - Path of the table inside the pak (or a loose .uasset when no pak is given)
+ * @param format - "csv" or "json"
+ * @param pakFile - Pak file containing the asset
+ * @param outputPath - Where to write the export; defaults to the asset name
+ * @returns Promise with the path that was written
+ */
+export async function exportDatatable(
+  assetPath: string,
+  format: 'csv' | 'json',
+  pakFile?: string,
+  outputPath?: string
+): Promise<string> {
+  try {
+    return await invoke<string>("export_datatable", {
+      assetPath,
+      format,
+      pakFile,
+      outputPath,
+    });
+  } catch (error) {
+    console.error(`Failed to export ${assetPath}:`, error);
+    throw new Error(`Failed to export ${assetPath}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets preview cache usage (entries, bytes, budget and hit rate since startup)
+ * @returns Promise with cache statistics
+ */
+export async function getPreviewCacheStats(): Promise<PreviewCacheStats> {
+  try {
+    return await invoke<PreviewCacheStats>("get_preview_cache_stats");
+  } catch (error) {
+    console.error("Failed to get preview cache stats:", error);
+    throw new Error(`Failed to get preview cache stats: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Deletes all cached previews
+ * @returns Promise with the number of previews removed
+ */
+export async function clearPreviewCache(): Promise<number> {
+  try {
+    return await invoke<number>("clear_preview_cache");
+  } catch (error) {
+    console.error("Failed to clear preview cache:", error);
+    throw new Error(`Failed to clear preview cache: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Deletes the saved asset indexes and dependency maps, so the next scan rebuilds them
+ * @returns Promise with the number of cache files removed
+ */
+export async function clearIndexCache(): Promise<number> {
+  try {
+    return await invoke<number>("clear_index_cache");
+  } catch (error) {
+    console.error("Failed to clear index cache:", error);
+    throw new Error(`Failed to clear index cache: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Sets the preview cache size budget
+ * @param maxSizeMb - Budget in megabytes; older previews are evicted beyond it
+ */
+export async function setPreviewCacheBudget(maxSizeMb: number): Promise<void> {
+  try {
+    await invoke("set_preview_cache_budget", { maxSizeMb });
+  } catch (error) {
+    console.error("Failed to set preview cache budget:", error);
+    throw new Error(`Failed to set preview cache budget: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Keeps a mount up to date as its paks are written, replaced or deleted
+ * @param path - The mount's path, as added
+ * @returns Promise with the mount, now `watched`
+ * @throws CommandError with `code` NOT_MOUNTED or WATCH_FAILED
+ */
+export async function watchFolder(path: string): Promise<Mount> {
+  try {
+    return await invoke<Mount>("watch_folder", { path });
+  } catch (error) {
+    console.error(`Failed to watch ${path}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to watch ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Stops keeping a mount up to date; its assets stay listed
+ * @param path - The mount's path, as added
+ * @returns Promise with the mount
+ * @throws CommandError with `code` NOT_MOUNTED
+ */
+export async function unwatchFolder(path: string): Promise<Mount> {
+  try {
+    return await invoke<Mount>("unwatch_folder", { path });
+  } catch (error) {
+    console.error(`Failed to stop watching ${path}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to stop watching ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Opens folders, .pak and .utoc files as mounts and scans them, as when they're dropped onto the window
+ * @param paths - Paths to open; the ones that can't be opened get a warning instead of failing the rest
+ * @returns Promise with what became of each path; scans report through `onScanProgress` and `onScanDone`
+ */
+export async function openDroppedPaths(paths: string[]): Promise<DroppedPath[]> {
+  try {
+    return await invoke<DroppedPath[]>("open_dropped_paths", { paths });
+  } catch (error) {
+    console.error("Failed to open dropped paths:", error);
+    throw new Error(`Failed to open dropped paths: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets the settings
+ * @param revealKeys - Send AES keys as they are instead of "<redacted>"
+ * @returns Promise with the settings
+ */
+export async function getSettings(revealKeys = false): Promise<Settings> {
+  try {
+    return await invoke<Settings>("get_settings", { revealKeys });
+  } catch (error) {
+    console.error("Failed to get settings:", error);
+    throw new Error(`Failed to get settings: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Changes and saves settings; they apply without restarting
+ * @param patch - Fields to change
+ * @param revealKeys - Send AES keys back as they are instead of "<redacted>"
+ * @returns Promise with the settings after the change
+ * @throws CommandError with code INVALID_REQUEST (`context.field`) or SETTINGS_WRITE_FAILED
+ */
+export async function updateSettings(patch: SettingsPatch, revealKeys = false): Promise<Settings> {
+  try {
+    return await invoke<Settings>("update_settings", { patch, revealKeys });
+  } catch (error) {
+    console.error("Failed to update settings:", error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to update settings: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Sets the AES key of one container, of a key GUID, or the default key; keys are looked up
+ * in that order
+ * @param pakPathOrGuid - .pak, .utoc or .ucas path, key GUID, or "*"
+ * @param key - Hex or base64 key; null removes it
+ * @returns Promise with the keys as `listPakKeys` returns them
+ * @throws CommandError with code INVALID_REQUEST (`context.field`) or SETTINGS_WRITE_FAILED
+ */
+export async function setPakKey(pakPathOrGuid: string, key: string | null): Promise<KeyAssignment[]> {
+  try {
+    return await invoke<KeyAssignment[]>("set_pak_key", { pakPathOrGuid, key });
+  } catch (error) {
+    console.error(`Failed to set the key for ${pakPathOrGuid}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to set the key for ${pakPathOrGuid}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Sets the .usmap mappings file that unversioned properties of cooked UE5 packages are read with
+ * @param path - .usmap file; null or empty forgets it
+ * @returns Promise with what the file holds, or null once forgotten
+ * @throws CommandError with code INVALID_REQUEST when the file can't be read or parsed, or SETTINGS_WRITE_FAILED
+ */
+export async function setMappingsFile(path: string | null): Promise<MappingsInfo | null> {
+  try {
+    return await invoke<MappingsInfo | null>("set_mappings_file", { path });
+  } catch (error) {
+    console.error("Failed to set the mappings file:", error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to set the mappings file: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets what the configured .usmap mappings file holds
+ * @returns Promise with the mappings, or null when none is set
+ */
+export async function getMappingsInfo(): Promise<MappingsInfo | null> {
+  try {
+    return await invoke<MappingsInfo | null>("get_mappings_info");
+  } catch (error) {
+    console.error("Failed to get the mappings file:", error);
+    throw new Error(`Failed to get the mappings file: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Lists the game profiles and which one is active
+ * @param revealKeys - Send keys as they are instead of "<redacted>"
+ * @returns Promise with the profiles by name
+ */
+export async function listProfiles(revealKeys = false): Promise<ProfileList> {
+  try {
+    return await invoke<ProfileList>("list_profiles", { revealKeys });
+  } catch (error) {
+    console.error("Failed to list profiles:", error);
+    throw new Error(`Failed to list profiles: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Saves a game profile
+ * @param name - Name of the new profile
+ * @param profile - Its keys, files and folders; without it, the ones in use and the paths mounted now
+ * @returns Promise with the profiles, keys redacted
+ * @throws CommandError with code PROFILE_EXISTS, INVALID_REQUEST (with the `field` at fault) or SETTINGS_WRITE_FAILED
+ */
+export async function createProfile(name: string, profile?: ProfileSpec): Promise<ProfileList> {
+  try {
+    return await invoke<ProfileList>("create_profile", { name, profile });
+  } catch (error) {
+    console.error(`Failed to create profile ${name}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to create profile ${name}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Switches to a game profile: its keys, Oodle library and mappings apply from the next extraction or preview
+ * @param name - Profile to activate
+ * @param rescan - Replace the mounts with the profile's folders; their scans send `progress` events
+ * @returns Promise with the profile and the mounts started for it
+ * @throws CommandError with code PROFILE_NOT_FOUND or SETTINGS_WRITE_FAILED
+ */
+export async function activateProfile(name: string, rescan = false): Promise<ProfileActivation> {
+  try {
+    return await invoke<ProfileActivation>("activate_profile", { name, rescan });
+  } catch (error) {
+    console.error(`Failed to activate profile ${name}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to activate profile ${name}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Lists the configured AES keys: per container, then per GUID, then the default
+ * @param revealKeys - Send keys as they are instead of "<redacted>"
+ * @returns Promise with the key assignments
+ */
+export async function listPakKeys(revealKeys = false): Promise<KeyAssignment[]> {
+  try {
+    return await invoke<KeyAssignment[]>("list_pak_keys", { revealKeys });
+  } catch (error) {
+    console.error("Failed to list keys:", error);
+    throw new Error(`Failed to list keys: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Checks whether a key decrypts a container's index, without saving it
+ * @param pakPath - .pak, .utoc or .ucas to check against
+ * @param key - Hex or base64 key
+ * @returns Promise with `valid` and the GUID to save the key under
+ * @throws CommandError with code INVALID_REQUEST, PAK_NOT_FOUND or PAK_READ_FAILED (not encrypted, unreadable)
+ */
+export async function testKey(pakPath: string, key: string): Promise<KeyTest> {
+  try {
+    return await invoke<KeyTest>("test_key", { pakPath, key });
+  } catch (error) {
+    console.error(`Failed to test the key for ${pakPath}:`, error);
+    const apiError = parseCommandError(error);
+    if (apiError) throw new CommandError(apiError);
+    throw new Error(`Failed to test the key for ${pakPath}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Gets the folders recently scanned with `startScan` and the favorite ones
+ * @returns Promise with both lists; missing folders have `exists: false`
+ */
+export async function getRecentFolders(): Promise<RecentFolders> {
+  try {
+    return await invoke<RecentFolders>("get_recent_folders");
+  } catch (error) {
+    console.error("Failed to get recent folders:", error);
+    throw new Error(`Failed to get recent folders: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Adds a folder to the favorites
+ * @param path - Folder to pin
+ * @returns Promise with the updated folders
+ */
+export async function pinFavorite(path: string): Promise<RecentFolders> {
+  try {
+    return await invoke<RecentFolders>("pin_favorite", { path });
+  } catch (error) {
+    console.error(`Failed to pin ${path}:`, error);
+    throw new Error(`Failed to pin ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Removes a folder from the favorites
+ * @param path - Folder to unpin
+ * @returns Promise with the updated folders
+ */
+export async function unpinFavorite(path: string): Promise<RecentFolders> {
+  try {
+    return await invoke<RecentFolders>("unpin_favorite", { path });
+  } catch (error) {
+    console.error(`Failed to unpin ${path}:`, error);
+    throw new Error(`Failed to unpin ${path}: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Subscribes to progress of `startScan` jobs, one event per pak
+ * @param handler - Called with each event
+ * @returns Promise with a function that unsubscribes
+ */
+export async function onScanProgress(handler: (event: ScanProgressEvent) => void): Promise<UnlistenFn> {
+  return listen<ScanProgressEvent>("scan://progress", (event) => handler(event.payload));
+}
+
+/**
+ * Subscribes to the end of `startScan` jobs, completed or cancelled
+ * @param handler - Called with the job's final progress
+ * @returns Promise with a function that unsubscribes
+ */
+export async function onScanDone(handler: (summary: ScanProgress) => void): Promise<UnlistenFn> {
+  return listen<ScanProgress>("scan://done", (event) => handler(event.payload));
+}
+
+/**
+ * Subscribes to rescans of watched mounts (`watchFolder`)
+ * @param handler - Called with the mount and the paks that changed
+ * @returns Promise with a function that unsubscribes
+ */
+export async function onAssetsChanged(handler: (event: AssetsChangedEvent) => void): Promise<UnlistenFn> {
+  return listen<AssetsChangedEvent>("assets://changed", (event) => handler(event.payload));
+}
+
+/**
+ * Subscribes to files and folders dropped onto the window, which the backend opens on its own
+ * @param handler - Called with what became of each dropped path
+ * @returns Promise with a function that unsubscribes
+ */
+export async function onPathsDropped(handler: (paths: DroppedPath[]) => void): Promise<UnlistenFn> {
+  return listen<DroppedPath[]>("drop://opened", (event) => handler(event.payload));
+}
+
+/**
+ * Subscribes to progress of `extractAssets` runs, one event per entry
+ * @param handler - Called with each event
+ * @returns Promise with a function that unsubscribes
+ */
+export async function onExtractProgress(handler: (event: ExtractProgressEvent) => void): Promise<UnlistenFn> {
+  return listen<ExtractProgressEvent>("extract://progress", (event) => handler(event.payload));
+}
+
+/**
+ * Subscribes to progress of long-running commands such as `addMount`
+ * @param handler - Called with each event
+ * @returns Promise with a function that unsubscribes
+ */
+export async function onProgress(handler: (event: ProgressEvent) => void): Promise<UnlistenFn> {
+  return listen<ProgressEvent>("progress", (event) => handler(event.payload));
+}
+
+/**
+ * Gets the bearer token for the HTTP API, sent as `Authorization: Bearer <token>`
+ * (or `?token=` when opening the `/ws` socket)
+ * @returns Promise with the token
+ */
+export async function getApiToken(): Promise<string> {
+  try {
+    return await invoke<string>("get_api_token");
+  } catch (error) {
+    console.error("Failed to get API token:", error);
+    throw new Error(`Failed to get API token: ${errorMessage(error)}`);
+  }
+}
+
+/**
+ * Subscribes to the HTTP API server coming up, which may not be on its preferred port
+ * @param handler - Called with the address the server listens on
+ * @returns Promise with a function that unsubscribes
+ */
+export async function onApiServerReady(handler: (info: ApiServerInfo) => void): Promise<UnlistenFn> {
+  return listen<ApiServerInfo>("api-server-ready", (event) => handler(event.payload));
+}
+
 /**
  * Gets application information
  * @returns Promise with app info
@@ -86,7 +1275,7 @@ export async function getAppInfo(): Promise<AppInfo> {
     return response;
   } catch (error) {
     console.error("Failed to get app info:", error);
-    throw new Error(`Failed to get app info: ${error}`);
+    throw new Error(`Failed to get app info: ${errorMessage(error)}`);
   }
 }
 
@@ -124,6 +1313,7 @@ export function convertAssetToFrontend(backendAsset: BackendAsset): Asset {
     type: mapAssetType(backendAsset.asset_type),
     size: backendAsset.size,
     path: backendAsset.path,
+    pakFile: backendAsset.pak_file ?? undefined,
     thumbnail: extractThumbnail(backendAsset),
     dependencies: [], // Will be populated from dependency map
   };
@@ -147,6 +1337,7 @@ function mapAssetType(backendType: string): Asset['type'] {
     case 'skeletal_mesh':
       return 'Mesh';
     case 'material':
+    case 'material_instance':
       return 'Material';
     case 'blueprint':
       return 'Blueprint';
@@ -180,7 +1371,7 @@ function extractThumbnail(asset: BackendAsset): string | undefined {
  */
 export function handleApiError(error: unknown, operation: string): string {
   if (typeof error === 'string') {
-    return `${operation} failed: ${error}`;
+    return `${operation} failed: ${errorMessage(error)}`;
   }
   
   if (error instanceof Error) {
@@ -216,6 +1407,7 @@ export function getAssetTypeColor(assetType: string): string {
     case 'skeletal_mesh':
       return 'text-blue-400';
     case 'material':
+    case 'material_instance':
       return 'text-orange-400';
     case 'blueprint':
       return 'text-red-400';
@@ -28,7 +28,7 @@ export default function PreviewPanel({ asset }: PreviewPanelProps) {
       setLoading(true);
       setError(null);
       try {
-        const preview = await getPreview(asset.name);
+        const preview = await getPreview(asset.path, asset.pakFile);
         setPreviewData(preview);
       } catch (err) {
         setError(err instanceof Error ? err.message : 'Failed to load preview');
@@ -27,19 +27,13 @@ export default function AssetBrowser() {
     loadAssets();
   }, [searchTerm]); // Load when search term changes
 
+  // Lists what the last scan loaded; scanning happens on the Home page
   const loadAssets = async () => {
-    const savedFolder = localStorage.getItem('ue-mapper-target-folder');
-    if (!savedFolder) {
-      setError('No target folder configured. Please set one in Settings.');
-      return;
-    }
-
     setLoading(true);
     setError(null);
     
     try {
       const result = await invoke<AssetsResponse>('list_assets', {
-        target_folder: savedFolder,
         asset_type: null, // No type filter for now
         search: searchTerm || null,
       });
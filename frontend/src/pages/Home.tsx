@@ -2,6 +2,8 @@ import { useState, useEffect } from 'react';
 import { FolderOpen, Info, RefreshCw, AlertCircle, Package, Database, FileText } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 import { useTheme } from '../hooks/useTheme';
+import { startScan, cancelScan, onScanProgress, onScanDone, listAssets } from '../api/tauriClient';
+import type { ScanProgress, ScanProgressEvent } from '../types';
 
 interface AssetsResponse {
   assets: any[];
@@ -18,6 +20,8 @@ export default function Home() {
   });
   const [scanResults, setScanResults] = useState<AssetsResponse | null>(null);
   const [isLoading, setIsLoading] = useState<boolean>(false);
+  const [scanJobId, setScanJobId] = useState<string | null>(null);
+  const [scanProgress, setScanProgress] = useState<ScanProgressEvent | null>(null);
   const [message, setMessage] = useState<{ type: 'success' | 'error' | 'info', text: string } | null>(null);
 
   const testConnection = async () => {
@@ -54,28 +58,60 @@ export default function Home() {
 
     console.log('=== FRONTEND DEBUG: Starting scan for folder:', folder);
 
+    // Listen before starting, so a scan that finishes at once isn't missed
+    let jobId: string | null = null;
+    const finished = new Map<string, ScanProgress>();
+    let resolveDone: (summary: ScanProgress) => void = () => {};
+    const done = new Promise<ScanProgress>((resolve) => { resolveDone = resolve; });
+    const unlistenDone = await onScanDone((summary) => {
+      finished.set(summary.job_id, summary);
+      if (summary.job_id === jobId) resolveDone(summary);
+    });
+    const unlistenProgress = await onScanProgress((event) => {
+      if (event.job_id === jobId) setScanProgress(event);
+    });
+
     try {
       setIsLoading(true);
-      console.log('=== FRONTEND DEBUG: About to invoke list_assets command...');
-      
-      const result = await invoke<AssetsResponse>('list_assets', {
-        target_folder: folder,
-      });
-      
+      jobId = await startScan(folder);
+      setScanJobId(jobId);
+      const early = finished.get(jobId);
+      if (early) resolveDone(early);
+
+      const summary = await done;
+      if (summary.status === 'cancelled') {
+        showMessage('info', 'Scan cancelled');
+        return;
+      }
+      const result = await listAssets();
       console.log('=== FRONTEND DEBUG: Received result:', result);
       setScanResults(result);
       if (result.total > 0) {
-        showMessage('success', `Found ${result.total} assets in ${result.assets.length} .pak files`);
+        showMessage('success', `Found ${result.total} assets in ${summary.paks_total} .pak files`);
         localStorage.setItem('ue-mapper-target-folder', folder);
       } else {
-        showMessage('error', 'No .pak files found in the selected directory');
+        showMessage('error', 'No assets found in the selected .pak files');
       }
     } catch (error) {
-      console.error('=== FRONTEND DEBUG: Error during invoke:', error);
+      console.error('=== FRONTEND DEBUG: Error during scan:', error);
       showMessage('error', 'Failed to scan directory. Make sure it contains .pak files and the path is correct.');
       setScanResults(null);
     } finally {
+      unlistenDone();
+      unlistenProgress();
       setIsLoading(false);
+      setScanJobId(null);
+      setScanProgress(null);
+    }
+  };
+
+  const handleCancelScan = async () => {
+    if (!scanJobId) return;
+    try {
+      await cancelScan(scanJobId);
+    } catch (error) {
+      // The scan finished meanwhile
+      console.error('=== FRONTEND DEBUG: Cancel failed:', error);
     }
   };
 
@@ -203,8 +239,24 @@ export default function Home() {
                   }}
                 >
                   <RefreshCw size={20} className={isLoading ? 'animate-spin' : ''} />
-                  <span>{isLoading ? 'Scanning...' : targetFolder.endsWith('.pak') ? 'Analyze .pak File' : 'Scan Directory'}</span>
+                  <span>
+                    {isLoading
+                      ? scanProgress
+                        ? `Scanning... ${scanProgress.paks_done}/${scanProgress.paks_total}`
+                        : 'Scanning...'
+                      : targetFolder.endsWith('.pak') ? 'Analyze .pak File' : 'Scan Directory'}
+                  </span>
                 </button>
+
+                {isLoading && scanJobId && (
+                  <button
+                    onClick={handleCancelScan}
+                    className="px-6 py-3 text-white font-medium rounded-lg border border-gray-500 hover:border-gray-400 transition-colors flex items-center space-x-2"
+                    style={{ backgroundColor: `${themeColors.surface}80` }}
+                  >
+                    <span>Cancel</span>
+                  </button>
+                )}
                 
                 <button
                   onClick={testConnection}
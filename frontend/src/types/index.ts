@@ -4,6 +4,7 @@ export interface Asset {
   type: 'Texture' | 'Audio' | 'Mesh' | 'Material' | 'Blueprint' | 'Animation';
   size: number;
   path: string;
+  pakFile?: string;
   thumbnail?: string;
   dependencies?: string[];
 }
@@ -40,28 +41,156 @@ export interface DependencyNode {
 // BACKEND TYPES - Keep in sync with Rust structs
 // ============================================================================
 
+/**
+ * Asset kind as a snake_case id; kinds without a variant of their own come through as
+ * other snake_case ids ("curve_table", "localization", "config", ...)
+ */
+export type AssetType =
+  | 'texture'
+  | 'static_mesh'
+  | 'skeletal_mesh'
+  | 'material'
+  | 'material_instance'
+  | 'blueprint'
+  | 'sound'
+  | 'animation'
+  | 'map'
+  | 'data_table'
+  | 'font'
+  | 'particle'
+  | (string & {});
+
 export interface BackendAsset {
+  /** Stable id from the pak and entry path; unique within a scan */
+  id: string;
+  /** Display name: the file stem without its naming-convention prefix, in title case */
   name: string;
-  asset_type: string;
+  /** The file name as stored, extension included ("SM_Rock.uasset") */
+  file_name: string;
+  asset_type: AssetType;
   size: number;
   path: string;
   last_modified: string; // ISO datetime string
   metadata?: Record<string, any>;
+  pak_file?: string | null;
+  /** SHA-1 from the pak index, as 40 lowercase hex digits */
+  hash?: string | null;
+  type_confidence?: 'class' | 'heuristic';
+  /**
+   * Entries of a package split into .uasset/.umap, .uexp, .ubulk and .uptnl, the header
+   * first; `size` is their sum. Absent for files that aren't packages.
+   */
+  parts?: AssetPart[];
+  /** Chunk of the asset's pak when it's named `pakchunkN-Platform` */
+  chunk_id?: number;
+  /**
+   * Asset registry tags of the package; listings carry only `Settings.listed_tags`,
+   * asset details all of them. Absent when the registry doesn't list the asset.
+   */
+  tags?: Record<string, string>;
+}
+
+/** One entry of a package split over several files */
+export interface AssetPart {
+  /** As stored, without the dot ("uexp") */
+  extension: string;
+  size: number;
+  compressed_size: number | null;
 }
 
 export interface AssetsResponse {
   assets: BackendAsset[];
   total: number;
   filtered: number;
+  /** Position of the first returned asset among the filtered ones */
+  offset: number;
+  limit: number;
+  /** Fuzzy match score of each returned asset, higher is closer (fuzzy search only) */
+  scores?: number[];
+  /** Counts by type, pak and extension for the sidebar's filters */
+  facets: AssetFacets;
+}
+
+/**
+ * `[key, count]` pairs, biggest first; each dimension ignores its own filter, so the counts
+ * say what picking another type (pak, extension, chunk) would list
+ */
+export interface AssetFacets {
+  by_type: [string, number][];
+  /** Keyed by the pak's file name */
+  by_pak: [string, number][];
+  /** Keyed by lowercase extension without the dot */
+  by_extension: [string, number][];
+  /** Keyed by chunk number; assets of paks outside the `pakchunkN` naming aren't counted */
+  by_chunk: [string, number][];
+}
+
+export type CompressionMethod = 'None' | 'Zlib' | 'Gzip' | 'LZ4' | 'Oodle' | { Unknown: number };
+
+/** Where and how an asset is stored in its pak */
+export interface EntryDetails {
+  pak_file: string;
+  offset: number;
+  compressed_size: number;
+  uncompressed_size: number;
+  compression_method: CompressionMethod;
+  /** Compressed size over uncompressed size (1.0 when stored uncompressed) */
+  compression_ratio: number;
+  is_encrypted: boolean;
 }
 
+/** One asset with what the listing leaves out */
+export interface AssetDetails {
+  asset: BackendAsset;
+  /** Missing when the pak can't be read */
+  entry: EntryDetails | null;
+  /** Whether a preview with default options is cached */
+  preview_cached: boolean;
+  dependencies: string[];
+  dependents: string[];
+}
+
+/** How a search term matches: case-insensitive substring, regex, or skim-style fuzzy */
+export type SearchMode = 'substring' | 'regex' | 'fuzzy';
+
+/** Filters of an asset listing; an asset has to pass every one given */
+export interface AssetListFilters {
+  type?: string;
+  /** Comma-separated types, any of which matches */
+  types?: string;
+  /** Name and path; `tag:Key=Value` words match asset registry tags instead */
+  search?: string;
+  /** Fuzzy results come back best match first, with `scores` */
+  search_mode?: SearchMode;
+  /** Uncompressed size in bytes, inclusive */
+  min_size?: number;
+  max_size?: number;
+  /** 'None', 'Zlib', 'Oodle', ... (case-insensitive) */
+  compression?: string;
+  encrypted?: boolean;
+  /** Full path or file name of the pak */
+  pak?: string;
+  /** With or without the dot */
+  extension?: string;
+  /** Chunk of the asset's pak (3 for `pakchunk3-Windows.pak`) */
+  chunk?: number;
+}
+
+/** Field to order assets by, with `:desc` for descending */
+export type AssetSort = 'name' | 'size' | 'type' | 'path' | 'name:desc' | 'size:desc' | 'type:desc' | 'path:desc';
+
 export interface PreviewData {
-  format: 'base64' | 'json' | 'text' | 'url';
-  content: string | Record<string, any>;
+  format: 'base64' | 'json' | 'text' | 'url' | 'file';
+  content?: string | Record<string, any>;
+  // 'url': link to GET /preview/raw, carrying the API token when the server has one
+  url?: string;
+  // 'file': temp file path for the asset protocol
+  path?: string;
+  mime_type?: string;
 }
 
 export interface PreviewType {
-  type: 'image' | 'audio' | 'text' | 'model' | 'unsupported';
+  type: 'image' | 'audio' | 'text' | 'model' | 'skeletal' | 'material' | 'blueprint' | 'data_table' | 'localization' | 'level' | 'font' | 'animation' | 'particle_system' | 'unsupported';
   // Additional properties based on type
   format?: string;
   width?: number;
@@ -73,23 +202,967 @@ export interface PreviewType {
   vertices?: number;
   triangles?: number;
   materials?: string[];
+  bounding_box?: { min: [number, number, number]; max: [number, number, number] };
+  bones?: number;
+  lods?: { vertices: number | null; triangles: number | null; sections: number | null }[];
+  parent?: string | null;
+  parameters?: number;
+  textures?: number;
+  parent_class?: string | null;
+  components?: number;
+  interfaces?: number;
+  row_struct?: string | null;
+  rows?: number;
+  entries?: number;
+  namespaces?: number;
+  actors?: number;
+  classes?: number;
+  family?: string | null;
+  style?: string | null;
+  frames?: number;
+  frame_rate?: number;
+  skeleton?: string | null;
+  emitters?: number;
+  user_parameters?: number;
   reason?: string;
 }
 
+export interface BatchPreviewResult {
+  path: string;
+  preview: PreviewResponse | null;
+  error: string | null;
+}
+
+/** Assets sharing a type, pak or compression method */
+export interface GroupStatistics {
+  /** null for assets without one (no pak, unknown compression) */
+  key: string | null;
+  count: number;
+  size: number;
+  compressed_size: number;
+}
+
+/** A folder of the loaded assets, from `getAssetTree` */
+export interface TreeFolder {
+  /** Empty for the root */
+  name: string;
+  /** Folder names from the root joined with "/"; empty for the root */
+  path: string;
+  /** Assets anywhere beneath */
+  asset_count: number;
+  /** Uncompressed bytes of the assets anywhere beneath */
+  total_size: number;
+  /** Subfolders directly inside, whether or not they're listed */
+  folder_count: number;
+  /** Files directly inside, whether or not they're listed */
+  file_count: number;
+  /** By name; absent below the requested depth */
+  folders?: TreeFolder[];
+  /** By name; absent below the requested depth */
+  files?: BackendAsset[];
+}
+
+/** Overview of the loaded assets for the dashboard */
+export interface ScanStatistics {
+  total_assets: number;
+  /** Uncompressed bytes */
+  total_size: number;
+  /** Stored bytes, for assets whose compressed size is known */
+  total_compressed_size: number;
+  encrypted_assets: number;
+  /** Largest total size first */
+  by_type: GroupStatistics[];
+  /** Largest total size first */
+  by_pak: GroupStatistics[];
+  /** Most assets first */
+  by_compression: GroupStatistics[];
+  /** The 20 largest assets, largest first */
+  largest: Asset[];
+}
+
+/** Entries of a size range; `max` is exclusive and null for the last bucket */
+export interface SizeBucket {
+  min: number;
+  max: number | null;
+  count: number;
+  size: number;
+}
+
+/** A pixel (or other) format of a type's assets with its share of the type's raw bytes */
+export interface FormatShare {
+  format: string;
+  count: number;
+  size: number;
+  /** 0 to 1 */
+  share: number;
+}
+
+/** Sizes of the assets sharing a type or extension, or of all of them */
+export interface StorageGroup {
+  /** Empty for the total */
+  key: string;
+  count: number;
+  /** Raw bytes */
+  size: number;
+  /** Stored bytes, for assets whose compressed size is known */
+  compressed_size: number;
+  /** Stored over raw bytes; null when no compressed size is known */
+  ratio: number | null;
+  /** Stored bytes of copies beyond the first of the same content */
+  duplicate_bytes: number;
+  histogram: SizeBucket[];
+  /** Largest first; empty unless a deep scan read formats */
+  formats: FormatShare[];
+}
+
+/** An entry marked compressed that is stored at (nearly) its raw size */
+export interface IncompressibleEntry {
+  path: string;
+  pak_file: string | null;
+  asset_type: string;
+  compression_method: string;
+  size: number;
+  compressed_size: number;
+  ratio: number;
+}
+
+/** What one chunk of a game split over `pakchunkN` paks holds, from `getChunkSummary` */
+export interface ChunkSummary {
+  /** null for the assets of paks outside the `pakchunkN` naming, and loose files */
+  chunk_id: number | null;
+  /** File names of the chunk's paks */
+  paks: string[];
+  asset_count: number;
+  total_size: number;
+  total_compressed_size: number;
+  /** The 20 top-level content folders with the most assets */
+  folders: ChunkFolder[];
+}
+
+/** A top-level content folder ("Game/Content/Audio") and the chunk's assets beneath it */
+export interface ChunkFolder {
+  path: string;
+  asset_count: number;
+  total_size: number;
+}
+
+/** What kind of container an audited file is; "utoc" for an IoStore .utoc/.ucas pair */
+export type ContainerKind = 'pak' | 'utoc';
+
+/**
+ * How much of a container is encrypted: "encrypted_index" means nothing can be listed
+ * without the key, "encrypted_entries" that the index is readable but entries use a key
+ */
+export type EncryptionStatus = 'none' | 'encrypted_index' | 'encrypted_entries';
+
+/** One container of a folder audited by `auditEncryption` */
+export interface ContainerAudit {
+  path: string;
+  kind: ContainerKind;
+  /** null when the container couldn't be read; `error` says why */
+  status: EncryptionStatus | null;
+  /** GUID of the key the container needs; null for the default key */
+  encryption_key_guid: string | null;
+  /** Whether a key applies: the container's own, its GUID's or the default one */
+  key_configured: boolean;
+  /** Whether that key decrypts the index; null without a key or an encrypted index */
+  key_valid: boolean | null;
+  error: ApiError | null;
+}
+
+/** A key GUID the audited containers need */
+export interface KeyGuidAudit {
+  /** null for the default key */
+  guid: string | null;
+  containers: number;
+  /** Of those, the ones without a key that works */
+  locked: number;
+}
+
+/** What in a folder needs keys, from `auditEncryption` */
+export interface EncryptionAudit {
+  folder: string;
+  /** By path */
+  containers: ContainerAudit[];
+  /** Containers with an encrypted index or encrypted entries */
+  encrypted: number;
+  /** Encrypted containers without a key known to work */
+  locked: number;
+  /** Containers that couldn't be read */
+  unreadable: number;
+  /** Key GUIDs of the encrypted containers, the default key last */
+  key_guids: KeyGuidAudit[];
+}
+
+/** Where the bytes go, from `analyzeStorage` */
+export interface StorageReport {
+  total: StorageGroup;
+  /** Largest stored size first */
+  by_type: StorageGroup[];
+  /** Lowercase without the dot; largest stored size first */
+  by_extension: StorageGroup[];
+  duplicate_bytes: number;
+  incompressible_count: number;
+  incompressible_bytes: number;
+  /** The 100 largest incompressible entries */
+  incompressible: IncompressibleEntry[];
+}
+
+/** Where a string is, independently of the culture it's translated to */
+export interface LocalizationKey {
+  /** Localization target, the .locres file's stem ("Game") */
+  target: string;
+  namespace: string;
+  key: string;
+}
+
+/** String counts of one culture, and the keys of the source culture it lacks */
+export interface CultureSummary {
+  culture: string;
+  /** Paths of the culture's .locres files */
+  files: string[];
+  /** Distinct keys, across the culture's targets */
+  string_count: number;
+  namespace_count: number;
+  missing_count: number;
+  /** Share of the source culture's keys this one has; null when the source has none */
+  coverage: number | null;
+  /** The first 500 missing keys, sorted */
+  missing_keys: LocalizationKey[];
+}
+
+/** Localization coverage of the loaded assets, from `getLocalizationSummary` */
+export interface LocalizationSummary {
+  /** Culture the others were compared with; null when no .locres file was read */
+  source_culture: string | null;
+  /** By culture name */
+  cultures: CultureSummary[];
+  /** .locres files (or paks holding them) that couldn't be read or parsed */
+  errors: ApiError[];
+}
+
+/** A localized string of one culture */
+export interface CultureEntry extends LocalizationKey {
+  value: string;
+}
+
+/** A page of one culture's strings, from `getLocalizationEntries` */
+export interface CultureEntries {
+  culture: string;
+  total: number;
+  /** Strings matching the search */
+  filtered: number;
+  offset: number;
+  entries: CultureEntry[];
+  /** The culture's .locres files that couldn't be read or parsed */
+  errors: ApiError[];
+}
+
+/** An asset in a `DuplicateGroup` */
+export interface DuplicateMember {
+  path: string;
+  pak_file: string | null;
+  compressed_size: number | null;
+}
+
+/** Assets with the same size and SHA-1, of which all but one copy could go */
+export interface DuplicateGroup {
+  /** Uncompressed size of each copy */
+  size: number;
+  /** Lowercase hex */
+  sha1: string;
+  /** Stored bytes of every copy but the smallest */
+  reclaimable_bytes: number;
+  members: DuplicateMember[];
+}
+
+/** What `findDuplicates` returns */
+export interface DuplicateReport {
+  /** Most reclaimable bytes first */
+  groups: DuplicateGroup[];
+  reclaimable_bytes: number;
+  /** Entries read to hash them, for lack of a hash in the index */
+  hashed_on_demand: number;
+  bytes_read: number;
+  /** Same-size entries left out: no hash and not read (budget spent, encrypted or unreadable) */
+  unhashed: number;
+}
+
+/** What `exportAssetList` wrote */
+export interface AssetListExport {
+  destination: string;
+  rows: number;
+  bytes_written: number;
+}
+
+export type AssetListFormat = 'csv' | 'json' | 'markdown';
+
+/** Per-user settings saved by the backend */
+export interface Settings {
+  /** AES keys by key GUID or "*"; "<redacted>" unless asked for with `revealKeys` */
+  aes_keys: Record<string, string>;
+  /** AES keys of single containers by .pak, .utoc or .ucas path, ahead of `aes_keys` */
+  pak_keys: Record<string, string>;
+  oodle_path?: string;
+  /** Mappings (.usmap) to read unversioned properties with; see `setMappingsFile` */
+  usmap_path?: string;
+  preview_cache_mb: number;
+  scan_concurrency: number;
+  /** Newest first */
+  last_folders: string[];
+  favorites: string[];
+  /** Naming-convention prefixes ("PFX_") dropped from display names, besides the UE ones */
+  name_prefixes: string[];
+  /** Asset registry tags kept on the assets of listings; the detail view has them all */
+  listed_tags: string[];
+  /** Profile whose keys, Oodle library and mappings are the ones above */
+  active_profile?: string;
+  /** Game profiles by name */
+  profiles: Record<string, Profile>;
+}
+
+/** What one game needs: its keys, mappings and Oodle library, and the folders it's in */
+export interface Profile {
+  /** Engine version the game was cooked with ("5.3"), for reference */
+  engine_version?: string;
+  /** AES keys by key GUID or "*" */
+  aes_keys: Record<string, string>;
+  /** AES keys of single containers by path */
+  pak_keys: Record<string, string>;
+  oodle_path?: string;
+  usmap_path?: string;
+  /** Folders (or paks) mounted when the profile is activated with a rescan */
+  mount_folders: string[];
+}
+
+/** A profile to create with `createProfile`; paths have to exist, except the folders */
+export interface ProfileSpec {
+  engine_version?: string;
+  /** Keys by GUID or "*", in hex ("0x" optional) or base64 */
+  aes_keys?: Record<string, string>;
+  oodle_path?: string;
+  usmap_path?: string;
+  mount_folders?: string[];
+}
+
+/** The game profiles, from `listProfiles` */
+export interface ProfileList {
+  /** Name of the active profile, if one was activated */
+  active: string | null;
+  profiles: Record<string, Profile>;
+}
+
+/** What `activateProfile` did */
+export interface ProfileActivation {
+  active: string;
+  profile: Profile;
+  /** Mounts of the profile's folders, still scanning; empty without `rescan` */
+  mounts: Mount[];
+  /** Folders that couldn't be mounted */
+  errors: ApiError[];
+}
+
+/** What a .usmap mappings file holds, from `setMappingsFile` */
+export interface MappingsInfo {
+  path: string;
+  version: number;
+  compression: 'none' | 'oodle' | 'brotli';
+  names: number;
+  enums: number;
+  structs: number;
+}
+
+/** What a key set with `setPakKey` applies to */
+export type KeyScope = 'pak' | 'guid' | 'default';
+
+/** An AES key and what it's used for */
+export interface KeyAssignment {
+  /** Container path, key GUID or "*" */
+  target: string;
+  scope: KeyScope;
+  /** "<redacted>" unless asked for with `revealKeys` */
+  key: string;
+}
+
+/** Whether a candidate key decrypts a container */
+export interface KeyTest {
+  pak_path: string;
+  valid: boolean;
+  /** GUID of the key the container needs; null for the default key */
+  encryption_key_guid: string | null;
+}
+
+/** A recent or favorite folder */
+export interface FolderEntry {
+  path: string;
+  /** False once the folder is moved or deleted */
+  exists: boolean;
+  pinned: boolean;
+}
+
+export interface RecentFolders {
+  /** Newest first, at most 15 */
+  recent: FolderEntry[];
+  favorites: FolderEntry[];
+}
+
+/** Changes for `updateSettings`; absent fields are left as they are */
+export interface SettingsPatch {
+  /** Hex or base64 keys by GUID or "*"; null removes one and "<redacted>" keeps it */
+  aes_keys?: Record<string, string | null>;
+  /** Empty to forget the library */
+  oodle_path?: string;
+  /** Empty to forget the mappings file */
+  usmap_path?: string;
+  preview_cache_mb?: number;
+  scan_concurrency?: number;
+  last_folders?: string[];
+  /** Replaces the list; blank entries are dropped */
+  name_prefixes?: string[];
+  /** Replaces the list; blank entries are dropped, and an empty list keeps no tags */
+  listed_tags?: string[];
+}
+
+export interface PreviewCacheStats {
+  cached_previews: number;
+  cache_size_bytes: number;
+  budget_bytes: number;
+  hits: number;
+  misses: number;
+  hit_rate: number;
+  directory: string;
+}
+
 export interface PreviewResponse {
   asset_name: string;
   preview_type: PreviewType;
   data: PreviewData;
   metadata?: Record<string, any>;
   generated_at: string; // ISO datetime string
+  request_id?: string | null;
 }
 
+export type DependencyKind = 'hard' | 'soft';
+
 export interface DependencyMap {
   dependencies: Record<string, string[]>;
+  // Edges missing here are hard references
+  edge_kinds?: Record<string, Record<string, DependencyKind>>;
+  nodes?: Record<string, AssetNode>;
+  // Edges contributed by each pak: pak -> asset -> dependency -> kind
+  sources?: Record<string, Record<string, Record<string, DependencyKind>>>;
+}
+
+export interface AssetNode {
+  asset_type?: string;
+  size?: number;
+  pak_file?: string;
+  /** Id of the scanned asset the node resolved to */
+  asset_id?: string;
+}
+
+export type DependencyExportFormat = 'json' | 'dot' | 'csv' | 'graphml' | 'cytoscape' | 'mermaid' | 'order';
+
+/** Root and limits of a mermaid export */
+export interface DependencyExportOptions {
+  root?: string;
+  max_depth?: number;
+  /** Nodes drawn before the rest collapse into a "…N more" node (default 100) */
+  max_nodes?: number;
 }
 
 export interface DependencyResponse {
   dependencies: DependencyMap;
+  /** What the subgraph filter left out, when one was given */
+  pruned?: SubgraphSummary;
+  /** Tree of the requested asset, in the requested direction */
+  tree?: DependencyTree;
+}
+
+/** "forward" walks what an asset uses, "reverse" what uses it */
+export type TreeDirection = 'forward' | 'reverse';
+
+export interface DependencyTree {
+  asset: string;
+  depth: number;
+  /** In a reverse tree, the assets that use this one */
+  dependencies: DependencyTree[];
+  is_circular: boolean;
+  kind: DependencyKind;
+}
+
+/** Which part of the graph to keep for the graph view */
+export interface SubgraphFilter {
+  /** Assets to walk outward from; every asset when empty */
+  roots?: string[];
+  max_depth?: number;
+  include_types?: string[];
+  path_prefix?: string;
+  /** Keep only assets whose name matches */
+  search?: string;
+  search_mode?: SearchMode;
+  /** Connect kept assets through pruned ones instead of dropping those edges */
+  keep_pass_through?: boolean;
+}
+
+export interface SubgraphSummary {
+  kept_nodes: number;
+  pruned_nodes: number;
+  pruned_edges: number;
+  bridged_edges: number;
+}
+
+export interface PakDiff {
+  added_files: string[];
+  removed_files: string[];
+  /** Present in both builds with a different size or hash */
+  changed_files: string[];
+}
+
+export interface DependencyDiff {
+  added_nodes: string[];
+  removed_nodes: string[];
+  /** [asset, dependency] pairs */
+  added_edges: [string, string][];
+  removed_edges: [string, string][];
+  changed_assets: { asset: string; old_count: number; new_count: number }[];
+  totals: {
+    added_nodes: number;
+    removed_nodes: number;
+    added_edges: number;
+    removed_edges: number;
+    changed_assets: number;
+  };
+}
+
+export interface VersionComparison {
+  pak_diff: PakDiff;
+  dependency_diff: DependencyDiff;
+}
+
+export interface UnreachableReport {
+  roots: number;
+  reachable: number;
+  /** [package, bytes], largest first */
+  unreachable: [string, number][];
+  reclaimable_bytes: number;
+  /** Packages that couldn't be parsed, so whether they're used is unknown */
+  no_dependency_data: string[];
+}
+
+/** Why a referenced asset isn't in the index */
+export type MissingReason = 'not_found' | 'unscanned';
+
+export interface MissingReference {
+  dependency: string;
+  kind: DependencyKind;
+  reason: MissingReason;
+}
+
+export type ValidationIssue =
+  | { issue: 'self_reference'; asset: string }
+  | { issue: 'circular_dependency'; cycle: string[] }
+  | { issue: 'empty_dependency_list'; asset: string }
+  | { issue: 'missing_references'; asset: string; missing: MissingReference[] };
+
+export interface MergeSummary {
+  edges_added: number;
+  edges_already_present: number;
+}
+
+export interface RemovalImpact {
+  asset: string;
+  /** Direct dependents first, then their dependents, ... */
+  dependents_by_depth: string[][];
+  total_dependents: number;
+  /** Affected assets nothing else depends on (maps, top-level blueprints) */
+  affected_roots: number;
+}
+
+export interface DependencyStatistics {
+  total_dependencies: number;
+  max_depth: number;
+  /** Assets of each cycle, sorted by name; not an edge path */
+  circular_references: string[][];
+  orphaned_assets: string[];
+  most_referenced: [string, number][];
+  soft_dependencies: number;
+  /** Assets with the largest transitive footprint in bytes, largest first */
+  largest_footprints: [string, number][];
+  metrics: GraphMetrics;
+  /** What each pak needs from other paks, when nodes know their pak */
+  containers: ContainerSummary[];
+}
+
+export interface ContainerDependency {
+  pak: string;
+  /** Distinct assets of `pak` needed */
+  assets: number;
+  edges: number;
+}
+
+export interface ContainerSummary {
+  pak: string;
+  assets: number;
+  /** Most needed pak first */
+  depends_on: ContainerDependency[];
+}
+
+export interface CrossContainerEdges {
+  from_pak: string;
+  to_pak: string;
+  count: number;
+  /** [dependent, dependency] pairs */
+  edges: [string, string][];
+}
+
+export interface FolderMetrics {
+  folder: string;
+  assets: number;
+  internal_edges: number;
+  outgoing_edges: number;
+  incoming_edges: number;
+}
+
+export interface GraphMetrics {
+  /** Number of assets per in-degree */
+  in_degree_distribution: Record<string, number>;
+  out_degree_distribution: Record<string, number>;
+  /** [asset, in + out degree], highest first */
+  hubs: [string, number][];
+  average_dependencies: number;
+  folders: FolderMetrics[];
+}
+
+export interface BuildOrderResponse {
+  /** Every asset, dependencies first; empty when there are cycles */
+  order: string[];
+  cycles: string[][];
+}
+
+/** Where the HTTP API server is listening, from the `api-server-ready` event */
+export interface ApiServerInfo {
+  /** "127.0.0.1:3001" */
+  addr: string;
+  port: number;
+}
+
+/** Stable reason of an HTTP API error */
+export type ApiErrorCode =
+  | 'INVALID_REQUEST'
+  | 'UNAUTHORIZED'
+  | 'ASSET_NOT_FOUND'
+  | 'ASSET_AMBIGUOUS'
+  | 'ASSET_NOT_IN_PAK'
+  | 'ENTRY_NOT_FOUND'
+  | 'ENTRY_ENCRYPTED'
+  | 'PAK_NOT_FOUND'
+  | 'PAK_READ_FAILED'
+  | 'EXTRACT_FAILED'
+  | 'DECOMPRESSOR_UNAVAILABLE'
+  | 'DESTINATION_EXISTS'
+  | 'NOT_MOUNTED'
+  | 'OPEN_FAILED'
+  | 'SETTINGS_WRITE_FAILED'
+  | 'WATCH_FAILED'
+  | 'NO_BINARY_PREVIEW'
+  | 'FORMAT_UNAVAILABLE'
+  | 'TEXTURE_UNREADABLE'
+  | 'LOCRES_UNREADABLE'
+  | 'MAPPINGS_REQUIRED'
+  | 'CONTAINER_READ_FAILED'
+  | 'PREVIEW_FAILED'
+  | 'IMPORT_FAILED'
+  | 'EXPORT_FAILED'
+  | 'FILE_NOT_FOUND'
+  | 'IO_FAILED'
+  | 'INTERNAL'
+  | 'NO_PAKS_FOUND'
+  | 'JOB_NOT_FOUND'
+  | 'MOUNT_NOT_FOUND'
+  | 'MOUNT_EXISTS'
+  | 'PROFILE_NOT_FOUND'
+  | 'PROFILE_EXISTS'
+  | 'TOO_MANY_REQUESTS'
+  | 'CANCELLED';
+
+/** Error of every command and of the HTTP API (as `error` of the body), also used for per-pak scan errors */
+export interface ApiError {
+  code: ApiErrorCode;
+  message: string;
+  /** What the error is about (path, pak_file, ...) */
+  context?: Record<string, unknown>;
+}
+
+/** What `extractAsset` wrote */
+export interface ExtractResult {
+  destination: string;
+  bytes_written: number;
+  /** Whether the bytes match the entry's SHA-1; null when the index has no hash for it */
+  hash_verified: boolean | null;
+}
+
+/** Image files `convertTexture` writes; DDS keeps the mip's bytes as stored */
+export type ImageFormat = 'png' | 'tga' | 'dds';
+
+/** What `convertTexture` wrote */
+export interface ConvertedTexture {
+  /** Where the image went; ends in .dds when the pixel format had to be kept as is */
+  destination: string;
+  format: ImageFormat;
+  width: number;
+  height: number;
+  /** Index of the mip written, 0 being the largest one cooked */
+  mip: number;
+  /** UE pixel format ("PF_DXT5", "PF_BC7", ...) */
+  pixel_format: string;
+  bytes_written: number;
+}
+
+/** What a pak's footer and the start of its index say */
+export interface PakInfo {
+  path: string;
+  /** From a `pakchunkN-Platform` name; null for other names */
+  chunk_id: number | null;
+  file_size: number;
+  version: number;
+  /** null when the index is encrypted */
+  mount_point: string | null;
+  /** null when the index is encrypted and the pak isn't loaded */
+  entry_count: number | null;
+  index_offset: number;
+  index_size: number;
+  encrypted_index: boolean;
+  /** GUID of the AES key the pak needs; null for the default key */
+  encryption_key_guid: string | null;
+  /** Methods named in the footer (v8+) */
+  compression_methods: string[];
+  /** Stored entry data: everything before the index */
+  total_compressed_bytes: number;
+  /** null when the pak isn't loaded */
+  total_uncompressed_bytes: number | null;
+}
+
+/** What a .utoc header says about its IoStore container */
+export interface TocInfo {
+  path: string;
+  ucas_path: string;
+  /** From a `pakchunkN-Platform` name; null for other names */
+  chunk_id: number | null;
+  file_size: number;
+  version: number;
+  container_id: string;
+  /** Compressed, Encrypted, Signed, Indexed, OnDemand */
+  container_flags: string[];
+  encrypted: boolean;
+  encryption_key_guid: string | null;
+  chunk_count: number;
+  compression_block_count: number;
+  compression_block_size: number;
+  directory_index_size: number;
+  partition_count: number;
+  partition_size: number;
+  compression_methods: string[];
+  /** Size of the .ucas */
+  total_compressed_bytes: number;
+  /** null when the container isn't loaded */
+  total_uncompressed_bytes: number | null;
+}
+
+export type ContainerInfo = ({ format: 'pak' } & PakInfo) | ({ format: 'utoc' } & TocInfo);
+
+/** An entry `extractAssets` couldn't extract */
+export interface FailedExtraction {
+  path: string;
+  reason: ApiError;
+}
+
+/** What an `extractAssets` run did */
+export interface ExtractionSummary {
+  job_id: string;
+  /** Files written */
+  extracted: number;
+  failed: FailedExtraction[];
+  /** Entries left out because no decryption key is configured for them */
+  skipped_encrypted: number;
+  total_bytes: number;
+  /** Stopped by `cancelJob`; entries not started by then are in none of the counts */
+  cancelled: boolean;
+}
+
+/** Payload of `extract://progress`, sent as each entry of an `extractAssets` run is done */
+export interface ExtractProgressEvent {
+  job_id: string;
+  /** The entry just handled */
+  path: string;
+  done: number;
+  total: number;
+  /** Bytes written for this entry */
+  bytes: number;
+  /** Bytes written so far */
+  total_bytes: number;
+  /** Why the entry wasn't extracted */
+  error: ApiError | null;
+}
+
+/** A folder or container whose assets are part of the listing */
+export interface Mount {
+  id: string;
+  /** Folder, .pak or .utoc as registered */
+  path: string;
+  /** Higher wins when mounts have the same asset path */
+  priority: number;
+  status: ScanStatus;
+  /** Class and tags are read from each package */
+  deep: boolean;
+  /** Changed paks are rescanned as they're written (`watchFolder`) */
+  watched: boolean;
+  pak_files: string[];
+  paks_done: number;
+  /** Assets in the mount's paks, shadowed ones included */
+  assets: number;
+  /** Assets hidden by a higher-priority mount or a patch pak */
+  shadowed_assets: number;
+  errors: ApiError[];
+}
+
+/** Sent as `assets://changed` once a watched mount's changed paks are rescanned */
+export interface AssetsChangedEvent {
+  mount_id: string;
+  /** Paks created, rewritten or deleted */
+  pak_files: string[];
+}
+
+/** What a dropped path was taken for */
+export type DroppedKind = 'pak' | 'utoc' | 'folder' | 'unsupported';
+
+/** What became of one dropped path; also sent as `drop://opened` for drops onto the window */
+export interface DroppedPath {
+  path: string;
+  kind: DroppedKind;
+  /** The mount it was added as, also the job id of its scan */
+  mount_id: string | null;
+  /** Why it wasn't opened */
+  warning: string | null;
+}
+
+/** `cancelled` scans left the listing as it was */
+export type ScanStatus = 'running' | 'completed' | 'cancelled';
+
+/** Progress of a folder scan; for a mount, `job_id` is the mount id */
+export interface ScanProgress {
+  job_id: string;
+  path: string;
+  status: ScanStatus;
+  paks_done: number;
+  paks_total: number;
+  assets_found: number;
+  errors: ApiError[];
+}
+
+/** Decompression slots and the budget for decompressed buffers, shared by scans, extractions and previews */
+export interface ResourceMetrics {
+  slots: number;
+  slots_in_use: number;
+  budget_bytes: number;
+  bytes_in_use: number;
+  /** Most bytes in use at once since startup */
+  peak_bytes: number;
+  /** Leases that had to wait for a slot or for room in the budget since startup */
+  waited_total: number;
+}
+
+/** What is loaded and what the process uses, from `get_status` or `state` of `/health` */
+export interface StatusReport {
+  mounts: number;
+  /** Paks across the mounts */
+  paks: number;
+  /** Assets in the listing, after dedup across mounts */
+  assets: number;
+  /** Scans still running */
+  scans: ScanProgress[];
+  preview_cache: PreviewCacheStats;
+  /** Parsed pak indexes currently held open */
+  open_pak_handles: number;
+  /** Decompression slots and buffer budget in use */
+  resources: ResourceMetrics;
+  /** Resident memory, null where the platform doesn't report it */
+  rss_bytes: number | null;
+  uptime_secs: number;
+}
+
+/** What to capture when the app is slow or uses too much memory */
+export interface Diagnostics {
+  generated_at: string;
+  version: string;
+  platform: string;
+  /** Game profile in use, null when none was activated */
+  active_profile: string | null;
+  uptime_secs: number;
+  /** null where the platform doesn't report it */
+  rss_bytes: number | null;
+  virtual_bytes: number | null;
+  open_pak_handles: number;
+  /** The pak of each open handle */
+  open_paks: string[];
+  /** Pak indexes read from their files since startup */
+  pak_index_reads: number;
+  /** Pak indexes loaded from the index cache instead */
+  pak_index_cache_hits: number;
+  preview_cache: PreviewCacheStats;
+  /** In-memory structures, largest first */
+  structures: StructureSize[];
+  /** The last 50 scans, previews and extractions, newest first */
+  timings: OperationTiming[];
+}
+
+export interface StructureSize {
+  name: string;
+  count: number;
+  /** `count` times the size of one item, without strings and other heap data */
+  approx_bytes: number;
+}
+
+export interface OperationTiming {
+  operation: 'scan' | 'preview' | 'extract';
+  /** The folder scanned, or the asset previewed or extracted */
+  target: string;
+  duration_ms: number;
+  finished_at: string;
+  /** False when it failed or was cancelled */
+  ok: boolean;
+}
+
+/** Progress of a long-running operation, from `progress` events or the `/ws` socket */
+export type ProgressEvent = { job_id: string } & (
+  | {
+      event: 'pak_scanned';
+      pak_file: string;
+      paks_done: number;
+      paks_total: number;
+      /** Running total */
+      assets_found: number;
+      error: ApiError | null;
+    }
+  | { event: 'scan_completed'; assets_found: number }
+  | { event: 'scan_cancelled'; assets_found: number }
+);
+
+/** Payload of `scan://progress`, sent as each pak of a `startScan` job is done */
+export interface ScanProgressEvent {
+  job_id: string;
+  paks_done: number;
+  paks_total: number;
+  assets_found: number;
+  /** The pak just scanned */
+  current_pak: string;
+  /** Why the pak couldn't be read */
+  error: ApiError | null;
+}
+
+export interface ScanOptions {
+  /** Read each package to fill in per-type metadata (slower) */
+  deep?: boolean;
 }
 
 export interface AppInfo {